@@ -0,0 +1,282 @@
+//! `roc build --bundle`: checking a package or platform and packing it into a tarball that can be
+//! published at a HTTPS URL.
+use bumpalo::Bump;
+use roc_build::program::{handle_loading_problem, report_problems_typechecked};
+use roc_load::{ExecutionMode, LoadConfig, LoadedModule, Threading};
+use roc_packaging::cache::RocCacheDir;
+use roc_packaging::tarball::{self, BundleRoot, Compression, Manifest};
+use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::FLAG_BUNDLE;
+
+/// Every target a platform can ship a prebuilt host for.
+const HOST_TARGETS: [&str; 8] = [
+    roc_target::LINUX_X86_64_TARGET_STR,
+    roc_target::LINUX_ARM64_TARGET_STR,
+    roc_target::MACOS_ARM64_TARGET_STR,
+    roc_target::MACOS_X86_64_TARGET_STR,
+    roc_target::WINDOWS_X86_64_TARGET_STR,
+    roc_target::WINDOWS_X86_32_TARGET_STR,
+    roc_target::WIDNOWS_ARM64_TARGET_STR,
+    roc_target::WASM_TARGET_STR,
+];
+
+/// Extensions of the files a prebuilt host is made of: surgical hosts and their metadata, legacy
+/// hosts, and the zig source optimized wasm builds are linked with.
+const HOST_EXTENSIONS: [&str; 6] = ["rh", "rm", "o", "obj", "wasm", "zig"];
+
+pub fn bundle(
+    path: &Path,
+    compression: Compression,
+    roc_cache_dir: RocCacheDir<'_>,
+    threading: Threading,
+) -> io::Result<i32> {
+    let start_time = Instant::now();
+    let bundle_root = match tarball::bundle_root(path) {
+        Ok(bundle_root) => bundle_root,
+        Err(err) => {
+            eprintln!("\n{err}\n");
+
+            return Ok(1);
+        }
+    };
+    let root_dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    // Load the package the same way an app depending on it would, so we know exactly which
+    // modules it needs - and don't publish one that doesn't compile.
+    let arena = Bump::new();
+    let load_config = LoadConfig {
+        target_info: TargetInfo::default_x86_64(),
+        render: RenderTarget::ColorTerminal,
+        palette: DEFAULT_PALETTE,
+        threading,
+        exec_mode: ExecutionMode::Check,
+    };
+    let mut loaded = match roc_load::load_and_typecheck(
+        &arena,
+        path.to_path_buf(),
+        roc_cache_dir,
+        load_config,
+    ) {
+        Ok(loaded) => loaded,
+        Err(problem) => return handle_loading_problem(problem),
+    };
+    let problems = report_problems_typechecked(&mut loaded, RenderTarget::ColorTerminal);
+
+    if problems.errors > 0 {
+        eprintln!(
+            "\n{} can't be bundled until its errors are fixed.\n",
+            path.to_string_lossy()
+        );
+
+        return Ok(problems.exit_code());
+    }
+
+    let mut warnings = Vec::new();
+    let modules = reachable_modules(&loaded, root_dir, roc_cache_dir, &mut warnings)?;
+
+    for file in tarball::roc_files(root_dir)? {
+        if !modules.contains(&file) {
+            warnings.push(format!(
+                "{} isn't reachable from {}, so it was left out of the bundle.",
+                root_dir.join(&file).to_string_lossy(),
+                path.to_string_lossy()
+            ));
+        }
+    }
+
+    let hosts = match bundle_root {
+        BundleRoot::Package => Vec::new(),
+        BundleRoot::Platform => prebuilt_hosts(root_dir, &mut warnings)?,
+    };
+
+    for warning in warnings.iter() {
+        eprintln!("\x1B[33mWarning:\x1B[39m {warning}\n");
+    }
+
+    // Print a note of advice. This is mainly here because brotli takes so long but produces
+    // such smaller output files; the idea is to encourage people to wait for brotli,
+    // so that downloads go faster. The compression only happens once, but the network
+    // transfer and decompression will happen many more times!
+    match compression {
+        Compression::Brotli => {
+            println!("Compressing with Brotli at maximum quality level…\n\n(Note: Brotli compression can take awhile! Using --{FLAG_BUNDLE} .tar.gz takes less time, but usually produces a significantly larger output file. Brotli is generally worth the up-front wait if this is a file people will be downloading!)\n");
+        }
+        Compression::Gzip => {
+            println!("Compressing with gzip at minimum quality…\n\n(Note: Gzip usually runs faster than Brotli but typically produces significantly larger output files. Consider using --{FLAG_BUNDLE} .tar.br if this is a file people will be downloading!)\n");
+        }
+        Compression::Uncompressed => {
+            println!("Building .tar archive without compression…\n\n(Note: Compression takes more time to run but typically produces much smaller output files. Consider using --{FLAG_BUNDLE} .tar.br if this is a file people will be downloading!)\n");
+        }
+    }
+
+    let manifest = Manifest { modules, hosts };
+    let filename = tarball::build(path, compression, &manifest)?;
+    let total_time_ms = start_time.elapsed().as_millis();
+    let total_time = if total_time_ms > 1000 {
+        format!("{}s {}ms", total_time_ms / 1000, total_time_ms % 1000)
+    } else {
+        format!("{total_time_ms} ms")
+    };
+    let created_path = path.with_file_name(&filename);
+    let manifest_path = path.with_file_name(tarball::manifest_filename(&filename));
+
+    // Packages are looked up by their main.roc unless the URL says otherwise.
+    let url_fragment = match path.file_name().and_then(OsStr::to_str) {
+        Some("main.roc") | None => String::new(),
+        Some(root_module) => format!("#{root_module}"),
+    };
+
+    println!(
+        "\nBundled \x1B[33m{}\x1B[39m and its dependent files into the following archive in {total_time}:\n\n\t\x1B[33m{}\x1B[39m\n\nThe files it contains are listed in:\n\n\t\x1B[33m{}\x1B[39m\n\nTo distribute this archive as a package, upload this to some URL and then add it as a dependency with:\n\n\t\x1B[32m\"https://your-url-goes-here/{filename}{url_fragment}\"\x1B[39m\n",
+        path.to_string_lossy(),
+        created_path.to_string_lossy(),
+        manifest_path.to_string_lossy(),
+    );
+
+    if warnings.is_empty() {
+        Ok(problems.exit_code())
+    } else {
+        Ok(2)
+    }
+}
+
+/// The .roc files of every module the loaded package uses, relative to its root dir. Modules from
+/// other packages are skipped, since apps will download those separately.
+fn reachable_modules(
+    loaded: &LoadedModule,
+    root_dir: &Path,
+    roc_cache_dir: RocCacheDir<'_>,
+    warnings: &mut Vec<String>,
+) -> io::Result<Vec<PathBuf>> {
+    let canonical_root_dir = root_dir.canonicalize()?;
+    let package_dirs: Vec<PathBuf> = [roc_cache_dir.cache_dir(), roc_cache_dir.vendor_dir()]
+        .into_iter()
+        .flatten()
+        .filter_map(|dir| dir.canonicalize().ok())
+        .collect();
+    let mut modules = Vec::new();
+
+    for (module_id, (module_path, _)) in loaded.sources.iter() {
+        if module_id.is_builtin() {
+            continue;
+        }
+
+        let canonical_path = module_path.canonicalize()?;
+
+        if let Ok(relative_path) = canonical_path.strip_prefix(&canonical_root_dir) {
+            modules.push(relative_path.to_path_buf());
+        } else if !package_dirs
+            .iter()
+            .any(|dir| canonical_path.starts_with(dir))
+        {
+            warnings.push(format!(
+                "{} is used by this package, but it's outside {}, so it can't be included in the bundle. It should be moved into that directory, or published as a package of its own.",
+                module_path.to_string_lossy(),
+                root_dir.to_string_lossy()
+            ));
+        }
+    }
+
+    modules.sort();
+
+    Ok(modules)
+}
+
+/// The prebuilt host files in the platform's root dir, grouped by target.
+fn prebuilt_hosts(
+    root_dir: &Path,
+    warnings: &mut Vec<String>,
+) -> io::Result<Vec<(String, Vec<PathBuf>)>> {
+    let mut hosts: Vec<(String, Vec<PathBuf>)> = HOST_TARGETS
+        .iter()
+        .map(|target| (target.to_string(), Vec::new()))
+        .collect();
+
+    for entry in fs::read_dir(root_dir)? {
+        let path = entry?.path();
+        let is_host_file = path.is_file()
+            && matches!(
+                path.extension().and_then(OsStr::to_str),
+                Some(ext) if HOST_EXTENSIONS.contains(&ext)
+            );
+
+        if !is_host_file {
+            continue;
+        }
+
+        let file_name = path.file_name().and_then(OsStr::to_str).unwrap_or_default();
+
+        match host_target(file_name) {
+            Some(target) => {
+                let (_, files) = hosts.iter_mut().find(|(t, _)| t == target).unwrap();
+
+                files.push(PathBuf::from(file_name));
+            }
+            None => warnings.push(format!(
+                "{} looks like part of a prebuilt host, but its name doesn't match any target, so it was left out of the bundle.",
+                path.to_string_lossy()
+            )),
+        }
+    }
+
+    let mut missing_targets = Vec::new();
+
+    for (target, files) in hosts.iter_mut() {
+        files.sort();
+
+        let has_file = |name: String| files.iter().any(|file| file.as_os_str() == name.as_str());
+        let surgical = has_file(format!("{target}.rh"));
+
+        if surgical && !has_file(format!("metadata_{target}.rm")) {
+            warnings.push(format!(
+                "{target}.rh is a surgical host, but metadata_{target}.rm is missing, so apps can't be linked with it."
+            ));
+        }
+
+        if !surgical
+            && !["o", "obj", "wasm"]
+                .iter()
+                .any(|ext| has_file(format!("{target}.{ext}")))
+            && !has_file("host.zig".to_string())
+        {
+            missing_targets.push(target.as_str());
+        }
+    }
+
+    if !missing_targets.is_empty() {
+        warnings.push(format!(
+            "There's no prebuilt host for {}, so apps using this platform can't be built for those targets.",
+            missing_targets.join(", ")
+        ));
+    }
+
+    hosts.retain(|(_, files)| !files.is_empty());
+
+    Ok(hosts)
+}
+
+/// The target a prebuilt host file is for, based on its name.
+fn host_target(file_name: &str) -> Option<&'static str> {
+    if file_name == "host.zig" {
+        return Some(roc_target::WASM_TARGET_STR);
+    }
+
+    let (stem, ext) = file_name.rsplit_once('.')?;
+    let target = match ext {
+        "rm" => stem.strip_prefix("metadata_")?,
+        "rh" | "o" | "obj" | "wasm" => stem,
+        _ => return None,
+    };
+
+    HOST_TARGETS.iter().copied().find(|t| *t == target)
+}
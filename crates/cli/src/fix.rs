@@ -0,0 +1,493 @@
+use std::path::{Path, PathBuf};
+
+use bumpalo::Bump;
+use roc_can::expr::{Declarations, Expr};
+use roc_can::traverse::{find_declaration, walk_expr, Visitor};
+use roc_exhaustive::Context;
+use roc_load::{ExecutionMode, LoadConfig, LoadingProblem, Threading};
+use roc_module::symbol::{Interns, Symbol};
+use roc_packaging::cache::RocCacheDir;
+use roc_problem::can::Problem;
+use roc_region::all::Region;
+use roc_reporting::error::r#type::missing_pattern_to_source;
+use roc_reporting::report::{RenderTarget, RocDocAllocator, DEFAULT_PALETTE};
+use roc_solve_problem::TypeError;
+use roc_target::TargetInfo;
+use roc_types::subs::Variable;
+
+use crate::format::format_src;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixKind {
+    /// Remove unused imports and defs, and prefix unused arguments with `_`.
+    UnusedCode,
+    /// Add a `crash "todo"` branch for every pattern a `when` is missing.
+    MissingBranches,
+    /// Remove `when` branches that can never be reached.
+    RedundantBranches,
+}
+
+/// Replaces `src[start..end]` with `replacement`.
+#[derive(Debug)]
+struct Edit {
+    start: usize,
+    end: usize,
+    replacement: String,
+}
+
+impl Edit {
+    fn insert(at: usize, text: &str) -> Self {
+        Edit {
+            start: at,
+            end: at,
+            replacement: text.to_string(),
+        }
+    }
+
+    fn remove(start: usize, end: usize) -> Self {
+        Edit {
+            start,
+            end,
+            replacement: String::new(),
+        }
+    }
+}
+
+/// Applies the machine-applicable fixes of the given kinds to every module of the project at
+/// `roc_file_path`, then formats the result. With `dry_run`, prints a diff instead of writing
+/// anything.
+pub fn fix(
+    arena: &Bump,
+    roc_file_path: PathBuf,
+    roc_cache_dir: RocCacheDir<'_>,
+    threading: Threading,
+    kinds: &[FixKind],
+    dry_run: bool,
+) -> Result<i32, LoadingProblem<'_>> {
+    // only used for generating errors. We don't do code generation, so hardcoding should be fine
+    let target_info = TargetInfo::default_x86_64();
+
+    let load_config = LoadConfig {
+        target_info,
+        render: RenderTarget::ColorTerminal,
+        palette: DEFAULT_PALETTE,
+        threading,
+        exec_mode: ExecutionMode::Check,
+    };
+    let loaded =
+        roc_load::load_and_typecheck(arena, roc_file_path.clone(), roc_cache_dir, load_config)?;
+
+    // Only touch modules that belong to this project, not packages from the cache.
+    let project_dir = roc_file_path
+        .canonicalize()
+        .ok()
+        .and_then(|path| path.parent().map(Path::to_path_buf));
+    let in_project = |path: &Path| match (&project_dir, path.canonicalize()) {
+        (Some(project_dir), Ok(path)) => path.starts_with(project_dir),
+        _ => false,
+    };
+
+    let mut modules: Vec<_> = loaded
+        .sources
+        .iter()
+        .filter(|(module_id, (path, _))| !module_id.is_builtin() && in_project(path))
+        .filter_map(|(module_id, (path, src))| {
+            let decls = loaded.declarations_by_id.get(module_id)?;
+
+            Some((*module_id, path, src, decls))
+        })
+        .collect();
+    modules.sort_by_key(|(_, path, ..)| *path);
+
+    let mut exit_code = 0;
+    let mut fixed_files = 0;
+
+    for (module_id, path, src, decls) in modules {
+        let mut edits = Vec::new();
+
+        if kinds.contains(&FixKind::UnusedCode) {
+            let problems = loaded.can_problems.get(&module_id).into_iter().flatten();
+
+            edits.extend(
+                problems
+                    .filter_map(|problem| edit_for_problem(problem, src, decls, &loaded.interns)),
+            );
+        }
+
+        if kinds.contains(&FixKind::MissingBranches) || kinds.contains(&FixKind::RedundantBranches)
+        {
+            let src_lines: Vec<&str> = src.split('\n').collect();
+            let alloc = RocDocAllocator::new(&src_lines, module_id, &loaded.interns);
+            let problems = loaded.type_problems.get(&module_id).into_iter().flatten();
+
+            edits.extend(problems.filter_map(|problem| match problem {
+                TypeError::Exhaustive(error) => {
+                    edit_for_exhaustive_error(error, kinds, src, decls, &alloc)
+                }
+                _ => None,
+            }));
+        }
+
+        let fixed_src = apply_edits(src, edits);
+        if fixed_src == **src {
+            continue;
+        }
+
+        let fmt_arena = Bump::new();
+        let formatted_src = match format_src(&fmt_arena, &fixed_src, None) {
+            Ok(formatted_src) => formatted_src,
+            Err(problem) => {
+                eprintln!(
+                    "I could not fix {}, because the fixed code did not format cleanly:\n\n{:?}\n",
+                    path.display(),
+                    problem
+                );
+                exit_code = 1;
+
+                continue;
+            }
+        };
+
+        fixed_files += 1;
+
+        if dry_run {
+            let name = path.display().to_string();
+
+            print!(
+                "{}",
+                similar::TextDiff::from_lines(&**src, formatted_src.as_str())
+                    .unified_diff()
+                    .header(&name, &name)
+            );
+        } else {
+            std::fs::write(path, formatted_src).unwrap();
+            println!("Fixed {}", path.display());
+        }
+    }
+
+    if fixed_files == 0 {
+        println!("There was nothing to fix.");
+    }
+
+    Ok(exit_code)
+}
+
+fn edit_for_problem(
+    problem: &Problem,
+    src: &str,
+    decls: &Declarations,
+    interns: &Interns,
+) -> Option<Edit> {
+    match problem {
+        Problem::UnusedImport(_, region) | Problem::UnusedModuleImport(_, region) => {
+            Some(remove_list_item(src, *region, ','))
+        }
+        Problem::UnusedArgument(_, _, symbol, region)
+        | Problem::UnusedBranchDef(symbol, region) => {
+            prefix_with_underscore(src, *region, symbol.as_str(interns))
+        }
+        Problem::UnusedDef(symbol, _) => remove_def(src, *symbol, decls, interns),
+        _ => None,
+    }
+}
+
+fn edit_for_exhaustive_error(
+    error: &roc_exhaustive::Error,
+    kinds: &[FixKind],
+    src: &str,
+    decls: &Declarations,
+    alloc: &RocDocAllocator<'_>,
+) -> Option<Edit> {
+    use roc_exhaustive::Error::*;
+
+    match error {
+        Incomplete(region, Context::BadCase, missing)
+            if kinds.contains(&FixKind::MissingBranches) =>
+        {
+            // The region spans from the condition to the end of the last branch
+            let branches = find_when(decls, |cond_region, branches| {
+                branches.last().map_or(false, |last| {
+                    Region::span_across(&cond_region, &last.value) == *region
+                })
+            })?;
+
+            add_missing_branches(src, &branches, missing, alloc)
+        }
+        Redundant { branch_region, .. } | Unmatchable { branch_region, .. }
+            if kinds.contains(&FixKind::RedundantBranches) =>
+        {
+            let branches = find_when(decls, |_, branches| {
+                branches
+                    .iter()
+                    .any(|branch| branch.patterns.contains(branch_region))
+            })?;
+
+            remove_branch_pattern(src, &branches, *branch_region)
+        }
+        _ => None,
+    }
+}
+
+/// Where the parts of a `when` branch are, which is all we need to edit it as text.
+struct BranchRegions {
+    patterns: Vec<Region>,
+    value: Region,
+}
+
+/// Finds the first `when` whose condition and branches satisfy `is_match`.
+fn find_when(
+    decls: &Declarations,
+    is_match: impl Fn(Region, &[BranchRegions]) -> bool,
+) -> Option<Vec<BranchRegions>> {
+    let mut visitor = WhenFinder {
+        is_match,
+        found: None,
+    };
+    visitor.visit_decls(decls);
+    return visitor.found;
+
+    struct WhenFinder<F> {
+        is_match: F,
+        found: Option<Vec<BranchRegions>>,
+    }
+
+    impl<F: Fn(Region, &[BranchRegions]) -> bool> Visitor for WhenFinder<F> {
+        fn should_visit(&mut self, _region: Region) -> bool {
+            self.found.is_none()
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            if !self.should_visit(region) {
+                return;
+            }
+
+            if let Expr::When {
+                loc_cond, branches, ..
+            } = expr
+            {
+                let branches: Vec<_> = branches
+                    .iter()
+                    .map(|branch| BranchRegions {
+                        patterns: branch.patterns.iter().map(|p| p.pattern.region).collect(),
+                        value: branch.value.region,
+                    })
+                    .collect();
+
+                if (self.is_match)(loc_cond.region, &branches) {
+                    self.found = Some(branches);
+                    return;
+                }
+            }
+
+            walk_expr(self, expr, var);
+        }
+    }
+}
+
+/// Adds a `crash "todo"` branch for each missing pattern, after the last branch.
+fn add_missing_branches(
+    src: &str,
+    branches: &[BranchRegions],
+    missing: &[roc_exhaustive::Pattern],
+    alloc: &RocDocAllocator<'_>,
+) -> Option<Edit> {
+    let first_pattern = branches.first()?.patterns.first()?.start().offset as usize;
+    let line_start = src[..first_pattern]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    let indent = &src[line_start..first_pattern];
+
+    // The new branches have to line up with the existing ones
+    if !indent.trim().is_empty() {
+        return None;
+    }
+
+    let mut new_branches = String::new();
+    for pattern in missing {
+        new_branches.push('\n');
+        new_branches.push_str(indent);
+        new_branches.push_str(&missing_pattern_to_source(alloc, pattern.clone()));
+        new_branches.push_str(" -> crash \"todo\"");
+    }
+
+    let end = branches.last()?.value.end().offset as usize;
+
+    Some(Edit::insert(end, &new_branches))
+}
+
+/// Removes a pattern that can never match: just the pattern, if its branch has others
+/// like `A | B ->`, and the whole branch otherwise.
+fn remove_branch_pattern(src: &str, branches: &[BranchRegions], region: Region) -> Option<Edit> {
+    let branch = branches
+        .iter()
+        .find(|branch| branch.patterns.contains(&region))?;
+
+    if branch.patterns.len() > 1 {
+        Some(remove_list_item(src, region, '|'))
+    } else if branches.len() > 1 {
+        let (start, end) = whole_lines(
+            src,
+            region.start().offset as usize,
+            branch.value.end().offset as usize,
+        )?;
+
+        Some(Edit::remove(start, end))
+    } else {
+        // A `when` needs at least one branch
+        None
+    }
+}
+
+/// Removes an entry of a list separated by `separator`, along with one of the separators
+/// around it.
+fn remove_list_item(src: &str, region: Region, separator: char) -> Edit {
+    let start = region.start().offset as usize;
+    let end = region.end().offset as usize;
+
+    let after = &src[end..];
+    let after_trimmed = after.trim_start();
+
+    if let Some(rest) = after_trimmed.strip_prefix(separator) {
+        let end = src.len() - rest.trim_start().len();
+
+        return Edit::remove(start, end);
+    }
+
+    let before = src[..start].trim_end();
+
+    match before.strip_suffix(separator) {
+        Some(before) => Edit::remove(before.len(), end),
+        None => Edit::remove(start, end),
+    }
+}
+
+/// Turns an unused binding like `x` into `_x`, taking care not to rename record fields.
+fn prefix_with_underscore(src: &str, region: Region, name: &str) -> Option<Edit> {
+    let start = region.start().offset as usize;
+    let end = region.end().offset as usize;
+    let text = &src[start..end];
+
+    if text == name {
+        let is_field_pun = innermost_open_bracket(&src[..start]) == Some('{')
+            && src[..start].trim_end().ends_with(['{', ',']);
+
+        if is_field_pun {
+            // `{ x }` becomes `{ x: _x }`, since the field itself still has to be there
+            Some(Edit {
+                start,
+                end,
+                replacement: format!("{}: _{}", name, name),
+            })
+        } else {
+            Some(Edit::insert(start, "_"))
+        }
+    } else {
+        // e.g. `Foo x as y`, where only `y` is unused
+        let before_name = text.strip_suffix(name)?;
+
+        if before_name.trim_end().ends_with(" as") && before_name.ends_with(char::is_whitespace) {
+            Some(Edit::insert(end - name.len(), "_"))
+        } else {
+            None
+        }
+    }
+}
+
+fn innermost_open_bracket(src: &str) -> Option<char> {
+    let mut depth = 0;
+
+    for c in src.chars().rev() {
+        match c {
+            ')' | ']' | '}' => depth += 1,
+            '(' | '[' | '{' if depth == 0 => return Some(c),
+            '(' | '[' | '{' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Removes a def along with its annotation, as long as it's the only thing on its lines.
+fn remove_def(src: &str, symbol: Symbol, decls: &Declarations, interns: &Interns) -> Option<Edit> {
+    let region = find_declaration(symbol, decls)?.region();
+    let def_start = region.start().offset as usize;
+    let (mut start, end) = whole_lines(src, def_start, region.end().offset as usize)?;
+
+    if let Some(annotation_start) =
+        annotation_start(src, start, def_start - start, symbol.as_str(interns))
+    {
+        start = annotation_start;
+    }
+
+    Some(Edit::remove(start, end))
+}
+
+/// Widens `start..end` to the lines it is on, if nothing else is on those lines. The end of a
+/// line may have trailing whitespace, but the start may only have indentation.
+fn whole_lines(src: &str, start: usize, end: usize) -> Option<(usize, usize)> {
+    let line_start = src[..start].rfind('\n').map_or(0, |index| index + 1);
+    if !src[line_start..start].trim().is_empty() {
+        return None;
+    }
+
+    let line_end = src[end..]
+        .find('\n')
+        .map_or(src.len(), |index| end + index + 1);
+    let end = if src[end..line_end].trim().is_empty() {
+        line_end
+    } else {
+        end
+    };
+
+    Some((line_start, end))
+}
+
+/// Finds the `name : Type` annotation directly above the def starting at `def_line_start`,
+/// whose type may span several more-indented lines.
+fn annotation_start(src: &str, def_line_start: usize, indent: usize, name: &str) -> Option<usize> {
+    let mut line_end = def_line_start;
+
+    while line_end > 0 {
+        let line_start = src[..line_end - 1].rfind('\n').map_or(0, |index| index + 1);
+        let line = &src[line_start..line_end - 1];
+        let trimmed = line.trim_start();
+        let line_indent = line.len() - trimmed.len();
+
+        let is_annotation = trimmed
+            .strip_prefix(name)
+            .map_or(false, |rest| rest.trim_start().starts_with(':'));
+
+        if line_indent == indent && is_annotation {
+            return Some(line_start);
+        } else if line_indent <= indent || trimmed.is_empty() {
+            return None;
+        }
+
+        line_end = line_start;
+    }
+
+    None
+}
+
+/// Applies the edits back to front, skipping any that overlap an edit already applied.
+fn apply_edits(src: &str, mut edits: Vec<Edit>) -> String {
+    // Wider edits come first, so that e.g. removing a whole import wins over
+    // removing one of the values it exposes.
+    edits.sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+
+    let mut kept: Vec<Edit> = Vec::with_capacity(edits.len());
+    for edit in edits {
+        match kept.last() {
+            Some(last) if edit.start < last.end || edit.start == last.start => {}
+            _ => kept.push(edit),
+        }
+    }
+
+    let mut fixed_src = src.to_string();
+    for edit in kept.iter().rev() {
+        fixed_src.replace_range(edit.start..edit.end, &edit.replacement);
+    }
+
+    fixed_src
+}
@@ -1,5 +1,6 @@
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use crate::FormatMode;
 use bumpalo::Bump;
@@ -9,6 +10,7 @@ use roc_fmt::module::fmt_module;
 use roc_fmt::spaces::RemoveSpaces;
 use roc_fmt::{Ast, Buf};
 use roc_parse::{
+    ast::Defs,
     module::{self, module_defs},
     parser::{Parser, SyntaxError},
     state::State,
@@ -66,45 +68,185 @@ pub fn format(files: std::vec::Vec<PathBuf>, mode: FormatMode) -> Result<(), Str
 
         let src = std::fs::read_to_string(&file).unwrap();
 
-        let ast = arena.alloc(parse_all(&arena, &src).unwrap_or_else(|e| {
-            user_error!("Unexpected parse failure when parsing this formatting:\n\n{:?}\n\nParse error was:\n\n{:?}\n\n", src, e)
-        }));
-        let mut buf = Buf::new_in(&arena);
-        fmt_all(&mut buf, ast);
+        let formatted_src = format_src(&arena, &src, None)
+            .unwrap_or_else(|problem| report_format_problem(&file, problem));
 
-        let reparsed_ast = arena.alloc(parse_all(&arena, buf.as_str()).unwrap_or_else(|e| {
-            let mut fail_file = file.clone();
+        match mode {
+            FormatMode::CheckOnly => {
+                // If we notice that this file needs to be formatted, return early
+                if formatted_src != src {
+                    return Err("One or more files need to be reformatted.".to_string());
+                }
+            }
+
+            FormatMode::Format => {
+                // If all the checks above passed, actually write out the new file.
+                std::fs::write(&file, formatted_src).unwrap();
+            }
+
+            FormatMode::WriteToStdout => {
+                print!("{}", formatted_src);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// An inclusive range of 1-based line numbers, like an editor selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl FromStr for LineRange {
+    type Err = String;
+
+    /// Parses `START:END`, e.g. `3:10`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_line = |line: &str| match line.trim().parse::<u32>() {
+            Ok(line) if line > 0 => Ok(line),
+            _ => Err(format!("{:?} is not a line number", line)),
+        };
+
+        match s.split_once(':') {
+            Some((start, end)) => {
+                let (start, end) = (parse_line(start)?, parse_line(end)?);
+
+                if start <= end {
+                    Ok(LineRange { start, end })
+                } else {
+                    Err(format!("{} comes after {}", start, end))
+                }
+            }
+            None => Err(format!("{:?} is not a line range like 3:10", s)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum FormatProblem {
+    /// The source isn't valid Roc, so there is nothing to format.
+    InvalidSource { parse_err: String },
+    /// The rest are all bugs in the formatter.
+    ParsingFailed {
+        formatted_src: String,
+        parse_err: String,
+    },
+    ReformattingChangedAst {
+        formatted_src: String,
+        ast_before: String,
+        ast_after: String,
+    },
+    ReformattingUnstable {
+        formatted_src: String,
+        reformatted_src: String,
+    },
+}
+
+/// Formats a whole module, or with a `LineRange`, only the top-level defs that overlap it.
+/// Either way the result must parse to the same tree as `src`, or it's a `FormatProblem`.
+pub fn format_src<'a>(
+    arena: &'a Bump,
+    src: &'a str,
+    lines: Option<LineRange>,
+) -> Result<String, FormatProblem> {
+    let ast = arena.alloc(
+        parse_all(arena, src).map_err(|e| FormatProblem::InvalidSource {
+            parse_err: format!("{:?}", e),
+        })?,
+    );
+
+    let formatted_src = match lines {
+        None => {
+            let mut buf = Buf::new_in(arena);
+            fmt_all(&mut buf, ast);
+            buf.as_str().to_string()
+        }
+        Some(lines) => fmt_def_range(arena, src, &ast.defs, lines),
+    };
+
+    let reparsed_ast = arena.alloc(parse_all(arena, arena.alloc_str(&formatted_src)).map_err(
+        |e| FormatProblem::ParsingFailed {
+            formatted_src: formatted_src.clone(),
+            parse_err: format!("{:?}", e),
+        },
+    )?);
+
+    let ast_normalized = ast.remove_spaces(arena);
+    let reparsed_ast_normalized = reparsed_ast.remove_spaces(arena);
+
+    // HACK!
+    // We compare the debug format strings of the ASTs, because I'm finding in practice that _somewhere_ deep inside the ast,
+    // the PartialEq implementation is returning `false` even when the Debug-formatted impl is exactly the same.
+    // I don't have the patience to debug this right now, so let's leave it for another day...
+    // TODO: fix PartialEq impl on ast types
+    if format!("{:?}", ast_normalized) != format!("{:?}", reparsed_ast_normalized) {
+        return Err(FormatProblem::ReformattingChangedAst {
+            formatted_src,
+            ast_before: format!("{:#?}\n", ast_normalized),
+            ast_after: format!("{:#?}\n", reparsed_ast_normalized),
+        });
+    }
+
+    // Now verify that the resultant formatting is _stable_ - i.e. that it doesn't change again if re-formatted.
+    // When only formatting some defs, the rest of the module may well change, so there is nothing to compare.
+    if lines.is_none() {
+        let mut reformatted_buf = Buf::new_in(arena);
+        fmt_all(&mut reformatted_buf, reparsed_ast);
+        if formatted_src != reformatted_buf.as_str() {
+            return Err(FormatProblem::ReformattingUnstable {
+                formatted_src,
+                reformatted_src: reformatted_buf.as_str().to_string(),
+            });
+        }
+    }
+
+    Ok(formatted_src)
+}
+
+/// Reports a problem from formatting `file`, writing whatever would help debug it next to it.
+pub fn report_format_problem(file: &Path, problem: FormatProblem) -> ! {
+    match problem {
+        FormatProblem::InvalidSource { parse_err } => {
+            user_error!(
+                "Unexpected parse failure when parsing this formatting:\n\n{}\n\nParse error was:\n\n{}\n\n",
+                file.display(),
+                parse_err
+            )
+        }
+        FormatProblem::ParsingFailed {
+            formatted_src,
+            parse_err,
+        } => {
+            let mut fail_file = file.to_path_buf();
             fail_file.set_extension("roc-format-failed");
-            std::fs::write(&fail_file, buf.as_str()).unwrap();
+            std::fs::write(&fail_file, formatted_src).unwrap();
             internal_error!(
                 "Formatting bug; formatted code isn't valid\n\n\
                 I wrote the incorrect result to this file for debugging purposes:\n{}\n\n\
-                Parse error was: {:?}\n\n",
+                Parse error was: {}\n\n",
                 fail_file.display(),
-                e
+                parse_err
             );
-        }));
-
-        let ast_normalized = ast.remove_spaces(&arena);
-        let reparsed_ast_normalized = reparsed_ast.remove_spaces(&arena);
-
-        // HACK!
-        // We compare the debug format strings of the ASTs, because I'm finding in practice that _somewhere_ deep inside the ast,
-        // the PartialEq implementation is returning `false` even when the Debug-formatted impl is exactly the same.
-        // I don't have the patience to debug this right now, so let's leave it for another day...
-        // TODO: fix PartialEq impl on ast types
-        if format!("{:?}", ast_normalized) != format!("{:?}", reparsed_ast_normalized) {
-            let mut fail_file = file.clone();
+        }
+        FormatProblem::ReformattingChangedAst {
+            formatted_src,
+            ast_before,
+            ast_after,
+        } => {
+            let mut fail_file = file.to_path_buf();
             fail_file.set_extension("roc-format-failed");
-            std::fs::write(&fail_file, buf.as_str()).unwrap();
+            std::fs::write(&fail_file, formatted_src).unwrap();
 
-            let mut before_file = file.clone();
+            let mut before_file = file.to_path_buf();
             before_file.set_extension("roc-format-failed-ast-before");
-            std::fs::write(&before_file, format!("{:#?}\n", ast_normalized)).unwrap();
+            std::fs::write(&before_file, ast_before).unwrap();
 
-            let mut after_file = file.clone();
+            let mut after_file = file.to_path_buf();
             after_file.set_extension("roc-format-failed-ast-after");
-            std::fs::write(&after_file, format!("{:#?}\n", reparsed_ast_normalized)).unwrap();
+            std::fs::write(&after_file, ast_after).unwrap();
 
             internal_error!(
                 "Formatting bug; formatting didn't reparse as the same tree\n\n\
@@ -114,18 +256,17 @@ pub fn format(files: std::vec::Vec<PathBuf>, mode: FormatMode) -> Result<(), Str
                 before_file.display(),
                 after_file.display());
         }
-
-        // Now verify that the resultant formatting is _stable_ - i.e. that it doesn't change again if re-formatted
-        let mut reformatted_buf = Buf::new_in(&arena);
-        fmt_all(&mut reformatted_buf, reparsed_ast);
-        if buf.as_str() != reformatted_buf.as_str() {
-            let mut unstable_1_file = file.clone();
+        FormatProblem::ReformattingUnstable {
+            formatted_src,
+            reformatted_src,
+        } => {
+            let mut unstable_1_file = file.to_path_buf();
             unstable_1_file.set_extension("roc-format-unstable-1");
-            std::fs::write(&unstable_1_file, buf.as_str()).unwrap();
+            std::fs::write(&unstable_1_file, formatted_src).unwrap();
 
-            let mut unstable_2_file = file.clone();
+            let mut unstable_2_file = file.to_path_buf();
             unstable_2_file.set_extension("roc-format-unstable-2");
-            std::fs::write(&unstable_2_file, reformatted_buf.as_str()).unwrap();
+            std::fs::write(&unstable_2_file, reformatted_src).unwrap();
 
             internal_error!(
                 "Formatting bug; formatting is not stable. Reformatting the formatted file changed it again.\n\n\
@@ -134,23 +275,71 @@ pub fn format(files: std::vec::Vec<PathBuf>, mode: FormatMode) -> Result<(), Str
                 unstable_1_file.display(),
                 unstable_2_file.display());
         }
+    }
+}
 
-        match mode {
-            FormatMode::CheckOnly => {
-                // If we notice that this file needs to be formatted, return early
-                if buf.as_str() != src {
-                    return Err("One or more files need to be reformatted.".to_string());
-                }
-            }
+/// Reformats the contiguous run of top-level defs that overlap `lines`, along with the
+/// comments between them, and leaves everything else in `src` as it was.
+fn fmt_def_range<'a>(arena: &'a Bump, src: &str, defs: &Defs<'a>, lines: LineRange) -> String {
+    let line_start = |line: u32| -> usize {
+        src.split_inclusive('\n')
+            .take(line as usize - 1)
+            .map(str::len)
+            .sum()
+    };
+    let range_start = line_start(lines.start);
+    let range_end = line_start(lines.end + 1);
 
-            FormatMode::Format => {
-                // If all the checks above passed, actually write out the new file.
-                std::fs::write(&file, buf.as_str()).unwrap();
-            }
+    let selected: Vec<usize> = (0..defs.len())
+        .filter(|&index| {
+            let region = defs.regions[index];
+            (region.start().offset as usize) < range_end
+                && range_start < region.end().offset as usize
+        })
+        .collect();
+
+    let (first, last) = match (selected.first(), selected.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return src.to_string(),
+    };
+
+    let mut selected_defs = Defs::default();
+    for index in first..=last {
+        let spaces_before: &[_] = if index == first {
+            &[]
+        } else {
+            &defs.spaces[defs.space_before[index].indices()]
+        };
+        let spaces_after: &[_] = if index == last {
+            &[]
+        } else {
+            &defs.spaces[defs.space_after[index].indices()]
+        };
+        let region = defs.regions[index];
+
+        match defs.tags[index].split() {
+            Ok(type_index) => selected_defs.push_type_def(
+                defs.type_defs[type_index.index()].clone(),
+                region,
+                spaces_before,
+                spaces_after,
+            ),
+            Err(value_index) => selected_defs.push_value_def(
+                defs.value_defs[value_index.index()].clone(),
+                region,
+                spaces_before,
+                spaces_after,
+            ),
         }
     }
 
-    Ok(())
+    let mut buf = Buf::new_in(arena);
+    fmt_defs(&mut buf, &selected_defs, 0);
+
+    let start = defs.regions[first].start().offset as usize;
+    let end = defs.regions[last].end().offset as usize;
+
+    format!("{}{}{}", &src[..start], buf.as_str(), &src[end..])
 }
 
 fn parse_all<'a>(arena: &'a Bump, src: &'a str) -> Result<Ast<'a>, SyntaxError<'a>> {
@@ -16,10 +16,11 @@ use roc_build::program::{
 use roc_error_macros::{internal_error, user_error};
 use roc_gen_dev::AssemblyBackendMode;
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
-use roc_load::{ExpectMetadata, Threading};
+use roc_load::{ExpectMetadata, LoadConfig, Threading};
 use roc_mono::ir::OptLevel;
 use roc_packaging::cache::RocCacheDir;
 use roc_packaging::tarball::Compression;
+use roc_reporting::report::RenderTarget;
 use std::env;
 use std::ffi::{CString, OsStr, OsString};
 use std::io;
@@ -36,8 +37,16 @@ use target_lexicon::{
 #[cfg(not(target_os = "linux"))]
 use tempfile::TempDir;
 
+mod bundle;
+mod fix;
 mod format;
-pub use format::format;
+pub use fix::{fix, FixKind};
+pub use format::{format, format_src, report_format_problem, FormatProblem, LineRange};
+
+#[cfg(not(windows))]
+mod test_report;
+#[cfg(not(windows))]
+mod watch;
 
 pub const CMD_BUILD: &str = "build";
 pub const CMD_RUN: &str = "run";
@@ -48,9 +57,11 @@ pub const CMD_DOCS: &str = "docs";
 pub const CMD_CHECK: &str = "check";
 pub const CMD_VERSION: &str = "version";
 pub const CMD_FORMAT: &str = "format";
+pub const CMD_FIX: &str = "fix";
 pub const CMD_TEST: &str = "test";
 pub const CMD_GLUE: &str = "glue";
 pub const CMD_GEN_STUB_LIB: &str = "gen-stub-lib";
+pub const CMD_VENDOR: &str = "vendor";
 
 pub const FLAG_DEBUG: &str = "debug";
 pub const FLAG_BUNDLE: &str = "bundle";
@@ -65,7 +76,23 @@ pub const FLAG_TIME: &str = "time";
 pub const FLAG_LINKER: &str = "linker";
 pub const FLAG_PREBUILT: &str = "prebuilt-platform";
 pub const FLAG_CHECK: &str = "check";
+pub const FLAG_STDIN: &str = "stdin";
+pub const FLAG_STDOUT: &str = "stdout";
+pub const FLAG_LINES: &str = "lines";
+pub const FLAG_DRY_RUN: &str = "dry-run";
+pub const FLAG_ADD_MISSING_BRANCHES: &str = "add-missing-branches";
+pub const FLAG_REMOVE_REDUNDANT_BRANCHES: &str = "remove-redundant-branches";
 pub const FLAG_WASM_STACK_SIZE_KB: &str = "wasm-stack-size-kb";
+pub const FLAG_FORMAT: &str = "format";
+pub const FLAG_WATCH: &str = "watch";
+pub const FLAG_WASI_DIR: &str = "wasi-dir";
+pub const FLAG_WASI_ENV: &str = "wasi-env";
+pub const FLAG_FILTER: &str = "filter";
+pub const FLAG_REPORT: &str = "report";
+pub const FLAG_REPORT_FILE: &str = "report-file";
+pub const FLAG_COVERAGE: &str = "coverage";
+pub const FLAG_OFFLINE: &str = "offline";
+pub const FLAG_MAIN: &str = "main";
 pub const ROC_FILE: &str = "ROC_FILE";
 pub const ROC_DIR: &str = "ROC_DIR";
 pub const GLUE_DIR: &str = "GLUE_DIR";
@@ -102,7 +129,7 @@ pub fn build_app() -> Command {
 
     let flag_debug = Arg::new(FLAG_DEBUG)
         .long(FLAG_DEBUG)
-        .help("Store LLVM debug information in the generated program")
+        .help("Store debug information (DWARF) that maps the generated program back to its Roc source")
         .action(ArgAction::SetTrue)
         .required(false);
 
@@ -130,6 +157,38 @@ pub fn build_app() -> Command {
         .value_parser(value_parser!(u32))
         .required(false);
 
+    let flag_format = Arg::new(FLAG_FORMAT)
+        .long(FLAG_FORMAT)
+        .help("Choose how to print problems found in the code\n(`json` prints one JSON object per problem, each on its own line.)")
+        .value_parser(["text", "json"])
+        .default_value("text")
+        .required(false);
+
+    let flag_watch = Arg::new(FLAG_WATCH)
+        .long(FLAG_WATCH)
+        .help("Keep running, and start over whenever a module this depends on changes")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_wasi_dir = Arg::new(FLAG_WASI_DIR)
+        .long(FLAG_WASI_DIR)
+        .help("Let a wasm32 app read and write this directory\n(Can be given more than once. By default, the app can't touch any files.)")
+        .value_parser(value_parser!(PathBuf))
+        .action(ArgAction::Append)
+        .required(false);
+
+    let flag_wasi_env = Arg::new(FLAG_WASI_ENV)
+        .long(FLAG_WASI_ENV)
+        .help("Let a wasm32 app see the environment variables\n(By default, it sees none.)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_offline = Arg::new(FLAG_OFFLINE)
+        .long(FLAG_OFFLINE)
+        .help("Never download packages\n(Instead, only use the ones in the `vendor` directory next to the .roc file, which `roc vendor` creates, and in the cache. Every package must be listed, with the same hash, in the `roc.lock` file that `roc vendor` writes.)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
     let roc_file_to_run = Arg::new(ROC_FILE)
         .help("The .roc file of an app to run")
         .value_parser(value_parser!(PathBuf))
@@ -159,6 +218,8 @@ pub fn build_app() -> Command {
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
             .arg(flag_wasm_stack_size_kb)
+            .arg(flag_format.clone())
+            .arg(flag_offline.clone())
             .arg(
                 Arg::new(FLAG_TARGET)
                     .long(FLAG_TARGET)
@@ -198,7 +259,7 @@ pub fn build_app() -> Command {
             )
         )
         .subcommand(Command::new(CMD_TEST)
-            .about("Run all top-level `expect`s in a main module and any modules it imports")
+            .about("Run all top-level `expect`s (including those in doc comment examples) in a main module and any modules it imports")
             .arg(flag_optimize.clone())
             .arg(flag_max_threads.clone())
             .arg(flag_opt_size.clone())
@@ -207,6 +268,39 @@ pub fn build_app() -> Command {
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_watch.clone())
+            .arg(flag_offline.clone())
+            .arg(
+                Arg::new(FLAG_FILTER)
+                    .long(FLAG_FILTER)
+                    .help("Only run the `expect`s whose module name or location (e.g. `Foo.roc:12`) contains this")
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_REPORT)
+                    .long(FLAG_REPORT)
+                    .help("Also write a report of which `expect`s passed, for tools like CI to read")
+                    .value_parser(["junit", "json"])
+                    .requires(FLAG_REPORT_FILE)
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_REPORT_FILE)
+                    .long(FLAG_REPORT_FILE)
+                    .help("Where to write the --report")
+                    .value_parser(value_parser!(PathBuf))
+                    .requires(FLAG_REPORT)
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_COVERAGE)
+                    .long(FLAG_COVERAGE)
+                    .help("Count how often each def, `when` branch and `if` arm runs, and write the counts to this lcov file")
+                    .value_parser(value_parser!(PathBuf))
+                    .num_args(0..=1)
+                    .default_missing_value("lcov.info")
+                    .required(false)
+            )
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file for the main module")
@@ -218,6 +312,13 @@ pub fn build_app() -> Command {
         )
         .subcommand(Command::new(CMD_REPL)
             .about("Launch the interactive Read Eval Print Loop (REPL)")
+            .arg(
+                Arg::new(FLAG_MAIN)
+                    .long(FLAG_MAIN)
+                    .help("The .roc file of an app, interface or package module whose packages and imports the REPL should be able to use\n(With an app, Tasks entered in the REPL get run using its platform.)")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+            )
         )
         .subcommand(Command::new(CMD_RUN)
             .about("Run a .roc file even if it has build errors")
@@ -229,6 +330,9 @@ pub fn build_app() -> Command {
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_wasi_dir.clone())
+            .arg(flag_wasi_env.clone())
+            .arg(flag_offline.clone())
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone().last(true))
         )
@@ -242,6 +346,10 @@ pub fn build_app() -> Command {
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_watch)
+            .arg(flag_wasi_dir.clone())
+            .arg(flag_wasi_env.clone())
+            .arg(flag_offline.clone())
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone().last(true))
         )
@@ -260,6 +368,63 @@ pub fn build_app() -> Command {
                     .action(ArgAction::SetTrue)
                     .required(false),
             )
+            .arg(
+                Arg::new(FLAG_STDIN)
+                    .long(FLAG_STDIN)
+                    .help("Read the source code to format from stdin and print the result to stdout")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with(DIRECTORY_OR_FILES)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_STDOUT)
+                    .long(FLAG_STDOUT)
+                    .help("Print the formatted files to stdout instead of writing them")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with(FLAG_CHECK)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_LINES)
+                    .long(FLAG_LINES)
+                    .help("Only format the top-level definitions overlapping these lines, e.g. 3:10\n(Requires --stdin.)")
+                    .value_parser(value_parser!(LineRange))
+                    .requires(FLAG_STDIN)
+                    .required(false),
+            )
+        )
+        .subcommand(Command::new(CMD_FIX)
+            .about("Automatically fix warnings about unused imports, arguments and definitions\n(Or, with the flags below, fix incomplete and redundant `when` branches instead.)")
+            .arg(flag_max_threads.clone())
+            .arg(flag_offline.clone())
+            .arg(
+                Arg::new(FLAG_DRY_RUN)
+                    .long(FLAG_DRY_RUN)
+                    .help("Print the fixes as a diff instead of applying them")
+                    .action(ArgAction::SetTrue)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_ADD_MISSING_BRANCHES)
+                    .long(FLAG_ADD_MISSING_BRANCHES)
+                    .help("Add a branch that crashes with \"todo\" for each pattern a `when` is missing")
+                    .action(ArgAction::SetTrue)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_REMOVE_REDUNDANT_BRANCHES)
+                    .long(FLAG_REMOVE_REDUNDANT_BRANCHES)
+                    .help("Remove `when` branches that can never be reached")
+                    .action(ArgAction::SetTrue)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file of an app or package to fix")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME),
+            )
         )
         .subcommand(Command::new(CMD_VERSION)
             .about(concatcp!("Print the Roc compiler’s version, which is currently ", VERSION)))
@@ -267,6 +432,8 @@ pub fn build_app() -> Command {
             .about("Check the code for problems, but don’t build or run it")
             .arg(flag_time.clone())
             .arg(flag_max_threads.clone())
+            .arg(flag_format)
+            .arg(flag_offline.clone())
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file of an app to check")
@@ -285,12 +452,22 @@ pub fn build_app() -> Command {
                     .default_value(DEFAULT_ROC_FILENAME),
                 )
         )
+        .subcommand(Command::new(CMD_VENDOR)
+            .about("Download the packages a .roc file depends on into a `vendor` directory next to it, and list them in a `roc.lock` file\n(Afterwards, it can be built with --offline.)")
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file whose packages should be vendored")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME),
+            )
+        )
         .subcommand(Command::new(CMD_GLUE)
             .about("Generate glue code between a platform's Roc API and its host language")
             .arg(&flag_dev)
             .arg(
                 Arg::new(GLUE_SPEC)
-                    .help("The specification for how to translate Roc types into output files.")
+                    .help("The specification for how to translate Roc types into output files.\nThis can also be `c` or `zig`, to use the C or Zig glue that's built into the compiler.\nThose names always mean the built-in glue; to use a spec file named `c` or `zig`, give its path with a directory, like `./c`.")
                     .value_parser(value_parser!(PathBuf))
                     .required(true)
             )
@@ -333,6 +510,9 @@ pub fn build_app() -> Command {
         .arg(flag_time)
         .arg(flag_linker)
         .arg(flag_prebuilt)
+        .arg(flag_wasi_dir)
+        .arg(flag_wasi_env)
+        .arg(flag_offline)
         .arg(roc_file_to_run)
         .arg(args_for_app.trailing_var_arg(true));
 
@@ -363,6 +543,7 @@ pub enum BuildConfig {
 pub enum FormatMode {
     Format,
     CheckOnly,
+    WriteToStdout,
 }
 
 fn opt_level_from_flags(matches: &ArgMatches) -> OptLevel {
@@ -379,6 +560,97 @@ fn opt_level_from_flags(matches: &ArgMatches) -> OptLevel {
     }
 }
 
+/// Reads `--format`. `roc run` and `roc dev` go through [`build`] too, but don't have that flag.
+pub fn render_target_from_flags(matches: &ArgMatches) -> RenderTarget {
+    match matches.try_get_one::<String>(FLAG_FORMAT).ok().flatten() {
+        Some(format) if format == "json" => RenderTarget::Json,
+        _ => RenderTarget::ColorTerminal,
+    }
+}
+
+/// Where packages are found: the user's cache dir, downloading into it if necessary, or with
+/// `--offline`, only the given vendor dir and the cache dir.
+pub fn roc_cache_dir_from_flags<'a>(
+    matches: &ArgMatches,
+    cache_dir: &'a Path,
+    vendor_dir: &'a Path,
+) -> RocCacheDir<'a> {
+    if matches.get_flag(FLAG_OFFLINE) {
+        RocCacheDir::Offline {
+            cache_dir,
+            vendor_dir,
+        }
+    } else {
+        RocCacheDir::Persistent(cache_dir)
+    }
+}
+
+/// The directory that `roc vendor` copies the packages of the given .roc file into.
+pub fn vendor_dir(roc_file: &Path) -> PathBuf {
+    roc_file
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(roc_packaging::vendor::VENDOR_DIR_NAME)
+}
+
+pub fn vendor(matches: &ArgMatches) -> io::Result<i32> {
+    use roc_packaging::cache;
+    use roc_packaging::vendor::{vendor_packages, VendorProblem, LOCKFILE_NAME};
+    use roc_reporting::report::to_https_problem_report_string;
+
+    let roc_file = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+
+    match vendor_packages(&cache::roc_cache_dir(), roc_file) {
+        Ok(lockfile) => {
+            println!(
+                "Vendored {} {} into {}, and listed {} in {}.",
+                lockfile.packages.len(),
+                if lockfile.packages.len() == 1 {
+                    "package"
+                } else {
+                    "packages"
+                },
+                vendor_dir(roc_file).display(),
+                if lockfile.packages.len() == 1 {
+                    "it"
+                } else {
+                    "them"
+                },
+                LOCKFILE_NAME,
+            );
+
+            Ok(0)
+        }
+        Err(VendorProblem::Package(url, problem)) => {
+            eprint!("{}", to_https_problem_report_string(&url, problem));
+
+            Ok(1)
+        }
+        Err(VendorProblem::ReadHeader(path, err)) => {
+            eprintln!(
+                "I couldn't read {} to find its packages: {}",
+                path.display(),
+                err
+            );
+
+            Ok(1)
+        }
+        Err(VendorProblem::ParseHeader(path)) => {
+            eprintln!(
+                "The header of {} has a syntax error, so I couldn't find its packages. Running `roc check` on it will show what the problem is.",
+                path.display()
+            );
+
+            Ok(1)
+        }
+        Err(VendorProblem::WriteLockfile(path, err)) => {
+            eprintln!("I couldn't write the lockfile {}: {}", path.display(), err);
+
+            Ok(1)
+        }
+    }
+}
+
 #[cfg(windows)]
 pub fn test(_matches: &ArgMatches, _triple: Triple) -> io::Result<i32> {
     todo!("running tests does not work on windows right now")
@@ -386,22 +658,6 @@ pub fn test(_matches: &ArgMatches, _triple: Triple) -> io::Result<i32> {
 
 #[cfg(not(windows))]
 pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
-    use roc_build::program::report_problems_monomorphized;
-    use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError};
-    use roc_packaging::cache;
-    use roc_target::TargetInfo;
-
-    let start_time = Instant::now();
-    let arena = Bump::new();
-    let opt_level = opt_level_from_flags(matches);
-
-    let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
-        None => Threading::AllAvailable,
-        Some(0) => user_error!("cannot build with at most 0 threads"),
-        Some(1) => Threading::Single,
-        Some(n) => Threading::AtMost(*n),
-    };
-
     let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
 
     // Spawn the root task
@@ -427,8 +683,59 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
         process::exit(1);
     }
 
+    if matches.get_flag(FLAG_WATCH) {
+        return watch::watch_test(matches, triple);
+    }
+
+    let run = test_modules(matches, &triple, &|_| true)?;
+
+    Ok(run.exit_code)
+}
+
+/// What happened when running the `expect`s once.
+#[cfg(not(windows))]
+struct TestRun {
+    exit_code: i32,
+    /// `None` if loading stopped before the import graph was known.
+    import_graph: Option<watch::ImportGraph>,
+    /// Whether we got as far as running the `expect`s.
+    ran_expects: bool,
+}
+
+/// Runs the top-level `expect`s of the modules whose files `should_test` accepts.
+#[cfg(not(windows))]
+fn test_modules(
+    matches: &ArgMatches,
+    triple: &Triple,
+    should_test: &dyn Fn(&Path) -> bool,
+) -> io::Result<TestRun> {
+    use roc_build::program::report_problems_monomorphized;
+    use roc_collections::{MutMap, MutSet, VecMap};
+    use roc_load::{ExecutionMode, LoadMonomorphizedError};
+    use roc_module::ident::ModuleName;
+    use roc_module::symbol::{ModuleId, Symbol};
+    use roc_packaging::cache;
+    use roc_region::all::{LineInfo, Region};
+    use roc_repl_expect::run::ExpectOutcome;
+    use roc_target::TargetInfo;
+    use std::time::Duration;
+
+    let start_time = Instant::now();
+    let arena = Bump::new();
+    let opt_level = opt_level_from_flags(matches);
+
+    let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
+        None => Threading::AllAvailable,
+        Some(0) => user_error!("cannot build with at most 0 threads"),
+        Some(1) => Threading::Single,
+        Some(n) => Threading::AtMost(*n),
+    };
+
+    let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+    let coverage_path = matches.get_one::<PathBuf>(FLAG_COVERAGE);
+
     let arena = &arena;
-    let target = &triple;
+    let target = triple;
     let opt_level = opt_level;
     let target_info = TargetInfo::from(target);
 
@@ -439,25 +746,122 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
         render: roc_reporting::report::RenderTarget::ColorTerminal,
         palette: roc_reporting::report::DEFAULT_PALETTE,
         threading,
-        exec_mode: ExecutionMode::Test,
+        exec_mode: match coverage_path {
+            Some(_) => ExecutionMode::TestWithCoverage,
+            None => ExecutionMode::Test,
+        },
     };
     let load_result = roc_load::load_and_monomorphize(
         arena,
         path.to_path_buf(),
-        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        roc_cache_dir_from_flags(matches, &cache::roc_cache_dir(), &vendor_dir(path)),
         load_config,
     );
 
     let mut loaded = match load_result {
         Ok(loaded) => loaded,
         Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
-            return handle_loading_problem(problem);
+            return Ok(TestRun {
+                exit_code: handle_loading_problem(problem)?,
+                import_graph: None,
+                ran_expects: false,
+            });
         }
         Err(LoadMonomorphizedError::ErrorModule(module)) => {
-            return handle_error_module(module, start_time.elapsed(), path.as_os_str(), false);
+            let import_graph = watch::ImportGraph::new(&module.sources, &module.imports);
+            let exit_code = handle_error_module(
+                module,
+                start_time.elapsed(),
+                path.as_os_str(),
+                false,
+                RenderTarget::ColorTerminal,
+            )?;
+
+            return Ok(TestRun {
+                exit_code,
+                import_graph: Some(import_graph),
+                ran_expects: false,
+            });
+        }
+    };
+    let problems = report_problems_monomorphized(&mut loaded, RenderTarget::ColorTerminal);
+
+    let import_graph = watch::ImportGraph::new(&loaded.sources, &loaded.imports);
+
+    let tested_modules: MutSet<_> = loaded
+        .sources
+        .iter()
+        .filter(|(_, (path, _))| should_test(path))
+        .map(|(module_id, _)| *module_id)
+        .collect();
+    let skipped_any = loaded
+        .sources
+        .keys()
+        .any(|module_id| !module_id.is_builtin() && !tested_modules.contains(module_id));
+
+    let covered_regions = loaded.covered_regions.take();
+
+    // Where the tested modules are, to tell which `expect` is which in --filter and the summary.
+    // With --coverage, this covers the other modules too.
+    let modules: MutMap<ModuleId, (String, PathBuf, LineInfo)> = loaded
+        .sources
+        .iter()
+        .filter(|(module_id, _)| {
+            tested_modules.contains(module_id)
+                || (covered_regions.is_some() && !module_id.is_builtin())
+        })
+        .map(|(module_id, (path, src))| {
+            let name = loaded.interns.module_name(*module_id).as_str();
+
+            // App modules don't have names, but their files do.
+            let name = if name == ModuleName::APP {
+                path.file_name()
+                    .map(|file_name| file_name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.display().to_string())
+            } else {
+                name.to_string()
+            };
+
+            (*module_id, (name, path.clone(), LineInfo::new(src)))
+        })
+        .collect();
+
+    let test_case = |symbol: Symbol, region: Region, outcome, duration| {
+        let (module, path, line_info) = &modules[&symbol.module_id()];
+
+        test_report::TestCase {
+            module: module.clone(),
+            path: path.clone(),
+            line: line_info.convert_pos(region.start()).line + 1,
+            outcome,
+            duration,
+        }
+    };
+
+    let filter = matches.get_one::<String>(FLAG_FILTER);
+    let should_run = |symbol: &Symbol, region: &Region| {
+        if !tested_modules.contains(&symbol.module_id()) {
+            return false;
+        }
+
+        match filter {
+            Some(filter) => {
+                let case = test_case(*symbol, *region, ExpectOutcome::Passed, Duration::ZERO);
+
+                case.module.contains(filter.as_str()) || case.location().contains(filter.as_str())
+            }
+            None => true,
         }
     };
-    let problems = report_problems_monomorphized(&mut loaded);
+
+    let retain_tested = |expects: &mut VecMap<Symbol, Region>| {
+        *expects = std::mem::take(expects)
+            .into_iter()
+            .filter(|(symbol, region)| should_run(symbol, region))
+            .collect();
+    };
+    retain_tested(&mut loaded.toplevel_expects.pure);
+    retain_tested(&mut loaded.toplevel_expects.fx);
 
     let mut expectations = std::mem::take(&mut loaded.expectations);
 
@@ -472,6 +876,13 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
     )
     .unwrap();
 
+    let coverage_counts = covered_regions.as_ref().map(|regions| {
+        let counts = roc_repl_expect::run::CoverageCounts::new(regions.len());
+        counts.set_coverage_buffer(&lib);
+
+        counts
+    });
+
     // Print warnings before running tests.
     {
         debug_assert_eq!(
@@ -490,7 +901,7 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
 
     let mut writer = std::io::stdout();
 
-    let (failed, passed) = roc_repl_expect::run::run_toplevel_expects(
+    let results = roc_repl_expect::run::run_toplevel_expects(
         &mut writer,
         roc_reporting::report::RenderTarget::ColorTerminal,
         arena,
@@ -503,31 +914,66 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
     .unwrap();
 
     let total_time = start_time.elapsed();
+    let cases: Vec<_> = results
+        .into_iter()
+        .map(|result| {
+            test_case(
+                result.symbol,
+                result.region,
+                result.outcome,
+                result.duration,
+            )
+        })
+        .collect();
+
+    if let Some(format) = matches.get_one::<String>(FLAG_REPORT) {
+        let report = match format.as_str() {
+            "junit" => test_report::junit(&cases, total_time),
+            "json" => test_report::json(&cases, total_time),
+            _ => unreachable!("clap only accepts the formats it lists"),
+        };
+        let report_path = matches.get_one::<PathBuf>(FLAG_REPORT_FILE).unwrap();
 
-    if failed == 0 && passed == 0 {
-        // TODO print this in a more nicely formatted way!
-        println!("No expectations were found.");
+        std::fs::write(report_path, report)?;
+    }
+
+    if let (Some(coverage_path), Some(regions), Some(counts)) =
+        (coverage_path, &covered_regions, &coverage_counts)
+    {
+        let lcov = test_report::lcov(regions, counts.counts(), &modules, interns);
+
+        std::fs::write(coverage_path, lcov)?;
+    }
+
+    let counts = test_report::counts(&cases);
+
+    let exit_code = if counts.total() == 0 {
+        if skipped_any {
+            println!("None of the changed modules have expectations.");
+        } else if filter.is_some() {
+            println!("None of the expectations match the filter.");
+        } else {
+            // TODO print this in a more nicely formatted way!
+            println!("No expectations were found.");
+        }
 
         // If no tests ran, treat that as an error. This is perhaps
         // briefly annoying at the very beginning of a project when
         // you actually have zero tests, but it can save you from
         // having a change to your CI script accidentally stop
         // running tests altogether!
-        Ok(2)
+        2
     } else {
-        let failed_color = if failed == 0 {
-            32 // green
-        } else {
-            31 // red
-        };
+        test_report::print_summary(&cases, total_time);
 
-        println!(
-            "\n\x1B[{failed_color}m{failed}\x1B[39m failed and \x1B[32m{passed}\x1B[39m passed in {} ms.\n",
-            total_time.as_millis(),
-        );
+        (counts.failed + counts.panicked > 0) as i32
+    };
 
-        Ok((failed > 0) as i32)
-    }
+    Ok(TestRun {
+        exit_code,
+        import_graph: Some(import_graph),
+        ran_expects: true,
+    })
 }
 
 /// Find the element of `options` with the smallest edit distance to
@@ -602,128 +1048,45 @@ pub fn build(
         }
 
         if config == BuildConfig::BuildOnly && matches.contains_id(FLAG_BUNDLE) {
-            let start_time = Instant::now();
-
             let compression =
                 Compression::try_from(matches.get_one::<String>(FLAG_BUNDLE).unwrap().as_str())
                     .unwrap();
-
-            // Print a note of advice. This is mainly here because brotli takes so long but produces
-            // such smaller output files; the idea is to encourage people to wait for brotli,
-            // so that downloads go faster. The compression only happens once, but the network
-            // transfer and decompression will happen many more times!
-            match compression {
-                Compression::Brotli => {
-                    println!("Compressing with Brotli at maximum quality level…\n\n(Note: Brotli compression can take awhile! Using --{FLAG_BUNDLE} .tar.gz takes less time, but usually produces a significantly larger output file. Brotli is generally worth the up-front wait if this is a file people will be downloading!)\n");
-                }
-                Compression::Gzip => {
-                    println!("Compressing with gzip at minimum quality…\n\n(Note: Gzip usually runs faster than Brotli but typically produces significantly larger output files. Consider using --{FLAG_BUNDLE} .tar.br if this is a file people will be downloading!)\n");
-                }
-                Compression::Uncompressed => {
-                    println!("Building .tar archive without compression…\n\n(Note: Compression takes more time to run but typically produces much smaller output files. Consider using --{FLAG_BUNDLE} .tar.br if this is a file people will be downloading!)\n");
-                }
-            }
+            let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
+                None => Threading::AllAvailable,
+                Some(0) => user_error!("cannot build with at most 0 threads"),
+                Some(1) => Threading::Single,
+                Some(n) => Threading::AtMost(*n),
+            };
 
             // Rather than building an executable or library, we're building
             // a tarball so this code can be distributed via a HTTPS
-            let filename = roc_packaging::tarball::build(path, compression)?;
-            let total_time_ms = start_time.elapsed().as_millis();
-            let total_time = if total_time_ms > 1000 {
-                format!("{}s {}ms", total_time_ms / 1000, total_time_ms % 1000)
-            } else {
-                format!("{total_time_ms} ms")
-            };
-            let created_path = path.with_file_name(&filename);
+            return bundle::bundle(path, compression, roc_cache_dir, threading);
+        }
+    }
 
-            println!(
-                "\nBundled \x1B[33m{}\x1B[39m and its dependent files into the following archive in {total_time}:\n\n\t\x1B[33m{}\x1B[39m\n\nTo distribute this archive as a package, upload this to some URL and then add it as a dependency with:\n\n\t\x1B[32m\"https://your-url-goes-here/{filename}\"\x1B[39m\n",
-                path.to_string_lossy(),
-                created_path.to_string_lossy()
-            );
+    // Only `roc dev` has this flag.
+    if let Ok(Some(true)) = matches.try_get_one::<bool>(FLAG_WATCH) {
+        #[cfg(not(windows))]
+        return watch::watch_dev(matches, triple, roc_cache_dir);
 
-            return Ok(0);
-        }
+        #[cfg(windows)]
+        user_error!("`--watch` does not work on windows right now");
     }
 
     // the process will end after this function,
     // so we don't want to spend time freeing these values
     let arena = ManuallyDrop::new(Bump::new());
 
-    let opt_level = if let BuildConfig::BuildAndRunIfNoErrors = config {
-        OptLevel::Development
-    } else {
-        opt_level_from_flags(matches)
-    };
-
-    // Note: This allows using `--dev` with `--optimize`.
-    // This means frontend optimizations and dev backend.
-    let code_gen_backend = if matches.get_flag(FLAG_DEV) {
-        if matches!(triple.architecture, Architecture::Wasm32) {
-            CodeGenBackend::Wasm
-        } else {
-            CodeGenBackend::Assembly(AssemblyBackendMode::Binary)
-        }
-    } else {
-        let backend_mode = match opt_level {
-            OptLevel::Development => LlvmBackendMode::BinaryDev,
-            OptLevel::Normal | OptLevel::Size | OptLevel::Optimize => LlvmBackendMode::Binary,
-        };
-
-        CodeGenBackend::Llvm(backend_mode)
-    };
-
-    let emit_debug_info = matches.get_flag(FLAG_DEBUG);
-    let emit_timings = matches.get_flag(FLAG_TIME);
-
-    let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
-        None => Threading::AllAvailable,
-        Some(0) => user_error!("cannot build with at most 0 threads"),
-        Some(1) => Threading::Single,
-        Some(n) => Threading::AtMost(*n),
-    };
-
-    let wasm_dev_backend = matches!(code_gen_backend, CodeGenBackend::Wasm);
-
-    let linking_strategy = if wasm_dev_backend {
-        LinkingStrategy::Additive
-    } else if !roc_linker::supported(link_type, &triple)
-        || matches.get_one::<String>(FLAG_LINKER).map(|s| s.as_str()) == Some("legacy")
-    {
-        LinkingStrategy::Legacy
-    } else {
-        LinkingStrategy::Surgical
-    };
-
-    let prebuilt = {
-        let cross_compile = triple != Triple::host();
-        let targeting_wasm = matches!(triple.architecture, Architecture::Wasm32);
-
-        matches.get_flag(FLAG_PREBUILT) ||
-            // When compiling for a different target, assume a prebuilt platform.
-            // Otherwise compilation would most likely fail because many toolchains
-            // assume you're compiling for the current machine. We make an exception
-            // for Wasm, because cross-compiling is the norm in that case.
-            (cross_compile && !targeting_wasm)
-    };
-
-    let wasm_dev_stack_bytes: Option<u32> = matches
-        .try_get_one::<u32>(FLAG_WASM_STACK_SIZE_KB)
-        .ok()
-        .flatten()
-        .map(|x| x * 1024);
-
-    let build_ordering = match config {
-        BuildAndRunIfNoErrors => BuildOrdering::BuildIfChecks,
-        _ => BuildOrdering::AlwaysBuild,
-    };
-
-    let code_gen_options = CodeGenOptions {
-        backend: code_gen_backend,
+    let BuildSettings {
         opt_level,
-        emit_debug_info,
-    };
-
-    let load_config = standard_load_config(&triple, build_ordering, threading);
+        code_gen_options,
+        emit_timings,
+        linking_strategy,
+        prebuilt,
+        wasm_dev_stack_bytes,
+        render,
+        load_config,
+    } = build_settings(matches, &config, &triple, link_type);
 
     let res_binary_path = build_file(
         &arena,
@@ -745,8 +1108,13 @@ pub fn build(
             problems,
             total_time,
             expect_metadata,
+            module_paths: _,
         }) => {
             match config {
+                BuildOnly if matches!(render, RenderTarget::Json) => {
+                    // Anything else we print would get in the way of parsing the diagnostics.
+                    Ok(problems.exit_code())
+                }
                 BuildOnly => {
                     // If possible, report the generated executable name relative to the current dir.
                     let generated_filename = binary_path
@@ -793,7 +1161,17 @@ pub fn build(
                     // ManuallyDrop will leak the bytes because we don't drop manually
                     let bytes = &ManuallyDrop::new(std::fs::read(&binary_path).unwrap());
 
-                    roc_run(&arena, opt_level, triple, args, bytes, expect_metadata)
+                    let wasi_access = WasiAccess::from_flags(matches);
+
+                    roc_run(
+                        &arena,
+                        opt_level,
+                        triple,
+                        args,
+                        bytes,
+                        expect_metadata,
+                        wasi_access,
+                    )
                 }
                 BuildAndRunIfNoErrors => {
                     if problems.fatally_errored {
@@ -828,17 +1206,157 @@ pub fn build(
                     // ManuallyDrop will leak the bytes because we don't drop manually
                     let bytes = &ManuallyDrop::new(std::fs::read(&binary_path).unwrap());
 
-                    roc_run(&arena, opt_level, triple, args, bytes, expect_metadata)
+                    let wasi_access = WasiAccess::from_flags(matches);
+
+                    roc_run(
+                        &arena,
+                        opt_level,
+                        triple,
+                        args,
+                        bytes,
+                        expect_metadata,
+                        wasi_access,
+                    )
                 }
             }
         }
         Err(BuildFileError::ErrorModule { module, total_time }) => {
-            handle_error_module(module, total_time, path.as_os_str(), true)
+            handle_error_module(module, total_time, path.as_os_str(), true, render)
         }
         Err(BuildFileError::LoadingProblem(problem)) => handle_loading_problem(problem),
     }
 }
 
+/// How to build an app, according to the command-line flags.
+struct BuildSettings {
+    opt_level: OptLevel,
+    code_gen_options: CodeGenOptions,
+    emit_timings: bool,
+    linking_strategy: LinkingStrategy,
+    prebuilt: bool,
+    wasm_dev_stack_bytes: Option<u32>,
+    render: RenderTarget,
+    load_config: LoadConfig,
+}
+
+fn build_settings(
+    matches: &ArgMatches,
+    config: &BuildConfig,
+    triple: &Triple,
+    link_type: LinkType,
+) -> BuildSettings {
+    let opt_level = if let BuildConfig::BuildAndRunIfNoErrors = config {
+        OptLevel::Development
+    } else {
+        opt_level_from_flags(matches)
+    };
+
+    // Note: This allows using `--dev` with `--optimize`.
+    // This means frontend optimizations and dev backend.
+    let code_gen_backend = if matches.get_flag(FLAG_DEV) {
+        if matches!(triple.architecture, Architecture::Wasm32) {
+            CodeGenBackend::Wasm
+        } else {
+            CodeGenBackend::Assembly(AssemblyBackendMode::Binary)
+        }
+    } else {
+        let backend_mode = match opt_level {
+            OptLevel::Development => LlvmBackendMode::BinaryDev,
+            OptLevel::Normal | OptLevel::Size | OptLevel::Optimize => LlvmBackendMode::Binary,
+        };
+
+        CodeGenBackend::Llvm(backend_mode)
+    };
+
+    let emit_debug_info = matches.get_flag(FLAG_DEBUG);
+    let emit_timings = matches.get_flag(FLAG_TIME);
+
+    let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
+        None => Threading::AllAvailable,
+        Some(0) => user_error!("cannot build with at most 0 threads"),
+        Some(1) => Threading::Single,
+        Some(n) => Threading::AtMost(*n),
+    };
+
+    let wasm_dev_backend = matches!(code_gen_backend, CodeGenBackend::Wasm);
+
+    let linking_strategy = if wasm_dev_backend {
+        LinkingStrategy::Additive
+    } else if !roc_linker::supported(link_type, triple)
+        || matches.get_one::<String>(FLAG_LINKER).map(|s| s.as_str()) == Some("legacy")
+    {
+        LinkingStrategy::Legacy
+    } else {
+        LinkingStrategy::Surgical
+    };
+
+    let prebuilt = {
+        let cross_compile = *triple != Triple::host();
+        let targeting_wasm = matches!(triple.architecture, Architecture::Wasm32);
+
+        matches.get_flag(FLAG_PREBUILT) ||
+            // When compiling for a different target, assume a prebuilt platform.
+            // Otherwise compilation would most likely fail because many toolchains
+            // assume you're compiling for the current machine. We make an exception
+            // for Wasm, because cross-compiling is the norm in that case.
+            (cross_compile && !targeting_wasm)
+    };
+
+    let wasm_dev_stack_bytes: Option<u32> = matches
+        .try_get_one::<u32>(FLAG_WASM_STACK_SIZE_KB)
+        .ok()
+        .flatten()
+        .map(|x| x * 1024);
+
+    let build_ordering = match config {
+        BuildConfig::BuildAndRunIfNoErrors => BuildOrdering::BuildIfChecks,
+        _ => BuildOrdering::AlwaysBuild,
+    };
+
+    let code_gen_options = CodeGenOptions {
+        backend: code_gen_backend,
+        opt_level,
+        emit_debug_info,
+    };
+
+    let render = render_target_from_flags(matches);
+    let mut load_config = standard_load_config(triple, build_ordering, threading);
+    load_config.render = render;
+
+    BuildSettings {
+        opt_level,
+        code_gen_options,
+        emit_timings,
+        linking_strategy,
+        prebuilt,
+        wasm_dev_stack_bytes,
+        render,
+        load_config,
+    }
+}
+
+/// What a wasm32 app run by the CLI may access on the host, through WASI.
+/// It gets nothing unless asked for with --wasi-dir or --wasi-env.
+#[cfg_attr(not(feature = "run-wasm32"), allow(dead_code))]
+struct WasiAccess {
+    dirs: Vec<PathBuf>,
+    inherit_env: bool,
+}
+
+impl WasiAccess {
+    fn from_flags(matches: &ArgMatches) -> Self {
+        let dirs = match matches.try_get_many::<PathBuf>(FLAG_WASI_DIR) {
+            Ok(Some(dirs)) => dirs.cloned().collect(),
+            _ => Vec::new(),
+        };
+
+        WasiAccess {
+            dirs,
+            inherit_env: matches!(matches.try_get_one::<bool>(FLAG_WASI_ENV), Ok(Some(true))),
+        }
+    }
+}
+
 fn roc_run<'a, I: IntoIterator<Item = &'a OsStr>>(
     arena: &Bump,
     opt_level: OptLevel,
@@ -846,6 +1364,7 @@ fn roc_run<'a, I: IntoIterator<Item = &'a OsStr>>(
     args: I,
     binary_bytes: &[u8],
     expect_metadata: ExpectMetadata,
+    wasi_access: WasiAccess,
 ) -> io::Result<i32> {
     match triple.architecture {
         Architecture::Wasm32 => {
@@ -863,6 +1382,7 @@ fn roc_run<'a, I: IntoIterator<Item = &'a OsStr>>(
                 run_wasm(
                     generated_filename,
                     args.into_iter().map(|os_str| os_str.as_bytes()),
+                    &wasi_access,
                 );
             }
 
@@ -875,6 +1395,7 @@ fn roc_run<'a, I: IntoIterator<Item = &'a OsStr>>(
                             "Roc does not currently support passing non-UTF8 arguments to Wasm.",
                         )
                     }),
+                    &wasi_access,
                 );
             }
 
@@ -949,23 +1470,12 @@ fn roc_run_native<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
     binary_bytes: &[u8],
     expect_metadata: ExpectMetadata,
 ) -> std::io::Result<i32> {
-    use bumpalo::collections::CollectIn;
-
     unsafe {
         let executable = roc_run_executable_file_path(binary_bytes)?;
         let (argv_cstrings, envp_cstrings) = make_argv_envp(arena, &executable, args);
 
-        let argv: bumpalo::collections::Vec<*const c_char> = argv_cstrings
-            .iter()
-            .map(|s| s.as_ptr())
-            .chain([std::ptr::null()])
-            .collect_in(arena);
-
-        let envp: bumpalo::collections::Vec<*const c_char> = envp_cstrings
-            .iter()
-            .map(|s| s.as_ptr())
-            .chain([std::ptr::null()])
-            .collect_in(arena);
+        let argv = null_terminated_pointers(arena, &argv_cstrings);
+        let envp = null_terminated_pointers(arena, &envp_cstrings);
 
         match opt_level {
             OptLevel::Development => roc_dev_native(arena, executable, argv, envp, expect_metadata),
@@ -978,6 +1488,40 @@ fn roc_run_native<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
     Ok(1)
 }
 
+/// Like [roc_run_native] in development mode, except that instead of taking over this process,
+/// the app runs until it exits or `stop` gets set.
+#[cfg(target_family = "unix")]
+fn roc_dev_until<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
+    arena: &Bump,
+    args: I,
+    binary_bytes: &[u8],
+    expect_metadata: ExpectMetadata,
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+) -> std::io::Result<()> {
+    let executable = roc_run_executable_file_path(binary_bytes)?;
+    let (argv_cstrings, envp_cstrings) = make_argv_envp(arena, &executable, args);
+
+    let argv = null_terminated_pointers(arena, &argv_cstrings);
+    let envp = null_terminated_pointers(arena, &envp_cstrings);
+
+    roc_dev_native_until(arena, executable, argv, envp, expect_metadata, stop);
+
+    Ok(())
+}
+
+fn null_terminated_pointers<'a>(
+    arena: &'a Bump,
+    cstrings: &[CString],
+) -> bumpalo::collections::Vec<'a, *const c_char> {
+    use bumpalo::collections::CollectIn;
+
+    cstrings
+        .iter()
+        .map(|s| s.as_ptr())
+        .chain([std::ptr::null()])
+        .collect_in(arena)
+}
+
 unsafe fn roc_run_native_fast(
     executable: ExecutableFile,
     argv: &[*const c_char],
@@ -1051,6 +1595,25 @@ fn roc_dev_native(
 ) -> ! {
     use std::sync::{atomic::AtomicBool, Arc};
 
+    let stop = Arc::new(AtomicBool::new(false));
+    roc_dev_native_until(arena, executable, argv, envp, expect_metadata, stop);
+
+    std::process::exit(0)
+}
+
+/// Runs the app in a child process until it exits, rendering its failed `expect`s and `dbg`s along
+/// the way. Setting `stop` kills the child.
+#[cfg(target_family = "unix")]
+fn roc_dev_native_until(
+    arena: &Bump,
+    executable: ExecutableFile,
+    argv: bumpalo::collections::Vec<*const c_char>,
+    envp: bumpalo::collections::Vec<*const c_char>,
+    expect_metadata: ExpectMetadata,
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+) {
+    use std::sync::Arc;
+
     use roc_repl_expect::run::{ChildProcessMsg, ExpectMemory};
 
     let ExpectMetadata {
@@ -1086,13 +1649,14 @@ fn roc_dev_native(
 
             std::process::exit(1)
         }
-        1.. => {
-            let sigchld = Arc::new(AtomicBool::new(false));
-            signal_hook::flag::register(signal_hook::consts::SIGCHLD, Arc::clone(&sigchld))
-                .unwrap();
+        child_pid @ 1.. => {
+            // The child exiting stops us too.
+            let sigchld =
+                signal_hook::flag::register(signal_hook::consts::SIGCHLD, Arc::clone(&stop))
+                    .unwrap();
 
             loop {
-                match memory.wait_for_child(sigchld.clone()) {
+                match memory.wait_for_child(stop.clone()) {
                     ChildProcessMsg::Terminate => break,
                     ChildProcessMsg::Expect => {
                         roc_repl_expect::run::render_expects_in_memory(
@@ -1123,7 +1687,13 @@ fn roc_dev_native(
                 }
             }
 
-            std::process::exit(0)
+            signal_hook::low_level::unregister(sigchld);
+
+            // If the child already exited, this just reaps it.
+            unsafe {
+                libc::kill(child_pid, libc::SIGKILL);
+                libc::waitpid(child_pid, std::ptr::null_mut(), 0);
+            }
         }
         _ => unreachable!(),
     }
@@ -1211,25 +1781,14 @@ fn roc_run_native<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
     binary_bytes: &[u8],
     _expect_metadata: ExpectMetadata,
 ) -> io::Result<i32> {
-    use bumpalo::collections::CollectIn;
-
     unsafe {
         let executable = roc_run_executable_file_path(binary_bytes)?;
 
         // TODO forward the arguments
         let (argv_cstrings, envp_cstrings) = make_argv_envp(&arena, &executable, args);
 
-        let argv: bumpalo::collections::Vec<*const c_char> = argv_cstrings
-            .iter()
-            .map(|s| s.as_ptr())
-            .chain([std::ptr::null()])
-            .collect_in(arena);
-
-        let envp: bumpalo::collections::Vec<*const c_char> = envp_cstrings
-            .iter()
-            .map(|s| s.as_ptr())
-            .chain([std::ptr::null()])
-            .collect_in(arena);
+        let argv = null_terminated_pointers(arena, &argv_cstrings);
+        let envp = null_terminated_pointers(arena, &envp_cstrings);
 
         match opt_level {
             OptLevel::Development => {
@@ -1246,7 +1805,11 @@ fn roc_run_native<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
 }
 
 #[cfg(feature = "run-wasm32")]
-fn run_wasm<I: Iterator<Item = S>, S: AsRef<[u8]>>(wasm_path: &std::path::Path, args: I) {
+fn run_wasm<I: Iterator<Item = S>, S: AsRef<[u8]>>(
+    wasm_path: &std::path::Path,
+    args: I,
+    wasi_access: &WasiAccess,
+) {
     use bumpalo::collections::Vec;
     use roc_wasm_interp::{DefaultImportDispatcher, Instance};
 
@@ -1259,7 +1822,15 @@ fn run_wasm<I: Iterator<Item = S>, S: AsRef<[u8]>>(wasm_path: &std::path::Path,
         arg_copy.extend_from_slice(arg.as_ref());
         argv.push(arg_copy.into_bump_slice());
     }
-    let import_dispatcher = DefaultImportDispatcher::new(&argv);
+    let mut import_dispatcher = DefaultImportDispatcher::new(&argv);
+    if wasi_access.inherit_env {
+        import_dispatcher.wasi.inherit_env();
+    }
+    for dir in wasi_access.dirs.iter() {
+        import_dispatcher
+            .wasi
+            .preopen_dir(&dir.to_string_lossy(), dir);
+    }
 
     let mut instance = Instance::from_bytes(&arena, &bytes, import_dispatcher, false).unwrap();
 
@@ -1272,7 +1843,11 @@ fn run_wasm<I: Iterator<Item = S>, S: AsRef<[u8]>>(wasm_path: &std::path::Path,
 }
 
 #[cfg(not(feature = "run-wasm32"))]
-fn run_wasm<I: Iterator<Item = S>, S: AsRef<[u8]>>(_wasm_path: &std::path::Path, _args: I) {
+fn run_wasm<I: Iterator<Item = S>, S: AsRef<[u8]>>(
+    _wasm_path: &std::path::Path,
+    _args: I,
+    _wasi_access: &WasiAccess,
+) {
     println!("Running wasm files is not supported on this target.");
 }
 
@@ -2,10 +2,13 @@
 use roc_build::link::LinkType;
 use roc_build::program::{check_file, CodeGenBackend};
 use roc_cli::{
-    build_app, format, test, BuildConfig, FormatMode, Target, CMD_BUILD, CMD_CHECK, CMD_DEV,
-    CMD_DOCS, CMD_EDIT, CMD_FORMAT, CMD_GEN_STUB_LIB, CMD_GLUE, CMD_REPL, CMD_RUN, CMD_TEST,
-    CMD_VERSION, DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_DEV, FLAG_LIB, FLAG_NO_LINK, FLAG_TARGET,
-    FLAG_TIME, GLUE_DIR, GLUE_SPEC, ROC_FILE,
+    build_app, fix, format, format_src, render_target_from_flags, report_format_problem,
+    roc_cache_dir_from_flags, test, vendor, vendor_dir, BuildConfig, FixKind, FormatMode,
+    FormatProblem, LineRange, Target, CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_DOCS, CMD_EDIT, CMD_FIX,
+    CMD_FORMAT, CMD_GEN_STUB_LIB, CMD_GLUE, CMD_REPL, CMD_RUN, CMD_TEST, CMD_VENDOR, CMD_VERSION,
+    DIRECTORY_OR_FILES, FLAG_ADD_MISSING_BRANCHES, FLAG_CHECK, FLAG_DEV, FLAG_DRY_RUN, FLAG_LIB,
+    FLAG_LINES, FLAG_MAIN, FLAG_NO_LINK, FLAG_REMOVE_REDUNDANT_BRANCHES, FLAG_STDIN, FLAG_STDOUT,
+    FLAG_TARGET, FLAG_TIME, GLUE_DIR, GLUE_SPEC, ROC_FILE,
 };
 use roc_docs::generate_docs_html;
 use roc_error_macros::user_error;
@@ -13,8 +16,9 @@ use roc_gen_dev::AssemblyBackendMode;
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
 use roc_load::{LoadingProblem, Threading};
 use roc_packaging::cache::{self, RocCacheDir};
+use roc_reporting::report::RenderTarget;
 use std::fs::{self, FileType};
-use std::io;
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use target_lexicon::Triple;
@@ -47,7 +51,11 @@ fn main() -> io::Result<()> {
                     &subcommands,
                     BuildConfig::BuildAndRunIfNoErrors,
                     Triple::host(),
-                    RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+                    roc_cache_dir_from_flags(
+                        &matches,
+                        &cache::roc_cache_dir(),
+                        &vendor_dir(matches.get_one::<PathBuf>(ROC_FILE).unwrap()),
+                    ),
                     LinkType::Executable,
                 )
             } else {
@@ -63,7 +71,11 @@ fn main() -> io::Result<()> {
                     &subcommands,
                     BuildConfig::BuildAndRun,
                     Triple::host(),
-                    RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+                    roc_cache_dir_from_flags(
+                        matches,
+                        &cache::roc_cache_dir(),
+                        &vendor_dir(matches.get_one::<PathBuf>(ROC_FILE).unwrap()),
+                    ),
                     LinkType::Executable,
                 )
             } else {
@@ -88,7 +100,11 @@ fn main() -> io::Result<()> {
                     &subcommands,
                     BuildConfig::BuildAndRunIfNoErrors,
                     Triple::host(),
-                    RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+                    roc_cache_dir_from_flags(
+                        matches,
+                        &cache::roc_cache_dir(),
+                        &vendor_dir(matches.get_one::<PathBuf>(ROC_FILE).unwrap()),
+                    ),
                     LinkType::Executable,
                 )
             } else {
@@ -145,7 +161,11 @@ fn main() -> io::Result<()> {
                 &subcommands,
                 BuildConfig::BuildOnly,
                 target.to_triple(),
-                RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+                roc_cache_dir_from_flags(
+                    matches,
+                    &cache::roc_cache_dir(),
+                    &vendor_dir(matches.get_one::<PathBuf>(ROC_FILE).unwrap()),
+                ),
                 link_type,
             )?)
         }
@@ -154,6 +174,7 @@ fn main() -> io::Result<()> {
 
             let emit_timings = matches.get_flag(FLAG_TIME);
             let roc_file_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+            let render = render_target_from_flags(matches);
             let threading = match matches.get_one::<usize>(roc_cli::FLAG_MAX_THREADS) {
                 None => Threading::AllAvailable,
                 Some(0) => user_error!("cannot build with at most 0 threads"),
@@ -165,9 +186,17 @@ fn main() -> io::Result<()> {
                 &arena,
                 roc_file_path.to_owned(),
                 emit_timings,
-                RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+                roc_cache_dir_from_flags(
+                    matches,
+                    &cache::roc_cache_dir(),
+                    &vendor_dir(roc_file_path),
+                ),
                 threading,
+                render,
             ) {
+                Ok((problems, _)) if matches!(render, RenderTarget::Json) => {
+                    Ok(problems.exit_code())
+                }
                 Ok((problems, total_time)) => {
                     println!(
                         "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms.",
@@ -209,7 +238,55 @@ fn main() -> io::Result<()> {
                 }
             }
         }
-        Some((CMD_REPL, _)) => Ok(roc_repl_cli::main()),
+        Some((CMD_FIX, matches)) => {
+            let arena = bumpalo::Bump::new();
+
+            let roc_file_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+            let threading = match matches.get_one::<usize>(roc_cli::FLAG_MAX_THREADS) {
+                None => Threading::AllAvailable,
+                Some(0) => user_error!("cannot build with at most 0 threads"),
+                Some(1) => Threading::Single,
+                Some(n) => Threading::AtMost(*n),
+            };
+
+            let mut kinds = Vec::new();
+            if matches.get_flag(FLAG_ADD_MISSING_BRANCHES) {
+                kinds.push(FixKind::MissingBranches);
+            }
+            if matches.get_flag(FLAG_REMOVE_REDUNDANT_BRANCHES) {
+                kinds.push(FixKind::RedundantBranches);
+            }
+            if kinds.is_empty() {
+                kinds.push(FixKind::UnusedCode);
+            }
+
+            match fix(
+                &arena,
+                roc_file_path.to_owned(),
+                roc_cache_dir_from_flags(
+                    matches,
+                    &cache::roc_cache_dir(),
+                    &vendor_dir(roc_file_path),
+                ),
+                threading,
+                &kinds,
+                matches.get_flag(FLAG_DRY_RUN),
+            ) {
+                Ok(exit_code) => Ok(exit_code),
+                Err(LoadingProblem::FormattedReport(report)) => {
+                    print!("{}", report);
+
+                    Ok(1)
+                }
+                Err(other) => {
+                    panic!("fix failed with error:\n{:?}", other);
+                }
+            }
+        }
+        Some((CMD_VENDOR, matches)) => vendor(matches),
+        Some((CMD_REPL, matches)) => Ok(roc_repl_cli::main(
+            matches.get_one::<PathBuf>(FLAG_MAIN).map(PathBuf::as_path),
+        )),
         Some((CMD_EDIT, matches)) => {
             match matches
                 .get_many::<OsString>(DIRECTORY_OR_FILES)
@@ -233,6 +310,40 @@ fn main() -> io::Result<()> {
 
             Ok(0)
         }
+        Some((CMD_FORMAT, matches)) if matches.get_flag(FLAG_STDIN) => {
+            let mut src = String::new();
+            io::stdin().read_to_string(&mut src)?;
+
+            let arena = bumpalo::Bump::new();
+            let lines = matches.get_one::<LineRange>(FLAG_LINES).copied();
+
+            match format_src(&arena, &src, lines) {
+                Ok(formatted_src) if matches.get_flag(FLAG_CHECK) => {
+                    if formatted_src == src {
+                        Ok(0)
+                    } else {
+                        eprintln!("The source code from stdin needs to be reformatted.");
+                        Ok(1)
+                    }
+                }
+                Ok(formatted_src) => {
+                    print!("{}", formatted_src);
+
+                    Ok(0)
+                }
+                Err(FormatProblem::InvalidSource { parse_err }) => {
+                    eprintln!(
+                        "I could not parse the source code from stdin:\n\n{}",
+                        parse_err
+                    );
+
+                    Ok(1)
+                }
+                Err(problem) => {
+                    report_format_problem(&std::env::temp_dir().join("stdin.roc"), problem)
+                }
+            }
+        }
         Some((CMD_FORMAT, matches)) => {
             let maybe_values = matches.get_many::<OsString>(DIRECTORY_OR_FILES);
 
@@ -264,9 +375,12 @@ fn main() -> io::Result<()> {
                 roc_files_recursive(os_str.as_os_str(), metadata.file_type(), &mut roc_files)?;
             }
 
-            let format_mode = match matches.get_flag(FLAG_CHECK) {
-                true => FormatMode::CheckOnly,
-                false => FormatMode::Format,
+            let format_mode = if matches.get_flag(FLAG_CHECK) {
+                FormatMode::CheckOnly
+            } else if matches.get_flag(FLAG_STDOUT) {
+                FormatMode::WriteToStdout
+            } else {
+                FormatMode::Format
             };
 
             let format_exit_code = match format(roc_files, format_mode) {
@@ -0,0 +1,445 @@
+//! What `roc test` says about the `expect`s it ran: a summary for people, JUnit XML or JSON
+//! reports for CI, and lcov files for coverage tools.
+use roc_collections::MutMap;
+use roc_module::symbol::{Interns, ModuleId};
+use roc_mono::coverage::{CoveredKind, CoveredRegion};
+use roc_region::all::LineInfo;
+use roc_repl_expect::run::ExpectOutcome;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// One top-level `expect`, after it ran.
+#[derive(Debug, Clone)]
+pub(crate) struct TestCase {
+    /// The module's name, or its file name for an app module, since those don't have one.
+    pub module: String,
+    pub path: PathBuf,
+    /// Starts at 1, like the line numbers in reports.
+    pub line: u32,
+    pub outcome: ExpectOutcome,
+    pub duration: Duration,
+}
+
+impl TestCase {
+    /// This is also what `--filter` matches, along with the module name.
+    pub fn location(&self) -> String {
+        format!("{}:{}", self.path.display(), self.line)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Counts {
+    pub passed: usize,
+    pub failed: usize,
+    pub panicked: usize,
+}
+
+impl Counts {
+    fn add(&mut self, outcome: ExpectOutcome) {
+        match outcome {
+            ExpectOutcome::Passed => self.passed += 1,
+            ExpectOutcome::Failed => self.failed += 1,
+            ExpectOutcome::Panicked => self.panicked += 1,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.passed + self.failed + self.panicked
+    }
+
+    /// e.g. "1 failed and 2 passed", mentioning panics only if there were any.
+    fn describe(&self) -> String {
+        let red_if_any = |count: usize| if count == 0 { 32 } else { 31 };
+        let mut buf = format!(
+            "\x1B[{}m{}\x1B[39m failed",
+            red_if_any(self.failed),
+            self.failed
+        );
+
+        if self.panicked > 0 {
+            write!(buf, ", \x1B[31m{}\x1B[39m panicked", self.panicked).unwrap();
+        }
+
+        write!(buf, " and \x1B[32m{}\x1B[39m passed", self.passed).unwrap();
+
+        buf
+    }
+}
+
+/// Groups the cases by module, sorted by module name so the order doesn't depend on scheduling.
+fn by_module(cases: &[TestCase]) -> Vec<(&str, Counts, Vec<&TestCase>)> {
+    let mut modules: Vec<(&str, Counts, Vec<&TestCase>)> = Vec::new();
+
+    for case in cases {
+        let index = match modules.iter().position(|(name, _, _)| *name == case.module) {
+            Some(index) => index,
+            None => {
+                modules.push((&case.module, Counts::default(), Vec::new()));
+                modules.len() - 1
+            }
+        };
+        let (_, counts, module_cases) = &mut modules[index];
+
+        counts.add(case.outcome);
+        module_cases.push(case);
+    }
+
+    modules.sort_by_key(|(name, _, _)| *name);
+
+    modules
+}
+
+pub(crate) fn counts(cases: &[TestCase]) -> Counts {
+    let mut counts = Counts::default();
+
+    for case in cases {
+        counts.add(case.outcome);
+    }
+
+    counts
+}
+
+/// A line for each module, and then the totals.
+pub(crate) fn print_summary(cases: &[TestCase], total_time: Duration) {
+    println!();
+
+    for (module, counts, _) in by_module(cases) {
+        println!("{module}: {}", counts.describe());
+    }
+
+    println!(
+        "\n{} in {} ms.\n",
+        counts(cases).describe(),
+        total_time.as_millis()
+    );
+}
+
+pub(crate) fn junit(cases: &[TestCase], total_time: Duration) -> String {
+    let totals = counts(cases);
+    let mut buf = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+    writeln!(
+        buf,
+        r#"<testsuites name="roc test" tests="{}" failures="{}" errors="{}" time="{:.3}">"#,
+        totals.total(),
+        totals.failed,
+        totals.panicked,
+        total_time.as_secs_f64()
+    )
+    .unwrap();
+
+    for (module, counts, module_cases) in by_module(cases) {
+        let module_time: Duration = module_cases.iter().map(|case| case.duration).sum();
+
+        writeln!(
+            buf,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}" time="{:.3}">"#,
+            xml_escape(module),
+            counts.total(),
+            counts.failed,
+            counts.panicked,
+            module_time.as_secs_f64()
+        )
+        .unwrap();
+
+        for case in module_cases {
+            write!(
+                buf,
+                r#"    <testcase name="{}" classname="{}" file="{}" line="{}" time="{:.3}""#,
+                xml_escape(&case.location()),
+                xml_escape(module),
+                xml_escape(&case.path.display().to_string()),
+                case.line,
+                case.duration.as_secs_f64()
+            )
+            .unwrap();
+
+            // JUnit calls it an error, rather than a failure, when a test crashes.
+            match case.outcome {
+                ExpectOutcome::Passed => buf.push_str("/>\n"),
+                ExpectOutcome::Failed => buf.push_str(
+                    ">\n      <failure message=\"This expectation failed.\"/>\n    </testcase>\n",
+                ),
+                ExpectOutcome::Panicked => buf.push_str(
+                    ">\n      <error message=\"This expectation crashed.\"/>\n    </testcase>\n",
+                ),
+            }
+        }
+
+        buf.push_str("  </testsuite>\n");
+    }
+
+    buf.push_str("</testsuites>\n");
+
+    buf
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut buf = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        match ch {
+            '&' => buf.push_str("&amp;"),
+            '<' => buf.push_str("&lt;"),
+            '>' => buf.push_str("&gt;"),
+            '"' => buf.push_str("&quot;"),
+            '\'' => buf.push_str("&apos;"),
+            _ => buf.push(ch),
+        }
+    }
+
+    buf
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    passed: usize,
+    failed: usize,
+    panicked: usize,
+    duration_ms: f64,
+    tests: Vec<JsonTest<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonTest<'a> {
+    module: &'a str,
+    file: &'a PathBuf,
+    line: u32,
+    outcome: &'static str,
+    duration_ms: f64,
+}
+
+pub(crate) fn json(cases: &[TestCase], total_time: Duration) -> String {
+    let totals = counts(cases);
+    let report = JsonReport {
+        passed: totals.passed,
+        failed: totals.failed,
+        panicked: totals.panicked,
+        duration_ms: total_time.as_secs_f64() * 1000.0,
+        tests: cases
+            .iter()
+            .map(|case| JsonTest {
+                module: &case.module,
+                file: &case.path,
+                line: case.line,
+                outcome: match case.outcome {
+                    ExpectOutcome::Passed => "passed",
+                    ExpectOutcome::Failed => "failed",
+                    ExpectOutcome::Panicked => "panicked",
+                },
+                duration_ms: case.duration.as_secs_f64() * 1000.0,
+            })
+            .collect(),
+    };
+
+    serde_json::to_string_pretty(&report).expect("test reports always serialize")
+}
+
+/// The hit counts of the covered regions, as an lcov tracefile. Defs are reported as functions,
+/// `when` branches and `if` arms as branches, and both count towards the lines they start on.
+pub(crate) fn lcov(
+    regions: &[CoveredRegion],
+    counts: &[u64],
+    modules: &MutMap<ModuleId, (String, PathBuf, LineInfo)>,
+    interns: &Interns,
+) -> String {
+    let mut by_module: Vec<(&PathBuf, &LineInfo, Vec<(&CoveredRegion, u64)>)> = Vec::new();
+
+    for (covered, count) in regions.iter().zip(counts) {
+        let (_, path, line_info) = match modules.get(&covered.module_id) {
+            Some(module) => module,
+            None => continue,
+        };
+
+        let index = match by_module.iter().position(|(p, _, _)| *p == path) {
+            Some(index) => index,
+            None => {
+                by_module.push((path, line_info, Vec::new()));
+                by_module.len() - 1
+            }
+        };
+
+        by_module[index].2.push((covered, *count));
+    }
+
+    by_module.sort_by_key(|(path, _, _)| *path);
+
+    let mut buf = String::new();
+
+    for (path, line_info, mut covered) in by_module {
+        let line_of =
+            |covered: &CoveredRegion| line_info.convert_pos(covered.region.start()).line + 1;
+
+        covered.sort_by_key(|(covered, _)| covered.region);
+
+        writeln!(buf, "TN:\nSF:{}", path.display()).unwrap();
+
+        let mut lines: BTreeMap<u32, u64> = BTreeMap::new();
+        let mut functions = Vec::new();
+        let mut branches = Vec::new();
+
+        for (covered, count) in covered {
+            let line = line_of(covered);
+            let line_count = lines.entry(line).or_insert(0);
+            *line_count = (*line_count).max(count);
+
+            match covered.kind {
+                CoveredKind::Def(symbol) => functions.push((line, symbol.as_str(interns), count)),
+                CoveredKind::Branch => branches.push((line, count)),
+            }
+        }
+
+        for (line, name, _) in &functions {
+            writeln!(buf, "FN:{line},{name}").unwrap();
+        }
+
+        for (_, name, count) in &functions {
+            writeln!(buf, "FNDA:{count},{name}").unwrap();
+        }
+
+        let functions_hit = functions.iter().filter(|(_, _, count)| *count > 0).count();
+
+        writeln!(buf, "FNF:{}\nFNH:{functions_hit}", functions.len()).unwrap();
+
+        // every branch gets its own block, since we don't track which `when` it belongs to
+        for (block, (line, count)) in branches.iter().enumerate() {
+            writeln!(buf, "BRDA:{line},{block},0,{count}").unwrap();
+        }
+
+        let branches_hit = branches.iter().filter(|(_, count)| *count > 0).count();
+
+        writeln!(buf, "BRF:{}\nBRH:{branches_hit}", branches.len()).unwrap();
+
+        for (line, count) in &lines {
+            writeln!(buf, "DA:{line},{count}").unwrap();
+        }
+
+        let lines_hit = lines.values().filter(|count| **count > 0).count();
+
+        writeln!(buf, "LF:{}\nLH:{lines_hit}", lines.len()).unwrap();
+
+        buf.push_str("end_of_record\n");
+    }
+
+    buf
+}
+
+#[cfg(test)]
+mod test {
+    use super::{json, junit, TestCase};
+    use roc_repl_expect::run::ExpectOutcome;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn case(module: &str, path: &str, line: u32, outcome: ExpectOutcome) -> TestCase {
+        TestCase {
+            module: module.to_string(),
+            path: PathBuf::from(path),
+            line,
+            outcome,
+            duration: Duration::from_millis(250),
+        }
+    }
+
+    fn cases() -> Vec<TestCase> {
+        vec![
+            case("B", "B.roc", 3, ExpectOutcome::Passed),
+            case("A<&>", "dir/\"quoted\" 'A'.roc", 1, ExpectOutcome::Failed),
+            case("B", "B.roc", 7, ExpectOutcome::Panicked),
+            case("B", "B.roc", 9, ExpectOutcome::Passed),
+        ]
+    }
+
+    #[test]
+    fn junit_report() {
+        assert_eq!(
+            junit(&cases(), Duration::from_millis(1500)),
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                r#"<testsuites name="roc test" tests="4" failures="1" errors="1" time="1.500">"#,
+                "\n",
+                r#"  <testsuite name="A&lt;&amp;&gt;" tests="1" failures="1" errors="0" time="0.250">"#,
+                "\n",
+                r#"    <testcase name="dir/&quot;quoted&quot; &apos;A&apos;.roc:1" classname="A&lt;&amp;&gt;" file="dir/&quot;quoted&quot; &apos;A&apos;.roc" line="1" time="0.250">"#,
+                "\n",
+                r#"      <failure message="This expectation failed."/>"#,
+                "\n",
+                "    </testcase>\n",
+                "  </testsuite>\n",
+                r#"  <testsuite name="B" tests="3" failures="0" errors="1" time="0.750">"#,
+                "\n",
+                r#"    <testcase name="B.roc:3" classname="B" file="B.roc" line="3" time="0.250"/>"#,
+                "\n",
+                r#"    <testcase name="B.roc:7" classname="B" file="B.roc" line="7" time="0.250">"#,
+                "\n",
+                r#"      <error message="This expectation crashed."/>"#,
+                "\n",
+                "    </testcase>\n",
+                r#"    <testcase name="B.roc:9" classname="B" file="B.roc" line="9" time="0.250"/>"#,
+                "\n",
+                "  </testsuite>\n",
+                "</testsuites>\n",
+            )
+        );
+    }
+
+    #[test]
+    fn junit_report_without_cases() {
+        assert_eq!(
+            junit(&[], Duration::ZERO),
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                r#"<testsuites name="roc test" tests="0" failures="0" errors="0" time="0.000">"#,
+                "\n",
+                "</testsuites>\n",
+            )
+        );
+    }
+
+    #[test]
+    fn json_report() {
+        let report: serde_json::Value =
+            serde_json::from_str(&json(&cases(), Duration::from_millis(1500))).unwrap();
+
+        assert_eq!(
+            report,
+            serde_json::json!({
+                "passed": 2,
+                "failed": 1,
+                "panicked": 1,
+                "duration_ms": 1500.0,
+                "tests": [
+                    { "module": "B", "file": "B.roc", "line": 3, "outcome": "passed", "duration_ms": 250.0 },
+                    { "module": "A<&>", "file": "dir/\"quoted\" 'A'.roc", "line": 1, "outcome": "failed", "duration_ms": 250.0 },
+                    { "module": "B", "file": "B.roc", "line": 7, "outcome": "panicked", "duration_ms": 250.0 },
+                    { "module": "B", "file": "B.roc", "line": 9, "outcome": "passed", "duration_ms": 250.0 },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn json_report_escapes() {
+        let cases = [case(
+            "Quote\"Backslash\\Newline\n",
+            "C:\\roc\\Tab\t.roc",
+            1,
+            ExpectOutcome::Passed,
+        )];
+        let report = json(&cases, Duration::ZERO);
+
+        assert!(
+            report.contains(r#""module": "Quote\"Backslash\\Newline\n""#),
+            "{}",
+            report
+        );
+        assert!(
+            report.contains(r#""file": "C:\\roc\\Tab\t.roc""#),
+            "{}",
+            report
+        );
+    }
+}
@@ -0,0 +1,484 @@
+//! `roc dev --watch` and `roc test --watch`: rather than exiting when done, wait for one of the
+//! modules to change, and then start over.
+use crate::{ARGS_FOR_APP, ROC_FILE};
+use clap::ArgMatches;
+use roc_collections::{MutMap, MutSet};
+use roc_module::symbol::ModuleId;
+use roc_packaging::cache::RocCacheDir;
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use target_lexicon::Triple;
+
+/// How often to check whether any watched file changed. Polling works the same everywhere, and
+/// this is plenty fast for files that people edit by hand.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The import graph of a load, in terms of files rather than [ModuleId]s, because module IDs can
+/// differ from one load to the next.
+#[derive(Debug, Default)]
+pub(crate) struct ImportGraph {
+    /// For the file of every (non-builtin) module, the files of the modules that import it.
+    importers: MutMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl ImportGraph {
+    pub fn new(
+        sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+        imports: &MutMap<ModuleId, MutSet<ModuleId>>,
+    ) -> Self {
+        let mut importers: MutMap<PathBuf, Vec<PathBuf>> = sources
+            .iter()
+            .filter(|(module_id, _)| !module_id.is_builtin())
+            .map(|(_, (path, _))| (path.clone(), Vec::new()))
+            .collect();
+
+        for (module_id, imported_ids) in imports {
+            let importer = match sources.get(module_id) {
+                Some((path, _)) if !module_id.is_builtin() => path,
+                _ => continue,
+            };
+
+            for imported_id in imported_ids {
+                if let Some((imported, _)) = sources.get(imported_id) {
+                    if let Some(importers_of_imported) = importers.get_mut(imported) {
+                        importers_of_imported.push(importer.clone());
+                    }
+                }
+            }
+        }
+
+        Self { importers }
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.importers.keys()
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.importers.contains_key(path)
+    }
+
+    /// The given files, plus the files of all the modules that import them, directly or not.
+    pub fn affected_by<'a>(
+        &self,
+        changed: impl IntoIterator<Item = &'a PathBuf>,
+    ) -> MutSet<PathBuf> {
+        let mut affected = MutSet::default();
+        let mut queue: VecDeque<&PathBuf> = changed.into_iter().collect();
+
+        while let Some(path) = queue.pop_front() {
+            if affected.insert(path.clone()) {
+                if let Some(importers) = self.importers.get(path) {
+                    queue.extend(importers);
+                }
+            }
+        }
+
+        affected
+    }
+}
+
+#[derive(Debug, Default)]
+struct Watcher {
+    /// When each watched file was last modified, or `None` if it doesn't exist.
+    modified: MutMap<PathBuf, Option<SystemTime>>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+impl Watcher {
+    /// Watch exactly these files from now on. Files that were already being watched keep their
+    /// last known modification time, so that we don't miss changes made during a build.
+    fn watch_only(&mut self, paths: impl IntoIterator<Item = PathBuf>) {
+        let mut previous = std::mem::take(&mut self.modified);
+
+        self.modified = paths
+            .into_iter()
+            .map(|path| {
+                let last_modified = previous.remove(&path).unwrap_or_else(|| modified(&path));
+
+                (path, last_modified)
+            })
+            .collect();
+    }
+
+    /// The files that were modified, created, or deleted since the last time we checked.
+    fn changed(&mut self) -> MutSet<PathBuf> {
+        let mut changed = MutSet::default();
+
+        for (path, last_modified) in self.modified.iter_mut() {
+            let now = modified(path);
+
+            if now != *last_modified {
+                *last_modified = now;
+                changed.insert(path.clone());
+            }
+        }
+
+        changed
+    }
+
+    /// Blocks until some files change.
+    fn wait_for_changes(&mut self) -> MutSet<PathBuf> {
+        debounce(|| {
+            thread::sleep(POLL_INTERVAL);
+
+            self.changed()
+        })
+    }
+}
+
+/// Polls until something changes. Editors sometimes save a file in several steps, so this keeps
+/// polling until a whole poll goes by without any more changes, and returns all of them.
+fn debounce(mut poll: impl FnMut() -> MutSet<PathBuf>) -> MutSet<PathBuf> {
+    let mut changed = MutSet::default();
+
+    loop {
+        let newly_changed = poll();
+
+        if newly_changed.is_empty() && !changed.is_empty() {
+            return changed;
+        }
+
+        changed.extend(newly_changed);
+    }
+}
+
+fn print_waiting() {
+    println!("\nWaiting for changes… (Press Ctrl+C to stop.)\n");
+}
+
+fn print_restarting(changed: &MutSet<PathBuf>) {
+    let mut changed: Vec<_> = changed.iter().map(|path| path.to_string_lossy()).collect();
+    changed.sort();
+
+    println!(
+        "\x1B[36m{}\x1B[39m\n\nChanged: \x1B[33m{}\x1B[39m\n",
+        "─".repeat(80),
+        changed.join(", ")
+    );
+}
+
+/// Runs all the `expect`s, and then, whenever files change, only the `expect`s in the modules that
+/// changed or that import a module that changed.
+pub(crate) fn watch_test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
+    let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+
+    let mut watcher = Watcher::default();
+    watcher.watch_only([path.clone()]);
+
+    let mut run = crate::test_modules(matches, &triple, &|_| true)?;
+
+    loop {
+        // If loading failed early on, keep watching whatever we were watching before.
+        if let Some(import_graph) = &run.import_graph {
+            watcher.watch_only(import_graph.paths().cloned());
+        }
+
+        print_waiting();
+        let changed = watcher.wait_for_changes();
+        print_restarting(&changed);
+
+        run = match (&run.import_graph, run.ran_expects) {
+            (Some(import_graph), true) => {
+                let affected = import_graph.affected_by(&changed);
+
+                // Modules we didn't know about before (because they were only just imported, say)
+                // haven't been tested yet either.
+                crate::test_modules(matches, &triple, &|path| {
+                    affected.contains(path) || !import_graph.contains(path)
+                })?
+            }
+            // The `expect`s didn't get to run last time, so run all of them.
+            _ => crate::test_modules(matches, &triple, &|_| true)?,
+        };
+    }
+}
+
+/// Builds and runs the app, and then does it again whenever any of its modules (including the
+/// platform's) change. If the app is still running at that point, it gets killed first.
+pub(crate) fn watch_dev(
+    matches: &ArgMatches,
+    triple: Triple,
+    roc_cache_dir: RocCacheDir<'_>,
+) -> io::Result<i32> {
+    let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+
+    let mut watcher = Watcher::default();
+    watcher.watch_only([path.clone()]);
+
+    loop {
+        let mut changed = dev_once(matches, &triple, roc_cache_dir, path, &mut watcher)?;
+
+        if changed.is_empty() {
+            print_waiting();
+            changed = watcher.wait_for_changes();
+        }
+
+        print_restarting(&changed);
+    }
+}
+
+/// Returns the files that changed while the app was running, if any.
+fn dev_once(
+    matches: &ArgMatches,
+    triple: &Triple,
+    roc_cache_dir: RocCacheDir<'_>,
+    path: &Path,
+    watcher: &mut Watcher,
+) -> io::Result<MutSet<PathBuf>> {
+    use crate::{build_settings, BuildConfig, BuildSettings};
+    use bumpalo::Bump;
+    use roc_build::link::LinkType;
+    use roc_build::program::{
+        build_file, handle_error_module, handle_loading_problem, BuildFileError, BuiltFile,
+    };
+    use std::ffi::OsString;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    let arena = Bump::new();
+
+    let BuildSettings {
+        opt_level: _,
+        code_gen_options,
+        emit_timings,
+        linking_strategy,
+        prebuilt,
+        wasm_dev_stack_bytes,
+        render,
+        load_config,
+    } = build_settings(
+        matches,
+        &BuildConfig::BuildAndRunIfNoErrors,
+        triple,
+        LinkType::Executable,
+    );
+
+    let res_binary_path = build_file(
+        &arena,
+        triple,
+        path.to_owned(),
+        code_gen_options,
+        emit_timings,
+        LinkType::Executable,
+        linking_strategy,
+        prebuilt,
+        wasm_dev_stack_bytes,
+        roc_cache_dir,
+        load_config,
+    );
+
+    let (binary_path, expect_metadata) = match res_binary_path {
+        Ok(BuiltFile {
+            binary_path,
+            problems,
+            total_time,
+            expect_metadata,
+            module_paths,
+        }) => {
+            watcher.watch_only(module_paths);
+
+            if problems.fatally_errored {
+                problems.print_to_stdout(total_time);
+                println!(".\n\nCannot run program due to fatal error…");
+
+                return Ok(MutSet::default());
+            }
+
+            if problems.warnings > 0 {
+                problems.print_to_stdout(total_time);
+                println!(
+                    ".\n\nRunning program…\n\n\x1B[36m{}\x1B[39m",
+                    "─".repeat(80)
+                );
+            }
+
+            (binary_path, expect_metadata)
+        }
+        Err(BuildFileError::ErrorModule { module, total_time }) => {
+            let import_graph = ImportGraph::new(&module.sources, &module.imports);
+            watcher.watch_only(import_graph.paths().cloned());
+
+            handle_error_module(module, total_time, path.as_os_str(), false, render)?;
+
+            return Ok(MutSet::default());
+        }
+        Err(BuildFileError::LoadingProblem(problem)) => {
+            // We don't know the import graph, so keep watching whatever we were watching before.
+            handle_loading_problem(problem)?;
+
+            return Ok(MutSet::default());
+        }
+    };
+
+    let args = matches
+        .get_many::<OsString>(ARGS_FOR_APP)
+        .unwrap_or_default()
+        .map(|s| s.as_os_str());
+    let bytes = fs::read(binary_path)?;
+
+    // Keep watching while the app runs, so that we can restart it right away.
+    let stop = Arc::new(AtomicBool::new(false));
+
+    thread::scope(|scope| {
+        let poller = scope.spawn(|| {
+            while !stop.load(Ordering::Relaxed) {
+                let changed = watcher.changed();
+
+                if !changed.is_empty() {
+                    stop.store(true, Ordering::Relaxed);
+
+                    return changed;
+                }
+
+                thread::sleep(POLL_INTERVAL);
+            }
+
+            MutSet::default()
+        });
+
+        let result = crate::roc_dev_until(&arena, args, &bytes, expect_metadata, Arc::clone(&stop));
+
+        // Make sure the poller stops, even if we never got the app running.
+        stop.store(true, Ordering::Relaxed);
+        let changed = poller.join().unwrap();
+
+        result.map(|()| changed)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{debounce, ImportGraph, Watcher};
+    use roc_collections::{MutMap, MutSet};
+    use roc_module::symbol::{ModuleId, ModuleIds};
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn paths(names: &[&str]) -> MutSet<PathBuf> {
+        names.iter().map(PathBuf::from).collect()
+    }
+
+    /// An import graph of the given modules, where each one imports the ones listed after it.
+    fn import_graph(modules: &[(&str, &[&str])]) -> ImportGraph {
+        let mut module_ids = ModuleIds::default();
+        let mut id = |name: &str| -> ModuleId { module_ids.get_or_insert(&name.into()) };
+
+        let mut sources = MutMap::default();
+        let mut imports = MutMap::default();
+
+        for (name, imported) in modules {
+            let module_id = id(name);
+            let imported_ids: MutSet<ModuleId> = imported.iter().map(|name| id(name)).collect();
+
+            sources.insert(module_id, (PathBuf::from(name), "".into()));
+            imports.insert(module_id, imported_ids);
+        }
+
+        ImportGraph::new(&sources, &imports)
+    }
+
+    #[test]
+    fn affected_by_transitive_importers() {
+        // main imports A, which imports B; C imports nothing and is imported by nothing.
+        let graph = import_graph(&[
+            ("main.roc", &["A.roc"]),
+            ("A.roc", &["B.roc"]),
+            ("B.roc", &[]),
+            ("C.roc", &[]),
+        ]);
+
+        assert_eq!(
+            graph.affected_by(&paths(&["B.roc"])),
+            paths(&["B.roc", "A.roc", "main.roc"])
+        );
+        assert_eq!(
+            graph.affected_by(&paths(&["A.roc"])),
+            paths(&["A.roc", "main.roc"])
+        );
+        assert_eq!(graph.affected_by(&paths(&["C.roc"])), paths(&["C.roc"]));
+        assert_eq!(
+            graph.affected_by(&paths(&["main.roc"])),
+            paths(&["main.roc"])
+        );
+    }
+
+    #[test]
+    fn affected_by_cycle() {
+        // A and B import each other, and main imports A.
+        let graph = import_graph(&[
+            ("main.roc", &["A.roc"]),
+            ("A.roc", &["B.roc"]),
+            ("B.roc", &["A.roc"]),
+        ]);
+
+        assert_eq!(
+            graph.affected_by(&paths(&["B.roc"])),
+            paths(&["A.roc", "B.roc", "main.roc"])
+        );
+    }
+
+    #[test]
+    fn affected_by_unknown_file() {
+        let graph = import_graph(&[("main.roc", &[])]);
+
+        assert_eq!(
+            graph.affected_by(&paths(&["Other.roc"])),
+            paths(&["Other.roc"])
+        );
+    }
+
+    #[test]
+    fn debounce_waits_for_a_quiet_poll() {
+        let mut polls = vec![
+            paths(&[]),
+            paths(&["A.roc"]),
+            paths(&["B.roc"]),
+            paths(&["A.roc"]),
+            paths(&[]),
+            paths(&["C.roc"]),
+        ]
+        .into_iter();
+
+        assert_eq!(
+            debounce(|| polls.next().unwrap()),
+            paths(&["A.roc", "B.roc"])
+        );
+
+        // The change that came after the quiet poll is left for next time.
+        assert_eq!(polls.next(), Some(paths(&["C.roc"])));
+    }
+
+    #[test]
+    fn changed_reports_each_change_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let created = dir.path().join("Created.roc");
+        let deleted = dir.path().join("Deleted.roc");
+        let untouched = dir.path().join("Untouched.roc");
+
+        fs::write(&deleted, "").unwrap();
+        fs::write(&untouched, "").unwrap();
+
+        let mut watcher = Watcher::default();
+        watcher.watch_only([created.clone(), deleted.clone(), untouched]);
+
+        assert_eq!(watcher.changed(), MutSet::default());
+
+        fs::write(&created, "").unwrap();
+        fs::remove_file(&deleted).unwrap();
+
+        let expected: MutSet<PathBuf> = [created, deleted].into_iter().collect();
+
+        assert_eq!(watcher.changed(), expected);
+        assert_eq!(watcher.changed(), MutSet::default());
+    }
+}
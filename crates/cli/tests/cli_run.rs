@@ -16,7 +16,7 @@ mod cli_run {
     };
     use const_format::concatcp;
     use indoc::indoc;
-    use roc_cli::{CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_FORMAT, CMD_RUN, CMD_TEST};
+    use roc_cli::{CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_FIX, CMD_FORMAT, CMD_RUN, CMD_TEST};
     use roc_test_utils::assert_multiline_str_eq;
     use serial_test::serial;
     use std::iter;
@@ -52,9 +52,24 @@ mod cli_run {
     const OPTIMIZE_FLAG: &str = concatcp!("--", roc_cli::FLAG_OPTIMIZE);
     const LINKER_FLAG: &str = concatcp!("--", roc_cli::FLAG_LINKER);
     const CHECK_FLAG: &str = concatcp!("--", roc_cli::FLAG_CHECK);
+    const STDIN_FLAG: &str = concatcp!("--", roc_cli::FLAG_STDIN);
+    const LINES_FLAG: &str = concatcp!("--", roc_cli::FLAG_LINES);
+    const STDOUT_FLAG: &str = concatcp!("--", roc_cli::FLAG_STDOUT);
     const PREBUILT_PLATFORM: &str = concatcp!("--", roc_cli::FLAG_PREBUILT);
+    const FORMAT_JSON_FLAG: &str = concatcp!("--", roc_cli::FLAG_FORMAT, "=json");
+    const DRY_RUN_FLAG: &str = concatcp!("--", roc_cli::FLAG_DRY_RUN);
+    const ADD_MISSING_BRANCHES_FLAG: &str = concatcp!("--", roc_cli::FLAG_ADD_MISSING_BRANCHES);
+    const REMOVE_REDUNDANT_BRANCHES_FLAG: &str =
+        concatcp!("--", roc_cli::FLAG_REMOVE_REDUNDANT_BRANCHES);
     #[allow(dead_code)]
     const TARGET_FLAG: &str = concatcp!("--", roc_cli::FLAG_TARGET);
+    const BUNDLE_FLAG: &str = concatcp!("--", roc_cli::FLAG_BUNDLE);
+    const FILTER_FLAG: &str = concatcp!("--", roc_cli::FLAG_FILTER);
+    const REPORT_FLAG: &str = concatcp!("--", roc_cli::FLAG_REPORT);
+    const REPORT_FILE_FLAG: &str = concatcp!("--", roc_cli::FLAG_REPORT_FILE);
+    const COVERAGE_FLAG: &str = concatcp!("--", roc_cli::FLAG_COVERAGE);
+    const DEV_FLAG: &str = concatcp!("--", roc_cli::FLAG_DEV);
+    const DEBUG_FLAG: &str = concatcp!("--", roc_cli::FLAG_DEBUG);
 
     #[derive(Debug)]
     enum CliMode {
@@ -626,6 +641,8 @@ mod cli_run {
 
 
 
+                expects.roc: 1 failed and 0 passed
+
                 1 failed and 0 passed in <ignored for test> ms."#
             ),
             UseValgrind::Yes,
@@ -633,6 +650,291 @@ mod cli_run {
         );
     }
 
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn doc_tests() {
+        let path = file_path_from_root("crates/cli_testing_examples/expects", "DocTests.roc");
+        let out = run_roc([CMD_TEST, path.to_str().unwrap()], &[], &[]);
+
+        // The failing example is reported in the doc comment it's written in
+        assert!(
+            out.stdout.contains("## expect double four == 9"),
+            "{}",
+            out.stdout
+        );
+        assert!(
+            out.stdout.contains("1 failed and 1 passed"),
+            "{}",
+            out.stdout
+        );
+        assert!(!out.status.success());
+    }
+
+    /// Runs `roc test` on the test-report fixture, which has three `expect`s: the ones on lines 8
+    /// and 12 pass, and the one on line 10 fails. Returns the output and the report, if any.
+    fn test_report_fixture(flags: &[&str]) -> (Out, Option<String>) {
+        let dir = tempfile::tempdir().unwrap();
+        let report_path = dir.path().join("report");
+        let roc_file = fixture_file("test-report", "Report.roc");
+        let report_file_flag = format!("{}={}", REPORT_FILE_FLAG, report_path.display());
+        let out = run_roc(
+            [CMD_TEST]
+                .iter()
+                .chain(flags)
+                .copied()
+                .chain([report_file_flag.as_str(), roc_file.to_str().unwrap()]),
+            &[],
+            &[],
+        );
+
+        (out, std::fs::read_to_string(report_path).ok())
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn test_report_junit() {
+        let (out, report) = test_report_fixture(&[REPORT_FLAG, "junit"]);
+        let report = report.expect("no report was written");
+
+        assert_eq!(out.status.code(), Some(1), "{}", out.stdout);
+        assert!(
+            report.contains(r#"<testsuites name="roc test" tests="3" failures="1" errors="0""#),
+            "{}",
+            report
+        );
+        assert!(
+            report.contains(r#"<testsuite name="Report" tests="3" failures="1" errors="0""#),
+            "{}",
+            report
+        );
+
+        for line in [8, 10, 12] {
+            assert!(report.contains(&format!(r#"line="{line}""#)), "{}", report);
+        }
+
+        assert_eq!(report.matches("<failure ").count(), 1, "{}", report);
+        assert_eq!(report.matches("<error ").count(), 0, "{}", report);
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn test_report_json() {
+        let (out, report) = test_report_fixture(&[REPORT_FLAG, "json"]);
+        let report: serde_json::Value =
+            serde_json::from_str(&report.expect("no report was written")).unwrap();
+
+        assert_eq!(out.status.code(), Some(1), "{}", out.stdout);
+        assert_eq!(report["passed"], 2);
+        assert_eq!(report["failed"], 1);
+        assert_eq!(report["panicked"], 0);
+
+        let mut outcomes: Vec<_> = report["tests"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|test| {
+                assert_eq!(test["module"], "Report");
+
+                (
+                    test["line"].as_u64().unwrap(),
+                    test["outcome"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        outcomes.sort();
+
+        assert_eq!(
+            outcomes,
+            [
+                (8, "passed".to_string()),
+                (10, "failed".to_string()),
+                (12, "passed".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn test_report_filter() {
+        // Only the failing `expect` is on line 10.
+        let (out, report) =
+            test_report_fixture(&[REPORT_FLAG, "json", FILTER_FLAG, "Report.roc:10"]);
+        let report: serde_json::Value =
+            serde_json::from_str(&report.expect("no report was written")).unwrap();
+
+        assert_eq!(out.status.code(), Some(1), "{}", out.stdout);
+        assert!(
+            strip_colors(&out.stdout).contains("1 failed and 0 passed"),
+            "{}",
+            out.stdout
+        );
+        assert_eq!(report["tests"].as_array().unwrap().len(), 1);
+        assert_eq!(report["tests"][0]["line"], 10);
+
+        // Matching the module name runs all of its `expect`s.
+        let (out, report) = test_report_fixture(&[REPORT_FLAG, "json", FILTER_FLAG, "Report"]);
+        let report: serde_json::Value =
+            serde_json::from_str(&report.expect("no report was written")).unwrap();
+
+        assert_eq!(out.status.code(), Some(1), "{}", out.stdout);
+        assert_eq!(report["tests"].as_array().unwrap().len(), 3);
+
+        // Running nothing is an error, so that CI notices.
+        let (out, _) = test_report_fixture(&[REPORT_FLAG, "json", FILTER_FLAG, "NoSuchModule"]);
+
+        assert_eq!(out.status.code(), Some(2), "{}", out.stdout);
+        assert!(
+            out.stdout
+                .contains("None of the expectations match the filter."),
+            "{}",
+            out.stdout
+        );
+    }
+
+    /// Runs `roc test --coverage` on the coverage fixture and returns the lcov file it wrote.
+    fn coverage_fixture(flags: &[&str]) -> String {
+        let dir = tempfile::tempdir().unwrap();
+        let lcov_path = dir.path().join("lcov.info");
+        let roc_file = fixture_file("coverage", "Coverage.roc");
+        let coverage_flag = format!("{}={}", COVERAGE_FLAG, lcov_path.display());
+        let out = run_roc(
+            [CMD_TEST]
+                .iter()
+                .chain(flags)
+                .copied()
+                .chain([coverage_flag.as_str(), roc_file.to_str().unwrap()]),
+            &[],
+            &[],
+        );
+
+        assert!(out.status.success(), "{}{}", out.stdout, out.stderr);
+
+        std::fs::read_to_string(lcov_path).expect("no lcov file was written")
+    }
+
+    fn assert_coverage(lcov: &str) {
+        assert!(
+            lcov.lines()
+                .any(|l| l.starts_with("SF:") && l.ends_with("Coverage.roc")),
+            "{}",
+            lcov
+        );
+        assert!(lcov.ends_with("end_of_record\n"), "{}", lcov);
+
+        // `classify` on line 5 runs once per `expect`, and only ever takes its `else` arm on
+        // line 9. `unused` on line 11 is never called, but still shows up.
+        for line in ["DA:5,2", "DA:7,0", "DA:9,2", "DA:11,0"] {
+            assert!(lcov.lines().any(|l| l == line), "{} in\n{}", line, lcov);
+        }
+
+        for line in ["FNDA:2,classify", "FNDA:0,unused", "FNF:2", "FNH:1"] {
+            assert!(lcov.lines().any(|l| l == line), "{} in\n{}", line, lcov);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn test_coverage() {
+        assert_coverage(&coverage_fixture(&[]));
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn test_coverage_dev() {
+        // `roc test` always compiles its `expect`s with LLVM; `--dev` only turns optimizations
+        // off, which must not drop any of the counters.
+        assert_coverage(&coverage_fixture(&[DEV_FLAG]));
+    }
+
+    /// Builds the debug-info fixture with `--debug`, and returns the `(address, line)` rows that
+    /// the executable's `.debug_line` has for `Main.roc`.
+    fn debug_info_fixture(flags: &[&str]) -> Vec<(u64, u64)> {
+        use gimli::{EndianSlice, LittleEndian};
+        use object::{Object, ObjectSection};
+
+        let roc_file = fixture_file("debug-info", "Main.roc");
+        run_roc_on_failure_is_panic(
+            &roc_file,
+            [CMD_BUILD, DEBUG_FLAG].iter().chain(flags).copied(),
+            &[],
+            &[],
+            &[],
+        );
+
+        let executable = std::fs::read(roc_file.with_file_name("debug-info")).unwrap();
+        let executable = object::File::parse(executable.as_slice()).unwrap();
+        let dwarf = gimli::Dwarf::load(|id| -> Result<_, gimli::Error> {
+            let data = executable
+                .section_by_name(id.name())
+                .and_then(|section| section.data().ok())
+                .unwrap_or(&[]);
+
+            Ok(EndianSlice::new(data, LittleEndian))
+        })
+        .unwrap();
+
+        let mut rows = Vec::new();
+        let mut units = dwarf.units();
+        while let Some(header) = units.next().unwrap() {
+            let unit = dwarf.unit(header).unwrap();
+            let program = match unit.line_program.clone() {
+                Some(program) => program,
+                None => continue,
+            };
+
+            let mut program_rows = program.rows();
+            while let Some((header, row)) = program_rows.next_row().unwrap() {
+                let file = match row.file(header) {
+                    Some(file) => dwarf.attr_string(&unit, file.path_name()).unwrap(),
+                    None => continue,
+                };
+
+                if let (false, Some(line)) = (row.end_sequence(), row.line()) {
+                    if file.to_string_lossy().ends_with("Main.roc") {
+                        rows.push((row.address(), line.get()));
+                    }
+                }
+            }
+        }
+
+        rows
+    }
+
+    fn assert_debug_info(rows: &[(u64, u64)]) {
+        // The `label` def in `describe` on line 11, then its `if` arms on lines 13 and 15.
+        for line in [11, 13, 15] {
+            assert!(
+                rows.iter().any(|(_, row_line)| *row_line == line),
+                "no row for line {} in {:?}",
+                line,
+                rows
+            );
+        }
+
+        // The app's addresses were relocated to where its code ended up in the executable.
+        assert!(rows.iter().all(|(address, _)| *address != 0), "{:?}", rows);
+    }
+
+    #[test]
+    #[serial(debug_info)]
+    #[cfg_attr(
+        not(target_os = "linux"),
+        ignore = "only ELF executables keep their DWARF"
+    )]
+    fn debug_info_llvm() {
+        assert_debug_info(&debug_info_fixture(&[]));
+    }
+
+    #[test]
+    #[serial(debug_info)]
+    #[cfg_attr(
+        not(target_os = "linux"),
+        ignore = "only ELF executables keep their DWARF"
+    )]
+    fn debug_info_dev() {
+        assert_debug_info(&debug_info_fixture(&[DEV_FLAG]));
+    }
+
     #[test]
     #[cfg_attr(
         windows,
@@ -1461,6 +1763,129 @@ mod cli_run {
         );
     }
 
+    #[test]
+    fn unused_import_json() {
+        let file = known_bad_file("UnusedImport.roc");
+        let out = run_roc(
+            [CMD_CHECK, file.to_str().unwrap(), FORMAT_JSON_FLAG],
+            &[],
+            &[],
+        );
+
+        let diagnostics: Vec<serde_json::Value> = out
+            .stdout
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(diagnostics.len(), 1);
+
+        let diagnostic = &diagnostics[0];
+
+        assert_eq!(diagnostic["severity"], "warning");
+        assert_eq!(diagnostic["title"], "UNUSED IMPORT");
+        assert_eq!(
+            diagnostic["region"],
+            serde_json::json!({
+                "start": { "line": 3, "column": 14 },
+                "end": { "line": 3, "column": 30 },
+            })
+        );
+        assert_eq!(diagnostic["related"], serde_json::json!([]));
+        assert!(diagnostic["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Nothing from Symbol is used in this module."));
+    }
+
+    #[test]
+    fn fix_unused_import_dry_run() {
+        let file = known_bad_file("UnusedImport.roc");
+        let before = std::fs::read_to_string(&file).unwrap();
+        let out = run_roc([CMD_FIX, file.to_str().unwrap(), DRY_RUN_FLAG], &[], &[]);
+
+        assert!(out.status.success(), "{}", out.stderr);
+        assert!(out.stdout.contains("-    imports [Symbol.{ Ident }]\n"));
+        assert!(out.stdout.contains("+    imports []\n"));
+
+        // --dry-run must leave the file alone
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), before);
+    }
+
+    #[test]
+    fn fix_unused_defs_and_arguments_dry_run() {
+        let file = known_bad_file("UnusedDefs.roc");
+        let out = run_roc([CMD_FIX, file.to_str().unwrap(), DRY_RUN_FLAG], &[], &[]);
+
+        assert!(out.status.success(), "{}", out.stderr);
+        for line in [
+            "-greet = \\name, greeting ->",
+            "+greet = \\name, _greeting ->",
+            "-    unused = \"!\"",
+            "-helper : Str -> Str",
+            "-helper = \\str -> str",
+        ] {
+            assert!(
+                out.stdout.lines().any(|diff_line| diff_line == line),
+                "expected {:?} in:\n{}",
+                line,
+                out.stdout
+            );
+        }
+    }
+
+    #[test]
+    fn fix_add_missing_branches_dry_run() {
+        let file = known_bad_file("IncompleteWhen.roc");
+        let out = run_roc(
+            [
+                CMD_FIX,
+                file.to_str().unwrap(),
+                ADD_MISSING_BRANCHES_FLAG,
+                DRY_RUN_FLAG,
+            ],
+            &[],
+            &[],
+        );
+
+        assert!(out.status.success(), "{}", out.stderr);
+        for line in [
+            "+        Green -> crash \"todo\"",
+            "+        Blue -> crash \"todo\"",
+        ] {
+            assert!(
+                out.stdout.lines().any(|diff_line| diff_line == line),
+                "expected {:?} in:\n{}",
+                line,
+                out.stdout
+            );
+        }
+        // Only the requested fix is applied
+        assert!(!out.stdout.contains("-        Red -> \"still red\""));
+    }
+
+    #[test]
+    fn fix_remove_redundant_branches_dry_run() {
+        let file = known_bad_file("IncompleteWhen.roc");
+        let out = run_roc(
+            [
+                CMD_FIX,
+                file.to_str().unwrap(),
+                REMOVE_REDUNDANT_BRANCHES_FLAG,
+                DRY_RUN_FLAG,
+            ],
+            &[],
+            &[],
+        );
+
+        assert!(out.status.success(), "{}", out.stderr);
+        assert!(out
+            .stdout
+            .lines()
+            .any(|line| line == "-        Red -> \"still red\""));
+        assert!(!out.stdout.contains("crash"));
+    }
+
     #[test]
     fn unknown_generates_with() {
         check_compile_error(
@@ -1485,6 +1910,103 @@ mod cli_run {
         );
     }
 
+    /// Copies a bundle fixture into a temp dir, since bundling writes the archive and its manifest
+    /// next to the root module, and runs `roc build --bundle .tar` on its main.roc.
+    fn bundle_fixture(dir_name: &str) -> (tempfile::TempDir, Out) {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        for entry in std::fs::read_dir(fixtures_dir(&format!("bundle/{dir_name}"))).unwrap() {
+            let path = entry.unwrap().path();
+
+            std::fs::copy(&path, temp_dir.path().join(path.file_name().unwrap())).unwrap();
+        }
+
+        let main_path = temp_dir.path().join("main.roc");
+        let out = run_roc(
+            [CMD_BUILD, BUNDLE_FLAG, ".tar", main_path.to_str().unwrap()],
+            &[],
+            &[],
+        );
+
+        (temp_dir, out)
+    }
+
+    /// The contents of the manifest that bundling wrote into the given dir, if any.
+    fn bundle_manifest(dir: &Path) -> Option<String> {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.extension().and_then(|ext| ext.to_str()) == Some("manifest"))
+            .map(|path| std::fs::read_to_string(path).unwrap())
+    }
+
+    #[test]
+    fn bundle_package_manifest() {
+        let (dir, out) = bundle_fixture("package");
+
+        // Unused.roc is left out, which is only a warning.
+        assert_eq!(out.status.code(), Some(2), "{}{}", out.stdout, out.stderr);
+        assert!(
+            out.stderr.contains("Unused.roc isn't reachable from"),
+            "{}",
+            out.stderr
+        );
+        assert_multiline_str_eq!(
+            bundle_manifest(dir.path()).unwrap().as_str(),
+            indoc!(
+                r#"
+                [modules]
+                Helper.roc
+                Used.roc
+                main.roc
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn bundle_platform_hosts() {
+        let (dir, out) = bundle_fixture("platform");
+
+        assert_eq!(out.status.code(), Some(2), "{}{}", out.stdout, out.stderr);
+
+        for warning in [
+            "linux-x86_64.rh is a surgical host, but metadata_linux-x86_64.rm is missing",
+            "There's no prebuilt host for linux-arm64, macos-x86_64, windows-x86_64, windows-x86_32, windows-arm64,",
+            "freebsd-x86_64.o looks like part of a prebuilt host, but its name doesn't match any target",
+        ] {
+            assert!(out.stderr.contains(warning), "{}", out.stderr);
+        }
+
+        assert_multiline_str_eq!(
+            bundle_manifest(dir.path()).unwrap().as_str(),
+            indoc!(
+                r#"
+                [modules]
+                main.roc
+
+                [linux-x86_64]
+                linux-x86_64.rh
+
+                [macos-arm64]
+                macos-arm64.o
+
+                [wasm32]
+                host.zig
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn bundle_missing_module() {
+        let (dir, out) = bundle_fixture("missing");
+
+        assert_eq!(out.status.code(), Some(1), "{}{}", out.stdout, out.stderr);
+        assert!(out.stdout.contains("FILE NOT FOUND"), "{}", out.stdout);
+        assert_eq!(bundle_manifest(dir.path()), None);
+    }
+
     #[test]
     fn format_check_good() {
         check_format_check_as_expected(&fixture_file("format", "Formatted.roc"), true);
@@ -1495,6 +2017,89 @@ mod cli_run {
         check_format_check_as_expected(&fixture_file("format", "NotFormatted.roc"), false);
     }
 
+    #[test]
+    fn format_stdin() {
+        let src = std::fs::read_to_string(fixture_file("format", "Formatted.roc")).unwrap();
+        let out = run_roc([CMD_FORMAT, STDIN_FLAG], &[&src], &[]);
+
+        assert!(out.status.success(), "{}", out.stderr);
+        assert_multiline_str_eq!(out.stdout.as_str(), src.as_str());
+    }
+
+    #[test]
+    fn format_stdin_check_reformatting_needed() {
+        let src = std::fs::read_to_string(fixture_file("format", "NotFormatted.roc")).unwrap();
+        let out = run_roc([CMD_FORMAT, STDIN_FLAG, CHECK_FLAG], &[&src], &[]);
+
+        assert!(!out.status.success());
+    }
+
+    #[test]
+    fn format_stdout() {
+        let file = fixture_file("format", "NotFormatted.roc");
+        let src = std::fs::read_to_string(&file).unwrap();
+        let out = run_roc([CMD_FORMAT, file.to_str().unwrap(), STDOUT_FLAG], &[], &[]);
+
+        assert!(out.status.success(), "{}", out.stderr);
+        assert_multiline_str_eq!(
+            out.stdout.as_str(),
+            indoc!(
+                r#"
+                app "formatted"
+                    packages { pf: "platform/main.roc" }
+                    provides [main] to pf
+
+                main : Str
+                main = Dep1.value1 {}
+                "#
+            )
+        );
+
+        // The file itself is left as it was
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), src);
+    }
+
+    #[test]
+    fn format_stdout_conflicts_with_check() {
+        let file = fixture_file("format", "NotFormatted.roc");
+        let out = run_roc(
+            [CMD_FORMAT, file.to_str().unwrap(), STDOUT_FLAG, CHECK_FLAG],
+            &[],
+            &[],
+        );
+
+        assert!(!out.status.success());
+        assert!(out.stderr.contains("cannot be used with"), "{}", out.stderr);
+    }
+
+    #[test]
+    fn format_stdin_lines() {
+        let src = indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            x   =   1
+
+            main =   x
+            "#
+        );
+        let out = run_roc([CMD_FORMAT, STDIN_FLAG, LINES_FLAG, "5:5"], &[src], &[]);
+
+        assert!(out.status.success(), "{}", out.stderr);
+        assert_multiline_str_eq!(
+            out.stdout.as_str(),
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                x   =   1
+
+                main = x
+                "#
+            )
+        );
+    }
+
     #[test]
     fn format_check_folders() {
         // This fails, because "NotFormatted.roc" is present in this folder
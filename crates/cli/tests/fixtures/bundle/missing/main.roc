@@ -0,0 +1,3 @@
+package "missing"
+    exposes [Missing]
+    packages {}
@@ -0,0 +1,6 @@
+interface Helper
+    exposes [name]
+    imports []
+
+name : Str
+name = "World"
@@ -0,0 +1,6 @@
+interface Unused
+    exposes [unused]
+    imports []
+
+unused : Str
+unused = "Nothing imports this module."
@@ -0,0 +1,6 @@
+interface Used
+    exposes [greeting]
+    imports [Helper]
+
+greeting : Str
+greeting = "Hello, \(Helper.name)!"
@@ -0,0 +1,3 @@
+package "bundled"
+    exposes [Used]
+    packages {}
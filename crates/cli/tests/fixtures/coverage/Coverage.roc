@@ -0,0 +1,15 @@
+interface Coverage
+    exposes [classify, unused]
+    imports []
+
+classify = \n ->
+    if n < 0 then
+        "negative"
+    else
+        "non-negative"
+
+unused = \n -> n + 1
+
+expect classify 1 == "non-negative"
+
+expect classify 2 == "non-negative"
@@ -0,0 +1,15 @@
+app "debug-info"
+    packages { pf: "platform/main.roc" }
+    imports []
+    provides [main] to pf
+
+main : Str
+main = describe (List.len [1, 2, 3])
+
+describe : U64 -> Str
+describe = \n ->
+    label = Num.toStr n
+    if n > 2 then
+        Str.concat "many: " label
+    else
+        Str.concat "few: " label
@@ -0,0 +1,14 @@
+interface Report
+    exposes [double]
+    imports []
+
+double : I64 -> I64
+double = \n -> n * 2
+
+expect double 2 == 4
+
+expect double 3 == 7
+
+expect
+    x = double 5
+    x == 10
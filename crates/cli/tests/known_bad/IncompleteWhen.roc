@@ -0,0 +1,9 @@
+interface IncompleteWhen
+    exposes [describe]
+    imports []
+
+describe : [Red, Green, Blue] -> Str
+describe = \color ->
+    when color is
+        Red -> "red"
+        Red -> "still red"
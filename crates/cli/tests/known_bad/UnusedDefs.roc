@@ -0,0 +1,12 @@
+interface UnusedDefs
+    exposes [greet]
+    imports []
+
+greet : Str, Str -> Str
+greet = \name, greeting ->
+    unused = "!"
+
+    name
+
+helper : Str -> Str
+helper = \str -> str
@@ -0,0 +1,19 @@
+interface DocTests
+    exposes [double]
+    imports []
+
+## Doubles a number.
+##
+## ```
+## expect double 2 == 4
+## ```
+##
+## The expect in this example fails, to check that `roc test` reports it here:
+##
+## ```roc
+## four = 4
+##
+## expect double four == 9
+## ```
+double : I64 -> I64
+double = \n -> n * 2
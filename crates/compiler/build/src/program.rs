@@ -11,11 +11,11 @@ use roc_load::{
     EntryPoint, ExecutionMode, ExpectMetadata, LoadConfig, LoadMonomorphizedError, LoadedModule,
     LoadingProblem, MonomorphizedModule, Threading,
 };
-use roc_mono::ir::{OptLevel, SingleEntryPoint};
+use roc_mono::ir::{DebugSources, OptLevel, SingleEntryPoint};
 use roc_packaging::cache::RocCacheDir;
 use roc_reporting::{
     cli::{report_problems, Problems},
-    report::{RenderTarget, DEFAULT_PALETTE},
+    report::{to_file_problem_report_string, RenderTarget, DEFAULT_PALETTE},
 };
 use roc_target::TargetInfo;
 use std::ffi::OsStr;
@@ -39,23 +39,28 @@ pub struct CodeGenTiming {
     pub total: Duration,
 }
 
-pub fn report_problems_monomorphized(loaded: &mut MonomorphizedModule) -> Problems {
+pub fn report_problems_monomorphized(
+    loaded: &mut MonomorphizedModule,
+    render: RenderTarget,
+) -> Problems {
     report_problems(
         loaded.total_problems(),
         &loaded.sources,
         &loaded.interns,
         &mut loaded.can_problems,
         &mut loaded.type_problems,
+        render,
     )
 }
 
-pub fn report_problems_typechecked(loaded: &mut LoadedModule) -> Problems {
+pub fn report_problems_typechecked(loaded: &mut LoadedModule, render: RenderTarget) -> Problems {
     report_problems(
         loaded.total_problems(),
         &loaded.sources,
         &loaded.interns,
         &mut loaded.can_problems,
         &mut loaded.type_problems,
+        render,
     )
 }
 
@@ -113,6 +118,7 @@ pub fn gen_from_mono_module<'a>(
             preprocessed_host_path,
             wasm_dev_stack_bytes,
             AssemblyBackendMode::Binary, // dummy value, unused in practice
+            debug,
         ),
         CodeGenBackend::Assembly(backend_mode) => gen_from_mono_module_dev(
             arena,
@@ -121,6 +127,7 @@ pub fn gen_from_mono_module<'a>(
             preprocessed_host_path,
             wasm_dev_stack_bytes,
             backend_mode,
+            debug,
         ),
         CodeGenBackend::Llvm(backend_mode) => {
             gen_from_mono_module_llvm(arena, loaded, path, target, opt, backend_mode, debug)
@@ -190,6 +197,7 @@ fn gen_from_mono_module_llvm<'a>(
 
     let builder = context.create_builder();
     let (dibuilder, compile_unit) = roc_gen_llvm::llvm::build::Env::new_debug_info(module);
+    let debug_sources = emit_debug_info.then(|| DebugSources::new(&loaded.sources));
     let (mpm, _fpm) = roc_gen_llvm::llvm::build::construct_optimization_passes(module, opt_level);
 
     // Compile and add all the Procs before adding main
@@ -210,6 +218,7 @@ fn gen_from_mono_module_llvm<'a>(
             .keys()
             .copied()
             .collect(),
+        debug_sources: debug_sources.as_ref(),
     };
 
     // does not add any externs for this mode (we have a host) but cleans up some functions around
@@ -246,8 +255,19 @@ fn gen_from_mono_module_llvm<'a>(
 
     env.dibuilder.finalize();
 
-    // we don't use the debug info, and it causes weird errors.
-    module.strip_debug_info();
+    if emit_debug_info {
+        // LLVM drops debug info from modules that don't say which version they use.
+        if module.get_flag("Debug Info Version").is_none() {
+            module.add_basic_value_flag(
+                "Debug Info Version",
+                inkwell::module::FlagBehavior::Warning,
+                context.i32_type().const_int(3, false),
+            );
+        }
+    } else {
+        // we don't use the debug info, and it causes weird errors.
+        module.strip_debug_info();
+    }
 
     // Uncomment this to see the module's optimized LLVM instruction output:
     // env.module.print_to_stderr();
@@ -360,65 +380,6 @@ fn gen_from_mono_module_llvm<'a>(
 
         assert!(bc_to_object.status.success(), "{:#?}", bc_to_object);
 
-        MemoryBuffer::create_from_file(&app_o_file).expect("memory buffer creation works")
-    } else if emit_debug_info {
-        module.strip_debug_info();
-
-        let mut app_ll_dbg_file = PathBuf::from(roc_file_path);
-        app_ll_dbg_file.set_extension("dbg.ll");
-
-        let mut app_o_file = PathBuf::from(roc_file_path);
-        app_o_file.set_extension("o");
-
-        use std::process::Command;
-
-        // write the ll code to a file, so we can modify it
-        module.print_to_file(&app_ll_file).unwrap();
-
-        // run the debugir https://github.com/vaivaswatha/debugir tool
-        match Command::new("debugir")
-            .args(["-instnamer", app_ll_file.to_str().unwrap()])
-            .output()
-        {
-            Ok(_) => {}
-            Err(error) => {
-                use std::io::ErrorKind;
-                match error.kind() {
-                    ErrorKind::NotFound => internal_error!(
-                        r"I could not find the `debugir` tool on the PATH, install it from https://github.com/vaivaswatha/debugir"
-                    ),
-                    _ => internal_error!("{:?}", error),
-                }
-            }
-        }
-
-        use target_lexicon::Architecture;
-        match target.architecture {
-            Architecture::X86_64
-            | Architecture::X86_32(_)
-            | Architecture::Aarch64(_)
-            | Architecture::Wasm32 => {
-                // write the .o file. Note that this builds the .o for the local machine,
-                // and ignores the `target_machine` entirely.
-                //
-                // different systems name this executable differently, so we shotgun for
-                // the most common ones and then give up.
-                let ll_to_object = Command::new("llc")
-                    .args([
-                        "-relocation-model=pic",
-                        "-filetype=obj",
-                        app_ll_dbg_file.to_str().unwrap(),
-                        "-o",
-                        app_o_file.to_str().unwrap(),
-                    ])
-                    .output()
-                    .unwrap();
-
-                assert!(ll_to_object.stderr.is_empty(), "{:#?}", ll_to_object);
-            }
-            _ => unreachable!(),
-        }
-
         MemoryBuffer::create_from_file(&app_o_file).expect("memory buffer creation works")
     } else {
         // Emit the .o file
@@ -471,6 +432,7 @@ fn gen_from_mono_module_dev<'a>(
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
     backend_mode: AssemblyBackendMode,
+    emit_debug_info: bool,
 ) -> GenFromMono<'a> {
     use target_lexicon::Architecture;
 
@@ -482,7 +444,7 @@ fn gen_from_mono_module_dev<'a>(
             wasm_dev_stack_bytes,
         ),
         Architecture::X86_64 | Architecture::Aarch64(_) => {
-            gen_from_mono_module_dev_assembly(arena, loaded, target, backend_mode)
+            gen_from_mono_module_dev_assembly(arena, loaded, target, backend_mode, emit_debug_info)
         }
         _ => todo!(),
     }
@@ -496,12 +458,13 @@ pub fn gen_from_mono_module_dev<'a>(
     _host_input_path: &Path,
     _wasm_dev_stack_bytes: Option<u32>,
     backend_mode: AssemblyBackendMode,
+    emit_debug_info: bool,
 ) -> GenFromMono<'a> {
     use target_lexicon::Architecture;
 
     match target.architecture {
         Architecture::X86_64 | Architecture::Aarch64(_) => {
-            gen_from_mono_module_dev_assembly(arena, loaded, target, backend_mode)
+            gen_from_mono_module_dev_assembly(arena, loaded, target, backend_mode, emit_debug_info)
         }
         _ => todo!(),
     }
@@ -586,6 +549,7 @@ fn gen_from_mono_module_dev_assembly<'a>(
     loaded: MonomorphizedModule<'a>,
     target: &target_lexicon::Triple,
     backend_mode: AssemblyBackendMode,
+    emit_debug_info: bool,
 ) -> GenFromMono<'a> {
     let all_code_gen_start = Instant::now();
 
@@ -597,6 +561,7 @@ fn gen_from_mono_module_dev_assembly<'a>(
         mut interns,
         exposed_to_host,
         mut layout_interner,
+        sources,
         ..
     } = loaded;
 
@@ -606,6 +571,7 @@ fn gen_from_mono_module_dev_assembly<'a>(
         exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
         lazy_literals,
         mode: backend_mode,
+        debug_sources: emit_debug_info.then(|| DebugSources::new(&sources)),
     };
 
     let module_object =
@@ -653,6 +619,8 @@ pub struct BuiltFile<'a> {
     pub problems: Problems,
     pub total_time: Duration,
     pub expect_metadata: ExpectMetadata<'a>,
+    /// The files of every non-builtin module that went into the binary, including the platform's.
+    pub module_paths: Vec<PathBuf>,
 }
 
 pub enum BuildOrdering {
@@ -691,10 +659,16 @@ pub fn handle_error_module(
     total_time: std::time::Duration,
     filename: &OsStr,
     print_run_anyway_hint: bool,
+    render: RenderTarget,
 ) -> std::io::Result<i32> {
     debug_assert!(module.total_problems() > 0);
 
-    let problems = report_problems_typechecked(&mut module);
+    let problems = report_problems_typechecked(&mut module, render);
+
+    if let RenderTarget::Json = render {
+        // Anything else we print would get in the way of parsing the diagnostics.
+        return Ok(problems.exit_code());
+    }
 
     problems.print_to_stdout(total_time);
 
@@ -719,6 +693,10 @@ pub fn handle_loading_problem(problem: LoadingProblem) -> std::io::Result<i32> {
             print!("{}", report);
             Ok(1)
         }
+        LoadingProblem::FileProblem { filename, error } => {
+            print!("{}", to_file_problem_report_string(&filename, error));
+            Ok(1)
+        }
         _ => {
             // TODO: tighten up the types here, we should always end up with a
             // formatted report from load.
@@ -764,6 +742,7 @@ pub fn build_file<'a>(
     load_config: LoadConfig,
 ) -> Result<BuiltFile<'a>, BuildFileError<'a>> {
     let compilation_start = Instant::now();
+    let render = load_config.render;
 
     // Step 1: compile the app and generate the .o file
     let loaded =
@@ -782,11 +761,13 @@ pub fn build_file<'a>(
         wasm_dev_stack_bytes,
         loaded,
         compilation_start,
+        render,
     )
 }
 
+/// Like [build_file], for an app that has already been loaded.
 #[allow(clippy::too_many_arguments)]
-fn build_loaded_file<'a>(
+pub fn build_loaded_file<'a>(
     arena: &'a Bump,
     target: &Triple,
     app_module_path: PathBuf,
@@ -798,6 +779,7 @@ fn build_loaded_file<'a>(
     wasm_dev_stack_bytes: Option<u32>,
     loaded: roc_load::MonomorphizedModule<'a>,
     compilation_start: Instant,
+    render: RenderTarget,
 ) -> Result<BuiltFile<'a>, BuildFileError<'a>> {
     let operating_system = roc_target::OperatingSystem::from(target.operating_system);
 
@@ -806,6 +788,26 @@ fn build_loaded_file<'a>(
         _ => unreachable!(),
     };
 
+    // For example, if we're loading the platform from a URL, it's automatically prebuilt
+    // even if the --prebuilt-platform CLI flag wasn't set.
+    let is_platform_prebuilt = prebuilt_requested || loaded.uses_prebuilt_platform;
+
+    // We can't build a shared library host ourselves, so a `--lib` build is only linked
+    // surgically if the platform has one, either already preprocessed or as a `dynhost.so`.
+    let lib_host_path = platform_main_roc.with_file_name("dynhost.so");
+    let has_lib_host = platform_main_roc
+        .with_file_name(roc_linker::preprocessed_lib_host_filename(target).unwrap())
+        .exists()
+        || (!is_platform_prebuilt && lib_host_path.exists());
+    let linking_strategy = if link_type == LinkType::Dylib
+        && linking_strategy == LinkingStrategy::Surgical
+        && !has_lib_host
+    {
+        LinkingStrategy::Legacy
+    } else {
+        linking_strategy
+    };
+
     // the preprocessed host is stored beside the platform's main.roc
     let preprocessed_host_path = if linking_strategy == LinkingStrategy::Legacy {
         if let roc_target::OperatingSystem::Wasi = operating_system {
@@ -815,23 +817,47 @@ fn build_loaded_file<'a>(
         } else {
             platform_main_roc.with_file_name(legacy_host_filename(target).unwrap())
         }
+    } else if link_type == LinkType::Dylib {
+        platform_main_roc
+            .with_file_name(roc_linker::preprocessed_lib_host_filename(target).unwrap())
     } else {
         platform_main_roc.with_file_name(roc_linker::preprocessed_host_filename(target).unwrap())
     };
 
-    // For example, if we're loading the platform from a URL, it's automatically prebuilt
-    // even if the --prebuilt-platform CLI flag wasn't set.
-    let is_platform_prebuilt = prebuilt_requested || loaded.uses_prebuilt_platform;
-
     let cwd = app_module_path.parent().unwrap();
     let mut output_exe_path = cwd.join(&*loaded.output_path);
 
-    if let Some(extension) = operating_system.executable_file_ext() {
+    if link_type == LinkType::Dylib && linking_strategy == LinkingStrategy::Surgical {
+        output_exe_path.set_extension("so");
+    } else if let Some(extension) = operating_system.executable_file_ext() {
         output_exe_path.set_extension(extension);
     }
 
     // We don't need to spawn a rebuild thread when using a prebuilt host.
-    let rebuild_thread = if matches!(link_type, LinkType::Dylib | LinkType::None) {
+    let rebuild_thread = if link_type == LinkType::None
+        || (link_type == LinkType::Dylib && linking_strategy == LinkingStrategy::Legacy)
+    {
+        None
+    } else if link_type == LinkType::Dylib {
+        if !is_platform_prebuilt && lib_host_path.exists() {
+            let dll_stub_symbols = roc_linker::ExposedSymbols::from_exposed_to_host(
+                &loaded.interns,
+                &loaded.exposed_to_host,
+            );
+
+            roc_linker::preprocess_host(
+                target,
+                link_type,
+                &platform_main_roc,
+                &preprocessed_host_path,
+                &platform_main_roc.with_file_name("libapp.so"),
+                &dll_stub_symbols,
+            );
+        }
+
+        // The surgical linker will modify this copy in-place.
+        std::fs::copy(&preprocessed_host_path, output_exe_path.as_path()).unwrap();
+
         None
     } else if is_platform_prebuilt {
         if !preprocessed_host_path.exists() {
@@ -899,7 +925,7 @@ fn build_loaded_file<'a>(
     // This only needs to be mutable for report_problems. This can't be done
     // inside a nested scope without causing a borrow error!
     let mut loaded = loaded;
-    let problems = report_problems_monomorphized(&mut loaded);
+    let problems = report_problems_monomorphized(&mut loaded, render);
     let loaded = loaded;
 
     enum HostRebuildTiming {
@@ -928,6 +954,13 @@ fn build_loaded_file<'a>(
         None
     };
 
+    let module_paths = loaded
+        .sources
+        .iter()
+        .filter(|(module_id, _)| !module_id.is_builtin())
+        .map(|(_, (path, _))| path.clone())
+        .collect();
+
     let (roc_app_bytes, code_gen_timing, expect_metadata) = gen_from_mono_module(
         arena,
         loaded,
@@ -986,6 +1019,7 @@ fn build_loaded_file<'a>(
         (LinkingStrategy::Surgical, _) => {
             roc_linker::link_preprocessed_host(
                 target,
+                link_type,
                 &platform_main_roc,
                 &roc_app_bytes,
                 &output_exe_path,
@@ -1061,6 +1095,7 @@ fn build_loaded_file<'a>(
         problems,
         total_time,
         expect_metadata,
+        module_paths,
     })
 }
 
@@ -1187,6 +1222,7 @@ fn build_and_preprocess_host_lowlevel(
 
     roc_linker::preprocess_host(
         target,
+        LinkType::Executable,
         platform_main_roc,
         preprocessed_host_path,
         &stub_lib,
@@ -1201,6 +1237,7 @@ pub fn check_file<'a>(
     emit_timings: bool,
     roc_cache_dir: RocCacheDir<'_>,
     threading: Threading,
+    render: RenderTarget,
 ) -> Result<(Problems, Duration), LoadingProblem<'a>> {
     let compilation_start = Instant::now();
 
@@ -1212,8 +1249,7 @@ pub fn check_file<'a>(
 
     let load_config = LoadConfig {
         target_info,
-        // TODO: expose this from CLI?
-        render: RenderTarget::ColorTerminal,
+        render,
         palette: DEFAULT_PALETTE,
         threading,
         exec_mode: ExecutionMode::Check,
@@ -1264,7 +1300,10 @@ pub fn check_file<'a>(
         println!("Finished checking in {} ms\n", compilation_end.as_millis(),);
     }
 
-    Ok((report_problems_typechecked(&mut loaded), compilation_end))
+    Ok((
+        report_problems_typechecked(&mut loaded, render),
+        compilation_end,
+    ))
 }
 
 pub fn build_str_test<'a>(
@@ -1291,6 +1330,7 @@ pub fn build_str_test<'a>(
     let threading = Threading::AtMost(2);
 
     let load_config = standard_load_config(&triple, build_ordering, threading);
+    let render = load_config.render;
 
     let compilation_start = std::time::Instant::now();
 
@@ -1317,5 +1357,6 @@ pub fn build_str_test<'a>(
         wasm_dev_stack_bytes,
         loaded,
         compilation_start,
+        render,
     )
 }
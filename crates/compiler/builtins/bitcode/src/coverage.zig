@@ -0,0 +1,16 @@
+// One counter for each region that `roc test --coverage` instruments. The compiler hands out
+// the ids, and calls to `roc_coverage_hit` count into this buffer.
+var COVERAGE_COUNTS: []u64 = &[_]u64{};
+
+pub fn setCoverageBuffer(ptr: [*]u64, length: usize) callconv(.C) usize {
+    COVERAGE_COUNTS = ptr[0..length];
+
+    // the rust side expects that a pointer is returned
+    return 0;
+}
+
+pub fn coverageHit(id: u32) callconv(.C) void {
+    if (id < COVERAGE_COUNTS.len) {
+        _ = @atomicRmw(u64, &COVERAGE_COUNTS[id], .Add, 1, .Monotonic);
+    }
+}
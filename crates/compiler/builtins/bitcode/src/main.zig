@@ -3,6 +3,7 @@ const builtin = @import("builtin");
 const math = std.math;
 const utils = @import("utils.zig");
 const expect = @import("expect.zig");
+const coverage = @import("coverage.zig");
 const panic_utils = @import("panic.zig");
 
 comptime {
@@ -214,6 +215,10 @@ comptime {
         @export(expect.setSharedBuffer, .{ .name = "set_shared_buffer", .linkage = .Weak });
 
         exportUtilsFn(expect.readSharedBufferEnv, "read_env_shared_buffer");
+
+        // counts executions of instrumented code; a host can provide its own roc_coverage_hit
+        @export(coverage.setCoverageBuffer, .{ .name = "set_coverage_buffer", .linkage = .Weak });
+        @export(coverage.coverageHit, .{ .name = "roc_coverage_hit", .linkage = .Weak });
     }
 
     if (builtin.target.cpu.arch == .aarch64) {
@@ -24,6 +24,7 @@ interface Decode
         list,
         record,
         tuple,
+        tag,
         custom,
         decodeWith,
         fromBytesPartial,
@@ -115,6 +116,14 @@ DecoderFormatting has
     ## `finalizer` should produce the tuple value from the decoded `state`.
     tuple : state, (state, Nat -> [Next (Decoder state fmt), TooLong]), (state -> Result val DecodeError) -> Decoder val fmt | fmt has DecoderFormatting
 
+    ## `tag stepTag` decodes a value of a tag union.
+    ##
+    ## `stepTag` returns a decoder for the payload of the tag with the given
+    ## name, or `Skip` if the tag is not a part of the decoded union. The
+    ## payload decoder reads the tag's arguments the same way [tuple] reads
+    ## the elements of a tuple.
+    tag : (Str -> [Keep (Decoder val fmt), Skip]) -> Decoder val fmt | fmt has DecoderFormatting
+
 ## Build a custom [Decoder] function. For example the implementation of
 ## `decodeBool` could be defined as follows;
 ##
@@ -21,6 +21,8 @@ interface Dict
         insertAll,
         keepShared,
         removeAll,
+        toSortedList,
+        sortedKeys,
     ]
     imports [
         Bool.{ Bool, Eq },
@@ -29,6 +31,7 @@ interface Dict
         Str,
         Num.{ Nat, U64, U8, I8 },
         Hash.{ Hasher, Hash },
+        Sort.{ Sort },
     ]
 
 ## A [dictionary](https://en.wikipedia.org/wiki/Associative_array) that lets you
@@ -483,6 +486,35 @@ values : Dict k v -> List v | k has Hash & Eq
 values = \@Dict { data } ->
     List.map data (\(_, v) -> v)
 
+## Returns the keys and values of a dictionary as a [List], in ascending order
+## of their keys. Use this rather than [Dict.toList] when the order matters,
+## since the order of [Dict.toList] depends on the order of insertions and
+## removals.
+## ```
+## expect
+##     Dict.single "b" 2
+##     |> Dict.insert "c" 3
+##     |> Dict.insert "a" 1
+##     |> Dict.toSortedList
+##     |> Bool.isEq [("a", 1), ("b", 2), ("c", 3)]
+## ```
+toSortedList : Dict k v -> List (k, v) | k has Hash & Eq & Sort
+toSortedList = \@Dict { data } ->
+    List.sortWith data (\(k1, _), (k2, _) -> Sort.compare k1 k2)
+
+## Returns the keys of a dictionary as a [List], in ascending order.
+## ```
+## expect
+##     Dict.single "b" 2
+##     |> Dict.insert "c" 3
+##     |> Dict.insert "a" 1
+##     |> Dict.sortedKeys
+##     |> Bool.isEq ["a", "b", "c"]
+## ```
+sortedKeys : Dict k v -> List k | k has Hash & Eq & Sort
+sortedKeys = \dict ->
+    List.sort (keys dict)
+
 ## Combine two dictionaries by keeping the [union](https://en.wikipedia.org/wiki/Union_(set_theory))
 ## of all the key-value pairs. This means that all the key-value pairs in
 ## both dictionaries will be combined. Note that where there are pairs
@@ -0,0 +1,325 @@
+interface Inspect
+    exposes [
+        Inspect,
+        toInspector,
+        Inspector,
+        InspectFormatter,
+        init,
+        tag,
+        tuple,
+        record,
+        bool,
+        str,
+        list,
+        set,
+        dict,
+        box,
+        opaque,
+        function,
+        u8,
+        i8,
+        u16,
+        i16,
+        u32,
+        i32,
+        u64,
+        i64,
+        u128,
+        i128,
+        f32,
+        f64,
+        dec,
+        nat,
+        custom,
+        apply,
+        inspect,
+        DbgFormatter,
+        toDbgStr,
+        toStr,
+    ]
+    imports [
+        Bool.{ Bool },
+        Num.{
+            U8,
+            U16,
+            U32,
+            U64,
+            U128,
+            I8,
+            I16,
+            I32,
+            I64,
+            I128,
+            F32,
+            F64,
+            Dec,
+            Nat,
+        },
+        List,
+        Str,
+        Result,
+    ]
+
+## A value that can be turned into a structured, human-readable representation.
+##
+## `Inspect` is derived automatically for records, tuples, tag unions, lists,
+## [Dict](Dict#Dict)s, [Set](Set#Set)s and [Box](Box#Box)es. Opaque types may
+## provide their own implementation or derive one, which shows the value they
+## wrap; those that do neither are shown as `<opaque>`, and functions are shown
+## as `<function>`.
+Inspect has
+    ## Describes how to present a value to an [InspectFormatter].
+    toInspector : val -> Inspector f | val has Inspect, f has InspectFormatter
+
+## Describes how to render the parts of an inspected value, for example as a
+## debug string.
+InspectFormatter has
+    init : {} -> f | f has InspectFormatter
+
+    tag : Str, List (Inspector f) -> Inspector f | f has InspectFormatter
+    tuple : List (Inspector f) -> Inspector f | f has InspectFormatter
+    record : List { key : Str, value : Inspector f } -> Inspector f | f has InspectFormatter
+    bool : Bool -> Inspector f | f has InspectFormatter
+    str : Str -> Inspector f | f has InspectFormatter
+
+    list : List elem, (elem -> Inspector f) -> Inspector f | f has InspectFormatter
+    ## Inspects the elements of a [Set](Set#Set), given as a list.
+    set : List elem, (elem -> Inspector f) -> Inspector f | f has InspectFormatter
+    ## Inspects the entries of a [Dict](Dict#Dict), given as a list of key-value pairs.
+    dict : List (key, value), (key -> Inspector f), (value -> Inspector f) -> Inspector f | f has InspectFormatter
+    ## Inspects the contents of a [Box](Box#Box), given unboxed.
+    box : elem, (elem -> Inspector f) -> Inspector f | f has InspectFormatter
+
+    ## Inspects a value of an opaque type that has no [Inspect] implementation
+    ## of its own.
+    opaque : * -> Inspector f | f has InspectFormatter
+    function : * -> Inspector f | f has InspectFormatter
+
+    u8 : U8 -> Inspector f | f has InspectFormatter
+    i8 : I8 -> Inspector f | f has InspectFormatter
+    u16 : U16 -> Inspector f | f has InspectFormatter
+    i16 : I16 -> Inspector f | f has InspectFormatter
+    u32 : U32 -> Inspector f | f has InspectFormatter
+    i32 : I32 -> Inspector f | f has InspectFormatter
+    u64 : U64 -> Inspector f | f has InspectFormatter
+    i64 : I64 -> Inspector f | f has InspectFormatter
+    u128 : U128 -> Inspector f | f has InspectFormatter
+    i128 : I128 -> Inspector f | f has InspectFormatter
+    f32 : F32 -> Inspector f | f has InspectFormatter
+    f64 : F64 -> Inspector f | f has InspectFormatter
+    dec : Dec -> Inspector f | f has InspectFormatter
+    nat : Nat -> Inspector f | f has InspectFormatter
+
+Inspector f := f -> f | f has InspectFormatter
+
+custom : (f -> f) -> Inspector f | f has InspectFormatter
+custom = \fn -> @Inspector fn
+
+apply : Inspector f, f -> f | f has InspectFormatter
+apply = \@Inspector fn, fmt -> fn fmt
+
+## Runs the [Inspector] of a value against a fresh formatter.
+inspect : val -> f | val has Inspect, f has InspectFormatter
+inspect = \val ->
+    apply (toInspector val) (init {})
+
+## Renders any value as a string, in the style of Roc source code. This is what
+## `dbg` and failing `expect`s use to show values.
+toStr : val -> Str | val has Inspect
+toStr = \val ->
+    val
+    |> inspect
+    |> toDbgStr
+
+## An [InspectFormatter] that renders values the way they would be written in
+## Roc source code.
+##
+## `nested` tracks whether we are writing the payload of a tag, where tags with
+## payloads of their own must be parenthesized.
+DbgFormatter := { data : Str, nested : Bool }
+    has [
+        InspectFormatter {
+            init: dbgInit,
+            tag: dbgTag,
+            tuple: dbgTuple,
+            record: dbgRecord,
+            bool: dbgBool,
+            str: dbgStr,
+            list: dbgList,
+            set: dbgSet,
+            dict: dbgDict,
+            box: dbgBox,
+            opaque: dbgOpaque,
+            function: dbgFunction,
+            u8: dbgNum,
+            i8: dbgNum,
+            u16: dbgNum,
+            i16: dbgNum,
+            u32: dbgNum,
+            i32: dbgNum,
+            u64: dbgNum,
+            i64: dbgNum,
+            u128: dbgNum,
+            i128: dbgNum,
+            f32: dbgNum,
+            f64: dbgNum,
+            dec: dbgNum,
+            nat: dbgNum,
+        },
+    ]
+
+toDbgStr : DbgFormatter -> Str
+toDbgStr = \@DbgFormatter { data } -> data
+
+dbgInit : {} -> DbgFormatter
+dbgInit = \{} -> @DbgFormatter { data: "", nested: Bool.false }
+
+dbgWrite : DbgFormatter, Str -> DbgFormatter
+dbgWrite = \@DbgFormatter { data, nested }, added ->
+    @DbgFormatter { data: Str.concat data added, nested }
+
+isNested : DbgFormatter -> Bool
+isNested = \@DbgFormatter { nested } -> nested
+
+setNested : DbgFormatter, Bool -> DbgFormatter
+setNested = \@DbgFormatter { data }, nested ->
+    @DbgFormatter { data, nested }
+
+## Writes `items` separated by commas, outside of any tag payload.
+dbgItems : DbgFormatter, List elem, (elem -> Inspector DbgFormatter) -> DbgFormatter
+dbgItems = \f0, items, toItemInspector ->
+    initial = { f: setNested f0 Bool.false, prependSep: Bool.false }
+
+    { f: written } =
+        List.walk items initial \{ f: f1, prependSep }, item ->
+            f2 = if prependSep then dbgWrite f1 ", " else f1
+
+            { f: apply (toItemInspector item) f2, prependSep: Bool.true }
+
+    setNested written (isNested f0)
+
+## Writes `body`, wrapped in parentheses if we are inside a tag payload.
+dbgParenthesized : DbgFormatter, (DbgFormatter -> DbgFormatter) -> DbgFormatter
+dbgParenthesized = \f0, body ->
+    if isNested f0 then
+        dbgWrite f0 "("
+        |> setNested Bool.false
+        |> body
+        |> dbgWrite ")"
+        |> setNested Bool.true
+    else
+        body f0
+
+dbgTag : Str, List (Inspector DbgFormatter) -> Inspector DbgFormatter
+dbgTag = \name, fields ->
+    if List.isEmpty fields then
+        custom \f0 -> dbgWrite f0 name
+    else
+        custom \f0 ->
+            dbgParenthesized f0 \f1 ->
+                List.walk fields (dbgWrite f1 name) \f2, inspector ->
+                    f3 = dbgWrite f2 " " |> setNested Bool.true
+
+                    apply inspector f3 |> setNested Bool.false
+
+dbgTuple : List (Inspector DbgFormatter) -> Inspector DbgFormatter
+dbgTuple = \fields ->
+    custom \f0 ->
+        dbgWrite f0 "("
+        |> dbgItems fields (\inspector -> inspector)
+        |> dbgWrite ")"
+
+dbgRecord : List { key : Str, value : Inspector DbgFormatter } -> Inspector DbgFormatter
+dbgRecord = \fields ->
+    if List.isEmpty fields then
+        custom \f0 -> dbgWrite f0 "{}"
+    else
+        custom \f0 ->
+            dbgWrite f0 "{ "
+            |> dbgItems fields dbgField
+            |> dbgWrite " }"
+
+dbgField : { key : Str, value : Inspector DbgFormatter } -> Inspector DbgFormatter
+dbgField = \{ key, value } ->
+    custom \f0 ->
+        f1 = dbgWrite f0 "\(key): "
+
+        apply value f1
+
+dbgBool : Bool -> Inspector DbgFormatter
+dbgBool = \b ->
+    if b then
+        custom \f0 -> dbgWrite f0 "Bool.true"
+    else
+        custom \f0 -> dbgWrite f0 "Bool.false"
+
+dbgStr : Str -> Inspector DbgFormatter
+dbgStr = \s ->
+    escaped =
+        s
+        |> escape "\\" "\\\\"
+        |> escape "\"" "\\\""
+
+    custom \f0 ->
+        dbgWrite f0 "\""
+        |> dbgWrite escaped
+        |> dbgWrite "\""
+
+escape : Str, Str, Str -> Str
+escape = \s, needle, replacement ->
+    Str.replaceEach s needle replacement
+    |> Result.withDefault s
+
+dbgList : List elem, (elem -> Inspector DbgFormatter) -> Inspector DbgFormatter
+dbgList = \content, toElemInspector ->
+    custom \f0 ->
+        dbgWrite f0 "["
+        |> dbgItems content toElemInspector
+        |> dbgWrite "]"
+
+dbgSet : List elem, (elem -> Inspector DbgFormatter) -> Inspector DbgFormatter
+dbgSet = \content, toElemInspector ->
+    custom \f0 ->
+        dbgParenthesized f0 \f1 ->
+            apply (dbgList content toElemInspector) (dbgWrite f1 "Set.fromList ")
+
+dbgDict : List (key, value), (key -> Inspector DbgFormatter), (value -> Inspector DbgFormatter) -> Inspector DbgFormatter
+dbgDict = \entries, toKeyInspector, toValueInspector ->
+    toEntryInspector = \(key, value) ->
+        dbgTuple [toKeyInspector key, toValueInspector value]
+
+    custom \f0 ->
+        dbgParenthesized f0 \f1 ->
+            apply (dbgList entries toEntryInspector) (dbgWrite f1 "Dict.fromList ")
+
+dbgBox : elem, (elem -> Inspector DbgFormatter) -> Inspector DbgFormatter
+dbgBox = \content, toContentInspector ->
+    dbgTag "Box.box" [toContentInspector content]
+
+dbgOpaque : * -> Inspector DbgFormatter
+dbgOpaque = \_ ->
+    custom \f0 -> dbgWrite f0 "<opaque>"
+
+dbgFunction : * -> Inspector DbgFormatter
+dbgFunction = \_ ->
+    custom \f0 -> dbgWrite f0 "<function>"
+
+dbgNum : Num a -> Inspector DbgFormatter
+dbgNum = \n ->
+    s = Num.toStr n
+
+    if Str.startsWith s "-" then
+        custom \f0 ->
+            if isNested f0 then
+                dbgWrite f0 "(\(s))"
+            else
+                dbgWrite f0 s
+    else
+        custom \f0 -> dbgWrite f0 s
+
+expect toStr 42 == "42"
+expect toStr "abc" == "\"abc\""
+expect toStr (A (B C)) == "A (B C)"
+expect toStr (A (Num.neg 1)) == "A (-1)"
+expect toStr { a: [1, 2], b: Ok (1, "x") } == "{ a: [1, 2], b: Ok (1, \"x\") }"
+expect toStr {} == "{}"
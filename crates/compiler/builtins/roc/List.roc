@@ -66,11 +66,14 @@ interface List
         releaseExcessCapacity,
         walkBackwardsUntil,
         countIf,
+        sort,
+        compare,
     ]
     imports [
         Bool.{ Bool, Eq },
         Result.{ Result },
         Num.{ Nat, Num, Int },
+        Sort.{ Sort },
     ]
 
 ## ## Types
@@ -877,6 +880,37 @@ sortAsc = \list -> List.sortWith list Num.compare
 sortDesc : List (Num a) -> List (Num a)
 sortDesc = \list -> List.sortWith list (\a, b -> Num.compare b a)
 
+## Sorts a list in ascending order, using the element type's implementation of
+## the [Sort](../Sort#Sort) ability.
+## ```
+## expect List.sort ["banana", "apple", "cherry"] == ["apple", "banana", "cherry"]
+## ```
+##
+## To sort in some other order, use [List.sortWith] with a comparison function.
+sort : List a -> List a | a has Sort
+sort = \list -> List.sortWith list Sort.compare
+
+## Compares two lists by their first element which differs. If one list is a
+## prefix of the other, the shorter list comes first.
+##
+## This is how [Sort](../Sort#Sort) puts lists in order.
+## ```
+## expect List.compare [1, 2] [1, 3] == LT
+## expect List.compare [1, 2] [1] == GT
+## ```
+compare : List a, List a -> [LT, EQ, GT] | a has Sort
+compare = \first, second ->
+    compareHelp first second 0 (Num.min (List.len first) (List.len second))
+
+compareHelp : List a, List a, Nat, Nat -> [LT, EQ, GT] | a has Sort
+compareHelp = \first, second, index, sharedLen ->
+    if index < sharedLen then
+        when Sort.compare (List.getUnsafe first index) (List.getUnsafe second index) is
+            EQ -> compareHelp first second (index + 1) sharedLen
+            order -> order
+    else
+        Num.compare (List.len first) (List.len second)
+
 swap : List a, Nat, Nat -> List a
 
 ## Returns the first element in the list, or `ListWasEmpty` if it was empty.
@@ -14,6 +14,7 @@ interface Set
         union,
         intersection,
         difference,
+        toSortedList,
     ]
     imports [
         List,
@@ -21,6 +22,7 @@ interface Set
         Dict.{ Dict },
         Num.{ Nat },
         Hash.{ Hash, Hasher },
+        Sort.{ Sort },
     ]
 
 ## Provides a [set](https://en.wikipedia.org/wiki/Set_(abstract_data_type))
@@ -181,6 +183,15 @@ toList : Set k -> List k | k has Hash & Eq
 toList = \@Set dict ->
     Dict.keys dict
 
+## Retrieve the values in a `Set` as a `List`, in ascending order. Use this
+## rather than [Set.toList] when the order matters.
+## ```
+## expect Set.toSortedList (Set.fromList [3, 1, 2]) == [1, 2, 3]
+## ```
+toSortedList : Set k -> List k | k has Hash & Eq & Sort
+toSortedList = \@Set dict ->
+    Dict.sortedKeys dict
+
 ## Create a `Set` from a `List` of values.
 ## ```
 ## values =
@@ -0,0 +1,97 @@
+interface Sort
+    exposes [
+        Sort,
+        compare,
+        isLt,
+        isLte,
+        isGt,
+        isGte,
+        min,
+        max,
+    ]
+    imports [
+        Bool.{ Bool },
+    ]
+
+## A value that can be put in order, so that it can be sorted (for example
+## with [`List.sort`](../List#sort)) or compared with [isLt], [isGt] and friends.
+##
+## `Sort` can be derived for numbers, strings, lists, records, tuples and tag
+## unions whose contents implement `Sort`:
+##
+## - Strings are ordered by their UTF-8 bytes, so `"Z"` comes before `"a"`.
+## - Lists are ordered by their first element which differs; a list that is a
+##   prefix of another comes first.
+## - Records are ordered by their first field which differs, with fields taken
+##   in alphabetical order of their names. Tuples are ordered the same way,
+##   going from their first element to their last.
+## - Tag unions are ordered by their first tag which differs, with tags taken
+##   in alphabetical order of their names. Values with the same tag are ordered
+##   by their payloads, as if they were tuples.
+##
+## Floating-point numbers are put in order with [`Num.compare`](../Num#compare).
+Sort has
+    ## Returns `LT` if the first value comes before the second one, `GT` if it
+    ## comes after it, and `EQ` if neither comes first.
+    ## ```
+    ## expect Sort.compare "apple" "banana" == LT
+    ## ```
+    compare : a, a -> [LT, EQ, GT] | a has Sort
+
+## Returns `Bool.true` if the first value comes before the second one.
+## ```
+## expect Sort.isLt { x: 1, y: 5 } { x: 2, y: 0 }
+## ```
+isLt : a, a -> Bool | a has Sort
+isLt = \a, b ->
+    when compare a b is
+        LT -> Bool.true
+        _ -> Bool.false
+
+## Returns `Bool.true` if the first value comes before the second one, or
+## neither comes first.
+isLte : a, a -> Bool | a has Sort
+isLte = \a, b ->
+    when compare a b is
+        GT -> Bool.false
+        _ -> Bool.true
+
+## Returns `Bool.true` if the first value comes after the second one.
+## ```
+## expect Sort.isGt (Ok 0) (Err "oops")
+## ```
+isGt : a, a -> Bool | a has Sort
+isGt = \a, b ->
+    when compare a b is
+        GT -> Bool.true
+        _ -> Bool.false
+
+## Returns `Bool.true` if the first value comes after the second one, or
+## neither comes first.
+isGte : a, a -> Bool | a has Sort
+isGte = \a, b ->
+    when compare a b is
+        LT -> Bool.false
+        _ -> Bool.true
+
+## Returns whichever of the two values comes first. If neither does, returns
+## the first one.
+## ```
+## expect Sort.min "b" "a" == "a"
+## ```
+min : a, a -> a | a has Sort
+min = \a, b ->
+    when compare a b is
+        GT -> b
+        _ -> a
+
+## Returns whichever of the two values comes last. If neither does, returns
+## the first one.
+## ```
+## expect Sort.max (1, "z") (2, "a") == (2, "a")
+## ```
+max : a, a -> a | a has Sort
+max = \a, b ->
+    when compare a b is
+        LT -> b
+        _ -> a
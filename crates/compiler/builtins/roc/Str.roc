@@ -135,6 +135,7 @@ interface Str
         withCapacity,
         withPrefix,
         graphemes,
+        compare,
     ]
     imports [
         Bool.{ Bool, Eq },
@@ -990,3 +991,35 @@ strToNumHelp = \string ->
 ## ```
 withPrefix : Str, Str -> Str
 withPrefix = \str, prefix -> Str.concat prefix str
+
+## Compares two strings by their `UTF-8` bytes, so strings are put in the order
+## of their first differing Unicode code point. This isn't the alphabetical
+## order of any particular language; for example, all uppercase ASCII letters
+## come before all lowercase ones.
+##
+## If one string is a prefix of the other, the shorter string comes first.
+##
+## This is how [Sort](../Sort#Sort) puts strings in order.
+## ```
+## expect Str.compare "apple" "banana" == LT
+## expect Str.compare "Zebra" "apple" == LT
+## expect Str.compare "apples" "apple" == GT
+## ```
+compare : Str, Str -> [LT, EQ, GT]
+compare = \first, second ->
+    sharedLen = Num.min (Str.countUtf8Bytes first) (Str.countUtf8Bytes second)
+
+    compareHelp first second 0 sharedLen
+
+compareHelp : Str, Str, Nat, Nat -> [LT, EQ, GT]
+compareHelp = \first, second, index, sharedLen ->
+    if index < sharedLen then
+        when Num.compare (Str.getUnsafe first index) (Str.getUnsafe second index) is
+            EQ -> compareHelp first second (index + 1) sharedLen
+            order -> order
+    else
+        Num.compare (Str.countUtf8Bytes first) (Str.countUtf8Bytes second)
+
+expect compare "" "" == EQ
+expect compare "鹏" "a" == GT
+expect compare "abc" "abd" == LT
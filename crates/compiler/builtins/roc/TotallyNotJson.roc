@@ -85,6 +85,7 @@ Json := { fieldNameMapping : FieldNameMapping }
              list: decodeList,
              record: decodeRecord,
              tuple: decodeTuple,
+             tag: decodeTag,
          },
      ]
 
@@ -693,7 +694,15 @@ decodeTuple = \initialState, stepElem, finalizer -> Decode.custom \initialBytes,
 
         { rest: afterBracketBytes } <- initialBytes |> openBracket |> tryDecode
 
-        { val: endStateResult, rest: beforeClosingBracketBytes } <- decodeElems stepElem initialState 0 afterBracketBytes |> tryDecode
+        { val: endStateResult, rest: beforeClosingBracketBytes } <-
+            (
+                # Tags without a payload are encoded with an empty `[]`
+                if List.first afterBracketBytes == Ok (Num.toU8 ']') then
+                    { result: Ok initialState, rest: afterBracketBytes }
+                else
+                    decodeElems stepElem initialState 0 afterBracketBytes
+            )
+            |> tryDecode
 
         { rest: afterTupleBytes } <- beforeClosingBracketBytes |> closingBracket |> tryDecode
 
@@ -708,6 +717,43 @@ expect
 
     actual.result == Ok ("The Answer is", 42)
 
+decodeTag = \stepTag -> Decode.custom \bytes, @Json { fieldNameMapping } ->
+        # Idea: decode `{"A": [v1, v2]}` as `A v1 v2`, the inverse of `encodeTag`
+        { rest: afterCurlyBytes } <- bytes |> openCurly |> tryDecode
+
+        { val: name, rest: afterNameBytes } <- Decode.decodeWith afterCurlyBytes decodeString json |> tryDecode
+
+        { rest: afterColonBytes } <- afterNameBytes |> colon |> tryDecode
+
+        { val: tagValue, rest: afterPayloadBytes } <-
+            (
+                when stepTag name is
+                    Skip -> { result: Err TooShort, rest: afterColonBytes }
+                    Keep payloadDecoder ->
+                        Decode.decodeWith afterColonBytes payloadDecoder (@Json { fieldNameMapping })
+            )
+            |> tryDecode
+
+        { rest: afterTagBytes } <- afterPayloadBytes |> closingCurly |> tryDecode
+
+        { result: Ok tagValue, rest: afterTagBytes }
+
+# Test decode of tag
+expect
+    input = Str.toUtf8 "{\"TheAnswer\":[\"is\",42]}"
+    actual : DecodeResult [TheAnswer Str U8, NoAnswer]
+    actual = Decode.fromBytesPartial input json
+
+    actual.result == Ok (TheAnswer "is" 42)
+
+# Test decode of tag without a payload
+expect
+    input = Str.toUtf8 "{\"NoAnswer\":[]}"
+    actual : DecodeResult [TheAnswer Str U8, NoAnswer]
+    actual = Decode.fromBytesPartial input json
+
+    actual.result == Ok NoAnswer
+
 parseExactChar : List U8, U8 -> DecodeResult {}
 parseExactChar = \bytes, char ->
     when List.get bytes 0 is
@@ -733,6 +779,15 @@ anything = \bytes -> { result: Err TooShort, rest: bytes }
 comma : List U8 -> DecodeResult {}
 comma = \bytes -> parseExactChar bytes ','
 
+openCurly : List U8 -> DecodeResult {}
+openCurly = \bytes -> parseExactChar bytes '{'
+
+closingCurly : List U8 -> DecodeResult {}
+closingCurly = \bytes -> parseExactChar bytes '}'
+
+colon : List U8 -> DecodeResult {}
+colon = \bytes -> parseExactChar bytes ':'
+
 tryDecode : DecodeResult a, ({ val : a, rest : List U8 } -> DecodeResult b) -> DecodeResult b
 tryDecode = \{ result, rest }, mapper ->
     when result is
@@ -1255,6 +1255,10 @@ pub struct TypeState {
 }
 
 impl TypeState {
+    /// Each part is deserialized from its own subslice, reinterpreting bytes as slices of the
+    /// serialized types, so every part has to start at a suitably aligned offset.
+    const PART_ALIGNMENT: usize = 8;
+
     pub fn serialize(&self, writer: &mut impl std::io::Write) -> std::io::Result<usize> {
         let Self {
             subs,
@@ -1264,24 +1268,36 @@ impl TypeState {
         } = self;
 
         let written_subs = subs.serialize(exposed_vars_by_symbol, writer)?;
+        let written = Self::write_padding(written_subs, writer)?;
+
         let written_ab = abilities.serialize(writer)?;
+        let written = Self::write_padding(written + written_ab, writer)?;
+
         let written_solved_impls =
             crate::abilities::serialize_solved_implementations(solved_implementations, writer)?;
 
-        Ok(written_subs + written_ab + written_solved_impls)
+        Ok(written + written_solved_impls)
+    }
+
+    fn write_padding(written: usize, writer: &mut impl std::io::Write) -> std::io::Result<usize> {
+        let padded = roc_serialize::bytes::next_multiple_of(written, Self::PART_ALIGNMENT);
+        writer.write_all(&[0; Self::PART_ALIGNMENT][..padded - written])?;
+
+        Ok(padded)
     }
 
     pub fn deserialize(bytes: &[u8]) -> (Self, usize) {
         let ((subs, exposed_vars_by_symbol), len_subs) = Subs::deserialize(bytes);
-        let bytes = &bytes[len_subs..];
+        let offset = roc_serialize::bytes::next_multiple_of(len_subs, Self::PART_ALIGNMENT);
 
-        let (abilities, len_abilities) = AbilitiesStore::deserialize(bytes);
-        let bytes = &bytes[len_abilities..];
+        let (abilities, len_abilities) = AbilitiesStore::deserialize(&bytes[offset..]);
+        let offset =
+            roc_serialize::bytes::next_multiple_of(offset + len_abilities, Self::PART_ALIGNMENT);
 
         let (solved_implementations, len_solved_impls) =
-            crate::abilities::deserialize_solved_implementations(bytes);
+            crate::abilities::deserialize_solved_implementations(&bytes[offset..]);
 
-        let total_offset = len_subs + len_abilities + len_solved_impls;
+        let total_offset = offset + len_solved_impls;
 
         (
             Self {
@@ -87,7 +87,7 @@ fn desugar_value_def<'a>(arena: &'a Bump, def: &'a ValueDef<'a>) -> ValueDef<'a>
             condition,
             preceding_comment,
         } => {
-            let desugared_condition = &*arena.alloc(desugar_expr(arena, condition));
+            let desugared_condition = desugar_dbg_condition(arena, condition);
             Dbg {
                 condition: desugared_condition,
                 preceding_comment: *preceding_comment,
@@ -420,7 +420,7 @@ pub fn desugar_expr<'a>(arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> &'a Loc
             })
         }
         Dbg(condition, continuation) => {
-            let desugared_condition = &*arena.alloc(desugar_expr(arena, condition));
+            let desugared_condition = desugar_dbg_condition(arena, condition);
             let desugared_continuation = &*arena.alloc(desugar_expr(arena, continuation));
             arena.alloc(Loc {
                 value: Dbg(desugared_condition, desugared_continuation),
@@ -430,6 +430,29 @@ pub fn desugar_expr<'a>(arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> &'a Loc
     }
 }
 
+/// `dbg` shows its value through `Inspect.toStr`, so that opaques can decide how they are shown.
+fn desugar_dbg_condition<'a>(arena: &'a Bump, condition: &'a Loc<Expr<'a>>) -> &'a Loc<Expr<'a>> {
+    let region = condition.region;
+    let desugared_condition = desugar_expr(arena, condition);
+
+    let inspect_to_str = arena.alloc(Loc {
+        value: Var {
+            module_name: ModuleName::INSPECT,
+            ident: "toStr",
+        },
+        region,
+    });
+
+    arena.alloc(Loc {
+        value: Apply(
+            inspect_to_str,
+            arena.alloc([desugared_condition]),
+            CalledVia::Space,
+        ),
+        region,
+    })
+}
+
 fn desugar_field<'a>(
     arena: &'a Bump,
     field: &'a AssignedField<'a, Expr<'a>>,
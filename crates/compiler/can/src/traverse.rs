@@ -1,7 +1,7 @@
 //! Traversals over the can ast.
 
 use roc_module::{ident::Lowercase, symbol::Symbol};
-use roc_region::all::{Loc, Region};
+use roc_region::all::{Loc, Position, Region};
 use roc_types::{subs::Variable, types::MemberImpl};
 
 use crate::{
@@ -664,6 +664,148 @@ pub fn find_type_at(region: Region, decls: &Declarations) -> Option<Variable> {
     visitor.typ
 }
 
+/// Attempts to find the type of the innermost expression or pattern containing `position`.
+pub fn find_closest_type_at(position: Position, decls: &Declarations) -> Option<Variable> {
+    let mut visitor = Finder {
+        position,
+        typ: None,
+    };
+    visitor.visit_decls(decls);
+    return visitor.typ;
+
+    struct Finder {
+        position: Position,
+        typ: Option<Variable>,
+    }
+
+    impl Visitor for Finder {
+        fn should_visit(&mut self, region: Region) -> bool {
+            region.contains_pos(self.position)
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            if region.contains_pos(self.position) {
+                // Nested expressions are visited after their parents, so the last
+                // match is the innermost one.
+                self.typ = Some(var);
+
+                walk_expr(self, expr, var);
+            }
+        }
+
+        fn visit_pattern(&mut self, pat: &Pattern, region: Region, opt_var: Option<Variable>) {
+            if region.contains_pos(self.position) {
+                if opt_var.is_some() {
+                    self.typ = opt_var;
+                }
+
+                walk_pattern(self, pat);
+            }
+        }
+    }
+}
+
+/// Attempts to find the symbol that is looked up or introduced by the innermost expression or
+/// pattern containing `position`.
+pub fn find_closest_symbol_at(position: Position, decls: &Declarations) -> Option<Symbol> {
+    let mut visitor = Finder {
+        position,
+        found: None,
+    };
+    visitor.visit_decls(decls);
+    return visitor.found;
+
+    struct Finder {
+        position: Position,
+        found: Option<Symbol>,
+    }
+
+    impl Visitor for Finder {
+        fn should_visit(&mut self, region: Region) -> bool {
+            region.contains_pos(self.position)
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            if region.contains_pos(self.position) {
+                match expr {
+                    Expr::Var(symbol, _) | Expr::AbilityMember(symbol, _, _) => {
+                        self.found = Some(*symbol)
+                    }
+                    _ => walk_expr(self, expr, var),
+                }
+            }
+        }
+
+        fn visit_pattern(&mut self, pat: &Pattern, region: Region, _opt_var: Option<Variable>) {
+            if region.contains_pos(self.position) {
+                match pat {
+                    Pattern::Identifier(symbol)
+                    | Pattern::Shadowed(_, _, symbol)
+                    | Pattern::AbilityMemberSpecialization { ident: symbol, .. } => {
+                        self.found = Some(*symbol)
+                    }
+                    _ => walk_pattern(self, pat),
+                }
+            }
+        }
+
+        fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+            if region.contains_pos(self.position) {
+                self.found = Some(destruct.symbol);
+
+                walk_record_destruct(self, destruct);
+            }
+        }
+    }
+}
+
+/// Finds the region of the pattern that binds `symbol`, be it a top-level declaration, a nested
+/// def, a function argument or a `when` branch.
+pub fn find_symbol_binding_region(symbol: Symbol, decls: &Declarations) -> Option<Region> {
+    let mut visitor = Finder {
+        symbol,
+        found: None,
+    };
+    visitor.visit_decls(decls);
+    return visitor.found;
+
+    struct Finder {
+        symbol: Symbol,
+        found: Option<Region>,
+    }
+
+    impl Visitor for Finder {
+        fn should_visit(&mut self, _region: Region) -> bool {
+            self.found.is_none()
+        }
+
+        fn visit_pattern(&mut self, pat: &Pattern, region: Region, _opt_var: Option<Variable>) {
+            match pat {
+                Pattern::Identifier(symbol)
+                | Pattern::Shadowed(_, _, symbol)
+                | Pattern::AbilityMemberSpecialization { ident: symbol, .. }
+                    if *symbol == self.symbol =>
+                {
+                    self.found = Some(region);
+                }
+                _ => {
+                    if self.should_visit(region) {
+                        walk_pattern(self, pat);
+                    }
+                }
+            }
+        }
+
+        fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+            if destruct.symbol == self.symbol {
+                self.found = Some(region);
+            } else if self.should_visit(region) {
+                walk_record_destruct(self, destruct);
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum FoundSymbol {
     /// Specialization(T, foo1) is the specialization of foo for T.
@@ -0,0 +1,587 @@
+//! Derivers for the `Sort` ability.
+
+use std::iter::{once, repeat_with};
+
+use roc_can::{
+    expr::{AnnotatedMark, ClosureData, Expr, Recursive, WhenBranch, WhenBranchPattern},
+    pattern::Pattern,
+};
+use roc_derive_key::compare::FlatCompareKey;
+use roc_module::{
+    called_via::CalledVia,
+    ident::{Lowercase, TagName},
+    symbol::Symbol,
+};
+use roc_region::all::{Loc, Region};
+use roc_types::{
+    subs::{
+        Content, ExhaustiveMark, FlatType, LambdaSet, OptVariable, RecordFields, RedundantMark,
+        SubsSlice, TagExt, TupleElems, UnionLambdas, UnionTags, Variable, VariableSubsSlice,
+    },
+    types::RecordField,
+};
+
+use crate::{
+    synth_var,
+    util::{Env, ExtensionKind},
+    DerivedBody,
+};
+
+pub(crate) fn derive_compare(
+    env: &mut Env<'_>,
+    key: FlatCompareKey,
+    def_symbol: Symbol,
+) -> DerivedBody {
+    let (body_type, body) = match key {
+        FlatCompareKey::Record(fields) => compare_record(env, def_symbol, fields),
+        FlatCompareKey::Tuple(arity) => compare_tuple(env, def_symbol, arity),
+        FlatCompareKey::TagUnion(tags) => compare_tag_union(env, def_symbol, tags),
+    };
+
+    let specialization_lambda_sets =
+        env.get_specialization_lambda_sets(body_type, Symbol::SORT_COMPARE);
+
+    DerivedBody {
+        body,
+        body_type,
+        specialization_lambda_sets,
+    }
+}
+
+fn compare_record(env: &mut Env<'_>, fn_name: Symbol, fields: Vec<Lowercase>) -> (Variable, Expr) {
+    // Suppose rcd = { f1, ..., fn }.
+    // Build a generalized type t_rcd = { f1: t1, ..., fn: tn }, with fresh t1, ..., tn,
+    // so that we can re-use the derived impl for many records of the same fields.
+    let (record_var, record_fields) = {
+        let flex_fields = fields
+            .into_iter()
+            .map(|name| {
+                (
+                    name,
+                    RecordField::Required(env.subs.fresh_unnamed_flex_var()),
+                )
+            })
+            .collect::<Vec<(Lowercase, _)>>();
+        let fields = RecordFields::insert_into_subs(env.subs, flex_fields);
+        let record_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::Record(fields, Variable::EMPTY_RECORD)),
+        );
+
+        (record_var, fields)
+    };
+
+    // Now, a comparison for this record is
+    //
+    // compare_rcd : { f1: t1, ..., fn: tn }, { f1: t1, ..., fn: tn } -> [LT, EQ, GT]
+    // compare_rcd = \lhs, rhs ->
+    //   when Sort.compare lhs.f1 rhs.f1 is
+    //     EQ ->
+    //       ...
+    //         Sort.compare lhs.fn rhs.fn
+    //     order -> order
+    //
+    // where the fields are in alphabetical order.
+    let lhs_sym = env.new_symbol("lhs");
+    let rhs_sym = env.new_symbol("rhs");
+    let order_var = order_var(env);
+
+    let comparisons = record_fields
+        .iter_all()
+        .map(|(field_name, field_var, _)| {
+            let field_name = env.subs[field_name].clone();
+            let field_var = env.subs[field_var];
+
+            let mut field_access = |rcd_sym| Expr::RecordAccess {
+                record_var,
+                field_var,
+                ext_var: env.subs.fresh_unnamed_flex_var(),
+                loc_expr: Box::new(Loc::at_zero(Expr::Var(
+                    rcd_sym,
+                    env.subs.fresh_unnamed_flex_var(),
+                ))),
+                field: field_name.clone(),
+            };
+
+            (field_var, field_access(lhs_sym), field_access(rhs_sym))
+        })
+        .collect();
+
+    let body = compare_in_turn(env, order_var, comparisons);
+
+    // Finally, build the closure
+    // \lhs, rhs -> body
+    build_outer_derived_closure(
+        env,
+        fn_name,
+        (record_var, lhs_sym, rhs_sym),
+        (order_var, body),
+    )
+}
+
+fn compare_tuple(env: &mut Env<'_>, fn_name: Symbol, arity: u32) -> (Variable, Expr) {
+    // Suppose tup = (v1, ..., vn).
+    // Build a generalized type t_tup = (t1, ..., tn), with fresh t1, ..., tn,
+    // so that we can re-use the derived impl for many tuples of the same arity.
+    let (tuple_var, tuple_elems) = {
+        // TODO: avoid an allocation here by pre-allocating the indices and variables `TupleElems`
+        // will be instantiated with.
+        let flex_elems: Vec<_> = (0..arity)
+            .map(|i| (i as usize, env.subs.fresh_unnamed_flex_var()))
+            .collect();
+        let elems = TupleElems::insert_into_subs(env.subs, flex_elems);
+        let tuple_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::Tuple(elems, Variable::EMPTY_TUPLE)),
+        );
+
+        (tuple_var, elems)
+    };
+
+    // Now, a comparison for this tuple is
+    //
+    // compare_tup : (t1, ..., tn), (t1, ..., tn) -> [LT, EQ, GT]
+    // compare_tup = \lhs, rhs ->
+    //   when Sort.compare lhs.0 rhs.0 is
+    //     EQ ->
+    //       ...
+    //         Sort.compare lhs.n rhs.n
+    //     order -> order
+    let lhs_sym = env.new_symbol("lhs");
+    let rhs_sym = env.new_symbol("rhs");
+    let order_var = order_var(env);
+
+    let comparisons = tuple_elems
+        .iter_all()
+        .map(|(elem_idx, elem_var)| {
+            let index = env.subs[elem_idx];
+            let elem_var = env.subs[elem_var];
+
+            let mut elem_access = |tup_sym| Expr::TupleAccess {
+                tuple_var,
+                elem_var,
+                ext_var: env.subs.fresh_unnamed_flex_var(),
+                loc_expr: Box::new(Loc::at_zero(Expr::Var(
+                    tup_sym,
+                    env.subs.fresh_unnamed_flex_var(),
+                ))),
+                index,
+            };
+
+            (elem_var, elem_access(lhs_sym), elem_access(rhs_sym))
+        })
+        .collect();
+
+    let body = compare_in_turn(env, order_var, comparisons);
+
+    // Finally, build the closure
+    // \lhs, rhs -> body
+    build_outer_derived_closure(
+        env,
+        fn_name,
+        (tuple_var, lhs_sym, rhs_sym),
+        (order_var, body),
+    )
+}
+
+fn compare_tag_union(
+    env: &mut Env<'_>,
+    fn_name: Symbol,
+    tags: Vec<(TagName, u16)>,
+) -> (Variable, Expr) {
+    // Suppose tags = [ A p11 .. p1n, ..., Q pq1 .. pqm ]
+    // Build a generalized type t_tags = [ A t11 .. t1n, ..., Q tq1 .. tqm ],
+    // with fresh t1, ..., tqm, so that we can re-use the derived impl for many
+    // unions of the same tags and payloads.
+    let (union_var, tags) = {
+        let flex_tags: Vec<(TagName, Vec<Variable>)> = tags
+            .into_iter()
+            .map(|(label, arity)| {
+                let payload_vars = repeat_with(|| env.subs.fresh_unnamed_flex_var())
+                    .take(arity.into())
+                    .collect();
+
+                (label, payload_vars)
+            })
+            .collect();
+        let union_tags = UnionTags::insert_into_subs(
+            env.subs,
+            flex_tags
+                .iter()
+                .map(|(label, payload_vars)| (label.clone(), payload_vars.iter().copied())),
+        );
+        let tag_union_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::TagUnion(
+                union_tags,
+                TagExt::Any(Variable::EMPTY_TAG_UNION),
+            )),
+        );
+
+        (tag_union_var, flex_tags)
+    };
+
+    // Now, a comparison for this tag union is
+    //
+    // compare_union : [ A t11 .. t1n, ..., Q tq1 .. tqm ], [ A t11 .. t1n, ..., Q tq1 .. tqm ]
+    //                 -> [LT, EQ, GT]
+    // compare_union = \lhs, rhs ->
+    //   when lhs is
+    //     A x11 .. x1n ->
+    //       when rhs is
+    //         A y11 .. y1n -> <compare x11 y11, ..., x1n y1n in turn, like a tuple>
+    //         B _ .. _ | ... | Q _ .. _ -> LT
+    //     ...
+    //     Q xq1 .. xqm ->
+    //       when rhs is
+    //         A _ .. _ | ... | P _ .. _ -> GT
+    //         Q yq1 .. yqm -> <compare xq1 yq1, ..., xqm yqm in turn, like a tuple>
+    //
+    // where the tags are in alphabetical order.
+    let lhs_sym = env.new_symbol("lhs");
+    let rhs_sym = env.new_symbol("rhs");
+    let order_var = order_var(env);
+
+    let mut branches = Vec::with_capacity(tags.len());
+    for (tag_index, (tag_name, payload_vars)) in tags.iter().enumerate() {
+        // x11 .. x1n
+        let lhs_payload_syms: Vec<_> = repeat_with(|| env.unique_symbol())
+            .take(payload_vars.len())
+            .collect();
+        // y11 .. y1n
+        let rhs_payload_syms: Vec<_> = repeat_with(|| env.unique_symbol())
+            .take(payload_vars.len())
+            .collect();
+
+        let comparisons = (payload_vars.iter())
+            .zip(lhs_payload_syms.iter().zip(rhs_payload_syms.iter()))
+            .map(|(&var, (&lhs, &rhs))| (var, Expr::Var(lhs, var), Expr::Var(rhs, var)))
+            .collect();
+        let same_tag_body = compare_in_turn(env, order_var, comparisons);
+
+        let (tags_before, tags_after) = (&tags[..tag_index], &tags[tag_index + 1..]);
+        let mut rhs_branches = Vec::with_capacity(3);
+
+        if !tags_before.is_empty() {
+            rhs_branches.push(when_branch(
+                tags_before
+                    .iter()
+                    .map(|(other_tag, other_vars)| {
+                        any_payload_pattern(union_var, other_tag, other_vars)
+                    })
+                    .collect(),
+                order_tag(env, order_var, "GT"),
+            ));
+        }
+
+        rhs_branches.push(when_branch(
+            vec![payload_pattern(
+                union_var,
+                tag_name,
+                payload_vars,
+                &rhs_payload_syms,
+            )],
+            same_tag_body,
+        ));
+
+        if !tags_after.is_empty() {
+            rhs_branches.push(when_branch(
+                tags_after
+                    .iter()
+                    .map(|(other_tag, other_vars)| {
+                        any_payload_pattern(union_var, other_tag, other_vars)
+                    })
+                    .collect(),
+                order_tag(env, order_var, "LT"),
+            ));
+        }
+
+        // when rhs is
+        //   ...
+        let rhs_when = Expr::When {
+            loc_cond: Box::new(Loc::at_zero(Expr::Var(rhs_sym, union_var))),
+            cond_var: union_var,
+            expr_var: order_var,
+            region: Region::zero(),
+            branches: rhs_branches,
+            branches_cond_var: union_var,
+            exhaustive: ExhaustiveMark::known_exhaustive(),
+        };
+
+        branches.push(when_branch(
+            vec![payload_pattern(
+                union_var,
+                tag_name,
+                payload_vars,
+                &lhs_payload_syms,
+            )],
+            rhs_when,
+        ));
+    }
+
+    // when lhs is
+    //   ...
+    let when_expr = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(lhs_sym, union_var))),
+        cond_var: union_var,
+        expr_var: order_var,
+        region: Region::zero(),
+        branches,
+        branches_cond_var: union_var,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    // Finally, build the closure
+    // \lhs, rhs -> body
+    build_outer_derived_closure(
+        env,
+        fn_name,
+        (union_var, lhs_sym, rhs_sym),
+        (order_var, when_expr),
+    )
+}
+
+/// `[EQ, GT, LT]`, the result of a comparison.
+fn order_var(env: &mut Env<'_>) -> Variable {
+    let union_tags = UnionTags::insert_slices_into_subs(
+        env.subs,
+        [
+            ("EQ".into(), VariableSubsSlice::default()),
+            ("GT".into(), VariableSubsSlice::default()),
+            ("LT".into(), VariableSubsSlice::default()),
+        ],
+    );
+
+    synth_var(
+        env.subs,
+        Content::Structure(FlatType::TagUnion(
+            union_tags,
+            TagExt::Any(Variable::EMPTY_TAG_UNION),
+        )),
+    )
+}
+
+fn order_tag(env: &mut Env<'_>, order_var: Variable, order: &str) -> Expr {
+    Expr::Tag {
+        tag_union_var: order_var,
+        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+        name: order.into(),
+        arguments: Vec::new(),
+    }
+}
+
+/// Compare each pair of values in turn, returning the first result that isn't `EQ`:
+///
+///   when Sort.compare lhs1 rhs1 is
+///     EQ ->
+///       ...
+///         Sort.compare lhsn rhsn
+///     order -> order
+///
+/// If there's nothing to compare, this is just `EQ`.
+fn compare_in_turn(
+    env: &mut Env<'_>,
+    order_var: Variable,
+    comparisons: Vec<(Variable, Expr, Expr)>,
+) -> Expr {
+    let mut body = None;
+
+    for (var, lhs, rhs) in comparisons.into_iter().rev() {
+        let compare = call_sort_compare(env, order_var, (var, lhs), (var, rhs));
+
+        body = Some(match body {
+            None => compare,
+            Some(rest) => {
+                let order_sym = env.new_symbol("order");
+
+                Expr::When {
+                    loc_cond: Box::new(Loc::at_zero(compare)),
+                    cond_var: order_var,
+                    expr_var: order_var,
+                    region: Region::zero(),
+                    branches: vec![
+                        // EQ -> rest
+                        when_branch(
+                            vec![Pattern::AppliedTag {
+                                whole_var: order_var,
+                                tag_name: "EQ".into(),
+                                ext_var: Variable::EMPTY_TAG_UNION,
+                                arguments: Vec::new(),
+                            }],
+                            rest,
+                        ),
+                        // order -> order
+                        when_branch(
+                            vec![Pattern::Identifier(order_sym)],
+                            Expr::Var(order_sym, order_var),
+                        ),
+                    ],
+                    branches_cond_var: order_var,
+                    exhaustive: ExhaustiveMark::known_exhaustive(),
+                }
+            }
+        });
+    }
+
+    match body {
+        Some(body) => body,
+        None => order_tag(env, order_var, "EQ"),
+    }
+}
+
+fn call_sort_compare(
+    env: &mut Env<'_>,
+    order_var: Variable,
+    lhs: (Variable, Expr),
+    rhs: (Variable, Expr),
+) -> Expr {
+    let (lhs_var, lhs_expr) = lhs;
+    let (rhs_var, rhs_expr) = rhs;
+
+    // build `Sort.compare ...` function type.
+    //
+    // a, a -[uls]-> [LT, EQ, GT] | a has Sort
+    let exposed_compare_fn_var = env.import_builtin_symbol_var(Symbol::SORT_COMPARE);
+
+    // (typeof lhs), (typeof rhs) -[clos]-> [LT, EQ, GT]
+    let this_arguments_slice = VariableSubsSlice::insert_into_subs(env.subs, [lhs_var, rhs_var]);
+    let this_compare_clos_var = env.subs.fresh_unnamed_flex_var();
+    let this_compare_fn_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::Func(
+            this_arguments_slice,
+            this_compare_clos_var,
+            order_var,
+        )),
+    );
+
+    //   a,             a              -[uls]->  [LT, EQ, GT] | a has Sort
+    // ~ (typeof lhs), (typeof rhs)    -[clos]-> [LT, EQ, GT]
+    env.unify(exposed_compare_fn_var, this_compare_fn_var);
+
+    // Sort.compare : (typeof lhs), (typeof rhs) -[clos]-> [LT, EQ, GT] | (typeof lhs) has Sort
+    let compare_fn_head = Expr::AbilityMember(Symbol::SORT_COMPARE, None, this_compare_fn_var);
+    let compare_fn_data = Box::new((
+        this_compare_fn_var,
+        Loc::at_zero(compare_fn_head),
+        this_compare_clos_var,
+        order_var,
+    ));
+
+    let compare_arguments = vec![
+        (lhs_var, Loc::at_zero(lhs_expr)),
+        (rhs_var, Loc::at_zero(rhs_expr)),
+    ];
+
+    Expr::Call(compare_fn_data, compare_arguments, CalledVia::Space)
+}
+
+fn when_branch(patterns: Vec<Pattern>, value: Expr) -> WhenBranch {
+    WhenBranch {
+        patterns: patterns
+            .into_iter()
+            .map(|pattern| WhenBranchPattern {
+                pattern: Loc::at_zero(pattern),
+                degenerate: false,
+            })
+            .collect(),
+        value: Loc::at_zero(value),
+        guard: None,
+        redundant: RedundantMark::known_non_redundant(),
+    }
+}
+
+/// `A x1 .. xn`
+fn payload_pattern(
+    union_var: Variable,
+    tag_name: &TagName,
+    payload_vars: &[Variable],
+    payload_syms: &[Symbol],
+) -> Pattern {
+    Pattern::AppliedTag {
+        whole_var: union_var,
+        tag_name: tag_name.clone(),
+        ext_var: Variable::EMPTY_TAG_UNION,
+        arguments: (payload_vars.iter())
+            .zip(payload_syms.iter())
+            .map(|(var, sym)| (*var, Loc::at_zero(Pattern::Identifier(*sym))))
+            .collect(),
+    }
+}
+
+/// `A _ .. _`
+fn any_payload_pattern(
+    union_var: Variable,
+    tag_name: &TagName,
+    payload_vars: &[Variable],
+) -> Pattern {
+    Pattern::AppliedTag {
+        whole_var: union_var,
+        tag_name: tag_name.clone(),
+        ext_var: Variable::EMPTY_TAG_UNION,
+        arguments: (payload_vars.iter())
+            .map(|var| (*var, Loc::at_zero(Pattern::Underscore)))
+            .collect(),
+    }
+}
+
+fn build_outer_derived_closure(
+    env: &mut Env<'_>,
+    fn_name: Symbol,
+    args: (Variable, Symbol, Symbol),
+    body: (Variable, Expr),
+) -> (Variable, Expr) {
+    let (val_var, lhs_sym, rhs_sym) = args;
+    let (body_var, body_expr) = body;
+
+    let (fn_var, fn_clos_var) = {
+        // Create fn_var for ambient capture; we fix it up below.
+        let fn_var = synth_var(env.subs, Content::Error);
+
+        // -[fn_name]->
+        let fn_captures = vec![];
+        let fn_name_labels = UnionLambdas::insert_into_subs(env.subs, once((fn_name, fn_captures)));
+        let fn_clos_var = synth_var(
+            env.subs,
+            Content::LambdaSet(LambdaSet {
+                solved: fn_name_labels,
+                recursion_var: OptVariable::NONE,
+                unspecialized: SubsSlice::default(),
+                ambient_function: fn_var,
+            }),
+        );
+
+        // val_var, val_var -[fn_name]-> (order = body_var)
+        let args_slice = SubsSlice::insert_into_subs(env.subs, [val_var, val_var]);
+        env.subs.set_content(
+            fn_var,
+            Content::Structure(FlatType::Func(args_slice, fn_clos_var, body_var)),
+        );
+
+        (fn_var, fn_clos_var)
+    };
+
+    let clos_expr = Expr::Closure(ClosureData {
+        function_type: fn_var,
+        closure_type: fn_clos_var,
+        return_type: body_var,
+        name: fn_name,
+        captured_symbols: vec![],
+        recursive: Recursive::NotRecursive,
+        arguments: vec![
+            (
+                val_var,
+                AnnotatedMark::known_exhaustive(),
+                Loc::at_zero(Pattern::Identifier(lhs_sym)),
+            ),
+            (
+                val_var,
+                AnnotatedMark::known_exhaustive(),
+                Loc::at_zero(Pattern::Identifier(rhs_sym)),
+            ),
+        ],
+        loc_body: Box::new(Loc::at_zero(body_expr)),
+    });
+
+    (fn_var, clos_expr)
+}
@@ -16,6 +16,7 @@ use crate::{synth_var, DerivedBody};
 
 mod list;
 mod record;
+mod tag_union;
 mod tuple;
 
 pub(crate) fn derive_decoder(
@@ -25,8 +26,11 @@ pub(crate) fn derive_decoder(
 ) -> DerivedBody {
     let (body, body_type) = match key {
         FlatDecodableKey::List() => list::decoder(env, def_symbol),
+        FlatDecodableKey::Set() => list::set_decoder(env, def_symbol),
+        FlatDecodableKey::Dict() => list::dict_decoder(env, def_symbol),
         FlatDecodableKey::Record(fields) => record::decoder(env, def_symbol, fields),
         FlatDecodableKey::Tuple(arity) => tuple::decoder(env, def_symbol, arity),
+        FlatDecodableKey::TagUnion(tags) => tag_union::decoder(env, def_symbol, tags),
     };
 
     let specialization_lambda_sets =
@@ -48,63 +52,150 @@ fn wrap_in_decode_custom_decode_with(
     fmt: (Symbol, Variable),
     sorted_inner_decoder_captures: Vec<(Symbol, Variable)>,
     inner_decoder: (Expr, Variable),
+) -> (Expr, Variable) {
+    let decode_with = decode_with(env, bytes, fmt, inner_decoder);
+
+    wrap_in_decode_custom(env, bytes, fmt, sorted_inner_decoder_captures, decode_with)
+}
+
+// Wraps `myDecoder` in
+// `Decode.custom \bytes, fmt -> Decode.mapResult (Decode.decodeWith bytes myDecoder fmt) mapper`,
+// where `mapper` is a builtin function converting the decoded value.
+fn wrap_in_decode_custom_map_result(
+    env: &mut Env,
+    bytes: Symbol,
+    fmt: (Symbol, Variable),
+    sorted_inner_decoder_captures: Vec<(Symbol, Variable)>,
+    inner_decoder: (Expr, Variable),
+    mapper: Symbol,
 ) -> (Expr, Variable) {
     use Expr::*;
 
-    debug_assert!({
-        let mut sorted = sorted_inner_decoder_captures.clone();
-        sorted.sort_by_key(|(sym, _)| *sym);
-        sorted == sorted_inner_decoder_captures
-    });
+    let (decode_with_call, decode_with_result_var) = decode_with(env, bytes, fmt, inner_decoder);
 
-    let (bytes_sym, bytes_var) = (bytes, Variable::LIST_U8);
-    let (fmt_sym, fmt_var) = fmt;
-    let (inner_decoder, inner_decoder_var) = inner_decoder;
+    // Decode.mapResult (Decode.decodeWith bytes inner_decoder fmt) mapper : DecodeResult b
+    let map_result = {
+        // mapper : a -> b
+        let mapper_var = env.import_builtin_symbol_var(mapper);
 
-    // Decode.decodeWith bytes inner_decoder fmt : DecodeResult val
-    let (decode_with_call, decode_with_result_var) = {
-        // Decode.decodeWith : List U8, Decoder val fmt, fmt -> DecodeResult val | fmt has DecoderFormatting
-        let decode_with_type = env.import_builtin_symbol_var(Symbol::DECODE_DECODE_WITH);
-
-        // Decode.decodeWith : bytes, inner_decoder, fmt -> DecoderResult (List val)
-        let this_decode_with_var_slice =
-            SubsSlice::insert_into_subs(env.subs, [bytes_var, inner_decoder_var, fmt_var]);
-        let this_decode_with_clos_var = env.subs.fresh_unnamed_flex_var();
-        let this_decode_with_ret_var = env.subs.fresh_unnamed_flex_var();
-        let this_decode_with_fn_var = synth_var(
+        // Decode.mapResult : DecodeResult a, (a -> b) -> DecodeResult b
+        let map_result_type = env.import_builtin_symbol_var(Symbol::DECODE_MAP_RESULT);
+
+        // Decode.mapResult : DecodeResult a, mapper -> c
+        let this_map_result_var_slice =
+            SubsSlice::insert_into_subs(env.subs, [decode_with_result_var, mapper_var]);
+        let this_map_result_clos_var = env.subs.fresh_unnamed_flex_var();
+        let this_map_result_ret_var = env.subs.fresh_unnamed_flex_var();
+        let this_map_result_fn_var = synth_var(
             env.subs,
             Content::Structure(FlatType::Func(
-                this_decode_with_var_slice,
-                this_decode_with_clos_var,
-                this_decode_with_ret_var,
+                this_map_result_var_slice,
+                this_map_result_clos_var,
+                this_map_result_ret_var,
             )),
         );
 
-        //   List U8, Decoder val fmt,         fmt -> DecodeResult val | fmt has DecoderFormatting
-        // ~ bytes,   Decoder (List elem) fmt, fmt -> DecoderResult (List val)
-        env.unify(decode_with_type, this_decode_with_fn_var);
+        //   DecodeResult a, (a -> b) -> DecodeResult b
+        // ~ DecodeResult a, mapper   -> c
+        env.unify(map_result_type, this_map_result_fn_var);
 
-        let decode_with_var = Var(Symbol::DECODE_DECODE_WITH, this_decode_with_fn_var);
-        let decode_with_fn = Box::new((
-            this_decode_with_fn_var,
-            Loc::at_zero(decode_with_var),
-            this_decode_with_clos_var,
-            this_decode_with_ret_var,
+        let map_result_var = Var(Symbol::DECODE_MAP_RESULT, this_map_result_fn_var);
+        let map_result_fn = Box::new((
+            this_map_result_fn_var,
+            Loc::at_zero(map_result_var),
+            this_map_result_clos_var,
+            this_map_result_ret_var,
         ));
-        let decode_with_call = Call(
-            decode_with_fn,
+        let map_result_call = Call(
+            map_result_fn,
             vec![
-                // bytes inner_decoder fmt
-                (bytes_var, Loc::at_zero(Var(bytes_sym, bytes_var))),
-                (inner_decoder_var, Loc::at_zero(inner_decoder)),
-                (fmt_var, Loc::at_zero(Var(fmt_sym, fmt_var))),
+                (decode_with_result_var, Loc::at_zero(decode_with_call)),
+                (mapper_var, Loc::at_zero(Var(mapper, mapper_var))),
             ],
             CalledVia::Space,
         );
 
-        (decode_with_call, this_decode_with_ret_var)
+        (map_result_call, this_map_result_ret_var)
     };
 
+    wrap_in_decode_custom(env, bytes, fmt, sorted_inner_decoder_captures, map_result)
+}
+
+// Builds `Decode.decodeWith bytes myDecoder fmt`.
+fn decode_with(
+    env: &mut Env,
+    bytes: Symbol,
+    fmt: (Symbol, Variable),
+    inner_decoder: (Expr, Variable),
+) -> (Expr, Variable) {
+    use Expr::*;
+
+    let (bytes_sym, bytes_var) = (bytes, Variable::LIST_U8);
+    let (fmt_sym, fmt_var) = fmt;
+    let (inner_decoder, inner_decoder_var) = inner_decoder;
+
+    // Decode.decodeWith : List U8, Decoder val fmt, fmt -> DecodeResult val | fmt has DecoderFormatting
+    let decode_with_type = env.import_builtin_symbol_var(Symbol::DECODE_DECODE_WITH);
+
+    // Decode.decodeWith : bytes, inner_decoder, fmt -> DecoderResult (List val)
+    let this_decode_with_var_slice =
+        SubsSlice::insert_into_subs(env.subs, [bytes_var, inner_decoder_var, fmt_var]);
+    let this_decode_with_clos_var = env.subs.fresh_unnamed_flex_var();
+    let this_decode_with_ret_var = env.subs.fresh_unnamed_flex_var();
+    let this_decode_with_fn_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::Func(
+            this_decode_with_var_slice,
+            this_decode_with_clos_var,
+            this_decode_with_ret_var,
+        )),
+    );
+
+    //   List U8, Decoder val fmt,         fmt -> DecodeResult val | fmt has DecoderFormatting
+    // ~ bytes,   Decoder (List elem) fmt, fmt -> DecoderResult (List val)
+    env.unify(decode_with_type, this_decode_with_fn_var);
+
+    let decode_with_var = Var(Symbol::DECODE_DECODE_WITH, this_decode_with_fn_var);
+    let decode_with_fn = Box::new((
+        this_decode_with_fn_var,
+        Loc::at_zero(decode_with_var),
+        this_decode_with_clos_var,
+        this_decode_with_ret_var,
+    ));
+    let decode_with_call = Call(
+        decode_with_fn,
+        vec![
+            // bytes inner_decoder fmt
+            (bytes_var, Loc::at_zero(Var(bytes_sym, bytes_var))),
+            (inner_decoder_var, Loc::at_zero(inner_decoder)),
+            (fmt_var, Loc::at_zero(Var(fmt_sym, fmt_var))),
+        ],
+        CalledVia::Space,
+    );
+
+    (decode_with_call, this_decode_with_ret_var)
+}
+
+// Wraps `body` in `Decode.custom \bytes, fmt -> body`.
+fn wrap_in_decode_custom(
+    env: &mut Env,
+    bytes: Symbol,
+    fmt: (Symbol, Variable),
+    sorted_inner_decoder_captures: Vec<(Symbol, Variable)>,
+    body: (Expr, Variable),
+) -> (Expr, Variable) {
+    use Expr::*;
+
+    debug_assert!({
+        let mut sorted = sorted_inner_decoder_captures.clone();
+        sorted.sort_by_key(|(sym, _)| *sym);
+        sorted == sorted_inner_decoder_captures
+    });
+
+    let (bytes_sym, bytes_var) = (bytes, Variable::LIST_U8);
+    let (fmt_sym, fmt_var) = fmt;
+    let (decode_with_call, decode_with_result_var) = body;
+
     // \bytes, fmt -> Decode.decodeWith bytes myDecoder fmt
     let (custom_lambda, custom_var) = {
         let fn_name = env.new_symbol("custom");
@@ -8,7 +8,8 @@ use roc_region::all::Loc;
 use roc_types::subs::{Content, FlatType, GetSubsSlice, SubsSlice, Variable};
 use roc_types::types::AliasKind;
 
-use crate::decoding::wrap_in_decode_custom_decode_with;
+use crate::decoding::tuple::{decode_tuple, tuple_value};
+use crate::decoding::{wrap_in_decode_custom_decode_with, wrap_in_decode_custom_map_result};
 use crate::synth_var;
 use crate::util::Env;
 
@@ -20,74 +21,12 @@ pub(crate) fn decoder(env: &mut Env<'_>, _def_symbol: Symbol) -> (Expr, Variable
     //
     // NB: reduction to `Decode.list Decode.decoder` is not possible to the HRR.
 
-    use Expr::*;
+    // List elem
+    let elem_var = env.subs.fresh_unnamed_flex_var();
 
     // Decode.list Decode.decoder : Decoder (List elem) fmt
-    let (decode_list_call, this_decode_list_ret_var) = {
-        // List elem
-        let elem_var = env.subs.fresh_unnamed_flex_var();
-
-        // Decode.decoder : Decoder elem fmt | elem has Decoding, fmt has EncoderFormatting
-        let (elem_decoder, elem_decoder_var) = {
-            // build `Decode.decoder : Decoder elem fmt` type
-            // Decoder val fmt | val has Decoding, fmt has EncoderFormatting
-            let elem_decoder_var = env.import_builtin_symbol_var(Symbol::DECODE_DECODER);
-
-            // set val ~ elem
-            let val_var = match env.subs.get_content_without_compacting(elem_decoder_var) {
-                Content::Alias(Symbol::DECODE_DECODER_OPAQUE, vars, _, AliasKind::Opaque)
-                    if vars.type_variables_len == 2 =>
-                {
-                    env.subs.get_subs_slice(vars.type_variables())[0]
-                }
-                _ => internal_error!("Decode.decode not an opaque type"),
-            };
-
-            env.unify(val_var, elem_var);
-
-            (
-                AbilityMember(Symbol::DECODE_DECODER, None, elem_decoder_var),
-                elem_decoder_var,
-            )
-        };
-
-        // Build `Decode.list Decode.decoder` type
-        // Decoder val fmt -[uls]-> Decoder (List val) fmt | fmt has DecoderFormatting
-        let decode_list_fn_var = env.import_builtin_symbol_var(Symbol::DECODE_LIST);
-
-        // Decoder elem fmt -a-> b
-        let elem_decoder_var_slice = SubsSlice::insert_into_subs(env.subs, [elem_decoder_var]);
-        let this_decode_list_clos_var = env.subs.fresh_unnamed_flex_var();
-        let this_decode_list_ret_var = env.subs.fresh_unnamed_flex_var();
-        let this_decode_list_fn_var = synth_var(
-            env.subs,
-            Content::Structure(FlatType::Func(
-                elem_decoder_var_slice,
-                this_decode_list_clos_var,
-                this_decode_list_ret_var,
-            )),
-        );
-
-        //   Decoder val  fmt -[uls]-> Decoder (List val) fmt | fmt has DecoderFormatting
-        // ~ Decoder elem fmt -a    -> b
-        env.unify(decode_list_fn_var, this_decode_list_fn_var);
-
-        let decode_list_member = AbilityMember(Symbol::DECODE_LIST, None, this_decode_list_fn_var);
-        let decode_list_fn = Box::new((
-            decode_list_fn_var,
-            Loc::at_zero(decode_list_member),
-            this_decode_list_clos_var,
-            this_decode_list_ret_var,
-        ));
-
-        let decode_list_call = Call(
-            decode_list_fn,
-            vec![(elem_decoder_var, Loc::at_zero(elem_decoder))],
-            CalledVia::Space,
-        );
-
-        (decode_list_call, this_decode_list_ret_var)
-    };
+    let elem_decoder = decode_decoder(env, elem_var);
+    let (decode_list_call, this_decode_list_ret_var) = decode_list(env, elem_decoder);
 
     let bytes_sym = env.new_symbol("bytes");
     let fmt_sym = env.new_symbol("fmt");
@@ -102,3 +41,130 @@ pub(crate) fn decoder(env: &mut Env<'_>, _def_symbol: Symbol) -> (Expr, Variable
         (decode_list_call, this_decode_list_ret_var),
     )
 }
+
+pub(crate) fn set_decoder(env: &mut Env<'_>, _def_symbol: Symbol) -> (Expr, Variable) {
+    // Build
+    //
+    //   def_symbol : Decoder (Set elem) fmt | elem has Decoding & Hash & Eq, fmt has DecoderFormatting
+    //   def_symbol = Decode.custom \bytes, fmt -> Decode.mapResult (Decode.decodeWith bytes (Decode.list Decode.decoder) fmt) Set.fromList
+
+    // Set elem
+    let elem_var = env.subs.fresh_unnamed_flex_var();
+
+    // Decode.list Decode.decoder : Decoder (List elem) fmt
+    let elem_decoder = decode_decoder(env, elem_var);
+    let decode_list = decode_list(env, elem_decoder);
+
+    let bytes_sym = env.new_symbol("bytes");
+    let fmt_sym = env.new_symbol("fmt");
+    let fmt_var = env.subs.fresh_unnamed_flex_var();
+
+    wrap_in_decode_custom_map_result(
+        env,
+        bytes_sym,
+        (fmt_sym, fmt_var),
+        vec![],
+        decode_list,
+        Symbol::SET_FROM_LIST,
+    )
+}
+
+pub(crate) fn dict_decoder(env: &mut Env<'_>, _def_symbol: Symbol) -> (Expr, Variable) {
+    // Build
+    //
+    //   def_symbol : Decoder (Dict k v) fmt | k has Decoding & Hash & Eq, v has Decoding, fmt has DecoderFormatting
+    //   def_symbol = Decode.custom \bytes, fmt -> Decode.mapResult (Decode.decodeWith bytes (Decode.list <decode (k, v)>) fmt) Dict.fromList
+    //
+    // where <decode (k, v)> decodes each entry like a derived tuple decoder.
+
+    // (k, v)
+    let index_vars = vec![
+        env.subs.fresh_unnamed_flex_var(),
+        env.subs.fresh_unnamed_flex_var(),
+    ];
+
+    // Decode.list (Decode.tuple initialState stepElem finalizer) : Decoder (List (k, v)) fmt
+    let entry_decoder = decode_tuple(env, index_vars, tuple_value);
+    let decode_list = decode_list(env, entry_decoder);
+
+    let bytes_sym = env.new_symbol("bytes");
+    let fmt_sym = env.new_symbol("fmt");
+    let fmt_var = env.subs.fresh_unnamed_flex_var();
+
+    wrap_in_decode_custom_map_result(
+        env,
+        bytes_sym,
+        (fmt_sym, fmt_var),
+        vec![],
+        decode_list,
+        Symbol::DICT_FROM_LIST,
+    )
+}
+
+// Build `Decode.decoder : Decoder elem fmt | elem has Decoding, fmt has DecoderFormatting`
+fn decode_decoder(env: &mut Env<'_>, elem_var: Variable) -> (Expr, Variable) {
+    // build `Decode.decoder : Decoder elem fmt` type
+    // Decoder val fmt | val has Decoding, fmt has EncoderFormatting
+    let elem_decoder_var = env.import_builtin_symbol_var(Symbol::DECODE_DECODER);
+
+    // set val ~ elem
+    let val_var = match env.subs.get_content_without_compacting(elem_decoder_var) {
+        Content::Alias(Symbol::DECODE_DECODER_OPAQUE, vars, _, AliasKind::Opaque)
+            if vars.type_variables_len == 2 =>
+        {
+            env.subs.get_subs_slice(vars.type_variables())[0]
+        }
+        _ => internal_error!("Decode.decode not an opaque type"),
+    };
+
+    env.unify(val_var, elem_var);
+
+    (
+        Expr::AbilityMember(Symbol::DECODE_DECODER, None, elem_decoder_var),
+        elem_decoder_var,
+    )
+}
+
+// Build `Decode.list elem_decoder : Decoder (List elem) fmt`
+fn decode_list(env: &mut Env<'_>, elem_decoder: (Expr, Variable)) -> (Expr, Variable) {
+    use Expr::*;
+
+    let (elem_decoder, elem_decoder_var) = elem_decoder;
+
+    // Build `Decode.list Decode.decoder` type
+    // Decoder val fmt -[uls]-> Decoder (List val) fmt | fmt has DecoderFormatting
+    let decode_list_fn_var = env.import_builtin_symbol_var(Symbol::DECODE_LIST);
+
+    // Decoder elem fmt -a-> b
+    let elem_decoder_var_slice = SubsSlice::insert_into_subs(env.subs, [elem_decoder_var]);
+    let this_decode_list_clos_var = env.subs.fresh_unnamed_flex_var();
+    let this_decode_list_ret_var = env.subs.fresh_unnamed_flex_var();
+    let this_decode_list_fn_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::Func(
+            elem_decoder_var_slice,
+            this_decode_list_clos_var,
+            this_decode_list_ret_var,
+        )),
+    );
+
+    //   Decoder val  fmt -[uls]-> Decoder (List val) fmt | fmt has DecoderFormatting
+    // ~ Decoder elem fmt -a    -> b
+    env.unify(decode_list_fn_var, this_decode_list_fn_var);
+
+    let decode_list_member = AbilityMember(Symbol::DECODE_LIST, None, this_decode_list_fn_var);
+    let decode_list_fn = Box::new((
+        decode_list_fn_var,
+        Loc::at_zero(decode_list_member),
+        this_decode_list_clos_var,
+        this_decode_list_ret_var,
+    ));
+
+    let decode_list_call = Call(
+        decode_list_fn,
+        vec![(elem_decoder_var, Loc::at_zero(elem_decoder))],
+        CalledVia::Space,
+    );
+
+    (decode_list_call, this_decode_list_ret_var)
+}
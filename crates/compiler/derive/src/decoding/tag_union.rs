@@ -0,0 +1,255 @@
+use roc_can::expr::{AnnotatedMark, ClosureData, Expr, Recursive, WhenBranch, WhenBranchPattern};
+use roc_can::pattern::Pattern;
+use roc_module::called_via::CalledVia;
+use roc_module::ident::TagName;
+use roc_module::symbol::Symbol;
+use roc_region::all::{Loc, Region};
+use roc_types::subs::{
+    Content, ExhaustiveMark, FlatType, GetSubsSlice, LambdaSet, OptVariable, RedundantMark,
+    SubsSlice, TagExt, UnionLambdas, UnionTags, Variable, VariableSubsSlice,
+};
+
+use crate::synth_var;
+use crate::util::{Env, ExtensionKind};
+
+use super::tuple::decode_tuple;
+use super::wrap_in_decode_custom_decode_with;
+
+/// Implements decoding of a tag union. Decoding a tag union `[A t1 t2, B t3]` is like:
+///
+/// ```roc
+/// decoder : Decoder [A t1 t2, B t3] fmt | t1 has Decoding, t2 has Decoding, t3 has Decoding, fmt has DecoderFormatting
+/// decoder =
+///     stepTag = \name ->
+///         when name is
+///             "A" -> Keep (Decode.tuple ... ) # decodes (t1, t2) and finalizes to `A e0 e1`
+///             "B" -> Keep (Decode.tuple ... ) # decodes (t3) and finalizes to `B e0`
+///             _ -> Skip
+///
+///     Decode.custom \bytes, fmt -> Decode.decodeWith bytes (Decode.tag stepTag) fmt
+/// ```
+///
+/// The payload of each tag is decoded the same way as a tuple, matching how tags are encoded.
+pub(crate) fn decoder(
+    env: &mut Env,
+    _def_symbol: Symbol,
+    tags: Vec<(TagName, u16)>,
+) -> (Expr, Variable) {
+    // Generalized tag union var so we can reuse this impl between many unions:
+    // if tags = [ A arity=2, B arity=1 ], this is [ A t1 t2, B t3 ] for fresh t1, t2, t3
+    let flex_tag_labels = tags
+        .into_iter()
+        .map(|(label, arity)| {
+            let variables_slice = VariableSubsSlice::reserve_into_subs(env.subs, arity.into());
+            for var_index in variables_slice {
+                env.subs[var_index] = env.subs.fresh_unnamed_flex_var();
+            }
+            (label, variables_slice)
+        })
+        .collect::<Vec<_>>();
+    let union_tags = UnionTags::insert_slices_into_subs(env.subs, flex_tag_labels.clone());
+    let tag_union_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::TagUnion(
+            union_tags,
+            TagExt::Any(Variable::EMPTY_TAG_UNION),
+        )),
+    );
+
+    // stepTag = ...
+    let (step_tag, step_tag_var) = step_tag(env, tag_union_var, flex_tag_labels);
+
+    // Decode.tag stepTag
+    let (call_decode_tag, tag_decoder_var) = {
+        let tag_decoder_var = env.subs.fresh_unnamed_flex_var();
+        let decode_tag_lambda_set = env.subs.fresh_unnamed_flex_var();
+        let decode_tag_var = env.import_builtin_symbol_var(Symbol::DECODE_TAG);
+        let this_decode_tag_var = {
+            let flat_type = FlatType::Func(
+                SubsSlice::insert_into_subs(env.subs, [step_tag_var]),
+                decode_tag_lambda_set,
+                tag_decoder_var,
+            );
+
+            synth_var(env.subs, Content::Structure(flat_type))
+        };
+
+        env.unify(decode_tag_var, this_decode_tag_var);
+
+        let call_decode_tag = Expr::Call(
+            Box::new((
+                this_decode_tag_var,
+                Loc::at_zero(Expr::AbilityMember(
+                    Symbol::DECODE_TAG,
+                    None,
+                    this_decode_tag_var,
+                )),
+                decode_tag_lambda_set,
+                tag_decoder_var,
+            )),
+            vec![(step_tag_var, Loc::at_zero(step_tag))],
+            CalledVia::Space,
+        );
+
+        (call_decode_tag, tag_decoder_var)
+    };
+
+    let bytes_sym = env.new_symbol("bytes");
+    let fmt_sym = env.new_symbol("fmt");
+    let fmt_var = env.subs.fresh_unnamed_flex_var();
+
+    wrap_in_decode_custom_decode_with(
+        env,
+        bytes_sym,
+        (fmt_sym, fmt_var),
+        vec![],
+        (call_decode_tag, tag_decoder_var),
+    )
+}
+
+// Example:
+// stepTag = \name ->
+//     when name is
+//         "A" -> Keep (Decode.tuple ... ) # finalizes to `A e0 e1`
+//         "B" -> Keep (Decode.tuple ... ) # finalizes to `B e0`
+//         _ -> Skip
+fn step_tag(
+    env: &mut Env,
+    tag_union_var: Variable,
+    tags: Vec<(TagName, VariableSubsSlice)>,
+) -> (Expr, Variable) {
+    let name_arg_symbol = env.new_symbol("name");
+
+    // +1 because of the default branch.
+    let mut branches = Vec::with_capacity(tags.len() + 1);
+    let keep_payload_var = env.subs.fresh_unnamed_flex_var();
+    let keep_or_skip_var = {
+        let keep_payload_subs_slice = SubsSlice::insert_into_subs(env.subs, [keep_payload_var]);
+        let flat_type = FlatType::TagUnion(
+            UnionTags::insert_slices_into_subs(
+                env.subs,
+                [
+                    ("Keep".into(), keep_payload_subs_slice),
+                    ("Skip".into(), Default::default()),
+                ],
+            ),
+            TagExt::Any(Variable::EMPTY_TAG_UNION),
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    for (tag_name, payload_vars) in tags {
+        let payload_vars = env.subs.get_subs_slice(payload_vars).to_vec();
+
+        // Decode.tuple initialState stepElem finalizer, finalizing to `A e0 e1`
+        let (decode_payload, payload_decoder_var) = {
+            let tag_name = tag_name.clone();
+
+            decode_tuple(env, payload_vars, move |env, elems| {
+                let tag = Expr::Tag {
+                    tag_union_var,
+                    ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                    name: tag_name,
+                    arguments: elems
+                        .iter()
+                        .map(|&(var, symbol)| (var, Loc::at_zero(Expr::Var(symbol, var))))
+                        .collect(),
+                };
+
+                (tag, tag_union_var)
+            })
+        };
+
+        env.unify(keep_payload_var, payload_decoder_var);
+
+        // Keep (Decode.tuple initialState stepElem finalizer)
+        let keep = Expr::Tag {
+            tag_union_var: keep_or_skip_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: "Keep".into(),
+            arguments: vec![(payload_decoder_var, Loc::at_zero(decode_payload))],
+        };
+
+        // "A" -> Keep (Decode.tuple initialState stepElem finalizer)
+        let branch = WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(Pattern::StrLiteral(tag_name.0.as_str().into())),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(keep),
+            guard: None,
+            redundant: RedundantMark::known_non_redundant(),
+        };
+
+        branches.push(branch);
+    }
+
+    // Example: `_ -> Skip`
+    let default_branch = WhenBranch {
+        patterns: vec![WhenBranchPattern {
+            pattern: Loc::at_zero(Pattern::Underscore),
+            degenerate: false,
+        }],
+        value: Loc::at_zero(Expr::Tag {
+            tag_union_var: keep_or_skip_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: "Skip".into(),
+            arguments: Vec::new(),
+        }),
+        guard: None,
+        redundant: RedundantMark::known_non_redundant(),
+    };
+
+    branches.push(default_branch);
+
+    // when name is
+    let body = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(name_arg_symbol, Variable::STR))),
+        cond_var: Variable::STR,
+        expr_var: keep_or_skip_var,
+        region: Region::zero(),
+        branches,
+        branches_cond_var: Variable::STR,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    let step_tag_closure = env.new_symbol("stepTag");
+    let function_type = env.subs.fresh_unnamed_flex_var();
+    let closure_type = {
+        let lambda_set = LambdaSet {
+            solved: UnionLambdas::tag_without_arguments(env.subs, step_tag_closure),
+            recursion_var: OptVariable::NONE,
+            unspecialized: Default::default(),
+            ambient_function: function_type,
+        };
+
+        synth_var(env.subs, Content::LambdaSet(lambda_set))
+    };
+
+    {
+        let args_slice = SubsSlice::insert_into_subs(env.subs, [Variable::STR]);
+
+        env.subs.set_content(
+            function_type,
+            Content::Structure(FlatType::Func(args_slice, closure_type, keep_or_skip_var)),
+        )
+    };
+
+    let expr = Expr::Closure(ClosureData {
+        function_type,
+        closure_type,
+        return_type: keep_or_skip_var,
+        name: step_tag_closure,
+        captured_symbols: Vec::new(),
+        recursive: Recursive::NotRecursive,
+        arguments: vec![(
+            Variable::STR,
+            AnnotatedMark::known_exhaustive(),
+            Loc::at_zero(Pattern::Identifier(name_arg_symbol)),
+        )],
+        loc_body: Box::new(Loc::at_zero(body)),
+    });
+
+    (expr, function_type)
+}
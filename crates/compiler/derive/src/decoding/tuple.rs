@@ -59,19 +59,48 @@ use super::wrap_in_decode_custom_decode_with;
 /// ```
 pub(crate) fn decoder(env: &mut Env, _def_symbol: Symbol, arity: u32) -> (Expr, Variable) {
     // The decoded type of each index in the tuple, e.g. (a, b).
-    let mut index_vars = Vec::with_capacity(arity as _);
+    let index_vars = (0..arity)
+        .map(|_| env.subs.fresh_unnamed_flex_var())
+        .collect();
+
+    // Decode.tuple initialState stepElem finalizer
+    let (call_decode_tuple, tuple_decoder_var) = decode_tuple(env, index_vars, tuple_value);
+
+    let (call_decode_custom, decode_custom_ret_var) = {
+        let bytes_sym = env.new_symbol("bytes");
+        let fmt_sym = env.new_symbol("fmt");
+        let fmt_var = env.subs.fresh_unnamed_flex_var();
+
+        let (decode_custom, decode_custom_var) = wrap_in_decode_custom_decode_with(
+            env,
+            bytes_sym,
+            (fmt_sym, fmt_var),
+            vec![],
+            (call_decode_tuple, tuple_decoder_var),
+        );
+
+        (decode_custom, decode_custom_var)
+    };
+
+    (call_decode_custom, decode_custom_ret_var)
+}
+
+/// Builds `Decode.tuple initialState stepElem finalizer` for elements of the given types, where
+/// the finalizer hands the decoded elements to `finish` to build the decoded value.
+///
+/// Returns the call and the type of the decoder it produces.
+pub(super) fn decode_tuple(
+    env: &mut Env,
+    index_vars: Vec<Variable>,
+    finish: impl FnOnce(&mut Env, &[(Variable, Symbol)]) -> (Expr, Variable),
+) -> (Expr, Variable) {
     // The type of each index in the decoding state, e.g. {e0: Result a [NoElem], e1: Result b [NoElem]}
-    let mut state_fields = Vec::with_capacity(arity as _);
-    let mut state_field_vars = Vec::with_capacity(arity as _);
+    let mut state_fields = Vec::with_capacity(index_vars.len());
+    let mut state_field_vars = Vec::with_capacity(index_vars.len());
 
     // initialState = ...
-    let (state_var, initial_state) = initial_state(
-        env,
-        arity,
-        &mut index_vars,
-        &mut state_fields,
-        &mut state_field_vars,
-    );
+    let (state_var, initial_state) =
+        initial_state(env, &index_vars, &mut state_fields, &mut state_field_vars);
 
     // finalizer = ...
     let (finalizer, finalizer_var, decode_err_var) = finalizer(
@@ -80,6 +109,7 @@ pub(crate) fn decoder(env: &mut Env, _def_symbol: Symbol, arity: u32) -> (Expr,
         state_var,
         &state_fields,
         &state_field_vars,
+        finish,
     );
 
     // stepElem = ...
@@ -128,23 +158,27 @@ pub(crate) fn decoder(env: &mut Env, _def_symbol: Symbol, arity: u32) -> (Expr,
         CalledVia::Space,
     );
 
-    let (call_decode_custom, decode_custom_ret_var) = {
-        let bytes_sym = env.new_symbol("bytes");
-        let fmt_sym = env.new_symbol("fmt");
-        let fmt_var = env.subs.fresh_unnamed_flex_var();
-
-        let (decode_custom, decode_custom_var) = wrap_in_decode_custom_decode_with(
-            env,
-            bytes_sym,
-            (fmt_sym, fmt_var),
-            vec![],
-            (call_decode_record, tuple_decoder_var),
-        );
+    (call_decode_record, tuple_decoder_var)
+}
 
-        (decode_custom, decode_custom_var)
+// Example: `(e0, e1)`
+pub(super) fn tuple_value(env: &mut Env, elems: &[(Variable, Symbol)]) -> (Expr, Variable) {
+    let tuple_indices_iter = elems.iter().map(|(var, _)| *var).enumerate();
+    let flat_type = FlatType::Tuple(
+        TupleElems::insert_into_subs(env.subs, tuple_indices_iter),
+        Variable::EMPTY_TUPLE,
+    );
+    let tuple_var = synth_var(env.subs, Content::Structure(flat_type));
+
+    let tuple = Expr::Tuple {
+        tuple_var,
+        elems: elems
+            .iter()
+            .map(|&(var, symbol)| (var, Box::new(Loc::at_zero(Expr::Var(symbol, var)))))
+            .collect(),
     };
 
-    (call_decode_custom, decode_custom_ret_var)
+    (tuple, tuple_var)
 }
 
 // Example:
@@ -746,9 +780,9 @@ fn finalizer(
     state_record_var: Variable,
     state_fields: &[Lowercase],
     state_field_vars: &[Variable],
+    finish: impl FnOnce(&mut Env, &[(Variable, Symbol)]) -> (Expr, Variable),
 ) -> (Expr, Variable, Variable) {
     let state_arg_symbol = env.new_symbol("stateRecord");
-    let mut tuple_elems = Vec::with_capacity(index_vars.len());
     let mut pattern_symbols = Vec::with_capacity(index_vars.len());
     let decode_err_var = {
         let flat_type = FlatType::TagUnion(
@@ -759,46 +793,35 @@ fn finalizer(
         synth_var(env.subs, Content::Structure(flat_type))
     };
 
-    for (i, &index_var) in index_vars.iter().enumerate() {
-        let symbol = env.new_symbol(i);
-
-        pattern_symbols.push(symbol);
-
-        let index_expr = Expr::Var(symbol, index_var);
-
-        tuple_elems.push((index_var, Box::new(Loc::at_zero(index_expr))));
+    for i in 0..index_vars.len() {
+        pattern_symbols.push(env.new_symbol(i));
     }
 
-    // The bottom of the happy path - return the decoded tuple (a, b) wrapped with
-    // "Ok".
+    // The bottom of the happy path - return the decoded value, e.g. the tuple (a, b), wrapped
+    // with "Ok".
     let return_type_var;
     let mut body = {
-        let subs = &mut env.subs;
-        let tuple_indices_iter = index_vars.iter().copied().enumerate();
-        let flat_type = FlatType::Tuple(
-            TupleElems::insert_into_subs(subs, tuple_indices_iter),
-            Variable::EMPTY_TUPLE,
-        );
-        let done_tuple_var = synth_var(subs, Content::Structure(flat_type));
-        let done_record = Expr::Tuple {
-            tuple_var: done_tuple_var,
-            elems: tuple_elems,
-        };
+        let elems: Vec<_> = index_vars
+            .iter()
+            .copied()
+            .zip(pattern_symbols.iter().copied())
+            .collect();
+        let (done_value, done_var) = finish(env, &elems);
 
         return_type_var = {
             let flat_type = FlatType::TagUnion(
-                UnionTags::for_result(subs, done_tuple_var, decode_err_var),
+                UnionTags::for_result(env.subs, done_var, decode_err_var),
                 TagExt::Any(Variable::EMPTY_TAG_UNION),
             );
 
-            synth_var(subs, Content::Structure(flat_type))
+            synth_var(env.subs, Content::Structure(flat_type))
         };
 
         Expr::Tag {
             tag_union_var: return_type_var,
             ext_var: env.new_ext_var(ExtensionKind::TagUnion),
             name: "Ok".into(),
-            arguments: vec![(done_tuple_var, Loc::at_zero(done_record))],
+            arguments: vec![(done_var, Loc::at_zero(done_value))],
         }
     };
 
@@ -916,18 +939,14 @@ fn finalizer(
 // initialState = {e0: Err NoElem, e1: Err NoElem}
 fn initial_state(
     env: &mut Env<'_>,
-    arity: u32,
-    index_vars: &mut Vec<Variable>,
+    index_vars: &[Variable],
     state_fields: &mut Vec<Lowercase>,
     state_field_vars: &mut Vec<Variable>,
 ) -> (Variable, Expr) {
     let mut initial_state_fields = SendMap::default();
 
-    for i in 0..arity {
+    for (i, &index_var) in index_vars.iter().enumerate() {
         let subs = &mut env.subs;
-        let index_var = subs.fresh_unnamed_flex_var();
-
-        index_vars.push(index_var);
 
         let state_field = Lowercase::from(format!("e{i}"));
         state_fields.push(state_field.clone());
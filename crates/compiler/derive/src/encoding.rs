@@ -28,9 +28,9 @@ pub(crate) fn derive_to_encoder(
     def_symbol: Symbol,
 ) -> DerivedBody {
     let (body, body_type) = match key {
-        FlatEncodableKey::List() => to_encoder_list(env, def_symbol),
-        FlatEncodableKey::Set() => todo!(),
-        FlatEncodableKey::Dict() => todo!(),
+        FlatEncodableKey::List() => to_encoder_list(env, ListLike::List, def_symbol),
+        FlatEncodableKey::Set() => to_encoder_list(env, ListLike::Set, def_symbol),
+        FlatEncodableKey::Dict() => to_encoder_list(env, ListLike::Dict, def_symbol),
         FlatEncodableKey::Record(fields) => {
             // Generalized record var so we can reuse this impl between many records:
             // if fields = { a, b }, this is { a: t1, b: t2 } for fresh t1, t2.
@@ -102,103 +102,99 @@ pub(crate) fn derive_to_encoder(
     }
 }
 
-fn to_encoder_list(env: &mut Env<'_>, fn_name: Symbol) -> (Expr, Variable) {
+/// A builtin collection that is encoded as a list of its elements.
+#[derive(Clone, Copy)]
+enum ListLike {
+    List,
+    /// Encoded as `Set.toList set`.
+    Set,
+    /// Encoded as `Dict.toList dict`, with each entry encoded as a `(key, value)` tuple.
+    Dict,
+}
+
+fn to_encoder_list(env: &mut Env<'_>, list_like: ListLike, fn_name: Symbol) -> (Expr, Variable) {
     // Build \lst -> Encode.list lst (\elem -> Encode.toEncoder elem)
     //
     // TODO eta reduce this baby     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+    //
+    // Sets and dictionaries are first converted with `Set.toList lst` or `Dict.toList lst`.
 
     use Expr::*;
 
     let lst_sym = env.new_symbol("lst");
-    let elem_sym = env.new_symbol("elem");
+
+    // \elem -> Encode.toEncoder elem
+    let (elem_var, to_elem_encoder, to_elem_encoder_fn_var) = match list_like {
+        ListLike::List | ListLike::Set => to_elem_encoder(env),
+        ListLike::Dict => {
+            // (k, v)
+            let flex_elems = (0..2)
+                .map(|idx| (idx, env.subs.fresh_unnamed_flex_var()))
+                .collect::<Vec<_>>();
+            let elems = TupleElems::insert_into_subs(env.subs, flex_elems);
+            let entry_var = synth_var(
+                env.subs,
+                Content::Structure(FlatType::Tuple(elems, Variable::EMPTY_TUPLE)),
+            );
+
+            // \tup -> Encode.tuple [ Encode.toEncoder tup.0, Encode.toEncoder tup.1 ]
+            let to_entry_encoder_sym = env.new_symbol("to_entry_encoder");
+            let (to_entry_encoder, to_entry_encoder_fn_var) =
+                to_encoder_tuple(env, entry_var, elems, to_entry_encoder_sym);
+
+            (entry_var, to_entry_encoder, to_entry_encoder_fn_var)
+        }
+    };
 
     // List elem
-    let elem_var = env.subs.fresh_unnamed_flex_var();
     let elem_var_slice = SubsSlice::insert_into_subs(env.subs, [elem_var]);
     let list_var = synth_var(
         env.subs,
         Content::Structure(FlatType::Apply(Symbol::LIST_LIST, elem_var_slice)),
     );
 
-    // build `toEncoder elem` type
-    // val -[uls]-> Encoder fmt | fmt has EncoderFormatting
-    let to_encoder_fn_var = env.import_builtin_symbol_var(Symbol::ENCODE_TO_ENCODER);
-
-    // elem -[clos]-> t1
-    let to_encoder_clos_var = env.subs.fresh_unnamed_flex_var(); // clos
-    let elem_encoder_var = env.subs.fresh_unnamed_flex_var(); // t1
-    let elem_to_encoder_fn_var = synth_var(
-        env.subs,
-        Content::Structure(FlatType::Func(
-            elem_var_slice,
-            to_encoder_clos_var,
-            elem_encoder_var,
-        )),
-    );
-
-    //   val  -[uls]->  Encoder fmt | fmt has EncoderFormatting
-    // ~ elem -[clos]-> t1
-    env.unify(to_encoder_fn_var, elem_to_encoder_fn_var);
+    // lst, or Set.toList lst, or Dict.toList lst
+    let (lst_var, list_expr) = match list_like {
+        ListLike::List => (list_var, Var(lst_sym, list_var)),
+        ListLike::Set | ListLike::Dict => {
+            let to_list_sym = match list_like {
+                ListLike::Set => Symbol::SET_TO_LIST,
+                _ => Symbol::DICT_TO_LIST,
+            };
 
-    // toEncoder : (typeof rcd.a) -[clos]-> Encoder fmt | fmt has EncoderFormatting
-    let to_encoder_var = AbilityMember(Symbol::ENCODE_TO_ENCODER, None, elem_to_encoder_fn_var);
-    let to_encoder_fn = Box::new((
-        to_encoder_fn_var,
-        Loc::at_zero(to_encoder_var),
-        to_encoder_clos_var,
-        elem_encoder_var,
-    ));
+            // build `toList lst` type
+            // Set k -> List k | k has Hash & Eq
+            let to_list_fn_var = env.import_builtin_symbol_var(to_list_sym);
 
-    // toEncoder elem
-    let to_encoder_call = Call(
-        to_encoder_fn,
-        vec![(elem_var, Loc::at_zero(Var(elem_sym, elem_var)))],
-        CalledVia::Space,
-    );
-
-    // elem -[to_elem_encoder]-> toEncoder elem
-    let to_elem_encoder_sym = env.new_symbol("to_elem_encoder");
+            // t1 -[clos]-> List elem
+            let lst_var = env.subs.fresh_unnamed_flex_var(); // t1
+            let lst_var_slice = SubsSlice::insert_into_subs(env.subs, [lst_var]);
+            let to_list_clos_var = env.subs.fresh_unnamed_flex_var(); // clos
+            let this_to_list_fn_var = synth_var(
+                env.subs,
+                Content::Structure(FlatType::Func(lst_var_slice, to_list_clos_var, list_var)),
+            );
 
-    // Create fn_var for ambient capture; we fix it up below.
-    let to_elem_encoder_fn_var = synth_var(env.subs, Content::Error);
+            //   Set k -> List k | k has Hash & Eq
+            // ~ t1 -[clos]-> List elem
+            env.unify(to_list_fn_var, this_to_list_fn_var);
 
-    // -[to_elem_encoder]->
-    let to_elem_encoder_labels =
-        UnionLambdas::insert_into_subs(env.subs, once((to_elem_encoder_sym, vec![])));
-    let to_elem_encoder_lset = synth_var(
-        env.subs,
-        Content::LambdaSet(LambdaSet {
-            solved: to_elem_encoder_labels,
-            recursion_var: OptVariable::NONE,
-            unspecialized: SubsSlice::default(),
-            ambient_function: to_elem_encoder_fn_var,
-        }),
-    );
-    // elem -[to_elem_encoder]-> toEncoder elem
-    env.subs.set_content(
-        to_elem_encoder_fn_var,
-        Content::Structure(FlatType::Func(
-            elem_var_slice,
-            to_elem_encoder_lset,
-            elem_encoder_var,
-        )),
-    );
+            // toList lst
+            let to_list_fn = Box::new((
+                this_to_list_fn_var,
+                Loc::at_zero(Var(to_list_sym, this_to_list_fn_var)),
+                to_list_clos_var,
+                list_var,
+            ));
+            let to_list_call = Call(
+                to_list_fn,
+                vec![(lst_var, Loc::at_zero(Var(lst_sym, lst_var)))],
+                CalledVia::Space,
+            );
 
-    // \elem -> toEncoder elem
-    let to_elem_encoder = Closure(ClosureData {
-        function_type: to_elem_encoder_fn_var,
-        closure_type: to_elem_encoder_lset,
-        return_type: elem_encoder_var,
-        name: to_elem_encoder_sym,
-        captured_symbols: vec![],
-        recursive: Recursive::NotRecursive,
-        arguments: vec![(
-            elem_var,
-            AnnotatedMark::known_exhaustive(),
-            Loc::at_zero(Pattern::Identifier(elem_sym)),
-        )],
-        loc_body: Box::new(Loc::at_zero(to_encoder_call)),
-    });
+            (lst_var, to_list_call)
+        }
+    };
 
     // build `Encode.list lst (\elem -> Encode.toEncoder elem)` type
     // List e, (e -> Encoder fmt) -[uls]-> Encoder fmt | fmt has EncoderFormatting
@@ -235,7 +231,7 @@ fn to_encoder_list(env: &mut Env<'_>, fn_name: Symbol) -> (Expr, Variable) {
     let encode_list_call = Call(
         encode_list_fn,
         vec![
-            (list_var, Loc::at_zero(Var(lst_sym, list_var))),
+            (list_var, Loc::at_zero(list_expr)),
             (to_elem_encoder_fn_var, Loc::at_zero(to_elem_encoder)),
         ],
         CalledVia::Space,
@@ -247,7 +243,7 @@ fn to_encoder_list(env: &mut Env<'_>, fn_name: Symbol) -> (Expr, Variable) {
         encode_list_call,
         this_list_encoder_var,
         lst_sym,
-        list_var,
+        lst_var,
     );
 
     // \lst -> Encode.list lst (\elem -> Encode.toEncoder elem)
@@ -266,14 +262,10 @@ fn to_encoder_list(env: &mut Env<'_>, fn_name: Symbol) -> (Expr, Variable) {
         }),
     );
     // List elem -[fn_name]-> Encoder fmt
-    let list_var_slice = SubsSlice::insert_into_subs(env.subs, once(list_var));
+    let lst_var_slice = SubsSlice::insert_into_subs(env.subs, once(lst_var));
     env.subs.set_content(
         fn_var,
-        Content::Structure(FlatType::Func(
-            list_var_slice,
-            fn_clos_var,
-            this_encoder_var,
-        )),
+        Content::Structure(FlatType::Func(lst_var_slice, fn_clos_var, this_encoder_var)),
     );
 
     // \lst -[fn_name]-> Encode.list lst (\elem -> Encode.toEncoder elem)
@@ -285,7 +277,7 @@ fn to_encoder_list(env: &mut Env<'_>, fn_name: Symbol) -> (Expr, Variable) {
         captured_symbols: vec![],
         recursive: Recursive::NotRecursive,
         arguments: vec![(
-            list_var,
+            lst_var,
             AnnotatedMark::known_exhaustive(),
             Loc::at_zero(Pattern::Identifier(lst_sym)),
         )],
@@ -295,6 +287,99 @@ fn to_encoder_list(env: &mut Env<'_>, fn_name: Symbol) -> (Expr, Variable) {
     (clos, fn_var)
 }
 
+// Build \elem -> Encode.toEncoder elem
+fn to_elem_encoder(env: &mut Env<'_>) -> (Variable, Expr, Variable) {
+    use Expr::*;
+
+    let elem_sym = env.new_symbol("elem");
+
+    // elem
+    let elem_var = env.subs.fresh_unnamed_flex_var();
+    let elem_var_slice = SubsSlice::insert_into_subs(env.subs, [elem_var]);
+
+    // build `toEncoder elem` type
+    // val -[uls]-> Encoder fmt | fmt has EncoderFormatting
+    let to_encoder_fn_var = env.import_builtin_symbol_var(Symbol::ENCODE_TO_ENCODER);
+
+    // elem -[clos]-> t1
+    let to_encoder_clos_var = env.subs.fresh_unnamed_flex_var(); // clos
+    let elem_encoder_var = env.subs.fresh_unnamed_flex_var(); // t1
+    let elem_to_encoder_fn_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::Func(
+            elem_var_slice,
+            to_encoder_clos_var,
+            elem_encoder_var,
+        )),
+    );
+
+    //   val  -[uls]->  Encoder fmt | fmt has EncoderFormatting
+    // ~ elem -[clos]-> t1
+    env.unify(to_encoder_fn_var, elem_to_encoder_fn_var);
+
+    // toEncoder : (typeof rcd.a) -[clos]-> Encoder fmt | fmt has EncoderFormatting
+    let to_encoder_var = AbilityMember(Symbol::ENCODE_TO_ENCODER, None, elem_to_encoder_fn_var);
+    let to_encoder_fn = Box::new((
+        to_encoder_fn_var,
+        Loc::at_zero(to_encoder_var),
+        to_encoder_clos_var,
+        elem_encoder_var,
+    ));
+
+    // toEncoder elem
+    let to_encoder_call = Call(
+        to_encoder_fn,
+        vec![(elem_var, Loc::at_zero(Var(elem_sym, elem_var)))],
+        CalledVia::Space,
+    );
+
+    // elem -[to_elem_encoder]-> toEncoder elem
+    let to_elem_encoder_sym = env.new_symbol("to_elem_encoder");
+
+    // Create fn_var for ambient capture; we fix it up below.
+    let to_elem_encoder_fn_var = synth_var(env.subs, Content::Error);
+
+    // -[to_elem_encoder]->
+    let to_elem_encoder_labels =
+        UnionLambdas::insert_into_subs(env.subs, once((to_elem_encoder_sym, vec![])));
+    let to_elem_encoder_lset = synth_var(
+        env.subs,
+        Content::LambdaSet(LambdaSet {
+            solved: to_elem_encoder_labels,
+            recursion_var: OptVariable::NONE,
+            unspecialized: SubsSlice::default(),
+            ambient_function: to_elem_encoder_fn_var,
+        }),
+    );
+    // elem -[to_elem_encoder]-> toEncoder elem
+    env.subs.set_content(
+        to_elem_encoder_fn_var,
+        Content::Structure(FlatType::Func(
+            elem_var_slice,
+            to_elem_encoder_lset,
+            elem_encoder_var,
+        )),
+    );
+
+    // \elem -> toEncoder elem
+    let to_elem_encoder = Closure(ClosureData {
+        function_type: to_elem_encoder_fn_var,
+        closure_type: to_elem_encoder_lset,
+        return_type: elem_encoder_var,
+        name: to_elem_encoder_sym,
+        captured_symbols: vec![],
+        recursive: Recursive::NotRecursive,
+        arguments: vec![(
+            elem_var,
+            AnnotatedMark::known_exhaustive(),
+            Loc::at_zero(Pattern::Identifier(elem_sym)),
+        )],
+        loc_body: Box::new(Loc::at_zero(to_encoder_call)),
+    });
+
+    (elem_var, to_elem_encoder, to_elem_encoder_fn_var)
+}
+
 fn to_encoder_record(
     env: &mut Env<'_>,
     record_var: Variable,
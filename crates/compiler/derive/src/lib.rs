@@ -1,6 +1,7 @@
 //! Auto-derivers of builtin ability methods.
 
 use std::iter::once;
+use std::ops::Range;
 use std::sync::{Arc, Mutex};
 
 use roc_can::abilities::SpecializationLambdaSets;
@@ -9,16 +10,18 @@ use roc_can::pattern::Pattern;
 use roc_can::{def::Def, module::ExposedByModule};
 use roc_collections::{MutMap, VecMap};
 use roc_derive_key::DeriveKey;
-use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+use roc_module::symbol::{IdentId, IdentIds, ModuleId, Symbol};
 use roc_region::all::Loc;
 use roc_types::subs::{
     copy_import_to, Content, Descriptor, Mark, OptVariable, Rank, Subs, Variable,
 };
 use util::Env;
 
+mod compare;
 mod decoding;
 mod encoding;
 mod hash;
+mod inspect;
 
 mod util;
 
@@ -43,6 +46,9 @@ pub fn synth_var(subs: &mut Subs, content: Content) -> Variable {
 #[derive(Debug, Default)]
 pub struct DerivedModule {
     map: MutMap<DeriveKey, (Symbol, Def, SpecializationLambdaSets)>,
+    /// The idents made while deriving each key. They're made in one go, and in the same order
+    /// every time the key is derived.
+    idents: MutMap<DeriveKey, Range<u32>>,
     subs: Subs,
     derived_ident_ids: IdentIds,
 }
@@ -79,6 +85,12 @@ fn build_derived_body(
             decoding::derive_decoder(&mut env, decoder_key, derived_symbol)
         }
         DeriveKey::Hash(hash_key) => hash::derive_hash(&mut env, hash_key, derived_symbol),
+        DeriveKey::ToInspector(to_inspector_key) => {
+            inspect::derive_to_inspector(&mut env, to_inspector_key, derived_symbol)
+        }
+        DeriveKey::Compare(compare_key) => {
+            compare::derive_compare(&mut env, compare_key, derived_symbol)
+        }
     };
 
     let def = Def {
@@ -106,6 +118,8 @@ impl DerivedModule {
             return unsafe { std::mem::transmute(entry) };
         }
 
+        let first_ident = self.derived_ident_ids.len() as u32;
+
         let ident_id = if cfg!(debug_assertions) || cfg!(feature = "debug-derived-symbols") {
             let debug_name = key.debug_name();
             let ident_id = self.derived_ident_ids.get_or_insert(&debug_name);
@@ -128,10 +142,53 @@ impl DerivedModule {
             key.clone(),
         );
 
+        let idents = first_ident..self.derived_ident_ids.len() as u32;
+        self.idents.insert(key.clone(), idents);
+
         let triple = (derived_symbol, derived_def, specialization_lsets);
         self.map.entry(key).or_insert(triple)
     }
 
+    /// The idents made while deriving `key`, if it has been derived.
+    pub fn idents_of(&self, key: &DeriveKey) -> Option<Range<u32>> {
+        self.idents.get(key).cloned()
+    }
+
+    /// The key whose derived implementation `symbol` was made for, if any.
+    pub fn key_of(&self, symbol: Symbol) -> Option<&DeriveKey> {
+        if symbol.module_id() != DERIVED_SYNTH {
+            return None;
+        }
+
+        let index = symbol.ident_id().index() as u32;
+
+        self.idents
+            .iter()
+            .find(|(_, idents)| idents.contains(&index))
+            .map(|(key, _)| key)
+    }
+
+    /// The symbol that an ident made while deriving `key` had when `key` was derived at
+    /// `old_idents` instead, e.g. by another invocation of the compiler.
+    pub fn translate_ident(
+        &self,
+        key: &DeriveKey,
+        old_idents: &Range<u32>,
+        old_symbol: Symbol,
+    ) -> Option<Symbol> {
+        let idents = self.idents.get(key)?;
+        let offset = (old_symbol.ident_id().index() as u32).checked_sub(old_idents.start)?;
+
+        if idents.len() != old_idents.len() || offset >= idents.len() as u32 {
+            return None;
+        }
+
+        // Safety: the ident was made while deriving `key`
+        let ident_id = unsafe { IdentId::from_index(idents.start + offset) };
+
+        Some(Symbol::new(DERIVED_SYNTH, ident_id))
+    }
+
     pub fn is_derived_def(&self, def_symbol: Symbol) -> bool {
         self.map
             .iter()
@@ -211,6 +268,7 @@ impl DerivedModule {
     pub unsafe fn from_components(subs: Subs, ident_ids: IdentIds) -> Self {
         Self {
             map: Default::default(),
+            idents: Default::default(),
             subs,
             derived_ident_ids: ident_ids,
         }
@@ -164,6 +164,9 @@ const fn from_builtin_symbol(symbol: Symbol) -> Option<Result<FlatEncodable, Der
         Symbol::NUM_F32 | Symbol::NUM_BINARY32 => Some(Ok(Immediate(Symbol::ENCODE_F32))),
         Symbol::NUM_F64 | Symbol::NUM_BINARY64 => Some(Ok(Immediate(Symbol::ENCODE_F64))),
         Symbol::NUM_NAT | Symbol::NUM_NATURAL => Some(Err(DeriveError::Underivable)),
+        // Encoded as a list of their elements, rather than their internal representation.
+        Symbol::SET_SET => Some(Ok(Key(FlatEncodableKey::Set()))),
+        Symbol::DICT_DICT => Some(Ok(Key(FlatEncodableKey::Dict()))),
         _ => None,
     }
 }
@@ -0,0 +1,178 @@
+use roc_module::{
+    ident::{Lowercase, TagName},
+    symbol::{ModuleId, Symbol},
+};
+use roc_types::{
+    subs::{Content, FlatType, GetSubsSlice, Subs, Variable},
+    types::AliasKind,
+};
+
+use crate::{
+    util::{check_derivable_ext_var, debug_name_record, debug_name_tag, debug_name_tuple},
+    DeriveError,
+};
+
+#[derive(Hash)]
+pub enum FlatInspectable {
+    Immediate(Symbol),
+    Key(FlatInspectableKey),
+}
+
+#[derive(Hash, PartialEq, Eq, Debug, Clone)]
+pub enum FlatInspectableKey {
+    List(/* takes one variable */),
+    Set(/* takes one variable */),
+    Dict(/* takes two variables */),
+    Box(/* takes one variable */),
+    // Unfortunate that we must allocate here, c'est la vie
+    Record(Vec<Lowercase>),
+    Tuple(u32),
+    TagUnion(Vec<(TagName, u16)>),
+}
+
+impl FlatInspectableKey {
+    pub(crate) fn debug_name(&self) -> String {
+        match self {
+            FlatInspectableKey::List() => "list".to_string(),
+            FlatInspectableKey::Set() => "set".to_string(),
+            FlatInspectableKey::Dict() => "dict".to_string(),
+            FlatInspectableKey::Box() => "box".to_string(),
+            FlatInspectableKey::Record(fields) => debug_name_record(fields),
+            FlatInspectableKey::Tuple(arity) => debug_name_tuple(*arity),
+            FlatInspectableKey::TagUnion(tags) => debug_name_tag(tags),
+        }
+    }
+}
+
+impl FlatInspectable {
+    pub(crate) fn from_var(subs: &Subs, var: Variable) -> Result<FlatInspectable, DeriveError> {
+        use DeriveError::*;
+        use FlatInspectable::*;
+        match *subs.get_content_without_compacting(var) {
+            Content::Structure(flat_type) => match flat_type {
+                FlatType::Apply(sym, _) => match sym {
+                    Symbol::LIST_LIST => Ok(Key(FlatInspectableKey::List())),
+                    Symbol::SET_SET => Ok(Key(FlatInspectableKey::Set())),
+                    Symbol::DICT_DICT => Ok(Key(FlatInspectableKey::Dict())),
+                    Symbol::BOX_BOX_TYPE => Ok(Key(FlatInspectableKey::Box())),
+                    Symbol::STR_STR => Ok(Immediate(Symbol::INSPECT_STR)),
+                    // Any other builtin type is shown without its contents.
+                    _ => Ok(Immediate(Symbol::INSPECT_OPAQUE)),
+                },
+                FlatType::Record(fields, ext) => {
+                    let (fields_iter, ext) = fields.unsorted_iterator_and_ext(subs, ext);
+
+                    check_derivable_ext_var(subs, ext, |ext| {
+                        matches!(ext, Content::Structure(FlatType::EmptyRecord))
+                    })?;
+
+                    let mut field_names = Vec::with_capacity(fields.len());
+                    for (field_name, _) in fields_iter {
+                        field_names.push(field_name.clone());
+                    }
+
+                    field_names.sort();
+
+                    Ok(Key(FlatInspectableKey::Record(field_names)))
+                }
+                FlatType::Tuple(elems, ext) => {
+                    let (elems_iter, ext) = elems.sorted_iterator_and_ext(subs, ext);
+
+                    check_derivable_ext_var(subs, ext, |ext| {
+                        matches!(ext, Content::Structure(FlatType::EmptyTuple))
+                    })?;
+
+                    Ok(Key(FlatInspectableKey::Tuple(elems_iter.count() as _)))
+                }
+                FlatType::TagUnion(tags, ext) | FlatType::RecursiveTagUnion(_, tags, ext) => {
+                    // As with encoding, the recursion var doesn't matter, because the derived
+                    // implementation only looks at the surface of the tag union type.
+                    let (tags_iter, ext) = tags.unsorted_tags_and_ext(subs, ext);
+
+                    check_derivable_ext_var(subs, ext.var(), |ext| {
+                        matches!(ext, Content::Structure(FlatType::EmptyTagUnion))
+                    })?;
+
+                    let mut tag_names_and_payload_sizes: Vec<_> = tags_iter
+                        .tags
+                        .into_iter()
+                        .map(|(name, payload_slice)| {
+                            let payload_size = payload_slice.len();
+                            (name.clone(), payload_size as _)
+                        })
+                        .collect();
+
+                    tag_names_and_payload_sizes.sort_by(|(t1, _), (t2, _)| t1.cmp(t2));
+
+                    Ok(Key(FlatInspectableKey::TagUnion(
+                        tag_names_and_payload_sizes,
+                    )))
+                }
+                FlatType::FunctionOrTagUnion(names_index, _, _) => {
+                    Ok(Key(FlatInspectableKey::TagUnion(
+                        subs.get_subs_slice(names_index)
+                            .iter()
+                            .map(|t| (t.clone(), 0))
+                            .collect(),
+                    )))
+                }
+                FlatType::EmptyRecord => Ok(Key(FlatInspectableKey::Record(vec![]))),
+                FlatType::EmptyTuple => Ok(Key(FlatInspectableKey::Tuple(0))),
+                FlatType::EmptyTagUnion => Ok(Key(FlatInspectableKey::TagUnion(vec![]))),
+                //
+                FlatType::Func(..) => Ok(Immediate(Symbol::INSPECT_FUNCTION)),
+            },
+            Content::Alias(sym, _, real_var, kind) => match from_builtin_symbol(sym) {
+                Some(lambda) => lambda,
+                // Opaques that reach this point have no `Inspect` implementation of their own,
+                // so their contents are kept hidden. Numbers are the exception, since their
+                // opaque wrappers must be looked through to find the concrete number type.
+                _ if matches!(kind, AliasKind::Opaque) && sym.module_id() != ModuleId::NUM => {
+                    Ok(Immediate(Symbol::INSPECT_OPAQUE))
+                }
+                _ => Self::from_var(subs, real_var),
+            },
+            Content::RangedNumber(range) => {
+                Self::from_var(subs, range.default_compilation_variable())
+            }
+            //
+            Content::RecursionVar { structure, .. } => Self::from_var(subs, structure),
+            //
+            Content::Error => Err(Underivable),
+            Content::FlexVar(_)
+            | Content::RigidVar(_)
+            | Content::FlexAbleVar(_, _)
+            | Content::RigidAbleVar(_, _) => Err(UnboundVar),
+            Content::LambdaSet(_) => Err(Underivable),
+        }
+    }
+
+    pub(crate) fn from_builtin_symbol(symbol: Symbol) -> Result<FlatInspectable, DeriveError> {
+        from_builtin_symbol(symbol).unwrap_or(Err(DeriveError::Underivable))
+    }
+}
+
+const fn from_builtin_symbol(symbol: Symbol) -> Option<Result<FlatInspectable, DeriveError>> {
+    use FlatInspectable::*;
+    match symbol {
+        Symbol::BOOL_BOOL => Some(Ok(Immediate(Symbol::INSPECT_BOOL))),
+        Symbol::NUM_U8 | Symbol::NUM_UNSIGNED8 => Some(Ok(Immediate(Symbol::INSPECT_U8))),
+        Symbol::NUM_U16 | Symbol::NUM_UNSIGNED16 => Some(Ok(Immediate(Symbol::INSPECT_U16))),
+        Symbol::NUM_U32 | Symbol::NUM_UNSIGNED32 => Some(Ok(Immediate(Symbol::INSPECT_U32))),
+        Symbol::NUM_U64 | Symbol::NUM_UNSIGNED64 => Some(Ok(Immediate(Symbol::INSPECT_U64))),
+        Symbol::NUM_U128 | Symbol::NUM_UNSIGNED128 => Some(Ok(Immediate(Symbol::INSPECT_U128))),
+        Symbol::NUM_I8 | Symbol::NUM_SIGNED8 => Some(Ok(Immediate(Symbol::INSPECT_I8))),
+        Symbol::NUM_I16 | Symbol::NUM_SIGNED16 => Some(Ok(Immediate(Symbol::INSPECT_I16))),
+        Symbol::NUM_I32 | Symbol::NUM_SIGNED32 => Some(Ok(Immediate(Symbol::INSPECT_I32))),
+        Symbol::NUM_I64 | Symbol::NUM_SIGNED64 => Some(Ok(Immediate(Symbol::INSPECT_I64))),
+        Symbol::NUM_I128 | Symbol::NUM_SIGNED128 => Some(Ok(Immediate(Symbol::INSPECT_I128))),
+        Symbol::NUM_DEC | Symbol::NUM_DECIMAL => Some(Ok(Immediate(Symbol::INSPECT_DEC))),
+        Symbol::NUM_F32 | Symbol::NUM_BINARY32 => Some(Ok(Immediate(Symbol::INSPECT_F32))),
+        Symbol::NUM_F64 | Symbol::NUM_BINARY64 => Some(Ok(Immediate(Symbol::INSPECT_F64))),
+        Symbol::NUM_NAT | Symbol::NUM_NATURAL => Some(Ok(Immediate(Symbol::INSPECT_NAT))),
+        // Inspected as a list of their elements, rather than their internal representation.
+        Symbol::SET_SET => Some(Ok(Key(FlatInspectableKey::Set()))),
+        Symbol::DICT_DICT => Some(Ok(Key(FlatInspectableKey::Dict()))),
+        _ => None,
+    }
+}
@@ -9,18 +9,25 @@
 //!   between e.g. required and optional record fields.
 //! - `Decoding` is like encoding, but has some differences. For one, it *does* need to distinguish
 //!   between required and optional record fields.
+//! - `Inspect` is keyed like encoding, since it presents the surface of a type.
+//! - `Sort` is keyed like hashing, since records and tag unions are ordered by their field and
+//!   tag names.
 //!
 //! For these reasons the content keying is based on a strategy as well, which are the variants of
 //! [`DeriveKey`].
 
+pub mod compare;
 pub mod decoding;
 pub mod encoding;
 pub mod hash;
+pub mod inspect;
 mod util;
 
+use compare::{FlatCompare, FlatCompareKey};
 use decoding::{FlatDecodable, FlatDecodableKey};
 use encoding::{FlatEncodable, FlatEncodableKey};
 use hash::{FlatHash, FlatHashKey};
+use inspect::{FlatInspectable, FlatInspectableKey};
 
 use roc_module::symbol::Symbol;
 use roc_types::subs::{Subs, Variable};
@@ -40,6 +47,8 @@ pub enum DeriveKey {
     ToEncoder(FlatEncodableKey),
     Decoder(FlatDecodableKey),
     Hash(FlatHashKey),
+    ToInspector(FlatInspectableKey),
+    Compare(FlatCompareKey),
 }
 
 impl DeriveKey {
@@ -48,6 +57,8 @@ impl DeriveKey {
             DeriveKey::ToEncoder(key) => format!("toEncoder_{}", key.debug_name()),
             DeriveKey::Decoder(key) => format!("decoder_{}", key.debug_name()),
             DeriveKey::Hash(key) => format!("hash_{}", key.debug_name()),
+            DeriveKey::ToInspector(key) => format!("toInspector_{}", key.debug_name()),
+            DeriveKey::Compare(key) => format!("compare_{}", key.debug_name()),
         }
     }
 }
@@ -77,6 +88,8 @@ pub enum DeriveBuiltin {
     Decoder,
     Hash,
     IsEq,
+    ToInspector,
+    Compare,
 }
 
 impl TryFrom<Symbol> for DeriveBuiltin {
@@ -88,6 +101,8 @@ impl TryFrom<Symbol> for DeriveBuiltin {
             Symbol::DECODE_DECODER => Ok(DeriveBuiltin::Decoder),
             Symbol::HASH_HASH => Ok(DeriveBuiltin::Hash),
             Symbol::BOOL_IS_EQ => Ok(DeriveBuiltin::IsEq),
+            Symbol::INSPECT_TO_INSPECTOR => Ok(DeriveBuiltin::ToInspector),
+            Symbol::SORT_COMPARE => Ok(DeriveBuiltin::Compare),
             _ => Err(value),
         }
     }
@@ -121,6 +136,16 @@ impl Derived {
                     Symbol::BOOL_STRUCTURAL_EQ,
                 ))
             }
+            DeriveBuiltin::ToInspector => match inspect::FlatInspectable::from_var(subs, var)? {
+                FlatInspectable::Immediate(imm) => Ok(Derived::Immediate(imm)),
+                FlatInspectable::Key(repr) => Ok(Derived::Key(DeriveKey::ToInspector(repr))),
+            },
+            DeriveBuiltin::Compare => match compare::FlatCompare::from_var(subs, var)? {
+                FlatCompare::SingleLambdaSetImmediate(imm) => {
+                    Ok(Derived::SingleLambdaSetImmediate(imm))
+                }
+                FlatCompare::Key(repr) => Ok(Derived::Key(DeriveKey::Compare(repr))),
+            },
         }
     }
 
@@ -151,6 +176,18 @@ impl Derived {
                     Symbol::BOOL_STRUCTURAL_EQ,
                 ))
             }
+            DeriveBuiltin::ToInspector => {
+                match inspect::FlatInspectable::from_builtin_symbol(symbol)? {
+                    FlatInspectable::Immediate(imm) => Ok(Derived::Immediate(imm)),
+                    FlatInspectable::Key(repr) => Ok(Derived::Key(DeriveKey::ToInspector(repr))),
+                }
+            }
+            DeriveBuiltin::Compare => match compare::FlatCompare::from_builtin_symbol(symbol)? {
+                FlatCompare::SingleLambdaSetImmediate(imm) => {
+                    Ok(Derived::SingleLambdaSetImmediate(imm))
+                }
+                FlatCompare::Key(repr) => Ok(Derived::Key(DeriveKey::Compare(repr))),
+            },
         }
     }
 }
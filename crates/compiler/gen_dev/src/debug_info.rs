@@ -0,0 +1,337 @@
+//! DWARF debug info for the objects built by the dev backend.
+//!
+//! Every proc gets a subprogram entry covering its text section, and procs with a source region
+//! get a line table sequence: a row for the start of their body, then one for the first statement
+//! of every def and `when`/`if` branch in it. That is enough for debuggers and profilers to show a
+//! file and line next to a `#UserApp_...` symbol, and to step through a proc a def at a time.
+use std::collections::hash_map::Entry;
+use std::path::Path;
+
+use gimli::write::{
+    Address, AttributeValue, DwarfUnit, EndianVec, FileId, LineProgram, LineString, Range,
+    RangeList, Sections, Writer,
+};
+use gimli::{Encoding, Format, LineEncoding, LittleEndian, SectionId as DwarfSectionId};
+use object::write::{Object, SectionId, SymbolId};
+use object::{BinaryFormat, RelocationEncoding, RelocationKind, SectionKind};
+use roc_collections::all::MutMap;
+use roc_error_macros::internal_error;
+use roc_module::symbol::ModuleId;
+use roc_mono::ir::{DebugSources, Proc};
+use roc_region::all::LineColumn;
+
+const ENCODING: Encoding = Encoding {
+    format: Format::Dwarf32,
+    version: 4,
+    address_size: 8,
+};
+
+pub(crate) struct DebugInfo {
+    dwarf: DwarfUnit,
+    files: MutMap<ModuleId, FileId>,
+    /// The proc symbols that `Address::Symbol` indices refer to.
+    symbols: std::vec::Vec<SymbolId>,
+    ranges: std::vec::Vec<Range>,
+}
+
+impl DebugInfo {
+    pub fn new() -> Self {
+        let comp_dir = ".";
+        let comp_name = "roc_app";
+
+        let mut dwarf = DwarfUnit::new(ENCODING);
+        dwarf.unit.line_program = LineProgram::new(
+            ENCODING,
+            LineEncoding::default(),
+            LineString::String(comp_dir.into()),
+            LineString::String(comp_name.into()),
+            None,
+        );
+
+        let producer = dwarf.strings.add("roc dev backend");
+        let name = dwarf.strings.add(comp_name);
+        let comp_dir = dwarf.strings.add(comp_dir);
+
+        let root = dwarf.unit.root();
+        let entry = dwarf.unit.get_mut(root);
+        entry.set(gimli::DW_AT_producer, AttributeValue::StringRef(producer));
+        // Roc has no DWARF language code yet; this matches what the LLVM backend reports.
+        entry.set(
+            gimli::DW_AT_language,
+            AttributeValue::Language(gimli::DW_LANG_C),
+        );
+        entry.set(gimli::DW_AT_name, AttributeValue::StringRef(name));
+        entry.set(gimli::DW_AT_comp_dir, AttributeValue::StringRef(comp_dir));
+
+        Self {
+            dwarf,
+            files: MutMap::default(),
+            symbols: std::vec::Vec::new(),
+            ranges: std::vec::Vec::new(),
+        }
+    }
+
+    /// The line table file and 0-based position of the start of `proc`'s body, if it has one.
+    pub fn proc_location(
+        &mut self,
+        sources: &DebugSources,
+        proc: &Proc,
+    ) -> Option<(FileId, LineColumn)> {
+        let (path, line_column) = sources.location(proc)?;
+
+        let file_id = match self.files.entry(proc.name.name().module_id()) {
+            Entry::Occupied(occupied) => *occupied.get(),
+            Entry::Vacant(vacant) => {
+                *vacant.insert(add_file(&mut self.dwarf.unit.line_program, path))
+            }
+        };
+
+        Some((file_id, line_column))
+    }
+
+    /// Describe a proc whose `size` bytes of code start at `symbol`. The `statements` are the
+    /// offsets into that code where each located statement starts, in order.
+    pub fn add_proc(
+        &mut self,
+        symbol: SymbolId,
+        name: &str,
+        linkage_name: &str,
+        location: Option<(FileId, LineColumn)>,
+        statements: &[(u64, LineColumn)],
+        size: u64,
+    ) {
+        let address = Address::Symbol {
+            symbol: self.symbols.len(),
+            addend: 0,
+        };
+        self.symbols.push(symbol);
+        self.ranges.push(Range::StartLength {
+            begin: address,
+            length: size,
+        });
+
+        let name = self.dwarf.strings.add(name);
+        let linkage_name = self.dwarf.strings.add(linkage_name);
+
+        let root = self.dwarf.unit.root();
+        let id = self.dwarf.unit.add(root, gimli::DW_TAG_subprogram);
+        let entry = self.dwarf.unit.get_mut(id);
+        entry.set(gimli::DW_AT_name, AttributeValue::StringRef(name));
+        entry.set(
+            gimli::DW_AT_linkage_name,
+            AttributeValue::StringRef(linkage_name),
+        );
+        entry.set(gimli::DW_AT_low_pc, AttributeValue::Address(address));
+        entry.set(gimli::DW_AT_high_pc, AttributeValue::Udata(size));
+
+        if let Some((file_id, LineColumn { line, column })) = location {
+            // DWARF numbers lines and columns from 1.
+            entry.set(
+                gimli::DW_AT_decl_file,
+                AttributeValue::FileIndex(Some(file_id)),
+            );
+            entry.set(
+                gimli::DW_AT_decl_line,
+                AttributeValue::Udata(line as u64 + 1),
+            );
+
+            let line_program = &mut self.dwarf.unit.line_program;
+            line_program.begin_sequence(Some(address));
+
+            let start = std::iter::once((0, LineColumn { line, column }));
+            for (offset, LineColumn { line, column }) in start.chain(statements.iter().copied()) {
+                let row = line_program.row();
+                row.address_offset = offset;
+                row.file = file_id;
+                row.line = line as u64 + 1;
+                row.column = column as u64 + 1;
+                line_program.generate_row();
+            }
+
+            line_program.end_sequence(size);
+        }
+    }
+
+    /// Write the `.debug_*` sections, and the relocations that tie them to the procs, into `output`.
+    pub fn write(mut self, output: &mut Object) {
+        let ranges = std::mem::take(&mut self.ranges);
+        let ranges = self.dwarf.unit.ranges.add(RangeList(ranges));
+
+        let root = self.dwarf.unit.root();
+        let entry = self.dwarf.unit.get_mut(root);
+        entry.set(
+            gimli::DW_AT_low_pc,
+            AttributeValue::Address(Address::Constant(0)),
+        );
+        entry.set(gimli::DW_AT_ranges, AttributeValue::RangeListRef(ranges));
+
+        let mut sections = Sections::new(DebugSection::new());
+        if let Err(e) = self.dwarf.write(&mut sections) {
+            internal_error!("failed to write debug info: {:?}", e);
+        }
+
+        let mut section_ids: MutMap<DwarfSectionId, SectionId> = MutMap::default();
+        let _ = sections.for_each(|id, section| {
+            if !section.data.slice().is_empty() {
+                let (segment, name) = match output.format() {
+                    BinaryFormat::MachO => (b"__DWARF".to_vec(), format!("__{}", &id.name()[1..])),
+                    _ => (vec![], id.name().to_string()),
+                };
+                let section_id = output.add_section(segment, name.into_bytes(), SectionKind::Debug);
+                output.append_section_data(section_id, section.data.slice(), 1);
+                section_ids.insert(id, section_id);
+            }
+
+            Ok::<(), ()>(())
+        });
+
+        let _ = sections.for_each(|id, section| {
+            for reloc in section.relocs.iter() {
+                let symbol = match reloc.target {
+                    DebugRelocTarget::Symbol(index) => self.symbols[index],
+                    // Mach-O debug sections refer to each other by plain offsets.
+                    DebugRelocTarget::Section(_) if output.format() == BinaryFormat::MachO => {
+                        continue
+                    }
+                    DebugRelocTarget::Section(target) => {
+                        output.section_symbol(section_ids[&target])
+                    }
+                };
+
+                let relocation = object::write::Relocation {
+                    offset: reloc.offset,
+                    size: reloc.size * 8,
+                    kind: RelocationKind::Absolute,
+                    encoding: RelocationEncoding::Generic,
+                    symbol,
+                    addend: reloc.addend,
+                };
+
+                if let Err(e) = output.add_relocation(section_ids[&id], relocation) {
+                    internal_error!("{:?}", e);
+                }
+            }
+
+            Ok::<(), ()>(())
+        });
+    }
+}
+
+fn add_file(line_program: &mut LineProgram, path: &Path) -> FileId {
+    let directory = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => line_program.add_directory(LineString::String(
+            dir.to_string_lossy().as_bytes().to_vec(),
+        )),
+        _ => line_program.default_directory(),
+    };
+
+    let file_name = match path.file_name() {
+        Some(file_name) => file_name.to_string_lossy(),
+        None => path.to_string_lossy(),
+    };
+
+    line_program.add_file(
+        LineString::String(file_name.as_bytes().to_vec()),
+        directory,
+        None,
+    )
+}
+
+#[derive(Debug, Clone, Copy)]
+enum DebugRelocTarget {
+    /// An index into `DebugInfo::symbols`
+    Symbol(usize),
+    /// The start of another debug section
+    Section(DwarfSectionId),
+}
+
+#[derive(Debug, Clone)]
+struct DebugReloc {
+    offset: u64,
+    /// In bytes
+    size: u8,
+    target: DebugRelocTarget,
+    addend: i64,
+}
+
+/// A `gimli` writer that records the relocations it needs instead of failing on symbolic addresses.
+#[derive(Clone)]
+struct DebugSection {
+    data: EndianVec<LittleEndian>,
+    relocs: std::vec::Vec<DebugReloc>,
+}
+
+impl DebugSection {
+    fn new() -> Self {
+        Self {
+            data: EndianVec::new(LittleEndian),
+            relocs: std::vec::Vec::new(),
+        }
+    }
+}
+
+impl Writer for DebugSection {
+    type Endian = LittleEndian;
+
+    fn endian(&self) -> Self::Endian {
+        LittleEndian
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> gimli::write::Result<()> {
+        self.data.write(bytes)
+    }
+
+    fn write_at(&mut self, offset: usize, bytes: &[u8]) -> gimli::write::Result<()> {
+        self.data.write_at(offset, bytes)
+    }
+
+    fn write_address(&mut self, address: Address, size: u8) -> gimli::write::Result<()> {
+        match address {
+            Address::Constant(val) => self.write_udata(val, size),
+            Address::Symbol { symbol, addend } => {
+                self.relocs.push(DebugReloc {
+                    offset: self.len() as u64,
+                    size,
+                    target: DebugRelocTarget::Symbol(symbol),
+                    addend,
+                });
+                self.write_udata(addend as u64, size)
+            }
+        }
+    }
+
+    fn write_offset(
+        &mut self,
+        val: usize,
+        section: DwarfSectionId,
+        size: u8,
+    ) -> gimli::write::Result<()> {
+        self.relocs.push(DebugReloc {
+            offset: self.len() as u64,
+            size,
+            target: DebugRelocTarget::Section(section),
+            addend: val as i64,
+        });
+        self.write_udata(val as u64, size)
+    }
+
+    fn write_offset_at(
+        &mut self,
+        offset: usize,
+        val: usize,
+        section: DwarfSectionId,
+        size: u8,
+    ) -> gimli::write::Result<()> {
+        self.relocs.push(DebugReloc {
+            offset: offset as u64,
+            size,
+            target: DebugRelocTarget::Section(section),
+            addend: val as i64,
+        });
+        self.write_udata_at(offset, val as u64, size)
+    }
+}
@@ -255,12 +255,56 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         AArch64GeneralReg::X13,
         AArch64GeneralReg::X14,
         AArch64GeneralReg::X15,
-        AArch64GeneralReg::IP0,
+        // Don't use IP0, it is reserved as a scratch register by the assembler: AArch64GeneralReg::IP0,
         AArch64GeneralReg::IP1,
     ];
-    const FLOAT_PARAM_REGS: &'static [AArch64FloatReg] = &[];
+    const FLOAT_PARAM_REGS: &'static [AArch64FloatReg] = &[
+        AArch64FloatReg::V0,
+        AArch64FloatReg::V1,
+        AArch64FloatReg::V2,
+        AArch64FloatReg::V3,
+        AArch64FloatReg::V4,
+        AArch64FloatReg::V5,
+        AArch64FloatReg::V6,
+        AArch64FloatReg::V7,
+    ];
     const FLOAT_RETURN_REGS: &'static [AArch64FloatReg] = Self::FLOAT_PARAM_REGS;
-    const FLOAT_DEFAULT_FREE_REGS: &'static [AArch64FloatReg] = &[];
+    const FLOAT_DEFAULT_FREE_REGS: &'static [AArch64FloatReg] = &[
+        // Use callee saved regs last.
+        AArch64FloatReg::V8,
+        AArch64FloatReg::V9,
+        AArch64FloatReg::V10,
+        AArch64FloatReg::V11,
+        AArch64FloatReg::V12,
+        AArch64FloatReg::V13,
+        AArch64FloatReg::V14,
+        AArch64FloatReg::V15,
+        // Use caller saved regs first.
+        AArch64FloatReg::V0,
+        AArch64FloatReg::V1,
+        AArch64FloatReg::V2,
+        AArch64FloatReg::V3,
+        AArch64FloatReg::V4,
+        AArch64FloatReg::V5,
+        AArch64FloatReg::V6,
+        AArch64FloatReg::V7,
+        AArch64FloatReg::V16,
+        AArch64FloatReg::V17,
+        AArch64FloatReg::V18,
+        AArch64FloatReg::V19,
+        AArch64FloatReg::V20,
+        AArch64FloatReg::V21,
+        AArch64FloatReg::V22,
+        AArch64FloatReg::V23,
+        AArch64FloatReg::V24,
+        AArch64FloatReg::V25,
+        AArch64FloatReg::V26,
+        AArch64FloatReg::V27,
+        AArch64FloatReg::V28,
+        AArch64FloatReg::V29,
+        AArch64FloatReg::V30,
+        AArch64FloatReg::V31,
+    ];
 
     const SHADOW_SPACE_SIZE: u8 = 0;
 
@@ -281,8 +325,19 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         )
     }
     #[inline(always)]
-    fn float_callee_saved(_reg: &AArch64FloatReg) -> bool {
-        todo!("AArch64 FloatRegs");
+    fn float_callee_saved(reg: &AArch64FloatReg) -> bool {
+        // Only the bottom 64 bits of V8-V15 are callee saved, which is all we ever use.
+        matches!(
+            reg,
+            AArch64FloatReg::V8
+                | AArch64FloatReg::V9
+                | AArch64FloatReg::V10
+                | AArch64FloatReg::V11
+                | AArch64FloatReg::V12
+                | AArch64FloatReg::V13
+                | AArch64FloatReg::V14
+                | AArch64FloatReg::V15
+        )
     }
 
     #[inline(always)]
@@ -293,9 +348,21 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         requested_stack_size: i32,
         fn_call_stack_size: i32,
     ) -> i32 {
-        // Full size is upcast to i64 to make sure we don't overflow here.
+        // Push the frame pointer and link register, then point the frame pointer at them.
+        // This could be optimized by using `STP` to store the pair.
+        AArch64Assembler::sub_reg64_reg64_imm32(
+            buf,
+            AArch64GeneralReg::ZRSP,
+            AArch64GeneralReg::ZRSP,
+            16,
+        );
+        str_reg64_reg64_imm12(buf, AArch64GeneralReg::FP, AArch64GeneralReg::ZRSP, 0);
+        str_reg64_reg64_imm12(buf, AArch64GeneralReg::LR, AArch64GeneralReg::ZRSP, 1);
+        // `MOV FP, SP` is an alias of `ADD FP, SP, #0`; the `ORR` form would read the zero register.
+        add_reg64_reg64_imm12(buf, AArch64GeneralReg::FP, AArch64GeneralReg::ZRSP, 0);
+
         let full_stack_size = match requested_stack_size
-            .checked_add(8 * (saved_general_regs.len() + saved_float_regs.len()) as i32 + 8) // The extra 8 is space to store the frame pointer.
+            .checked_add(8 * (saved_general_regs.len() + saved_float_regs.len()) as i32)
             .and_then(|size| size.checked_add(fn_call_stack_size))
         {
             Some(size) => size,
@@ -313,11 +380,6 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         };
         if let Some(aligned_stack_size) = full_stack_size.checked_add(offset as i32) {
             if aligned_stack_size > 0 {
-                AArch64Assembler::mov_reg64_reg64(
-                    buf,
-                    AArch64GeneralReg::FP,
-                    AArch64GeneralReg::ZRSP,
-                );
                 AArch64Assembler::sub_reg64_reg64_imm32(
                     buf,
                     AArch64GeneralReg::ZRSP,
@@ -325,21 +387,15 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
                     aligned_stack_size,
                 );
 
-                // All the following stores could be optimized by using `STP` to store pairs.
-                let mut offset = aligned_stack_size;
-                offset -= 8;
-                AArch64Assembler::mov_stack32_reg64(buf, offset, AArch64GeneralReg::LR);
-                offset -= 8;
-                AArch64Assembler::mov_stack32_reg64(buf, offset, AArch64GeneralReg::FP);
-
-                offset = aligned_stack_size - fn_call_stack_size;
+                // Put values at the top of the stack to avoid conflicts with previously saved variables.
+                let mut offset = aligned_stack_size - fn_call_stack_size;
                 for reg in saved_general_regs {
+                    AArch64Assembler::mov_base32_reg64(buf, -offset, *reg);
                     offset -= 8;
-                    AArch64Assembler::mov_base32_reg64(buf, offset, *reg);
                 }
                 for reg in saved_float_regs {
+                    AArch64Assembler::mov_base32_freg64(buf, -offset, *reg);
                     offset -= 8;
-                    AArch64Assembler::mov_base32_freg64(buf, offset, *reg);
                 }
                 aligned_stack_size
             } else {
@@ -359,21 +415,14 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         fn_call_stack_size: i32,
     ) {
         if aligned_stack_size > 0 {
-            // All the following stores could be optimized by using `STP` to store pairs.
-            let mut offset = aligned_stack_size;
-            offset -= 8;
-            AArch64Assembler::mov_reg64_stack32(buf, AArch64GeneralReg::LR, offset);
-            offset -= 8;
-            AArch64Assembler::mov_reg64_stack32(buf, AArch64GeneralReg::FP, offset);
-
-            offset = aligned_stack_size - fn_call_stack_size;
+            let mut offset = aligned_stack_size - fn_call_stack_size;
             for reg in saved_general_regs {
+                AArch64Assembler::mov_reg64_base32(buf, *reg, -offset);
                 offset -= 8;
-                AArch64Assembler::mov_reg64_base32(buf, *reg, offset);
             }
             for reg in saved_float_regs {
+                AArch64Assembler::mov_freg64_base32(buf, *reg, -offset);
                 offset -= 8;
-                AArch64Assembler::mov_freg64_base32(buf, *reg, offset);
             }
             AArch64Assembler::add_reg64_reg64_imm32(
                 buf,
@@ -382,6 +431,16 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
                 aligned_stack_size,
             );
         }
+
+        // Pop the frame pointer and link register.
+        ldr_reg64_reg64_imm12(buf, AArch64GeneralReg::FP, AArch64GeneralReg::ZRSP, 0);
+        ldr_reg64_reg64_imm12(buf, AArch64GeneralReg::LR, AArch64GeneralReg::ZRSP, 1);
+        AArch64Assembler::add_reg64_reg64_imm32(
+            buf,
+            AArch64GeneralReg::ZRSP,
+            AArch64GeneralReg::ZRSP,
+            16,
+        );
     }
 
     #[inline(always)]
@@ -482,11 +541,9 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         imm32: i32,
     ) {
         if imm32 < 0 {
-            todo!("immediate addition with values less than 0");
-        } else if imm32 < 0xFFF {
-            add_reg64_reg64_imm12(buf, dst, src, imm32 as u16);
+            Self::add_or_sub_reg64_reg64_imm(buf, true, dst, src, imm32.unsigned_abs());
         } else {
-            todo!("immediate additions with values greater than 12bits");
+            Self::add_or_sub_reg64_reg64_imm(buf, false, dst, src, imm32 as u32);
         }
     }
     #[inline(always)]
@@ -518,8 +575,12 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     }
 
     #[inline(always)]
-    fn call(_buf: &mut Vec<'_, u8>, _relocs: &mut Vec<'_, Relocation>, _fn_name: String) {
-        todo!("calling functions literal for AArch64");
+    fn call(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>, fn_name: String) {
+        bl_imm26(buf, 0);
+        relocs.push(Relocation::LinkedFunction {
+            offset: buf.len() as u64 - 4,
+            name: fn_name,
+        });
     }
 
     #[inline(always)]
@@ -582,29 +643,33 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     }
 
     fn irem_reg64_reg64_reg64<'a, ASM, CC>(
-        _buf: &mut Vec<'a, u8>,
+        buf: &mut Vec<'a, u8>,
         _storage_manager: &mut StorageManager<'a, '_, AArch64GeneralReg, AArch64FloatReg, ASM, CC>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) where
         ASM: Assembler<AArch64GeneralReg, AArch64FloatReg>,
         CC: CallConv<AArch64GeneralReg, AArch64FloatReg, ASM>,
     {
-        todo!()
+        // There is no remainder instruction, so compute `src1 - (src1 / src2) * src2`.
+        sdiv_reg64_reg64_reg64(buf, AArch64GeneralReg::IP0, src1, src2);
+        msub_reg64_reg64_reg64_reg64(buf, dst, AArch64GeneralReg::IP0, src2, src1);
     }
 
     fn urem_reg64_reg64_reg64<'a, ASM, CC>(
-        _buf: &mut Vec<'a, u8>,
+        buf: &mut Vec<'a, u8>,
         _storage_manager: &mut StorageManager<'a, '_, AArch64GeneralReg, AArch64FloatReg, ASM, CC>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) where
         ASM: Assembler<AArch64GeneralReg, AArch64FloatReg>,
         CC: CallConv<AArch64GeneralReg, AArch64FloatReg, ASM>,
     {
-        todo!()
+        // There is no remainder instruction, so compute `src1 - (src1 / src2) * src2`.
+        udiv_reg64_reg64_reg64(buf, AArch64GeneralReg::IP0, src1, src2);
+        msub_reg64_reg64_reg64_reg64(buf, dst, AArch64GeneralReg::IP0, src2, src1);
     }
 
     #[inline(always)]
@@ -650,16 +715,19 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         if (-(1 << 27)..(1 << 27)).contains(&offset) {
             b_imm26(buf, offset);
         } else {
-            todo!("jump offsets over 27 bits for AArch64: {:#x}", offset);
+            // Jumps to a not yet known location are emitted with a placeholder offset that does not fit.
+            // They are always overwritten once the real offset is known.
+            b_imm26(buf, 0);
         }
 
-        buf.len()
+        // AArch64 branches are relative to the start of the branch instruction.
+        buf.len() - 4
     }
 
     #[inline(always)]
     fn tail_call(buf: &mut Vec<'_, u8>) -> u64 {
         Self::jmp_imm32(buf, 0);
-        buf.len() as u64 - 4 // TODO is 4 the correct offset in ARM?
+        buf.len() as u64 - 4
     }
 
     #[inline(always)]
@@ -677,10 +745,8 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         if imm < (1 << 12) {
             cmp_reg64_imm12(buf, reg, imm as u16);
         } else {
-            todo!(
-                "cmp immediate with value over 12 bits for AArch64: {:#x}",
-                imm
-            );
+            Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, imm as i64);
+            cmp_reg64_reg64(buf, reg, AArch64GeneralReg::IP0);
         }
 
         if (-(1 << 20)..(1 << 20)).contains(&offset) {
@@ -689,7 +755,8 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
             todo!("jump offsets over 20 bits for AArch64: {:#x}", offset);
         }
 
-        buf.len()
+        // AArch64 branches are relative to the start of the branch instruction.
+        buf.len() - 4
     }
 
     #[inline(always)]
@@ -709,7 +776,8 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
                 fmov_freg_imm8(buf, FloatWidth::F32, dst, imm8);
             }
             None => {
-                todo!("loading f32 literal over 8 bits for AArch64");
+                Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, imm.to_bits() as i64);
+                fmov_freg_reg64(buf, FloatWidth::F32, dst, AArch64GeneralReg::IP0);
             }
         }
     }
@@ -730,7 +798,8 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
                 fmov_freg_imm8(buf, FloatWidth::F64, dst, imm8);
             }
             None => {
-                todo!("loading f64 literal over 8 bits for AArch64");
+                Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, imm.to_bits() as i64);
+                fmov_freg_reg64(buf, FloatWidth::F64, dst, AArch64GeneralReg::IP0);
             }
         }
     }
@@ -757,12 +826,12 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     }
 
     #[inline(always)]
-    fn mov_reg32_freg32(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg, _src: AArch64FloatReg) {
-        unimplemented!();
+    fn mov_reg32_freg32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64FloatReg) {
+        fmov_reg64_freg(buf, FloatWidth::F32, dst, src);
     }
     #[inline(always)]
-    fn mov_reg64_freg64(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg, _src: AArch64FloatReg) {
-        unimplemented!();
+    fn mov_reg64_freg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64FloatReg) {
+        fmov_reg64_freg(buf, FloatWidth::F64, dst, src);
     }
 
     #[inline(always)]
@@ -773,97 +842,153 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         src: AArch64GeneralReg,
     ) {
         match register_width {
-            RegisterWidth::W8 => todo!(),
-            RegisterWidth::W16 => todo!(),
-            RegisterWidth::W32 => todo!(),
+            RegisterWidth::W8 | RegisterWidth::W16 | RegisterWidth::W32 => {
+                uxt_reg64_reg64(buf, register_width, dst, src)
+            }
             RegisterWidth::W64 => mov_reg64_reg64(buf, dst, src),
         }
     }
 
     #[inline(always)]
     fn movsx_reg_reg(
-        _buf: &mut Vec<'_, u8>,
-        _input_width: RegisterWidth,
-        _dst: AArch64GeneralReg,
-        _src: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        input_width: RegisterWidth,
+        dst: AArch64GeneralReg,
+        src: AArch64GeneralReg,
     ) {
-        todo!("move with sign extension");
+        match input_width {
+            RegisterWidth::W8 | RegisterWidth::W16 | RegisterWidth::W32 => {
+                sxt_reg64_reg64(buf, input_width, dst, src)
+            }
+            RegisterWidth::W64 => mov_reg64_reg64(buf, dst, src),
+        }
     }
 
     #[inline(always)]
     fn movzx_reg_reg(
-        _buf: &mut Vec<'_, u8>,
-        _input_width: RegisterWidth,
-        _dst: AArch64GeneralReg,
-        _src: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        input_width: RegisterWidth,
+        dst: AArch64GeneralReg,
+        src: AArch64GeneralReg,
     ) {
-        todo!("move with zero extension");
+        match input_width {
+            RegisterWidth::W8 | RegisterWidth::W16 | RegisterWidth::W32 => {
+                uxt_reg64_reg64(buf, input_width, dst, src)
+            }
+            RegisterWidth::W64 => mov_reg64_reg64(buf, dst, src),
+        }
     }
 
     #[inline(always)]
-    fn mov_freg64_base32(_buf: &mut Vec<'_, u8>, _dst: AArch64FloatReg, _offset: i32) {
-        todo!("loading floating point reg from base offset for AArch64");
+    fn mov_freg64_base32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, offset: i32) {
+        Self::load_store_offset32(
+            buf,
+            LoadStoreKind::LDR_D,
+            dst.id(),
+            AArch64GeneralReg::FP,
+            offset,
+        );
     }
     #[inline(always)]
     fn mov_reg64_base32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32) {
-        if offset < 0 {
-            todo!("negative base offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            ldr_reg64_reg64_imm12(buf, dst, AArch64GeneralReg::FP, (offset as u16) >> 3);
-        } else {
-            todo!("base offsets over 32k for AArch64");
-        }
+        Self::load_store_offset32(
+            buf,
+            LoadStoreKind::LDR64,
+            dst.id(),
+            AArch64GeneralReg::FP,
+            offset,
+        );
     }
     #[inline(always)]
-    fn mov_reg32_base32(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg, _offset: i32) {
-        todo!()
+    fn mov_reg32_base32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32) {
+        Self::load_store_offset32(
+            buf,
+            LoadStoreKind::LDR32,
+            dst.id(),
+            AArch64GeneralReg::FP,
+            offset,
+        );
     }
     #[inline(always)]
-    fn mov_reg16_base32(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg, _offset: i32) {
-        todo!()
+    fn mov_reg16_base32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32) {
+        Self::load_store_offset32(
+            buf,
+            LoadStoreKind::LDRH,
+            dst.id(),
+            AArch64GeneralReg::FP,
+            offset,
+        );
     }
     #[inline(always)]
-    fn mov_reg8_base32(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg, _offset: i32) {
-        todo!()
+    fn mov_reg8_base32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32) {
+        Self::load_store_offset32(
+            buf,
+            LoadStoreKind::LDRB,
+            dst.id(),
+            AArch64GeneralReg::FP,
+            offset,
+        );
     }
     #[inline(always)]
-    fn mov_base32_freg64(_buf: &mut Vec<'_, u8>, _offset: i32, _src: AArch64FloatReg) {
-        todo!("saving floating point reg to base offset for AArch64");
+    fn mov_base32_freg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64FloatReg) {
+        Self::load_store_offset32(
+            buf,
+            LoadStoreKind::STR_D,
+            src.id(),
+            AArch64GeneralReg::FP,
+            offset,
+        );
     }
     #[inline(always)]
     fn movesd_mem64_offset32_freg64(
-        _buf: &mut Vec<'_, u8>,
-        _ptr: AArch64GeneralReg,
-        _offset: i32,
-        _src: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        ptr: AArch64GeneralReg,
+        offset: i32,
+        src: AArch64FloatReg,
     ) {
-        todo!()
+        Self::load_store_offset32(buf, LoadStoreKind::STR_D, src.id(), ptr, offset);
     }
 
     #[inline(always)]
     fn mov_base32_reg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64GeneralReg) {
-        if offset < 0 {
-            todo!("negative base offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            str_reg64_reg64_imm12(buf, src, AArch64GeneralReg::FP, (offset as u16) >> 3);
-        } else {
-            todo!("base offsets over 32k for AArch64");
-        }
+        Self::load_store_offset32(
+            buf,
+            LoadStoreKind::STR64,
+            src.id(),
+            AArch64GeneralReg::FP,
+            offset,
+        );
     }
 
     #[inline(always)]
-    fn mov_base32_reg32(_buf: &mut Vec<'_, u8>, _offset: i32, _src: AArch64GeneralReg) {
-        todo!()
+    fn mov_base32_reg32(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64GeneralReg) {
+        Self::load_store_offset32(
+            buf,
+            LoadStoreKind::STR32,
+            src.id(),
+            AArch64GeneralReg::FP,
+            offset,
+        );
     }
     #[inline(always)]
-    fn mov_base32_reg16(_buf: &mut Vec<'_, u8>, _offset: i32, _src: AArch64GeneralReg) {
-        todo!()
+    fn mov_base32_reg16(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64GeneralReg) {
+        Self::load_store_offset32(
+            buf,
+            LoadStoreKind::STRH,
+            src.id(),
+            AArch64GeneralReg::FP,
+            offset,
+        );
     }
     #[inline(always)]
-    fn mov_base32_reg8(_buf: &mut Vec<'_, u8>, _offset: i32, _src: AArch64GeneralReg) {
-        todo!()
+    fn mov_base32_reg8(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64GeneralReg) {
+        Self::load_store_offset32(
+            buf,
+            LoadStoreKind::STRB,
+            src.id(),
+            AArch64GeneralReg::FP,
+            offset,
+        );
     }
 
     #[inline(always)]
@@ -873,14 +998,7 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         src: AArch64GeneralReg,
         offset: i32,
     ) {
-        if offset < 0 {
-            todo!("negative mem offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            ldr_reg64_reg64_imm12(buf, dst, src, (offset as u16) >> 3);
-        } else {
-            todo!("mem offsets over 32k for AArch64");
-        }
+        Self::load_store_offset32(buf, LoadStoreKind::LDR64, dst.id(), src, offset);
     }
     #[inline(always)]
     fn mov_reg32_mem32_offset32(
@@ -889,32 +1007,25 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         src: AArch64GeneralReg,
         offset: i32,
     ) {
-        if offset < 0 {
-            todo!("negative mem offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            ldr_reg64_reg64_imm12(buf, dst, src, (offset as u16) >> 3);
-        } else {
-            todo!("mem offsets over 32k for AArch64");
-        }
+        Self::load_store_offset32(buf, LoadStoreKind::LDR32, dst.id(), src, offset);
     }
     #[inline(always)]
     fn mov_reg16_mem16_offset32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src: AArch64GeneralReg,
-        _offset: i32,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src: AArch64GeneralReg,
+        offset: i32,
     ) {
-        todo!()
+        Self::load_store_offset32(buf, LoadStoreKind::LDRH, dst.id(), src, offset);
     }
     #[inline(always)]
     fn mov_reg8_mem8_offset32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src: AArch64GeneralReg,
-        _offset: i32,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src: AArch64GeneralReg,
+        offset: i32,
     ) {
-        todo!()
+        Self::load_store_offset32(buf, LoadStoreKind::LDRB, dst.id(), src, offset);
     }
 
     #[inline(always)]
@@ -924,44 +1035,37 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         offset: i32,
         src: AArch64GeneralReg,
     ) {
-        if offset < 0 {
-            todo!("negative mem offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            str_reg64_reg64_imm12(buf, src, dst, (offset as u16) >> 3);
-        } else {
-            todo!("mem offsets over 32k for AArch64");
-        }
+        Self::load_store_offset32(buf, LoadStoreKind::STR64, src.id(), dst, offset);
     }
 
     #[inline(always)]
     fn mov_mem32_offset32_reg32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _offset: i32,
-        _src: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        offset: i32,
+        src: AArch64GeneralReg,
     ) {
-        todo!()
+        Self::load_store_offset32(buf, LoadStoreKind::STR32, src.id(), dst, offset);
     }
 
     #[inline(always)]
     fn mov_mem16_offset32_reg16(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _offset: i32,
-        _src: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        offset: i32,
+        src: AArch64GeneralReg,
     ) {
-        todo!()
+        Self::load_store_offset32(buf, LoadStoreKind::STRH, src.id(), dst, offset);
     }
 
     #[inline(always)]
     fn mov_mem8_offset32_reg8(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _offset: i32,
-        _src: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        offset: i32,
+        src: AArch64GeneralReg,
     ) {
-        todo!()
+        Self::load_store_offset32(buf, LoadStoreKind::STRB, src.id(), dst, offset);
     }
 
     #[inline(always)]
@@ -971,12 +1075,13 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         dst: AArch64GeneralReg,
         offset: i32,
     ) {
-        match register_width {
-            RegisterWidth::W8 => todo!("sign extend 1 byte values"),
-            RegisterWidth::W16 => todo!("sign extend 2 byte values"),
-            RegisterWidth::W32 => todo!("sign extend 4 byte values"),
-            RegisterWidth::W64 => Self::mov_reg64_base32(buf, dst, offset),
-        }
+        let kind = match register_width {
+            RegisterWidth::W8 => LoadStoreKind::LDRSB,
+            RegisterWidth::W16 => LoadStoreKind::LDRSH,
+            RegisterWidth::W32 => LoadStoreKind::LDRSW,
+            RegisterWidth::W64 => LoadStoreKind::LDR64,
+        };
+        Self::load_store_offset32(buf, kind, dst.id(), AArch64GeneralReg::FP, offset);
     }
 
     #[inline(always)]
@@ -986,32 +1091,44 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         dst: AArch64GeneralReg,
         offset: i32,
     ) {
-        match register_width {
-            RegisterWidth::W8 => todo!("zero extend 1 byte values"),
-            RegisterWidth::W16 => todo!("zero extend 2 byte values"),
-            RegisterWidth::W32 => todo!("zero extend 4 byte values"),
-            RegisterWidth::W64 => Self::mov_reg64_base32(buf, dst, offset),
-        }
+        let kind = match register_width {
+            RegisterWidth::W8 => LoadStoreKind::LDRB,
+            RegisterWidth::W16 => LoadStoreKind::LDRH,
+            RegisterWidth::W32 => LoadStoreKind::LDR32,
+            RegisterWidth::W64 => LoadStoreKind::LDR64,
+        };
+        Self::load_store_offset32(buf, kind, dst.id(), AArch64GeneralReg::FP, offset);
     }
 
     #[inline(always)]
-    fn mov_freg64_stack32(_buf: &mut Vec<'_, u8>, _dst: AArch64FloatReg, _offset: i32) {
-        todo!("loading floating point reg from stack for AArch64");
+    fn mov_freg64_stack32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, offset: i32) {
+        Self::load_store_offset32(
+            buf,
+            LoadStoreKind::LDR_D,
+            dst.id(),
+            AArch64GeneralReg::ZRSP,
+            offset,
+        );
     }
     #[inline(always)]
     fn mov_reg64_stack32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32) {
-        if offset < 0 {
-            todo!("negative stack offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            ldr_reg64_reg64_imm12(buf, dst, AArch64GeneralReg::ZRSP, (offset as u16) >> 3);
-        } else {
-            todo!("stack offsets over 32k for AArch64");
-        }
+        Self::load_store_offset32(
+            buf,
+            LoadStoreKind::LDR64,
+            dst.id(),
+            AArch64GeneralReg::ZRSP,
+            offset,
+        );
     }
     #[inline(always)]
-    fn mov_stack32_freg64(_buf: &mut Vec<'_, u8>, _offset: i32, _src: AArch64FloatReg) {
-        todo!("saving floating point reg to stack for AArch64");
+    fn mov_stack32_freg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64FloatReg) {
+        Self::load_store_offset32(
+            buf,
+            LoadStoreKind::STR_D,
+            src.id(),
+            AArch64GeneralReg::ZRSP,
+            offset,
+        );
     }
     #[inline(always)]
     fn mov_stack32_reg(
@@ -1020,21 +1137,13 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         offset: i32,
         src: AArch64GeneralReg,
     ) {
-        match register_width {
-            RegisterWidth::W8 => todo!(),
-            RegisterWidth::W16 => todo!(),
-            RegisterWidth::W32 => todo!(),
-            RegisterWidth::W64 => {
-                if offset < 0 {
-                    todo!("negative stack offsets for AArch64");
-                } else if offset < (0xFFF << 8) {
-                    debug_assert!(offset % 8 == 0);
-                    str_reg64_reg64_imm12(buf, src, AArch64GeneralReg::ZRSP, (offset as u16) >> 3);
-                } else {
-                    todo!("stack offsets over 32k for AArch64");
-                }
-            }
-        }
+        let kind = match register_width {
+            RegisterWidth::W8 => LoadStoreKind::STRB,
+            RegisterWidth::W16 => LoadStoreKind::STRH,
+            RegisterWidth::W32 => LoadStoreKind::STR32,
+            RegisterWidth::W64 => LoadStoreKind::STR64,
+        };
+        Self::load_store_offset32(buf, kind, src.id(), AArch64GeneralReg::ZRSP, offset);
     }
     #[inline(always)]
     fn neg_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
@@ -1049,11 +1158,9 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         imm32: i32,
     ) {
         if imm32 < 0 {
-            todo!("immediate subtractions with values less than 0");
-        } else if imm32 < 0xFFF {
-            sub_reg64_reg64_imm12(buf, dst, src, imm32 as u16);
+            Self::add_or_sub_reg64_reg64_imm(buf, false, dst, src, imm32.unsigned_abs());
         } else {
-            todo!("immediate subtractions with values greater than 12bits");
+            Self::add_or_sub_reg64_reg64_imm(buf, true, dst, src, imm32 as u32);
         }
     }
     #[inline(always)]
@@ -1065,6 +1172,24 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     ) {
         sub_reg64_reg64_reg64(buf, dst, src1, src2);
     }
+    #[inline(always)]
+    fn sub_freg32_freg32_freg32(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
+    ) {
+        fsub_freg_freg_freg(buf, FloatWidth::F32, dst, src1, src2);
+    }
+    #[inline(always)]
+    fn sub_freg64_freg64_freg64(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
+    ) {
+        fsub_freg_freg_freg(buf, FloatWidth::F64, dst, src1, src2);
+    }
 
     #[inline(always)]
     fn eq_reg_reg_reg(
@@ -1163,8 +1288,8 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         fcvt_freg64_freg32(buf, dst, src);
     }
 
-    fn set_if_overflow(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg) {
-        todo!("set if overflow for AArch64");
+    fn set_if_overflow(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg) {
+        cset_reg64_cond(buf, dst, ConditionCode::VS);
     }
 
     #[inline(always)]
@@ -1283,25 +1408,84 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     }
 
     fn mov_freg64_mem64_offset32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64GeneralReg,
-        _offset: i32,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src: AArch64GeneralReg,
+        offset: i32,
     ) {
-        todo!()
+        Self::load_store_offset32(buf, LoadStoreKind::LDR_D, dst.id(), src, offset);
     }
 
     fn mov_freg32_mem32_offset32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64GeneralReg,
-        _offset: i32,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src: AArch64GeneralReg,
+        offset: i32,
     ) {
-        todo!()
+        Self::load_store_offset32(buf, LoadStoreKind::LDR_S, dst.id(), src, offset);
     }
 }
 
-impl AArch64Assembler {}
+impl AArch64Assembler {
+    /// Adds `imm` to, or subtracts it from, `src` and places the result into `dst`.
+    /// Either register may be SP.
+    #[inline(always)]
+    fn add_or_sub_reg64_reg64_imm(
+        buf: &mut Vec<'_, u8>,
+        subtract: bool,
+        dst: AArch64GeneralReg,
+        src: AArch64GeneralReg,
+        imm: u32,
+    ) {
+        if imm <= 0xFFF {
+            if subtract {
+                sub_reg64_reg64_imm12(buf, dst, src, imm as u16);
+            } else {
+                add_reg64_reg64_imm12(buf, dst, src, imm as u16);
+            }
+        } else if imm <= 0xFF_FFFF {
+            let (high, low) = ((imm >> 12) as u16, (imm & 0xFFF) as u16);
+            if subtract {
+                sub_reg64_reg64_imm12_lsl12(buf, dst, src, high);
+            } else {
+                add_reg64_reg64_imm12_lsl12(buf, dst, src, high);
+            }
+            if low != 0 {
+                Self::add_or_sub_reg64_reg64_imm(buf, subtract, dst, dst, low as u32);
+            }
+        } else {
+            // IP0 is never handed out by the register allocator, so it is free to use as scratch here.
+            debug_assert_ne!(src, AArch64GeneralReg::IP0);
+            Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, imm as i64);
+            if subtract {
+                sub_reg64_reg64_reg64_extended(buf, dst, src, AArch64GeneralReg::IP0);
+            } else {
+                add_reg64_reg64_reg64_extended(buf, dst, src, AArch64GeneralReg::IP0);
+            }
+        }
+    }
+
+    /// Loads or stores `rt` at `base + offset`, using the shortest encoding that can hold the offset.
+    #[inline(always)]
+    fn load_store_offset32(
+        buf: &mut Vec<'_, u8>,
+        kind: LoadStoreKind,
+        rt: u8,
+        base: AArch64GeneralReg,
+        offset: i32,
+    ) {
+        let scale = kind.bytes();
+        if offset >= 0 && offset % scale == 0 && offset / scale <= 0xFFF {
+            load_store_reg_reg64_imm12(buf, kind, rt, base, (offset / scale) as u16);
+        } else if (-256..256).contains(&offset) {
+            load_store_reg_reg64_simm9(buf, kind, rt, base, offset as i16);
+        } else {
+            // IP0 is never handed out by the register allocator, so it is free to use as scratch here.
+            Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, offset as i64);
+            load_store_reg_reg64_reg64(buf, kind, rt, base, AArch64GeneralReg::IP0);
+        }
+    }
+}
 
 // Instructions
 // ARM manual section C3
@@ -1498,6 +1682,52 @@ impl ArithmeticShifted {
     }
 }
 
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct ArithmeticExtended {
+    sf: bool,
+    op: bool, // add or subtract
+    s: bool,
+    fixed: Integer<u8, packed_bits::Bits<5>>, // = 0b01011,
+    opt: Integer<u8, packed_bits::Bits<2>>,   // = 0b00,
+    fixed2: bool,                             // = 0b1,
+    reg_m: Integer<u8, packed_bits::Bits<5>>,
+    option: Integer<u8, packed_bits::Bits<3>>,
+    imm3: Integer<u8, packed_bits::Bits<3>>,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for ArithmeticExtended {}
+
+pub struct ArithmeticExtendedParams {
+    op: bool,
+    rm: AArch64GeneralReg,
+    rn: AArch64GeneralReg,
+    rd: AArch64GeneralReg,
+}
+
+impl ArithmeticExtended {
+    /// Xm is used as is (UXTX with no shift). Unlike the shifted register form,
+    /// Xd and Xn are SP rather than ZR when they are ZRSP.
+    #[inline(always)]
+    fn new(ArithmeticExtendedParams { op, rm, rn, rd }: ArithmeticExtendedParams) -> Self {
+        Self {
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            imm3: 0.into(),
+            option: 0b011.into(),
+            reg_m: rm.id().into(),
+            fixed2: true,
+            opt: 0.into(),
+            fixed: 0b01011.into(),
+            s: false,
+            op,
+            sf: true,
+        }
+    }
+}
+
 // ARM manual section C1.2.4
 #[derive(Copy, Clone, PartialEq)]
 #[allow(dead_code)]
@@ -1739,6 +1969,7 @@ impl Aarch64Bytes for DataProcessingThreeSource {}
 
 pub struct DataProcessingThreeSourceParams {
     op31: u8,
+    o0: bool,
     rm: AArch64GeneralReg,
     ra: AArch64GeneralReg,
     rn: AArch64GeneralReg,
@@ -1750,6 +1981,7 @@ impl DataProcessingThreeSource {
     fn new(
         DataProcessingThreeSourceParams {
             op31,
+            o0,
             rm,
             ra,
             rn,
@@ -1764,7 +1996,7 @@ impl DataProcessingThreeSource {
             fixed: 0b011011.into(),
             op31: op31.into(),
             rm: rm.id().into(),
-            o0: false,
+            o0,
             ra: ra.id().into(),
             rn: rn.id().into(),
             rd: rd.id().into(),
@@ -1772,6 +2004,61 @@ impl DataProcessingThreeSource {
     }
 }
 
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct Bitfield {
+    sf: bool,
+    opc: Integer<u8, packed_bits::Bits<2>>,
+    fixed: Integer<u8, packed_bits::Bits<6>>, // = 0b100110,
+    n: bool,
+    immr: Integer<u8, packed_bits::Bits<6>>,
+    imms: Integer<u8, packed_bits::Bits<6>>,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    rd: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for Bitfield {}
+
+pub struct BitfieldParams {
+    /// 0b00 for SBFM, 0b10 for UBFM
+    opc: u8,
+    sf: bool,
+    immr: u8,
+    imms: u8,
+    rn: AArch64GeneralReg,
+    rd: AArch64GeneralReg,
+}
+
+impl Bitfield {
+    #[inline(always)]
+    fn new(
+        BitfieldParams {
+            opc,
+            sf,
+            immr,
+            imms,
+            rn,
+            rd,
+        }: BitfieldParams,
+    ) -> Self {
+        debug_assert!(opc <= 0b11);
+        debug_assert!(immr <= 0b111111);
+        debug_assert!(imms <= 0b111111);
+
+        Self {
+            rd: rd.id().into(),
+            rn: rn.id().into(),
+            imms: imms.into(),
+            immr: immr.into(),
+            // n must match sf
+            n: sf,
+            fixed: 0b100110.into(),
+            opc: opc.into(),
+            sf,
+        }
+    }
+}
+
 #[derive(Debug)]
 #[allow(dead_code)]
 enum LogicalOp {
@@ -1923,6 +2210,50 @@ impl UnconditionalBranchImmediate {
     }
 }
 
+/// The access size, register file and direction of a load or store.
+/// These map directly onto the `size`, `V` and `opc` fields that all load/store encodings share.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LoadStoreKind {
+    size: u8,
+    v: bool,
+    opc: u8,
+}
+
+impl LoadStoreKind {
+    const STRB: Self = Self::general(0b00, 0b00);
+    const LDRB: Self = Self::general(0b00, 0b01);
+    const LDRSB: Self = Self::general(0b00, 0b10);
+    const STRH: Self = Self::general(0b01, 0b00);
+    const LDRH: Self = Self::general(0b01, 0b01);
+    const LDRSH: Self = Self::general(0b01, 0b10);
+    const STR32: Self = Self::general(0b10, 0b00);
+    const LDR32: Self = Self::general(0b10, 0b01);
+    const LDRSW: Self = Self::general(0b10, 0b10);
+    const STR64: Self = Self::general(0b11, 0b00);
+    const LDR64: Self = Self::general(0b11, 0b01);
+
+    const LDR_S: Self = Self::float(0b10, 0b01);
+    const STR_D: Self = Self::float(0b11, 0b00);
+    const LDR_D: Self = Self::float(0b11, 0b01);
+
+    const fn general(size: u8, opc: u8) -> Self {
+        Self {
+            size,
+            v: false,
+            opc,
+        }
+    }
+
+    const fn float(size: u8, opc: u8) -> Self {
+        Self { size, v: true, opc }
+    }
+
+    /// The number of bytes that are loaded or stored.
+    const fn bytes(&self) -> i32 {
+        1 << self.size
+    }
+}
+
 // Uses unsigned Offset
 // opc = 0b01 means load
 // opc = 0b00 means store
@@ -1931,8 +2262,8 @@ impl UnconditionalBranchImmediate {
 pub struct LoadStoreRegisterImmediate {
     size: Integer<u8, packed_bits::Bits<2>>,
     fixed: Integer<u8, packed_bits::Bits<3>>, // = 0b111,
-    fixed2: bool,
-    fixed3: Integer<u8, packed_bits::Bits<2>>,
+    v: bool,
+    fixed2: Integer<u8, packed_bits::Bits<2>>, // = 0b01,
     opc: Integer<u8, packed_bits::Bits<2>>,
     imm12: Integer<u16, packed_bits::Bits<12>>,
     rn: Integer<u8, packed_bits::Bits<5>>,
@@ -1941,47 +2272,103 @@ pub struct LoadStoreRegisterImmediate {
 
 impl Aarch64Bytes for LoadStoreRegisterImmediate {}
 
-pub struct LoadStoreRegisterImmediateParams {
-    size: u8,
-    imm12: u16,
-    rn: AArch64GeneralReg,
-    rt: AArch64GeneralReg,
-}
-
 impl LoadStoreRegisterImmediate {
+    /// `rt` is the id of either a general or a float register, depending on `kind`.
     #[inline(always)]
-    fn new(
-        opc: u8,
-        LoadStoreRegisterImmediateParams {
-            size,
-            imm12,
-            rn,
-            rt,
-        }: LoadStoreRegisterImmediateParams,
-    ) -> Self {
-        debug_assert!(size <= 0b11);
+    fn new(kind: LoadStoreKind, imm12: u16, rn: AArch64GeneralReg, rt: u8) -> Self {
+        debug_assert!(kind.size <= 0b11);
+        debug_assert!(kind.opc <= 0b11);
         debug_assert!(imm12 <= 0xFFF);
 
         Self {
-            rt: rt.id().into(),
+            rt: rt.into(),
             rn: rn.id().into(),
             imm12: imm12.into(),
-            opc: opc.into(),
-            fixed3: 0b01.into(),
-            fixed2: false,
+            opc: kind.opc.into(),
+            fixed2: 0b01.into(),
+            v: kind.v,
             fixed: 0b111.into(),
-            size: size.into(),
+            size: kind.size.into(),
         }
     }
+}
 
+// Uses an unscaled, signed offset (LDUR/STUR)
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct LoadStoreRegisterUnscaledImmediate {
+    size: Integer<u8, packed_bits::Bits<2>>,
+    fixed: Integer<u8, packed_bits::Bits<3>>, // = 0b111,
+    v: bool,
+    fixed2: Integer<u8, packed_bits::Bits<2>>, // = 0b00,
+    opc: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool,
+    imm9: Integer<u16, packed_bits::Bits<9>>,
+    fixed4: Integer<u8, packed_bits::Bits<2>>, // = 0b00,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    rt: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for LoadStoreRegisterUnscaledImmediate {}
+
+impl LoadStoreRegisterUnscaledImmediate {
     #[inline(always)]
-    fn new_load(params: LoadStoreRegisterImmediateParams) -> Self {
-        Self::new(0b01, params)
+    fn new(kind: LoadStoreKind, simm9: i16, rn: AArch64GeneralReg, rt: u8) -> Self {
+        debug_assert!((-256..256).contains(&simm9));
+
+        Self {
+            rt: rt.into(),
+            rn: rn.id().into(),
+            fixed4: 0b00.into(),
+            imm9: ((simm9 as u16) & 0x1FF).into(),
+            fixed3: false,
+            opc: kind.opc.into(),
+            fixed2: 0b00.into(),
+            v: kind.v,
+            fixed: 0b111.into(),
+            size: kind.size.into(),
+        }
     }
+}
 
+// Uses a register offset, which is not extended or shifted
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct LoadStoreRegisterRegisterOffset {
+    size: Integer<u8, packed_bits::Bits<2>>,
+    fixed: Integer<u8, packed_bits::Bits<3>>, // = 0b111,
+    v: bool,
+    fixed2: Integer<u8, packed_bits::Bits<2>>, // = 0b00,
+    opc: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool,
+    rm: Integer<u8, packed_bits::Bits<5>>,
+    option: Integer<u8, packed_bits::Bits<3>>,
+    s: bool,
+    fixed4: Integer<u8, packed_bits::Bits<2>>, // = 0b10,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    rt: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for LoadStoreRegisterRegisterOffset {}
+
+impl LoadStoreRegisterRegisterOffset {
     #[inline(always)]
-    fn new_store(params: LoadStoreRegisterImmediateParams) -> Self {
-        Self::new(0b00, params)
+    fn new(kind: LoadStoreKind, rm: AArch64GeneralReg, rn: AArch64GeneralReg, rt: u8) -> Self {
+        Self {
+            rt: rt.into(),
+            rn: rn.id().into(),
+            fixed4: 0b10.into(),
+            s: false,
+            // LSL, which is a plain 64 bit offset when s is false
+            option: 0b011.into(),
+            rm: rm.id().into(),
+            fixed3: true,
+            opc: kind.opc.into(),
+            fixed2: 0b00.into(),
+            v: kind.v,
+            fixed: 0b111.into(),
+            size: kind.size.into(),
+        }
     }
 }
 
@@ -2324,6 +2711,26 @@ fn add_reg64_reg64_imm12(
     buf.extend(inst.bytes());
 }
 
+/// `ADD Xd, Xn, imm12, LSL #12` -> Add Xn and imm12 shifted left by 12 bits and place the result into Xd.
+#[inline(always)]
+fn add_reg64_reg64_imm12_lsl12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = ArithmeticImmediate::new(ArithmeticImmediateParams {
+        op: false,
+        s: false,
+        rd: dst,
+        rn: src,
+        imm12,
+        sh: true,
+    });
+
+    buf.extend(inst.bytes());
+}
+
 /// `ADD Xd, Xm, Xn` -> Add Xm and Xn and place the result into Xd.
 #[inline(always)]
 fn add_reg64_reg64_reg64(
@@ -2345,6 +2752,24 @@ fn add_reg64_reg64_reg64(
     buf.extend(inst.bytes());
 }
 
+/// `ADD Xd|SP, Xn|SP, Xm` -> Add Xn and Xm and place the result into Xd. ZRSP is SP for Xd and Xn.
+#[inline(always)]
+fn add_reg64_reg64_reg64_extended(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = ArithmeticExtended::new(ArithmeticExtendedParams {
+        op: false,
+        rm: src2,
+        rn: src1,
+        rd: dst,
+    });
+
+    buf.extend(inst.bytes());
+}
+
 /// `AND Xd, Xn, Xm` -> Bitwise AND Xn and Xm and place the result into Xd.
 #[inline(always)]
 fn and_reg64_reg64_reg64(
@@ -2435,6 +2860,22 @@ fn b_imm26(buf: &mut Vec<'_, u8>, imm26: i32) {
     buf.extend(inst.bytes());
 }
 
+/// `BL imm26` -> Jump to PC + imm26, storing the return address in LR.
+#[inline(always)]
+fn bl_imm26(buf: &mut Vec<'_, u8>, imm26: i32) {
+    debug_assert!(imm26 & 0b11 == 0, "branch location must be 4-byte aligned");
+    let shifted = imm26 >> 2;
+    // Our offset is only 26 bits, so we need to remove the first 6 bits
+    let left_removed = ((shifted as u32) << 6) >> 6;
+
+    let inst = UnconditionalBranchImmediate::new(UnconditionalBranchImmediateParams {
+        op: true,
+        imm26: left_removed,
+    });
+
+    buf.extend(inst.bytes());
+}
+
 /// `CMP Xn, imm12` -> Compare Xn and imm12, setting condition flags.
 #[inline(always)]
 fn cmp_reg64_imm12(buf: &mut Vec<'_, u8>, src: AArch64GeneralReg, imm12: u16) {
@@ -2543,12 +2984,50 @@ fn ldr_reg64_reg64_imm12(
     base: AArch64GeneralReg,
     imm12: u16,
 ) {
-    let inst = LoadStoreRegisterImmediate::new_load(LoadStoreRegisterImmediateParams {
-        size: 0b11,
-        imm12,
-        rn: base,
-        rt: dst,
-    });
+    load_store_reg_reg64_imm12(buf, LoadStoreKind::LDR64, dst.id(), base, imm12);
+}
+
+/// `LDR/STR Rt, [Xn, imm12]` -> Load or store Rt at Xn + imm12, where imm12 is scaled by the access size.
+/// Which LDR/STR variant this is (and whether Rt is a general or a float register) is decided by `kind`.
+#[inline(always)]
+fn load_store_reg_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    kind: LoadStoreKind,
+    rt: u8,
+    base: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = LoadStoreRegisterImmediate::new(kind, imm12, base, rt);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDR/STR Rt, [Xn, Xm]` -> Load or store Rt at Xn + Xm.
+/// Which LDR/STR variant this is (and whether Rt is a general or a float register) is decided by `kind`.
+#[inline(always)]
+fn load_store_reg_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    kind: LoadStoreKind,
+    rt: u8,
+    base: AArch64GeneralReg,
+    offset: AArch64GeneralReg,
+) {
+    let inst = LoadStoreRegisterRegisterOffset::new(kind, offset, base, rt);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDUR/STUR Rt, [Xn, simm9]` -> Load or store Rt at Xn + simm9, where simm9 is a signed byte offset.
+/// Which LDUR/STUR variant this is (and whether Rt is a general or a float register) is decided by `kind`.
+#[inline(always)]
+fn load_store_reg_reg64_simm9(
+    buf: &mut Vec<'_, u8>,
+    kind: LoadStoreKind,
+    rt: u8,
+    base: AArch64GeneralReg,
+    simm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new(kind, simm9, base, rt);
 
     buf.extend(inst.bytes());
 }
@@ -2600,6 +3079,7 @@ fn madd_reg64_reg64_reg64_reg64(
 ) {
     let inst = DataProcessingThreeSource::new(DataProcessingThreeSourceParams {
         op31: 0b000000,
+        o0: false,
         rm: src2,
         ra: src3,
         rn: src1,
@@ -2644,6 +3124,27 @@ fn movz_reg64_imm16(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm16: u16, h
     buf.extend(inst.bytes());
 }
 
+/// `MSUB Xd, Xn, Xm, Xa` -> Multiply Xn and Xm, subtract the result from Xa, and place the result into Xd.
+#[inline(always)]
+fn msub_reg64_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+    src3: AArch64GeneralReg,
+) {
+    let inst = DataProcessingThreeSource::new(DataProcessingThreeSourceParams {
+        op31: 0b000000,
+        o0: true,
+        rm: src2,
+        ra: src3,
+        rn: src1,
+        rd: dst,
+    });
+
+    buf.extend(inst.bytes());
+}
+
 /// `MUL Xd, Xn, Xm` -> Multiply Xn and Xm and place the result into Xd.
 #[inline(always)]
 fn mul_reg64_reg64_reg64(
@@ -2709,19 +3210,32 @@ fn str_reg64_reg64_imm12(
     base: AArch64GeneralReg,
     imm12: u16,
 ) {
-    let inst = LoadStoreRegisterImmediate::new_store(LoadStoreRegisterImmediateParams {
-        size: 0b11,
+    load_store_reg_reg64_imm12(buf, LoadStoreKind::STR64, src.id(), base, imm12);
+}
+
+/// `SUB Xd, Xn, imm12` -> Subtract Xn and imm12 and place the result into Xd.
+#[inline(always)]
+fn sub_reg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = ArithmeticImmediate::new(ArithmeticImmediateParams {
+        op: true,
+        s: false,
+        rd: dst,
+        rn: src,
         imm12,
-        rn: base,
-        rt: src,
+        sh: false,
     });
 
     buf.extend(inst.bytes());
 }
 
-/// `SUB Xd, Xn, imm12` -> Subtract Xn and imm12 and place the result into Xd.
+/// `SUB Xd, Xn, imm12, LSL #12` -> Subtract imm12 shifted left by 12 bits from Xn and place the result into Xd.
 #[inline(always)]
-fn sub_reg64_reg64_imm12(
+fn sub_reg64_reg64_imm12_lsl12(
     buf: &mut Vec<'_, u8>,
     dst: AArch64GeneralReg,
     src: AArch64GeneralReg,
@@ -2733,7 +3247,7 @@ fn sub_reg64_reg64_imm12(
         rd: dst,
         rn: src,
         imm12,
-        sh: false,
+        sh: true,
     });
 
     buf.extend(inst.bytes());
@@ -2760,6 +3274,24 @@ fn sub_reg64_reg64_reg64(
     buf.extend(inst.bytes());
 }
 
+/// `SUB Xd|SP, Xn|SP, Xm` -> Subtract Xm from Xn and place the result into Xd. ZRSP is SP for Xd and Xn.
+#[inline(always)]
+fn sub_reg64_reg64_reg64_extended(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = ArithmeticExtended::new(ArithmeticExtendedParams {
+        op: true,
+        rm: src2,
+        rn: src1,
+        rd: dst,
+    });
+
+    buf.extend(inst.bytes());
+}
+
 /// `SUBS Xd, Xn, imm12` -> Subtract Xn and imm12 and place the result into Xd. Set condition flags.
 #[inline(always)]
 fn subs_reg64_reg64_imm12(
@@ -2810,6 +3342,34 @@ fn ret_reg64(buf: &mut Vec<'_, u8>, xn: AArch64GeneralReg) {
     buf.extend(inst.bytes());
 }
 
+/// `SXTB/SXTH/SXTW Xd, Wn` -> Sign extend the lowest byte/halfword/word of Wn and place the result into Xd.
+#[inline(always)]
+fn sxt_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    input_width: RegisterWidth,
+    dst: AArch64GeneralReg,
+    src: AArch64GeneralReg,
+) {
+    let imms = match input_width {
+        RegisterWidth::W8 => 7,
+        RegisterWidth::W16 => 15,
+        RegisterWidth::W32 => 31,
+        RegisterWidth::W64 => internal_error!("cannot sign extend a 64 bit value"),
+    };
+
+    // An alias of `SBFM Xd, Xn, #0, #imms`
+    let inst = Bitfield::new(BitfieldParams {
+        opc: 0b00,
+        sf: true,
+        immr: 0,
+        imms,
+        rn: src,
+        rd: dst,
+    });
+
+    buf.extend(inst.bytes());
+}
+
 /// `UDIV Xd, Xn, Xm` -> Divide Xn by Xm and place the result into Xd.
 /// Xn, Xm, and Xd are unsigned integers.
 #[inline(always)]
@@ -2829,6 +3389,35 @@ fn udiv_reg64_reg64_reg64(
     buf.extend(inst.bytes());
 }
 
+/// `UXTB/UXTH Wd, Wn`, `UBFX Xd, Xn, #0, #32` -> Zero extend the lowest byte/halfword/word of Xn and place the result into Xd.
+#[inline(always)]
+fn uxt_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    input_width: RegisterWidth,
+    dst: AArch64GeneralReg,
+    src: AArch64GeneralReg,
+) {
+    // Writing to a W register clears the upper 32 bits, so UXTB and UXTH only need 32 bit operands.
+    let (sf, imms) = match input_width {
+        RegisterWidth::W8 => (false, 7),
+        RegisterWidth::W16 => (false, 15),
+        RegisterWidth::W32 => (true, 31),
+        RegisterWidth::W64 => internal_error!("cannot zero extend a 64 bit value"),
+    };
+
+    // An alias of `UBFM Xd, Xn, #0, #imms`
+    let inst = Bitfield::new(BitfieldParams {
+        opc: 0b10,
+        sf,
+        immr: 0,
+        imms,
+        rn: src,
+        rd: dst,
+    });
+
+    buf.extend(inst.bytes());
+}
+
 // Floating point (and advanced SIMD) instructions
 // ARM manual section C7
 
@@ -3052,6 +3641,29 @@ fn encode_f64_to_imm8(imm: f64) -> Option<u8> {
     Some((ret_sign | ret_exp_first | ret_exp_last | ret_frac) as u8)
 }
 
+/// `FMOV Sd, Wn` / `FMOV Dd, Xn` -> Move the bits of Wn/Xn into Sd/Dd.
+#[inline(always)]
+fn fmov_freg_reg64(
+    buf: &mut Vec<'_, u8>,
+    ftype: FloatWidth,
+    dst: AArch64FloatReg,
+    src: AArch64GeneralReg,
+) {
+    let mut inst = ConversionBetweenFloatingPointAndInteger::new(
+        ConversionBetweenFloatingPointAndIntegerParams {
+            opcode: 0b111,
+            rmode: 0b00,
+            ptype: ftype,
+            rd: dst,
+            rn: src,
+        },
+    );
+    // The register widths must match
+    inst.sf = ftype == FloatWidth::F64;
+
+    buf.extend(inst.bytes());
+}
+
 /// `FMOV Sd/Dd, imm8` -> Move imm8 to a float register.
 /// imm8 is a float encoded using encode_f32_to_imm8 or encode_f64_to_imm8.
 #[inline(always)]
@@ -3065,6 +3677,32 @@ fn fmov_freg_imm8(buf: &mut Vec<'_, u8>, ftype: FloatWidth, dst: AArch64FloatReg
     buf.extend(inst.bytes());
 }
 
+/// `FMOV Wd, Sn` / `FMOV Xd, Dn` -> Move the bits of Sn/Dn into Wd/Xd.
+#[inline(always)]
+fn fmov_reg64_freg(
+    buf: &mut Vec<'_, u8>,
+    ftype: FloatWidth,
+    dst: AArch64GeneralReg,
+    src: AArch64FloatReg,
+) {
+    // The encoding is the same as `FMOV Dd, Xn` with the opcode changed and the operands swapped.
+    let inst = ConversionBetweenFloatingPointAndInteger {
+        sf: ftype == FloatWidth::F64,
+        fixed: false,
+        s: false,
+        fixed2: 0b11110.into(),
+        ptype: encode_float_width(ftype).into(),
+        fixed3: true,
+        rmode: 0b00.into(),
+        opcode: 0b110.into(),
+        fixed4: 0b000000.into(),
+        rn: src.id().into(),
+        rd: dst.id().into(),
+    };
+
+    buf.extend(inst.bytes());
+}
+
 /// `FMUL Sd/Dd, Sn/Dn, Sm/Dm` -> Multiply Sn/Dn by Sm/Dm and store the result in Sd/Dd.
 #[inline(always)]
 fn fmul_freg_freg_freg(
@@ -3105,6 +3743,27 @@ fn fsqrt_freg_freg(
     buf.extend(inst.bytes());
 }
 
+/// `FSUB Sd/Dd, Sn/Dn, Sm/Dm` -> Subtract Sm/Dm from Sn/Dn and place the result into Sd/Dd.
+#[inline(always)]
+fn fsub_freg_freg_freg(
+    buf: &mut Vec<'_, u8>,
+    ftype: FloatWidth,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatingPointDataProcessingTwoSourceParams {
+            opcode: 0b0011,
+            ptype: ftype,
+            rd: dst,
+            rn: src1,
+            rm: src2,
+        });
+
+    buf.extend(inst.bytes());
+}
+
 /// Currently, we're only using MOVI to set a float register to 0.0.
 /// `MOVI Dd, #0.0` -> Move 0.0 to Dd
 #[inline(always)]
@@ -3165,6 +3824,16 @@ mod tests {
         }
     }
 
+    impl AArch64GeneralReg {
+        fn capstone_string_width(&self, width: RegisterWidth) -> String {
+            match (width, self) {
+                (RegisterWidth::W64, _) => self.capstone_string(UsesZR),
+                (_, AArch64GeneralReg::ZRSP) => "wzr".to_owned(),
+                _ => format!("w{}", self.id()),
+            }
+        }
+    }
+
     impl AArch64FloatReg {
         fn capstone_string(&self, float_type: FloatWidth) -> String {
             match float_type {
@@ -3268,6 +3937,31 @@ mod tests {
         ConditionCode::AL,
     ];
 
+    const GENERAL_LOAD_STORE_KINDS: &[(LoadStoreKind, &str, RegisterWidth)] = &[
+        (LoadStoreKind::STRB, "strb", RegisterWidth::W32),
+        (LoadStoreKind::LDRB, "ldrb", RegisterWidth::W32),
+        (LoadStoreKind::LDRSB, "ldrsb", RegisterWidth::W64),
+        (LoadStoreKind::STRH, "strh", RegisterWidth::W32),
+        (LoadStoreKind::LDRH, "ldrh", RegisterWidth::W32),
+        (LoadStoreKind::LDRSH, "ldrsh", RegisterWidth::W64),
+        (LoadStoreKind::STR32, "str", RegisterWidth::W32),
+        (LoadStoreKind::LDR32, "ldr", RegisterWidth::W32),
+        (LoadStoreKind::LDRSW, "ldrsw", RegisterWidth::W64),
+        (LoadStoreKind::STR64, "str", RegisterWidth::W64),
+        (LoadStoreKind::LDR64, "ldr", RegisterWidth::W64),
+    ];
+
+    const FLOAT_LOAD_STORE_KINDS: &[(LoadStoreKind, &str, FloatWidth)] = &[
+        (LoadStoreKind::LDR_S, "ldr", FloatWidth::F32),
+        (LoadStoreKind::STR_D, "str", FloatWidth::F64),
+        (LoadStoreKind::LDR_D, "ldr", FloatWidth::F64),
+    ];
+
+    /// The unscaled variant of a load/store mnemonic, e.g. `ldrsb` -> `ldursb`.
+    fn unscaled_mnemonic(name: &str) -> String {
+        format!("{}ur{}", &name[..2], &name[3..])
+    }
+
     fn setup_capstone_and_arena<T>(
         arena: &bumpalo::Bump,
     ) -> (bumpalo::collections::Vec<T>, Capstone) {
@@ -3302,6 +3996,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_add_reg64_reg64_reg64_extended() {
+        disassembler_test!(
+            add_reg64_reg64_reg64_extended,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| {
+                // With SP as either of the first registers, the UXTX is implied and not printed.
+                let extend = if reg1 == AArch64GeneralReg::ZRSP || reg2 == AArch64GeneralReg::ZRSP {
+                    ""
+                } else {
+                    ", uxtx"
+                };
+                format!(
+                    "add {}, {}, {}{}",
+                    reg1.capstone_string(UsesSP),
+                    reg2.capstone_string(UsesSP),
+                    reg3.capstone_string(UsesZR),
+                    extend
+                )
+            },
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_add_reg64_reg64_imm32() {
+        disassembler_test!(
+            AArch64Assembler::add_reg64_reg64_imm32,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm: i32| {
+                let (dst, src) = (reg1.capstone_string(UsesSP), reg2.capstone_string(UsesSP));
+                let uses_sp = reg1 == AArch64GeneralReg::ZRSP || reg2 == AArch64GeneralReg::ZRSP;
+                let extend = if uses_sp { "" } else { ", uxtx" };
+                match imm {
+                    0x123 => format!("add {dst}, {src}, #0x123"),
+                    0x123456 => format!(
+                        "add {dst}, {src}, #0x123, lsl #12\nadd {dst}, {dst}, #0x456"
+                    ),
+                    -0x123 => format!("sub {dst}, {src}, #0x123"),
+                    0x12345678 => format!(
+                        "mov x16, #0x5678\nmovk x16, #0x1234, lsl #16\nadd {dst}, {src}, x16{extend}"
+                    ),
+                    i32::MIN => format!(
+                        "mov x16, #0\nmovk x16, #0x8000, lsl #16\nsub {dst}, {src}, x16{extend}"
+                    ),
+                    _ => unreachable!(),
+                }
+            },
+            [AArch64GeneralReg::X0, AArch64GeneralReg::ZRSP],
+            [AArch64GeneralReg::X1, AArch64GeneralReg::ZRSP],
+            [0x123, 0x123456, -0x123, 0x12345678, i32::MIN]
+        );
+    }
+
     #[test]
     fn test_add_reg64_reg64_imm12() {
         disassembler_test!(
@@ -3318,6 +4066,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_add_reg64_reg64_imm12_lsl12() {
+        disassembler_test!(
+            add_reg64_reg64_imm12_lsl12,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "add {}, {}, #0x{:x}, lsl #12",
+                reg1.capstone_string(UsesSP),
+                reg2.capstone_string(UsesSP),
+                imm
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
     #[test]
     fn test_and_reg64_reg64_reg64() {
         disassembler_test!(
@@ -3369,6 +4133,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bl_imm26() {
+        disassembler_test!(
+            bl_imm26,
+            |imm| format!("bl #0x{:x}", imm as i64),
+            [0x120, -0x120, (1 << 27) - 4, -(1 << 27)]
+        );
+    }
+
     #[test]
     fn test_cmp_reg64_imm12() {
         disassembler_test!(
@@ -3550,6 +4323,158 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_load_store_reg_reg64_imm12() {
+        disassembler_test!(
+            |buf: &mut Vec<'_, u8>,
+             (kind, _, _): (LoadStoreKind, &str, RegisterWidth),
+             rt: AArch64GeneralReg,
+             base: AArch64GeneralReg,
+             imm12: u16| load_store_reg_reg64_imm12(buf, kind, rt.id(), base, imm12),
+            |(kind, name, width): (LoadStoreKind, &str, RegisterWidth),
+             rt: AArch64GeneralReg,
+             base: AArch64GeneralReg,
+             imm12: u16| format!(
+                "{} {}, [{}, #0x{:x}]",
+                name,
+                rt.capstone_string_width(width),
+                base.capstone_string(UsesSP),
+                imm12 as i32 * kind.bytes()
+            ),
+            GENERAL_LOAD_STORE_KINDS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+        disassembler_test!(
+            |buf: &mut Vec<'_, u8>,
+             (kind, _, _): (LoadStoreKind, &str, FloatWidth),
+             rt: AArch64FloatReg,
+             base: AArch64GeneralReg,
+             imm12: u16| load_store_reg_reg64_imm12(buf, kind, rt.id(), base, imm12),
+            |(kind, name, ftype): (LoadStoreKind, &str, FloatWidth),
+             rt: AArch64FloatReg,
+             base: AArch64GeneralReg,
+             imm12: u16| format!(
+                "{} {}, [{}, #0x{:x}]",
+                name,
+                rt.capstone_string(ftype),
+                base.capstone_string(UsesSP),
+                imm12 as i32 * kind.bytes()
+            ),
+            FLOAT_LOAD_STORE_KINDS,
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
+    #[test]
+    fn test_load_store_reg_reg64_reg64() {
+        disassembler_test!(
+            |buf: &mut Vec<'_, u8>,
+             (kind, _, _): (LoadStoreKind, &str, RegisterWidth),
+             rt: AArch64GeneralReg,
+             base: AArch64GeneralReg,
+             offset: AArch64GeneralReg| load_store_reg_reg64_reg64(
+                buf,
+                kind,
+                rt.id(),
+                base,
+                offset
+            ),
+            |(_, name, width): (LoadStoreKind, &str, RegisterWidth),
+             rt: AArch64GeneralReg,
+             base: AArch64GeneralReg,
+             offset: AArch64GeneralReg| format!(
+                "{} {}, [{}, {}]",
+                name,
+                rt.capstone_string_width(width),
+                base.capstone_string(UsesSP),
+                offset.capstone_string(UsesZR)
+            ),
+            GENERAL_LOAD_STORE_KINDS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+        disassembler_test!(
+            |buf: &mut Vec<'_, u8>,
+             (kind, _, _): (LoadStoreKind, &str, FloatWidth),
+             rt: AArch64FloatReg,
+             base: AArch64GeneralReg,
+             offset: AArch64GeneralReg| load_store_reg_reg64_reg64(
+                buf,
+                kind,
+                rt.id(),
+                base,
+                offset
+            ),
+            |(_, name, ftype): (LoadStoreKind, &str, FloatWidth),
+             rt: AArch64FloatReg,
+             base: AArch64GeneralReg,
+             offset: AArch64GeneralReg| format!(
+                "{} {}, [{}, {}]",
+                name,
+                rt.capstone_string(ftype),
+                base.capstone_string(UsesSP),
+                offset.capstone_string(UsesZR)
+            ),
+            FLOAT_LOAD_STORE_KINDS,
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_load_store_reg_reg64_simm9() {
+        disassembler_test!(
+            |buf: &mut Vec<'_, u8>,
+             (kind, _, _): (LoadStoreKind, &str, RegisterWidth),
+             rt: AArch64GeneralReg,
+             base: AArch64GeneralReg,
+             simm9: i16| load_store_reg_reg64_simm9(buf, kind, rt.id(), base, simm9),
+            |(_, name, width): (LoadStoreKind, &str, RegisterWidth),
+             rt: AArch64GeneralReg,
+             base: AArch64GeneralReg,
+             simm9: i16| format!(
+                "{} {}, [{}, #{}0x{:x}]",
+                unscaled_mnemonic(name),
+                rt.capstone_string_width(width),
+                base.capstone_string(UsesSP),
+                if simm9 < 0 { "-" } else { "" },
+                simm9.unsigned_abs()
+            ),
+            GENERAL_LOAD_STORE_KINDS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [-0x100, -0x10, 0xFF]
+        );
+        disassembler_test!(
+            |buf: &mut Vec<'_, u8>,
+             (kind, _, _): (LoadStoreKind, &str, FloatWidth),
+             rt: AArch64FloatReg,
+             base: AArch64GeneralReg,
+             simm9: i16| load_store_reg_reg64_simm9(buf, kind, rt.id(), base, simm9),
+            |(_, name, ftype): (LoadStoreKind, &str, FloatWidth),
+             rt: AArch64FloatReg,
+             base: AArch64GeneralReg,
+             simm9: i16| format!(
+                "{} {}, [{}, #{}0x{:x}]",
+                unscaled_mnemonic(name),
+                rt.capstone_string(ftype),
+                base.capstone_string(UsesSP),
+                if simm9 < 0 { "-" } else { "" },
+                simm9.unsigned_abs()
+            ),
+            FLOAT_LOAD_STORE_KINDS,
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS,
+            [-0x100, -0x10, 0xFF]
+        );
+    }
+
     #[test]
     fn test_lsl_reg64_reg64_reg64() {
         disassembler_test!(
@@ -3664,6 +4589,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_msub_reg64_reg64_reg64_reg64() {
+        disassembler_test!(
+            msub_reg64_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg,
+             reg2: AArch64GeneralReg,
+             reg3: AArch64GeneralReg,
+             reg4: AArch64GeneralReg| {
+                if reg4 == AArch64GeneralReg::ZRSP {
+                    format!(
+                        "mneg {}, {}, {}",
+                        reg1.capstone_string(UsesZR),
+                        reg2.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesZR)
+                    )
+                } else {
+                    format!(
+                        "msub {}, {}, {}, {}",
+                        reg1.capstone_string(UsesZR),
+                        reg2.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesZR),
+                        reg4.capstone_string(UsesZR)
+                    )
+                }
+            },
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
     #[test]
     fn test_mul_reg64_reg64_reg64() {
         disassembler_test!(
@@ -3752,6 +4709,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sub_reg64_reg64_reg64_extended() {
+        disassembler_test!(
+            sub_reg64_reg64_reg64_extended,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| {
+                // With SP as either of the first registers, the UXTX is implied and not printed.
+                let extend = if reg1 == AArch64GeneralReg::ZRSP || reg2 == AArch64GeneralReg::ZRSP {
+                    ""
+                } else {
+                    ", uxtx"
+                };
+                format!(
+                    "sub {}, {}, {}{}",
+                    reg1.capstone_string(UsesSP),
+                    reg2.capstone_string(UsesSP),
+                    reg3.capstone_string(UsesZR),
+                    extend
+                )
+            },
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
     #[test]
     fn test_sub_reg64_reg64_imm12() {
         disassembler_test!(
@@ -3768,6 +4750,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sub_reg64_reg64_imm12_lsl12() {
+        disassembler_test!(
+            sub_reg64_reg64_imm12_lsl12,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "sub {}, {}, #0x{:x}, lsl #12",
+                reg1.capstone_string(UsesSP),
+                reg2.capstone_string(UsesSP),
+                imm
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
     #[test]
     fn test_sub_reg64_reg64_reg64() {
         disassembler_test!(
@@ -3870,6 +4868,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sxt_reg64_reg64() {
+        disassembler_test!(
+            sxt_reg64_reg64,
+            |width: RegisterWidth, reg1: AArch64GeneralReg, reg2: AArch64GeneralReg| format!(
+                "sxt{} {}, {}",
+                match width {
+                    RegisterWidth::W8 => "b",
+                    RegisterWidth::W16 => "h",
+                    _ => "w",
+                },
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string_width(RegisterWidth::W32)
+            ),
+            [RegisterWidth::W8, RegisterWidth::W16, RegisterWidth::W32],
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
     #[test]
     fn test_udiv_reg64_reg64_reg64() {
         disassembler_test!(
@@ -3888,6 +4906,32 @@ mod tests {
 
     // Float instructions
 
+    #[test]
+    fn test_uxt_reg64_reg64() {
+        disassembler_test!(
+            uxt_reg64_reg64,
+            |width: RegisterWidth, reg1: AArch64GeneralReg, reg2: AArch64GeneralReg| match width {
+                RegisterWidth::W8 | RegisterWidth::W16 => format!(
+                    "uxt{} {}, {}",
+                    match width {
+                        RegisterWidth::W8 => "b",
+                        _ => "h",
+                    },
+                    reg1.capstone_string_width(RegisterWidth::W32),
+                    reg2.capstone_string_width(RegisterWidth::W32)
+                ),
+                _ => format!(
+                    "ubfx {}, {}, #0, #0x20",
+                    reg1.capstone_string(UsesZR),
+                    reg2.capstone_string(UsesZR)
+                ),
+            },
+            [RegisterWidth::W8, RegisterWidth::W16, RegisterWidth::W32],
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
     #[test]
     fn test_fabs_freg_freg() {
         disassembler_test!(
@@ -4151,6 +5195,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fmov_freg_reg64() {
+        disassembler_test!(
+            fmov_freg_reg64,
+            |ftype: FloatWidth, reg1: AArch64FloatReg, reg2: AArch64GeneralReg| format!(
+                "fmov {}, {}",
+                reg1.capstone_string(ftype),
+                match ftype {
+                    FloatWidth::F32 => reg2.capstone_string_width(RegisterWidth::W32),
+                    FloatWidth::F64 => reg2.capstone_string(UsesZR),
+                }
+            ),
+            ALL_FLOAT_TYPES,
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_fmov_reg64_freg() {
+        disassembler_test!(
+            fmov_reg64_freg,
+            |ftype: FloatWidth, reg1: AArch64GeneralReg, reg2: AArch64FloatReg| format!(
+                "fmov {}, {}",
+                match ftype {
+                    FloatWidth::F32 => reg1.capstone_string_width(RegisterWidth::W32),
+                    FloatWidth::F64 => reg1.capstone_string(UsesZR),
+                },
+                reg2.capstone_string(ftype)
+            ),
+            ALL_FLOAT_TYPES,
+            ALL_GENERAL_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
     #[test]
     fn test_fmul_freg_freg_freg() {
         disassembler_test!(
@@ -4186,6 +5266,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fsub_freg_freg_freg() {
+        disassembler_test!(
+            fsub_freg_freg_freg,
+            |ftype: FloatWidth,
+             reg1: AArch64FloatReg,
+             reg2: AArch64FloatReg,
+             reg3: AArch64FloatReg| format!(
+                "fsub {}, {}, {}",
+                reg1.capstone_string(ftype),
+                reg2.capstone_string(ftype),
+                reg3.capstone_string(ftype)
+            ),
+            ALL_FLOAT_TYPES,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
     #[test]
     fn test_movi_freg_zero() {
         disassembler_test!(
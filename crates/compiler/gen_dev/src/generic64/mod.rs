@@ -17,6 +17,7 @@ use roc_mono::layout::{
     TagIdIntType, UnionLayout,
 };
 use roc_mono::low_level::HigherOrder;
+use roc_region::all::LineColumn;
 use roc_target::TargetInfo;
 use std::marker::PhantomData;
 
@@ -520,6 +521,18 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
         src1: GeneralReg,
         src2: GeneralReg,
     );
+    fn sub_freg32_freg32_freg32(
+        buf: &mut Vec<'_, u8>,
+        dst: FloatReg,
+        src1: FloatReg,
+        src2: FloatReg,
+    );
+    fn sub_freg64_freg64_freg64(
+        buf: &mut Vec<'_, u8>,
+        dst: FloatReg,
+        src1: FloatReg,
+        src2: FloatReg,
+    );
 
     fn eq_reg_reg_reg(
         buf: &mut Vec<'_, u8>,
@@ -640,6 +653,9 @@ pub struct Backend64Bit<
     literal_map: MutMap<Symbol, (*const Literal<'a>, *const InLayout<'a>)>,
     join_map: MutMap<JoinPointId, Vec<'a, (u64, u64)>>,
 
+    statement_locations: MutMap<Symbol, LineColumn>,
+    statement_rows: Vec<'a, (u64, LineColumn)>,
+
     storage_manager: StorageManager<'a, 'r, GeneralReg, FloatReg, ASM, CC>,
 }
 
@@ -675,6 +691,8 @@ pub fn new_backend_64bit<
         free_map: MutMap::default(),
         literal_map: MutMap::default(),
         join_map: MutMap::default(),
+        statement_locations: MutMap::default(),
+        statement_rows: bumpalo::vec![in env.arena],
         storage_manager: storage::new_storage_manager(env, target_info),
     }
 }
@@ -750,6 +768,8 @@ impl<
         self.layout_map.clear();
         self.join_map.clear();
         self.free_map.clear();
+        self.statement_locations.clear();
+        self.statement_rows.clear();
         self.buf.clear();
         self.storage_manager.reset();
     }
@@ -774,6 +794,18 @@ impl<
         &mut self.free_map
     }
 
+    fn statement_locations(&mut self) -> &mut MutMap<Symbol, LineColumn> {
+        &mut self.statement_locations
+    }
+
+    fn mark_statement(&mut self, location: LineColumn) {
+        self.statement_rows.push((self.buf.len() as u64, location));
+    }
+
+    fn statement_rows(&self) -> &[(u64, LineColumn)] {
+        &self.statement_rows
+    }
+
     fn finalize(&mut self) -> (Vec<u8>, Vec<Relocation>) {
         let mut out = bumpalo::vec![in self.env.arena];
 
@@ -833,6 +865,11 @@ impl<
         // Add function body.
         out.extend(&self.buf[..self.buf.len() - end_jmp_size]);
 
+        // Line table rows point into the body, which now comes after the stack setup.
+        for (offset, _) in self.statement_rows.iter_mut() {
+            *offset += setup_offset as u64;
+        }
+
         // Cleanup stack.
         CC::cleanup_stack(
             &mut out,
@@ -1123,11 +1160,55 @@ impl<
                 let src_reg = self.storage_manager.load_to_general_reg(&mut self.buf, src);
                 ASM::abs_reg64_reg64(&mut self.buf, dst_reg, src_reg);
             }
+            LayoutRepr::Builtin(Builtin::Int(
+                int_width @ (IntWidth::I32 | IntWidth::I16 | IntWidth::I8),
+            )) => {
+                let dst_reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
+                let src_reg = self.storage_manager.load_to_general_reg(&mut self.buf, src);
+
+                let register_width = match int_width {
+                    IntWidth::I32 => RegisterWidth::W32,
+                    IntWidth::I16 => RegisterWidth::W16,
+                    _ => RegisterWidth::W8,
+                };
+
+                // the upper bits of a small integer are not guaranteed to be a sign extension
+                self.storage_manager
+                    .with_tmp_general_reg(&mut self.buf, |_, buf, tmp_reg| {
+                        ASM::movsx_reg_reg(buf, register_width, tmp_reg, src_reg);
+                        ASM::abs_reg64_reg64(buf, dst_reg, tmp_reg);
+                    });
+            }
+            LayoutRepr::Builtin(Builtin::Int(IntWidth::U32 | IntWidth::U16 | IntWidth::U8)) => {
+                let dst_reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
+                let src_reg = self.storage_manager.load_to_general_reg(&mut self.buf, src);
+                ASM::mov_reg64_reg64(&mut self.buf, dst_reg, src_reg);
+            }
+            LayoutRepr::Builtin(Builtin::Int(IntWidth::U128)) => {
+                let base_offset = self.storage_manager.claim_stack_area(dst, 16);
+                self.storage_manager.copy_symbol_to_stack_offset(
+                    self.layout_interner,
+                    &mut self.buf,
+                    base_offset,
+                    src,
+                    layout,
+                );
+            }
+            LayoutRepr::Builtin(Builtin::Int(IntWidth::I128) | Builtin::Decimal) => {
+                self.build_int128_abs(dst, src);
+            }
             LayoutRepr::Builtin(Builtin::Float(FloatWidth::F64)) => {
                 let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
                 let src_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src);
                 ASM::abs_freg64_freg64(&mut self.buf, &mut self.relocs, dst_reg, src_reg);
             }
+            LayoutRepr::Builtin(Builtin::Float(FloatWidth::F32)) => self.build_fn_call(
+                dst,
+                bitcode::NUM_FABS[FloatWidth::F32].to_string(),
+                &[*src],
+                &[*layout],
+                layout,
+            ),
             x => todo!("NumAbs: layout, {:?}", x),
         }
     }
@@ -1156,6 +1237,18 @@ impl<
                 let src2_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src2);
                 ASM::add_freg32_freg32_freg32(&mut self.buf, dst_reg, src1_reg, src2_reg);
             }
+            LayoutRepr::Builtin(Builtin::Int(IntWidth::I128 | IntWidth::U128)) => {
+                self.build_int128_add_sub(dst, src1, src2, false);
+            }
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_ADD_OR_PANIC.to_string(),
+                    &[*src1, *src2],
+                    &[*layout, *layout],
+                    layout,
+                );
+            }
             x => todo!("NumAdd: layout, {:?}", x),
         }
     }
@@ -1202,16 +1295,16 @@ impl<
     ) {
         use Builtin::Int;
 
-        let buf = &mut self.buf;
-
-        let struct_size = self.layout_interner.stack_size(*return_layout);
-
-        let base_offset = self.storage_manager.claim_stack_area(dst, struct_size);
-
-        match self.layout_interner.get_repr(*num_layout) {
+        let function_name = match self.layout_interner.get_repr(*num_layout) {
             LayoutRepr::Builtin(Int(
                 IntWidth::I64 | IntWidth::I32 | IntWidth::I16 | IntWidth::I8,
             )) => {
+                let buf = &mut self.buf;
+
+                let struct_size = self.layout_interner.stack_size(*return_layout);
+
+                let base_offset = self.storage_manager.claim_stack_area(dst, struct_size);
+
                 let dst_reg = self
                     .storage_manager
                     .claim_general_reg(buf, &Symbol::DEV_TMP);
@@ -1231,20 +1324,22 @@ impl<
 
                 self.free_symbol(&Symbol::DEV_TMP);
                 self.free_symbol(&Symbol::DEV_TMP2);
+
+                return;
             }
-            LayoutRepr::Builtin(Int(
-                IntWidth::U64 | IntWidth::U32 | IntWidth::U16 | IntWidth::U8,
-            )) => {
-                todo!("addChecked for unsigned integers")
-            }
-            LayoutRepr::Builtin(Builtin::Float(FloatWidth::F64)) => {
-                todo!("addChecked for f64")
-            }
-            LayoutRepr::Builtin(Builtin::Float(FloatWidth::F32)) => {
-                todo!("addChecked for f32")
-            }
-            x => todo!("NumAdd: layout, {:?}", x),
-        }
+            LayoutRepr::Builtin(Int(width)) => &bitcode::NUM_ADD_CHECKED_INT[width],
+            LayoutRepr::Builtin(Builtin::Float(width)) => &bitcode::NUM_ADD_CHECKED_FLOAT[width],
+            LayoutRepr::Builtin(Builtin::Decimal) => bitcode::DEC_ADD_WITH_OVERFLOW,
+            x => internal_error!("NumAddChecked is not defined for {:?}", x),
+        };
+
+        self.build_fn_call(
+            dst,
+            function_name.to_string(),
+            &[*src1, *src2],
+            &[*num_layout, *num_layout],
+            return_layout,
+        )
     }
 
     fn build_num_sub_checked(
@@ -1345,6 +1440,15 @@ impl<
                 let src2_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src2);
                 ASM::mul_freg32_freg32_freg32(&mut self.buf, dst_reg, src1_reg, src2_reg);
             }
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_MUL_OR_PANIC.to_string(),
+                    &[*src1, *src2],
+                    &[*layout, *layout],
+                    layout,
+                );
+            }
             x => todo!("NumMulWrap: layout, {:?}", x),
         }
     }
@@ -1401,6 +1505,15 @@ impl<
                 let src2_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src2);
                 ASM::div_freg32_freg32_freg32(&mut self.buf, dst_reg, src1_reg, src2_reg);
             }
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_DIV.to_string(),
+                    &[*src1, *src2],
+                    &[*layout, *layout],
+                    layout,
+                );
+            }
             x => todo!("NumDiv: layout, {:?}", x),
         }
     }
@@ -1451,11 +1564,41 @@ impl<
 
     fn build_num_neg(&mut self, dst: &Symbol, src: &Symbol, layout: &InLayout<'a>) {
         match self.layout_interner.get_repr(*layout) {
-            LayoutRepr::Builtin(Builtin::Int(IntWidth::I64 | IntWidth::U64)) => {
+            LayoutRepr::Builtin(Builtin::Int(quadword_and_smaller!())) => {
                 let dst_reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
                 let src_reg = self.storage_manager.load_to_general_reg(&mut self.buf, src);
                 ASM::neg_reg64_reg64(&mut self.buf, dst_reg, src_reg);
             }
+            LayoutRepr::Builtin(Builtin::Int(IntWidth::I128 | IntWidth::U128)) => {
+                self.build_int128_neg(dst, src);
+            }
+            LayoutRepr::Builtin(Builtin::Float(float_width)) => {
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
+                let src_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src);
+
+                // multiplying by -1 (rather than subtracting from 0) also flips the sign of zero
+                let relocs = &mut self.relocs;
+                self.storage_manager
+                    .with_tmp_float_reg(&mut self.buf, |_, buf, tmp_reg| match float_width {
+                        FloatWidth::F32 => {
+                            ASM::mov_freg32_imm32(buf, relocs, tmp_reg, -1.0);
+                            ASM::mul_freg32_freg32_freg32(buf, dst_reg, src_reg, tmp_reg);
+                        }
+                        FloatWidth::F64 => {
+                            ASM::mov_freg64_imm64(buf, relocs, tmp_reg, -1.0);
+                            ASM::mul_freg64_freg64_freg64(buf, dst_reg, src_reg, tmp_reg);
+                        }
+                    });
+            }
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_NEGATE.to_string(),
+                    &[*src],
+                    &[*layout],
+                    layout,
+                );
+            }
             x => todo!("NumNeg: layout, {:?}", x),
         }
     }
@@ -1484,6 +1627,30 @@ impl<
                     .load_to_general_reg(&mut self.buf, src2);
                 ASM::sub_reg64_reg64_reg64(&mut self.buf, dst_reg, src1_reg, src2_reg);
             }
+            LayoutRepr::Builtin(Builtin::Int(IntWidth::I128 | IntWidth::U128)) => {
+                self.build_int128_add_sub(dst, src1, src2, true);
+            }
+            LayoutRepr::Builtin(Builtin::Float(FloatWidth::F64)) => {
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
+                let src1_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src1);
+                let src2_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src2);
+                ASM::sub_freg64_freg64_freg64(&mut self.buf, dst_reg, src1_reg, src2_reg);
+            }
+            LayoutRepr::Builtin(Builtin::Float(FloatWidth::F32)) => {
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
+                let src1_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src1);
+                let src2_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src2);
+                ASM::sub_freg32_freg32_freg32(&mut self.buf, dst_reg, src1_reg, src2_reg);
+            }
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_SUB_OR_PANIC.to_string(),
+                    &[*src1, *src2],
+                    &[*layout, *layout],
+                    layout,
+                );
+            }
             x => todo!("NumSubWrap: layout, {:?}", x),
         }
     }
@@ -1509,7 +1676,7 @@ impl<
                     .load_to_general_reg(&mut self.buf, src2);
                 ASM::eq_reg_reg_reg(&mut self.buf, width, dst_reg, src1_reg, src2_reg);
             }
-            LayoutRepr::U128 | LayoutRepr::I128 => {
+            LayoutRepr::U128 | LayoutRepr::I128 | LayoutRepr::DEC => {
                 let dst_reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
 
                 // put the arguments on the stack
@@ -1560,7 +1727,6 @@ impl<
 
                 ASM::eq_freg_freg_reg64(&mut self.buf, dst_reg, src_reg1, src_reg2, float_width)
             }
-            LayoutRepr::DEC => todo!("NumEq: layout, {:?}", self.layout_interner.dbg(Layout::DEC)),
             LayoutRepr::STR => {
                 // use a zig call
                 self.build_fn_call(
@@ -1721,6 +1887,39 @@ impl<
                 let src_reg = self.storage_manager.load_to_general_reg(&mut self.buf, src);
                 ASM::to_float_freg32_reg64(&mut self.buf, dst_reg, src_reg);
             }
+            (
+                LayoutRepr::Builtin(Builtin::Int(
+                    int_width @ (IntWidth::I8
+                    | IntWidth::I16
+                    | IntWidth::U8
+                    | IntWidth::U16
+                    | IntWidth::U32),
+                )),
+                LayoutRepr::Builtin(Builtin::Float(float_width)),
+            ) => {
+                let src_reg = self.storage_manager.load_to_general_reg(&mut self.buf, src);
+
+                // extend to 64 bits first; every value of these widths fits in an I64
+                self.storage_manager
+                    .with_tmp_general_reg(&mut self.buf, |_, buf, tmp_reg| {
+                        let register_width = match int_width.stack_size() {
+                            4 => RegisterWidth::W32,
+                            2 => RegisterWidth::W16,
+                            _ => RegisterWidth::W8,
+                        };
+
+                        if int_width.is_signed() {
+                            ASM::movsx_reg_reg(buf, register_width, tmp_reg, src_reg);
+                        } else {
+                            ASM::movzx_reg_reg(buf, register_width, tmp_reg, src_reg);
+                        }
+
+                        match float_width {
+                            FloatWidth::F32 => ASM::to_float_freg32_reg64(buf, dst_reg, tmp_reg),
+                            FloatWidth::F64 => ASM::to_float_freg64_reg64(buf, dst_reg, tmp_reg),
+                        }
+                    });
+            }
             (
                 LayoutRepr::Builtin(Builtin::Float(FloatWidth::F64)),
                 LayoutRepr::Builtin(Builtin::Float(FloatWidth::F32)),
@@ -3540,7 +3739,9 @@ impl<
         let buf = &mut self.buf;
 
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => {
+                self.build_int128_bitwise(dst, src1, src2, ASM::and_reg64_reg64_reg64)
+            }
             _ => {
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
@@ -3560,7 +3761,9 @@ impl<
         let buf = &mut self.buf;
 
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => {
+                self.build_int128_bitwise(dst, src1, src2, ASM::or_reg64_reg64_reg64)
+            }
             _ => {
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
@@ -3580,7 +3783,9 @@ impl<
         let buf = &mut self.buf;
 
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => {
+                self.build_int128_bitwise(dst, src1, src2, ASM::xor_reg64_reg64_reg64)
+            }
             _ => {
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
@@ -3749,30 +3954,72 @@ impl<
         let buf = &mut self.buf;
 
         match (source, target) {
-            (U128, U64) => {
+            (U128 | I128, U128 | I128) => {
+                let base_offset = self.storage_manager.claim_stack_area(dst, 16);
+                let (src_offset, size) = self.storage_manager.stack_offset_and_size(src);
+                debug_assert_eq!(size, 16);
+
+                self.storage_manager
+                    .with_tmp_general_reg(buf, |_, buf, tmp_reg| {
+                        ASM::mov_reg64_base32(buf, tmp_reg, src_offset);
+                        ASM::mov_base32_reg64(buf, base_offset, tmp_reg);
+                        ASM::mov_reg64_base32(buf, tmp_reg, src_offset + 8);
+                        ASM::mov_base32_reg64(buf, base_offset + 8, tmp_reg);
+                    });
+
+                return;
+            }
+            (U128 | I128, _) => {
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
 
                 let (offset, _size) = self.storage_manager.stack_offset_and_size(src);
 
-                ASM::mov_reg64_base32(buf, dst_reg, offset + 8);
+                // the lower 8 bytes hold everything a smaller integer can represent
+                ASM::mov_reg64_base32(buf, dst_reg, offset);
 
                 return;
             }
-            (U64, U128) => {
+            (_, U128 | I128) => {
                 let src_reg = self.storage_manager.load_to_general_reg(buf, src);
 
                 let base_offset = self.storage_manager.claim_stack_area(dst, 16);
 
-                let tmp = Symbol::DEV_TMP;
-                let tmp_reg = self.storage_manager.claim_general_reg(buf, &tmp);
+                let register_width = match source.stack_size() {
+                    8 => RegisterWidth::W64,
+                    4 => RegisterWidth::W32,
+                    2 => RegisterWidth::W16,
+                    _ => RegisterWidth::W8,
+                };
 
-                // move a zero into the lower 8 bytes
-                ASM::mov_reg64_imm64(buf, tmp_reg, 0x0);
-                ASM::mov_base32_reg64(buf, base_offset, tmp_reg);
+                let low = Symbol::DEV_TMP;
+                let low_reg = self.storage_manager.claim_general_reg(buf, &low);
+                let high = Symbol::DEV_TMP2;
+                let high_reg = self.storage_manager.claim_general_reg(buf, &high);
 
-                ASM::mov_base32_reg64(buf, base_offset + 8, src_reg);
+                if source.is_signed() {
+                    ASM::movsx_reg_reg(buf, register_width, low_reg, src_reg);
 
-                self.free_symbol(&tmp);
+                    // the upper 8 bytes are all ones for negative numbers, and zero otherwise
+                    ASM::mov_reg64_imm64(buf, high_reg, 0x0);
+                    ASM::signed_compare_reg64(
+                        buf,
+                        RegisterWidth::W64,
+                        CompareOperation::LessThan,
+                        high_reg,
+                        low_reg,
+                        high_reg,
+                    );
+                    ASM::neg_reg64_reg64(buf, high_reg, high_reg);
+                } else {
+                    ASM::movzx_reg_reg(buf, register_width, low_reg, src_reg);
+                    ASM::mov_reg64_imm64(buf, high_reg, 0x0);
+                }
+
+                ASM::mov_base32_reg64(buf, base_offset, low_reg);
+                ASM::mov_base32_reg64(buf, base_offset + 8, high_reg);
+
+                self.free_symbol(&low);
+                self.free_symbol(&high);
 
                 return;
             }
@@ -3901,10 +4148,228 @@ impl<
                     op,
                 );
             }
+            LayoutRepr::U128 => self.build_int128_compare(op, dst, src1, src2, false),
+            LayoutRepr::I128 | LayoutRepr::DEC => {
+                self.build_int128_compare(op, dst, src1, src2, true)
+            }
             x => todo!("NumLt: layout, {:?}", x),
         }
     }
 
+    /// 128-bit integers live on the stack as two 8-byte halves, the lower half first.
+    /// This claims a register for each of the four temporaries the 128-bit helpers below need.
+    fn claim_int128_tmp_regs(&mut self) -> [GeneralReg; 4] {
+        let buf = &mut self.buf;
+
+        [
+            self.storage_manager
+                .claim_general_reg(buf, &Symbol::DEV_TMP),
+            self.storage_manager
+                .claim_general_reg(buf, &Symbol::DEV_TMP2),
+            self.storage_manager
+                .claim_general_reg(buf, &Symbol::DEV_TMP3),
+            self.storage_manager
+                .claim_general_reg(buf, &Symbol::DEV_TMP4),
+        ]
+    }
+
+    fn free_int128_tmp_regs(&mut self) {
+        self.free_symbol(&Symbol::DEV_TMP);
+        self.free_symbol(&Symbol::DEV_TMP2);
+        self.free_symbol(&Symbol::DEV_TMP3);
+        self.free_symbol(&Symbol::DEV_TMP4);
+    }
+
+    fn build_int128_add_sub(&mut self, dst: &Symbol, src1: &Symbol, src2: &Symbol, is_sub: bool) {
+        let base_offset = self.storage_manager.claim_stack_area(dst, 16);
+        let (src1_offset, _) = self.storage_manager.stack_offset_and_size(src1);
+        let (src2_offset, _) = self.storage_manager.stack_offset_and_size(src2);
+
+        let [lhs, rhs, result, carry] = self.claim_int128_tmp_regs();
+        let buf = &mut self.buf;
+
+        // lower half, remembering the carry (or borrow) going into the upper half
+        ASM::mov_reg64_base32(buf, lhs, src1_offset);
+        ASM::mov_reg64_base32(buf, rhs, src2_offset);
+        if is_sub {
+            ASM::sub_reg64_reg64_reg64(buf, result, lhs, rhs);
+            ASM::unsigned_compare_reg64(
+                buf,
+                RegisterWidth::W64,
+                CompareOperation::LessThan,
+                carry,
+                lhs,
+                rhs,
+            );
+        } else {
+            ASM::add_reg64_reg64_reg64(buf, result, lhs, rhs);
+            ASM::unsigned_compare_reg64(
+                buf,
+                RegisterWidth::W64,
+                CompareOperation::LessThan,
+                carry,
+                result,
+                lhs,
+            );
+        }
+        ASM::mov_base32_reg64(buf, base_offset, result);
+
+        // upper half
+        ASM::mov_reg64_base32(buf, lhs, src1_offset + 8);
+        ASM::mov_reg64_base32(buf, rhs, src2_offset + 8);
+        if is_sub {
+            ASM::sub_reg64_reg64_reg64(buf, result, lhs, rhs);
+            ASM::sub_reg64_reg64_reg64(buf, result, result, carry);
+        } else {
+            ASM::add_reg64_reg64_reg64(buf, result, lhs, rhs);
+            ASM::add_reg64_reg64_reg64(buf, result, result, carry);
+        }
+        ASM::mov_base32_reg64(buf, base_offset + 8, result);
+
+        self.free_int128_tmp_regs();
+    }
+
+    fn build_int128_neg(&mut self, dst: &Symbol, src: &Symbol) {
+        let base_offset = self.storage_manager.claim_stack_area(dst, 16);
+        let (src_offset, _) = self.storage_manager.stack_offset_and_size(src);
+
+        let [value, zero, result, borrow] = self.claim_int128_tmp_regs();
+        let buf = &mut self.buf;
+
+        ASM::mov_reg64_imm64(buf, zero, 0);
+
+        // 0 - lower half borrows from the upper half unless the lower half is 0
+        ASM::mov_reg64_base32(buf, value, src_offset);
+        ASM::neg_reg64_reg64(buf, result, value);
+        ASM::unsigned_compare_reg64(
+            buf,
+            RegisterWidth::W64,
+            CompareOperation::LessThan,
+            borrow,
+            zero,
+            value,
+        );
+        ASM::mov_base32_reg64(buf, base_offset, result);
+
+        ASM::mov_reg64_base32(buf, value, src_offset + 8);
+        ASM::neg_reg64_reg64(buf, result, value);
+        ASM::sub_reg64_reg64_reg64(buf, result, result, borrow);
+        ASM::mov_base32_reg64(buf, base_offset + 8, result);
+
+        self.free_int128_tmp_regs();
+    }
+
+    fn build_int128_abs(&mut self, dst: &Symbol, src: &Symbol) {
+        let base_offset = self.storage_manager.claim_stack_area(dst, 16);
+        let (src_offset, _) = self.storage_manager.stack_offset_and_size(src);
+
+        let [low, high, mask, tmp] = self.claim_int128_tmp_regs();
+        let buf = &mut self.buf;
+
+        // mask is all ones for negative numbers and zero otherwise; abs(x) = (x ^ mask) - mask
+        ASM::mov_reg64_base32(buf, high, src_offset + 8);
+        ASM::mov_reg64_imm64(buf, tmp, 0);
+        ASM::signed_compare_reg64(
+            buf,
+            RegisterWidth::W64,
+            CompareOperation::LessThan,
+            mask,
+            high,
+            tmp,
+        );
+        ASM::neg_reg64_reg64(buf, mask, mask);
+
+        ASM::mov_reg64_base32(buf, low, src_offset);
+        ASM::xor_reg64_reg64_reg64(buf, low, low, mask);
+        ASM::xor_reg64_reg64_reg64(buf, high, high, mask);
+
+        ASM::unsigned_compare_reg64(
+            buf,
+            RegisterWidth::W64,
+            CompareOperation::LessThan,
+            tmp,
+            low,
+            mask,
+        );
+        ASM::sub_reg64_reg64_reg64(buf, low, low, mask);
+        ASM::sub_reg64_reg64_reg64(buf, high, high, mask);
+        ASM::sub_reg64_reg64_reg64(buf, high, high, tmp);
+
+        ASM::mov_base32_reg64(buf, base_offset, low);
+        ASM::mov_base32_reg64(buf, base_offset + 8, high);
+
+        self.free_int128_tmp_regs();
+    }
+
+    fn build_int128_bitwise(
+        &mut self,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        op: fn(&mut Vec<'a, u8>, GeneralReg, GeneralReg, GeneralReg),
+    ) {
+        let base_offset = self.storage_manager.claim_stack_area(dst, 16);
+        let (src1_offset, _) = self.storage_manager.stack_offset_and_size(src1);
+        let (src2_offset, _) = self.storage_manager.stack_offset_and_size(src2);
+
+        let [lhs, rhs, _, _] = self.claim_int128_tmp_regs();
+        let buf = &mut self.buf;
+
+        for half in [0, 8] {
+            ASM::mov_reg64_base32(buf, lhs, src1_offset + half);
+            ASM::mov_reg64_base32(buf, rhs, src2_offset + half);
+            op(buf, lhs, lhs, rhs);
+            ASM::mov_base32_reg64(buf, base_offset + half, lhs);
+        }
+
+        self.free_int128_tmp_regs();
+    }
+
+    fn build_int128_compare(
+        &mut self,
+        op: CompareOperation,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        signed: bool,
+    ) {
+        let dst_reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
+        let (src1_offset, _) = self.storage_manager.stack_offset_and_size(src1);
+        let (src2_offset, _) = self.storage_manager.stack_offset_and_size(src2);
+
+        let [lhs, rhs, high_equal, _] = self.claim_int128_tmp_regs();
+        let buf = &mut self.buf;
+
+        // the upper halves decide the result, unless they are equal
+        let strict_op = match op {
+            CompareOperation::LessThan | CompareOperation::LessThanOrEqual => {
+                CompareOperation::LessThan
+            }
+            CompareOperation::GreaterThan | CompareOperation::GreaterThanOrEqual => {
+                CompareOperation::GreaterThan
+            }
+        };
+
+        ASM::mov_reg64_base32(buf, lhs, src1_offset + 8);
+        ASM::mov_reg64_base32(buf, rhs, src2_offset + 8);
+        if signed {
+            ASM::signed_compare_reg64(buf, RegisterWidth::W64, strict_op, dst_reg, lhs, rhs);
+        } else {
+            ASM::unsigned_compare_reg64(buf, RegisterWidth::W64, strict_op, dst_reg, lhs, rhs);
+        }
+        ASM::eq_reg64_reg64_reg64(buf, high_equal, lhs, rhs);
+
+        // the lower halves are always compared as unsigned integers
+        ASM::mov_reg64_base32(buf, lhs, src1_offset);
+        ASM::mov_reg64_base32(buf, rhs, src2_offset);
+        ASM::unsigned_compare_reg64(buf, RegisterWidth::W64, op, lhs, lhs, rhs);
+
+        ASM::and_reg64_reg64_reg64(buf, high_equal, high_equal, lhs);
+        ASM::or_reg64_reg64_reg64(buf, dst_reg, dst_reg, high_equal);
+
+        self.free_int128_tmp_regs();
+    }
+
     fn allocate_with_refcount(
         &mut self,
         dst: Symbol,
@@ -253,7 +253,7 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Syste
 
     #[inline(always)]
     fn load_args<'a>(
-        _buf: &mut Vec<'a, u8>,
+        buf: &mut Vec<'a, u8>,
         storage_manager: &mut StorageManager<
             'a,
             '_,
@@ -281,7 +281,7 @@ impl CallConv<X86_64GeneralReg, X86_64FloatReg, X86_64Assembler> for X86_64Syste
         }
 
         for (in_layout, sym) in args.iter() {
-            state.load_arg(storage_manager, layout_interner, *sym, *in_layout);
+            state.load_arg(buf, storage_manager, layout_interner, *sym, *in_layout);
         }
     }
 
@@ -463,7 +463,7 @@ impl X64_64SystemVStoreArgs {
             single_register_integers!() => self.store_arg_general(buf, storage_manager, sym),
             pointer_layouts!() => self.store_arg_general(buf, storage_manager, sym),
             single_register_floats!() => self.store_arg_float(buf, storage_manager, sym),
-            LayoutRepr::I128 | LayoutRepr::U128 => {
+            LayoutRepr::I128 | LayoutRepr::U128 | LayoutRepr::DEC => {
                 let (offset, _) = storage_manager.stack_offset_and_size(&sym);
 
                 if self.general_i + 1 < Self::GENERAL_PARAM_REGS.len() {
@@ -648,6 +648,7 @@ type X86_64StorageManager<'a, 'r, CallConv> =
 impl X64_64SystemVLoadArgs {
     fn load_arg<'a>(
         &mut self,
+        buf: &mut Vec<'a, u8>,
         storage_manager: &mut X86_64StorageManager<'a, '_, X86_64SystemV>,
         layout_interner: &mut STLayoutInterner<'a>,
         sym: Symbol,
@@ -667,6 +668,7 @@ impl X64_64SystemVLoadArgs {
                 self.argument_offset += stack_size as i32;
             }
             LayoutRepr::LambdaSet(lambda_set) => self.load_arg(
+                buf,
                 storage_manager,
                 layout_interner,
                 sym,
@@ -677,9 +679,23 @@ impl X64_64SystemVLoadArgs {
                 storage_manager.complex_stack_arg(&sym, self.argument_offset, stack_size);
                 self.argument_offset += stack_size as i32;
             }
-            LayoutRepr::Builtin(Builtin::Int(IntWidth::U128 | IntWidth::I128)) => {
-                storage_manager.complex_stack_arg(&sym, self.argument_offset, stack_size);
-                self.argument_offset += stack_size as i32;
+            LayoutRepr::Builtin(Builtin::Int(IntWidth::U128 | IntWidth::I128))
+            | LayoutRepr::Builtin(Builtin::Decimal) => {
+                // passed in two general registers if both are available, mirroring `store_arg`
+                if self.general_i + 1 < X86_64SystemV::GENERAL_PARAM_REGS.len() {
+                    let reg1 = X86_64SystemV::GENERAL_PARAM_REGS[self.general_i];
+                    let reg2 = X86_64SystemV::GENERAL_PARAM_REGS[self.general_i + 1];
+
+                    let offset = storage_manager.claim_stack_area(&sym, 16);
+
+                    X86_64Assembler::mov_base32_reg64(buf, offset, reg1);
+                    X86_64Assembler::mov_base32_reg64(buf, offset + 8, reg2);
+
+                    self.general_i += 2;
+                } else {
+                    storage_manager.complex_stack_arg(&sym, self.argument_offset, stack_size);
+                    self.argument_offset += stack_size as i32;
+                }
             }
             LayoutRepr::Union(UnionLayout::NonRecursive(_)) => {
                 // for now, just also store this on the stack
@@ -1771,6 +1787,34 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         mov_reg64_reg64(buf, dst, src1);
         sub_reg64_reg64(buf, dst, src2);
     }
+    #[inline(always)]
+    fn sub_freg32_freg32_freg32(
+        buf: &mut Vec<'_, u8>,
+        dst: X86_64FloatReg,
+        src1: X86_64FloatReg,
+        src2: X86_64FloatReg,
+    ) {
+        debug_assert!(dst == src1 || dst != src2);
+
+        if dst != src1 {
+            movss_freg32_freg32(buf, dst, src1);
+        }
+        subss_freg32_freg32(buf, dst, src2);
+    }
+    #[inline(always)]
+    fn sub_freg64_freg64_freg64(
+        buf: &mut Vec<'_, u8>,
+        dst: X86_64FloatReg,
+        src1: X86_64FloatReg,
+        src2: X86_64FloatReg,
+    ) {
+        debug_assert!(dst == src1 || dst != src2);
+
+        if dst != src1 {
+            movsd_freg64_freg64(buf, dst, src1);
+        }
+        subsd_freg64_freg64(buf, dst, src2);
+    }
 
     #[inline(always)]
     fn eq_reg_reg_reg(
@@ -2360,6 +2404,46 @@ fn divsd_freg64_freg64(buf: &mut Vec<'_, u8>, dst: X86_64FloatReg, src: X86_64Fl
     }
 }
 
+/// `SUBSS xmm1,xmm2/m64` -> Subtract the low single-precision floating-point value in xmm2/mem from xmm1 and store the result in xmm1.
+#[inline(always)]
+fn subss_freg32_freg32(buf: &mut Vec<'_, u8>, dst: X86_64FloatReg, src: X86_64FloatReg) {
+    let dst_high = dst as u8 > 7;
+    let dst_mod = dst as u8 % 8;
+    let src_high = src as u8 > 7;
+    let src_mod = src as u8 % 8;
+    if dst_high || src_high {
+        buf.extend([
+            0xF3,
+            0x40 | ((dst_high as u8) << 2) | (src_high as u8),
+            0x0F,
+            0x5C,
+            0xC0 | (dst_mod << 3) | (src_mod),
+        ])
+    } else {
+        buf.extend([0xF3, 0x0F, 0x5C, 0xC0 | (dst_mod << 3) | (src_mod)])
+    }
+}
+
+/// `SUBSD xmm1,xmm2/m64` -> Subtract the low double-precision floating-point value in xmm2/mem from xmm1 and store the result in xmm1.
+#[inline(always)]
+fn subsd_freg64_freg64(buf: &mut Vec<'_, u8>, dst: X86_64FloatReg, src: X86_64FloatReg) {
+    let dst_high = dst as u8 > 7;
+    let dst_mod = dst as u8 % 8;
+    let src_high = src as u8 > 7;
+    let src_mod = src as u8 % 8;
+    if dst_high || src_high {
+        buf.extend([
+            0xF2,
+            0x40 | ((dst_high as u8) << 2) | (src_high as u8),
+            0x0F,
+            0x5C,
+            0xC0 | (dst_mod << 3) | (src_mod),
+        ])
+    } else {
+        buf.extend([0xF2, 0x0F, 0x5C, 0xC0 | (dst_mod << 3) | (src_mod)])
+    }
+}
+
 /// `ADDSS xmm1,xmm2/m64` -> Add the low single-precision floating-point value from xmm2/mem to xmm1 and store the result in xmm1.
 #[inline(always)]
 fn mulss_freg32_freg32(buf: &mut Vec<'_, u8>, dst: X86_64FloatReg, src: X86_64FloatReg) {
@@ -3792,6 +3876,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_subsd_freg64_freg64() {
+        disassembler_test!(
+            subsd_freg64_freg64,
+            |reg1, reg2| format!("subsd {}, {}", reg1, reg2),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_subss_freg32_freg32() {
+        disassembler_test!(
+            subss_freg32_freg32,
+            |reg1, reg2| format!("subss {}, {}", reg1, reg2),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
     #[test]
     fn test_jmp_imm32() {
         const INST_SIZE: i32 = 5;
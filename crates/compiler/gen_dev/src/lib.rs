@@ -16,15 +16,17 @@ use roc_module::low_level::{LowLevel, LowLevelWrapperType};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::code_gen_help::{CallerProc, CodeGenHelp};
 use roc_mono::ir::{
-    BranchInfo, CallType, CrashTag, Expr, HigherOrderLowLevel, JoinPointId, ListLiteralElement,
-    Literal, Param, Proc, ProcLayout, SelfRecursive, Stmt,
+    BranchInfo, CallType, CrashTag, DebugSources, Expr, HigherOrderLowLevel, JoinPointId,
+    ListLiteralElement, Literal, Param, Proc, ProcLayout, SelfRecursive, Stmt,
 };
 use roc_mono::layout::{
     Builtin, InLayout, LambdaName, Layout, LayoutIds, LayoutInterner, LayoutRepr, STLayoutInterner,
     TagIdIntType, UnionLayout,
 };
 use roc_mono::list_element_layout;
+use roc_region::all::LineColumn;
 
+mod debug_info;
 mod generic64;
 mod object_builder;
 pub use object_builder::build_module;
@@ -53,6 +55,8 @@ pub struct Env<'a> {
     pub exposed_to_host: MutSet<Symbol>,
     pub lazy_literals: bool,
     pub mode: AssemblyBackendMode,
+    /// When set, emit DWARF debug info that maps procs back to these sources
+    pub debug_sources: Option<DebugSources>,
 }
 
 // These relocations likely will need a length.
@@ -470,7 +474,16 @@ trait Backend<'a> {
 
         let body = self.env().arena.alloc(proc.body);
 
+        let statement_locations = self
+            .env()
+            .debug_sources
+            .as_ref()
+            .map(|sources| sources.statement_locations(&proc));
+
         self.reset(proc_name, proc.is_self_recursive);
+        if let Some(statement_locations) = statement_locations {
+            *self.statement_locations() = statement_locations;
+        }
         self.load_args(proc.args, &proc.ret_layout);
         for (layout, sym) in proc.args {
             self.set_layout_map(*sym, layout);
@@ -512,6 +525,9 @@ trait Backend<'a> {
     ) {
         match stmt {
             Stmt::Let(sym, expr, layout, following) => {
+                if let Some(location) = self.statement_locations().get(sym).copied() {
+                    self.mark_statement(location);
+                }
                 self.build_expr(sym, expr, layout);
                 self.set_layout_map(*sym, layout);
                 self.free_symbols(stmt);
@@ -2383,6 +2399,16 @@ trait Backend<'a> {
     /// set_free_map sets the free map to the given map.
     fn set_free_map(&mut self, map: MutMap<*const Stmt<'a>, Vec<'a, Symbol>>);
 
+    /// statement_locations maps the symbols bound by the first statement of each def and branch
+    /// to where they are in the source. It is only filled in when emitting debug info.
+    fn statement_locations(&mut self) -> &mut MutMap<Symbol, LineColumn>;
+
+    /// mark_statement starts a line table row for `location` at the current end of the buffer.
+    fn mark_statement(&mut self, location: LineColumn);
+
+    /// statement_rows gets the line table rows of the last proc built, as offsets from its start.
+    fn statement_rows(&self) -> &[(u64, LineColumn)];
+
     /// scan_ast runs through the ast and fill the last seen map.
     /// This must iterate through the ast in the same way that build_stmt does. i.e. then before else.
     fn scan_ast(&mut self, stmt: &'a Stmt<'a>) {
@@ -1,3 +1,4 @@
+use crate::debug_info::DebugInfo;
 use crate::generic64::{aarch64, new_backend_64bit, x86_64};
 use crate::{Backend, Env, Relocation};
 use bumpalo::collections::Vec;
@@ -153,14 +154,7 @@ fn generate_wrapper<'a, B: Backend<'a>>(
     };
     output.add_symbol(symbol);
     if let Some(sym_id) = output.symbol_id(name) {
-        let reloc = write::Relocation {
-            offset: offset + proc_offset,
-            size: 32,
-            kind: RelocationKind::PltRelative,
-            encoding: RelocationEncoding::X86Branch,
-            symbol: sym_id,
-            addend: -4,
-        };
+        let reloc = branch_relocation(output, offset + proc_offset, sym_id);
 
         match output.add_relocation(text_section, reloc) {
             Ok(obj) => obj,
@@ -171,6 +165,29 @@ fn generate_wrapper<'a, B: Backend<'a>>(
     }
 }
 
+/// The relocation for a call or tail call to `symbol`, where `offset` points at the branch.
+fn branch_relocation(output: &Object, offset: u64, symbol: SymbolId) -> write::Relocation {
+    match output.architecture() {
+        // AArch64 branches are relative to the start of the instruction and encode a 26 bit word offset.
+        Architecture::Aarch64 => write::Relocation {
+            offset,
+            size: 26,
+            kind: RelocationKind::PltRelative,
+            encoding: RelocationEncoding::AArch64Call,
+            symbol,
+            addend: 0,
+        },
+        _ => write::Relocation {
+            offset,
+            size: 32,
+            kind: RelocationKind::PltRelative,
+            encoding: RelocationEncoding::X86Branch,
+            symbol,
+            addend: -4,
+        },
+    }
+}
+
 fn build_object<'a, B: Backend<'a>>(
     procedures: MutMap<(symbol::Symbol, ProcLayout<'a>), Proc<'a>>,
     mut backend: B,
@@ -233,6 +250,12 @@ fn build_object<'a, B: Backend<'a>>(
         }
     }
 
+    let mut debug_info = backend
+        .env()
+        .debug_sources
+        .as_ref()
+        .map(|_| DebugInfo::new());
+
     // Setup layout_ids for procedure calls.
     let mut layout_ids = LayoutIds::default();
     let mut procs = Vec::with_capacity_in(procedures.len(), arena);
@@ -296,6 +319,7 @@ fn build_object<'a, B: Backend<'a>>(
             &mut backend,
             &mut relocations,
             &mut layout_ids,
+            debug_info.as_mut(),
             data_section,
             fn_name,
             section_id,
@@ -387,6 +411,7 @@ fn build_object<'a, B: Backend<'a>>(
             &mut backend,
             &mut relocations,
             &mut layout_ids,
+            debug_info.as_mut(),
             data_section,
             fn_name,
             section_id,
@@ -402,6 +427,11 @@ fn build_object<'a, B: Backend<'a>>(
             Err(e) => internal_error!("{:?}", e),
         }
     }
+
+    if let Some(debug_info) = debug_info {
+        debug_info.write(&mut output);
+    }
+
     output
 }
 
@@ -444,6 +474,8 @@ fn build_exposed_proc<'a, B: Backend<'a>>(backend: &mut B, proc: &Proc<'a>) -> P
         ret_layout: proc.ret_layout,
         is_self_recursive: roc_mono::ir::SelfRecursive::NotSelfRecursive,
         host_exposed_layouts: roc_mono::ir::HostExposedLayouts::NotHostExposed,
+        region: roc_region::all::Region::zero(),
+        statement_regions: &[],
     }
 }
 
@@ -525,6 +557,8 @@ fn build_exposed_generic_proc<'a, B: Backend<'a>>(backend: &mut B, proc: &Proc<'
         ret_layout: roc_mono::layout::Layout::UNIT,
         is_self_recursive: roc_mono::ir::SelfRecursive::NotSelfRecursive,
         host_exposed_layouts: roc_mono::ir::HostExposedLayouts::NotHostExposed,
+        region: roc_region::all::Region::zero(),
+        statement_regions: &[],
     }
 }
 
@@ -592,6 +626,7 @@ fn build_proc<'a, B: Backend<'a>>(
     backend: &mut B,
     relocations: &mut Vec<'a, (SectionId, object::write::Relocation)>,
     layout_ids: &mut LayoutIds<'a>,
+    debug_info: Option<&mut DebugInfo>,
     data_section: SectionId,
     fn_name: String,
     section_id: SectionId,
@@ -599,8 +634,30 @@ fn build_proc<'a, B: Backend<'a>>(
     proc: Proc<'a>,
 ) {
     let mut local_data_index = 0;
+
+    // Look up where the proc came from before the backend takes ownership of it.
+    let debug_info = debug_info.map(|debug_info| {
+        let env = backend.env();
+        let location = env
+            .debug_sources
+            .as_ref()
+            .and_then(|sources| debug_info.proc_location(sources, &proc));
+        let name = proc
+            .name
+            .name()
+            .fully_qualified(backend.interns(), env.module_id);
+
+        (debug_info, location, name)
+    });
+
     let (proc_data, relocs, rc_proc_names) = backend.build_proc(proc, layout_ids);
     let proc_offset = output.add_symbol_data(proc_id, section_id, &proc_data, 16);
+
+    if let Some((debug_info, location, name)) = debug_info {
+        let size = proc_data.len() as u64;
+        let statements = backend.statement_rows();
+        debug_info.add_proc(proc_id, name.as_str(), &fn_name, location, statements, size);
+    }
     for reloc in relocs.iter() {
         let elfreloc = match reloc {
             Relocation::LocalData { offset, data } => {
@@ -684,14 +741,7 @@ fn build_proc<'a, B: Backend<'a>>(
                 }
 
                 if let Some(sym_id) = output.symbol_id(name.as_bytes()) {
-                    write::Relocation {
-                        offset: offset + proc_offset,
-                        size: 32,
-                        kind: RelocationKind::PltRelative,
-                        encoding: RelocationEncoding::X86Branch,
-                        symbol: sym_id,
-                        addend: -4,
-                    }
+                    branch_relocation(output, offset + proc_offset, sym_id)
                 } else {
                     internal_error!("failed to find fn symbol for {:?}", name);
                 }
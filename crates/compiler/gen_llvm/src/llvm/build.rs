@@ -15,7 +15,7 @@ use inkwell::attributes::{Attribute, AttributeLoc};
 use inkwell::builder::Builder;
 use inkwell::context::Context;
 use inkwell::debug_info::{
-    AsDIScope, DICompileUnit, DIFlagsConstants, DISubprogram, DebugInfoBuilder,
+    AsDIScope, DICompileUnit, DIFile, DIFlagsConstants, DISubprogram, DebugInfoBuilder,
 };
 use inkwell::memory_buffer::MemoryBuffer;
 use inkwell::module::{Linkage, Module};
@@ -40,13 +40,14 @@ use roc_debug_flags::dbg_do;
 use roc_debug_flags::ROC_PRINT_LLVM_FN_VERIFICATION;
 use roc_module::symbol::{Interns, Symbol};
 use roc_mono::ir::{
-    BranchInfo, CallType, CrashTag, EntryPoint, GlueLayouts, HostExposedLambdaSet,
+    BranchInfo, CallType, CrashTag, DebugSources, EntryPoint, GlueLayouts, HostExposedLambdaSet,
     ListLiteralElement, ModifyRc, OptLevel, ProcLayout, SingleEntryPoint,
 };
 use roc_mono::layout::{
     Builtin, InLayout, LambdaName, LambdaSet, Layout, LayoutIds, LayoutInterner, LayoutRepr, Niche,
     RawFunctionLayout, STLayoutInterner, TagIdIntType, UnionLayout,
 };
+use roc_region::all::LineColumn;
 use roc_std::RocDec;
 use roc_target::{PtrWidth, TargetInfo};
 use std::convert::TryInto;
@@ -222,6 +223,8 @@ pub struct Env<'a, 'ctx, 'env> {
     pub target_info: TargetInfo,
     pub mode: LlvmBackendMode,
     pub exposed_to_host: MutSet<Symbol>,
+    /// When set, debug info points at these Roc sources rather than line 0 of `roc_app`
+    pub debug_sources: Option<&'env DebugSources>,
 }
 
 impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
@@ -425,6 +428,44 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
     }
 
     pub fn new_subprogram(&self, function_name: &str) -> DISubprogram<'ctx> {
+        self.new_subprogram_at(function_name, self.compile_unit.get_file(), 0)
+    }
+
+    /// A subprogram for a proc, pointing at its Roc source when we are emitting debug info for it.
+    pub fn new_proc_subprogram(
+        &self,
+        function_name: &str,
+        proc: &roc_mono::ir::Proc<'a>,
+    ) -> DISubprogram<'ctx> {
+        match self.proc_location(proc) {
+            Some((path, line_column)) => {
+                let file_name = path.file_name().unwrap_or(path.as_os_str());
+                let directory = match path.parent() {
+                    Some(dir) if !dir.as_os_str().is_empty() => dir.to_string_lossy(),
+                    _ => ".".into(),
+                };
+                let file = self
+                    .dibuilder
+                    .create_file(&file_name.to_string_lossy(), &directory);
+
+                // DWARF numbers lines from 1
+                self.new_subprogram_at(function_name, file, line_column.line + 1)
+            }
+            None => self.new_subprogram(function_name),
+        }
+    }
+
+    /// The file a proc was defined in, and the 0-based position its body starts at.
+    pub fn proc_location(&self, proc: &roc_mono::ir::Proc<'a>) -> Option<(&'env Path, LineColumn)> {
+        self.debug_sources?.location(proc)
+    }
+
+    fn new_subprogram_at(
+        &self,
+        function_name: &str,
+        file: DIFile<'ctx>,
+        line: u32,
+    ) -> DISubprogram<'ctx> {
         let dibuilder = self.dibuilder;
         let compile_unit = self.compile_unit;
 
@@ -445,15 +486,15 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
         );
 
         dibuilder.create_function(
-            /* scope */ compile_unit.get_file().as_debug_info_scope(),
+            /* scope */ file.as_debug_info_scope(),
             /* func name */ function_name,
             /* linkage_name */ None,
-            /* file */ compile_unit.get_file(),
-            /* line_no */ 0,
+            /* file */ file,
+            /* line_no */ line,
             /* DIType */ subroutine_type,
             /* is_local_to_unit */ true,
             /* is_definition */ true,
-            /* scope_line */ 0,
+            /* scope_line */ line,
             /* flags */ inkwell::debug_info::DIFlags::PUBLIC,
             /* is_optimized */ false,
         )
@@ -2482,6 +2523,10 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
             let mut stack = Vec::with_capacity_in(queue.len(), env.arena);
 
             for (symbol, expr, layout) in queue {
+                if let Some(location) = scope.get_debug_location(symbol) {
+                    env.builder.set_current_debug_location(location);
+                }
+
                 debug_assert!(!matches!(
                     layout_interner.get_repr(*layout),
                     LayoutRepr::RecursivePointer(_)
@@ -5045,7 +5090,7 @@ fn build_proc_header<'a, 'ctx>(
         Linkage::Internal,
     );
 
-    let subprogram = env.new_subprogram(&fn_name);
+    let subprogram = env.new_proc_subprogram(&fn_name, proc);
     fn_val.set_subprogram(subprogram);
 
     if env.exposed_to_host.contains(&symbol) {
@@ -5389,6 +5434,26 @@ fn build_proc<'a, 'ctx>(
 
     debug_info_init!(env, fn_val);
 
+    if let (Some(sources), Some((_, line_column))) = (env.debug_sources, env.proc_location(proc)) {
+        let func_scope = fn_val.get_subprogram().expect("subprogram");
+        let debug_location = |LineColumn { line, column }| {
+            env.dibuilder.create_debug_location(
+                context,
+                /* line */ line + 1,
+                /* column */ column + 1,
+                /* current_scope */ func_scope.as_debug_info_scope(),
+                /* inlined_at */ None,
+            )
+        };
+
+        builder.set_current_debug_location(debug_location(line_column));
+
+        // build_exp_stmt moves the debug location along as it reaches each def and branch
+        for (symbol, line_column) in sources.statement_locations(proc) {
+            scope.insert_debug_location(symbol, debug_location(line_column));
+        }
+    }
+
     // Add args to scope
     for (arg_val, (layout, arg_symbol)) in fn_val.get_param_iter().zip(args) {
         arg_val.set_name(arg_symbol.as_str(&env.interns));
@@ -22,15 +22,23 @@ pub fn add_default_roc_externs(env: &Env<'_, '_, '_>) {
 
     match env.mode {
         super::build::LlvmBackendMode::CliTest => {
-            // expose this function
-            if let Some(fn_val) = module.get_function("set_shared_buffer") {
-                fn_val.set_linkage(Linkage::External);
+            // expose these functions
+            for name in ["set_shared_buffer", "set_coverage_buffer"] {
+                if let Some(fn_val) = module.get_function(name) {
+                    fn_val.set_linkage(Linkage::External);
+                }
             }
         }
         _ => {
-            // remove this function from the module
-            if let Some(fn_val) = module.get_function("set_shared_buffer") {
-                unsafe { fn_val.delete() };
+            // remove these functions from the module; only tests are instrumented for coverage
+            for name in [
+                "set_shared_buffer",
+                "set_coverage_buffer",
+                "roc_coverage_hit",
+            ] {
+                if let Some(fn_val) = module.get_function(name) {
+                    unsafe { fn_val.delete() };
+                }
             }
         }
     }
@@ -1,5 +1,6 @@
 use inkwell::{
     basic_block::BasicBlock,
+    debug_info::DILocation,
     values::{BasicValueEnum, FunctionValue, PhiValue},
 };
 use roc_collections::ImMap;
@@ -14,6 +15,8 @@ pub(crate) struct Scope<'a, 'ctx> {
     symbols: ImMap<Symbol, (InLayout<'a>, BasicValueEnum<'ctx>)>,
     top_level_thunks: ImMap<Symbol, (ProcLayout<'a>, FunctionValue<'ctx>)>,
     join_points: ImMap<JoinPointId, (BasicBlock<'ctx>, Vec<PhiValue<'ctx>>)>,
+    /// Where the statements binding these symbols are in the Roc source, when emitting debug info
+    debug_locations: ImMap<Symbol, DILocation<'ctx>>,
 }
 
 #[derive(Debug)]
@@ -61,6 +64,14 @@ impl<'a, 'ctx> Scope<'a, 'ctx> {
             .retain(|s, _| s.module_id() == module_id);
     }
 
+    pub fn insert_debug_location(&mut self, symbol: Symbol, location: DILocation<'ctx>) {
+        self.debug_locations.insert(symbol, location);
+    }
+
+    pub fn get_debug_location(&self, symbol: &Symbol) -> Option<DILocation<'ctx>> {
+        self.debug_locations.get(symbol).copied()
+    }
+
     pub fn insert_join_point(
         &mut self,
         join_point_id: JoinPointId,
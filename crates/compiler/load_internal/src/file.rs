@@ -22,7 +22,7 @@ use roc_debug_flags::{
     ROC_CHECK_MONO_IR, ROC_PRINT_IR_AFTER_DROP_SPECIALIZATION, ROC_PRINT_IR_AFTER_REFCOUNT,
     ROC_PRINT_IR_AFTER_RESET_REUSE, ROC_PRINT_IR_AFTER_SPECIALIZATION, ROC_PRINT_LOAD_LOG,
 };
-use roc_derive::SharedDerivedModule;
+use roc_derive::{DerivedModule, SharedDerivedModule};
 use roc_error_macros::internal_error;
 use roc_late_solve::{AbilitiesView, WorldAbilities};
 use roc_module::ident::{Ident, ModuleName, QualifiedModuleName};
@@ -30,6 +30,7 @@ use roc_module::symbol::{
     IdentIds, IdentIdsByModule, Interns, ModuleId, ModuleIds, PQModuleName, PackageModuleIds,
     PackageQualified, Symbol,
 };
+use roc_mono::coverage::{Coverage, CoveredRegion};
 use roc_mono::ir::{
     CapturedSymbols, ExternalSpecializations, GlueLayouts, LambdaSetId, PartialProc, Proc,
     ProcLayout, Procs, ProcsBase, UpdateModeIds, UsageTrackingMap,
@@ -41,10 +42,12 @@ use roc_mono::layout::{
 use roc_mono::reset_reuse;
 use roc_mono::{drop_specialization, inc_dec};
 use roc_packaging::cache::RocCacheDir;
+use roc_packaging::tarball::is_tarball_src;
 use roc_parse::ast::{
     self, CommentOrNewline, Defs, Expr, ExtractSpaces, Pattern, Spaced, StrLiteral, TypeAnnotation,
     ValueDef,
 };
+use roc_parse::doc_tests::doc_tests;
 use roc_parse::header::{
     ExposedName, ImportsEntry, PackageEntry, PackageHeader, PlatformHeader, To, TypedIdent,
 };
@@ -77,6 +80,7 @@ use {
     roc_packaging::https::{PackageMetadata, Problem},
 };
 
+use crate::type_cache::{CacheKey, CachedDerive, TypeCache, TypeCacheEntry};
 pub use crate::work::Phase;
 use crate::work::{DepCycle, Dependencies};
 
@@ -118,6 +122,8 @@ pub enum ExecutionMode {
     /// Test is like [`ExecutionMode::ExecutableIfCheck`], but rather than producing a proper
     /// executable, run tests.
     Test,
+    /// Like [`ExecutionMode::Test`], but also counts how often each def and branch runs.
+    TestWithCoverage,
 }
 
 impl ExecutionMode {
@@ -126,12 +132,19 @@ impl ExecutionMode {
 
         match self {
             Executable => Phase::MakeSpecializations,
-            Check | ExecutableIfCheck | Test => Phase::SolveTypes,
+            Check | ExecutableIfCheck | Test | TestWithCoverage => Phase::SolveTypes,
         }
     }
 
     fn build_if_checks(&self) -> bool {
-        matches!(self, Self::ExecutableIfCheck | Self::Test)
+        matches!(
+            self,
+            Self::ExecutableIfCheck | Self::Test | Self::TestWithCoverage
+        )
+    }
+
+    fn is_test(&self) -> bool {
+        matches!(self, Self::Test | Self::TestWithCoverage)
     }
 }
 
@@ -206,6 +219,8 @@ impl Default for ModuleCache<'_> {
             ENCODE,
             DECODE,
             HASH,
+            INSPECT,
+            SORT,
             JSON,
         }
 
@@ -284,7 +299,18 @@ fn start_phase<'a>(
                 // parse the file
                 let header = state.module_cache.headers.remove(&module_id).unwrap();
 
-                BuildTask::Parse { header }
+                // Like other expects, doc tests only run for the modules of the root package.
+                let include_doc_tests = state.exec_mode.is_test() && {
+                    let modules = state.arc_modules.lock();
+                    modules
+                        .package_eq(module_id, state.root_id)
+                        .expect("root or this module is not yet known - that's a bug!")
+                };
+
+                BuildTask::Parse {
+                    header,
+                    include_doc_tests,
+                }
             }
             Phase::CanonicalizeAndConstrain => {
                 // canonicalize the file
@@ -380,6 +406,29 @@ fn start_phase<'a>(
                     }
                 }
 
+                if let Some(type_cache) = &state.type_cache {
+                    if is_type_cacheable(&parsed.header_type) {
+                        let imports = parsed
+                            .imported_modules
+                            .keys()
+                            .map(|imported| (*imported, state.type_cache_keys.get(imported)));
+
+                        if let Some(key) = type_cache.key(
+                            module_id,
+                            parsed.src,
+                            parsed.includes_doc_tests,
+                            imports,
+                        ) {
+                            if let Some((type_state, derives)) = type_cache.read(&key) {
+                                state.cached_types.lock().insert(module_id, type_state);
+                                state.cached_derives.insert(module_id, derives);
+                            }
+
+                            state.type_cache_keys.insert(module_id, key);
+                        }
+                    }
+                }
+
                 let skip_constraint_gen = {
                     // Give this its own scope to make sure that the Guard from the lock() is dropped
                     // immediately after contains_key returns
@@ -418,6 +467,15 @@ fn start_phase<'a>(
 
                 let derived_module = SharedDerivedModule::clone(&state.derived_module);
 
+                let type_cache_entry = match state.cached_derives.remove(&module_id) {
+                    Some(derives) => Some(TypeCacheEntry::Read(derives)),
+                    None => state
+                        .type_cache
+                        .clone()
+                        .zip(state.type_cache_keys.get(&module_id).copied())
+                        .map(|(type_cache, key)| TypeCacheEntry::Write(type_cache, key)),
+                };
+
                 BuildTask::solve_module(
                     module,
                     ident_ids,
@@ -432,6 +490,7 @@ fn start_phase<'a>(
                     dep_idents,
                     declarations,
                     state.cached_types.clone(),
+                    type_cache_entry,
                     derived_module,
                 )
             }
@@ -463,8 +522,7 @@ fn start_phase<'a>(
 
                 let derived_module = SharedDerivedModule::clone(&state.derived_module);
 
-                let build_expects =
-                    matches!(state.exec_mode, ExecutionMode::Test) && expectations.is_some();
+                let build_expects = state.exec_mode.is_test() && expectations.is_some();
 
                 BuildTask::BuildPendingSpecializations {
                     layout_cache,
@@ -479,6 +537,7 @@ fn start_phase<'a>(
                     // TODO: awful, how can we get rid of the clone?
                     exposed_by_module: state.exposed_types.clone(),
                     derived_module,
+                    coverage: state.coverage.clone(),
                     expectations,
                     build_expects,
                 }
@@ -615,6 +674,7 @@ fn start_phase<'a>(
                     // TODO: awful, how can we get rid of the clone?
                     exposed_by_module: state.exposed_types.clone(),
                     derived_module,
+                    coverage: state.coverage.clone(),
                     expectations,
                 }
             }
@@ -637,8 +697,12 @@ pub struct LoadedModule {
     pub exposed_aliases: MutMap<Symbol, Alias>,
     pub exposed_values: Vec<Symbol>,
     pub exposed_types_storage: ExposedTypesStorageSubs,
+    /// The exposed types of every module that was loaded, including the root module.
+    pub exposed_types_by_module: MutMap<ModuleId, ExposedTypesStorageSubs>,
     pub resolved_implementations: ResolvedImplementations,
     pub sources: MutMap<ModuleId, (PathBuf, Box<str>)>,
+    /// The modules each module imports directly.
+    pub imports: MutMap<ModuleId, MutSet<ModuleId>>,
     pub timings: MutMap<ModuleId, ModuleTiming>,
     pub docs_by_module: VecMap<ModuleId, ModuleDocumentation>,
     pub abilities_store: AbilitiesStore,
@@ -769,10 +833,15 @@ pub struct MonomorphizedModule<'a> {
     pub entry_point: EntryPoint<'a>,
     pub exposed_to_host: ExposedToHost,
     pub sources: MutMap<ModuleId, (PathBuf, Box<str>)>,
+    /// The modules each module imports directly.
+    pub imports: MutMap<ModuleId, MutSet<ModuleId>>,
     pub timings: MutMap<ModuleId, ModuleTiming>,
     pub expectations: VecMap<ModuleId, Expectations>,
     pub uses_prebuilt_platform: bool,
     pub glue_layouts: GlueLayouts<'a>,
+    /// When measuring coverage, the regions whose executions are counted, indexed by the id
+    /// they are counted under.
+    pub covered_regions: Option<Vec<CoveredRegion>>,
 }
 
 /// Values used to render expect output
@@ -841,6 +910,8 @@ struct ParsedModule<'a> {
     symbols_from_requires: Vec<(Loc<Symbol>, Loc<TypeAnnotation<'a>>)>,
     header_type: HeaderType<'a>,
     header_comments: &'a [CommentOrNewline<'a>],
+    /// Whether expects from the examples in doc comments were added to `parsed_defs`.
+    includes_doc_tests: bool,
 }
 
 type LocExpects = VecMap<Region, Vec<ExpectLookup>>;
@@ -973,6 +1044,8 @@ struct State<'a> {
     pub root_id: ModuleId,
     pub root_subs: Option<Subs>,
     pub cache_dir: PathBuf,
+    /// When loading offline, the project's vendor dir, which is searched before the cache dir
+    pub vendor_dir: Option<PathBuf>,
     /// If the root is an app module, the shorthand specified in its header's `to` field
     pub opt_platform_shorthand: Option<&'a str>,
     pub platform_data: Option<PlatformData<'a>>,
@@ -999,6 +1072,8 @@ struct State<'a> {
     pub arc_modules: Arc<Mutex<PackageModuleIds<'a>>>,
     pub arc_shorthands: Arc<Mutex<MutMap<&'a str, ShorthandPath>>>,
     pub derived_module: SharedDerivedModule,
+    /// Ids for the regions we count executions of, if we are measuring coverage.
+    pub coverage: Option<Coverage>,
 
     pub ident_ids_by_module: SharedIdentIdsByModule,
 
@@ -1024,9 +1099,17 @@ struct State<'a> {
 
     make_specializations_pass: MakeSpecializationsPass,
 
-    // cached types (used for builtin modules, could include packages in the future too)
+    // cached types (used for builtin modules, and for other modules found in the type cache)
     cached_types: CachedTypeState,
 
+    /// Where typechecked modules are persisted between invocations, if anywhere
+    type_cache: Option<TypeCache>,
+    /// The type cache keys of the modules that have one, by the time they're canonicalized
+    type_cache_keys: MutMap<ModuleId, CacheKey>,
+    /// The derived implementations referred to by the modules whose types were read from the
+    /// type cache, until they're solved
+    cached_derives: MutMap<ModuleId, Vec<CachedDerive>>,
+
     layout_interner: GlobalLayoutInterner<'a>,
 }
 
@@ -1045,6 +1128,8 @@ impl<'a> State<'a> {
         arc_modules: Arc<Mutex<PackageModuleIds<'a>>>,
         ident_ids_by_module: SharedIdentIdsByModule,
         cached_types: MutMap<ModuleId, TypeState>,
+        type_cache: Option<TypeCache>,
+        vendor_dir: Option<PathBuf>,
         render: RenderTarget,
         palette: Palette,
         number_of_workers: usize,
@@ -1059,6 +1144,7 @@ impl<'a> State<'a> {
             root_subs: None,
             opt_platform_shorthand,
             cache_dir,
+            vendor_dir,
             target_info,
             platform_data: None,
             output_path: None,
@@ -1073,6 +1159,10 @@ impl<'a> State<'a> {
             arc_modules,
             arc_shorthands,
             derived_module: Default::default(),
+            coverage: match exec_mode {
+                ExecutionMode::TestWithCoverage => Some(Coverage::default()),
+                _ => None,
+            },
             constrained_ident_ids: IdentIds::exposed_builtins(0),
             ident_ids_by_module,
             declarations_by_id: MutMap::default(),
@@ -1080,6 +1170,9 @@ impl<'a> State<'a> {
             timings: MutMap::default(),
             layout_caches: std::vec::Vec::with_capacity(number_of_workers),
             cached_types: Arc::new(Mutex::new(cached_types)),
+            type_cache,
+            type_cache_keys: MutMap::default(),
+            cached_derives: MutMap::default(),
             render,
             palette,
             exec_mode,
@@ -1101,6 +1194,9 @@ pub struct ModuleTiming {
     pub find_specializations: Duration,
     // indexed by make specializations pass
     pub make_specializations: Vec<Duration>,
+    /// Whether the types came from a cache (the builtins' or the type cache), instead of being
+    /// solved
+    pub solved_from_cache: bool,
     // TODO pub monomorphize: Duration,
     /// Total duration will always be more than the sum of the other fields, due
     /// to things like state lookups in between phases, waiting on other threads, etc.
@@ -1119,6 +1215,7 @@ impl ModuleTiming {
             solve: Duration::default(),
             find_specializations: Duration::default(),
             make_specializations: Vec::with_capacity(2),
+            solved_from_cache: false,
             start_time,
             end_time: start_time, // just for now; we'll overwrite this at the end
         }
@@ -1139,6 +1236,7 @@ impl ModuleTiming {
             solve,
             find_specializations,
             make_specializations,
+            solved_from_cache: _,
             start_time,
             end_time,
         } = self;
@@ -1217,6 +1315,8 @@ enum BuildTask<'a> {
     },
     Parse {
         header: ModuleHeader<'a>,
+        /// Whether to add the doc tests in the module's doc comments to its expects
+        include_doc_tests: bool,
     },
     CanonicalizeAndConstrain {
         parsed: ParsedModule<'a>,
@@ -1241,6 +1341,7 @@ enum BuildTask<'a> {
         declarations: Declarations,
         dep_idents: IdentIdsByModule,
         cached_subs: CachedTypeState,
+        type_cache_entry: Option<TypeCacheEntry>,
         derived_module: SharedDerivedModule,
     },
     BuildPendingSpecializations {
@@ -1255,6 +1356,7 @@ enum BuildTask<'a> {
         exposed_by_module: ExposedByModule,
         abilities_store: AbilitiesStore,
         derived_module: SharedDerivedModule,
+        coverage: Option<Coverage>,
         expectations: Option<Expectations>,
         build_expects: bool,
     },
@@ -1269,6 +1371,7 @@ enum BuildTask<'a> {
         exposed_by_module: ExposedByModule,
         world_abilities: WorldAbilities,
         derived_module: SharedDerivedModule,
+        coverage: Option<Coverage>,
         expectations: Option<Expectations>,
     },
 }
@@ -1672,6 +1775,8 @@ pub fn load_single_threaded<'a>(
         arc_modules,
         ident_ids_by_module,
         cached_types,
+        TypeCache::new(roc_cache_dir),
+        roc_cache_dir.vendor_dir().map(Path::to_path_buf),
         render,
         palette,
         number_of_workers,
@@ -2002,6 +2107,8 @@ fn load_multi_threaded<'a>(
         arc_modules,
         ident_ids_by_module,
         cached_types,
+        TypeCache::new(roc_cache_dir),
+        roc_cache_dir.vendor_dir().map(Path::to_path_buf),
         render,
         palette,
         num_workers,
@@ -2239,7 +2346,7 @@ fn worker_task<'a>(
                             BuildTask::LoadModule { module_name, .. } => {
                                 format!("BuildTask::LoadModule({:?})", module_name)
                             }
-                            BuildTask::Parse { header } => {
+                            BuildTask::Parse { header, .. } => {
                                 format!("BuildTask::Parse({})", header.module_path.display())
                             }
                             BuildTask::CanonicalizeAndConstrain { parsed, .. } => format!(
@@ -2435,17 +2542,24 @@ fn update<'a>(
 
                 for (shorthand, package_name) in header.packages.iter() {
                     let package_str = package_name.as_str();
-                    let shorthand_path = if package_str.starts_with("https://") {
+                    let shorthand_path = if is_tarball_src(package_str) {
                         #[cfg(not(target_family = "wasm"))]
                         {
                             let url = package_str;
                             match PackageMetadata::try_from(url) {
                                 Ok(url_metadata) => {
                                     // This was a valid URL
-                                    let root_module_dir = state
-                                        .cache_dir
-                                        .join(url_metadata.cache_subdir)
+                                    let package_subdir = Path::new(url_metadata.cache_subdir)
                                         .join(url_metadata.content_hash);
+                                    let root_module_dir = match &state.vendor_dir {
+                                        // Like `install_package`, prefer the vendored copy.
+                                        Some(vendor_dir)
+                                            if vendor_dir.join(&package_subdir).exists() =>
+                                        {
+                                            vendor_dir.join(package_subdir)
+                                        }
+                                        _ => state.cache_dir.join(package_subdir),
+                                    };
                                     let root_module = root_module_dir.join(
                                         url_metadata.root_module_filename.unwrap_or("main.roc"),
                                     );
@@ -2617,6 +2731,8 @@ fn update<'a>(
                 extend_header_with_builtin(header, ModuleId::ENCODE);
                 extend_header_with_builtin(header, ModuleId::DECODE);
                 extend_header_with_builtin(header, ModuleId::HASH);
+                extend_header_with_builtin(header, ModuleId::INSPECT);
+                extend_header_with_builtin(header, ModuleId::SORT);
             }
 
             state
@@ -2784,7 +2900,7 @@ fn update<'a>(
 
             let add_to_host_exposed = is_host_exposed &&
                 // During testing, we don't need to expose anything to the host.
-                !matches!(state.exec_mode, ExecutionMode::Test);
+                !state.exec_mode.is_test();
 
             if add_to_host_exposed {
                 state.exposed_to_host.top_level_values.extend(
@@ -2894,6 +3010,10 @@ fn update<'a>(
                 } else {
                     state.constrained_ident_ids.insert(module_id, ident_ids);
                     state.timings.insert(module_id, module_timing);
+
+                    // Keep the declarations of dependencies around when only checking, so
+                    // tooling (e.g. the language server) can resolve symbols defined in them.
+                    state.declarations_by_id.insert(module_id, decls);
                 }
 
                 let work = if is_host_exposed && state.exec_mode.build_if_checks() {
@@ -3325,7 +3445,7 @@ fn finish_specialization<'a>(
     let entry_point = {
         let interns: &mut Interns = &mut interns;
         match state.exec_mode {
-            ExecutionMode::Test => Ok(EntryPoint::Test),
+            ExecutionMode::Test | ExecutionMode::TestWithCoverage => Ok(EntryPoint::Test),
             ExecutionMode::Executable | ExecutionMode::ExecutableIfCheck => {
                 use PlatformPath::*;
 
@@ -3401,6 +3521,7 @@ fn finish_specialization<'a>(
         type_problems,
         can_problems,
         sources,
+        imports,
         ..
     } = module_cache;
 
@@ -3483,12 +3604,14 @@ fn finish_specialization<'a>(
         procedures,
         entry_point,
         sources,
+        imports,
         timings: state.timings,
         toplevel_expects,
         glue_layouts: GlueLayouts {
             getters: glue_getters,
         },
         uses_prebuilt_platform,
+        covered_regions: state.coverage.map(|coverage| coverage.regions()),
     })
 }
 
@@ -3552,6 +3675,14 @@ fn finish(
 
     let exposed_values = exposed_vars_by_symbol.iter().map(|x| x.0).collect();
 
+    let mut exposed_types_by_module: MutMap<ModuleId, ExposedTypesStorageSubs> = state
+        .exposed_types
+        .iter_all()
+        .map(|(module_id, exposed)| (*module_id, exposed.exposed_types_storage_subs.clone()))
+        .collect();
+
+    exposed_types_by_module.insert(state.root_id, exposed_types_storage.clone());
+
     LoadedModule {
         module_id: state.root_id,
         interns,
@@ -3564,8 +3695,10 @@ fn finish(
         exposed_values,
         exposed_to_host: exposed_vars_by_symbol.into_iter().collect(),
         exposed_types_storage,
+        exposed_types_by_module,
         resolved_implementations,
         sources,
+        imports: state.module_cache.imports,
         timings: state.timings,
         docs_by_module: documentation,
         abilities_store,
@@ -3846,6 +3979,8 @@ fn load_module<'a>(
         "Encode", ModuleId::ENCODE
         "Decode", ModuleId::DECODE
         "Hash", ModuleId::HASH
+        "Inspect", ModuleId::INSPECT
+        "Sort", ModuleId::SORT
         "TotallyNotJson", ModuleId::JSON
     }
 
@@ -4321,15 +4456,26 @@ fn load_packages<'a>(
 
         // find the `package` or `platform` module on disk,
         // downloading it into a cache dir first if necessary.
-        let root_module_path = if src.starts_with("https://") {
+        let root_module_path = if is_tarball_src(src) {
             #[cfg(not(target_family = "wasm"))]
             {
                 // If this is a HTTPS package, synchronously download it
-                // to the cache before proceeding.
+                // to the cache before proceeding. Tarballs on the local filesystem
+                // get unpacked into the cache the same way.
 
                 // TODO we should do this async; however, with the current
                 // architecture of file.rs (which doesn't use async/await),
                 // this would be very difficult!
+                let resolved_src;
+                let src = if src.starts_with("https://") || src.starts_with("file://") {
+                    src
+                } else {
+                    // Tarball paths are relative to the module that names them.
+                    resolved_src = cwd.join(src).to_string_lossy().into_owned();
+
+                    resolved_src.as_str()
+                };
+
                 match cache::install_package(roc_cache_dir, src) {
                     Ok((package_dir, opt_root_module)) => {
                         // You can optionally specify the root module using the URL fragment,
@@ -4790,6 +4936,7 @@ impl<'a> BuildTask<'a> {
         dep_idents: IdentIdsByModule,
         declarations: Declarations,
         cached_subs: CachedTypeState,
+        type_cache_entry: Option<TypeCacheEntry>,
         derived_module: SharedDerivedModule,
     ) -> Self {
         let exposed_by_module = exposed_types.retain_modules(imported_modules.keys());
@@ -4811,6 +4958,7 @@ impl<'a> BuildTask<'a> {
             dep_idents,
             module_timing,
             cached_subs,
+            type_cache_entry,
             derived_module,
         }
     }
@@ -5182,6 +5330,115 @@ fn run_solve_solve(
     }
 }
 
+fn write_type_cache(
+    type_cache: &TypeCache,
+    key: &CacheKey,
+    solve_result: SolveResult,
+    derives: &[CachedDerive],
+) -> SolveResult {
+    let SolveResult {
+        solved,
+        solved_implementations,
+        exposed_vars_by_symbol,
+        problems,
+        abilities_store,
+    } = solve_result;
+
+    let type_state = TypeState {
+        subs: solved.into_inner(),
+        exposed_vars_by_symbol,
+        abilities: abilities_store,
+        solved_implementations,
+    };
+
+    type_cache.write(key, &type_state, derives);
+
+    let TypeState {
+        subs,
+        exposed_vars_by_symbol,
+        abilities,
+        solved_implementations,
+    } = type_state;
+
+    SolveResult {
+        solved: Solved(subs),
+        solved_implementations,
+        exposed_vars_by_symbol,
+        problems,
+        abilities_store: abilities,
+    }
+}
+
+/// The derived implementations that solved types refer to. Returns `None` if they refer to a
+/// derived symbol that isn't part of any of them, which we wouldn't know how to translate.
+///
+/// Derived symbols only end up in a module's types as the names in lambda sets.
+fn derives_referred_to(subs: &Subs, derived_module: &DerivedModule) -> Option<Vec<CachedDerive>> {
+    let mut derives: Vec<CachedDerive> = Vec::new();
+
+    for symbol in subs.symbol_names.iter() {
+        if symbol.module_id() != ModuleId::DERIVED_SYNTH {
+            continue;
+        }
+
+        let key = derived_module.key_of(*symbol)?;
+
+        if !derives.iter().any(|derive| &derive.key == key) {
+            derives.push(CachedDerive {
+                key: key.clone(),
+                idents: derived_module.idents_of(key)?,
+            });
+        }
+    }
+
+    Some(derives)
+}
+
+/// Derive the implementations that types read from the type cache refer to, which may give them
+/// different symbols than they had when the types were cached, and point the types at them.
+fn rederive(
+    subs: &mut Subs,
+    derives: &[CachedDerive],
+    derived_module: &mut DerivedModule,
+    exposed_by_module: &ExposedByModule,
+) {
+    for derive in derives {
+        derived_module.get_or_insert(exposed_by_module, derive.key.clone());
+    }
+
+    for symbol in subs.symbol_names.iter_mut() {
+        if symbol.module_id() != ModuleId::DERIVED_SYNTH {
+            continue;
+        }
+
+        let index = symbol.ident_id().index() as u32;
+        let translated = derives
+            .iter()
+            .find(|derive| derive.idents.contains(&index))
+            .and_then(|derive| {
+                derived_module.translate_ident(&derive.key, &derive.idents, *symbol)
+            });
+
+        match translated {
+            Some(translated) => *symbol = translated,
+            // the compiler fingerprint in the key means the derivers haven't changed
+            None => internal_error!(
+                "a type cache entry refers to {:?}, which it doesn't say how to derive",
+                symbol
+            ),
+        }
+    }
+}
+
+/// App and platform modules are checked against each other through `requires` and `provides`,
+/// so their types depend on more than just their imports. Builtins have their own cache.
+fn is_type_cacheable(header_type: &HeaderType) -> bool {
+    matches!(
+        header_type,
+        HeaderType::Interface { .. } | HeaderType::Hosted { .. }
+    )
+}
+
 fn run_solve<'a>(
     module: Module,
     ident_ids: IdentIds,
@@ -5195,6 +5452,7 @@ fn run_solve<'a>(
     decls: Declarations,
     dep_idents: IdentIdsByModule,
     cached_types: CachedTypeState,
+    type_cache_entry: Option<TypeCacheEntry>,
     derived_module: SharedDerivedModule,
 ) -> Msg<'a> {
     let solve_start = Instant::now();
@@ -5209,34 +5467,14 @@ fn run_solve<'a>(
     let loc_dbgs = std::mem::take(&mut module.loc_dbgs);
     let module = module;
 
-    let solve_result = {
-        if module_id.is_builtin() {
-            match cached_types.lock().remove(&module_id) {
-                None => run_solve_solve(
-                    exposed_for_module,
-                    types,
-                    constraints,
-                    constraint,
-                    pending_derives,
-                    var_store,
-                    module,
-                    derived_module,
-                ),
-                Some(TypeState {
-                    subs,
-                    exposed_vars_by_symbol,
-                    abilities,
-                    solved_implementations,
-                }) => SolveResult {
-                    solved: Solved(subs),
-                    solved_implementations,
-                    exposed_vars_by_symbol,
-                    problems: vec![],
-                    abilities_store: abilities,
-                },
-            }
-        } else {
-            run_solve_solve(
+    // Take the cached types out in their own statement, so we don't hold the lock while solving
+    let cached_type_state = cached_types.lock().remove(&module_id);
+
+    let solve_result = match cached_type_state {
+        None => {
+            let shared_derived_module = SharedDerivedModule::clone(&derived_module);
+
+            let solve_result = run_solve_solve(
                 exposed_for_module,
                 types,
                 constraints,
@@ -5245,7 +5483,53 @@ fn run_solve<'a>(
                 var_store,
                 module,
                 derived_module,
-            )
+            );
+
+            match type_cache_entry {
+                // We can't replay problems from the cache, so only cache modules that type-check
+                // cleanly.
+                Some(TypeCacheEntry::Write(type_cache, key))
+                    if solve_result.problems.is_empty() =>
+                {
+                    let derives = derives_referred_to(
+                        solve_result.solved.inner(),
+                        &shared_derived_module.lock().unwrap(),
+                    );
+
+                    match derives {
+                        Some(derives) => {
+                            write_type_cache(&type_cache, &key, solve_result, &derives)
+                        }
+                        None => solve_result,
+                    }
+                }
+                _ => solve_result,
+            }
+        }
+        Some(TypeState {
+            mut subs,
+            exposed_vars_by_symbol,
+            abilities,
+            solved_implementations,
+        }) => {
+            module_timing.solved_from_cache = true;
+
+            if let Some(TypeCacheEntry::Read(derives)) = &type_cache_entry {
+                rederive(
+                    &mut subs,
+                    derives,
+                    &mut derived_module.lock().unwrap(),
+                    &exposed_for_module.exposed_by_module,
+                );
+            }
+
+            SolveResult {
+                solved: Solved(subs),
+                solved_implementations,
+                exposed_vars_by_symbol,
+                problems: vec![],
+                abilities_store: abilities,
+            }
         }
     };
 
@@ -5567,6 +5851,8 @@ fn canonicalize_and_constrain<'a>(
                         | ModuleId::DICT
                         | ModuleId::SET
                         | ModuleId::HASH
+                        | ModuleId::INSPECT
+                        | ModuleId::SORT
                 );
 
                 if !name.is_builtin() || should_include_builtin {
@@ -5610,7 +5896,11 @@ fn canonicalize_and_constrain<'a>(
     }
 }
 
-fn parse<'a>(arena: &'a Bump, header: ModuleHeader<'a>) -> Result<Msg<'a>, LoadingProblem<'a>> {
+fn parse<'a>(
+    arena: &'a Bump,
+    header: ModuleHeader<'a>,
+    include_doc_tests: bool,
+) -> Result<Msg<'a>, LoadingProblem<'a>> {
     let mut module_timing = header.module_timing;
     let parse_start = Instant::now();
     let source = header.parse_state.original_bytes();
@@ -5628,6 +5918,31 @@ fn parse<'a>(arena: &'a Bump, header: ModuleHeader<'a>) -> Result<Msg<'a>, Loadi
         parsed_defs.push_value_def(value, Region::zero(), &[], &[]);
     }
 
+    let mut includes_doc_tests = false;
+
+    if include_doc_tests {
+        // SAFETY: the source was already parsed successfully, so it's valid UTF-8.
+        let src = unsafe { from_utf8_unchecked(source) };
+
+        for doc_test in doc_tests(src) {
+            // The masked source has the sample's code at the same offsets as in the module,
+            // so its regions (and any syntax errors) point into the doc comment.
+            let masked_src = arena.alloc_str(&doc_test.masked_src);
+            let doc_test_state = roc_parse::state::State::new(masked_src.as_bytes());
+            let doc_test_defs = match module_defs().parse(arena, doc_test_state.clone(), 0) {
+                Ok((_, success, _state)) => success,
+                Err((_, fail)) => {
+                    return Err(LoadingProblem::ParsingFailed(
+                        fail.into_file_error(header.module_path, &doc_test_state),
+                    ));
+                }
+            };
+
+            push_doc_test_expects(arena, &mut parsed_defs, doc_test_defs);
+            includes_doc_tests = true;
+        }
+    }
+
     // Record the parse end time once, to avoid checking the time a second time
     // immediately afterward (for the beginning of canonicalization).
     let parse_end = Instant::now();
@@ -5666,11 +5981,52 @@ fn parse<'a>(arena: &'a Bump, header: ModuleHeader<'a>) -> Result<Msg<'a>, Loadi
         symbols_from_requires,
         header_type,
         header_comments: header_docs,
+        includes_doc_tests,
     };
 
     Ok(Msg::Parsed(parsed))
 }
 
+/// Add a doc test's expects to the module's defs, as top-level expects. Any other defs in the
+/// sample are only in scope of its expects, so they can't clash with the module's own defs.
+fn push_doc_test_expects<'a>(arena: &'a Bump, parsed_defs: &mut Defs<'a>, doc_test_defs: Defs<'a>) {
+    let mut helpers = Defs::default();
+    let mut expects = Vec::new();
+
+    for (index, def) in doc_test_defs.defs().enumerate() {
+        let region = doc_test_defs.regions[index];
+
+        match def {
+            Err(ValueDef::Expect {
+                condition,
+                preceding_comment,
+            }) => expects.push((*condition, *preceding_comment, region)),
+            Err(value_def) => helpers.push_value_def(*value_def, region, &[], &[]),
+            Ok(type_def) => helpers.push_type_def(*type_def, region, &[], &[]),
+        }
+    }
+
+    let helpers: &'a Defs<'a> = arena.alloc(helpers);
+
+    for (condition, preceding_comment, region) in expects {
+        let condition = if helpers.is_empty() {
+            condition
+        } else {
+            &*arena.alloc(Loc::at(condition.region, Expr::Defs(helpers, condition)))
+        };
+
+        parsed_defs.push_value_def(
+            ValueDef::Expect {
+                condition,
+                preceding_comment,
+            },
+            region,
+            &[],
+            &[],
+        );
+    }
+}
+
 fn exposed_from_import<'a>(
     entry: &ImportsEntry<'a>,
 ) -> Option<(QualifiedModuleName<'a>, Vec<Loc<Ident>>)> {
@@ -5785,6 +6141,7 @@ fn make_specializations<'a>(
     world_abilities: WorldAbilities,
     exposed_by_module: &ExposedByModule,
     derived_module: SharedDerivedModule,
+    coverage: Option<Coverage>,
     mut expectations: Option<Expectations>,
 ) -> Msg<'a> {
     let make_specializations_start = Instant::now();
@@ -5803,7 +6160,9 @@ fn make_specializations<'a>(
         abilities: AbilitiesView::World(&world_abilities),
         exposed_by_module,
         derived_module: &derived_module,
+        coverage: coverage.as_ref(),
         struct_indexing: UsageTrackingMap::default(),
+        statement_regions: Vec::new(),
     };
 
     let mut procs = Procs::new_in(arena);
@@ -5870,6 +6229,7 @@ fn build_pending_specializations<'a>(
     exposed_by_module: &ExposedByModule,
     abilities_store: AbilitiesStore,
     derived_module: SharedDerivedModule,
+    coverage: Option<Coverage>,
     mut expectations: Option<Expectations>,
     build_expects: bool,
 ) -> Msg<'a> {
@@ -5904,7 +6264,9 @@ fn build_pending_specializations<'a>(
         abilities: AbilitiesView::Module(&abilities_store),
         exposed_by_module,
         derived_module: &derived_module,
+        coverage: coverage.as_ref(),
         struct_indexing: UsageTrackingMap::default(),
+        statement_regions: Vec::new(),
     };
 
     let layout_cache_snapshot = layout_cache.snapshot();
@@ -5923,6 +6285,16 @@ fn build_pending_specializations<'a>(
         let body = declarations.expressions[index].clone();
 
         let tag = declarations.declarations[index];
+
+        if let Some(coverage) = &coverage {
+            if !home.is_builtin()
+                && matches!(tag, Value | Function(_) | Recursive(_) | TailRecursive(_))
+            {
+                let name_region = declarations.symbols[index].region;
+                coverage.add_def(symbol, Region::span_across(&name_region, &body.region));
+            }
+        }
+
         match tag {
             Value => {
                 // If this is an exposed symbol, we need to
@@ -5991,6 +6363,7 @@ fn build_pending_specializations<'a>(
                             body_var: expr_var,
                             // This is a 0-arity thunk, so it cannot be recursive
                             is_self_recursive: false,
+                            region: body.region,
                         };
 
                         procs_base.partial_procs.insert(symbol, proc);
@@ -6130,6 +6503,7 @@ fn build_pending_specializations<'a>(
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: body.region,
                 };
 
                 procs_base.partial_procs.insert(symbol, proc);
@@ -6199,6 +6573,7 @@ fn build_pending_specializations<'a>(
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: body.region,
                 };
 
                 // extend the region of the expect expression with the region of the preceding
@@ -6272,6 +6647,7 @@ fn build_pending_specializations<'a>(
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: body.region,
                 };
 
                 // extend the region of the expect expression with the region of the preceding
@@ -6386,7 +6762,10 @@ fn load_derived_partial_procs<'a>(
             abilities: AbilitiesView::World(world_abilities),
             exposed_by_module,
             derived_module,
+            // Derived code isn't covered.
+            coverage: None,
             struct_indexing: UsageTrackingMap::default(),
+            statement_regions: Vec::new(),
         };
 
         let partial_proc = match derived_expr {
@@ -6424,6 +6803,8 @@ fn load_derived_partial_procs<'a>(
                     body_var: derived_expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    // Derived code has no source to point at.
+                    region: Region::zero(),
                 }
             }
         };
@@ -6470,7 +6851,10 @@ fn run_task<'a>(
             ident_ids_by_module,
         )
         .map(|HeaderOutput { msg, .. }| msg),
-        Parse { header } => parse(arena, header),
+        Parse {
+            header,
+            include_doc_tests,
+        } => parse(arena, header, include_doc_tests),
         CanonicalizeAndConstrain {
             parsed,
             module_ids,
@@ -6508,6 +6892,7 @@ fn run_task<'a>(
             declarations,
             dep_idents,
             cached_subs,
+            type_cache_entry,
             derived_module,
         } => Ok(run_solve(
             module,
@@ -6522,6 +6907,7 @@ fn run_task<'a>(
             declarations,
             dep_idents,
             cached_subs,
+            type_cache_entry,
             derived_module,
         )),
         BuildPendingSpecializations {
@@ -6536,6 +6922,7 @@ fn run_task<'a>(
             abilities_store,
             exposed_by_module,
             derived_module,
+            coverage,
             expectations,
             build_expects,
         } => Ok(build_pending_specializations(
@@ -6552,6 +6939,7 @@ fn run_task<'a>(
             &exposed_by_module,
             abilities_store,
             derived_module,
+            coverage,
             expectations,
             build_expects,
         )),
@@ -6566,6 +6954,7 @@ fn run_task<'a>(
             world_abilities,
             exposed_by_module,
             derived_module,
+            coverage,
             expectations,
         } => Ok(make_specializations(
             arena,
@@ -6580,6 +6969,7 @@ fn run_task<'a>(
             world_abilities,
             &exposed_by_module,
             derived_module,
+            coverage,
             expectations,
         )),
     }?;
@@ -6,6 +6,7 @@
 use roc_module::symbol::ModuleId;
 pub mod docs;
 pub mod file;
+mod type_cache;
 mod work;
 
 #[cfg(target_family = "wasm")]
@@ -23,5 +24,7 @@ pub const BUILTIN_MODULES: &[(ModuleId, &str)] = &[
     (ModuleId::ENCODE, "Encode"),
     (ModuleId::DECODE, "Decode"),
     (ModuleId::HASH, "Hash"),
+    (ModuleId::INSPECT, "Inspect"),
+    (ModuleId::SORT, "Sort"),
     (ModuleId::JSON, "TotallyNotJson"),
 ];
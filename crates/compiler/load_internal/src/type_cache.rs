@@ -0,0 +1,466 @@
+//! An on-disk cache of typechecked modules, so that modules which haven't changed since the
+//! last `roc` invocation don't need to be solved again.
+//!
+//! An entry is keyed by a hash of the module's source, its [ModuleId], and the keys of all the
+//! modules it imports. Any change to a module therefore invalidates the entries of everything
+//! that (transitively) depends on it.
+//!
+//! Solving a module can also derive implementations of builtin abilities, which live in a module
+//! shared by the whole build and are referred to by symbols that depend on the order they were
+//! derived in. So an entry also lists the [DeriveKey]s it refers to, and the symbols they had;
+//! reading it back derives them again and translates the symbols.
+use roc_can::module::TypeState;
+use roc_derive_key::{
+    compare::FlatCompareKey, decoding::FlatDecodableKey, encoding::FlatEncodableKey,
+    hash::FlatHashKey, inspect::FlatInspectableKey, DeriveKey,
+};
+use roc_module::ident::{Lowercase, TagName};
+use roc_module::symbol::ModuleId;
+use roc_packaging::cache::RocCacheDir;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+
+const TYPES_DIR_NAME: &str = "types";
+const CHECKSUM_LEN: usize = blake3::OUT_LEN;
+
+/// Identifies the typechecked state of one module, together with everything that state depends on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CacheKey(blake3::Hash);
+
+#[derive(Debug, Clone)]
+pub(crate) struct TypeCache {
+    dir: PathBuf,
+    /// Changes whenever the compiler does, because a new compiler may solve (or serialize) types
+    /// differently.
+    compiler_fingerprint: blake3::Hash,
+}
+
+/// What solving a module has to do with the type cache.
+pub(crate) enum TypeCacheEntry {
+    /// The module has to be solved; write the result under this key.
+    Write(TypeCache, CacheKey),
+    /// The module's types were read from the cache, and refer to these derived implementations.
+    Read(Vec<CachedDerive>),
+}
+
+/// A derived implementation that a cached module refers to, and the idents it was made with.
+#[derive(Debug, Clone)]
+pub(crate) struct CachedDerive {
+    pub key: DeriveKey,
+    pub idents: Range<u32>,
+}
+
+/// Lets us feed anything that implements [Hash] into a [blake3::Hasher], whose output is stable
+/// across processes.
+struct KeyHasher<'a>(&'a mut blake3::Hasher);
+
+impl Hasher for KeyHasher<'_> {
+    fn finish(&self) -> u64 {
+        unreachable!("only the blake3 hash is used")
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+}
+
+impl TypeCache {
+    /// The cache lives next to the downloaded packages. Tests don't get to use it, because they
+    /// load with [RocCacheDir::Disallowed].
+    pub fn new(roc_cache_dir: RocCacheDir<'_>) -> Option<Self> {
+        match roc_cache_dir {
+            RocCacheDir::Persistent(packages_dir)
+            | RocCacheDir::Offline {
+                cache_dir: packages_dir,
+                ..
+            } => {
+                let cache_dir = packages_dir.parent().unwrap_or(packages_dir);
+
+                Some(Self {
+                    dir: cache_dir.join(TYPES_DIR_NAME),
+                    compiler_fingerprint: compiler_fingerprint()?,
+                })
+            }
+            RocCacheDir::Disallowed => None,
+        }
+    }
+
+    pub fn key<'k>(
+        &self,
+        module_id: ModuleId,
+        src: &str,
+        includes_doc_tests: bool,
+        imports: impl IntoIterator<Item = (ModuleId, Option<&'k CacheKey>)>,
+    ) -> Option<CacheKey> {
+        let mut hasher = blake3::Hasher::new();
+
+        hasher.update(self.compiler_fingerprint.as_bytes());
+        module_id.hash(&mut KeyHasher(&mut hasher));
+        hasher.update(&(src.len() as u64).to_le_bytes());
+        hasher.update(src.as_bytes());
+
+        // `roc test` adds the expects in doc comments to a module, and those have to be solved too
+        hasher.update(&[includes_doc_tests as u8]);
+
+        // Hash each import on its own and sort the results, so the order of imports doesn't matter
+        let mut import_hashes = Vec::new();
+
+        for (import_id, import_key) in imports {
+            let mut import_hasher = blake3::Hasher::new();
+            import_id.hash(&mut KeyHasher(&mut import_hasher));
+
+            // Builtins are part of the compiler, so the fingerprint already covers them.
+            match import_key {
+                Some(CacheKey(import_hash)) => {
+                    import_hasher.update(import_hash.as_bytes());
+                }
+                None if import_id.is_builtin() => {}
+                None => return None,
+            }
+
+            import_hashes.push(*import_hasher.finalize().as_bytes());
+        }
+
+        import_hashes.sort_unstable();
+
+        for import_hash in import_hashes {
+            hasher.update(&import_hash);
+        }
+
+        Some(CacheKey(hasher.finalize()))
+    }
+
+    fn path(&self, key: &CacheKey) -> PathBuf {
+        self.dir.join(format!("{}.dat", key.0.to_hex()))
+    }
+
+    /// Returns `None` if there's no entry for this key, or the entry is unusable.
+    pub fn read(&self, key: &CacheKey) -> Option<(TypeState, Vec<CachedDerive>)> {
+        let bytes = fs::read(self.path(key)).ok()?;
+
+        if bytes.len() < CHECKSUM_LEN {
+            return None;
+        }
+
+        let (payload, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+
+        if blake3::hash(payload).as_bytes() != checksum {
+            return None;
+        }
+
+        // Deserialization reinterprets the bytes as slices of the serialized types, and
+        // `fs::read` makes no promises about the alignment of its buffer.
+        let mut aligned = vec![0u64; (payload.len() + 7) / 8];
+        let aligned_bytes = unsafe {
+            std::slice::from_raw_parts_mut(aligned.as_mut_ptr() as *mut u8, payload.len())
+        };
+        aligned_bytes.copy_from_slice(payload);
+
+        let (state, len) = TypeState::deserialize(aligned_bytes);
+        let mut reader = Reader(payload.get(len..)?);
+        let derives = read_derives(&mut reader)?;
+
+        reader.0.is_empty().then_some((state, derives))
+    }
+
+    /// Writing is best-effort: if it fails, the module just gets solved again next time.
+    pub fn write(&self, key: &CacheKey, state: &TypeState, derives: &[CachedDerive]) {
+        let _ = self.try_write(key, state, derives);
+    }
+
+    fn try_write(
+        &self,
+        key: &CacheKey,
+        state: &TypeState,
+        derives: &[CachedDerive],
+    ) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        state.serialize(&mut bytes)?;
+        write_derives(&mut bytes, derives);
+        let checksum = blake3::hash(&bytes);
+        bytes.extend_from_slice(checksum.as_bytes());
+
+        fs::create_dir_all(&self.dir)?;
+
+        // Concurrent `roc` invocations may race to write the same entry, so write to a file
+        // nobody else reads and then atomically move it into place.
+        let path = self.path(key);
+        let tmp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+
+        let result = fs::File::create(&tmp_path)
+            .and_then(|mut file| file.write_all(&bytes))
+            .and_then(|()| fs::rename(&tmp_path, &path));
+
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp_path);
+        }
+
+        result
+    }
+}
+
+/// The version, plus the size and modification time of the running executable. This is cheaper
+/// than hashing the executable, and changes whenever it gets rebuilt.
+fn compiler_fingerprint() -> Option<blake3::Hash> {
+    let exe_metadata = std::env::current_exe().and_then(fs::metadata).ok()?;
+    let modified = exe_metadata
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    hasher.update(&exe_metadata.len().to_le_bytes());
+    hasher.update(&modified.as_nanos().to_le_bytes());
+
+    Some(hasher.finalize())
+}
+
+/// The shape of a [DeriveKey], which is the same for every ability that can derive it.
+enum Shape {
+    List,
+    Set,
+    Dict,
+    Box,
+    Record(Vec<Lowercase>),
+    Tuple(u32),
+    TagUnion(Vec<(TagName, u16)>),
+}
+
+const ABILITY_TO_ENCODER: u8 = 0;
+const ABILITY_DECODER: u8 = 1;
+const ABILITY_HASH: u8 = 2;
+const ABILITY_TO_INSPECTOR: u8 = 3;
+const ABILITY_COMPARE: u8 = 4;
+
+fn write_derives(bytes: &mut Vec<u8>, derives: &[CachedDerive]) {
+    write_u32(bytes, derives.len() as u32);
+
+    for CachedDerive { key, idents } in derives {
+        write_u32(bytes, idents.start);
+        write_u32(bytes, idents.end);
+        write_derive_key(bytes, key);
+    }
+}
+
+fn write_derive_key(bytes: &mut Vec<u8>, key: &DeriveKey) {
+    use Shape::*;
+
+    let (ability, shape) = match key.clone() {
+        DeriveKey::ToEncoder(key) => (
+            ABILITY_TO_ENCODER,
+            match key {
+                FlatEncodableKey::List() => List,
+                FlatEncodableKey::Set() => Set,
+                FlatEncodableKey::Dict() => Dict,
+                FlatEncodableKey::Record(fields) => Record(fields),
+                FlatEncodableKey::Tuple(arity) => Tuple(arity),
+                FlatEncodableKey::TagUnion(tags) => TagUnion(tags),
+            },
+        ),
+        DeriveKey::Decoder(key) => (
+            ABILITY_DECODER,
+            match key {
+                FlatDecodableKey::List() => List,
+                FlatDecodableKey::Set() => Set,
+                FlatDecodableKey::Dict() => Dict,
+                FlatDecodableKey::Record(fields) => Record(fields),
+                FlatDecodableKey::Tuple(arity) => Tuple(arity),
+                FlatDecodableKey::TagUnion(tags) => TagUnion(tags),
+            },
+        ),
+        DeriveKey::Hash(key) => (
+            ABILITY_HASH,
+            match key {
+                FlatHashKey::Record(fields) => Record(fields),
+                FlatHashKey::Tuple(arity) => Tuple(arity),
+                FlatHashKey::TagUnion(tags) => TagUnion(tags),
+            },
+        ),
+        DeriveKey::ToInspector(key) => (
+            ABILITY_TO_INSPECTOR,
+            match key {
+                FlatInspectableKey::List() => List,
+                FlatInspectableKey::Set() => Set,
+                FlatInspectableKey::Dict() => Dict,
+                FlatInspectableKey::Box() => Box,
+                FlatInspectableKey::Record(fields) => Record(fields),
+                FlatInspectableKey::Tuple(arity) => Tuple(arity),
+                FlatInspectableKey::TagUnion(tags) => TagUnion(tags),
+            },
+        ),
+        DeriveKey::Compare(key) => (
+            ABILITY_COMPARE,
+            match key {
+                FlatCompareKey::Record(fields) => Record(fields),
+                FlatCompareKey::Tuple(arity) => Tuple(arity),
+                FlatCompareKey::TagUnion(tags) => TagUnion(tags),
+            },
+        ),
+    };
+
+    bytes.push(ability);
+
+    match shape {
+        List => bytes.push(0),
+        Set => bytes.push(1),
+        Dict => bytes.push(2),
+        Box => bytes.push(3),
+        Record(fields) => {
+            bytes.push(4);
+            write_u32(bytes, fields.len() as u32);
+
+            for field in fields {
+                write_str(bytes, field.as_str());
+            }
+        }
+        Tuple(arity) => {
+            bytes.push(5);
+            write_u32(bytes, arity);
+        }
+        TagUnion(tags) => {
+            bytes.push(6);
+            write_u32(bytes, tags.len() as u32);
+
+            for (tag, arity) in tags {
+                write_str(bytes, tag.0.as_str());
+                bytes.extend_from_slice(&arity.to_le_bytes());
+            }
+        }
+    }
+}
+
+fn write_u32(bytes: &mut Vec<u8>, n: u32) {
+    bytes.extend_from_slice(&n.to_le_bytes());
+}
+
+fn write_str(bytes: &mut Vec<u8>, string: &str) {
+    write_u32(bytes, string.len() as u32);
+    bytes.extend_from_slice(string.as_bytes());
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if len > self.0.len() {
+            return None;
+        }
+
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.bytes(2)?.try_into().ok()?))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+
+    fn str(&mut self) -> Option<&'a str> {
+        let len = self.u32()? as usize;
+
+        std::str::from_utf8(self.bytes(len)?).ok()
+    }
+}
+
+fn read_derives(reader: &mut Reader) -> Option<Vec<CachedDerive>> {
+    let len = reader.u32()?;
+
+    (0..len)
+        .map(|_| {
+            let idents = reader.u32()?..reader.u32()?;
+            let key = read_derive_key(reader)?;
+
+            Some(CachedDerive { key, idents })
+        })
+        .collect()
+}
+
+fn read_derive_key(reader: &mut Reader) -> Option<DeriveKey> {
+    use Shape::*;
+
+    let ability = reader.u8()?;
+    let shape = match reader.u8()? {
+        0 => List,
+        1 => Set,
+        2 => Dict,
+        3 => Box,
+        4 => {
+            let len = reader.u32()?;
+            let fields = (0..len)
+                .map(|_| Some(Lowercase::from(reader.str()?)))
+                .collect::<Option<_>>()?;
+
+            Record(fields)
+        }
+        5 => Tuple(reader.u32()?),
+        6 => {
+            let len = reader.u32()?;
+            let tags = (0..len)
+                .map(|_| Some((TagName(reader.str()?.into()), reader.u16()?)))
+                .collect::<Option<_>>()?;
+
+            TagUnion(tags)
+        }
+        _ => return None,
+    };
+
+    let key = match (ability, shape) {
+        (ABILITY_TO_ENCODER, List) => DeriveKey::ToEncoder(FlatEncodableKey::List()),
+        (ABILITY_TO_ENCODER, Set) => DeriveKey::ToEncoder(FlatEncodableKey::Set()),
+        (ABILITY_TO_ENCODER, Dict) => DeriveKey::ToEncoder(FlatEncodableKey::Dict()),
+        (ABILITY_TO_ENCODER, Record(fields)) => {
+            DeriveKey::ToEncoder(FlatEncodableKey::Record(fields))
+        }
+        (ABILITY_TO_ENCODER, Tuple(arity)) => DeriveKey::ToEncoder(FlatEncodableKey::Tuple(arity)),
+        (ABILITY_TO_ENCODER, TagUnion(tags)) => {
+            DeriveKey::ToEncoder(FlatEncodableKey::TagUnion(tags))
+        }
+
+        (ABILITY_DECODER, List) => DeriveKey::Decoder(FlatDecodableKey::List()),
+        (ABILITY_DECODER, Set) => DeriveKey::Decoder(FlatDecodableKey::Set()),
+        (ABILITY_DECODER, Dict) => DeriveKey::Decoder(FlatDecodableKey::Dict()),
+        (ABILITY_DECODER, Record(fields)) => DeriveKey::Decoder(FlatDecodableKey::Record(fields)),
+        (ABILITY_DECODER, Tuple(arity)) => DeriveKey::Decoder(FlatDecodableKey::Tuple(arity)),
+        (ABILITY_DECODER, TagUnion(tags)) => DeriveKey::Decoder(FlatDecodableKey::TagUnion(tags)),
+
+        (ABILITY_HASH, Record(fields)) => DeriveKey::Hash(FlatHashKey::Record(fields)),
+        (ABILITY_HASH, Tuple(arity)) => DeriveKey::Hash(FlatHashKey::Tuple(arity)),
+        (ABILITY_HASH, TagUnion(tags)) => DeriveKey::Hash(FlatHashKey::TagUnion(tags)),
+
+        (ABILITY_TO_INSPECTOR, List) => DeriveKey::ToInspector(FlatInspectableKey::List()),
+        (ABILITY_TO_INSPECTOR, Set) => DeriveKey::ToInspector(FlatInspectableKey::Set()),
+        (ABILITY_TO_INSPECTOR, Dict) => DeriveKey::ToInspector(FlatInspectableKey::Dict()),
+        (ABILITY_TO_INSPECTOR, Box) => DeriveKey::ToInspector(FlatInspectableKey::Box()),
+        (ABILITY_TO_INSPECTOR, Record(fields)) => {
+            DeriveKey::ToInspector(FlatInspectableKey::Record(fields))
+        }
+        (ABILITY_TO_INSPECTOR, Tuple(arity)) => {
+            DeriveKey::ToInspector(FlatInspectableKey::Tuple(arity))
+        }
+        (ABILITY_TO_INSPECTOR, TagUnion(tags)) => {
+            DeriveKey::ToInspector(FlatInspectableKey::TagUnion(tags))
+        }
+
+        (ABILITY_COMPARE, Record(fields)) => DeriveKey::Compare(FlatCompareKey::Record(fields)),
+        (ABILITY_COMPARE, Tuple(arity)) => DeriveKey::Compare(FlatCompareKey::Tuple(arity)),
+        (ABILITY_COMPARE, TagUnion(tags)) => DeriveKey::Compare(FlatCompareKey::TagUnion(tags)),
+
+        _ => return None,
+    };
+
+    Some(key)
+}
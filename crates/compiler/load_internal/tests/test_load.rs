@@ -31,7 +31,7 @@ use roc_target::TargetInfo;
 use roc_types::pretty_print::name_and_print_var;
 use roc_types::pretty_print::DebugPrint;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 fn load_and_typecheck(
     arena: &Bump,
@@ -39,13 +39,29 @@ fn load_and_typecheck(
     exposed_types: ExposedByModule,
     target_info: TargetInfo,
 ) -> Result<LoadedModule, LoadingProblem> {
+    load_and_typecheck_with_cache_dir(
+        arena,
+        filename,
+        exposed_types,
+        target_info,
+        RocCacheDir::Disallowed,
+    )
+}
+
+fn load_and_typecheck_with_cache_dir<'a>(
+    arena: &'a Bump,
+    filename: PathBuf,
+    exposed_types: ExposedByModule,
+    target_info: TargetInfo,
+    roc_cache_dir: RocCacheDir<'_>,
+) -> Result<LoadedModule, LoadingProblem<'a>> {
     use LoadResult::*;
 
     let load_start = LoadStart::from_path(
         arena,
         filename,
         RenderTarget::Generic,
-        RocCacheDir::Disallowed,
+        roc_cache_dir,
         DEFAULT_PALETTE,
     )?;
     let load_config = LoadConfig {
@@ -61,7 +77,7 @@ fn load_and_typecheck(
         load_start,
         exposed_types,
         Default::default(), // these tests will re-compile the builtins
-        RocCacheDir::Disallowed,
+        roc_cache_dir,
         load_config,
     )? {
         Monomorphized(_) => unreachable!(""),
@@ -537,6 +553,259 @@ fn iface_dep_types() {
     );
 }
 
+/// Loads with a type cache in `cache_dir`, and returns the loaded module along with the names of
+/// the modules whose types were read back from the cache.
+fn load_with_type_cache(filename: &Path, cache_dir: &Path) -> (LoadedModule, Vec<String>) {
+    let arena = Bump::new();
+    let packages_dir = cache_dir.join("packages");
+    let loaded_module = load_and_typecheck_with_cache_dir(
+        &arena,
+        filename.to_path_buf(),
+        Default::default(),
+        TARGET_INFO,
+        RocCacheDir::Persistent(&packages_dir),
+    )
+    .unwrap();
+
+    let mut from_cache: Vec<String> = loaded_module
+        .timings
+        .iter()
+        .filter(|(module_id, timing)| !module_id.is_builtin() && timing.solved_from_cache)
+        .map(|(module_id, _)| {
+            loaded_module
+                .interns
+                .module_name(*module_id)
+                .as_str()
+                .to_string()
+        })
+        .collect();
+    from_cache.sort();
+
+    (loaded_module, from_cache)
+}
+
+fn type_cache_entries(cache_dir: &Path) -> usize {
+    std::fs::read_dir(cache_dir.join("types")).unwrap().count()
+}
+
+#[test]
+fn type_cache_reuses_unchanged_modules() {
+    let cache_dir = roc_test_utils::TmpDir::new("tmp/type_cache_reuses_unchanged_modules");
+    let filename = fixtures_dir()
+        .join("interface_with_deps")
+        .join("Primary.roc");
+
+    let load = || {
+        let (loaded_module, from_cache) = load_with_type_cache(&filename, cache_dir.path());
+
+        expect_types(
+            loaded_module,
+            hashmap! {
+                "blah2" => "Float *",
+                "blah3" => "Str",
+                "str" => "Str",
+                "alwaysThree" => "* -> Float *",
+                "identity" => "a -> a",
+                "z" => "Float *",
+                "w" => "Dep1.Identity {}",
+                "succeed" => "a -> Dep1.Identity a",
+                "yay" => "Res.Res {} err",
+                "withDefault" => "Res.Res a err, a -> a",
+            },
+        );
+
+        from_cache
+    };
+
+    assert_eq!(load(), Vec::<String>::new());
+    assert_eq!(type_cache_entries(cache_dir.path()), 5);
+
+    // Nothing changed, so every module is read back from the cache, and no new entries appear
+    assert_eq!(load(), ["Dep1", "Dep2", "Dep3.Blah", "Primary", "Res"]);
+    assert_eq!(type_cache_entries(cache_dir.path()), 5);
+}
+
+#[test]
+fn type_cache_invalidates_changed_modules_and_their_dependents() {
+    let cache_dir = roc_test_utils::TmpDir::new("tmp/type_cache_invalidates_changed_modules");
+    let src_dir = roc_test_utils::TmpDir::new("tmp/type_cache_invalidates_changed_modules_src");
+    let fixture_dir = fixtures_dir().join("interface_with_deps");
+
+    std::fs::create_dir(src_dir.path().join("Dep3")).unwrap();
+    for file in [
+        "Primary.roc",
+        "Dep1.roc",
+        "Dep2.roc",
+        "Dep3/Blah.roc",
+        "Res.roc",
+    ] {
+        std::fs::copy(fixture_dir.join(file), src_dir.path().join(file)).unwrap();
+    }
+
+    let filename = src_dir.path().join("Primary.roc");
+
+    let (_, from_cache) = load_with_type_cache(&filename, cache_dir.path());
+    assert_eq!(from_cache, Vec::<String>::new());
+
+    // Only Primary imports Dep2, so everything else can still come from the cache
+    let dep2 = src_dir.path().join("Dep2.roc");
+    let src = std::fs::read_to_string(&dep2).unwrap();
+    std::fs::write(&dep2, format!("{}\nthree = 3\n", src)).unwrap();
+
+    let (_, from_cache) = load_with_type_cache(&filename, cache_dir.path());
+    assert_eq!(from_cache, ["Dep1", "Dep3.Blah", "Res"]);
+    assert_eq!(type_cache_entries(cache_dir.path()), 7);
+
+    // Changing Dep3.Blah invalidates everything that depends on it, transitively
+    let blah = src_dir.path().join("Dep3").join("Blah.roc");
+    let src = std::fs::read_to_string(&blah).unwrap();
+    std::fs::write(&blah, format!("{}\nbaz = 3\n", src)).unwrap();
+
+    let (_, from_cache) = load_with_type_cache(&filename, cache_dir.path());
+    assert_eq!(from_cache, ["Res"]);
+}
+
+#[test]
+fn type_cache_rederives_implementations() {
+    let cache_dir = roc_test_utils::TmpDir::new("tmp/type_cache_rederives_implementations");
+    let src_dir = roc_test_utils::TmpDir::new("tmp/type_cache_rederives_implementations_src");
+    let write = |name: &str, src: &str| std::fs::write(src_dir.path().join(name), src).unwrap();
+
+    write(
+        "DepA.roc",
+        indoc!(
+            r#"
+            interface DepA exposes [a] imports []
+
+            a = Inspect.toStr { x: 1u8 }
+            "#
+        ),
+    );
+    write(
+        "DepB.roc",
+        indoc!(
+            r#"
+            interface DepB exposes [b] imports []
+
+            b = Inspect.toStr { y: "y" }
+            "#
+        ),
+    );
+    write(
+        "Main.roc",
+        indoc!(
+            r#"
+            interface Main exposes [both] imports [DepA, DepB]
+
+            both = Str.concat DepA.a DepB.b
+            "#
+        ),
+    );
+
+    let filename = src_dir.path().join("Main.roc");
+    let load = || {
+        let (loaded_module, from_cache) = load_with_type_cache(&filename, cache_dir.path());
+
+        expect_types(loaded_module, hashmap! { "both" => "Str" });
+
+        from_cache
+    };
+
+    assert_eq!(load(), Vec::<String>::new());
+
+    // DepA now derives something else first, so what DepB derives gets a different symbol than
+    // it had when DepB was cached
+    write(
+        "DepA.roc",
+        indoc!(
+            r#"
+            interface DepA exposes [a] imports []
+
+            a = Inspect.toStr { x: 1u8, z: [Z] }
+            "#
+        ),
+    );
+
+    assert_eq!(load(), ["DepB"]);
+    assert_eq!(load(), ["DepA", "DepB", "Main"]);
+}
+
+#[test]
+fn type_cache_keeps_doc_tests_apart_from_check() {
+    let cache_dir = roc_test_utils::TmpDir::new("tmp/type_cache_keeps_doc_tests_apart_from_check");
+    let src_dir =
+        roc_test_utils::TmpDir::new("tmp/type_cache_keeps_doc_tests_apart_from_check_src");
+    let filename = src_dir.path().join("Doubles.roc");
+
+    std::fs::write(
+        &filename,
+        indoc!(
+            r#"
+            interface Doubles exposes [double] imports []
+
+            ## ```
+            ## two = 2
+            ##
+            ## expect double two == 4
+            ## ```
+            double : I64 -> I64
+            double = \n -> n * 2
+            "#
+        ),
+    )
+    .unwrap();
+
+    // `roc check` caches the module without its doc tests
+    let (_, from_cache) = load_with_type_cache(&filename, cache_dir.path());
+    assert_eq!(from_cache, Vec::<String>::new());
+
+    let test = || {
+        let arena = Bump::new();
+        let packages_dir = cache_dir.path().join("packages");
+        let roc_cache_dir = RocCacheDir::Persistent(&packages_dir);
+        let load_start = LoadStart::from_path(
+            &arena,
+            filename.clone(),
+            RenderTarget::Generic,
+            roc_cache_dir,
+            DEFAULT_PALETTE,
+        )
+        .unwrap();
+        let load_config = LoadConfig {
+            target_info: TARGET_INFO,
+            render: RenderTarget::Generic,
+            palette: DEFAULT_PALETTE,
+            threading: Threading::Single,
+            exec_mode: ExecutionMode::Test,
+        };
+
+        let module = match roc_load_internal::file::load(
+            &arena,
+            load_start,
+            Default::default(),
+            Default::default(),
+            roc_cache_dir,
+            load_config,
+        )
+        .unwrap()
+        {
+            LoadResult::Monomorphized(module) => module,
+            LoadResult::TypeChecked(_) => unreachable!(""),
+        };
+
+        let home = module.module_id;
+        let expects = module.expectations.get(&home).unwrap().expectations.len();
+
+        (expects, module.timings[&home].solved_from_cache)
+    };
+
+    // `roc test` has to solve the doc tests, so it can't use what `roc check` cached...
+    assert_eq!(test(), (1, false));
+
+    // ...but it can use what an earlier `roc test` cached
+    assert_eq!(test(), (1, true));
+}
+
 #[test]
 fn app_dep_types() {
     let subs_by_module = Default::default();
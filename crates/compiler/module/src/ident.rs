@@ -113,6 +113,8 @@ impl ModuleName {
     pub const ENCODE: &'static str = "Encode";
     pub const DECODE: &'static str = "Decode";
     pub const HASH: &'static str = "Hash";
+    pub const INSPECT: &'static str = "Inspect";
+    pub const SORT: &'static str = "Sort";
     pub const JSON: &'static str = "TotallyNotJson";
 
     pub fn as_str(&self) -> &str {
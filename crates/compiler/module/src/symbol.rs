@@ -53,6 +53,8 @@ pub const DERIVABLE_ABILITIES: &[(Symbol, &[Symbol])] = &[
     (Symbol::DECODE_DECODING, &[Symbol::DECODE_DECODER]),
     (Symbol::HASH_HASH_ABILITY, &[Symbol::HASH_HASH]),
     (Symbol::BOOL_EQ, &[Symbol::BOOL_IS_EQ]),
+    (Symbol::INSPECT_INSPECT_ABILITY, &[Symbol::INSPECT_TO_INSPECTOR]),
+    (Symbol::SORT_SORT_ABILITY, &[Symbol::SORT_COMPARE]),
 ];
 
 /// In Debug builds only, Symbol has a name() method that lets
@@ -1344,6 +1346,7 @@ define_builtins! {
         56 STR_IS_VALID_SCALAR: "isValidScalar"
         57 STR_RELEASE_EXCESS_CAPACITY: "releaseExcessCapacity"
         58 STR_WALK_UTF8: "walkUtf8"
+        59 STR_COMPARE: "compare"
     }
     6 LIST: "List" => {
         0 LIST_LIST: "List" exposed_apply_type=true // the List.List type alias
@@ -1429,6 +1432,8 @@ define_builtins! {
         80 LIST_ITER_HELP: "iterHelp"
         81 LIST_RELEASE_EXCESS_CAPACITY: "releaseExcessCapacity"
         82 LIST_UPDATE: "update"
+        83 LIST_SORT: "sort"
+        84 LIST_COMPARE: "compare"
     }
     7 RESULT: "Result" => {
         0 RESULT_RESULT: "Result" exposed_type=true // the Result.Result type alias
@@ -1471,6 +1476,8 @@ define_builtins! {
 
         22 DICT_LIST_GET_UNSAFE: "listGetUnsafe"
         23 DICT_PSEUDO_SEED: "pseudoSeed"
+        24 DICT_TO_SORTED_LIST: "toSortedList"
+        25 DICT_SORTED_KEYS: "sortedKeys"
     }
     9 SET: "Set" => {
         0 SET_SET: "Set" exposed_type=true // the Set.Set type alias
@@ -1490,6 +1497,7 @@ define_builtins! {
         14 SET_CONTAINS: "contains"
         15 SET_TO_DICT: "toDict"
         16 SET_CAPACITY: "capacity"
+        17 SET_TO_SORTED_LIST: "toSortedList"
     }
     10 BOX: "Box" => {
         0 BOX_BOX_TYPE: "Box" exposed_apply_type=true // the Box.Box opaque type
@@ -1555,6 +1563,7 @@ define_builtins! {
         26 DECODE_FROM_BYTES_PARTIAL: "fromBytesPartial"
         27 DECODE_FROM_BYTES: "fromBytes"
         28 DECODE_MAP_RESULT: "mapResult"
+        29 DECODE_TAG: "tag"
     }
     13 HASH: "Hash" => {
         0 HASH_HASH_ABILITY: "Hash" exposed_type=true
@@ -1580,9 +1589,57 @@ define_builtins! {
         20 HASH_HASH_LIST: "hashList"
         21 HASH_HASH_UNORDERED: "hashUnordered"
     }
-    14 JSON: "TotallyNotJson" => {
+    14 INSPECT: "Inspect" => {
+        0 INSPECT_INSPECT_ABILITY: "Inspect" exposed_type=true
+        1 INSPECT_TO_INSPECTOR: "toInspector"
+        2 INSPECT_INSPECTOR: "Inspector" exposed_type=true
+        3 INSPECT_INSPECT_FORMATTER: "InspectFormatter" exposed_type=true
+        4 INSPECT_INIT: "init"
+        5 INSPECT_TAG: "tag"
+        6 INSPECT_TUPLE: "tuple"
+        7 INSPECT_RECORD: "record"
+        8 INSPECT_BOOL: "bool"
+        9 INSPECT_STR: "str"
+        10 INSPECT_LIST: "list"
+        11 INSPECT_SET: "set"
+        12 INSPECT_DICT: "dict"
+        13 INSPECT_OPAQUE: "opaque"
+        14 INSPECT_FUNCTION: "function"
+        15 INSPECT_U8: "u8"
+        16 INSPECT_I8: "i8"
+        17 INSPECT_U16: "u16"
+        18 INSPECT_I16: "i16"
+        19 INSPECT_U32: "u32"
+        20 INSPECT_I32: "i32"
+        21 INSPECT_U64: "u64"
+        22 INSPECT_I64: "i64"
+        23 INSPECT_U128: "u128"
+        24 INSPECT_I128: "i128"
+        25 INSPECT_F32: "f32"
+        26 INSPECT_F64: "f64"
+        27 INSPECT_DEC: "dec"
+        28 INSPECT_NAT: "nat"
+        29 INSPECT_CUSTOM: "custom"
+        30 INSPECT_APPLY: "apply"
+        31 INSPECT_INSPECT: "inspect"
+        32 INSPECT_DBG_FORMATTER: "DbgFormatter" exposed_type=true
+        33 INSPECT_TO_DBG_STR: "toDbgStr"
+        34 INSPECT_TO_STR: "toStr"
+        35 INSPECT_BOX: "box"
+    }
+    15 SORT: "Sort" => {
+        0 SORT_SORT_ABILITY: "Sort" exposed_type=true
+        1 SORT_COMPARE: "compare"
+        2 SORT_IS_LT: "isLt"
+        3 SORT_IS_LTE: "isLte"
+        4 SORT_IS_GT: "isGt"
+        5 SORT_IS_GTE: "isGte"
+        6 SORT_MIN: "min"
+        7 SORT_MAX: "max"
+    }
+    16 JSON: "TotallyNotJson" => {
         0 JSON_JSON: "TotallyNotJson"
     }
 
-    num_modules: 15 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
+    num_modules: 17 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
 }
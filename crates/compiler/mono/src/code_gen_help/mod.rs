@@ -452,6 +452,8 @@ impl<'a> CodeGenHelp<'a> {
             ret_layout,
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+            region: roc_region::all::Region::zero(),
+            statement_regions: &[],
         });
 
         proc_symbol
@@ -774,6 +776,8 @@ impl<'a> CallerProc<'a> {
             ret_layout: Layout::UNIT,
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+            region: roc_region::all::Region::zero(),
+            statement_regions: &[],
         };
 
         if false {
@@ -0,0 +1,141 @@
+//! Instrumentation for code coverage.
+//!
+//! When coverage is enabled, every top-level def body, `when` branch and `if` arm of non-builtin
+//! modules starts with a call to [COVERAGE_HIT], passing the id of its region. The runtime keeps a
+//! counter for each id, and the ids map back to source regions through [Coverage::regions].
+use std::sync::{Arc, Mutex};
+
+use roc_collections::all::MutMap;
+use roc_module::ident::ForeignSymbol;
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_region::all::Region;
+
+use crate::ir::{Call, CallType, Env, Expr, Literal, Stmt};
+use crate::layout::Layout;
+
+/// `void roc_coverage_hit(uint32_t id)`. The builtins provide a weak definition that counts into
+/// the buffer given to `set_coverage_buffer`; a host can provide its own.
+pub const COVERAGE_HIT: &str = "roc_coverage_hit";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CoveredKind {
+    /// The body of a top-level def. Counted every time a function is called, or a constant is
+    /// evaluated.
+    Def(Symbol),
+    /// A `when` branch or an `if` arm.
+    Branch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CoveredRegion {
+    pub module_id: ModuleId,
+    pub region: Region,
+    pub kind: CoveredKind,
+}
+
+#[derive(Debug, Default)]
+struct CoverageTable {
+    /// Indexed by id.
+    regions: Vec<CoveredRegion>,
+    ids: MutMap<CoveredRegion, u32>,
+    defs: MutMap<Symbol, u32>,
+}
+
+impl CoverageTable {
+    fn id(&mut self, covered: CoveredRegion) -> u32 {
+        let next_id = self.regions.len() as u32;
+        let regions = &mut self.regions;
+
+        *self.ids.entry(covered).or_insert_with(|| {
+            regions.push(covered);
+            next_id
+        })
+    }
+}
+
+/// Hands out ids for covered regions. Modules are specialized in parallel, so this is shared
+/// between threads; every specialization of the same region gets the same id.
+#[derive(Debug, Default, Clone)]
+pub struct Coverage(Arc<Mutex<CoverageTable>>);
+
+impl Coverage {
+    /// Registers a top-level def, so that it is reported even if it is never specialized.
+    pub fn add_def(&self, symbol: Symbol, region: Region) {
+        let mut table = self.0.lock().unwrap();
+        let id = table.id(CoveredRegion {
+            module_id: symbol.module_id(),
+            region,
+            kind: CoveredKind::Def(symbol),
+        });
+
+        table.defs.insert(symbol, id);
+    }
+
+    fn def_id(&self, symbol: Symbol) -> Option<u32> {
+        self.0.lock().unwrap().defs.get(&symbol).copied()
+    }
+
+    fn branch_id(&self, module_id: ModuleId, region: Region) -> u32 {
+        self.0.lock().unwrap().id(CoveredRegion {
+            module_id,
+            region,
+            kind: CoveredKind::Branch,
+        })
+    }
+
+    /// All covered regions, indexed by the id passed to [COVERAGE_HIT].
+    pub fn regions(&self) -> Vec<CoveredRegion> {
+        self.0.lock().unwrap().regions.clone()
+    }
+}
+
+/// Counts an execution of the body of the top-level def `symbol`, if it was registered.
+pub(crate) fn count_def<'a>(env: &mut Env<'a, '_>, symbol: Symbol, body: Stmt<'a>) -> Stmt<'a> {
+    match env.coverage.and_then(|coverage| coverage.def_id(symbol)) {
+        Some(id) => count_hit(env, id, body),
+        None => body,
+    }
+}
+
+/// Counts an execution of the branch at `region`.
+pub(crate) fn count_branch<'a>(
+    env: &mut Env<'a, '_>,
+    region: Region,
+    branch: Stmt<'a>,
+) -> Stmt<'a> {
+    // branches we made up ourselves have no source to point at
+    if region.is_empty() || env.home.is_builtin() {
+        return branch;
+    }
+
+    match env.coverage {
+        Some(coverage) => {
+            let id = coverage.branch_id(env.home, region);
+
+            count_hit(env, id, branch)
+        }
+        None => branch,
+    }
+}
+
+fn count_hit<'a>(env: &mut Env<'a, '_>, id: u32, stmt: Stmt<'a>) -> Stmt<'a> {
+    let id_symbol = env.unique_symbol();
+    let unit_symbol = env.unique_symbol();
+
+    let call = Expr::Call(Call {
+        call_type: CallType::Foreign {
+            foreign_symbol: ForeignSymbol::from(COVERAGE_HIT),
+            ret_layout: Layout::UNIT,
+        },
+        arguments: env.arena.alloc([id_symbol]),
+    });
+
+    let stmt = Stmt::Let(unit_symbol, call, Layout::UNIT, env.arena.alloc(stmt));
+
+    Stmt::Let(
+        id_symbol,
+        Expr::Literal(Literal::Int((id as i128).to_ne_bytes())),
+        Layout::U32,
+        env.arena.alloc(stmt),
+    )
+}
@@ -1,6 +1,7 @@
 #![allow(clippy::manual_map)]
 
 use crate::borrow::Ownership;
+use crate::coverage::{self, Coverage};
 use crate::ir::literal::{make_num_literal, IntOrFloatValue};
 use crate::layout::{
     self, Builtin, ClosureCallOptions, ClosureRepresentation, EnumDispatch, InLayout, LambdaName,
@@ -28,14 +29,15 @@ use roc_module::ident::{ForeignSymbol, Lowercase, TagName};
 use roc_module::low_level::{LowLevel, LowLevelWrapperType};
 use roc_module::symbol::{IdentIds, ModuleId, Symbol};
 use roc_problem::can::{RuntimeError, ShadowKind};
-use roc_region::all::{Loc, Region};
+use roc_region::all::{LineColumn, LineInfo, Loc, Region};
 use roc_std::RocDec;
 use roc_target::TargetInfo;
 use roc_types::subs::{
-    instantiate_rigids, storage_copy_var_to, Content, ExhaustiveMark, FlatType, RedundantMark,
-    StorageSubs, Subs, Variable, VariableSubsSlice,
+    instantiate_rigids, storage_copy_var_to, Content, ExhaustiveMark, FlatType, OptVariable,
+    RedundantMark, StorageSubs, Subs, SubsSlice, UnionLambdas, Variable, VariableSubsSlice,
 };
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use ven_pretty::{text, BoxAllocator, DocAllocator, DocBuilder};
 
 use pattern::{from_can_pattern, store_pattern, Pattern};
@@ -208,6 +210,8 @@ pub struct PartialProc<'a> {
     pub body: roc_can::expr::Expr,
     pub body_var: Variable,
     pub is_self_recursive: bool,
+    /// Where the body of this function lives in its module's source, used for debug info
+    pub region: Region,
 }
 
 impl<'a> PartialProc<'a> {
@@ -222,6 +226,8 @@ impl<'a> PartialProc<'a> {
         ret_var: Variable,
     ) -> PartialProc<'a> {
         let number_of_arguments = loc_args.len();
+        // destructuring arguments wraps the body in a `when` without a region
+        let region = loc_body.region;
 
         match patterns_to_when(env, loc_args, ret_var, loc_body) {
             Ok((_, pattern_symbols, body)) => {
@@ -237,6 +243,7 @@ impl<'a> PartialProc<'a> {
                     body: body.value,
                     body_var: ret_var,
                     is_self_recursive,
+                    region,
                 }
             }
 
@@ -254,6 +261,7 @@ impl<'a> PartialProc<'a> {
                     body: roc_can::expr::Expr::RuntimeError(error.value),
                     body_var: ret_var,
                     is_self_recursive: false,
+                    region,
                 }
             }
         }
@@ -306,6 +314,12 @@ pub struct Proc<'a> {
     pub ret_layout: InLayout<'a>,
     pub is_self_recursive: SelfRecursive,
     pub host_exposed_layouts: HostExposedLayouts<'a>,
+    /// Source region of the function body; `Region::zero()` for compiler-generated procs
+    pub region: Region,
+    /// Where the code binding each of these symbols came from, for the first statement of every
+    /// def and `when`/`if` branch in the body. When a symbol appears more than once, its first
+    /// region is the most precise one.
+    pub statement_regions: &'a [(Symbol, Region)],
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -433,6 +447,54 @@ impl<'a> Proc<'a> {
     }
 }
 
+/// The source of every module that went into a build, so backends can turn a [`Proc`]'s
+/// region into the file, line and column that its debug info points at.
+#[derive(Default)]
+pub struct DebugSources {
+    modules: MutMap<ModuleId, (PathBuf, LineInfo)>,
+}
+
+impl DebugSources {
+    pub fn new(sources: &MutMap<ModuleId, (PathBuf, Box<str>)>) -> Self {
+        let modules = sources
+            .iter()
+            .map(|(module_id, (path, src))| (*module_id, (path.clone(), LineInfo::new(src))))
+            .collect();
+
+        Self { modules }
+    }
+
+    /// The file a proc was defined in, and the 0-based line and column its body starts at.
+    ///
+    /// Returns `None` for procs the compiler generated, which have no source to point at.
+    pub fn location(&self, proc: &Proc) -> Option<(&Path, LineColumn)> {
+        if proc.region == Region::zero() {
+            return None;
+        }
+
+        let module_id = proc.name.name().module_id();
+        let (path, line_info) = self.modules.get(&module_id)?;
+
+        Some((path.as_path(), line_info.convert_pos(proc.region.start())))
+    }
+
+    /// Where each of the [Proc::statement_regions] of `proc` starts, in the file that
+    /// [DebugSources::location] gives for the proc.
+    pub fn statement_locations(&self, proc: &Proc) -> MutMap<Symbol, LineColumn> {
+        let mut locations = MutMap::default();
+
+        if let Some((_, line_info)) = self.modules.get(&proc.name.name().module_id()) {
+            for (symbol, region) in proc.statement_regions {
+                locations
+                    .entry(*symbol)
+                    .or_insert_with(|| line_info.convert_pos(region.start()));
+            }
+        }
+
+        locations
+    }
+}
+
 /// A host-exposed function must be specialized; it's a seed for subsequent specializations
 #[derive(Clone, Debug)]
 pub struct HostSpecializations<'a> {
@@ -1072,6 +1134,8 @@ impl<'a> Procs<'a> {
             _ => false,
         };
 
+        let region = loc_body.region;
+
         match patterns_to_when(env, loc_args, ret_var, loc_body) {
             Ok((_, pattern_symbols, body)) => {
                 // an anonymous closure. These will always be specialized already
@@ -1124,6 +1188,7 @@ impl<'a> Procs<'a> {
                                         body: body.value,
                                         body_var: ret_var,
                                         is_self_recursive,
+                                        region,
                                     };
 
                                     self.partial_procs.insert(name.name(), partial_proc);
@@ -1152,6 +1217,7 @@ impl<'a> Procs<'a> {
                                     body: body.value,
                                     body_var: ret_var,
                                     is_self_recursive,
+                                    region,
                                 };
 
                                 self.partial_procs.insert(name.name(), partial_proc)
@@ -1386,7 +1452,11 @@ pub struct Env<'a, 'i> {
     pub abilities: AbilitiesView<'i>,
     pub exposed_by_module: &'i ExposedByModule,
     pub derived_module: &'i SharedDerivedModule,
+    /// [Some] if executions of defs and branches should be counted.
+    pub coverage: Option<&'i Coverage>,
     pub struct_indexing: UsageTrackingMap<(Symbol, u64), Symbol>,
+    /// The [Proc::statement_regions] of the proc being specialized.
+    pub statement_regions: std::vec::Vec<(Symbol, Region)>,
 }
 
 impl<'a, 'i> Env<'a, 'i> {
@@ -2357,6 +2427,8 @@ fn from_can_let<'a>(
         };
     }
 
+    let def_region = Region::span_across(&def.loc_pattern.region, &def.loc_expr.region);
+
     if let roc_can::pattern::Pattern::Identifier(symbol) = &def.loc_pattern.value {
         return match def.loc_expr.value {
             Closure(closure_data) => {
@@ -2623,7 +2695,7 @@ fn from_can_let<'a>(
             _ => {
                 let rest = lower_rest!(variable, cont.value);
 
-                with_hole(
+                let stmt = with_hole(
                     env,
                     def.loc_expr.value,
                     def.expr_var,
@@ -2631,7 +2703,10 @@ fn from_can_let<'a>(
                     layout_cache,
                     *symbol,
                     env.arena.alloc(rest),
-                )
+                );
+                record_statement_region(env, def_region, &stmt);
+
+                stmt
             }
         };
     }
@@ -2661,7 +2736,7 @@ fn from_can_let<'a>(
             stmt = store_pattern(env, procs, layout_cache, &mono_pattern, outer_symbol, stmt);
 
             // convert the def body, store in outer_symbol
-            with_hole(
+            let stmt = with_hole(
                 env,
                 def.loc_expr.value,
                 def.expr_var,
@@ -2669,7 +2744,35 @@ fn from_can_let<'a>(
                 layout_cache,
                 outer_symbol,
                 env.arena.alloc(stmt),
-            )
+            );
+            record_statement_region(env, def_region, &stmt);
+
+            stmt
+        }
+    }
+}
+
+/// Remembers that the code of `stmt` comes from `region`, so that debug info can point there.
+///
+/// The region goes to the symbol bound by the first statement. Statements are lowered from the
+/// inside out, so if that symbol already has a region, it is from a nested def or branch that
+/// starts at the same code, and is more precise.
+fn record_statement_region<'a>(env: &mut Env<'a, '_>, region: Region, mut stmt: &Stmt<'a>) {
+    // defs and branches we made up ourselves have no source to point at
+    if region.is_empty() {
+        return;
+    }
+
+    loop {
+        match stmt {
+            Stmt::Let(symbol, ..) => {
+                env.statement_regions.push((*symbol, region));
+
+                return;
+            }
+            Stmt::Join { remainder, .. } => stmt = remainder,
+            // the statement is a lookup or a jump, with no code of its own to point at
+            _ => return,
         }
     }
 }
@@ -3197,6 +3300,8 @@ fn generate_runtime_error_function<'a>(
         ret_layout,
         is_self_recursive: SelfRecursive::NotSelfRecursive,
         host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+        region: Region::zero(),
+        statement_regions: &[],
     }
 }
 
@@ -3289,6 +3394,8 @@ fn generate_host_exposed_function<'a>(
                 ret_layout: result,
                 is_self_recursive: SelfRecursive::NotSelfRecursive,
                 host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+                region: Region::zero(),
+                statement_regions: &[],
             };
 
             let top_level = ProcLayout::from_raw_named(env.arena, lambda_name, layout);
@@ -3353,6 +3460,8 @@ fn generate_host_exposed_lambda_set<'a>(
         ret_layout: return_layout,
         is_self_recursive: SelfRecursive::NotSelfRecursive,
         host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+        region: Region::zero(),
+        statement_regions: &[],
     };
 
     let top_level = ProcLayout::new(
@@ -3414,11 +3523,19 @@ fn specialize_proc_help<'a>(
 
     let body = partial_proc.body.clone();
     let body_var = partial_proc.body_var;
+    let partial_region = partial_proc.region;
 
     // host-exposed functions are tagged on later
     let host_exposed_layouts = HostExposedLayouts::NotHostExposed;
 
+    // Other procs may be specialized while this body is lowered; they keep their own regions.
+    let outer_statement_regions = std::mem::take(&mut env.statement_regions);
+
     let mut specialized_body = from_can(env, body_var, body, procs, layout_cache);
+    specialized_body = coverage::count_def(env, lambda_name.name(), specialized_body);
+
+    let statement_regions = std::mem::replace(&mut env.statement_regions, outer_statement_regions);
+    let statement_regions = env.arena.alloc_slice_copy(&statement_regions);
 
     let specialized_proc = match specialized {
         SpecializedLayout::FunctionPointerBody {
@@ -3449,6 +3566,8 @@ fn specialize_proc_help<'a>(
                 ret_layout,
                 is_self_recursive: recursivity,
                 host_exposed_layouts,
+                region: partial_region,
+                statement_regions,
             }
         }
         SpecializedLayout::FunctionBody {
@@ -3644,6 +3763,8 @@ fn specialize_proc_help<'a>(
                 ret_layout,
                 is_self_recursive: recursivity,
                 host_exposed_layouts,
+                region: partial_region,
+                statement_regions,
             }
         }
     };
@@ -4467,7 +4588,7 @@ pub fn with_hole<'a>(
                     if is_terminated {
                         let terminator = hole;
 
-                        let mut stmt = with_hole(
+                        let else_stmt = with_hole(
                             env,
                             final_else.value,
                             branch_var,
@@ -4476,6 +4597,8 @@ pub fn with_hole<'a>(
                             assigned,
                             terminator,
                         );
+                        record_statement_region(env, final_else.region, &else_stmt);
+                        let mut stmt = coverage::count_branch(env, final_else.region, else_stmt);
 
                         for (loc_cond, loc_then) in branches.into_iter().rev() {
                             let branching_symbol = env.unique_symbol();
@@ -4489,6 +4612,8 @@ pub fn with_hole<'a>(
                                 assigned,
                                 terminator,
                             );
+                            record_statement_region(env, loc_then.region, &then);
+                            let then = coverage::count_branch(env, loc_then.region, then);
 
                             stmt = cond(env, branching_symbol, cond_layout, then, stmt, ret_layout);
 
@@ -4512,7 +4637,7 @@ pub fn with_hole<'a>(
                             .arena
                             .alloc(Stmt::Jump(id, env.arena.alloc([assigned_in_jump])));
 
-                        let mut stmt = with_hole(
+                        let else_stmt = with_hole(
                             env,
                             final_else.value,
                             branch_var,
@@ -4521,6 +4646,8 @@ pub fn with_hole<'a>(
                             assigned_in_jump,
                             terminator,
                         );
+                        record_statement_region(env, final_else.region, &else_stmt);
+                        let mut stmt = coverage::count_branch(env, final_else.region, else_stmt);
 
                         for (loc_cond, loc_then) in branches.into_iter().rev() {
                             let branching_symbol = possible_reuse_symbol_or_specialize(
@@ -4540,6 +4667,8 @@ pub fn with_hole<'a>(
                                 assigned_in_jump,
                                 terminator,
                             );
+                            record_statement_region(env, loc_then.region, &then);
+                            let then = coverage::count_branch(env, loc_then.region, then);
 
                             stmt = cond(env, branching_symbol, cond_layout, then, stmt, ret_layout);
 
@@ -5693,6 +5822,157 @@ fn compile_dbg<'a>(
     }
 }
 
+/// Compiles an `expect` or `expect-fx`.
+///
+/// The values looked up in the condition are only rendered, with `Inspect.toStr`, once the
+/// condition is known to have failed:
+///
+/// ```text
+/// join rest = <continuation>
+/// cond = <condition>
+/// if cond then
+///     jump rest
+/// else
+///     inspected1 = Inspect.toStr lookup1
+///     ...
+///     expect cond [inspected1, ..]
+///     jump rest
+/// ```
+#[allow(clippy::too_many_arguments)]
+fn compile_expect<'a>(
+    env: &mut Env<'a, '_>,
+    procs: &mut Procs<'a>,
+    layout_cache: &mut LayoutCache<'a>,
+    variable: Variable,
+    loc_condition: Loc<roc_can::expr::Expr>,
+    lookups_in_cond: &[ExpectLookup],
+    continuation: Stmt<'a>,
+    make_expect: impl FnOnce(Symbol, Region, &'a [Symbol], &'a [Variable], &'a Stmt<'a>) -> Stmt<'a>,
+) -> Stmt<'a> {
+    let cond_symbol = env.unique_symbol();
+    let join_point_id = JoinPointId(env.unique_symbol());
+
+    let mut lookups = Vec::with_capacity_in(lookups_in_cond.len(), env.arena);
+    let mut inspected_symbols = Vec::with_capacity_in(lookups_in_cond.len(), env.arena);
+
+    for ExpectLookup {
+        symbol,
+        var,
+        ability_info,
+    } in lookups_in_cond.iter().copied()
+    {
+        let symbol = match ability_info {
+            Some(specialization_id) => {
+                late_resolve_ability_specialization(env, symbol, Some(specialization_id), var)
+            }
+            None => symbol,
+        };
+
+        if !env.subs.is_function(var) {
+            // Exclude functions from lookups
+            lookups.push((symbol, var));
+            inspected_symbols.push(env.unique_symbol());
+        }
+    }
+
+    // Every lookup is reported as the string `Inspect.toStr` made of it.
+    let inspected_symbols = inspected_symbols.into_bump_slice();
+    let variables = env
+        .arena
+        .alloc_slice_fill_copy(inspected_symbols.len(), Variable::STR);
+
+    let jump_to_continuation = env.arena.alloc(Stmt::Jump(join_point_id, &[]));
+    let mut failed = make_expect(
+        cond_symbol,
+        loc_condition.region,
+        inspected_symbols,
+        variables,
+        jump_to_continuation,
+    );
+
+    for ((symbol, var), inspected) in lookups.into_iter().zip(inspected_symbols).rev() {
+        failed = inspect_to_str(
+            env,
+            procs,
+            layout_cache,
+            symbol,
+            var,
+            *inspected,
+            env.arena.alloc(failed),
+        );
+    }
+
+    let ret_layout = layout_cache
+        .from_var(env.arena, variable, env.subs)
+        .unwrap_or_else(|err| internal_error!("invalid layout for expect: {:?}", err));
+
+    let check = Stmt::if_then_else(
+        env.arena,
+        cond_symbol,
+        ret_layout,
+        Stmt::Jump(join_point_id, &[]),
+        env.arena.alloc(failed),
+    );
+
+    let check = with_hole(
+        env,
+        loc_condition.value,
+        Variable::BOOL,
+        procs,
+        layout_cache,
+        cond_symbol,
+        env.arena.alloc(check),
+    );
+
+    Stmt::Join {
+        id: join_point_id,
+        parameters: &[],
+        body: env.arena.alloc(continuation),
+        remainder: env.arena.alloc(check),
+    }
+}
+
+/// Assigns `Inspect.toStr symbol` to `assigned`.
+fn inspect_to_str<'a>(
+    env: &mut Env<'a, '_>,
+    procs: &mut Procs<'a>,
+    layout_cache: &mut LayoutCache<'a>,
+    symbol: Symbol,
+    var: Variable,
+    assigned: Symbol,
+    hole: &'a Stmt<'a>,
+) -> Stmt<'a> {
+    // var -[[toStr]]-> Str
+    let fn_var = env.subs.fresh_unnamed_flex_var();
+    let lambdas = UnionLambdas::insert_into_subs(env.subs, [(Symbol::INSPECT_TO_STR, vec![])]);
+    let lambda_set = env.subs.fresh_unnamed_flex_var();
+    env.subs.set_content(
+        lambda_set,
+        Content::LambdaSet(roc_types::subs::LambdaSet {
+            solved: lambdas,
+            recursion_var: OptVariable::NONE,
+            unspecialized: SubsSlice::default(),
+            ambient_function: fn_var,
+        }),
+    );
+    let arguments = VariableSubsSlice::insert_into_subs(env.subs, [var]);
+    env.subs.set_content(
+        fn_var,
+        Content::Structure(FlatType::Func(arguments, lambda_set, Variable::STR)),
+    );
+
+    call_by_name(
+        env,
+        procs,
+        fn_var,
+        Symbol::INSPECT_TO_STR,
+        vec![(var, Loc::at_zero(roc_can::expr::Expr::Var(symbol, var)))],
+        layout_cache,
+        assigned,
+        hole,
+    )
+}
+
 /// Compiles an access into a tuple or record.
 fn compile_struct_like_access<'a>(
     env: &mut Env<'a, '_>,
@@ -6720,7 +7000,9 @@ pub fn from_can<'a>(
                 "invalid condition type in if expression"
             );
 
-            let mut stmt = from_can(env, branch_var, final_else.value, procs, layout_cache);
+            let else_stmt = from_can(env, branch_var, final_else.value, procs, layout_cache);
+            record_statement_region(env, final_else.region, &else_stmt);
+            let mut stmt = coverage::count_branch(env, final_else.region, else_stmt);
 
             for (loc_cond, loc_then) in branches.into_iter().rev() {
                 let branching_symbol = possible_reuse_symbol_or_specialize(
@@ -6731,6 +7013,8 @@ pub fn from_can<'a>(
                     cond_var,
                 );
                 let then = from_can(env, branch_var, loc_then.value, procs, layout_cache);
+                record_statement_region(env, loc_then.region, &then);
+                let then = coverage::count_branch(env, loc_then.region, then);
 
                 stmt = cond(env, branching_symbol, cond_layout, then, stmt, ret_layout);
 
@@ -6754,67 +7038,23 @@ pub fn from_can<'a>(
             lookups_in_cond,
         } => {
             let rest = from_can(env, variable, loc_continuation.value, procs, layout_cache);
-            let cond_symbol = env.unique_symbol();
-
-            let mut lookups = Vec::with_capacity_in(lookups_in_cond.len(), env.arena);
-            let mut lookup_variables = Vec::with_capacity_in(lookups_in_cond.len(), env.arena);
-            let mut specialized_variables = Vec::with_capacity_in(lookups_in_cond.len(), env.arena);
-
-            for ExpectLookup {
-                symbol,
-                var,
-                ability_info,
-            } in lookups_in_cond.iter().copied()
-            {
-                let symbol = match ability_info {
-                    Some(specialization_id) => late_resolve_ability_specialization(
-                        env,
-                        symbol,
-                        Some(specialization_id),
-                        var,
-                    ),
-                    None => symbol,
-                };
 
-                let expectation_subs = env
-                    .expectation_subs
-                    .as_deref_mut()
-                    .expect("if expects are compiled, their subs should be available");
-                let spec_var = expectation_subs.fresh_unnamed_flex_var();
-
-                if !env.subs.is_function(var) {
-                    // Exclude functions from lookups
-                    lookups.push(symbol);
-                    lookup_variables.push(var);
-                    specialized_variables.push(spec_var);
-                }
-            }
-
-            let specialized_variables = specialized_variables.into_bump_slice();
-
-            let mut stmt = Stmt::Expect {
-                condition: cond_symbol,
-                region: loc_condition.region,
-                lookups: lookups.into_bump_slice(),
-                variables: specialized_variables,
-                remainder: env.arena.alloc(rest),
-            };
-
-            stmt = with_hole(
+            compile_expect(
                 env,
-                loc_condition.value,
-                Variable::BOOL,
                 procs,
                 layout_cache,
-                cond_symbol,
-                env.arena.alloc(stmt),
-            );
-
-            // Now that the condition has been specialized, export the specialized types of our
-            // lookups into the expectation subs.
-            store_specialized_expectation_lookups(env, lookup_variables, specialized_variables);
-
-            stmt
+                variable,
+                *loc_condition,
+                &lookups_in_cond,
+                rest,
+                |condition, region, lookups, variables, remainder| Stmt::Expect {
+                    condition,
+                    region,
+                    lookups,
+                    variables,
+                    remainder,
+                },
+            )
         }
 
         ExpectFx {
@@ -6823,65 +7063,23 @@ pub fn from_can<'a>(
             lookups_in_cond,
         } => {
             let rest = from_can(env, variable, loc_continuation.value, procs, layout_cache);
-            let cond_symbol = env.unique_symbol();
-
-            let mut lookups = Vec::with_capacity_in(lookups_in_cond.len(), env.arena);
-            let mut lookup_variables = Vec::with_capacity_in(lookups_in_cond.len(), env.arena);
-            let mut specialized_variables = Vec::with_capacity_in(lookups_in_cond.len(), env.arena);
-
-            for ExpectLookup {
-                symbol,
-                var,
-                ability_info,
-            } in lookups_in_cond.iter().copied()
-            {
-                let symbol = match ability_info {
-                    Some(specialization_id) => late_resolve_ability_specialization(
-                        env,
-                        symbol,
-                        Some(specialization_id),
-                        var,
-                    ),
-                    None => symbol,
-                };
-
-                let expectation_subs = env
-                    .expectation_subs
-                    .as_deref_mut()
-                    .expect("if expects are compiled, their subs should be available");
-                let spec_var = expectation_subs.fresh_unnamed_flex_var();
-
-                if !env.subs.is_function(var) {
-                    // Exclude functions from lookups
-                    lookups.push(symbol);
-                    lookup_variables.push(var);
-                    specialized_variables.push(spec_var);
-                }
-            }
 
-            let specialized_variables = specialized_variables.into_bump_slice();
-
-            let mut stmt = Stmt::ExpectFx {
-                condition: cond_symbol,
-                region: loc_condition.region,
-                lookups: lookups.into_bump_slice(),
-                variables: specialized_variables,
-                remainder: env.arena.alloc(rest),
-            };
-
-            stmt = with_hole(
+            compile_expect(
                 env,
-                loc_condition.value,
-                Variable::BOOL,
                 procs,
                 layout_cache,
-                cond_symbol,
-                env.arena.alloc(stmt),
-            );
-
-            store_specialized_expectation_lookups(env, lookup_variables, specialized_variables);
-
-            stmt
+                variable,
+                *loc_condition,
+                &lookups_in_cond,
+                rest,
+                |condition, region, lookups, variables, remainder| Stmt::ExpectFx {
+                    condition,
+                    region,
+                    lookups,
+                    variables,
+                    remainder,
+                },
+            )
         }
 
         Dbg {
@@ -6962,7 +7160,7 @@ fn to_opt_branches<'a>(
 ) -> std::vec::Vec<(
     Pattern<'a>,
     Option<Loc<roc_can::expr::Expr>>,
-    roc_can::expr::Expr,
+    Loc<roc_can::expr::Expr>,
 )> {
     debug_assert!(!branches.is_empty());
 
@@ -6974,6 +7172,9 @@ fn to_opt_branches<'a>(
             continue;
         }
 
+        // the region of the branch body, even after the pattern's bindings are put in front of it
+        let body_region = when_branch.value.region;
+
         for loc_pattern in when_branch.patterns {
             match from_can_pattern(env, procs, layout_cache, &loc_pattern.pattern.value) {
                 Ok((mono_pattern, assignments)) => {
@@ -7007,14 +7208,21 @@ fn to_opt_branches<'a>(
                     };
 
                     // TODO remove clone?
-                    opt_branches.push((mono_pattern, when_branch.guard.clone(), loc_expr.value));
+                    opt_branches.push((
+                        mono_pattern,
+                        when_branch.guard.clone(),
+                        Loc::at(body_region, loc_expr.value),
+                    ));
                 }
                 Err(runtime_error) => {
                     // TODO remove clone?
                     opt_branches.push((
                         Pattern::Underscore,
                         when_branch.guard.clone(),
-                        roc_can::expr::Expr::RuntimeError(runtime_error),
+                        Loc::at(
+                            body_region,
+                            roc_can::expr::Expr::RuntimeError(runtime_error),
+                        ),
                     ));
                 }
             }
@@ -7027,7 +7235,9 @@ fn to_opt_branches<'a>(
         opt_branches.push((
             Pattern::Underscore,
             None,
-            roc_can::expr::Expr::RuntimeError(roc_problem::can::RuntimeError::NonExhaustivePattern),
+            Loc::at_zero(roc_can::expr::Expr::RuntimeError(
+                roc_problem::can::RuntimeError::NonExhaustivePattern,
+            )),
         ));
     }
 
@@ -7068,7 +7278,7 @@ fn from_can_when<'a>(
     let arena = env.arena;
     let it = opt_branches
         .into_iter()
-        .filter_map(|(pattern, opt_guard, can_expr)| {
+        .filter_map(|(pattern, opt_guard, loc_can_expr)| {
             // If the pattern has a void layout we can drop it; however, we must still perform the
             // work of building the body, because that may contain specializations we must
             // discover for use elsewhere. See
@@ -7085,6 +7295,7 @@ fn from_can_when<'a>(
                 None
             };
 
+            let can_expr = loc_can_expr.value;
             let branch_stmt = match join_point {
                 None => from_can(env, expr_var, can_expr, procs, layout_cache),
                 Some(id) => {
@@ -7095,6 +7306,8 @@ fn from_can_when<'a>(
                     with_hole(env, can_expr, expr_var, procs, layout_cache, symbol, jump)
                 }
             };
+            record_statement_region(env, loc_can_expr.region, &branch_stmt);
+            let branch_stmt = coverage::count_branch(env, loc_can_expr.region, branch_stmt);
 
             use decision_tree::Guard;
             let result = if let Some(loc_expr) = opt_guard {
@@ -9991,6 +10204,8 @@ where
             ret_layout: *field,
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+            region: Region::zero(),
+            statement_regions: &[],
         };
 
         answer.push(GlueProc {
@@ -10086,6 +10301,8 @@ where
             ret_layout: *field,
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+            region: Region::zero(),
+            statement_regions: &[],
         };
 
         answer.push(GlueProc {
@@ -0,0 +1,165 @@
+//! Code samples in doc comments which `roc test` runs, so they can't silently go out of date.
+//! A doc test is a fenced Roc code block containing at least one `expect`, e.g.
+//!
+//! ```text
+//! ## ```
+//! ## expect List.len [1, 2] == 2
+//! ## ```
+//! ```
+use roc_region::all::{Position, Region};
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocTest {
+    /// From the start of the opening fence to the end of the closing one
+    pub region: Region,
+    /// The whole module's source, with everything but this sample's code blanked out. Parsing
+    /// it gives the sample's defs the same regions they have in the module, so problems with
+    /// them get reported inside the doc comment.
+    pub masked_src: String,
+}
+
+/// Whether a fenced code block with this info string (e.g. the `roc` in ```` ```roc ````) and
+/// code is a doc test.
+pub fn is_doc_test(info: &str, code: &str) -> bool {
+    let lang = info.trim();
+
+    (lang.is_empty() || lang == "roc")
+        && code
+            .lines()
+            .any(|line| line.split_whitespace().next() == Some("expect"))
+}
+
+struct OpenBlock<'a> {
+    start: usize,
+    info: &'a str,
+    code: String,
+    lines: Vec<Range<usize>>,
+}
+
+/// Every doc test in the doc comments of this module's source, in order.
+pub fn doc_tests(src: &str) -> Vec<DocTest> {
+    let mut doc_tests = Vec::new();
+    let mut open_block: Option<OpenBlock> = None;
+    let mut line_start = 0;
+
+    for line in src.split_inclusive('\n') {
+        let line_end = line_start + line.len();
+        let trimmed = line.trim_start();
+        let comment_start = line_start + line.len() - trimmed.len();
+
+        match doc_comment_text(trimmed) {
+            None => {
+                // The doc comment ended, so a code block that's still open was never closed.
+                open_block = None;
+            }
+            Some(text) => {
+                let is_fence = text.trim_start().starts_with("```");
+
+                match open_block.take() {
+                    None if is_fence => {
+                        open_block = Some(OpenBlock {
+                            start: comment_start,
+                            info: text.trim_start().trim_start_matches('`'),
+                            code: String::new(),
+                            lines: Vec::new(),
+                        });
+                    }
+                    None => {}
+                    Some(block) if is_fence => {
+                        if is_doc_test(block.info, &block.code) {
+                            let end = comment_start + "##".len() + text.trim_end().len();
+
+                            doc_tests.push(DocTest {
+                                region: Region::new(
+                                    Position::new(block.start as u32),
+                                    Position::new(end as u32),
+                                ),
+                                masked_src: mask(src, &block.lines),
+                            });
+                        }
+                    }
+                    Some(mut block) => {
+                        block.code.push_str(text);
+                        // Everything after the `##`, so the code keeps its columns
+                        block.lines.push(comment_start + "##".len()..line_end);
+                        open_block = Some(block);
+                    }
+                }
+            }
+        }
+
+        line_start = line_end;
+    }
+
+    doc_tests
+}
+
+/// The text after the `##` of a doc comment line (including its newline), or None if this
+/// isn't one. Like the parser, this requires a space or the end of the line after the `##`.
+fn doc_comment_text(trimmed_line: &str) -> Option<&str> {
+    let text = trimmed_line.strip_prefix("##")?;
+
+    if text.is_empty()
+        || text.starts_with(' ')
+        || text.starts_with('\n')
+        || text.starts_with("\r\n")
+    {
+        Some(text)
+    } else {
+        None
+    }
+}
+
+/// Replace everything outside the given ranges with spaces, except for newlines.
+fn mask(src: &str, keep: &[Range<usize>]) -> String {
+    let mut bytes: Vec<u8> = src
+        .bytes()
+        .map(|byte| if byte == b'\n' { b'\n' } else { b' ' })
+        .collect();
+
+    for range in keep {
+        bytes[range.clone()].copy_from_slice(&src.as_bytes()[range.clone()]);
+    }
+
+    // The kept ranges start right after an ASCII `##` and end at a newline or the end of the
+    // source, so they're whole UTF-8 sequences.
+    String::from_utf8(bytes).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_doc_tests() {
+        let src =
+            "## Adds one.\n##\n## ```\n## expect addOne 1 == 2\n## ```\naddOne = \\n -> n + 1\n";
+        let doc_tests = doc_tests(src);
+
+        assert_eq!(doc_tests.len(), 1);
+        assert_eq!(
+            doc_tests[0].region,
+            Region::new(Position::new(16), Position::new(53))
+        );
+        assert_eq!(doc_tests[0].masked_src.len(), src.len());
+        assert_eq!(doc_tests[0].masked_src.trim(), "expect addOne 1 == 2");
+        assert_eq!(doc_tests[0].masked_src.find("expect"), src.find("expect"));
+    }
+
+    #[test]
+    fn skips_other_code_blocks() {
+        // No expect, a different language, an unclosed block, and a line comment
+        let src = "## ```\n## addOne 1\n## ```\n## ```repl\n## expect 1 == 1\n## ```\n## ```\n## expect 1 == 1\nx = 1\n# ```\n# expect 1 == 1\n# ```\n";
+
+        assert_eq!(doc_tests(src), Vec::new());
+    }
+
+    #[test]
+    fn doc_test_info_strings() {
+        assert!(is_doc_test("", "expect 1 == 1\n"));
+        assert!(is_doc_test("roc", "x = 1\n\nexpect x == 1\n"));
+        assert!(!is_doc_test("unchecked", "expect 1 == 1\n"));
+        assert!(!is_doc_test("", "expected = 1\n"));
+    }
+}
@@ -249,7 +249,7 @@ impl ObligationCache {
     ) -> ObligationResult {
         let MustImplementAbility { typ, ability } = mia;
 
-        match typ {
+        match builtin_container_obligation(subs, typ, ability) {
             Obligated::Adhoc(var) => self.check_adhoc(subs, abilities_store, var, ability),
             Obligated::Opaque(opaque) => self
                 .check_opaque_and_read(abilities_store, opaque, ability)
@@ -301,6 +301,17 @@ impl ObligationCache {
 
             Symbol::BOOL_EQ => Some(DeriveEq::is_derivable(self, abilities_store, subs, var)),
 
+            Symbol::INSPECT_INSPECT_ABILITY => Some(DeriveInspect::is_derivable(
+                self,
+                abilities_store,
+                subs,
+                var,
+            )),
+
+            Symbol::SORT_SORT_ABILITY => {
+                Some(DeriveSort::is_derivable(self, abilities_store, subs, var))
+            }
+
             _ => None,
         };
 
@@ -379,6 +390,8 @@ impl ObligationCache {
             DeriveDecoding::ABILITY => DeriveDecoding::is_derivable_builtin_opaque(opaque),
             DeriveEq::ABILITY => DeriveEq::is_derivable_builtin_opaque(opaque),
             DeriveHash::ABILITY => DeriveHash::is_derivable_builtin_opaque(opaque),
+            DeriveInspect::ABILITY => DeriveInspect::is_derivable_builtin_opaque(opaque),
+            DeriveSort::ABILITY => DeriveSort::is_derivable_builtin_opaque(opaque),
             _ => false,
         };
 
@@ -511,6 +524,34 @@ fn is_builtin_bool_alias(symbol: Symbol) -> bool {
     matches!(symbol, Symbol::BOOL_BOOL)
 }
 
+#[inline(always)]
+fn is_builtin_dict_or_set_alias(symbol: Symbol) -> bool {
+    matches!(symbol, Symbol::DICT_DICT | Symbol::SET_SET)
+}
+
+/// `Dict` and `Set` obligations are ad-hoc, so that their elements are checked when they are
+/// encoded, decoded or inspected as lists. For any other ability they must use the
+/// implementation they list.
+fn builtin_container_obligation(subs: &Subs, obligated: Obligated, ability: Symbol) -> Obligated {
+    match obligated {
+        Obligated::Adhoc(var) => match *subs.get_content_without_compacting(var) {
+            Content::Alias(opaque, _, _, AliasKind::Opaque)
+                if is_builtin_dict_or_set_alias(opaque)
+                    && !matches!(
+                        ability,
+                        Symbol::ENCODE_ENCODING
+                            | Symbol::DECODE_DECODING
+                            | Symbol::INSPECT_INSPECT_ABILITY
+                    ) =>
+            {
+                Obligated::Opaque(opaque)
+            }
+            _ => obligated,
+        },
+        Obligated::Opaque(_) => obligated,
+    }
+}
+
 struct NotDerivable {
     var: Variable,
     context: NotDerivableContext,
@@ -801,8 +842,14 @@ trait DerivableVisitor {
                         stack.push(real_var)
                     }
                 }
-                Alias(opaque, _alias_variables, _real_var, AliasKind::Opaque) => {
-                    if obligation_cache
+                Alias(opaque, alias_variables, _real_var, AliasKind::Opaque) => {
+                    if is_builtin_dict_or_set_alias(opaque)
+                        && Self::is_derivable_builtin_opaque(opaque)
+                    {
+                        // Builtin containers like `Dict k v` are only derivable if their
+                        // arguments are.
+                        push_var_slice!(alias_variables.type_variables())
+                    } else if obligation_cache
                         .check_opaque_and_read(abilities_store, opaque, Self::ABILITY)
                         .is_err()
                         && !Self::is_derivable_builtin_opaque(opaque)
@@ -849,6 +896,7 @@ impl DerivableVisitor for DeriveEncoding {
     fn is_derivable_builtin_opaque(symbol: Symbol) -> bool {
         (is_builtin_number_alias(symbol) && !is_builtin_nat_alias(symbol))
             || is_builtin_bool_alias(symbol)
+            || is_builtin_dict_or_set_alias(symbol)
     }
 
     #[inline(always)]
@@ -954,6 +1002,7 @@ impl DerivableVisitor for DeriveDecoding {
     fn is_derivable_builtin_opaque(symbol: Symbol) -> bool {
         (is_builtin_number_alias(symbol) && !is_builtin_nat_alias(symbol))
             || is_builtin_bool_alias(symbol)
+            || is_builtin_dict_or_set_alias(symbol)
     }
 
     #[inline(always)]
@@ -1308,9 +1357,224 @@ impl DerivableVisitor for DeriveEq {
     }
 }
 
+struct DeriveInspect;
+impl DerivableVisitor for DeriveInspect {
+    const ABILITY: Symbol = Symbol::INSPECT_INSPECT_ABILITY;
+    const ABILITY_SLICE: SubsSlice<Symbol> = Subs::AB_INSPECT;
+
+    /// Every type can be inspected. Opaques that don't implement `Inspect` themselves are shown
+    /// as `<opaque>`, so they never block a derive.
+    #[inline(always)]
+    fn is_derivable_builtin_opaque(_symbol: Symbol) -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn visit_recursion(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_apply(_var: Variable, symbol: Symbol) -> Result<Descend, NotDerivable> {
+        // Any other builtin types are shown without their contents.
+        Ok(Descend(matches!(
+            symbol,
+            Symbol::LIST_LIST
+                | Symbol::SET_SET
+                | Symbol::DICT_DICT
+                | Symbol::BOX_BOX_TYPE
+                | Symbol::STR_STR,
+        )))
+    }
+
+    #[inline(always)]
+    fn visit_func(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(false))
+    }
+
+    #[inline(always)]
+    fn visit_record(
+        _subs: &Subs,
+        _var: Variable,
+        _fields: RecordFields,
+    ) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_tuple(
+        _subs: &Subs,
+        _var: Variable,
+        _elems: TupleElems,
+    ) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_recursive_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_function_or_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_empty_record(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_empty_tuple(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_empty_tag_union(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_alias(_var: Variable, symbol: Symbol) -> Result<Descend, NotDerivable> {
+        if is_builtin_number_alias(symbol) {
+            Ok(Descend(false))
+        } else {
+            Ok(Descend(true))
+        }
+    }
+
+    #[inline(always)]
+    fn visit_ranged_number(_var: Variable, _range: NumericRange) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_floating_point_content(
+        _var: Variable,
+        _subs: &mut Subs,
+        _content_var: Variable,
+    ) -> Result<Descend, NotDerivable> {
+        Ok(Descend(false))
+    }
+}
+
+struct DeriveSort;
+impl DerivableVisitor for DeriveSort {
+    const ABILITY: Symbol = Symbol::SORT_SORT_ABILITY;
+    const ABILITY_SLICE: SubsSlice<Symbol> = Subs::AB_SORT;
+
+    #[inline(always)]
+    fn is_derivable_builtin_opaque(symbol: Symbol) -> bool {
+        is_builtin_number_alias(symbol)
+    }
+
+    #[inline(always)]
+    fn visit_recursion(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_apply(var: Variable, symbol: Symbol) -> Result<Descend, NotDerivable> {
+        // `Dict` and `Set` have no order of their own to compare by.
+        if matches!(symbol, Symbol::LIST_LIST | Symbol::STR_STR) {
+            Ok(Descend(true))
+        } else {
+            Err(NotDerivable {
+                var,
+                context: NotDerivableContext::NoContext,
+            })
+        }
+    }
+
+    #[inline(always)]
+    fn visit_record(
+        subs: &Subs,
+        var: Variable,
+        fields: RecordFields,
+    ) -> Result<Descend, NotDerivable> {
+        for (field_name, _, field) in fields.iter_all() {
+            if subs[field].is_optional() {
+                return Err(NotDerivable {
+                    var,
+                    context: NotDerivableContext::Decode(NotDerivableDecode::OptionalRecordField(
+                        subs[field_name].clone(),
+                    )),
+                });
+            }
+        }
+
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_tuple(
+        _subs: &Subs,
+        _var: Variable,
+        _elems: TupleElems,
+    ) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_recursive_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_function_or_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_empty_record(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_empty_tag_union(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_alias(_var: Variable, symbol: Symbol) -> Result<Descend, NotDerivable> {
+        if is_builtin_number_alias(symbol) {
+            Ok(Descend(false))
+        } else {
+            Ok(Descend(true))
+        }
+    }
+
+    #[inline(always)]
+    fn visit_ranged_number(_var: Variable, _range: NumericRange) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    /// Unlike `Eq`, all floating-point numbers can be put in order, by `Num.compare`.
+    #[inline(always)]
+    fn visit_floating_point_content(
+        _var: Variable,
+        _subs: &mut Subs,
+        _content_var: Variable,
+    ) -> Result<Descend, NotDerivable> {
+        Ok(Descend(false))
+    }
+}
+
 /// Determines what type implements an ability member of a specialized signature, given the
 /// [MustImplementAbility] constraints of the signature.
 pub fn type_implementing_specialization(
+    subs: &Subs,
     specialization_must_implement_constraints: &MustImplementConstraints,
     ability: Symbol,
 ) -> Option<Obligated> {
@@ -1329,7 +1593,7 @@ pub fn type_implementing_specialization(
     specialization_must_implement_constraints
         .iter_for_ability(ability)
         .next()
-        .map(|mia| mia.typ)
+        .map(|mia| builtin_container_obligation(subs, mia.typ, ability))
 }
 
 /// Result of trying to resolve an ability specialization.
@@ -1388,6 +1652,25 @@ pub(crate) fn builtin_module_with_unlisted_ability_impl(module_id: ModuleId) ->
     matches!(module_id, ModuleId::NUM | ModuleId::BOOL)
 }
 
+/// Whether `opaque`'s implementation of `ability_member` should be found via derive_key, rather
+/// than through the implementations it lists.
+///
+/// `Dict` and `Set` list their `Eq` and `Hash` implementations, but are encoded, decoded and
+/// inspected as lists of their elements since `Encode`, `Decode` and `Inspect` can't depend on
+/// them.
+#[inline]
+pub(crate) fn builtin_opaque_with_unlisted_ability_impl(
+    opaque: Symbol,
+    ability_member: Symbol,
+) -> bool {
+    builtin_module_with_unlisted_ability_impl(opaque.module_id())
+        || (is_builtin_dict_or_set_alias(opaque)
+            && matches!(
+                ability_member,
+                Symbol::ENCODE_TO_ENCODER | Symbol::DECODE_DECODER | Symbol::INSPECT_TO_INSPECTOR
+            ))
+}
+
 #[derive(Debug)]
 pub enum ResolveError {
     NonDerivableAbility(Symbol),
@@ -1432,12 +1715,12 @@ pub fn resolve_ability_specialization<R: AbilityResolver>(
 
     use ResolveError::*;
 
-    let obligated = type_implementing_specialization(&must_implement_ability, parent_ability)
+    let obligated = type_implementing_specialization(subs, &must_implement_ability, parent_ability)
         .ok_or(NoTypeImplementingSpecialization)?;
 
     let resolved = match obligated {
         Obligated::Opaque(symbol) => {
-            if builtin_module_with_unlisted_ability_impl(symbol.module_id()) {
+            if builtin_opaque_with_unlisted_ability_impl(symbol, ability_member) {
                 let derive_key = roc_derive_key::Derived::builtin_with_builtin_symbol(
                     ability_member.try_into().map_err(NonDerivableAbility)?,
                     symbol,
@@ -1450,16 +1733,19 @@ pub fn resolve_ability_specialization<R: AbilityResolver>(
                     ability_member,
                 };
 
-                match resolver
-                    .get_implementation(impl_key)
-                    .ok_or(NoTypeImplementingSpecialization)?
-                {
-                    roc_types::types::MemberImpl::Impl(spec_symbol) => {
+                match resolver.get_implementation(impl_key) {
+                    Some(roc_types::types::MemberImpl::Impl(spec_symbol)) => {
                         Resolved::Specialization(spec_symbol)
                     }
+                    // Every opaque can be inspected; those without an implementation of their
+                    // own are shown without their contents.
+                    None if ability_member == Symbol::INSPECT_TO_INSPECTOR => {
+                        Resolved::Derive(roc_derive_key::Derived::Immediate(Symbol::INSPECT_OPAQUE))
+                    }
+                    None => return Err(NoTypeImplementingSpecialization),
                     // TODO this is not correct. We can replace `Resolved` with `MemberImpl` entirely,
                     // which will make this simpler.
-                    roc_types::types::MemberImpl::Error => {
+                    Some(roc_types::types::MemberImpl::Error) => {
                         Resolved::Specialization(Symbol::UNDERSCORE)
                     }
                 }
@@ -1587,8 +1587,11 @@ fn check_ability_specialization(
                 lambda_sets_to_specialize,
                 extra_metadata: SpecializationLsetCollector(specialization_lambda_sets),
             } => {
-                let specialization_type =
-                    type_implementing_specialization(&must_implement_ability, parent_ability);
+                let specialization_type = type_implementing_specialization(
+                    env.subs,
+                    &must_implement_ability,
+                    parent_ability,
+                );
 
                 match specialization_type {
                     Some(Obligated::Opaque(opaque)) => {
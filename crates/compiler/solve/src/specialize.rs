@@ -20,7 +20,7 @@ use roc_types::{
 use roc_unify::unify::{unify, Mode, MustImplementConstraints};
 
 use crate::{
-    ability::builtin_module_with_unlisted_ability_impl,
+    ability::builtin_opaque_with_unlisted_ability_impl,
     deep_copy::deep_copy_var_in,
     env::{DerivedEnv, Env},
 };
@@ -620,17 +620,26 @@ fn make_specialization_decision<P: Phase>(
     use SpecializationTypeKey::*;
     match subs.get_content_without_compacting(var) {
         Alias(opaque, _, _, AliasKind::Opaque)
-            if !builtin_module_with_unlisted_ability_impl(opaque.module_id()) =>
+            if !builtin_opaque_with_unlisted_ability_impl(*opaque, ability_member) =>
         {
-            if P::IS_LATE {
+            let impl_key = ImplKey {
+                opaque: *opaque,
+                ability_member,
+            };
+            let has_impl = || {
+                phase.with_module_abilities_store(opaque.module_id(), |abilities_store| {
+                    abilities_store.get_implementation(impl_key).is_some()
+                })
+            };
+
+            if ability_member == Symbol::INSPECT_TO_INSPECTOR && !has_impl() {
+                // Opaques that don't implement `Inspect` are shown without their contents.
+                SpecializeDecision::Specialize(Immediate(Symbol::INSPECT_OPAQUE))
+            } else if P::IS_LATE {
                 SpecializeDecision::Specialize(Opaque(*opaque))
             } else {
                 // Solving within a module.
                 phase.with_module_abilities_store(opaque.module_id(), |abilities_store| {
-                    let impl_key = ImplKey {
-                        opaque: *opaque,
-                        ability_member,
-                    };
                     match abilities_store.get_implementation(impl_key) {
                         None => {
                             // Doesn't specialize; an error will already be reported for this.
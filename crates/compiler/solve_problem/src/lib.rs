@@ -56,6 +56,41 @@ impl TypeError {
             TypeError::IngestedFileUnsupportedType(..) => Fatal,
         }
     }
+
+    /// Returns the Region the error should be reported at, if there is one.
+    /// Errors with more than one region report the one closest to where the
+    /// problem was introduced, e.g. the first def of a cycle.
+    pub fn region(&self) -> Option<Region> {
+        match self {
+            TypeError::BadExpr(region, ..)
+            | TypeError::BadPattern(region, ..)
+            | TypeError::CircularType(region, ..)
+            | TypeError::BadExprMissingAbility(region, ..)
+            | TypeError::BadPatternMissingAbility(region, ..)
+            | TypeError::StructuralSpecialization { region, .. }
+            | TypeError::WrongSpecialization { region, .. }
+            | TypeError::UnfulfilledAbility(Unfulfilled::OpaqueUnderivable {
+                derive_region: region,
+                ..
+            })
+            | TypeError::Exhaustive(roc_exhaustive::Error::Incomplete(region, ..))
+            | TypeError::Exhaustive(roc_exhaustive::Error::Redundant {
+                branch_region: region,
+                ..
+            })
+            | TypeError::Exhaustive(roc_exhaustive::Error::Unmatchable {
+                branch_region: region,
+                ..
+            }) => Some(*region),
+            TypeError::CircularDef(cycle_entries) => {
+                cycle_entries.first().map(|entry| entry.expr_region)
+            }
+            TypeError::UnexposedLookup(_)
+            | TypeError::UnfulfilledAbility(_)
+            | TypeError::IngestedFileBadUtf8(..)
+            | TypeError::IngestedFileUnsupportedType(..) => None,
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
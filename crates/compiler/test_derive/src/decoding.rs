@@ -33,8 +33,21 @@ test_key_eq! {
     same_tuple_fields_diff_types:
         v!((v!(U8), v!(U16),)), v!((v!(U32), v!(U64),))
 
+    same_tag_union:
+        v!([ A v!(U8) v!(STR), B v!(STR) ]), v!([ A v!(U8) v!(STR), B v!(STR) ])
+    same_tag_union_tags_diff_types:
+        v!([ A v!(U8) v!(U8), B v!(U8) ]), v!([ A v!(STR) v!(STR), B v!(STR) ])
+    same_tag_union_tags_any_order:
+        v!([ A v!(U8) v!(U8), B v!(U8), C ]), v!([ C, B v!(STR), A v!(STR) v!(STR) ])
+    explicit_empty_tag_union_and_implicit_empty_tag_union:
+        v!(EMPTY_TAG_UNION), v!([])
+
     list_list_diff_types:
         v!(Symbol::LIST_LIST v!(STR)), v!(Symbol::LIST_LIST v!(U8))
+    set_set_diff_types:
+        v!(Symbol::SET_SET v!(STR)), v!(Symbol::SET_SET v!(U8))
+    dict_dict_diff_types:
+        v!(Symbol::DICT_DICT v!(STR) v!(STR)), v!(Symbol::DICT_DICT v!(U8) v!(U8))
     str_str:
         v!(Symbol::STR_STR), v!(Symbol::STR_STR)
 }
@@ -49,6 +62,16 @@ test_key_neq! {
 
     different_tuple_arities:
         v!((v!(U8), v!(U16),)), v!((v!(U8), v!(U16), v!(U32),))
+
+    different_tag_union_tags:
+        v!([ A v!(U8) ]), v!([ B v!(U8) ])
+    different_tag_union_arities:
+        v!([ A v!(U8) ]), v!([ A v!(U8) v!(U8) ])
+    tag_union_empty_vs_nonempty:
+        v!(EMPTY_TAG_UNION), v!([ B v!(U8) ])
+
+    list_vs_set:
+        v!(Symbol::LIST_LIST v!(STR)), v!(Symbol::SET_SET v!(STR))
 }
 
 #[test]
@@ -101,6 +124,41 @@ fn derivable_record_with_record_ext() {
     );
 }
 
+#[test]
+fn derivable_tag_ext_flex_var() {
+    check_derivable(
+        Decoder,
+        v!([ A v!(STR) ]* ),
+        DeriveKey::Decoder(FlatDecodableKey::TagUnion(vec![("A".into(), 1)])),
+    );
+}
+
+#[test]
+fn derivable_tag_with_tag_ext() {
+    check_derivable(
+        Decoder,
+        v!([ B v!(STR) v!(U8) ][ A v!(STR) ]),
+        DeriveKey::Decoder(FlatDecodableKey::TagUnion(vec![
+            ("A".into(), 1),
+            ("B".into(), 2),
+        ])),
+    );
+}
+
+#[test]
+fn derivable_set_and_dict() {
+    check_derivable(
+        Decoder,
+        v!(Symbol::SET_SET v!(STR)),
+        DeriveKey::Decoder(FlatDecodableKey::Set()),
+    );
+    check_derivable(
+        Decoder,
+        v!(Symbol::DICT_DICT v!(STR) v!(U8)),
+        DeriveKey::Decoder(FlatDecodableKey::Dict()),
+    );
+}
+
 #[test]
 fn list() {
     derive_test(Decoder, v!(Symbol::LIST_LIST v!(STR)), |golden| {
@@ -231,3 +289,81 @@ fn tuple_2_fields() {
         )
     })
 }
+
+#[test]
+fn tag_two_labels() {
+    derive_test(Decoder, v!([A v!(STR) v!(U8), B]), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for [A Str U8, B]
+        # Decoder [A val val1, B] fmt | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # List U8, fmt -[[custom(29)]]-> { rest : List U8, result : [Err [TooShort], Ok [A val val1, B]] } | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # Specialization lambda sets:
+        #   @<1>: [[custom(29)]]
+        #Derived.decoder_[A 2,B 0] =
+          custom
+            \#Derived.bytes3, #Derived.fmt3 ->
+              decodeWith
+                #Derived.bytes3
+                (tag
+                  \#Derived.name ->
+                    when #Derived.name is
+                      "A" ->
+                        Keep (tuple
+                          { e1: Err NoElem, e0: Err NoElem }
+                          \#Derived.stateRecord2, #Derived.index ->
+                            when #Derived.index is
+                              0 ->
+                                Next (custom
+                                  \#Derived.bytes, #Derived.fmt ->
+                                    when decodeWith
+                                        #Derived.bytes
+                                        decoder
+                                        #Derived.fmt is
+                                      #Derived.rec ->
+                                        {
+                                          result: when #Derived.rec.result is
+                                              Ok #Derived.val ->
+                                                Ok {
+                                                stateRecord2 & e0: Ok #Derived.val
+                                                }
+                                              Err #Derived.err -> Err #Derived.err,
+                                          rest: #Derived.rec.rest
+                                        })
+                              1 ->
+                                Next (custom
+                                  \#Derived.bytes2, #Derived.fmt2 ->
+                                    when decodeWith
+                                        #Derived.bytes2
+                                        decoder
+                                        #Derived.fmt2 is
+                                      #Derived.rec2 ->
+                                        {
+                                          result: when #Derived.rec2.result is
+                                              Ok #Derived.val2 ->
+                                                Ok {
+                                                stateRecord2 & e1: Ok #Derived.val2
+                                                }
+                                              Err #Derived.err2 -> Err #Derived.err2,
+                                          rest: #Derived.rec2.rest
+                                        })
+                              _ -> TooLong
+                          \#Derived.stateRecord ->
+                            when #Derived.stateRecord.e0 is
+                              Ok #Derived.0 ->
+                                when #Derived.stateRecord.e1 is
+                                  Ok #Derived.1 -> Ok (A #Derived.0 #Derived.1)
+                                  _ -> Err TooShort
+                              _ -> Err TooShort)
+                      "B" ->
+                        Keep (tuple
+                          { }
+                          \#Derived.stateRecord4, #Derived.index2 ->
+                            when #Derived.index2 is
+                              _ -> TooLong
+                          \#Derived.stateRecord3 -> Ok B)
+                      _ -> Skip)
+                #Derived.fmt3
+        "###
+        )
+    })
+}
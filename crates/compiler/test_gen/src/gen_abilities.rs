@@ -1,21 +1,42 @@
 #[cfg(feature = "gen-llvm")]
 use crate::helpers::llvm::assert_evals_to;
 
+#[cfg(all(feature = "gen-dev", target_arch = "x86_64"))]
+use crate::helpers::dev::assert_evals_to;
+
 #[cfg(feature = "gen-wasm")]
 use crate::helpers::wasm::assert_evals_to;
 
-#[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
+#[cfg(all(
+    test,
+    any(
+        feature = "gen-llvm",
+        all(feature = "gen-dev", target_arch = "x86_64"),
+        feature = "gen-wasm"
+    )
+))]
 use indoc::indoc;
 
 #[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
 use roc_std::RocList;
-#[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
+#[cfg(all(
+    test,
+    any(
+        feature = "gen-llvm",
+        all(feature = "gen-dev", target_arch = "x86_64"),
+        feature = "gen-wasm"
+    )
+))]
 use roc_std::RocStr;
 
 use crate::helpers::with_larger_debug_stack;
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn hash_specialization() {
     assert_evals_to!(
         indoc!(
@@ -38,7 +59,11 @@ fn hash_specialization() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn hash_specialization_multiple_add() {
     assert_evals_to!(
         indoc!(
@@ -65,7 +90,11 @@ fn hash_specialization_multiple_add() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn alias_member_specialization() {
     assert_evals_to!(
         indoc!(
@@ -90,7 +119,11 @@ fn alias_member_specialization() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn ability_constrained_in_non_member_usage() {
     assert_evals_to!(
         indoc!(
@@ -115,7 +148,11 @@ fn ability_constrained_in_non_member_usage() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn ability_constrained_in_non_member_usage_inferred() {
     assert_evals_to!(
         indoc!(
@@ -139,7 +176,11 @@ fn ability_constrained_in_non_member_usage_inferred() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn ability_constrained_in_non_member_multiple_specializations() {
     assert_evals_to!(
         indoc!(
@@ -167,7 +208,11 @@ fn ability_constrained_in_non_member_multiple_specializations() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn ability_constrained_in_non_member_multiple_specializations_inferred() {
     assert_evals_to!(
         indoc!(
@@ -222,7 +267,11 @@ fn ability_used_as_type_still_compiles() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn bounds_to_multiple_abilities() {
     assert_evals_to!(
         indoc!(
@@ -293,7 +342,11 @@ fn encode() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 #[ignore = "running into weird let-generalization issue when a variable is only in output position, see #3660"]
 fn decode() {
     assert_evals_to!(
@@ -1227,7 +1280,7 @@ fn encode_then_decode_list_of_strings() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", all(feature = "gen-dev", target_arch = "x86_64")))]
 #[ignore = "#3696: Currently hits some weird panic in borrow checking, not sure if it's directly related to abilities."]
 fn encode_then_decode_list_of_lists_of_strings() {
     with_larger_debug_stack(|| {
@@ -1361,7 +1414,11 @@ fn decode_record_two_fields_string_and_string_infer_local_var_destructured() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 #[ignore = "json parsing impl must be fixed first"]
 fn decode_empty_record() {
     assert_evals_to!(
@@ -1447,6 +1504,119 @@ fn decode_tuple_of_tuples() {
     )
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn encode_then_decode_tag_union() {
+    with_larger_debug_stack(|| {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" imports [TotallyNotJson] provides [main] to "./platform"
+
+                main =
+                    answers : List [TheAnswer Str U8, NoAnswer]
+                    answers = [TheAnswer "is" 42, NoAnswer]
+
+                    when Encode.toBytes answers TotallyNotJson.json |> Decode.fromBytes TotallyNotJson.json is
+                        Ok decoded if decoded == answers -> "roundtrip"
+                        _ -> "something went wrong"
+                "#
+            ),
+            RocStr::from("roundtrip"),
+            RocStr
+        )
+    })
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn decode_tag_union_unknown_tag() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [TotallyNotJson] provides [main] to "./platform"
+
+            main =
+                decoded : Result [A U8, B] _
+                decoded = Str.toUtf8 "{\"C\":[]}" |> Decode.fromBytes TotallyNotJson.json
+
+                when decoded is
+                    Err (Leftover _) -> "leftover"
+                    Err TooShort -> "too short"
+                    Ok _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("too short"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn encode_then_decode_set() {
+    with_larger_debug_stack(|| {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" imports [TotallyNotJson] provides [main] to "./platform"
+
+                main =
+                    set = Set.fromList ["a", "b", "c"]
+
+                    when Encode.toBytes set TotallyNotJson.json |> Decode.fromBytes TotallyNotJson.json is
+                        Ok decoded if decoded == set -> "roundtrip"
+                        _ -> "something went wrong"
+                "#
+            ),
+            RocStr::from("roundtrip"),
+            RocStr
+        )
+    })
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn encode_then_decode_dict() {
+    with_larger_debug_stack(|| {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" imports [TotallyNotJson] provides [main] to "./platform"
+
+                main =
+                    dict = Dict.fromList [("a", 1u8), ("b", 2), ("c", 3)]
+
+                    when Encode.toBytes dict TotallyNotJson.json |> Decode.fromBytes TotallyNotJson.json is
+                        Ok decoded if decoded == dict -> "roundtrip"
+                        _ -> "something went wrong"
+                "#
+            ),
+            RocStr::from("roundtrip"),
+            RocStr
+        )
+    })
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn encode_dict() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [TotallyNotJson] provides [main] to "./platform"
+
+            main =
+                Dict.single "a" 1u8
+                |> Encode.toBytes TotallyNotJson.json
+                |> Str.fromUtf8
+                |> Result.withDefault ""
+            "#
+        ),
+        RocStr::from(r#"[["a",1]]"#),
+        RocStr
+    )
+}
+
 #[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
 mod hash {
     #[cfg(feature = "gen-llvm")]
@@ -2146,6 +2316,417 @@ mod eq {
     }
 }
 
+#[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
+mod inspect {
+    #[cfg(feature = "gen-llvm")]
+    use crate::helpers::llvm::assert_evals_to;
+
+    #[cfg(feature = "gen-wasm")]
+    use crate::helpers::wasm::assert_evals_to;
+
+    use indoc::indoc;
+    use roc_std::RocStr;
+
+    #[test]
+    fn number() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = Inspect.toStr 42u8
+                "#
+            ),
+            RocStr::from("42"),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn string() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = Inspect.toStr "foo \"bar\""
+                "#
+            ),
+            RocStr::from(r#""foo \"bar\"""#),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn record() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = Inspect.toStr { b: Bool.true, a: [1u8, 2u8], c: ("x", {}) }
+                "#
+            ),
+            RocStr::from(r#"{ a: [1, 2], b: Bool.true, c: ("x", {}) }"#),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn nested_tags() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = Inspect.toStr (Ok (A (B 1u8) (-1i8)))
+                "#
+            ),
+            RocStr::from("Ok (A (B 1) (-1))"),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn recursive_tag_union() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                LinkedList : [Nil, Cons U8 LinkedList]
+
+                list : LinkedList
+                list = Cons 1 (Cons 2 Nil)
+
+                main = Inspect.toStr list
+                "#
+            ),
+            RocStr::from("Cons 1 (Cons 2 Nil)"),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn dict_and_set() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main =
+                    dict = Dict.single "a" 1u8
+                    set = Set.single 2u8
+
+                    Inspect.toStr (dict, set)
+                "#
+            ),
+            RocStr::from(r#"(Dict.fromList [("a", 1)], Set.fromList [2])"#),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn boxed() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = Inspect.toStr (Box.box "a", Ok (Box.box 1u8))
+                "#
+            ),
+            RocStr::from(r#"(Box.box "a", Ok (Box.box 1))"#),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn opaque_without_inspect() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                Secret := Str
+
+                main = Inspect.toStr { secret: @Secret "hunter2" }
+                "#
+            ),
+            RocStr::from("{ secret: <opaque> }"),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn derived_opaque() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                Id := U64 has [Inspect]
+
+                main = Inspect.toStr ([@Id 1], Ok (@Id 2))
+                "#
+            ),
+            RocStr::from("([@Id 1], Ok (@Id 2))"),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn custom_opaque() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                Secret := Str has [Inspect { toInspector: hidden }]
+
+                hidden = \@Secret _ -> Inspect.str "<redacted>"
+
+                main = Inspect.toStr (Named (@Secret "hunter2"))
+                "#
+            ),
+            RocStr::from(r#"Named "<redacted>""#),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn function() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = Inspect.toStr { f: \x -> x + 1u8 }
+                "#
+            ),
+            RocStr::from("{ f: <function> }"),
+            RocStr
+        )
+    }
+}
+
+#[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
+mod sort {
+    #[cfg(feature = "gen-llvm")]
+    use crate::helpers::llvm::assert_evals_to;
+
+    #[cfg(feature = "gen-wasm")]
+    use crate::helpers::wasm::assert_evals_to;
+
+    use indoc::indoc;
+    use roc_std::{RocList, RocStr};
+
+    #[test]
+    fn numbers() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = [Sort.compare 1u8 2u8, Sort.compare 1.5f64 1.5f64, Sort.compare 10i64 (-10i64)] == [LT, EQ, GT]
+                "#
+            ),
+            true,
+            bool
+        )
+    }
+
+    #[test]
+    fn strings() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = [Sort.compare "apple" "banana", Sort.compare "Zebra" "apple", Sort.compare "apples" "apple"] == [LT, LT, GT]
+                "#
+            ),
+            true,
+            bool
+        )
+    }
+
+    #[test]
+    fn lists() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = [Sort.compare [1u8, 2] [1, 3], Sort.compare [1u8, 2] [1], Sort.compare [[1u8]] [[1u8]]] == [LT, GT, EQ]
+                "#
+            ),
+            true,
+            bool
+        )
+    }
+
+    #[test]
+    fn records_compare_fields_in_alphabetical_order() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = [Sort.compare { b: 1u8, a: "y" } { a: "x", b: 2u8 }, Sort.compare { a: "x", b: 1u8 } { b: 2u8, a: "x" }, Sort.compare {} {}] == [GT, LT, EQ]
+                "#
+            ),
+            true,
+            bool
+        )
+    }
+
+    #[test]
+    fn tuples() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = [Sort.compare (1u8, "b") (1u8, "a"), Sort.compare (1u8, "b") (2u8, "a")] == [GT, LT]
+                "#
+            ),
+            true,
+            bool
+        )
+    }
+
+    #[test]
+    fn tag_unions_compare_tags_then_payloads() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = [Sort.compare (Err "a") (Ok 1u8), Sort.compare (Ok 2u8) (Ok 1u8), Sort.compare (Ok 1u8) (Ok 1u8)] == [LT, GT, EQ]
+                "#
+            ),
+            true,
+            bool
+        )
+    }
+
+    #[test]
+    fn recursive_tag_union() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                LinkedList : [Nil, Cons U8 LinkedList]
+
+                a : LinkedList
+                a = Cons 1 (Cons 2 Nil)
+
+                b : LinkedList
+                b = Cons 1 (Cons 2 (Cons 3 Nil))
+
+                main = [Sort.compare a b, Sort.compare b a, Sort.compare a a] == [LT, GT, EQ]
+                "#
+            ),
+            true,
+            bool
+        )
+    }
+
+    #[test]
+    fn opaque_derived() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                Version := { major : U8, minor : U8 } has [Eq, Sort]
+
+                main = Sort.isLt (@Version { major: 1, minor: 9 }) (@Version { major: 2, minor: 0 })
+                "#
+            ),
+            true,
+            bool
+        )
+    }
+
+    #[test]
+    fn opaque_custom() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                Reversed := U8 has [Sort { compare: compareReversed }]
+
+                compareReversed = \@Reversed a, @Reversed b -> Num.compare b a
+
+                main = List.sort [@Reversed 1, @Reversed 3, @Reversed 2] |> List.map \@Reversed n -> n
+                "#
+            ),
+            RocList::from_slice(&[3u8, 2, 1]),
+            RocList<u8>
+        )
+    }
+
+    #[test]
+    fn list_sort() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main =
+                    List.sort [("b", 2u8), ("a", 2u8), ("b", 1u8)]
+                    |> List.map \(s, n) -> Str.concat s (Num.toStr n)
+                "#
+            ),
+            RocList::from_slice(&[RocStr::from("a2"), RocStr::from("b1"), RocStr::from("b2")]),
+            RocList<RocStr>
+        )
+    }
+
+    #[test]
+    fn min_and_max() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = Str.concat (Sort.min "b" "a") (Sort.max "b" "c")
+                "#
+            ),
+            RocStr::from("ac"),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn dict_and_set_sorted() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                dict =
+                    Dict.empty {}
+                    |> Dict.insert "c" 3u8
+                    |> Dict.insert "a" 1u8
+                    |> Dict.insert "b" 2u8
+
+                main =
+                    values = Dict.toSortedList dict |> List.map \(_, v) -> v
+
+                    List.concat values (Set.toSortedList (Set.fromList [6u8, 4, 5]))
+                "#
+            ),
+            RocList::from_slice(&[1u8, 2, 3, 4, 5, 6]),
+            RocList<u8>
+        )
+    }
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn issue_4772_weakened_monomorphic_destructure() {
@@ -158,7 +158,11 @@ fn not_bool() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn empty_record() {
     assert_evals_to!("{} == {}", true, bool);
     assert_evals_to!("{} != {}", false, bool);
@@ -191,7 +195,11 @@ fn record() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn unit() {
     assert_evals_to!("Unit == Unit", true, bool);
     assert_evals_to!("Unit != Unit", false, bool);
@@ -243,7 +251,11 @@ fn large_str() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn eq_result_tag_true() {
     assert_evals_to!(
         indoc!(
@@ -263,7 +275,11 @@ fn eq_result_tag_true() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn eq_result_tag_false() {
     assert_evals_to!(
         indoc!(
@@ -283,7 +299,11 @@ fn eq_result_tag_false() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn eq_expr() {
     assert_evals_to!(
         indoc!(
@@ -305,7 +325,11 @@ fn eq_expr() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn eq_linked_list() {
     assert_evals_to!(
         indoc!(
@@ -363,7 +387,11 @@ fn eq_linked_list() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn eq_linked_list_false() {
     assert_evals_to!(
         indoc!(
@@ -418,7 +446,11 @@ fn eq_linked_list_long() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn eq_nullable_expr() {
     assert_evals_to!(
         indoc!(
@@ -575,21 +607,33 @@ fn rosetree_with_tag() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn list_eq_empty() {
     assert_evals_to!("[] == []", true, bool);
     assert_evals_to!("[] != []", false, bool);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn list_eq_by_length() {
     assert_evals_to!("[1] == []", false, bool);
     assert_evals_to!("[] == [1]", false, bool);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn list_eq_compare_pointwise() {
     assert_evals_to!("[1] == [1]", true, bool);
     assert_evals_to!("[2] == [1]", false, bool);
@@ -603,7 +647,11 @@ fn list_eq_nested() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn list_neq_compare_pointwise() {
     assert_evals_to!("[1] != [1]", false, bool);
     assert_evals_to!("[2] != [1]", true, bool);
@@ -617,7 +665,11 @@ fn list_neq_nested() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn compare_union_same_content() {
     assert_evals_to!(
         indoc!(
@@ -639,7 +691,11 @@ fn compare_union_same_content() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn compare_recursive_union_same_content() {
     assert_evals_to!(
         indoc!(
@@ -661,7 +717,11 @@ fn compare_recursive_union_same_content() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn compare_nullable_recursive_union_same_content() {
     assert_evals_to!(
         indoc!(
@@ -683,7 +743,11 @@ fn compare_nullable_recursive_union_same_content() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn boxed_eq_int() {
     assert_evals_to!("Box.box 1i64 == Box.box 1", true, bool);
     assert_evals_to!("Box.box 2i64 == Box.box 1", false, bool);
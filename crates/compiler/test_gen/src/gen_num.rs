@@ -486,7 +486,11 @@ fn f64_sqrt_checked_positive() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn f64_sqrt_checked_negative() {
     assert_evals_to!("Num.sqrtChecked -1f64", RocResult::err(()), RocResult<f64, ()>);
 }
@@ -614,7 +618,11 @@ fn gen_if_fn() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_float_eq() {
     assert_evals_to!(
         indoc!(
@@ -628,7 +636,11 @@ fn gen_float_eq() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_add_dec() {
     assert_evals_to!(
         indoc!(
@@ -737,7 +749,11 @@ fn gen_div_checked_by_zero_f64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_div_dec() {
     assert_evals_to!(
         indoc!(
@@ -814,7 +830,11 @@ fn gen_int_eq() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_int_neq() {
     assert_evals_to!(
         indoc!(
@@ -846,7 +866,11 @@ fn float_greater_than() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_dec_eq() {
     assert_evals_to!(
         indoc!(
@@ -866,7 +890,11 @@ fn gen_dec_eq() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_dec_neq() {
     assert_evals_to!(
         indoc!(
@@ -886,7 +914,11 @@ fn gen_dec_neq() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_wrap_int_neq() {
     assert_evals_to!(
         indoc!(
@@ -1002,7 +1034,11 @@ fn gen_add_i64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_sub_dec() {
     assert_evals_to!(
         indoc!(
@@ -1025,7 +1061,11 @@ fn gen_sub_dec() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_mul_dec() {
     assert_evals_to!(
         indoc!(
@@ -1048,13 +1088,21 @@ fn gen_mul_dec() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_sub_f64() {
     assert_evals_to!("1.5f64 - 2.4 - 3", -3.9, f64);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_sub_f32() {
     assert_evals_to!("1.5f32 - 2.4 - 3", -3.9, f32);
 }
@@ -1144,7 +1192,11 @@ fn gen_div_u64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_div_checked_i64() {
     assert_evals_to!(
         indoc!(
@@ -1160,7 +1212,11 @@ fn gen_div_checked_i64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_div_checked_by_zero_i64() {
     assert_evals_to!(
         indoc!(
@@ -1198,7 +1254,11 @@ fn gen_rem_checked_div_by_zero_i64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_is_positive_i64() {
     assert_evals_to!("Num.isPositive 0", false, bool);
     assert_evals_to!("Num.isPositive 1", true, bool);
@@ -1206,7 +1266,11 @@ fn gen_is_positive_i64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_is_negative_i64() {
     assert_evals_to!("Num.isNegative 0", false, bool);
     assert_evals_to!("Num.isNegative 3", false, bool);
@@ -1214,7 +1278,11 @@ fn gen_is_negative_i64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_is_positive_f64() {
     assert_evals_to!("Num.isPositive 0.0", false, bool);
     assert_evals_to!("Num.isPositive 4.7", true, bool);
@@ -1222,7 +1290,11 @@ fn gen_is_positive_f64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_is_negative_f64() {
     assert_evals_to!("Num.isNegative 0.0", false, bool);
     assert_evals_to!("Num.isNegative 9.9", false, bool);
@@ -1303,7 +1375,11 @@ fn bitwise_or() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn lt_u8() {
     assert_evals_to!("1u8 < 2u8", true, bool);
     assert_evals_to!("1u8 < 1u8", false, bool);
@@ -1313,7 +1389,11 @@ fn lt_u8() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn lte_u8() {
     assert_evals_to!("1u8 <= 1u8", true, bool);
     assert_evals_to!("2u8 <= 1u8", false, bool);
@@ -1323,7 +1403,11 @@ fn lte_u8() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gt_u8() {
     assert_evals_to!("2u8 > 1u8", true, bool);
     assert_evals_to!("2u8 > 2u8", false, bool);
@@ -1333,7 +1417,11 @@ fn gt_u8() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gte_u8() {
     assert_evals_to!("1u8 >= 1u8", true, bool);
     assert_evals_to!("1u8 >= 2u8", false, bool);
@@ -1353,7 +1441,11 @@ fn lt_u64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn lte_u64() {
     assert_evals_to!("1u64 <= 1u64", true, bool);
     assert_evals_to!("2u64 <= 1u64", false, bool);
@@ -1373,7 +1465,11 @@ fn gt_u64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gte_u64() {
     assert_evals_to!("1u64 >= 1u64", true, bool);
     assert_evals_to!("1u64 >= 2u64", false, bool);
@@ -1392,7 +1488,11 @@ fn lt_i64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn lte_i64() {
     assert_evals_to!("1 <= 1", true, bool);
     assert_evals_to!("2 <= 1", false, bool);
@@ -1410,7 +1510,11 @@ fn gt_i64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gte_i64() {
     assert_evals_to!("1 >= 1", true, bool);
     assert_evals_to!("1 >= 2", false, bool);
@@ -1419,7 +1523,11 @@ fn gte_i64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn lt_f64() {
     assert_evals_to!("1.1 < 1.2", true, bool);
     assert_evals_to!("1.1 < 1.1", false, bool);
@@ -1428,7 +1536,11 @@ fn lt_f64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn lte_f64() {
     assert_evals_to!("1.1 <= 1.1", true, bool);
     assert_evals_to!("1.2 <= 1.1", false, bool);
@@ -1437,7 +1549,11 @@ fn lte_f64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gt_f64() {
     assert_evals_to!("2.2 > 1.1", true, bool);
     assert_evals_to!("2.2 > 2.2", false, bool);
@@ -1446,7 +1562,11 @@ fn gt_f64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gte_f64() {
     assert_evals_to!("1.1 >= 1.1", true, bool);
     assert_evals_to!("1.1 >= 1.2", false, bool);
@@ -1469,7 +1589,11 @@ fn gen_order_of_arithmetic_ops() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_order_of_arithmetic_ops_complex_float() {
     assert_evals_to!(
         indoc!(
@@ -1551,7 +1675,11 @@ fn int_negate_dev() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn int_negate() {
     assert_evals_to!("Num.neg 123", -123, i64);
     assert_evals_to!("Num.neg Num.maxI64", -i64::MAX, i64);
@@ -1576,7 +1704,11 @@ fn neg_min_int_overflow() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn gen_wrap_int_neg() {
     assert_evals_to!(
         indoc!(
@@ -1731,7 +1863,11 @@ fn frac_is_finite() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn int_compare() {
     assert_evals_to!("Num.compare 0 1", RocOrder::Lt, RocOrder);
     assert_evals_to!("Num.compare 1 1", RocOrder::Eq, RocOrder);
@@ -1739,7 +1875,11 @@ fn int_compare() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn float_compare() {
     assert_evals_to!("Num.compare 0.01 3.14", RocOrder::Lt, RocOrder);
     assert_evals_to!("Num.compare 3.14 3.14", RocOrder::Eq, RocOrder);
@@ -1812,7 +1952,11 @@ fn int_add_checked_err() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn int_add_wrap() {
     assert_evals_to!(
         "Num.addWrap 9_223_372_036_854_775_807 1",
@@ -1871,7 +2015,11 @@ fn int_sub_wrap() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn float_sub_overflow() {
     assert_evals_to!(
         "-1.7976931348623157e308 - 1.7976931348623157e308",
@@ -1967,7 +2115,11 @@ fn int_negative_mul_overflow() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn float_positive_mul_overflow() {
     assert_evals_to!(
         indoc!(
@@ -1981,7 +2133,11 @@ fn float_positive_mul_overflow() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn float_negative_mul_overflow() {
     assert_evals_to!(
         indoc!(
@@ -2168,121 +2324,201 @@ fn max_i128() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn min_i64() {
     assert_evals_to!("Num.minI64", i64::MIN, i64);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn max_i64() {
     assert_evals_to!("Num.maxI64", i64::MAX, i64);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn min_u64() {
     assert_evals_to!("Num.minU64", u64::MIN, u64);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn max_u64() {
     assert_evals_to!("Num.maxU64", u64::MAX, u64);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn min_i32() {
     assert_evals_to!("Num.minI32", i32::MIN, i32);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn max_i32() {
     assert_evals_to!("Num.maxI32", i32::MAX, i32);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn min_u32() {
     assert_evals_to!("Num.minU32", u32::MIN, u32);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn max_u32() {
     assert_evals_to!("Num.maxU32", u32::MAX, u32);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn min_i16() {
     assert_evals_to!("Num.minI16", i16::MIN, i16);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn max_i16() {
     assert_evals_to!("Num.maxI16", i16::MAX, i16);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn min_u16() {
     assert_evals_to!("Num.minU16", u16::MIN, u16);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn max_u16() {
     assert_evals_to!("Num.maxU16", u16::MAX, u16);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn min_i8() {
     assert_evals_to!("Num.minI8", i8::MIN, i8);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn max_i8() {
     assert_evals_to!("Num.maxI8", i8::MAX, i8);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn min_u8() {
     assert_evals_to!("Num.minU8", u8::MIN, u8);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn max_u8() {
     assert_evals_to!("Num.maxU8", u8::MAX, u8);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn max_f64() {
     assert_evals_to!("Num.maxF64", f64::MAX, f64);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn min_f64() {
     assert_evals_to!("Num.minF64", f64::MIN, f64);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn max_f32() {
     assert_evals_to!("Num.maxF32", f32::MAX, f32);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn min_f32() {
     assert_evals_to!("Num.minF32", f32::MIN, f32);
 }
@@ -2912,7 +3148,11 @@ fn bytes_to_u128_random_u8s() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn when_on_i32() {
     assert_evals_to!(
         indoc!(
@@ -2935,7 +3175,11 @@ fn when_on_i32() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn when_on_i16() {
     assert_evals_to!(
         indoc!(
@@ -3190,7 +3434,11 @@ fn num_to_str_f64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn num_to_str_dec() {
     use roc_std::RocStr;
 
@@ -3214,7 +3462,11 @@ fn num_to_str_dec() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn u8_addition_greater_than_i8() {
     assert_evals_to!(
         indoc!(
@@ -3232,7 +3484,11 @@ fn u8_addition_greater_than_i8() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn u8_sub_greater_than_i8() {
     assert_evals_to!(
         indoc!(
@@ -3250,7 +3506,11 @@ fn u8_sub_greater_than_i8() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn u8_mul_greater_than_i8() {
     assert_evals_to!(
         indoc!(
@@ -3428,7 +3688,11 @@ fn mul_saturated() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn monomorphized_ints() {
     assert_evals_to!(
         indoc!(
@@ -3447,7 +3711,11 @@ fn monomorphized_ints() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn monomorphized_floats() {
     assert_evals_to!(
         indoc!(
@@ -3466,7 +3734,11 @@ fn monomorphized_floats() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn monomorphized_ints_names_dont_conflict() {
     assert_evals_to!(
         indoc!(
@@ -3490,7 +3762,11 @@ fn monomorphized_ints_names_dont_conflict() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn monomorphized_ints_aliased() {
     assert_evals_to!(
         indoc!(
@@ -3517,7 +3793,11 @@ fn monomorphized_ints_aliased() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn to_float_f32() {
     assert_evals_to!(
         indoc!(
@@ -3536,7 +3816,11 @@ fn to_float_f32() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn to_float_f64() {
     assert_evals_to!(
         indoc!(
@@ -3587,7 +3871,11 @@ fn upcast_of_int_checked_is_zext() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn modulo_of_unsigned() {
     assert_evals_to!(
         indoc!(
@@ -3601,7 +3889,11 @@ fn modulo_of_unsigned() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn div_of_unsigned() {
     assert_evals_to!(
         indoc!(
@@ -3615,7 +3907,11 @@ fn div_of_unsigned() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn dec_float_suffix() {
     assert_evals_to!(
         indoc!(
@@ -3629,7 +3925,11 @@ fn dec_float_suffix() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn dec_no_decimal() {
     assert_evals_to!(
         indoc!(
@@ -3691,7 +3991,11 @@ fn round_to_u32() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn promote_u64_number_layout() {
     assert_evals_to!(
         indoc!(
@@ -3705,7 +4009,11 @@ fn promote_u64_number_layout() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn promote_i128_number_layout() {
     assert_evals_to!(
         indoc!(
@@ -3722,7 +4030,11 @@ fn promote_i128_number_layout() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn promote_u128_number_layout() {
     assert_evals_to!(
         indoc!(
@@ -3736,7 +4048,11 @@ fn promote_u128_number_layout() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn when_on_decimals() {
     assert_evals_to!(
         indoc!(
@@ -3785,7 +4101,11 @@ fn when_on_i128() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn when_on_u128() {
     assert_evals_to!(
         indoc!(
@@ -3803,7 +4123,11 @@ fn when_on_u128() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn condition_polymorphic_num_becomes_float() {
     assert_evals_to!(
         indoc!(
@@ -3845,7 +4169,11 @@ fn num_count_one_bits() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn num_abs_diff_int() {
     assert_evals_to!(r#"Num.absDiff 0u8 0u8"#, 0, u8);
     assert_evals_to!(r#"Num.absDiff 1u8 2u8"#, 1, u8);
@@ -3856,7 +4184,7 @@ fn num_abs_diff_int() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", all(feature = "gen-dev", target_arch = "x86_64")))]
 fn num_abs_diff_large_bits() {
     assert_evals_to!(r#"Num.absDiff 0u128 0u128"#, 0, u128);
     assert_evals_to!(r#"Num.absDiff 1u128 2u128"#, 1, u128);
@@ -3865,7 +4193,11 @@ fn num_abs_diff_large_bits() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn num_abs_diff_float() {
     assert_evals_to!(r#"Num.absDiff 0.0 0.0"#, 0.0, f64);
     assert_evals_to!(r#"Num.absDiff 1.0 2.0"#, 1.0, f64);
@@ -3896,7 +4228,11 @@ fn num_abs_large_bits_min_overflow() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn num_abs_float_overflow() {
     assert_evals_to!("Num.absDiff Num.maxF64 Num.minF64", f64::INFINITY, f64);
 }
@@ -3926,7 +4262,11 @@ fn bool_in_switch() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn add_checked_dec() {
     assert_evals_to!(
         indoc!(
@@ -3940,7 +4280,11 @@ fn add_checked_dec() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn sub_checked_dec() {
     assert_evals_to!(
         indoc!(
@@ -1510,7 +1510,11 @@ fn rbtree_balance_3() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 #[ignore]
 fn rbtree_layout_issue() {
     // there is a flex var in here somewhere that blows up layout creation
@@ -1552,7 +1556,11 @@ fn rbtree_layout_issue() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 #[ignore]
 fn rbtree_balance_mono_problem() {
     // because of how the function is written, only `Red` is used and so in the function's
@@ -1951,7 +1959,11 @@ fn alias_of_alias_with_type_arguments() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 #[ignore]
 fn todo_bad_error_message() {
     assert_evals_to!(
@@ -2054,7 +2066,11 @@ fn unsupported_pattern_str_interp() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 #[ignore]
 fn fingertree_basic() {
     assert_evals_to!(
@@ -2117,7 +2133,11 @@ fn case_or_pattern() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 #[ignore]
 fn rosetree_basic() {
     assert_evals_to!(
@@ -2511,7 +2531,11 @@ fn function_malformed_pattern() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 #[ignore = "causes alias analysis panics, should roc_panic"]
 fn call_invalid_layout() {
     assert_evals_to!(
@@ -10,7 +10,14 @@ use crate::helpers::wasm::assert_evals_to;
 // use crate::assert_wasm_evals_to as assert_evals_to;
 use indoc::indoc;
 
-#[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
+#[cfg(all(
+    test,
+    any(
+        feature = "gen-llvm",
+        all(feature = "gen-dev", target_arch = "x86_64"),
+        feature = "gen-wasm"
+    )
+))]
 use roc_std::{RocList, RocStr};
 
 #[test]
@@ -88,7 +95,11 @@ fn f64_record() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn fn_record() {
     assert_evals_to!(
         indoc!(
@@ -394,7 +405,11 @@ fn return_record() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn optional_field_when_use_default() {
     assert_evals_to!(
         indoc!(
@@ -422,7 +437,11 @@ fn optional_field_when_use_default() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn optional_field_when_use_default_nested() {
     assert_evals_to!(
         indoc!(
@@ -446,7 +465,11 @@ fn optional_field_when_use_default_nested() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn optional_field_destructure_module() {
     assert_evals_to!(
         indoc!(
@@ -467,7 +490,11 @@ fn optional_field_destructure_module() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn optional_field_destructure_expr() {
     assert_evals_to!(
         indoc!(
@@ -506,7 +533,11 @@ fn optional_field_let_use_default() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn optional_field_let_no_use_default() {
     assert_evals_to!(
         indoc!(
@@ -527,7 +558,11 @@ fn optional_field_let_no_use_default() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn optional_field_let_no_use_default_nested() {
     assert_evals_to!(
         indoc!(
@@ -562,7 +597,11 @@ fn optional_field_function_use_default() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn optional_field_function_no_use_default() {
     assert_evals_to!(
         indoc!(
@@ -581,7 +620,11 @@ fn optional_field_function_no_use_default() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn optional_field_function_no_use_default_nested() {
     assert_evals_to!(
         indoc!(
@@ -726,7 +769,11 @@ fn return_record_float_int() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn return_record_int_float() {
     assert_evals_to!(
         indoc!(
@@ -740,7 +787,11 @@ fn return_record_int_float() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn return_record_float_float() {
     assert_evals_to!(
         indoc!(
@@ -754,7 +805,11 @@ fn return_record_float_float() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn return_record_float_float_float() {
     assert_evals_to!(
         indoc!(
@@ -782,7 +837,11 @@ fn return_nested_record() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn nested_record_load() {
     assert_evals_to!(
         indoc!(
@@ -800,13 +859,21 @@ fn nested_record_load() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn accessor_twice() {
     assert_evals_to!(".foo { foo: 4 }  + .foo { bar: 2.46, foo: 3 } ", 7, i64);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn accessor_multi_element_record() {
     assert_evals_to!(
         indoc!(
@@ -820,7 +887,11 @@ fn accessor_multi_element_record() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn accessor_single_element_record() {
     assert_evals_to!(
         indoc!(
@@ -866,7 +937,11 @@ fn update_single_element_record() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn booleans_in_record() {
     assert_evals_to!(
         indoc!("{ x: 1 == 1, y: 1 == 1 }"),
@@ -891,7 +966,11 @@ fn booleans_in_record() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn alignment_in_record() {
     assert_evals_to!(
         indoc!(
@@ -903,7 +982,11 @@ fn alignment_in_record() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn blue_and_present() {
     assert_evals_to!(
         indoc!(
@@ -922,7 +1005,11 @@ fn blue_and_present() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn blue_and_absent() {
     assert_evals_to!(
         indoc!(
@@ -965,7 +1052,11 @@ fn update_the_only_field() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 // https://github.com/roc-lang/roc/issues/1513
 fn both_have_unique_fields() {
     assert_evals_to!(
@@ -986,7 +1077,11 @@ fn both_have_unique_fields() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 // https://github.com/roc-lang/roc/issues/2535
 fn different_proc_types_specialized_to_same_layout() {
     assert_evals_to!(
@@ -1038,7 +1133,11 @@ fn call_with_bad_record_runtime_error() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn generalized_accessor() {
     assert_evals_to!(
         indoc!(
@@ -1094,7 +1193,11 @@ fn update_record_that_is_a_thunk_single_field() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn toplevel_accessor_fn_thunk() {
     assert_evals_to!(
         indoc!(
@@ -1113,7 +1216,11 @@ fn toplevel_accessor_fn_thunk() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn pass_record_of_u8s() {
     assert_evals_to!(
         indoc!(
@@ -13,7 +13,11 @@ use indoc::indoc;
 use roc_std::{RocResult, RocStr};
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn with_default_ok() {
     assert_evals_to!(
         indoc!(
@@ -30,7 +34,11 @@ fn with_default_ok() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn with_default_err() {
     assert_evals_to!(
         indoc!(
@@ -47,7 +55,11 @@ fn with_default_err() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn result_map() {
     assert_evals_to!(
         indoc!(
@@ -81,7 +93,11 @@ fn result_map() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn result_map_err() {
     assert_evals_to!(
         indoc!(
@@ -115,7 +131,11 @@ fn result_map_err() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn err_type_var() {
     assert_evals_to!(
         indoc!(
@@ -130,7 +150,11 @@ fn err_type_var() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn err_type_var_annotation() {
     assert_evals_to!(
         indoc!(
@@ -148,7 +172,11 @@ fn err_type_var_annotation() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn err_empty_tag_union() {
     assert_evals_to!(
         indoc!(
@@ -166,7 +194,11 @@ fn err_empty_tag_union() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn is_ok() {
     assert_evals_to!(
         indoc!(
@@ -196,7 +228,11 @@ fn is_ok() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn is_err() {
     assert_evals_to!(
         indoc!(
@@ -292,7 +328,11 @@ fn issue_2583_specialize_errors_behind_unified_branches() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn roc_result_after_on_ok() {
     assert_evals_to!(indoc!(
         r#"
@@ -308,7 +348,11 @@ fn roc_result_after_on_ok() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn roc_result_after_on_err() {
     assert_evals_to!(indoc!(
         r#"
@@ -324,7 +368,11 @@ fn roc_result_after_on_err() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(
+    feature = "gen-llvm",
+    all(feature = "gen-dev", target_arch = "x86_64"),
+    feature = "gen-wasm"
+))]
 fn roc_result_after_err() {
     assert_evals_to!(
         indoc!(
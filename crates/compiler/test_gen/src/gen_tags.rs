@@ -1380,7 +1380,7 @@ fn issue_2445() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", all(feature = "gen-dev", target_arch = "x86_64")))]
 fn issue_2458() {
     assert_evals_to!(
         indoc!(
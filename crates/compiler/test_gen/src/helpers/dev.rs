@@ -191,6 +191,7 @@ pub fn helper(
         exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
         lazy_literals,
         mode: roc_gen_dev::AssemblyBackendMode::Test,
+        debug_sources: None,
     };
 
     let target = target_lexicon::Triple::host();
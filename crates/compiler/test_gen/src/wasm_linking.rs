@@ -117,6 +117,8 @@ fn build_app_mono<'a>(
         ret_layout: int_layout,
         is_self_recursive: SelfRecursive::NotSelfRecursive,
         host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+        region: roc_region::all::Region::zero(),
+        statement_regions: &[],
     };
 
     let proc_layout = ProcLayout {
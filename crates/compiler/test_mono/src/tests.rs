@@ -83,6 +83,7 @@ fn compiles_to_ir(test_name: &str, src: &str, mode: &str, allow_type_errors: boo
     let exec_mode = match mode {
         "exec" => ExecutionMode::Executable,
         "test" => ExecutionMode::Test,
+        "test-coverage" => ExecutionMode::TestWithCoverage,
         _ => panic!("Invalid test_mono exec mode {mode}"),
     };
 
@@ -3146,3 +3147,20 @@ fn dbg_str_followed_by_number() {
         "#
     )
 }
+
+#[mono_test(mode = "test-coverage")]
+fn coverage_counts_defs_and_branches() {
+    indoc!(
+        r###"
+        interface Test exposes [] imports []
+
+        classify : I64 -> Str
+        classify = \n ->
+            when n is
+                0 -> "zero"
+                _ -> if n > 0 then "positive" else "negative"
+
+        expect classify 1 == "positive"
+        "###
+    )
+}
@@ -1717,6 +1717,10 @@ impl Subs {
     pub const AB_HASH: SubsSlice<Symbol>     = SubsSlice::new(3, 1);
     #[rustfmt::skip]
     pub const AB_EQ: SubsSlice<Symbol>       = SubsSlice::new(4, 1);
+    #[rustfmt::skip]
+    pub const AB_INSPECT: SubsSlice<Symbol>  = SubsSlice::new(5, 1);
+    #[rustfmt::skip]
+    pub const AB_SORT: SubsSlice<Symbol>     = SubsSlice::new(6, 1);
 
     pub fn new() -> Self {
         Self::with_capacity(0)
@@ -1741,6 +1745,8 @@ impl Subs {
         symbol_names.push(Symbol::HASH_HASHER);
         symbol_names.push(Symbol::HASH_HASH_ABILITY);
         symbol_names.push(Symbol::BOOL_EQ);
+        symbol_names.push(Symbol::INSPECT_INSPECT_ABILITY);
+        symbol_names.push(Symbol::SORT_SORT_ABILITY);
 
         let mut subs = Subs {
             utable: UnificationTable::default(),
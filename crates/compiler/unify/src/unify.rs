@@ -1013,6 +1013,8 @@ fn opaque_obligation(opaque: Symbol, opaque_var: Variable) -> Obligated {
     match opaque.module_id() {
         // Numbers should be treated as ad-hoc obligations for ability checking.
         ModuleId::NUM => Obligated::Adhoc(opaque_var),
+        // So are builtin containers, whose elements must implement the ability too.
+        _ if matches!(opaque, Symbol::DICT_DICT | Symbol::SET_SET) => Obligated::Adhoc(opaque_var),
         _ => Obligated::Opaque(opaque),
     }
 }
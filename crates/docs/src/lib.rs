@@ -10,12 +10,15 @@ use roc_load::docs::{ModuleDocumentation, RecordField};
 use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threading};
 use roc_module::symbol::{Interns, Symbol};
 use roc_packaging::cache::{self, RocCacheDir};
+use roc_parse::doc_tests::is_doc_test;
 use roc_parse::ident::{parse_ident, Accessor, Ident};
 use roc_parse::state::State;
 use roc_region::all::Region;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+pub mod search;
+
 const BUILD_DIR: &str = "./generated-docs";
 
 const LINK_SVG: &str = include_str!("./static/link.svg");
@@ -98,9 +101,24 @@ pub fn generate_docs_html(root_file: PathBuf) {
         })
     }
 
+    // Write the search index, which is generated rather than copied
+    {
+        let search_index = search::search_index(&loaded_module);
+        let dir = build_dir.join("search-index.js");
+
+        fs::write(&dir, search::search_index_to_js(&search_index)).unwrap_or_else(|error| {
+            panic!(
+                "Attempted to write {} but failed with this error: {}",
+                dir.display(),
+                error
+            )
+        })
+    }
+
     // Insert asset urls & sidebar links
     let template_html = assets
         .raw_template_html
+        .replace("<!-- search-index.js -->", "/search-index.js")
         .replace("<!-- search.js -->", "/search.js")
         .replace("<!-- styles.css -->", "/styles.css")
         .replace("<!-- favicon.svg -->", "/favicon.svg")
@@ -969,6 +987,13 @@ fn markdown_to_html(
                         }
 
                         // TODO HANDLE CHECKING BY DEFAULT
+                        if is_doc_test(&code_str, &to_highlight) {
+                            // `roc test` runs the expects in this example
+                            docs_parser.push(Event::Html(CowStr::from(
+                                r#"<p class="tested-example" title="The expects in this example are run by roc test">Tested example</p>"#,
+                            )));
+                        }
+
                         let highlighted_html = roc_highlight::highlight_roc_code(&to_highlight);
                         docs_parser.push(Event::Html(CowStr::from(highlighted_html)));
                     }
@@ -0,0 +1,311 @@
+//! The search index for generated docs. It lists every exposed value, type and ability along with
+//! its type signature, so `search.js` can find entries by name or by the shape of their type -
+//! e.g. searching for `List a, (a -> b) -> List b` finds `List.map`.
+use crate::{module_link_url, type_annotation_to_html};
+use roc_load::docs::{DocDef, DocEntry, TypeAnnotation};
+use roc_load::LoadedModule;
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
+use roc_types::subs::Subs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchEntryKind {
+    Value,
+    Type,
+    Ability,
+}
+
+impl SearchEntryKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SearchEntryKind::Value => "value",
+            SearchEntryKind::Type => "type",
+            SearchEntryKind::Ability => "ability",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchEntry {
+    pub module_name: String,
+    pub name: String,
+    pub kind: SearchEntryKind,
+    /// For values, their inferred type, e.g. `List a, (a -> b) -> List b`. For types and
+    /// abilities, their name and type variables, followed by their definition if it's not opaque.
+    pub type_signature: String,
+    /// The type signature as [type_shape] normalizes it, which is what type searches match on.
+    pub type_shape: String,
+    /// Where the entry is documented, e.g. `/List#map`
+    pub url: String,
+}
+
+/// Every exposed value, type and ability in the loaded modules. Ability members are listed as
+/// values of their own, since that's how they're used.
+pub fn search_index(loaded_module: &LoadedModule) -> Vec<SearchEntry> {
+    let mut entries = Vec::new();
+
+    for (module_id, module) in loaded_module.docs_by_module.iter() {
+        // Naming type variables mutates the subs, so work on a copy.
+        let mut opt_subs = loaded_module
+            .exposed_types_by_module
+            .get(module_id)
+            .map(|exposed_types| exposed_types.storage_subs.as_inner().clone());
+
+        for entry in module.entries.iter() {
+            let doc_def = match entry {
+                DocEntry::DocDef(doc_def) if module.exposed_symbols.contains(&doc_def.symbol) => {
+                    doc_def
+                }
+                _ => continue,
+            };
+
+            match &doc_def.type_annotation {
+                TypeAnnotation::Ability { members } => {
+                    entries.push(search_entry(
+                        &module.name,
+                        &doc_def.name,
+                        &doc_def.name,
+                        SearchEntryKind::Ability,
+                        type_header(doc_def),
+                    ));
+
+                    for member in members {
+                        let symbol = loaded_module
+                            .interns
+                            .all_ident_ids
+                            .get(module_id)
+                            .and_then(|ident_ids| ident_ids.get_id(&member.name))
+                            .map(|ident_id| Symbol::new(*module_id, ident_id));
+                        let type_signature = symbol
+                            .and_then(|symbol| {
+                                inferred_type(loaded_module, *module_id, &mut opt_subs, symbol)
+                            })
+                            .unwrap_or_else(|| annotation_to_string(&member.type_annotation));
+
+                        // Members are documented along with their ability.
+                        entries.push(search_entry(
+                            &module.name,
+                            &member.name,
+                            &doc_def.name,
+                            SearchEntryKind::Value,
+                            type_signature,
+                        ));
+                    }
+                }
+                _ if is_type_name(&doc_def.name) => {
+                    let mut type_signature = type_header(doc_def);
+
+                    if !matches!(doc_def.type_annotation, TypeAnnotation::NoTypeAnn) {
+                        type_signature.push_str(" : ");
+                        type_signature.push_str(&annotation_to_string(&doc_def.type_annotation));
+                    }
+
+                    entries.push(search_entry(
+                        &module.name,
+                        &doc_def.name,
+                        &doc_def.name,
+                        SearchEntryKind::Type,
+                        type_signature,
+                    ));
+                }
+                type_annotation => {
+                    let type_signature =
+                        inferred_type(loaded_module, *module_id, &mut opt_subs, doc_def.symbol)
+                            .unwrap_or_else(|| annotation_to_string(type_annotation));
+
+                    entries.push(search_entry(
+                        &module.name,
+                        &doc_def.name,
+                        &doc_def.name,
+                        SearchEntryKind::Value,
+                        type_signature,
+                    ));
+                }
+            }
+        }
+    }
+
+    entries
+}
+
+fn search_entry(
+    module_name: &str,
+    name: &str,
+    anchor: &str,
+    kind: SearchEntryKind,
+    type_signature: String,
+) -> SearchEntry {
+    SearchEntry {
+        module_name: module_name.to_string(),
+        name: name.to_string(),
+        kind,
+        type_shape: type_shape(&type_signature),
+        type_signature,
+        url: format!("{}#{anchor}", module_link_url(module_name)),
+    }
+}
+
+fn is_type_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_uppercase())
+}
+
+/// e.g. `Dict k v`
+fn type_header(doc_def: &DocDef) -> String {
+    let mut buf = doc_def.name.clone();
+
+    for type_var in doc_def.type_vars.iter() {
+        buf.push(' ');
+        buf.push_str(type_var);
+    }
+
+    buf
+}
+
+/// The type the compiler inferred for this exposed symbol, if it was recorded.
+fn inferred_type(
+    loaded_module: &LoadedModule,
+    home: ModuleId,
+    opt_subs: &mut Option<Subs>,
+    symbol: Symbol,
+) -> Option<String> {
+    let subs = opt_subs.as_mut()?;
+    let var = *loaded_module
+        .exposed_types_by_module
+        .get(&home)?
+        .stored_vars_by_symbol
+        .get(&symbol)?;
+
+    Some(name_and_print_var(
+        var,
+        subs,
+        home,
+        &loaded_module.interns,
+        DebugPrint::NOTHING,
+    ))
+}
+
+/// The annotation as it would be rendered in the docs, but on one line.
+fn annotation_to_string(type_ann: &TypeAnnotation) -> String {
+    let mut buf = String::new();
+
+    type_annotation_to_html(0, &mut buf, type_ann, false);
+
+    buf.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Normalize a type signature so that signatures which only differ in the names of their type
+/// variables, their ability constraints or their spacing are equal. Type variables are renamed to
+/// `a`, `b`, `c`, ... in the order they first appear.
+///
+/// `search.js` normalizes queries the same way, so keep the two in sync!
+pub fn type_shape(type_signature: &str) -> String {
+    // Ability constraints are written after a `|`, e.g. `a -> U64 | a has Hash`
+    let signature = match type_signature.split_once(" | ") {
+        Some((before_constraints, _)) => before_constraints,
+        None => type_signature,
+    };
+    let mut var_names: Vec<&str> = Vec::new();
+    let mut shape = String::with_capacity(signature.len());
+    let mut rest = signature.trim();
+
+    while let Some(c) = rest.chars().next() {
+        if c.is_alphanumeric() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            // Record field names look like type variables, but come right before a `:` or `?`
+            let is_field_name = rest[end..].trim_start().starts_with(&[':', '?'][..]);
+
+            if word.starts_with(|c: char| c.is_lowercase()) && !word.contains('.') && !is_field_name
+            {
+                let index = match var_names.iter().position(|name| *name == word) {
+                    Some(index) => index,
+                    None => {
+                        var_names.push(word);
+                        var_names.len() - 1
+                    }
+                };
+
+                shape.push_str(&type_var_name(index));
+            } else {
+                shape.push_str(word);
+            }
+
+            rest = &rest[end..];
+        } else if c.is_whitespace() {
+            rest = rest.trim_start();
+
+            // Collapse runs of whitespace into one space, and drop it next to punctuation
+            // so that e.g. `( a -> b )` and `(a -> b)` are the same.
+            let prev_is_word = shape.ends_with(|c: char| c.is_alphanumeric() || c == '_');
+            let next_is_word = rest.starts_with(|c: char| c.is_alphanumeric() || c == '_');
+
+            if prev_is_word && next_is_word {
+                shape.push(' ');
+            }
+        } else {
+            shape.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    shape
+}
+
+/// `a`, `b`, ..., `z`, `a1`, `b1`, ...
+fn type_var_name(index: usize) -> String {
+    let letter = (b'a' + (index % 26) as u8) as char;
+
+    match index / 26 {
+        0 => letter.to_string(),
+        round => format!("{letter}{round}"),
+    }
+}
+
+/// The index as a JavaScript file that sets `window.ROC_SEARCH_INDEX`, so it can be loaded with
+/// a plain `<script>` tag (the docs may be viewed straight from the filesystem, where `fetch`
+/// isn't allowed).
+pub fn search_index_to_js(entries: &[SearchEntry]) -> String {
+    let mut buf = String::from("window.ROC_SEARCH_INDEX = [\n");
+
+    for entry in entries {
+        buf.push_str("  {");
+        push_json_field(&mut buf, "module", &entry.module_name);
+        buf.push_str(", ");
+        push_json_field(&mut buf, "name", &entry.name);
+        buf.push_str(", ");
+        push_json_field(&mut buf, "kind", entry.kind.as_str());
+        buf.push_str(", ");
+        push_json_field(&mut buf, "signature", &entry.type_signature);
+        buf.push_str(", ");
+        push_json_field(&mut buf, "shape", &entry.type_shape);
+        buf.push_str(", ");
+        push_json_field(&mut buf, "url", &entry.url);
+        buf.push_str("},\n");
+    }
+
+    buf.push_str("];\n");
+
+    buf
+}
+
+fn push_json_field(buf: &mut String, key: &str, value: &str) {
+    buf.push('"');
+    buf.push_str(key);
+    buf.push_str("\": \"");
+
+    for c in value.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            // Keep the index from closing the <script> tag it's in
+            '<' => buf.push_str("\\u003c"),
+            c if c.is_control() => buf.push_str(&format!("\\u{:04x}", c as u32)),
+            c => buf.push(c),
+        }
+    }
+
+    buf.push('"');
+}
@@ -1,8 +1,116 @@
 (() => {
   let sidebar = document.getElementById("sidebar-nav");
   let searchBox = document.getElementById("module-search");
+  let searchResults = document.getElementById("search-results");
+  let searchIndex = window.ROC_SEARCH_INDEX || [];
+
+  const MAX_SEARCH_RESULTS = 50;
+
+  // `a`, `b`, ..., `z`, `a1`, `b1`, ...
+  function typeVarName(index) {
+    let letter = String.fromCharCode(97 + (index % 26));
+    let round = Math.floor(index / 26);
+
+    return round === 0 ? letter : letter + round;
+  }
+
+  // Rename type variables in order of appearance, and drop ability constraints and extra
+  // whitespace, so that e.g. `List x, (x -> y) -> List y` matches `List.map`.
+  //
+  // This must stay in sync with `type_shape` in search.rs, which normalizes the index!
+  function typeShape(signature) {
+    let [beforeConstraints] = signature.split(" | ");
+    let tokens = beforeConstraints.trim().match(/[\p{L}\p{N}_][\p{L}\p{N}_.]*|\s+|./gu) || [];
+    let isWordChar = (char) => /[\p{L}\p{N}_]/u.test(char);
+    let varNames = [];
+    let shape = "";
+
+    tokens.forEach((token, index) => {
+      if (/^\s/.test(token)) {
+        let next = tokens[index + 1] || "";
+
+        if (isWordChar(shape.slice(-1)) && isWordChar(next.charAt(0))) {
+          shape += " ";
+        }
+      } else if (isWordChar(token.charAt(0))) {
+        // Record field names look like type variables, but come right before a `:` or `?`
+        let nextNonSpace = tokens.slice(index + 1).find((t) => !/^\s/.test(t));
+        let isFieldName = nextNonSpace === ":" || nextNonSpace === "?";
+
+        if (/^\p{Ll}/u.test(token) && !token.includes(".") && !isFieldName) {
+          let varIndex = varNames.indexOf(token);
+
+          if (varIndex === -1) {
+            varNames.push(token);
+            varIndex = varNames.length - 1;
+          }
+
+          shape += typeVarName(varIndex);
+        } else {
+          shape += token;
+        }
+      } else {
+        shape += token;
+      }
+    });
+
+    return shape;
+  }
+
+  // Queries containing `->`, or starting with `:`, are searches by type, Hoogle-style.
+  function isTypeQuery(query) {
+    return query.includes("->") || query.startsWith(":");
+  }
+
+  function searchIndexMatches(query) {
+    if (isTypeQuery(query)) {
+      let shape = typeShape(query.replace(/^:/, ""));
+
+      // Exact shape matches first, then signatures which contain the shape (e.g. a
+      // function that takes more arguments).
+      let exact = searchIndex.filter((entry) => entry.shape === shape);
+      let partial = searchIndex.filter((entry) => entry.shape !== shape && entry.shape.includes(shape));
+
+      return exact.concat(partial);
+    } else {
+      let text = query.toLowerCase();
+
+      return searchIndex.filter((entry) =>
+        `${entry.module}.${entry.name}`.toLowerCase().includes(text) ||
+        entry.signature.toLowerCase().includes(text)
+      );
+    }
+  }
+
+  function renderSearchResults(query) {
+    searchResults.replaceChildren();
+
+    if (query === "") {
+      searchResults.classList.add("hidden");
+      return;
+    }
+
+    searchIndexMatches(query).slice(0, MAX_SEARCH_RESULTS).forEach((entry) => {
+      let item = document.createElement("li");
+      let link = document.createElement("a");
+      let signature = document.createElement("span");
+
+      link.href = entry.url;
+      link.textContent = `${entry.module}.${entry.name}`;
+      signature.classList.add("search-result-signature");
+      signature.textContent = entry.kind === "value" ? `: ${entry.signature}` : entry.signature;
+
+      item.appendChild(link);
+      item.appendChild(signature);
+      searchResults.appendChild(item);
+    });
+
+    searchResults.classList.toggle("hidden", searchResults.childElementCount === 0);
+  }
 
   function search() {
+    renderSearchResults(searchBox.value.trim());
+
     let text = searchBox.value.toLowerCase(); // Search is case-insensitive.
 
     if (text === "") {
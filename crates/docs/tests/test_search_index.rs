@@ -0,0 +1,75 @@
+#[macro_use]
+extern crate pretty_assertions;
+
+#[cfg(test)]
+mod test_search_index {
+    use roc_docs::load_module_for_docs;
+    use roc_docs::search::{search_index, type_shape, SearchEntry, SearchEntryKind};
+    use std::path::PathBuf;
+
+    fn builtins_search_index() -> Vec<SearchEntry> {
+        let main_roc =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../compiler/builtins/roc/main.roc");
+
+        search_index(&load_module_for_docs(main_roc))
+    }
+
+    fn find<'a>(index: &'a [SearchEntry], module_name: &str, name: &str) -> &'a SearchEntry {
+        index
+            .iter()
+            .find(|entry| entry.module_name == module_name && entry.name == name)
+            .unwrap_or_else(|| panic!("{}.{} is not in the search index", module_name, name))
+    }
+
+    #[test]
+    fn type_shapes() {
+        assert_eq!(
+            type_shape("List x, (x -> y) -> List y"),
+            type_shape("List a, (a -> b) -> List b")
+        );
+        assert_eq!(
+            type_shape("hasher, a -> hasher | a has Hash, hasher has Hasher"),
+            "a,b->a"
+        );
+        assert_eq!(type_shape("{ name : Str }a -> a"), "{name:Str}a->a");
+    }
+
+    #[test]
+    fn builtins_values_types_and_abilities() {
+        let index = builtins_search_index();
+
+        let map = find(&index, "List", "map");
+        assert_eq!(map.kind, SearchEntryKind::Value);
+        assert_eq!(map.type_shape, type_shape("List x, (x -> y) -> List y"));
+        assert_eq!(map.url, "/List#map");
+
+        let dict = find(&index, "Dict", "Dict");
+        assert_eq!(dict.kind, SearchEntryKind::Type);
+        assert_eq!(dict.type_signature, "Dict k v");
+
+        let hash_ability = index
+            .iter()
+            .find(|entry| entry.module_name == "Hash" && entry.kind == SearchEntryKind::Ability)
+            .unwrap();
+        assert_eq!(hash_ability.name, "Hash");
+
+        // Ability members are searchable like any other value, and link to their ability
+        let hash = find(&index, "Hash", "hash");
+        assert_eq!(hash.kind, SearchEntryKind::Value);
+        assert_eq!(hash.type_shape, "a,b->a");
+        assert_eq!(hash.url, "/Hash#Hash");
+    }
+
+    #[test]
+    fn builtins_search_by_type_shape() {
+        let index = builtins_search_index();
+        let shape = type_shape("Str, Str -> Str");
+        let matches: Vec<String> = index
+            .iter()
+            .filter(|entry| entry.type_shape == shape)
+            .map(|entry| format!("{}.{}", entry.module_name, entry.name))
+            .collect();
+
+        assert!(matches.contains(&"Str.concat".to_string()), "{:?}", matches);
+    }
+}
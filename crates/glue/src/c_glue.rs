@@ -0,0 +1,920 @@
+//! Generates a C header for platform hosts, from the same [Types] the Rust glue uses.
+use crate::types::{
+    File, RocFn, RocNum, RocSingleTagPayload, RocStructFields, RocTagUnion, RocType, TypeId, Types,
+};
+use indexmap::IndexMap;
+use roc_target::{Architecture, TargetInfo};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+pub static HEADER: &[u8] = include_bytes!("../templates/header.h");
+const INDENT: &str = "    ";
+
+/// For each declared name, every distinct body it has, along with the targets which use that body.
+/// Keeping all the bodies for one name together means each `#if`/`#elif` chain appears at the
+/// position of the first one, so declarations stay ahead of whatever refers to them.
+type DeclsByName = IndexMap<String, IndexMap<String, Vec<TargetInfo>>>;
+
+#[derive(Default)]
+struct Decls {
+    /// The handles of recursive tag unions. These only hold a pointer, so they can go before
+    /// everything else, which lets the unions' payloads refer back to them.
+    forward: DeclsByName,
+    rest: DeclsByName,
+}
+
+fn add_decl(decls: &mut DeclsByName, name: &str, target_info: TargetInfo, body: String) {
+    let targets = decls
+        .entry(name.to_string())
+        .or_default()
+        .entry(body)
+        .or_default();
+
+    if !targets.contains(&target_info) {
+        targets.push(target_info);
+    }
+}
+
+pub fn emit(types: &[Types]) -> Vec<File> {
+    let mut buf = std::str::from_utf8(HEADER).unwrap().to_string();
+    let mut decls = Decls::default();
+
+    for types in types {
+        for id in declaration_order(types) {
+            add_type(types.target(), id, types, &mut decls);
+        }
+    }
+
+    write_decls(&mut buf, decls.forward, types.len());
+    write_decls(&mut buf, decls.rest, types.len());
+
+    vec![File {
+        name: "roc_app.h".to_string(),
+        content: buf,
+    }]
+}
+
+/// Unlike Rust, C needs a type to be declared before anything which holds it by value,
+/// so this puts every type after the fields, payloads, and arguments it's made of.
+fn declaration_order(types: &Types) -> Vec<TypeId> {
+    fn visit(id: TypeId, types: &Types, visited: &mut HashSet<TypeId>, order: &mut Vec<TypeId>) {
+        if !visited.insert(id) {
+            return;
+        }
+
+        for dep in value_deps(types.get_type(id)) {
+            visit(dep, types, visited, order);
+        }
+
+        order.push(id);
+    }
+
+    let mut visited = HashSet::default();
+    let mut order = Vec::with_capacity(types.ids().len());
+
+    for id in types.sorted_ids() {
+        visit(id, types, &mut visited, &mut order);
+    }
+
+    order
+}
+
+/// The types which need to be declared before this one. Collections and boxes are
+/// declared generically in the header, and recursive pointers go through the forward
+/// declarations, so neither of those count.
+fn value_deps(roc_type: &RocType) -> Vec<TypeId> {
+    match roc_type {
+        RocType::Struct { fields, .. } | RocType::TagUnionPayload { fields, .. } => match fields {
+            RocStructFields::HasNoClosure { fields } => fields.iter().map(|(_, id)| *id).collect(),
+            RocStructFields::HasClosure { fields } => fields.iter().map(|(_, id, _)| *id).collect(),
+        },
+        RocType::TagUnion(tag_union) => match tag_union {
+            RocTagUnion::NonRecursive { tags, .. }
+            | RocTagUnion::Recursive { tags, .. }
+            | RocTagUnion::NullableWrapped { tags, .. } => {
+                tags.iter().filter_map(|(_, payload)| *payload).collect()
+            }
+            RocTagUnion::NullableUnwrapped {
+                non_null_payload, ..
+            } => vec![*non_null_payload],
+            RocTagUnion::NonNullableUnwrapped { payload, .. } => vec![*payload],
+            RocTagUnion::SingleTagStruct { payload, .. } => match payload {
+                RocSingleTagPayload::HasNoClosure { payload_fields } => payload_fields.clone(),
+                RocSingleTagPayload::HasClosure { payload_getters } => {
+                    payload_getters.iter().map(|(id, _)| *id).collect()
+                }
+            },
+            RocTagUnion::Enumeration { .. } => Vec::new(),
+        },
+        RocType::RocResult(ok_id, err_id) => vec![*ok_id, *err_id],
+        RocType::Function(RocFn {
+            args,
+            lambda_set,
+            ret,
+            ..
+        }) => {
+            let mut deps = args.clone();
+
+            deps.push(*lambda_set);
+            deps.push(*ret);
+
+            deps
+        }
+        RocType::RocStr
+        | RocType::Bool
+        | RocType::Num(_)
+        | RocType::RocList(_)
+        | RocType::RocDict(_, _)
+        | RocType::RocSet(_)
+        | RocType::RocBox(_)
+        | RocType::EmptyTagUnion
+        | RocType::RecursivePointer(_)
+        | RocType::Unit
+        | RocType::Unsized => Vec::new(),
+    }
+}
+
+fn write_decls(buf: &mut String, decls: DeclsByName, target_count: usize) {
+    for (_, bodies) in decls {
+        buf.push('\n');
+
+        // Declarations which are the same on every target don't need to be guarded.
+        if let Some((body, targets)) = bodies.first() {
+            if bodies.len() == 1 && targets.len() == target_count {
+                buf.push_str(body);
+                buf.push('\n');
+
+                continue;
+            }
+        }
+
+        for (index, (body, targets)) in bodies.into_iter().enumerate() {
+            let directive = if index == 0 { "#if" } else { "#elif" };
+            let conditions: Vec<_> = targets
+                .iter()
+                .map(|target_info| format!("defined({})", arch_macro(target_info.architecture)))
+                .collect();
+
+            writeln!(buf, "{directive} {}", conditions.join(" || ")).unwrap();
+            buf.push_str(&body);
+            buf.push('\n');
+        }
+
+        buf.push_str("#endif\n");
+    }
+}
+
+fn add_type(target_info: TargetInfo, id: TypeId, types: &Types, decls: &mut Decls) {
+    match types.get_type(id) {
+        RocType::Struct { name, fields } => {
+            add_struct(name, target_info, fields, id, types, decls, false)
+        }
+        RocType::TagUnionPayload { name, fields } => {
+            add_struct(name, target_info, fields, id, types, decls, true)
+        }
+        RocType::TagUnion(tag_union) => match tag_union {
+            RocTagUnion::Enumeration { name, tags, size } => {
+                add_enumeration(name, target_info, tags, *size, decls)
+            }
+            RocTagUnion::NonRecursive {
+                name,
+                tags,
+                discriminant_size,
+                discriminant_offset,
+            } => {
+                // Empty tag unions can never come up at runtime,
+                // and so don't need declared types.
+                if !tags.is_empty() {
+                    add_non_recursive(
+                        name,
+                        target_info,
+                        id,
+                        tags,
+                        *discriminant_size,
+                        *discriminant_offset,
+                        types,
+                        decls,
+                    );
+                }
+            }
+            RocTagUnion::Recursive {
+                name,
+                tags,
+                discriminant_size,
+                discriminant_offset,
+            } => {
+                if !tags.is_empty() {
+                    add_recursive(
+                        name,
+                        target_info,
+                        tags,
+                        None,
+                        *discriminant_size,
+                        *discriminant_offset,
+                        types,
+                        decls,
+                    );
+                }
+            }
+            RocTagUnion::NullableWrapped {
+                name,
+                index_of_null_tag,
+                tags,
+                discriminant_size,
+                discriminant_offset,
+            } => add_recursive(
+                name,
+                target_info,
+                tags,
+                Some(*index_of_null_tag as usize),
+                *discriminant_size,
+                *discriminant_offset,
+                types,
+                decls,
+            ),
+            RocTagUnion::NullableUnwrapped {
+                name,
+                null_tag,
+                non_null_tag,
+                non_null_payload,
+                null_represents_first_tag: _,
+            } => add_unwrapped(
+                name,
+                target_info,
+                Some(null_tag),
+                non_null_tag,
+                *non_null_payload,
+                types,
+                decls,
+            ),
+            RocTagUnion::NonNullableUnwrapped {
+                name,
+                tag_name,
+                payload,
+            } => add_unwrapped(name, target_info, None, tag_name, *payload, types, decls),
+            RocTagUnion::SingleTagStruct {
+                name,
+                tag_name: _,
+                payload,
+            } => add_single_tag_struct(name, target_info, id, payload, types, decls),
+        },
+        RocType::RocResult(ok_id, err_id) => {
+            add_result(target_info, id, *ok_id, *err_id, types, decls)
+        }
+        RocType::Function(roc_fn) => add_function(target_info, roc_fn, types, decls),
+        // These are either declared in the header, or don't need to be declared in C.
+        RocType::Unit
+        | RocType::EmptyTagUnion
+        | RocType::Num(_)
+        | RocType::Bool
+        | RocType::RocStr
+        | RocType::RocDict(_, _)
+        | RocType::RocSet(_)
+        | RocType::RocList(_)
+        | RocType::RocBox(_)
+        | RocType::Unsized => {}
+        RocType::RecursivePointer { .. } => {
+            // This is recursively pointing to a type that should already have been added,
+            // so no extra work needs to happen.
+        }
+    }
+}
+
+/// C can't check that it lays a type out the way Roc does, but it can at least check the size.
+fn write_size_assertion(buf: &mut String, name: &str, size: u32) {
+    write!(
+        buf,
+        "\n\n_Static_assert(sizeof({name}) == {size}, \"{name} should be {size} bytes\");"
+    )
+    .unwrap();
+}
+
+fn add_struct(
+    name: &str,
+    target_info: TargetInfo,
+    fields: &RocStructFields,
+    struct_id: TypeId,
+    types: &Types,
+    decls: &mut Decls,
+    is_tag_union_payload: bool,
+) {
+    let name = escape_kw(name.to_string());
+    let mut buf = format!("typedef struct {name} {{\n");
+
+    let field_ids: Vec<(&String, TypeId)> = match fields {
+        RocStructFields::HasNoClosure { fields } => {
+            fields.iter().map(|(label, id)| (label, *id)).collect()
+        }
+        RocStructFields::HasClosure { fields } => {
+            fields.iter().map(|(label, id, _)| (label, *id)).collect()
+        }
+    };
+
+    for (label, type_id) in field_ids {
+        // Tag union payloads have numbered fields, so we prefix them
+        // with an "f" because C doesn't allow struct fields to be numbers.
+        let label = if is_tag_union_payload {
+            format!("f{label}")
+        } else {
+            escape_kw(label.to_string())
+        };
+
+        writeln!(buf, "{INDENT}{} {label};", type_name(type_id, types)).unwrap();
+    }
+
+    write!(buf, "}} {name};").unwrap();
+
+    // If there's a closure in there, only the app knows how big this is. Payloads are checked
+    // as part of their tag union instead, since the size recorded for a recursive union's
+    // payload is the size of the pointer to it.
+    if !is_tag_union_payload && matches!(fields, RocStructFields::HasNoClosure { .. }) {
+        write_size_assertion(&mut buf, &name, types.size_rounded_to_alignment(struct_id));
+    }
+
+    add_decl(&mut decls.rest, &name, target_info, buf);
+}
+
+fn add_enumeration<S: AsRef<str>>(
+    name: &str,
+    target_info: TargetInfo,
+    tags: &[S],
+    tag_bytes: u32,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+    let repr_bits = tag_bytes * 8;
+
+    // C enums are always int-sized, so the type is an integer of the right size, and the
+    // tags are constants of that type.
+    let mut buf = format!("typedef uint{repr_bits}_t {name};\n\nenum {{\n");
+
+    for (index, tag_name) in tags.iter().enumerate() {
+        writeln!(buf, "{INDENT}{name}_{} = {index},", tag_name.as_ref()).unwrap();
+    }
+
+    buf.push_str("};");
+
+    add_decl(&mut decls.rest, &name, target_info, buf);
+}
+
+/// Declares the enumeration of a tag union's tags, and returns its name.
+fn add_discriminant(
+    name: &str,
+    target_info: TargetInfo,
+    tag_names: &[&String],
+    size: u32,
+    decls: &mut Decls,
+) -> String {
+    let discriminant_name = format!("discriminant_{name}");
+
+    add_enumeration(&discriminant_name, target_info, tag_names, size, decls);
+
+    discriminant_name
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_non_recursive(
+    name: &str,
+    target_info: TargetInfo,
+    type_id: TypeId,
+    tags: &[(String, Option<TypeId>)],
+    discriminant_size: u32,
+    discriminant_offset: u32,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+    let tag_names: Vec<_> = tags.iter().map(|(tag_name, _)| tag_name).collect();
+    let discriminant_name =
+        add_discriminant(&name, target_info, &tag_names, discriminant_size, decls);
+    let size = types.size_rounded_to_alignment(type_id);
+
+    // Roc puts the discriminant in the padding after the largest payload, rather than in a
+    // separate field (which could make the whole thing bigger), so this is a union that gets
+    // its discriminant read and written at the right offset.
+    let mut buf = format!("typedef union {name} {{\n");
+
+    for (tag_name, opt_payload_id) in tags {
+        if let Some(payload_id) = opt_payload_id {
+            writeln!(buf, "{INDENT}{} {tag_name};", type_name(*payload_id, types)).unwrap();
+        }
+    }
+
+    // Make sure there's room for the discriminant, even if none of the payloads have
+    // padding at the end for it to go in.
+    writeln!(buf, "{INDENT}uint8_t _sizer[{size}];").unwrap();
+
+    write!(buf, "}} {name};").unwrap();
+    write_size_assertion(&mut buf, &name, size);
+
+    write!(
+        buf,
+        r#"
+
+static inline {discriminant_name} {name}_discriminant(const {name} *self) {{
+    {discriminant_name} discriminant;
+    memcpy(&discriminant, (const uint8_t *)self + {discriminant_offset}, sizeof(discriminant));
+    return discriminant;
+}}
+
+static inline void {name}_set_discriminant({name} *self, {discriminant_name} discriminant) {{
+    memcpy((uint8_t *)self + {discriminant_offset}, &discriminant, sizeof(discriminant));
+}}"#
+    )
+    .unwrap();
+
+    for (tag_name, opt_payload_id) in tags {
+        match opt_payload_id {
+            Some(payload_id) => {
+                let payload_type = type_name(*payload_id, types);
+
+                write!(
+                    buf,
+                    r#"
+
+static inline {name} {name}_new_{tag_name}({payload_type} payload) {{
+    {name} self;
+    self.{tag_name} = payload;
+    {name}_set_discriminant(&self, {discriminant_name}_{tag_name});
+    return self;
+}}
+
+static inline const {payload_type} *{name}_get_{tag_name}(const {name} *self) {{
+    return &self->{tag_name};
+}}"#
+                )
+                .unwrap();
+            }
+            None => {
+                write!(
+                    buf,
+                    r#"
+
+static inline {name} {name}_new_{tag_name}(void) {{
+    {name} self;
+    memset(&self, 0, sizeof(self));
+    {name}_set_discriminant(&self, {discriminant_name}_{tag_name});
+    return self;
+}}"#
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    add_decl(&mut decls.rest, &name, target_info, buf);
+}
+
+/// Writes the start of a constructor for a recursive tag union: allocating room for the payload
+/// (with a reference count of 1 in front of it) and pointing `data` at it.
+fn write_allocation(buf: &mut String, data_type: &str) {
+    // The reference count goes right before the data, and the allocation is aligned
+    // for whichever of the two needs it more.
+    write!(
+        buf,
+        r#"
+    size_t alignment = _Alignof({data_type}) > sizeof(intptr_t) ? _Alignof({data_type}) : sizeof(intptr_t);
+    uint8_t *allocation = roc_alloc(sizeof({data_type}) + alignment, alignment);
+    {data_type} *data = ({data_type} *)(allocation + alignment);
+    // A reference count of 1
+    ((intptr_t *)data)[-1] = INTPTR_MIN;"#
+    )
+    .unwrap();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_recursive(
+    name: &str,
+    target_info: TargetInfo,
+    tags: &[(String, Option<TypeId>)],
+    null_tag_index: Option<usize>, // used only in the nullable-wrapped case
+    discriminant_size: u32,
+    discriminant_offset: u32,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+    let union_name = format!("union_{name}");
+
+    add_decl(
+        &mut decls.forward,
+        &name,
+        target_info,
+        format!("typedef struct {name} {{\n{INDENT}union {union_name} *pointer;\n}} {name};"),
+    );
+
+    let tag_names: Vec<_> = tags.iter().map(|(tag_name, _)| tag_name).collect();
+    let discriminant_name =
+        add_discriminant(&name, target_info, &tag_names, discriminant_size, decls);
+
+    let mut buf = format!("typedef union {union_name} {{\n");
+
+    for (tag_name, opt_payload_id) in tags {
+        if let Some(payload_id) = opt_payload_id {
+            writeln!(buf, "{INDENT}{} {tag_name};", type_name(*payload_id, types)).unwrap();
+        }
+    }
+
+    // The null tag doesn't need a discriminant, so it doesn't count here.
+    let non_null_tags = tags.len() - null_tag_index.map_or(0, |_| 1);
+
+    // If there are few enough tags, the discriminant goes in the unused bits of the
+    // (always aligned) pointer; otherwise it goes after the payload, so make room for it.
+    let bitmask = if non_null_tags < target_info.ptr_size() {
+        Some(target_info.ptr_size() - 1)
+    } else {
+        writeln!(
+            buf,
+            "{INDENT}uint8_t _sizer[{}];",
+            discriminant_offset + discriminant_size
+        )
+        .unwrap();
+
+        None
+    };
+
+    write!(buf, "}} {union_name};").unwrap();
+
+    match bitmask {
+        Some(bitmask) => write!(
+            buf,
+            r#"
+
+static inline {union_name} *{name}_union_pointer(const {name} *self) {{
+    return ({union_name} *)((uintptr_t)self->pointer & ~(uintptr_t){bitmask:#x});
+}}"#
+        ),
+        None => write!(
+            buf,
+            r#"
+
+static inline {union_name} *{name}_union_pointer(const {name} *self) {{
+    return self->pointer;
+}}"#
+        ),
+    }
+    .unwrap();
+
+    write!(
+        buf,
+        "\n\nstatic inline {discriminant_name} {name}_discriminant(const {name} *self) {{"
+    )
+    .unwrap();
+
+    if let Some(index) = null_tag_index {
+        write!(
+            buf,
+            r#"
+    if (self->pointer == NULL) {{
+        return {discriminant_name}_{};
+    }}
+"#,
+            tags[index].0
+        )
+        .unwrap();
+    }
+
+    match bitmask {
+        Some(bitmask) => write!(
+            buf,
+            r#"
+    return ({discriminant_name})((uintptr_t)self->pointer & {bitmask:#x});
+}}"#
+        ),
+        None => write!(
+            buf,
+            r#"
+    {discriminant_name} discriminant;
+    memcpy(&discriminant, (const uint8_t *)self->pointer + {discriminant_offset}, sizeof(discriminant));
+    return discriminant;
+}}"#
+        ),
+    }
+    .unwrap();
+
+    for (index, (tag_name, opt_payload_id)) in tags.iter().enumerate() {
+        if Some(index) == null_tag_index {
+            write!(
+                buf,
+                r#"
+
+static inline {name} {name}_new_{tag_name}(void) {{
+    {name} self = {{ NULL }};
+    return self;
+}}"#
+            )
+            .unwrap();
+
+            continue;
+        }
+
+        match opt_payload_id {
+            Some(payload_id) => write!(
+                buf,
+                "\n\nstatic inline {name} {name}_new_{tag_name}({} payload) {{",
+                type_name(*payload_id, types)
+            ),
+            None => write!(
+                buf,
+                "\n\nstatic inline {name} {name}_new_{tag_name}(void) {{"
+            ),
+        }
+        .unwrap();
+
+        write_allocation(&mut buf, &union_name);
+
+        if opt_payload_id.is_some() {
+            write!(buf, "\n{INDENT}data->{tag_name} = payload;").unwrap();
+        }
+
+        match bitmask {
+            Some(_) => write!(
+                buf,
+                r#"
+    {name} self = {{ ({union_name} *)((uintptr_t)data | {discriminant_name}_{tag_name}) }};
+    return self;
+}}"#
+            ),
+            None => write!(
+                buf,
+                r#"
+    {discriminant_name} discriminant = {discriminant_name}_{tag_name};
+    memcpy((uint8_t *)data + {discriminant_offset}, &discriminant, sizeof(discriminant));
+    {name} self = {{ data }};
+    return self;
+}}"#
+            ),
+        }
+        .unwrap();
+
+        if let Some(payload_id) = opt_payload_id {
+            write!(
+                buf,
+                r#"
+
+static inline const {} *{name}_get_{tag_name}(const {name} *self) {{
+    return &{name}_union_pointer(self)->{tag_name};
+}}"#,
+                type_name(*payload_id, types)
+            )
+            .unwrap();
+        }
+    }
+
+    add_decl(&mut decls.rest, &union_name, target_info, buf);
+}
+
+/// A recursive tag union which doesn't need a discriminant, because it either has just one
+/// tag, or two tags where one of them is represented by a null pointer.
+fn add_unwrapped(
+    name: &str,
+    target_info: TargetInfo,
+    null_tag: Option<&String>,
+    non_null_tag: &str,
+    payload_id: TypeId,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+    let payload_type = type_name(payload_id, types);
+
+    // The payload is always a struct, which C lets us point to before it's been declared.
+    add_decl(
+        &mut decls.forward,
+        &name,
+        target_info,
+        format!("typedef struct {name} {{\n{INDENT}struct {payload_type} *pointer;\n}} {name};"),
+    );
+
+    let mut buf = String::new();
+
+    if let Some(null_tag) = null_tag {
+        let non_null_tag = non_null_tag.to_string();
+        let mut tag_names = vec![null_tag, &non_null_tag];
+
+        tag_names.sort();
+
+        let discriminant_name = add_discriminant(&name, target_info, &tag_names, 1, decls);
+
+        write!(
+            buf,
+            r#"static inline {discriminant_name} {name}_discriminant(const {name} *self) {{
+    return self->pointer == NULL ? {discriminant_name}_{null_tag} : {discriminant_name}_{non_null_tag};
+}}
+
+static inline {name} {name}_new_{null_tag}(void) {{
+    {name} self = {{ NULL }};
+    return self;
+}}
+
+"#
+        )
+        .unwrap();
+    }
+
+    write!(
+        buf,
+        "static inline {name} {name}_new_{non_null_tag}({payload_type} payload) {{"
+    )
+    .unwrap();
+
+    write_allocation(&mut buf, &payload_type);
+
+    write!(
+        buf,
+        r#"
+    *data = payload;
+    {name} self = {{ data }};
+    return self;
+}}
+
+static inline const {payload_type} *{name}_get_{non_null_tag}(const {name} *self) {{
+    return self->pointer;
+}}"#
+    )
+    .unwrap();
+
+    add_decl(&mut decls.rest, &name, target_info, buf);
+}
+
+fn add_single_tag_struct(
+    name: &str,
+    target_info: TargetInfo,
+    type_id: TypeId,
+    payload: &RocSingleTagPayload,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+
+    let field_ids: Vec<TypeId> = match payload {
+        RocSingleTagPayload::HasNoClosure { payload_fields } => payload_fields.clone(),
+        RocSingleTagPayload::HasClosure { payload_getters } => {
+            payload_getters.iter().map(|(id, _)| *id).collect()
+        }
+    };
+
+    let buf = if field_ids.is_empty() {
+        format!("typedef RocUnit {name};")
+    } else {
+        let mut buf = format!("typedef struct {name} {{\n");
+
+        for (index, type_id) in field_ids.into_iter().enumerate() {
+            writeln!(buf, "{INDENT}{} f{index};", type_name(type_id, types)).unwrap();
+        }
+
+        write!(buf, "}} {name};").unwrap();
+
+        if let RocSingleTagPayload::HasNoClosure { .. } = payload {
+            write_size_assertion(&mut buf, &name, types.size_rounded_to_alignment(type_id));
+        }
+
+        buf
+    };
+
+    add_decl(&mut decls.rest, &name, target_info, buf);
+}
+
+/// C has no generics, so each kind of `Result` gets its own struct.
+fn add_result(
+    target_info: TargetInfo,
+    id: TypeId,
+    ok_id: TypeId,
+    err_id: TypeId,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = type_name(id, types);
+    let buf = format!(
+        r#"typedef struct {name} {{
+    union {{
+        {} ok;
+        {} err;
+    }} payload;
+    bool is_ok;
+}} {name};"#,
+        type_name(ok_id, types),
+        type_name(err_id, types)
+    );
+
+    add_decl(&mut decls.rest, &name, target_info, buf);
+}
+
+fn add_function(target_info: TargetInfo, roc_fn: &RocFn, types: &Types, decls: &mut Decls) {
+    let name = escape_kw(roc_fn.function_name.to_string());
+    let extern_name = &roc_fn.extern_name;
+    let return_type_str = type_name(roc_fn.ret, types);
+    let mut buf = format!(
+        "typedef struct {name} {{\n{INDENT}{} closure_data;\n}} {name};\n\n",
+        type_name(roc_fn.lambda_set, types)
+    );
+
+    // void extern_name(const arg1_type *arg_1, ..., uint8_t *closure_data, return_type *output);
+    write!(buf, "extern void {extern_name}(").unwrap();
+
+    for (i, argument_type) in roc_fn.args.iter().enumerate() {
+        write!(buf, "const {} *arg_{i}, ", type_name(*argument_type, types)).unwrap();
+    }
+
+    writeln!(buf, "uint8_t *closure_data, {return_type_str} *output);\n").unwrap();
+
+    write!(
+        buf,
+        "static inline {return_type_str} {name}_force_thunk({name} *self"
+    )
+    .unwrap();
+
+    for (i, argument_type) in roc_fn.args.iter().enumerate() {
+        write!(buf, ", {} arg_{i}", type_name(*argument_type, types)).unwrap();
+    }
+
+    writeln!(buf, ") {{\n{INDENT}{return_type_str} output;").unwrap();
+    write!(buf, "{INDENT}{extern_name}(").unwrap();
+
+    for i in 0..roc_fn.args.len() {
+        write!(buf, "&arg_{i}, ").unwrap();
+    }
+
+    // Closures whose captures can vary in size hold them in a list of bytes.
+    let closure_data = match types.get_type(roc_fn.lambda_set) {
+        RocType::Unsized => "(uint8_t *)self->closure_data.elements",
+        _ => "(uint8_t *)&self->closure_data",
+    };
+
+    write!(buf, "{closure_data}, &output);\n{INDENT}return output;\n}}").unwrap();
+
+    add_decl(&mut decls.rest, &name, target_info, buf);
+}
+
+fn type_name(id: TypeId, types: &Types) -> String {
+    match types.get_type(id) {
+        RocType::Unit | RocType::EmptyTagUnion => "RocUnit".to_string(),
+        RocType::RocStr => "RocStr".to_string(),
+        RocType::Bool => "bool".to_string(),
+        RocType::Num(RocNum::U8) => "uint8_t".to_string(),
+        RocType::Num(RocNum::U16) => "uint16_t".to_string(),
+        RocType::Num(RocNum::U32) => "uint32_t".to_string(),
+        RocType::Num(RocNum::U64) => "uint64_t".to_string(),
+        RocType::Num(RocNum::U128) => "RocU128".to_string(),
+        RocType::Num(RocNum::I8) => "int8_t".to_string(),
+        RocType::Num(RocNum::I16) => "int16_t".to_string(),
+        RocType::Num(RocNum::I32) => "int32_t".to_string(),
+        RocType::Num(RocNum::I64) => "int64_t".to_string(),
+        RocType::Num(RocNum::I128) => "RocI128".to_string(),
+        RocType::Num(RocNum::F32) => "float".to_string(),
+        RocType::Num(RocNum::F64) => "double".to_string(),
+        RocType::Num(RocNum::Dec) => "RocDec".to_string(),
+        // C has no generics, so collections don't say what they contain.
+        RocType::RocDict(_, _) => "RocDict".to_string(),
+        RocType::RocSet(_) => "RocSet".to_string(),
+        RocType::RocList(_) | RocType::Unsized => "RocList".to_string(),
+        RocType::RocBox(_) => "RocBox".to_string(),
+        RocType::RocResult(ok_id, err_id) => {
+            format!(
+                "RocResult_{}_{}",
+                type_name(*ok_id, types),
+                type_name(*err_id, types)
+            )
+        }
+        RocType::Struct { name, .. }
+        | RocType::TagUnionPayload { name, .. }
+        | RocType::TagUnion(RocTagUnion::NonRecursive { name, .. })
+        | RocType::TagUnion(RocTagUnion::Recursive { name, .. })
+        | RocType::TagUnion(RocTagUnion::Enumeration { name, .. })
+        | RocType::TagUnion(RocTagUnion::NullableWrapped { name, .. })
+        | RocType::TagUnion(RocTagUnion::NullableUnwrapped { name, .. })
+        | RocType::TagUnion(RocTagUnion::NonNullableUnwrapped { name, .. })
+        | RocType::TagUnion(RocTagUnion::SingleTagStruct { name, .. }) => escape_kw(name.clone()),
+        RocType::RecursivePointer(content) => type_name(*content, types),
+        RocType::Function(RocFn { function_name, .. }) => escape_kw(function_name.clone()),
+    }
+}
+
+/// The macro that GCC and Clang define when compiling for this architecture.
+fn arch_macro(architecture: Architecture) -> &'static str {
+    match architecture {
+        Architecture::X86_64 => "__x86_64__",
+        Architecture::X86_32 => "__i386__",
+        Architecture::Aarch64 => "__aarch64__",
+        Architecture::Aarch32 => "__arm__",
+        Architecture::Wasm32 => "__wasm32__",
+    }
+}
+
+// Based on https://en.cppreference.com/w/c/keyword
+const RESERVED_KEYWORDS: &[&str] = &[
+    "auto", "break", "case", "char", "const", "continue", "default", "do", "double", "else",
+    "enum", "extern", "float", "for", "goto", "if", "inline", "int", "long", "register",
+    "restrict", "return", "short", "signed", "sizeof", "static", "struct", "switch", "typedef",
+    "union", "unsigned", "void", "volatile", "while", "bool", "true", "false",
+];
+
+/// Escape a C reserved keyword, if necessary.
+fn escape_kw(input: String) -> String {
+    if RESERVED_KEYWORDS.contains(&input.as_str()) {
+        // C has no raw identifiers, so the best we can do is add an underscore.
+        format!("{input}_")
+    } else {
+        input
+    }
+}
@@ -46,6 +46,19 @@ pub fn generate(
         IgnoreErrors::NONE,
     ) {
         Ok(types) => {
+            if let Some(builtin) = BuiltinGlue::from_spec_path(spec_path) {
+                for crate::types::File { name, content } in builtin.emit(&types) {
+                    write_glue_file(output_path, &name, &content);
+                }
+
+                println!(
+                    "🎉 Generated type declarations in:\n\n\t{}",
+                    output_path.display()
+                );
+
+                return Ok(0);
+            }
+
             // TODO: we should to modify the app file first before loading it.
             // Somehow it has to point to the correct platform file which may not exist on the target machine.
             let triple = Triple::host();
@@ -90,6 +103,7 @@ pub fn generate(
                     problems,
                     total_time,
                     expect_metadata: _,
+                    module_paths: _,
                 }) => {
                     // TODO: Should binary_path be update to deal with extensions?
                     use target_lexicon::OperatingSystem;
@@ -143,45 +157,7 @@ pub fn generate(
                         process::exit(1);
                     });
                     for roc_type::File { name, content } in &files {
-                        let valid_name = PathBuf::from(name.as_str())
-                            .components()
-                            .all(|comp| matches!(comp, Component::CurDir | Component::Normal(_)));
-                        if !valid_name {
-                            eprintln!("File name was invalid: {}", &name);
-
-                            process::exit(1);
-                        }
-                        let full_path = output_path.join(name.as_str());
-                        if let Some(dir_path) = full_path.parent() {
-                            std::fs::create_dir_all(dir_path).unwrap_or_else(|err| {
-                                eprintln!(
-                                    "Unable to create output directory {} - {:?}",
-                                    dir_path.display(),
-                                    err
-                                );
-
-                                process::exit(1);
-                            });
-                        }
-                        let mut file = File::create(&full_path).unwrap_or_else(|err| {
-                            eprintln!(
-                                "Unable to create output file {} - {:?}",
-                                full_path.display(),
-                                err
-                            );
-
-                            process::exit(1);
-                        });
-
-                        file.write_all(content.as_bytes()).unwrap_or_else(|err| {
-                            eprintln!(
-                                "Unable to write bindings to output file {} - {:?}",
-                                full_path.display(),
-                                err
-                            );
-
-                            process::exit(1);
-                        });
+                        write_glue_file(output_path, name.as_str(), content.as_str());
                     }
 
                     println!(
@@ -191,9 +167,13 @@ pub fn generate(
 
                     Ok(0)
                 }
-                Err(BuildFileError::ErrorModule { module, total_time }) => {
-                    handle_error_module(module, total_time, spec_path.as_os_str(), true)
-                }
+                Err(BuildFileError::ErrorModule { module, total_time }) => handle_error_module(
+                    module,
+                    total_time,
+                    spec_path.as_os_str(),
+                    true,
+                    RenderTarget::ColorTerminal,
+                ),
                 Err(BuildFileError::LoadingProblem(problem)) => handle_loading_problem(problem),
             }
         }
@@ -214,6 +194,75 @@ pub fn generate(
     }
 }
 
+/// A glue generator which is built into the compiler, rather than written in Roc.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuiltinGlue {
+    C,
+    Zig,
+}
+
+impl BuiltinGlue {
+    /// Built-in glue is picked by passing its bare name (`c` or `zig`) in place of a glue spec.
+    /// The bare name always means the built-in glue, even if the current directory has a spec
+    /// file by that name; to use such a file, give its path with a directory, like `./c`.
+    pub fn from_spec_path(spec_path: &Path) -> Option<Self> {
+        match spec_path.to_str() {
+            Some("c") => Some(BuiltinGlue::C),
+            Some("zig") => Some(BuiltinGlue::Zig),
+            _ => None,
+        }
+    }
+
+    pub fn emit(self, types: &[Types]) -> Vec<crate::types::File> {
+        match self {
+            BuiltinGlue::C => crate::c_glue::emit(types),
+            BuiltinGlue::Zig => crate::zig_glue::emit(types),
+        }
+    }
+}
+
+fn write_glue_file(output_path: &Path, name: &str, content: &str) {
+    let valid_name = PathBuf::from(name)
+        .components()
+        .all(|comp| matches!(comp, Component::CurDir | Component::Normal(_)));
+    if !valid_name {
+        eprintln!("File name was invalid: {}", &name);
+
+        process::exit(1);
+    }
+    let full_path = output_path.join(name);
+    if let Some(dir_path) = full_path.parent() {
+        std::fs::create_dir_all(dir_path).unwrap_or_else(|err| {
+            eprintln!(
+                "Unable to create output directory {} - {:?}",
+                dir_path.display(),
+                err
+            );
+
+            process::exit(1);
+        });
+    }
+    let mut file = File::create(&full_path).unwrap_or_else(|err| {
+        eprintln!(
+            "Unable to create output file {} - {:?}",
+            full_path.display(),
+            err
+        );
+
+        process::exit(1);
+    });
+
+    file.write_all(content.as_bytes()).unwrap_or_else(|err| {
+        eprintln!(
+            "Unable to write bindings to output file {} - {:?}",
+            full_path.display(),
+            err
+        );
+
+        process::exit(1);
+    });
+}
+
 fn number_lambda_sets(subs: &Subs, initial: Variable) -> Vec<Variable> {
     let mut lambda_sets = vec![];
     let mut stack = vec![initial];
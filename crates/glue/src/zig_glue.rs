@@ -0,0 +1,891 @@
+//! Generates Zig declarations for platform hosts, from the same [Types] the Rust glue uses.
+use crate::types::{
+    File, RocFn, RocNum, RocSingleTagPayload, RocStructFields, RocTagUnion, RocType, TypeId, Types,
+};
+use indexmap::IndexMap;
+use roc_target::{Architecture, TargetInfo};
+use std::fmt::Write;
+
+pub static HEADER: &[u8] = include_bytes!("../templates/header.zig");
+const INDENT: &str = "    ";
+
+/// For each declared name, every distinct body it has, along with the targets which use that body.
+/// The bodies become the arms of a `switch` on the target architecture.
+type Decls = IndexMap<String, IndexMap<String, Vec<TargetInfo>>>;
+
+fn add_decl(decls: &mut Decls, name: &str, target_info: TargetInfo, body: String) {
+    let targets = decls
+        .entry(name.to_string())
+        .or_default()
+        .entry(body)
+        .or_default();
+
+    if !targets.contains(&target_info) {
+        targets.push(target_info);
+    }
+}
+
+pub fn emit(types: &[Types]) -> Vec<File> {
+    let mut buf = std::str::from_utf8(HEADER).unwrap().to_string();
+    let mut decls = Decls::default();
+
+    for types in types {
+        for id in types.sorted_ids() {
+            add_type(types.target(), id, types, &mut decls);
+        }
+    }
+
+    for (name, bodies) in decls {
+        buf.push('\n');
+
+        // Declarations which are the same on every target don't need a switch.
+        if let Some((body, targets)) = bodies.first() {
+            if bodies.len() == 1 && targets.len() == types.len() {
+                writeln!(buf, "pub const {name} = {body};").unwrap();
+
+                continue;
+            }
+        }
+
+        writeln!(buf, "pub const {name} = switch (builtin.cpu.arch) {{").unwrap();
+
+        for (body, targets) in bodies {
+            let arches: Vec<_> = targets
+                .iter()
+                .map(|target_info| format!(".{}", arch_to_str(target_info.architecture)))
+                .collect();
+
+            writeln!(buf, "{INDENT}{} => {},", arches.join(", "), indent(&body)).unwrap();
+        }
+
+        writeln!(
+            buf,
+            "{INDENT}else => @compileError(\"Roc does not support this architecture\"),"
+        )
+        .unwrap();
+        buf.push_str("};\n");
+    }
+
+    vec![File {
+        name: "roc_app.zig".to_string(),
+        content: buf,
+    }]
+}
+
+/// Indents every line but the first, which goes after the `=>` of a switch arm.
+fn indent(body: &str) -> String {
+    let mut lines = body.lines();
+    let mut buf = lines.next().unwrap_or_default().to_string();
+
+    for line in lines {
+        buf.push('\n');
+
+        if !line.is_empty() {
+            buf.push_str(INDENT);
+            buf.push_str(line);
+        }
+    }
+
+    buf
+}
+
+fn add_type(target_info: TargetInfo, id: TypeId, types: &Types, decls: &mut Decls) {
+    match types.get_type(id) {
+        RocType::Struct { name, fields } => {
+            add_struct(name, target_info, fields, id, types, decls, false)
+        }
+        RocType::TagUnionPayload { name, fields } => {
+            add_struct(name, target_info, fields, id, types, decls, true)
+        }
+        RocType::TagUnion(tag_union) => match tag_union {
+            RocTagUnion::Enumeration { name, tags, size } => {
+                add_enumeration(name, target_info, tags, *size, decls)
+            }
+            RocTagUnion::NonRecursive {
+                name,
+                tags,
+                discriminant_size,
+                discriminant_offset,
+            } => {
+                // Empty tag unions can never come up at runtime,
+                // and so don't need declared types.
+                if !tags.is_empty() {
+                    add_non_recursive(
+                        name,
+                        target_info,
+                        id,
+                        tags,
+                        *discriminant_size,
+                        *discriminant_offset,
+                        types,
+                        decls,
+                    );
+                }
+            }
+            RocTagUnion::Recursive {
+                name,
+                tags,
+                discriminant_size,
+                discriminant_offset,
+            } => {
+                if !tags.is_empty() {
+                    add_recursive(
+                        name,
+                        target_info,
+                        tags,
+                        None,
+                        *discriminant_size,
+                        *discriminant_offset,
+                        types,
+                        decls,
+                    );
+                }
+            }
+            RocTagUnion::NullableWrapped {
+                name,
+                index_of_null_tag,
+                tags,
+                discriminant_size,
+                discriminant_offset,
+            } => add_recursive(
+                name,
+                target_info,
+                tags,
+                Some(*index_of_null_tag as usize),
+                *discriminant_size,
+                *discriminant_offset,
+                types,
+                decls,
+            ),
+            RocTagUnion::NullableUnwrapped {
+                name,
+                null_tag,
+                non_null_tag,
+                non_null_payload,
+                null_represents_first_tag: _,
+            } => add_unwrapped(
+                name,
+                target_info,
+                Some(null_tag),
+                non_null_tag,
+                *non_null_payload,
+                types,
+                decls,
+            ),
+            RocTagUnion::NonNullableUnwrapped {
+                name,
+                tag_name,
+                payload,
+            } => add_unwrapped(name, target_info, None, tag_name, *payload, types, decls),
+            RocTagUnion::SingleTagStruct {
+                name,
+                tag_name: _,
+                payload,
+            } => add_single_tag_struct(name, target_info, id, payload, types, decls),
+        },
+        RocType::Function(roc_fn) => add_function(target_info, roc_fn, types, decls),
+        // These are either declared in the header, or don't need to be declared in Zig.
+        RocType::Unit
+        | RocType::EmptyTagUnion
+        | RocType::Num(_)
+        | RocType::Bool
+        | RocType::RocResult(_, _)
+        | RocType::RocStr
+        | RocType::RocDict(_, _)
+        | RocType::RocSet(_)
+        | RocType::RocList(_)
+        | RocType::RocBox(_)
+        | RocType::Unsized => {}
+        RocType::RecursivePointer { .. } => {
+            // This is recursively pointing to a type that should already have been added,
+            // so no extra work needs to happen.
+        }
+    }
+}
+
+/// Zig lays out extern types the way C does, which is how Roc does too; this checks that.
+fn write_size_assertion(buf: &mut String, size: u32) {
+    write!(
+        buf,
+        r#"
+
+    comptime {{
+        std.debug.assert(@sizeOf(@This()) == {size});
+    }}"#
+    )
+    .unwrap();
+}
+
+fn add_struct(
+    name: &str,
+    target_info: TargetInfo,
+    fields: &RocStructFields,
+    struct_id: TypeId,
+    types: &Types,
+    decls: &mut Decls,
+    is_tag_union_payload: bool,
+) {
+    let name = escape_kw(name.to_string());
+    let mut buf = "extern struct {\n".to_string();
+
+    let field_ids: Vec<(&String, TypeId)> = match fields {
+        RocStructFields::HasNoClosure { fields } => {
+            fields.iter().map(|(label, id)| (label, *id)).collect()
+        }
+        RocStructFields::HasClosure { fields } => {
+            fields.iter().map(|(label, id, _)| (label, *id)).collect()
+        }
+    };
+
+    for (label, type_id) in field_ids {
+        // Tag union payloads have numbered fields, so we prefix them with an "f"
+        // to make them look like the other fields, rather than using @"0" and so on.
+        let label = if is_tag_union_payload {
+            format!("f{label}")
+        } else {
+            escape_kw(label.to_string())
+        };
+
+        writeln!(buf, "{INDENT}{label}: {},", type_name(type_id, types)).unwrap();
+    }
+
+    // If there's a closure in there, only the app knows how big this is. Payloads are checked
+    // as part of their tag union instead, since the size recorded for a recursive union's
+    // payload is the size of the pointer to it.
+    if !is_tag_union_payload && matches!(fields, RocStructFields::HasNoClosure { .. }) {
+        buf.pop();
+        write_size_assertion(&mut buf, types.size_rounded_to_alignment(struct_id));
+        buf.push('\n');
+    }
+
+    buf.push('}');
+
+    add_decl(decls, &name, target_info, buf);
+}
+
+fn add_enumeration<S: AsRef<str>>(
+    name: &str,
+    target_info: TargetInfo,
+    tags: &[S],
+    tag_bytes: u32,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+    let repr_bits = tag_bytes * 8;
+    let mut buf = format!("enum(u{repr_bits}) {{\n");
+
+    for (index, tag_name) in tags.iter().enumerate() {
+        writeln!(buf, "{INDENT}{} = {index},", tag_name.as_ref()).unwrap();
+    }
+
+    buf.push('}');
+
+    add_decl(decls, &name, target_info, buf);
+}
+
+/// Declares the enumeration of a tag union's tags, and returns its name.
+fn add_discriminant(
+    name: &str,
+    target_info: TargetInfo,
+    tag_names: &[&String],
+    size: u32,
+    decls: &mut Decls,
+) -> String {
+    let discriminant_name = format!("discriminant_{name}");
+
+    add_enumeration(&discriminant_name, target_info, tag_names, size, decls);
+
+    discriminant_name
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_non_recursive(
+    name: &str,
+    target_info: TargetInfo,
+    type_id: TypeId,
+    tags: &[(String, Option<TypeId>)],
+    discriminant_size: u32,
+    discriminant_offset: u32,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+    let tag_names: Vec<_> = tags.iter().map(|(tag_name, _)| tag_name).collect();
+    let discriminant_name =
+        add_discriminant(&name, target_info, &tag_names, discriminant_size, decls);
+    let discriminant_end = discriminant_offset + discriminant_size;
+    let repr_bits = discriminant_size * 8;
+    let size = types.size_rounded_to_alignment(type_id);
+
+    // Roc puts the discriminant in the padding after the largest payload, rather than in a
+    // separate field (which could make the whole thing bigger), so this is a union that gets
+    // its discriminant read and written at the right offset.
+    let mut buf = "extern union {\n".to_string();
+
+    for (tag_name, opt_payload_id) in tags {
+        if let Some(payload_id) = opt_payload_id {
+            writeln!(
+                buf,
+                "{INDENT}{tag_name}: {},",
+                type_name(*payload_id, types)
+            )
+            .unwrap();
+        }
+    }
+
+    // Make sure there's room for the discriminant, even if none of the payloads have
+    // padding at the end for it to go in.
+    write!(buf, "{INDENT}_sizer: [{size}]u8,").unwrap();
+    write_size_assertion(&mut buf, size);
+
+    write!(
+        buf,
+        r#"
+
+    pub fn discriminant(self: *const @This()) {discriminant_name} {{
+        const bytes = @ptrCast([*]const u8, self);
+
+        return @intToEnum({discriminant_name}, std.mem.readIntNative(u{repr_bits}, bytes[{discriminant_offset}..{discriminant_end}]));
+    }}
+
+    fn setDiscriminant(self: *@This(), discriminant: {discriminant_name}) void {{
+        const bytes = @ptrCast([*]u8, self);
+
+        std.mem.writeIntNative(u{repr_bits}, bytes[{discriminant_offset}..{discriminant_end}], @enumToInt(discriminant));
+    }}"#
+    )
+    .unwrap();
+
+    for (tag_name, opt_payload_id) in tags {
+        match opt_payload_id {
+            Some(payload_id) => {
+                let payload_type = type_name(*payload_id, types);
+
+                write!(
+                    buf,
+                    r#"
+
+    pub fn init{tag_name}(payload: {payload_type}) @This() {{
+        var self = @This(){{ .{tag_name} = payload }};
+        self.setDiscriminant(.{tag_name});
+        return self;
+    }}
+
+    pub fn get{tag_name}(self: *const @This()) *const {payload_type} {{
+        return &self.{tag_name};
+    }}"#
+                )
+                .unwrap();
+            }
+            None => {
+                write!(
+                    buf,
+                    r#"
+
+    pub fn init{tag_name}() @This() {{
+        var self = std.mem.zeroes(@This());
+        self.setDiscriminant(.{tag_name});
+        return self;
+    }}"#
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    buf.push_str("\n}");
+
+    add_decl(decls, &name, target_info, buf);
+}
+
+/// Writes the start of a constructor for a recursive tag union: allocating room for the payload
+/// (with a reference count of 1 in front of it) and pointing `data` at it.
+fn write_allocation(buf: &mut String, data_type: &str) {
+    write!(
+        buf,
+        r#"
+        const alignment = comptime std.math.max(@alignOf({data_type}), @sizeOf(isize));
+        const allocation = @ptrCast([*]u8, roc_alloc(@sizeOf({data_type}) + alignment, alignment).?);
+        const data = @ptrCast(*{data_type}, @alignCast(@alignOf({data_type}), allocation + alignment));
+        // A reference count of 1
+        @ptrCast(*isize, @alignCast(@alignOf(isize), allocation + alignment - @sizeOf(isize))).* = std.math.minInt(isize);"#
+    )
+    .unwrap();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_recursive(
+    name: &str,
+    target_info: TargetInfo,
+    tags: &[(String, Option<TypeId>)],
+    null_tag_index: Option<usize>, // used only in the nullable-wrapped case
+    discriminant_size: u32,
+    discriminant_offset: u32,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+    let tag_names: Vec<_> = tags.iter().map(|(tag_name, _)| tag_name).collect();
+    let discriminant_name =
+        add_discriminant(&name, target_info, &tag_names, discriminant_size, decls);
+    let discriminant_end = discriminant_offset + discriminant_size;
+    let repr_bits = discriminant_size * 8;
+
+    // The pointer may have the discriminant in its low bits, which makes it misaligned as far
+    // as Zig is concerned, so it's an opaque pointer until those bits have been cleared.
+    let pointer_type = match null_tag_index {
+        Some(_) => "?*anyopaque",
+        None => "*anyopaque",
+    };
+    let mut buf = format!("extern struct {{\n{INDENT}pointer: {pointer_type},\n\n");
+
+    writeln!(buf, "{INDENT}pub const Union = extern union {{").unwrap();
+
+    for (tag_name, opt_payload_id) in tags {
+        if let Some(payload_id) = opt_payload_id {
+            writeln!(
+                buf,
+                "{INDENT}{INDENT}{tag_name}: {},",
+                type_name(*payload_id, types)
+            )
+            .unwrap();
+        }
+    }
+
+    // The null tag doesn't need a discriminant, so it doesn't count here.
+    let non_null_tags = tags.len() - null_tag_index.map_or(0, |_| 1);
+
+    // If there are few enough tags, the discriminant goes in the unused bits of the
+    // (always aligned) pointer; otherwise it goes after the payload, so make room for it.
+    let bitmask = if non_null_tags < target_info.ptr_size() {
+        Some(target_info.ptr_size() - 1)
+    } else {
+        writeln!(buf, "{INDENT}{INDENT}_sizer: [{discriminant_end}]u8,").unwrap();
+
+        None
+    };
+
+    write!(buf, "{INDENT}}};").unwrap();
+
+    let unwrap = match null_tag_index {
+        Some(_) => ".?",
+        None => "",
+    };
+
+    match bitmask {
+        Some(bitmask) => write!(
+            buf,
+            r#"
+
+    fn unionPointer(self: *const @This()) *Union {{
+        return @intToPtr(*Union, @ptrToInt(self.pointer{unwrap}) & ~@as(usize, {bitmask:#b}));
+    }}"#
+        ),
+        None => write!(
+            buf,
+            r#"
+
+    fn unionPointer(self: *const @This()) *Union {{
+        return @ptrCast(*Union, @alignCast(@alignOf(Union), self.pointer{unwrap}));
+    }}"#
+        ),
+    }
+    .unwrap();
+
+    write!(
+        buf,
+        "\n\n{INDENT}pub fn discriminant(self: *const @This()) {discriminant_name} {{"
+    )
+    .unwrap();
+
+    if let Some(index) = null_tag_index {
+        write!(
+            buf,
+            r#"
+        if (self.pointer == null) {{
+            return .{};
+        }}
+"#,
+            tags[index].0
+        )
+        .unwrap();
+    }
+
+    match bitmask {
+        Some(bitmask) => write!(
+            buf,
+            r#"
+        return @intToEnum({discriminant_name}, @truncate(u{repr_bits}, @ptrToInt(self.pointer{unwrap}) & {bitmask:#b}));
+    }}"#
+        ),
+        None => write!(
+            buf,
+            r#"
+        const bytes = @ptrCast([*]const u8, self.unionPointer());
+
+        return @intToEnum({discriminant_name}, std.mem.readIntNative(u{repr_bits}, bytes[{discriminant_offset}..{discriminant_end}]));
+    }}"#
+        ),
+    }
+    .unwrap();
+
+    for (index, (tag_name, opt_payload_id)) in tags.iter().enumerate() {
+        if Some(index) == null_tag_index {
+            write!(
+                buf,
+                r#"
+
+    pub fn init{tag_name}() @This() {{
+        return .{{ .pointer = null }};
+    }}"#
+            )
+            .unwrap();
+
+            continue;
+        }
+
+        match opt_payload_id {
+            Some(payload_id) => write!(
+                buf,
+                "\n\n{INDENT}pub fn init{tag_name}(payload: {}) @This() {{",
+                type_name(*payload_id, types)
+            ),
+            None => write!(buf, "\n\n{INDENT}pub fn init{tag_name}() @This() {{"),
+        }
+        .unwrap();
+
+        write_allocation(&mut buf, "Union");
+
+        if opt_payload_id.is_some() {
+            write!(buf, "\n{INDENT}{INDENT}data.{tag_name} = payload;").unwrap();
+        }
+
+        match bitmask {
+            Some(_) => write!(
+                buf,
+                r#"
+
+        return .{{ .pointer = @intToPtr(*anyopaque, @ptrToInt(data) | @enumToInt({discriminant_name}.{tag_name})) }};
+    }}"#
+            ),
+            None => write!(
+                buf,
+                r#"
+        const bytes = @ptrCast([*]u8, data);
+        std.mem.writeIntNative(u{repr_bits}, bytes[{discriminant_offset}..{discriminant_end}], @enumToInt({discriminant_name}.{tag_name}));
+
+        return .{{ .pointer = data }};
+    }}"#
+            ),
+        }
+        .unwrap();
+
+        if let Some(payload_id) = opt_payload_id {
+            write!(
+                buf,
+                r#"
+
+    pub fn get{tag_name}(self: *const @This()) *const {} {{
+        return &self.unionPointer().{tag_name};
+    }}"#,
+                type_name(*payload_id, types)
+            )
+            .unwrap();
+        }
+    }
+
+    buf.push_str("\n}");
+
+    add_decl(decls, &name, target_info, buf);
+}
+
+/// A recursive tag union which doesn't need a discriminant, because it either has just one
+/// tag, or two tags where one of them is represented by a null pointer.
+fn add_unwrapped(
+    name: &str,
+    target_info: TargetInfo,
+    null_tag: Option<&String>,
+    non_null_tag: &str,
+    payload_id: TypeId,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+    let payload_type = type_name(payload_id, types);
+
+    let pointer_type = match null_tag {
+        Some(_) => format!("?*{payload_type}"),
+        None => format!("*{payload_type}"),
+    };
+    let mut buf = format!("extern struct {{\n{INDENT}pointer: {pointer_type},");
+
+    if let Some(null_tag) = null_tag {
+        let non_null_tag = non_null_tag.to_string();
+        let mut tag_names = vec![null_tag, &non_null_tag];
+
+        tag_names.sort();
+
+        let discriminant_name = add_discriminant(&name, target_info, &tag_names, 1, decls);
+
+        write!(
+            buf,
+            r#"
+
+    pub fn discriminant(self: *const @This()) {discriminant_name} {{
+        return if (self.pointer == null) .{null_tag} else .{non_null_tag};
+    }}
+
+    pub fn init{null_tag}() @This() {{
+        return .{{ .pointer = null }};
+    }}"#
+        )
+        .unwrap();
+    }
+
+    // Only the nullable version needs to unwrap the pointer.
+    let unwrap = null_tag.map_or("", |_| ".?");
+
+    write!(
+        buf,
+        "\n\n{INDENT}pub fn init{non_null_tag}(payload: {payload_type}) @This() {{"
+    )
+    .unwrap();
+
+    write_allocation(&mut buf, &payload_type);
+
+    write!(
+        buf,
+        r#"
+        data.* = payload;
+
+        return .{{ .pointer = data }};
+    }}
+
+    pub fn get{non_null_tag}(self: *const @This()) *const {payload_type} {{
+        return self.pointer{unwrap};
+    }}
+}}"#
+    )
+    .unwrap();
+
+    add_decl(decls, &name, target_info, buf);
+}
+
+fn add_single_tag_struct(
+    name: &str,
+    target_info: TargetInfo,
+    type_id: TypeId,
+    payload: &RocSingleTagPayload,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+
+    let field_ids: Vec<TypeId> = match payload {
+        RocSingleTagPayload::HasNoClosure { payload_fields } => payload_fields.clone(),
+        RocSingleTagPayload::HasClosure { payload_getters } => {
+            payload_getters.iter().map(|(id, _)| *id).collect()
+        }
+    };
+
+    let buf = if field_ids.is_empty() {
+        "RocUnit".to_string()
+    } else {
+        let mut buf = "extern struct {\n".to_string();
+
+        for (index, type_id) in field_ids.into_iter().enumerate() {
+            writeln!(buf, "{INDENT}f{index}: {},", type_name(type_id, types)).unwrap();
+        }
+
+        if let RocSingleTagPayload::HasNoClosure { .. } = payload {
+            buf.pop();
+            write_size_assertion(&mut buf, types.size_rounded_to_alignment(type_id));
+            buf.push('\n');
+        }
+
+        buf.push('}');
+
+        buf
+    };
+
+    add_decl(decls, &name, target_info, buf);
+}
+
+fn add_function(target_info: TargetInfo, roc_fn: &RocFn, types: &Types, decls: &mut Decls) {
+    let name = escape_kw(roc_fn.function_name.to_string());
+    let extern_name = &roc_fn.extern_name;
+    let return_type_str = type_name(roc_fn.ret, types);
+    let mut buf = format!(
+        "extern struct {{\n{INDENT}closure_data: {},\n\n",
+        type_name(roc_fn.lambda_set, types)
+    );
+
+    // extern fn extern_name(arg_0: *const arg_0_type, ..., closure_data: ?[*]u8, output: *return_type) callconv(.C) void;
+    write!(buf, "{INDENT}extern fn {extern_name}(").unwrap();
+
+    for (i, argument_type) in roc_fn.args.iter().enumerate() {
+        write!(
+            buf,
+            "arg_{i}: *const {}, ",
+            type_name(*argument_type, types)
+        )
+        .unwrap();
+    }
+
+    writeln!(
+        buf,
+        "closure_data: ?[*]u8, output: *{return_type_str}) callconv(.C) void;\n"
+    )
+    .unwrap();
+
+    write!(buf, "{INDENT}pub fn forceThunk(self: *@This()").unwrap();
+
+    for (i, argument_type) in roc_fn.args.iter().enumerate() {
+        write!(buf, ", arg_{i}: {}", type_name(*argument_type, types)).unwrap();
+    }
+
+    writeln!(
+        buf,
+        ") {return_type_str} {{\n{INDENT}{INDENT}var output: {return_type_str} = undefined;"
+    )
+    .unwrap();
+    write!(buf, "{INDENT}{INDENT}{extern_name}(").unwrap();
+
+    for i in 0..roc_fn.args.len() {
+        write!(buf, "&arg_{i}, ").unwrap();
+    }
+
+    // Closures whose captures can vary in size hold them in a list of bytes.
+    let closure_data = match types.get_type(roc_fn.lambda_set) {
+        RocType::Unsized => "self.closure_data.elements",
+        _ => "@ptrCast([*]u8, &self.closure_data)",
+    };
+
+    write!(
+        buf,
+        "{closure_data}, &output);\n{INDENT}{INDENT}return output;\n{INDENT}}}\n}}"
+    )
+    .unwrap();
+
+    add_decl(decls, &name, target_info, buf);
+}
+
+fn type_name(id: TypeId, types: &Types) -> String {
+    match types.get_type(id) {
+        RocType::Unit | RocType::EmptyTagUnion => "RocUnit".to_string(),
+        RocType::RocStr => "RocStr".to_string(),
+        RocType::Bool => "bool".to_string(),
+        RocType::Num(RocNum::U8) => "u8".to_string(),
+        RocType::Num(RocNum::U16) => "u16".to_string(),
+        RocType::Num(RocNum::U32) => "u32".to_string(),
+        RocType::Num(RocNum::U64) => "u64".to_string(),
+        RocType::Num(RocNum::U128) => "u128".to_string(),
+        RocType::Num(RocNum::I8) => "i8".to_string(),
+        RocType::Num(RocNum::I16) => "i16".to_string(),
+        RocType::Num(RocNum::I32) => "i32".to_string(),
+        RocType::Num(RocNum::I64) => "i64".to_string(),
+        RocType::Num(RocNum::I128) => "i128".to_string(),
+        RocType::Num(RocNum::F32) => "f32".to_string(),
+        RocType::Num(RocNum::F64) => "f64".to_string(),
+        RocType::Num(RocNum::Dec) => "RocDec".to_string(),
+        RocType::RocDict(key_id, val_id) => format!(
+            "RocDict({}, {})",
+            type_name(*key_id, types),
+            type_name(*val_id, types)
+        ),
+        RocType::RocSet(elem_id) => format!("RocSet({})", type_name(*elem_id, types)),
+        RocType::RocList(elem_id) => format!("RocList({})", type_name(*elem_id, types)),
+        RocType::RocBox(elem_id) => format!("RocBox({})", type_name(*elem_id, types)),
+        RocType::Unsized => "RocList(u8)".to_string(),
+        RocType::RocResult(ok_id, err_id) => {
+            format!(
+                "RocResult({}, {})",
+                type_name(*ok_id, types),
+                type_name(*err_id, types)
+            )
+        }
+        RocType::Struct { name, .. }
+        | RocType::TagUnionPayload { name, .. }
+        | RocType::TagUnion(RocTagUnion::NonRecursive { name, .. })
+        | RocType::TagUnion(RocTagUnion::Recursive { name, .. })
+        | RocType::TagUnion(RocTagUnion::Enumeration { name, .. })
+        | RocType::TagUnion(RocTagUnion::NullableWrapped { name, .. })
+        | RocType::TagUnion(RocTagUnion::NullableUnwrapped { name, .. })
+        | RocType::TagUnion(RocTagUnion::NonNullableUnwrapped { name, .. })
+        | RocType::TagUnion(RocTagUnion::SingleTagStruct { name, .. }) => escape_kw(name.clone()),
+        RocType::RecursivePointer(content) => type_name(*content, types),
+        RocType::Function(RocFn { function_name, .. }) => escape_kw(function_name.clone()),
+    }
+}
+
+/// The name of this architecture's tag in `std.Target.Cpu.Arch`.
+fn arch_to_str(architecture: Architecture) -> &'static str {
+    match architecture {
+        Architecture::X86_64 => "x86_64",
+        Architecture::X86_32 => "i386",
+        Architecture::Aarch64 => "aarch64",
+        Architecture::Aarch32 => "arm",
+        Architecture::Wasm32 => "wasm32",
+    }
+}
+
+// Based on https://ziglang.org/documentation/0.9.1/#Keyword-Reference
+const RESERVED_KEYWORDS: &[&str] = &[
+    "align",
+    "allowzero",
+    "and",
+    "anyframe",
+    "anytype",
+    "asm",
+    "async",
+    "await",
+    "break",
+    "callconv",
+    "catch",
+    "comptime",
+    "const",
+    "continue",
+    "defer",
+    "else",
+    "enum",
+    "errdefer",
+    "error",
+    "export",
+    "extern",
+    "fn",
+    "for",
+    "if",
+    "inline",
+    "noalias",
+    "noinline",
+    "nosuspend",
+    "opaque",
+    "or",
+    "orelse",
+    "packed",
+    "pub",
+    "resume",
+    "return",
+    "linksection",
+    "struct",
+    "suspend",
+    "switch",
+    "test",
+    "threadlocal",
+    "try",
+    "union",
+    "unreachable",
+    "usingnamespace",
+    "var",
+    "volatile",
+    "while",
+];
+
+/// Escape a Zig reserved keyword, if necessary.
+fn escape_kw(input: String) -> String {
+    if RESERVED_KEYWORDS.contains(&input.as_str()) {
+        format!("@\"{input}\"")
+    } else {
+        input
+    }
+}
@@ -0,0 +1,48 @@
+// ⚠️ GENERATED CODE ⚠️ - this entire file was generated by the `roc glue` CLI command
+
+#pragma once
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <string.h>
+
+// The host provides this, just like it does for the Roc app.
+void *roc_alloc(size_t size, uint32_t alignment);
+
+// C doesn't allow zero-sized types, so this stands in for values that take up no space in Roc.
+typedef struct RocUnit {
+    uint8_t _unused;
+} RocUnit;
+
+typedef struct RocStr {
+    uint8_t *bytes;
+    size_t len;
+    size_t capacity;
+} RocStr;
+
+typedef struct RocList {
+    void *elements;
+    size_t len;
+    size_t capacity;
+} RocList;
+
+typedef RocList RocDict;
+
+typedef RocList RocSet;
+
+typedef struct RocBox {
+    void *contents;
+} RocBox;
+
+typedef struct RocI128 {
+    _Alignas(16) uint8_t bytes[16];
+} RocI128;
+
+typedef struct RocU128 {
+    _Alignas(16) uint8_t bytes[16];
+} RocU128;
+
+typedef struct RocDec {
+    _Alignas(16) uint8_t bytes[16];
+} RocDec;
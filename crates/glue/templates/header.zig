@@ -0,0 +1,61 @@
+// ⚠️ GENERATED CODE ⚠️ - this entire file was generated by the `roc glue` CLI command
+
+const std = @import("std");
+const builtin = @import("builtin");
+
+// The host provides this, just like it does for the Roc app.
+extern fn roc_alloc(size: usize, alignment: u32) callconv(.C) ?*anyopaque;
+
+// This stands in for values that take up no space in Roc.
+pub const RocUnit = extern struct {
+    _unused: u8 = 0,
+};
+
+pub const RocStr = extern struct {
+    bytes: ?[*]u8,
+    len: usize,
+    capacity: usize,
+};
+
+pub fn RocList(comptime T: type) type {
+    return extern struct {
+        elements: ?[*]T,
+        len: usize,
+        capacity: usize,
+    };
+}
+
+pub fn RocDict(comptime K: type, comptime V: type) type {
+    // Whichever of the key and the value has the bigger alignment comes first.
+    const Entry = if (@alignOf(K) >= @alignOf(V))
+        extern struct { key: K, value: V }
+    else
+        extern struct { value: V, key: K };
+
+    return RocList(Entry);
+}
+
+// A set is a dictionary whose values take up no space, so its entries are just the elements.
+pub fn RocSet(comptime T: type) type {
+    return RocList(T);
+}
+
+pub fn RocBox(comptime T: type) type {
+    return extern struct {
+        contents: *T,
+    };
+}
+
+pub fn RocResult(comptime T: type, comptime E: type) type {
+    return extern struct {
+        payload: extern union {
+            ok: T,
+            err: E,
+        },
+        is_ok: bool,
+    };
+}
+
+pub const RocDec = extern struct {
+    num: i128,
+};
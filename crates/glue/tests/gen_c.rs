@@ -0,0 +1,584 @@
+mod helpers;
+
+#[cfg(test)]
+mod test_gen_c {
+    use crate::helpers::generate_bindings_with;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+    use roc_glue::c_glue::{self, HEADER};
+    use roc_glue::load::BuiltinGlue;
+    use roc_glue::types::File;
+    use std::path::Path;
+
+    #[test]
+    fn basic_record_aliased() {
+        let module = indoc!(
+            r#"
+            MyRcd : { a : U64, b : I128 }
+
+            main : MyRcd
+            main = { a: 1u64, b: 2i128 }
+            "#
+        );
+
+        let full_header = std::str::from_utf8(HEADER).unwrap().to_string() + "\n";
+        assert_eq!(
+            generate_bindings_with(module, c_glue::emit),
+            vec![File {
+                name: "roc_app.h".to_string(),
+                content: full_header
+                    + indoc!(
+                        r#"
+                    typedef struct MyRcd {
+                        RocI128 b;
+                        uint64_t a;
+                    } MyRcd;
+
+                    _Static_assert(sizeof(MyRcd) == 32, "MyRcd should be 32 bytes");
+                    "#
+                    )
+            }]
+        );
+    }
+
+    #[test]
+    fn nested_record_aliased() {
+        let module = indoc!(
+            r#"
+            Outer : { x : Inner, y : Str, z : List U8 }
+
+            Inner : { a : U16, b : F32 }
+
+            main : Outer
+            main = { x: { a: 5, b: 24 }, y: "foo", z: [1, 2] }
+            "#
+        );
+
+        let full_header = std::str::from_utf8(HEADER).unwrap().to_string() + "\n";
+        assert_eq!(
+            generate_bindings_with(module, c_glue::emit),
+            vec![File {
+                name: "roc_app.h".to_string(),
+                content: full_header
+                    + indoc!(
+                        r#"
+                    typedef struct Inner {
+                        float b;
+                        uint16_t a;
+                    } Inner;
+
+                    _Static_assert(sizeof(Inner) == 8, "Inner should be 8 bytes");
+
+                    #if defined(__arm__) || defined(__wasm32__) || defined(__i386__)
+                    typedef struct Outer {
+                        Inner x;
+                        RocStr y;
+                        RocList z;
+                    } Outer;
+
+                    _Static_assert(sizeof(Outer) == 32, "Outer should be 32 bytes");
+                    #elif defined(__aarch64__) || defined(__x86_64__)
+                    typedef struct Outer {
+                        RocStr y;
+                        RocList z;
+                        Inner x;
+                    } Outer;
+
+                    _Static_assert(sizeof(Outer) == 56, "Outer should be 56 bytes");
+                    #endif
+                    "#
+                    )
+            }]
+        );
+    }
+
+    #[test]
+    fn enumeration() {
+        let module = indoc!(
+            r#"
+            Enumeration : [Blah, Foo, Bar,]
+
+            main : Enumeration
+            main = Foo
+            "#
+        );
+
+        let full_header = std::str::from_utf8(HEADER).unwrap().to_string() + "\n";
+        assert_eq!(
+            generate_bindings_with(module, c_glue::emit),
+            vec![File {
+                name: "roc_app.h".to_string(),
+                content: full_header
+                    + indoc!(
+                        r#"
+                    typedef uint8_t Enumeration;
+
+                    enum {
+                        Enumeration_Bar = 0,
+                        Enumeration_Blah = 1,
+                        Enumeration_Foo = 2,
+                    };
+                    "#
+                    )
+            }]
+        );
+    }
+
+    #[test]
+    fn nullable_unwrapped() {
+        let module = indoc!(
+            r#"
+            StrConsList : [Nil, Cons Str StrConsList]
+
+            main : StrConsList
+            main = Nil
+            "#
+        );
+
+        let full_header = std::str::from_utf8(HEADER).unwrap().to_string() + "\n";
+        assert_eq!(
+            generate_bindings_with(module, c_glue::emit),
+            vec![File {
+                name: "roc_app.h".to_string(),
+                content: full_header
+                    + indoc!(
+                        r#"
+                    typedef struct StrConsList {
+                        struct StrConsList_Cons *pointer;
+                    } StrConsList;
+
+                    typedef struct StrConsList_Cons {
+                        RocStr f0;
+                        StrConsList f1;
+                    } StrConsList_Cons;
+
+                    typedef uint8_t discriminant_StrConsList;
+
+                    enum {
+                        discriminant_StrConsList_Cons = 0,
+                        discriminant_StrConsList_Nil = 1,
+                    };
+
+                    static inline discriminant_StrConsList StrConsList_discriminant(const StrConsList *self) {
+                        return self->pointer == NULL ? discriminant_StrConsList_Nil : discriminant_StrConsList_Cons;
+                    }
+
+                    static inline StrConsList StrConsList_new_Nil(void) {
+                        StrConsList self = { NULL };
+                        return self;
+                    }
+
+                    static inline StrConsList StrConsList_new_Cons(StrConsList_Cons payload) {
+                        size_t alignment = _Alignof(StrConsList_Cons) > sizeof(intptr_t) ? _Alignof(StrConsList_Cons) : sizeof(intptr_t);
+                        uint8_t *allocation = roc_alloc(sizeof(StrConsList_Cons) + alignment, alignment);
+                        StrConsList_Cons *data = (StrConsList_Cons *)(allocation + alignment);
+                        // A reference count of 1
+                        ((intptr_t *)data)[-1] = INTPTR_MIN;
+                        *data = payload;
+                        StrConsList self = { data };
+                        return self;
+                    }
+
+                    static inline const StrConsList_Cons *StrConsList_get_Cons(const StrConsList *self) {
+                        return self->pointer;
+                    }
+                    "#
+                    )
+            }]
+        );
+    }
+
+    #[test]
+    fn recursive_union() {
+        let module = indoc!(
+            r#"
+            Expr : [String Str, Concat Expr Expr]
+
+            main : Expr
+            main = String "hello"
+            "#
+        );
+
+        let full_header = std::str::from_utf8(HEADER).unwrap().to_string() + "\n";
+        assert_eq!(
+            generate_bindings_with(module, c_glue::emit),
+            vec![File {
+                name: "roc_app.h".to_string(),
+                content: full_header
+                    + indoc!(
+                        r#"
+                    typedef struct Expr {
+                        union union_Expr *pointer;
+                    } Expr;
+
+                    typedef struct Expr_Concat {
+                        Expr f0;
+                        Expr f1;
+                    } Expr_Concat;
+
+                    typedef struct Expr_String {
+                        RocStr f0;
+                    } Expr_String;
+
+                    typedef uint8_t discriminant_Expr;
+
+                    enum {
+                        discriminant_Expr_Concat = 0,
+                        discriminant_Expr_String = 1,
+                    };
+
+                    #if defined(__arm__) || defined(__wasm32__) || defined(__i386__)
+                    typedef union union_Expr {
+                        Expr_Concat Concat;
+                        Expr_String String;
+                    } union_Expr;
+
+                    static inline union_Expr *Expr_union_pointer(const Expr *self) {
+                        return (union_Expr *)((uintptr_t)self->pointer & ~(uintptr_t)0x3);
+                    }
+
+                    static inline discriminant_Expr Expr_discriminant(const Expr *self) {
+                        return (discriminant_Expr)((uintptr_t)self->pointer & 0x3);
+                    }
+
+                    static inline Expr Expr_new_Concat(Expr_Concat payload) {
+                        size_t alignment = _Alignof(union_Expr) > sizeof(intptr_t) ? _Alignof(union_Expr) : sizeof(intptr_t);
+                        uint8_t *allocation = roc_alloc(sizeof(union_Expr) + alignment, alignment);
+                        union_Expr *data = (union_Expr *)(allocation + alignment);
+                        // A reference count of 1
+                        ((intptr_t *)data)[-1] = INTPTR_MIN;
+                        data->Concat = payload;
+                        Expr self = { (union_Expr *)((uintptr_t)data | discriminant_Expr_Concat) };
+                        return self;
+                    }
+
+                    static inline const Expr_Concat *Expr_get_Concat(const Expr *self) {
+                        return &Expr_union_pointer(self)->Concat;
+                    }
+
+                    static inline Expr Expr_new_String(Expr_String payload) {
+                        size_t alignment = _Alignof(union_Expr) > sizeof(intptr_t) ? _Alignof(union_Expr) : sizeof(intptr_t);
+                        uint8_t *allocation = roc_alloc(sizeof(union_Expr) + alignment, alignment);
+                        union_Expr *data = (union_Expr *)(allocation + alignment);
+                        // A reference count of 1
+                        ((intptr_t *)data)[-1] = INTPTR_MIN;
+                        data->String = payload;
+                        Expr self = { (union_Expr *)((uintptr_t)data | discriminant_Expr_String) };
+                        return self;
+                    }
+
+                    static inline const Expr_String *Expr_get_String(const Expr *self) {
+                        return &Expr_union_pointer(self)->String;
+                    }
+                    #elif defined(__aarch64__) || defined(__x86_64__)
+                    typedef union union_Expr {
+                        Expr_Concat Concat;
+                        Expr_String String;
+                    } union_Expr;
+
+                    static inline union_Expr *Expr_union_pointer(const Expr *self) {
+                        return (union_Expr *)((uintptr_t)self->pointer & ~(uintptr_t)0x7);
+                    }
+
+                    static inline discriminant_Expr Expr_discriminant(const Expr *self) {
+                        return (discriminant_Expr)((uintptr_t)self->pointer & 0x7);
+                    }
+
+                    static inline Expr Expr_new_Concat(Expr_Concat payload) {
+                        size_t alignment = _Alignof(union_Expr) > sizeof(intptr_t) ? _Alignof(union_Expr) : sizeof(intptr_t);
+                        uint8_t *allocation = roc_alloc(sizeof(union_Expr) + alignment, alignment);
+                        union_Expr *data = (union_Expr *)(allocation + alignment);
+                        // A reference count of 1
+                        ((intptr_t *)data)[-1] = INTPTR_MIN;
+                        data->Concat = payload;
+                        Expr self = { (union_Expr *)((uintptr_t)data | discriminant_Expr_Concat) };
+                        return self;
+                    }
+
+                    static inline const Expr_Concat *Expr_get_Concat(const Expr *self) {
+                        return &Expr_union_pointer(self)->Concat;
+                    }
+
+                    static inline Expr Expr_new_String(Expr_String payload) {
+                        size_t alignment = _Alignof(union_Expr) > sizeof(intptr_t) ? _Alignof(union_Expr) : sizeof(intptr_t);
+                        uint8_t *allocation = roc_alloc(sizeof(union_Expr) + alignment, alignment);
+                        union_Expr *data = (union_Expr *)(allocation + alignment);
+                        // A reference count of 1
+                        ((intptr_t *)data)[-1] = INTPTR_MIN;
+                        data->String = payload;
+                        Expr self = { (union_Expr *)((uintptr_t)data | discriminant_Expr_String) };
+                        return self;
+                    }
+
+                    static inline const Expr_String *Expr_get_String(const Expr *self) {
+                        return &Expr_union_pointer(self)->String;
+                    }
+                    #endif
+                    "#
+                    )
+            }]
+        );
+    }
+
+    #[test]
+    fn nullable_wrapped() {
+        let module = indoc!(
+            r#"
+            StrFingerTree : [Empty, Single Str, More Str StrFingerTree]
+
+            main : StrFingerTree
+            main = Empty
+            "#
+        );
+
+        let full_header = std::str::from_utf8(HEADER).unwrap().to_string() + "\n";
+        assert_eq!(
+            generate_bindings_with(module, c_glue::emit),
+            vec![File {
+                name: "roc_app.h".to_string(),
+                content: full_header
+                    + indoc!(
+                        r#"
+                    typedef struct StrFingerTree {
+                        union union_StrFingerTree *pointer;
+                    } StrFingerTree;
+
+                    typedef struct StrFingerTree_More {
+                        RocStr f0;
+                        StrFingerTree f1;
+                    } StrFingerTree_More;
+
+                    typedef struct StrFingerTree_Single {
+                        RocStr f0;
+                    } StrFingerTree_Single;
+
+                    typedef uint8_t discriminant_StrFingerTree;
+
+                    enum {
+                        discriminant_StrFingerTree_Empty = 0,
+                        discriminant_StrFingerTree_More = 1,
+                        discriminant_StrFingerTree_Single = 2,
+                    };
+
+                    #if defined(__arm__) || defined(__wasm32__) || defined(__i386__)
+                    typedef union union_StrFingerTree {
+                        StrFingerTree_More More;
+                        StrFingerTree_Single Single;
+                    } union_StrFingerTree;
+
+                    static inline union_StrFingerTree *StrFingerTree_union_pointer(const StrFingerTree *self) {
+                        return (union_StrFingerTree *)((uintptr_t)self->pointer & ~(uintptr_t)0x3);
+                    }
+
+                    static inline discriminant_StrFingerTree StrFingerTree_discriminant(const StrFingerTree *self) {
+                        if (self->pointer == NULL) {
+                            return discriminant_StrFingerTree_Empty;
+                        }
+
+                        return (discriminant_StrFingerTree)((uintptr_t)self->pointer & 0x3);
+                    }
+
+                    static inline StrFingerTree StrFingerTree_new_Empty(void) {
+                        StrFingerTree self = { NULL };
+                        return self;
+                    }
+
+                    static inline StrFingerTree StrFingerTree_new_More(StrFingerTree_More payload) {
+                        size_t alignment = _Alignof(union_StrFingerTree) > sizeof(intptr_t) ? _Alignof(union_StrFingerTree) : sizeof(intptr_t);
+                        uint8_t *allocation = roc_alloc(sizeof(union_StrFingerTree) + alignment, alignment);
+                        union_StrFingerTree *data = (union_StrFingerTree *)(allocation + alignment);
+                        // A reference count of 1
+                        ((intptr_t *)data)[-1] = INTPTR_MIN;
+                        data->More = payload;
+                        StrFingerTree self = { (union_StrFingerTree *)((uintptr_t)data | discriminant_StrFingerTree_More) };
+                        return self;
+                    }
+
+                    static inline const StrFingerTree_More *StrFingerTree_get_More(const StrFingerTree *self) {
+                        return &StrFingerTree_union_pointer(self)->More;
+                    }
+
+                    static inline StrFingerTree StrFingerTree_new_Single(StrFingerTree_Single payload) {
+                        size_t alignment = _Alignof(union_StrFingerTree) > sizeof(intptr_t) ? _Alignof(union_StrFingerTree) : sizeof(intptr_t);
+                        uint8_t *allocation = roc_alloc(sizeof(union_StrFingerTree) + alignment, alignment);
+                        union_StrFingerTree *data = (union_StrFingerTree *)(allocation + alignment);
+                        // A reference count of 1
+                        ((intptr_t *)data)[-1] = INTPTR_MIN;
+                        data->Single = payload;
+                        StrFingerTree self = { (union_StrFingerTree *)((uintptr_t)data | discriminant_StrFingerTree_Single) };
+                        return self;
+                    }
+
+                    static inline const StrFingerTree_Single *StrFingerTree_get_Single(const StrFingerTree *self) {
+                        return &StrFingerTree_union_pointer(self)->Single;
+                    }
+                    #elif defined(__aarch64__) || defined(__x86_64__)
+                    typedef union union_StrFingerTree {
+                        StrFingerTree_More More;
+                        StrFingerTree_Single Single;
+                    } union_StrFingerTree;
+
+                    static inline union_StrFingerTree *StrFingerTree_union_pointer(const StrFingerTree *self) {
+                        return (union_StrFingerTree *)((uintptr_t)self->pointer & ~(uintptr_t)0x7);
+                    }
+
+                    static inline discriminant_StrFingerTree StrFingerTree_discriminant(const StrFingerTree *self) {
+                        if (self->pointer == NULL) {
+                            return discriminant_StrFingerTree_Empty;
+                        }
+
+                        return (discriminant_StrFingerTree)((uintptr_t)self->pointer & 0x7);
+                    }
+
+                    static inline StrFingerTree StrFingerTree_new_Empty(void) {
+                        StrFingerTree self = { NULL };
+                        return self;
+                    }
+
+                    static inline StrFingerTree StrFingerTree_new_More(StrFingerTree_More payload) {
+                        size_t alignment = _Alignof(union_StrFingerTree) > sizeof(intptr_t) ? _Alignof(union_StrFingerTree) : sizeof(intptr_t);
+                        uint8_t *allocation = roc_alloc(sizeof(union_StrFingerTree) + alignment, alignment);
+                        union_StrFingerTree *data = (union_StrFingerTree *)(allocation + alignment);
+                        // A reference count of 1
+                        ((intptr_t *)data)[-1] = INTPTR_MIN;
+                        data->More = payload;
+                        StrFingerTree self = { (union_StrFingerTree *)((uintptr_t)data | discriminant_StrFingerTree_More) };
+                        return self;
+                    }
+
+                    static inline const StrFingerTree_More *StrFingerTree_get_More(const StrFingerTree *self) {
+                        return &StrFingerTree_union_pointer(self)->More;
+                    }
+
+                    static inline StrFingerTree StrFingerTree_new_Single(StrFingerTree_Single payload) {
+                        size_t alignment = _Alignof(union_StrFingerTree) > sizeof(intptr_t) ? _Alignof(union_StrFingerTree) : sizeof(intptr_t);
+                        uint8_t *allocation = roc_alloc(sizeof(union_StrFingerTree) + alignment, alignment);
+                        union_StrFingerTree *data = (union_StrFingerTree *)(allocation + alignment);
+                        // A reference count of 1
+                        ((intptr_t *)data)[-1] = INTPTR_MIN;
+                        data->Single = payload;
+                        StrFingerTree self = { (union_StrFingerTree *)((uintptr_t)data | discriminant_StrFingerTree_Single) };
+                        return self;
+                    }
+
+                    static inline const StrFingerTree_Single *StrFingerTree_get_Single(const StrFingerTree *self) {
+                        return &StrFingerTree_union_pointer(self)->Single;
+                    }
+                    #endif
+                    "#
+                    )
+            }]
+        );
+    }
+
+    #[test]
+    fn nonnullable_unwrapped() {
+        let module = indoc!(
+            r#"
+            StrRoseTree : [Tree Str (List StrRoseTree)]
+
+            main : StrRoseTree
+            main = Tree "root" []
+            "#
+        );
+
+        let full_header = std::str::from_utf8(HEADER).unwrap().to_string() + "\n";
+        assert_eq!(
+            generate_bindings_with(module, c_glue::emit),
+            vec![File {
+                name: "roc_app.h".to_string(),
+                content: full_header
+                    + indoc!(
+                        r#"
+                    typedef struct StrRoseTree {
+                        struct StrRoseTree_Tree *pointer;
+                    } StrRoseTree;
+
+                    typedef struct StrRoseTree_Tree {
+                        RocStr f0;
+                        RocList f1;
+                    } StrRoseTree_Tree;
+
+                    static inline StrRoseTree StrRoseTree_new_Tree(StrRoseTree_Tree payload) {
+                        size_t alignment = _Alignof(StrRoseTree_Tree) > sizeof(intptr_t) ? _Alignof(StrRoseTree_Tree) : sizeof(intptr_t);
+                        uint8_t *allocation = roc_alloc(sizeof(StrRoseTree_Tree) + alignment, alignment);
+                        StrRoseTree_Tree *data = (StrRoseTree_Tree *)(allocation + alignment);
+                        // A reference count of 1
+                        ((intptr_t *)data)[-1] = INTPTR_MIN;
+                        *data = payload;
+                        StrRoseTree self = { data };
+                        return self;
+                    }
+
+                    static inline const StrRoseTree_Tree *StrRoseTree_get_Tree(const StrRoseTree *self) {
+                        return self->pointer;
+                    }
+                    "#
+                    )
+            }]
+        );
+    }
+
+    #[test]
+    fn record_of_functions() {
+        let module = indoc!(
+            r#"
+            Ops : { f : I64, I64 -> I64, g : I64, I64 -> I64 }
+
+            main : Ops
+            main = { f: add, g: sub }
+
+            add : I64, I64 -> I64
+            add = \x, y -> x + y
+
+            sub : I64, I64 -> I64
+            sub = \x, y -> x - y
+            "#
+        );
+
+        let files = generate_bindings_with(module, c_glue::emit);
+        let header = &files[0].content;
+
+        // The functions' names come from their type variables, so rather than matching the
+        // whole header, check what gets declared for each of them, and that they're declared
+        // before the record which holds them.
+        assert_eq!(
+            header
+                .matches("_caller(const int64_t *arg_0, const int64_t *arg_1, uint8_t *closure_data, int64_t *output);")
+                .count(),
+            2,
+            "{header}"
+        );
+        assert_eq!(header.matches("_force_thunk(").count(), 2, "{header}");
+        assert_eq!(
+            header
+                .matches(" *self, int64_t arg_0, int64_t arg_1) {\n    int64_t output;\n")
+                .count(),
+            2,
+            "{header}"
+        );
+
+        let first_function = header.find("typedef struct RocFunction_").expect(header);
+        let record = header.find("typedef struct Ops {").expect(header);
+
+        assert!(first_function < record, "{header}");
+    }
+
+    #[test]
+    fn builtin_glue_spec_paths() {
+        assert_eq!(
+            BuiltinGlue::from_spec_path(Path::new("c")),
+            Some(BuiltinGlue::C)
+        );
+        assert_eq!(
+            BuiltinGlue::from_spec_path(Path::new("zig")),
+            Some(BuiltinGlue::Zig)
+        );
+
+        // Only the bare names are built in; anything else is a path to a spec written in Roc,
+        // which is how to use a spec file that happens to be called `c` or `zig`.
+        for spec_path in ["./c", "./zig", "glue/c", "c.roc", "C", "RustGlue.roc"] {
+            assert_eq!(BuiltinGlue::from_spec_path(Path::new(spec_path)), None);
+        }
+    }
+}
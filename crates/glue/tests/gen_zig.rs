@@ -0,0 +1,563 @@
+mod helpers;
+
+#[cfg(test)]
+mod test_gen_zig {
+    use crate::helpers::generate_bindings_with;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+    use roc_glue::types::File;
+    use roc_glue::zig_glue::{self, HEADER};
+
+    #[test]
+    fn basic_record_aliased() {
+        let module = indoc!(
+            r#"
+            MyRcd : { a : U64, b : I128 }
+
+            main : MyRcd
+            main = { a: 1u64, b: 2i128 }
+            "#
+        );
+
+        let full_header = std::str::from_utf8(HEADER).unwrap().to_string() + "\n";
+        assert_eq!(
+            generate_bindings_with(module, zig_glue::emit),
+            vec![File {
+                name: "roc_app.zig".to_string(),
+                content: full_header
+                    + indoc!(
+                        r#"
+                    pub const MyRcd = extern struct {
+                        b: i128,
+                        a: u64,
+
+                        comptime {
+                            std.debug.assert(@sizeOf(@This()) == 32);
+                        }
+                    };
+                    "#
+                    )
+            }]
+        );
+    }
+
+    #[test]
+    fn nested_record_aliased() {
+        let module = indoc!(
+            r#"
+            Outer : { x : Inner, y : Str, z : List U8 }
+
+            Inner : { a : U16, b : F32 }
+
+            main : Outer
+            main = { x: { a: 5, b: 24 }, y: "foo", z: [1, 2] }
+            "#
+        );
+
+        let full_header = std::str::from_utf8(HEADER).unwrap().to_string() + "\n";
+        assert_eq!(
+            generate_bindings_with(module, zig_glue::emit),
+            vec![File {
+                name: "roc_app.zig".to_string(),
+                content: full_header
+                    + indoc!(
+                        r#"
+                    pub const Outer = switch (builtin.cpu.arch) {
+                        .arm, .wasm32, .i386 => extern struct {
+                            x: Inner,
+                            y: RocStr,
+                            z: RocList(u8),
+
+                            comptime {
+                                std.debug.assert(@sizeOf(@This()) == 32);
+                            }
+                        },
+                        .aarch64, .x86_64 => extern struct {
+                            y: RocStr,
+                            z: RocList(u8),
+                            x: Inner,
+
+                            comptime {
+                                std.debug.assert(@sizeOf(@This()) == 56);
+                            }
+                        },
+                        else => @compileError("Roc does not support this architecture"),
+                    };
+
+                    pub const Inner = extern struct {
+                        b: f32,
+                        a: u16,
+
+                        comptime {
+                            std.debug.assert(@sizeOf(@This()) == 8);
+                        }
+                    };
+                    "#
+                    )
+            }]
+        );
+    }
+
+    #[test]
+    fn enumeration() {
+        let module = indoc!(
+            r#"
+            Enumeration : [Blah, Foo, Bar,]
+
+            main : Enumeration
+            main = Foo
+            "#
+        );
+
+        let full_header = std::str::from_utf8(HEADER).unwrap().to_string() + "\n";
+        assert_eq!(
+            generate_bindings_with(module, zig_glue::emit),
+            vec![File {
+                name: "roc_app.zig".to_string(),
+                content: full_header
+                    + indoc!(
+                        r#"
+                    pub const Enumeration = enum(u8) {
+                        Bar = 0,
+                        Blah = 1,
+                        Foo = 2,
+                    };
+                    "#
+                    )
+            }]
+        );
+    }
+
+    #[test]
+    fn nullable_unwrapped() {
+        let module = indoc!(
+            r#"
+            StrConsList : [Nil, Cons Str StrConsList]
+
+            main : StrConsList
+            main = Nil
+            "#
+        );
+
+        let full_header = std::str::from_utf8(HEADER).unwrap().to_string() + "\n";
+        assert_eq!(
+            generate_bindings_with(module, zig_glue::emit),
+            vec![File {
+                name: "roc_app.zig".to_string(),
+                content: full_header
+                    + indoc!(
+                        r#"
+                    pub const discriminant_StrConsList = enum(u8) {
+                        Cons = 0,
+                        Nil = 1,
+                    };
+
+                    pub const StrConsList = extern struct {
+                        pointer: ?*StrConsList_Cons,
+
+                        pub fn discriminant(self: *const @This()) discriminant_StrConsList {
+                            return if (self.pointer == null) .Nil else .Cons;
+                        }
+
+                        pub fn initNil() @This() {
+                            return .{ .pointer = null };
+                        }
+
+                        pub fn initCons(payload: StrConsList_Cons) @This() {
+                            const alignment = comptime std.math.max(@alignOf(StrConsList_Cons), @sizeOf(isize));
+                            const allocation = @ptrCast([*]u8, roc_alloc(@sizeOf(StrConsList_Cons) + alignment, alignment).?);
+                            const data = @ptrCast(*StrConsList_Cons, @alignCast(@alignOf(StrConsList_Cons), allocation + alignment));
+                            // A reference count of 1
+                            @ptrCast(*isize, @alignCast(@alignOf(isize), allocation + alignment - @sizeOf(isize))).* = std.math.minInt(isize);
+                            data.* = payload;
+
+                            return .{ .pointer = data };
+                        }
+
+                        pub fn getCons(self: *const @This()) *const StrConsList_Cons {
+                            return self.pointer.?;
+                        }
+                    };
+
+                    pub const StrConsList_Cons = extern struct {
+                        f0: RocStr,
+                        f1: StrConsList,
+                    };
+                    "#
+                    )
+            }]
+        );
+    }
+
+    #[test]
+    fn recursive_union() {
+        let module = indoc!(
+            r#"
+            Expr : [String Str, Concat Expr Expr]
+
+            main : Expr
+            main = String "hello"
+            "#
+        );
+
+        let full_header = std::str::from_utf8(HEADER).unwrap().to_string() + "\n";
+        assert_eq!(
+            generate_bindings_with(module, zig_glue::emit),
+            vec![File {
+                name: "roc_app.zig".to_string(),
+                content: full_header
+                    + indoc!(
+                        r#"
+                    pub const discriminant_Expr = enum(u8) {
+                        Concat = 0,
+                        String = 1,
+                    };
+
+                    pub const Expr = switch (builtin.cpu.arch) {
+                        .arm, .wasm32, .i386 => extern struct {
+                            pointer: *anyopaque,
+
+                            pub const Union = extern union {
+                                Concat: Expr_Concat,
+                                String: Expr_String,
+                            };
+
+                            fn unionPointer(self: *const @This()) *Union {
+                                return @intToPtr(*Union, @ptrToInt(self.pointer) & ~@as(usize, 0b11));
+                            }
+
+                            pub fn discriminant(self: *const @This()) discriminant_Expr {
+                                return @intToEnum(discriminant_Expr, @truncate(u8, @ptrToInt(self.pointer) & 0b11));
+                            }
+
+                            pub fn initConcat(payload: Expr_Concat) @This() {
+                                const alignment = comptime std.math.max(@alignOf(Union), @sizeOf(isize));
+                                const allocation = @ptrCast([*]u8, roc_alloc(@sizeOf(Union) + alignment, alignment).?);
+                                const data = @ptrCast(*Union, @alignCast(@alignOf(Union), allocation + alignment));
+                                // A reference count of 1
+                                @ptrCast(*isize, @alignCast(@alignOf(isize), allocation + alignment - @sizeOf(isize))).* = std.math.minInt(isize);
+                                data.Concat = payload;
+
+                                return .{ .pointer = @intToPtr(*anyopaque, @ptrToInt(data) | @enumToInt(discriminant_Expr.Concat)) };
+                            }
+
+                            pub fn getConcat(self: *const @This()) *const Expr_Concat {
+                                return &self.unionPointer().Concat;
+                            }
+
+                            pub fn initString(payload: Expr_String) @This() {
+                                const alignment = comptime std.math.max(@alignOf(Union), @sizeOf(isize));
+                                const allocation = @ptrCast([*]u8, roc_alloc(@sizeOf(Union) + alignment, alignment).?);
+                                const data = @ptrCast(*Union, @alignCast(@alignOf(Union), allocation + alignment));
+                                // A reference count of 1
+                                @ptrCast(*isize, @alignCast(@alignOf(isize), allocation + alignment - @sizeOf(isize))).* = std.math.minInt(isize);
+                                data.String = payload;
+
+                                return .{ .pointer = @intToPtr(*anyopaque, @ptrToInt(data) | @enumToInt(discriminant_Expr.String)) };
+                            }
+
+                            pub fn getString(self: *const @This()) *const Expr_String {
+                                return &self.unionPointer().String;
+                            }
+                        },
+                        .aarch64, .x86_64 => extern struct {
+                            pointer: *anyopaque,
+
+                            pub const Union = extern union {
+                                Concat: Expr_Concat,
+                                String: Expr_String,
+                            };
+
+                            fn unionPointer(self: *const @This()) *Union {
+                                return @intToPtr(*Union, @ptrToInt(self.pointer) & ~@as(usize, 0b111));
+                            }
+
+                            pub fn discriminant(self: *const @This()) discriminant_Expr {
+                                return @intToEnum(discriminant_Expr, @truncate(u8, @ptrToInt(self.pointer) & 0b111));
+                            }
+
+                            pub fn initConcat(payload: Expr_Concat) @This() {
+                                const alignment = comptime std.math.max(@alignOf(Union), @sizeOf(isize));
+                                const allocation = @ptrCast([*]u8, roc_alloc(@sizeOf(Union) + alignment, alignment).?);
+                                const data = @ptrCast(*Union, @alignCast(@alignOf(Union), allocation + alignment));
+                                // A reference count of 1
+                                @ptrCast(*isize, @alignCast(@alignOf(isize), allocation + alignment - @sizeOf(isize))).* = std.math.minInt(isize);
+                                data.Concat = payload;
+
+                                return .{ .pointer = @intToPtr(*anyopaque, @ptrToInt(data) | @enumToInt(discriminant_Expr.Concat)) };
+                            }
+
+                            pub fn getConcat(self: *const @This()) *const Expr_Concat {
+                                return &self.unionPointer().Concat;
+                            }
+
+                            pub fn initString(payload: Expr_String) @This() {
+                                const alignment = comptime std.math.max(@alignOf(Union), @sizeOf(isize));
+                                const allocation = @ptrCast([*]u8, roc_alloc(@sizeOf(Union) + alignment, alignment).?);
+                                const data = @ptrCast(*Union, @alignCast(@alignOf(Union), allocation + alignment));
+                                // A reference count of 1
+                                @ptrCast(*isize, @alignCast(@alignOf(isize), allocation + alignment - @sizeOf(isize))).* = std.math.minInt(isize);
+                                data.String = payload;
+
+                                return .{ .pointer = @intToPtr(*anyopaque, @ptrToInt(data) | @enumToInt(discriminant_Expr.String)) };
+                            }
+
+                            pub fn getString(self: *const @This()) *const Expr_String {
+                                return &self.unionPointer().String;
+                            }
+                        },
+                        else => @compileError("Roc does not support this architecture"),
+                    };
+
+                    pub const Expr_String = extern struct {
+                        f0: RocStr,
+                    };
+
+                    pub const Expr_Concat = extern struct {
+                        f0: Expr,
+                        f1: Expr,
+                    };
+                    "#
+                    )
+            }]
+        );
+    }
+
+    #[test]
+    fn nullable_wrapped() {
+        let module = indoc!(
+            r#"
+            StrFingerTree : [Empty, Single Str, More Str StrFingerTree]
+
+            main : StrFingerTree
+            main = Empty
+            "#
+        );
+
+        let full_header = std::str::from_utf8(HEADER).unwrap().to_string() + "\n";
+        assert_eq!(
+            generate_bindings_with(module, zig_glue::emit),
+            vec![File {
+                name: "roc_app.zig".to_string(),
+                content: full_header
+                    + indoc!(
+                        r#"
+                    pub const discriminant_StrFingerTree = enum(u8) {
+                        Empty = 0,
+                        More = 1,
+                        Single = 2,
+                    };
+
+                    pub const StrFingerTree = switch (builtin.cpu.arch) {
+                        .arm, .wasm32, .i386 => extern struct {
+                            pointer: ?*anyopaque,
+
+                            pub const Union = extern union {
+                                More: StrFingerTree_More,
+                                Single: StrFingerTree_Single,
+                            };
+
+                            fn unionPointer(self: *const @This()) *Union {
+                                return @intToPtr(*Union, @ptrToInt(self.pointer.?) & ~@as(usize, 0b11));
+                            }
+
+                            pub fn discriminant(self: *const @This()) discriminant_StrFingerTree {
+                                if (self.pointer == null) {
+                                    return .Empty;
+                                }
+
+                                return @intToEnum(discriminant_StrFingerTree, @truncate(u8, @ptrToInt(self.pointer.?) & 0b11));
+                            }
+
+                            pub fn initEmpty() @This() {
+                                return .{ .pointer = null };
+                            }
+
+                            pub fn initMore(payload: StrFingerTree_More) @This() {
+                                const alignment = comptime std.math.max(@alignOf(Union), @sizeOf(isize));
+                                const allocation = @ptrCast([*]u8, roc_alloc(@sizeOf(Union) + alignment, alignment).?);
+                                const data = @ptrCast(*Union, @alignCast(@alignOf(Union), allocation + alignment));
+                                // A reference count of 1
+                                @ptrCast(*isize, @alignCast(@alignOf(isize), allocation + alignment - @sizeOf(isize))).* = std.math.minInt(isize);
+                                data.More = payload;
+
+                                return .{ .pointer = @intToPtr(*anyopaque, @ptrToInt(data) | @enumToInt(discriminant_StrFingerTree.More)) };
+                            }
+
+                            pub fn getMore(self: *const @This()) *const StrFingerTree_More {
+                                return &self.unionPointer().More;
+                            }
+
+                            pub fn initSingle(payload: StrFingerTree_Single) @This() {
+                                const alignment = comptime std.math.max(@alignOf(Union), @sizeOf(isize));
+                                const allocation = @ptrCast([*]u8, roc_alloc(@sizeOf(Union) + alignment, alignment).?);
+                                const data = @ptrCast(*Union, @alignCast(@alignOf(Union), allocation + alignment));
+                                // A reference count of 1
+                                @ptrCast(*isize, @alignCast(@alignOf(isize), allocation + alignment - @sizeOf(isize))).* = std.math.minInt(isize);
+                                data.Single = payload;
+
+                                return .{ .pointer = @intToPtr(*anyopaque, @ptrToInt(data) | @enumToInt(discriminant_StrFingerTree.Single)) };
+                            }
+
+                            pub fn getSingle(self: *const @This()) *const StrFingerTree_Single {
+                                return &self.unionPointer().Single;
+                            }
+                        },
+                        .aarch64, .x86_64 => extern struct {
+                            pointer: ?*anyopaque,
+
+                            pub const Union = extern union {
+                                More: StrFingerTree_More,
+                                Single: StrFingerTree_Single,
+                            };
+
+                            fn unionPointer(self: *const @This()) *Union {
+                                return @intToPtr(*Union, @ptrToInt(self.pointer.?) & ~@as(usize, 0b111));
+                            }
+
+                            pub fn discriminant(self: *const @This()) discriminant_StrFingerTree {
+                                if (self.pointer == null) {
+                                    return .Empty;
+                                }
+
+                                return @intToEnum(discriminant_StrFingerTree, @truncate(u8, @ptrToInt(self.pointer.?) & 0b111));
+                            }
+
+                            pub fn initEmpty() @This() {
+                                return .{ .pointer = null };
+                            }
+
+                            pub fn initMore(payload: StrFingerTree_More) @This() {
+                                const alignment = comptime std.math.max(@alignOf(Union), @sizeOf(isize));
+                                const allocation = @ptrCast([*]u8, roc_alloc(@sizeOf(Union) + alignment, alignment).?);
+                                const data = @ptrCast(*Union, @alignCast(@alignOf(Union), allocation + alignment));
+                                // A reference count of 1
+                                @ptrCast(*isize, @alignCast(@alignOf(isize), allocation + alignment - @sizeOf(isize))).* = std.math.minInt(isize);
+                                data.More = payload;
+
+                                return .{ .pointer = @intToPtr(*anyopaque, @ptrToInt(data) | @enumToInt(discriminant_StrFingerTree.More)) };
+                            }
+
+                            pub fn getMore(self: *const @This()) *const StrFingerTree_More {
+                                return &self.unionPointer().More;
+                            }
+
+                            pub fn initSingle(payload: StrFingerTree_Single) @This() {
+                                const alignment = comptime std.math.max(@alignOf(Union), @sizeOf(isize));
+                                const allocation = @ptrCast([*]u8, roc_alloc(@sizeOf(Union) + alignment, alignment).?);
+                                const data = @ptrCast(*Union, @alignCast(@alignOf(Union), allocation + alignment));
+                                // A reference count of 1
+                                @ptrCast(*isize, @alignCast(@alignOf(isize), allocation + alignment - @sizeOf(isize))).* = std.math.minInt(isize);
+                                data.Single = payload;
+
+                                return .{ .pointer = @intToPtr(*anyopaque, @ptrToInt(data) | @enumToInt(discriminant_StrFingerTree.Single)) };
+                            }
+
+                            pub fn getSingle(self: *const @This()) *const StrFingerTree_Single {
+                                return &self.unionPointer().Single;
+                            }
+                        },
+                        else => @compileError("Roc does not support this architecture"),
+                    };
+
+                    pub const StrFingerTree_Single = extern struct {
+                        f0: RocStr,
+                    };
+
+                    pub const StrFingerTree_More = extern struct {
+                        f0: RocStr,
+                        f1: StrFingerTree,
+                    };
+                    "#
+                    )
+            }]
+        );
+    }
+
+    #[test]
+    fn nonnullable_unwrapped() {
+        let module = indoc!(
+            r#"
+            StrRoseTree : [Tree Str (List StrRoseTree)]
+
+            main : StrRoseTree
+            main = Tree "root" []
+            "#
+        );
+
+        let full_header = std::str::from_utf8(HEADER).unwrap().to_string() + "\n";
+        assert_eq!(
+            generate_bindings_with(module, zig_glue::emit),
+            vec![File {
+                name: "roc_app.zig".to_string(),
+                content: full_header
+                    + indoc!(
+                        r#"
+                    pub const StrRoseTree = extern struct {
+                        pointer: *StrRoseTree_Tree,
+
+                        pub fn initTree(payload: StrRoseTree_Tree) @This() {
+                            const alignment = comptime std.math.max(@alignOf(StrRoseTree_Tree), @sizeOf(isize));
+                            const allocation = @ptrCast([*]u8, roc_alloc(@sizeOf(StrRoseTree_Tree) + alignment, alignment).?);
+                            const data = @ptrCast(*StrRoseTree_Tree, @alignCast(@alignOf(StrRoseTree_Tree), allocation + alignment));
+                            // A reference count of 1
+                            @ptrCast(*isize, @alignCast(@alignOf(isize), allocation + alignment - @sizeOf(isize))).* = std.math.minInt(isize);
+                            data.* = payload;
+
+                            return .{ .pointer = data };
+                        }
+
+                        pub fn getTree(self: *const @This()) *const StrRoseTree_Tree {
+                            return self.pointer;
+                        }
+                    };
+
+                    pub const StrRoseTree_Tree = extern struct {
+                        f0: RocStr,
+                        f1: RocList(StrRoseTree),
+                    };
+                    "#
+                    )
+            }]
+        );
+    }
+
+    #[test]
+    fn record_of_functions() {
+        let module = indoc!(
+            r#"
+            Ops : { f : I64, I64 -> I64, g : I64, I64 -> I64 }
+
+            main : Ops
+            main = { f: add, g: sub }
+
+            add : I64, I64 -> I64
+            add = \x, y -> x + y
+
+            sub : I64, I64 -> I64
+            sub = \x, y -> x - y
+            "#
+        );
+
+        let files = generate_bindings_with(module, zig_glue::emit);
+        let decls = &files[0].content;
+
+        // The functions' names come from their type variables, so rather than matching all
+        // the declarations, check what gets declared for each of them.
+        assert_eq!(
+            decls
+                .matches("_caller(arg_0: *const i64, arg_1: *const i64, closure_data: ?[*]u8, output: *i64) callconv(.C) void;")
+                .count(),
+            2,
+            "{decls}"
+        );
+        assert_eq!(
+            decls
+                .matches("pub fn forceThunk(self: *@This(), arg_0: i64, arg_1: i64) i64 {")
+                .count(),
+            2,
+            "{decls}"
+        );
+        assert!(decls.contains("pub const Ops = "), "{decls}");
+    }
+}
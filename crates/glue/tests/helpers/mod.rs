@@ -1,6 +1,7 @@
 use indoc::indoc;
 use roc_glue::load::{load_types, IgnoreErrors};
 use roc_glue::rust_glue;
+use roc_glue::types::Types;
 use roc_load::Threading;
 use std::env;
 use std::fs::File;
@@ -9,6 +10,15 @@ use std::path::PathBuf;
 
 #[allow(dead_code)]
 pub fn generate_bindings(decl_src: &str) -> Vec<roc_glue::types::File> {
+    generate_bindings_with(decl_src, rust_glue::emit)
+}
+
+/// Like [generate_bindings], but for any of the languages glue can be generated for.
+#[allow(dead_code)]
+pub fn generate_bindings_with(
+    decl_src: &str,
+    emit: fn(&[Types]) -> Vec<roc_glue::types::File>,
+) -> Vec<roc_glue::types::File> {
     use tempfile::tempdir;
 
     let mut src = indoc!(
@@ -46,7 +56,7 @@ pub fn generate_bindings(decl_src: &str) -> Vec<roc_glue::types::File> {
         result.expect("had problems loading")
     };
 
-    rust_glue::emit(&types)
+    emit(&types)
 }
 
 #[allow(dead_code)]
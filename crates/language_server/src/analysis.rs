@@ -0,0 +1,189 @@
+//! Typechecks documents with `roc_load` and answers queries about the result.
+use std::path::Path;
+
+use bumpalo::Bump;
+use roc_can::traverse::{find_closest_symbol_at, find_closest_type_at, find_symbol_binding_region};
+use roc_load::{LoadedModule, LoadingProblem};
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_problem::Severity;
+use roc_region::all::{LineInfo, Region};
+use roc_reporting::report::{
+    can_problem, type_problem, RenderTarget, Report, RocDocAllocator, DEFAULT_PALETTE,
+};
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
+
+use crate::protocol::{
+    path_to_uri, Diagnostic, DiagnosticSeverity, Hover, LineIndex, Location, MarkupContent,
+    Position, Range,
+};
+
+/// The result of typechecking one version of a document.
+pub struct Analysis {
+    /// The text that was typechecked. Positions in queries are resolved against it.
+    source: String,
+    module: LoadedModule,
+}
+
+/// Typechecks `source` as if it were the contents of the file at `path`, which means
+/// imports are resolved relative to the file's directory.
+///
+/// Returns the diagnostics to publish for the document, and the analysis if loading got far
+/// enough to answer queries.
+pub fn analyze(path: &Path, source: &str) -> (Vec<Diagnostic>, Option<Analysis>) {
+    let arena = Bump::new();
+    let src_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let cache_dir = cache::roc_cache_dir();
+
+    let loaded = roc_load::load_and_typecheck_str(
+        &arena,
+        path.to_path_buf(),
+        source,
+        src_dir,
+        // We only typecheck, so the target doesn't matter.
+        roc_target::TargetInfo::default_x86_64(),
+        RenderTarget::Generic,
+        RocCacheDir::Persistent(cache_dir.as_path()),
+        DEFAULT_PALETTE,
+    );
+
+    match loaded {
+        Ok(module) => {
+            let diagnostics = module_diagnostics(&module);
+            let analysis = Analysis {
+                source: source.to_string(),
+                module,
+            };
+
+            (diagnostics, Some(analysis))
+        }
+        Err(LoadingProblem::FormattedReport(report)) => {
+            // Parse errors and the like only come back as an already rendered report,
+            // so we report them at the start of the document.
+            (vec![whole_document_diagnostic(report)], None)
+        }
+        Err(other) => (
+            vec![whole_document_diagnostic(format!(
+                "The compiler could not load this module: {:?}",
+                other
+            ))],
+            None,
+        ),
+    }
+}
+
+pub(crate) fn whole_document_diagnostic(message: String) -> Diagnostic {
+    let start = Position {
+        line: 0,
+        character: 0,
+    };
+
+    Diagnostic {
+        range: Range { start, end: start },
+        severity: DiagnosticSeverity::Error,
+        code: None,
+        source: "roc",
+        message: message.trim().to_string(),
+    }
+}
+
+fn module_diagnostics(module: &LoadedModule) -> Vec<Diagnostic> {
+    let home = module.module_id;
+
+    let (module_path, src) = match module.sources.get(&home) {
+        Some(source) => source,
+        None => return Vec::new(),
+    };
+
+    let src_lines: Vec<&str> = src.split('\n').collect();
+    let lines = LineInfo::new(src);
+    let line_index = LineIndex::new(src);
+    let alloc = RocDocAllocator::new(&src_lines, home, &module.interns);
+
+    let mut diagnostics = Vec::new();
+
+    for problem in module.can_problems.get(&home).into_iter().flatten() {
+        let region = problem.region();
+        let report = can_problem(&alloc, &lines, module_path.clone(), problem.clone());
+
+        diagnostics.push(report_to_diagnostic(report, region, &alloc, &line_index));
+    }
+
+    for problem in module.type_problems.get(&home).into_iter().flatten() {
+        let region = problem.region();
+
+        if let Some(report) = type_problem(&alloc, &lines, module_path.clone(), problem.clone()) {
+            diagnostics.push(report_to_diagnostic(report, region, &alloc, &line_index));
+        }
+    }
+
+    diagnostics
+}
+
+fn report_to_diagnostic<'b>(
+    mut report: Report<'b>,
+    region: Option<Region>,
+    alloc: &'b RocDocAllocator<'b>,
+    line_index: &LineIndex,
+) -> Diagnostic {
+    // The title goes into the diagnostic's code; rendering without it leaves out the header.
+    let title = std::mem::take(&mut report.title);
+    let severity = match report.severity {
+        Severity::Warning => DiagnosticSeverity::Warning,
+        Severity::RuntimeError | Severity::Fatal => DiagnosticSeverity::Error,
+    };
+
+    let mut message = String::new();
+    report.render_ci(&mut message, alloc);
+
+    let range = region
+        .map(|region| line_index.range(region))
+        .unwrap_or_else(|| line_index.range(Region::zero()));
+
+    Diagnostic {
+        range,
+        severity,
+        code: Some(title),
+        source: "roc",
+        message: message.trim().to_string(),
+    }
+}
+
+impl Analysis {
+    /// Shows the type of the innermost expression or pattern at `position`.
+    pub fn hover(&mut self, position: Position) -> Option<Hover> {
+        let home = self.module.module_id;
+        let offset = LineIndex::new(&self.source).offset(position)?;
+        let decls = self.module.declarations_by_id.get(&home)?;
+        let var = find_closest_type_at(offset, decls)?;
+
+        let subs = self.module.solved.inner_mut();
+        let snapshot = subs.snapshot();
+        let typ = name_and_print_var(var, subs, home, &self.module.interns, DebugPrint::NOTHING);
+        subs.rollback_to(snapshot);
+
+        Some(Hover {
+            contents: MarkupContent {
+                kind: "markdown",
+                value: format!("```roc\n{}\n```", typ),
+            },
+        })
+    }
+
+    /// Finds where the symbol at `position` is bound, which may be in another module.
+    pub fn definition(&self, position: Position) -> Option<Location> {
+        let home = self.module.module_id;
+        let offset = LineIndex::new(&self.source).offset(position)?;
+        let symbol = find_closest_symbol_at(offset, self.module.declarations_by_id.get(&home)?)?;
+
+        let module_id = symbol.module_id();
+        let decls = self.module.declarations_by_id.get(&module_id)?;
+        let region = find_symbol_binding_region(symbol, decls)?;
+
+        let (path, src) = self.module.sources.get(&module_id)?;
+
+        Some(Location {
+            uri: path_to_uri(path),
+            range: LineIndex::new(src).range(region),
+        })
+    }
+}
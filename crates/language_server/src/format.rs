@@ -0,0 +1,51 @@
+//! Formatting of whole documents via `roc_fmt`.
+use bumpalo::Bump;
+use roc_fmt::def::fmt_defs;
+use roc_fmt::module::fmt_module;
+use roc_fmt::spaces::RemoveSpaces;
+use roc_fmt::{Ast, Buf};
+use roc_parse::{
+    module::{self, module_defs},
+    parser::{Parser, SyntaxError},
+    state::State,
+};
+
+fn parse_all<'a>(arena: &'a Bump, src: &'a str) -> Result<Ast<'a>, SyntaxError<'a>> {
+    let (module, state) = module::parse_header(arena, State::new(src.as_bytes()))
+        .map_err(|e| SyntaxError::Header(e.problem))?;
+
+    let (_, defs, _) = module_defs().parse(arena, state, 0).map_err(|(_, e)| e)?;
+
+    Ok(Ast { module, defs })
+}
+
+fn fmt_all<'a>(buf: &mut Buf<'a>, ast: &'a Ast) {
+    fmt_module(buf, &ast.module);
+
+    fmt_defs(buf, &ast.defs, 0);
+
+    buf.fmt_end_of_file();
+}
+
+/// Formats a whole module. Returns `None` if the source doesn't parse, or if formatting
+/// would change the meaning of the code; in both cases the document must be left alone.
+pub fn format_src(src: &str) -> Option<String> {
+    let arena = Bump::new();
+
+    let ast = arena.alloc(parse_all(&arena, src).ok()?);
+    let mut buf = Buf::new_in(&arena);
+    fmt_all(&mut buf, ast);
+
+    let reparsed_ast = parse_all(&arena, buf.as_str()).ok()?;
+
+    // Same check as `roc format`: compare the debug output of both trees, because the
+    // PartialEq impls on the ast types are not reliable.
+    let ast_normalized = ast.remove_spaces(&arena);
+    let reparsed_ast_normalized = reparsed_ast.remove_spaces(&arena);
+
+    if format!("{:?}", ast_normalized) != format!("{:?}", reparsed_ast_normalized) {
+        return None;
+    }
+
+    Some(buf.as_str().to_string())
+}
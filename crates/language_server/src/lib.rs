@@ -0,0 +1,11 @@
+//! A language server for Roc. It speaks the [Language Server Protocol](https://microsoft.github.io/language-server-protocol/)
+//! over stdio, publishing compiler reports as diagnostics and serving hover types,
+//! go-to-definition and formatting. Each workspace root is typechecked on its own thread.
+mod analysis;
+mod format;
+pub mod protocol;
+mod rpc;
+mod server;
+mod worker;
+
+pub use server::run;
@@ -0,0 +1,277 @@
+//! The subset of Language Server Protocol types the server understands.
+//!
+//! Field names follow the specification, which is why everything is serialized in camelCase.
+use serde::{Deserialize, Serialize};
+
+/// A position in a document, where `character` counts UTF-16 code units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    pub line: u32,
+    pub character: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    pub uri: String,
+    pub range: Range,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeParams {
+    pub root_uri: Option<String>,
+    /// Takes precedence over `root_uri` for clients which support multiple folders.
+    pub workspace_folders: Option<Vec<WorkspaceFolder>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkspaceFolder {
+    pub uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TextDocumentIdentifier {
+    pub uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentItem {
+    pub uri: String,
+    pub version: i32,
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VersionedTextDocumentIdentifier {
+    pub uri: String,
+    pub version: i32,
+}
+
+/// We only advertise full document sync, so every change carries the whole text.
+#[derive(Debug, Deserialize)]
+pub struct TextDocumentContentChangeEvent {
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidOpenTextDocumentParams {
+    pub text_document: TextDocumentItem,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidChangeTextDocumentParams {
+    pub text_document: VersionedTextDocumentIdentifier,
+    pub content_changes: Vec<TextDocumentContentChangeEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidSaveTextDocumentParams {
+    pub text_document: TextDocumentIdentifier,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidCloseTextDocumentParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentPositionParams {
+    pub text_document: TextDocumentIdentifier,
+    pub position: Position,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentFormattingParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    Error = 1,
+    Warning = 2,
+}
+
+impl Serialize for DiagnosticSeverity {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(*self as u8)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub range: Range,
+    pub severity: DiagnosticSeverity,
+    /// The report title, e.g. `TYPE MISMATCH`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    pub source: &'static str,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PublishDiagnosticsParams<'a> {
+    pub uri: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<i32>,
+    pub diagnostics: &'a [Diagnostic],
+}
+
+#[derive(Debug, Serialize)]
+pub struct MarkupContent {
+    pub kind: &'static str,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Hover {
+    pub contents: MarkupContent,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextEdit {
+    pub range: Range,
+    pub new_text: String,
+}
+
+/// Converts between roc's byte offsets and LSP positions for one source text.
+pub struct LineIndex<'a> {
+    src: &'a str,
+    line_info: roc_region::all::LineInfo,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(src: &'a str) -> Self {
+        Self {
+            src,
+            line_info: roc_region::all::LineInfo::new(src),
+        }
+    }
+
+    fn line_text(&self, line: u32) -> Option<&'a str> {
+        self.src.split('\n').nth(line as usize)
+    }
+
+    pub fn position(&self, pos: roc_region::all::Position) -> Position {
+        let line_column = self.line_info.convert_pos(pos);
+        let line_text = self.line_text(line_column.line).unwrap_or("");
+        let byte_column = (line_column.column as usize).min(line_text.len());
+
+        let character = line_text
+            .get(..byte_column)
+            .map(|prefix| prefix.encode_utf16().count())
+            .unwrap_or(byte_column);
+
+        Position {
+            line: line_column.line,
+            character: character as u32,
+        }
+    }
+
+    pub fn range(&self, region: roc_region::all::Region) -> Range {
+        Range {
+            start: self.position(region.start()),
+            end: self.position(region.end()),
+        }
+    }
+
+    /// Returns `None` if the position lies outside of the source.
+    pub fn offset(&self, position: Position) -> Option<roc_region::all::Position> {
+        let line_text = self.line_text(position.line)?;
+        let mut utf16_column = 0;
+        let mut byte_column = line_text.len();
+
+        for (index, ch) in line_text.char_indices() {
+            if utf16_column >= position.character as usize {
+                byte_column = index;
+                break;
+            }
+
+            utf16_column += ch.len_utf16();
+        }
+
+        let line_start = self
+            .line_info
+            .convert_line_column(roc_region::all::LineColumn {
+                line: position.line,
+                column: 0,
+            });
+
+        Some(line_start.bump_column(byte_column as u32))
+    }
+
+    /// The range spanning the whole source, used to replace it wholesale.
+    pub fn full_range(&self) -> Range {
+        Range {
+            start: Position {
+                line: 0,
+                character: 0,
+            },
+            end: self.position(roc_region::all::Position::new(self.src.len() as u32)),
+        }
+    }
+}
+
+/// Turns a `file://` URI into a path, decoding percent-escapes.
+pub fn uri_to_path(uri: &str) -> Option<std::path::PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'%' && index + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[index + 1..index + 3]).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+
+    let path = String::from_utf8(decoded).ok()?;
+
+    // On Windows, file URIs look like file:///C:/path
+    #[cfg(windows)]
+    let path = path.trim_start_matches('/').to_string();
+
+    Some(std::path::PathBuf::from(path))
+}
+
+/// Turns an absolute path into a `file://` URI, percent-encoding anything but unreserved
+/// characters and path separators.
+pub fn path_to_uri(path: &std::path::Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    uri
+}
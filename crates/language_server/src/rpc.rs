@@ -0,0 +1,127 @@
+//! JSON-RPC 2.0 messages, framed with `Content-Length` headers as the Language Server
+//! Protocol requires.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{self, BufRead, Write};
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const INTERNAL_ERROR: i64 = -32603;
+
+/// A message sent by the client. Requests have an `id`, notifications don't.
+/// Responses to requests we sent have an `id` but no `method`.
+#[derive(Debug, Deserialize)]
+pub struct Incoming {
+    pub id: Option<Value>,
+    pub method: Option<String>,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResponseError {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Response<'a> {
+    jsonrpc: &'static str,
+    id: &'a Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ResponseError>,
+}
+
+#[derive(Debug, Serialize)]
+struct Notification<'a, P> {
+    jsonrpc: &'static str,
+    method: &'a str,
+    params: P,
+}
+
+/// Reads the next message, returning `None` once the input is exhausted.
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Result<Incoming, String>>> {
+    let mut content_length = None;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let header = line.trim_end_matches(['\r', '\n']);
+
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let content_length = match content_length {
+        Some(len) => len,
+        None => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "message header did not contain a valid Content-Length",
+            ))
+        }
+    };
+
+    let mut content = vec![0; content_length];
+    reader.read_exact(&mut content)?;
+
+    Ok(Some(
+        serde_json::from_slice(&content).map_err(|err| err.to_string()),
+    ))
+}
+
+fn write_content(writer: &mut impl Write, content: &[u8]) -> io::Result<()> {
+    write!(writer, "Content-Length: {}\r\n\r\n", content.len())?;
+    writer.write_all(content)?;
+    writer.flush()
+}
+
+pub fn write_response(
+    writer: &mut impl Write,
+    id: &Value,
+    result: Result<Value, ResponseError>,
+) -> io::Result<()> {
+    let (result, error) = match result {
+        Ok(value) => (Some(value), None),
+        Err(error) => (None, Some(error)),
+    };
+
+    let response = Response {
+        jsonrpc: "2.0",
+        id,
+        result,
+        error,
+    };
+
+    write_content(writer, &serde_json::to_vec(&response)?)
+}
+
+pub fn write_notification<P: Serialize>(
+    writer: &mut impl Write,
+    method: &str,
+    params: P,
+) -> io::Result<()> {
+    let notification = Notification {
+        jsonrpc: "2.0",
+        method,
+        params,
+    };
+
+    write_content(writer, &serde_json::to_vec(&notification)?)
+}
@@ -0,0 +1,546 @@
+//! Dispatches incoming messages and keeps track of the documents open in the editor,
+//! grouped by the workspace root they belong to.
+//!
+//! Typechecking happens on one worker thread per workspace root, so this loop only reads
+//! messages, decides when documents need typechecking, and writes out what the workers send back.
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use roc_collections::MutMap;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::format::format_src;
+use crate::protocol::{
+    uri_to_path, Diagnostic, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, DidSaveTextDocumentParams, DocumentFormattingParams,
+    InitializeParams, LineIndex, PublishDiagnosticsParams, TextDocumentPositionParams, TextEdit,
+};
+use crate::rpc::{
+    read_message, write_notification, write_response, Incoming, ResponseError, INVALID_PARAMS,
+    INVALID_REQUEST, METHOD_NOT_FOUND, PARSE_ERROR,
+};
+use crate::worker::{Job, Query, Worker};
+
+/// Full document sync: every change notification carries the whole text.
+const TEXT_DOCUMENT_SYNC_FULL: u8 = 1;
+
+/// How long a document has to go without edits before it gets typechecked,
+/// so that typing doesn't start an analysis on every keystroke.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// What the message loop waits on: messages from the client, and results from the workers.
+pub(crate) enum Event {
+    Message(Result<Incoming, String>),
+    /// The client closed its end of the input, or reading from it failed.
+    InputClosed(io::Result<()>),
+    Analyzed {
+        uri: String,
+        version: i32,
+        diagnostics: Vec<Diagnostic>,
+    },
+    Answered {
+        id: Value,
+        result: Result<Value, ResponseError>,
+    },
+    WorkerStopped,
+}
+
+struct Document {
+    version: i32,
+    text: String,
+    /// When the document should next be typechecked, if it has changed since it was last
+    /// handed to the worker.
+    scheduled: Option<Instant>,
+}
+
+/// The open documents under one workspace root, and the worker which typechecks them.
+struct Workspace {
+    documents: MutMap<String, Document>,
+    worker: Worker,
+}
+
+impl Workspace {
+    /// Hands every document whose time has come to the worker.
+    fn analyze_due(&mut self, now: Instant) {
+        for (uri, doc) in self.documents.iter_mut() {
+            if !doc.scheduled.map_or(false, |scheduled| scheduled <= now) {
+                continue;
+            }
+
+            doc.scheduled = None;
+
+            // We can only resolve imports for documents that live on disk.
+            if let Some(path) = uri_to_path(uri) {
+                self.worker.send(Job::Analyze {
+                    uri: uri.clone(),
+                    version: doc.version,
+                    path,
+                    text: doc.text.clone(),
+                });
+            }
+        }
+    }
+
+    /// Typechecks the document's pending edits right away, rather than after the debounce.
+    fn analyze_now(&mut self, uri: &str) {
+        let now = Instant::now();
+
+        if let Some(doc) = self.documents.get_mut(uri) {
+            if doc.scheduled.is_some() {
+                doc.scheduled = Some(now);
+            }
+        }
+
+        self.analyze_due(now);
+    }
+}
+
+enum Shutdown {
+    NotRequested,
+    /// The workers are finishing what they were given, so that no diagnostics get lost.
+    /// The `shutdown` request is answered once they're done.
+    Pending {
+        id: Value,
+        running_workers: usize,
+        exit_requested: bool,
+    },
+    Done,
+}
+
+pub struct Server<W> {
+    writer: W,
+    events: Sender<Event>,
+    /// The workspace folders the client opened. Documents outside all of them are grouped
+    /// by the directory they're in.
+    roots: Vec<PathBuf>,
+    workspaces: MutMap<PathBuf, Workspace>,
+    shutdown: Shutdown,
+}
+
+/// Serves one client until it sends `exit` or closes the input, and returns the exit code
+/// the process should use.
+pub fn run<R, W>(reader: R, writer: W) -> io::Result<i32>
+where
+    R: BufRead + Send + 'static,
+    W: Write,
+{
+    let (events, receiver) = mpsc::channel();
+    let input = events.clone();
+
+    thread::spawn(move || read_input(reader, input));
+
+    let mut server = Server {
+        writer,
+        events,
+        roots: Vec::new(),
+        workspaces: MutMap::default(),
+        shutdown: Shutdown::NotRequested,
+    };
+
+    loop {
+        // The server holds on to a sender, so the channel can't disconnect.
+        let event = match server.next_scheduled() {
+            Some(scheduled) => {
+                match receiver.recv_timeout(scheduled.saturating_duration_since(Instant::now())) {
+                    Ok(event) => Some(event),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => unreachable!(),
+                }
+            }
+            None => Some(receiver.recv().unwrap()),
+        };
+
+        if let Some(event) = event {
+            if let Some(exit_code) = server.handle_event(event)? {
+                return Ok(exit_code);
+            }
+        }
+
+        let now = Instant::now();
+
+        for workspace in server.workspaces.values_mut() {
+            workspace.analyze_due(now);
+        }
+    }
+}
+
+fn read_input(mut reader: impl BufRead, events: Sender<Event>) {
+    loop {
+        let event = match read_message(&mut reader) {
+            Ok(Some(message)) => Event::Message(message),
+            Ok(None) => Event::InputClosed(Ok(())),
+            Err(err) => Event::InputClosed(Err(err)),
+        };
+        let closed = matches!(event, Event::InputClosed(_));
+
+        if events.send(event).is_err() || closed {
+            return;
+        }
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, ResponseError> {
+    serde_json::from_value(params).map_err(|err| ResponseError {
+        code: INVALID_PARAMS,
+        message: err.to_string(),
+    })
+}
+
+impl<W: Write> Server<W> {
+    /// Returns the exit code once the server should exit.
+    fn handle_event(&mut self, event: Event) -> io::Result<Option<i32>> {
+        match event {
+            Event::Message(Ok(Incoming { id, method, params })) => match (id, method) {
+                (Some(id), Some(method)) => match self.handle_request(&id, &method, params) {
+                    Ok(Some(result)) => write_response(&mut self.writer, &id, Ok(result))?,
+                    // A worker will answer it.
+                    Ok(None) => {}
+                    Err(error) => write_response(&mut self.writer, &id, Err(error))?,
+                },
+                (None, Some(method)) if method == "exit" => return Ok(self.exit()),
+                (None, Some(method)) => self.handle_notification(&method, params)?,
+                (_, None) => {
+                    // A response to a request we sent; we never send any.
+                }
+            },
+            Event::Message(Err(message)) => {
+                let error = ResponseError {
+                    code: PARSE_ERROR,
+                    message,
+                };
+
+                write_response(&mut self.writer, &Value::Null, Err(error))?;
+            }
+            Event::InputClosed(result) => {
+                result?;
+
+                match self.shutdown {
+                    // We've been told to exit, and will once the workers are done.
+                    Shutdown::Pending {
+                        exit_requested: true,
+                        ..
+                    } => {}
+                    // The client went away without asking us to exit.
+                    _ => return Ok(Some(1)),
+                }
+            }
+            Event::Analyzed {
+                uri,
+                version,
+                diagnostics,
+            } => {
+                // If the document has been edited since, its diagnostics are out of date,
+                // and the ones for the newer version will follow.
+                let is_current = self
+                    .workspaces
+                    .get(&self.root_of(&uri))
+                    .and_then(|workspace| workspace.documents.get(&uri))
+                    .map_or(false, |doc| doc.version == version);
+
+                if is_current {
+                    self.publish_diagnostics(&uri, Some(version), &diagnostics)?;
+                }
+            }
+            Event::Answered { id, result } => write_response(&mut self.writer, &id, result)?,
+            Event::WorkerStopped => {
+                if let Shutdown::Pending {
+                    id,
+                    running_workers,
+                    exit_requested,
+                } = &mut self.shutdown
+                {
+                    *running_workers -= 1;
+
+                    if *running_workers == 0 {
+                        write_response(&mut self.writer, id, Ok(Value::Null))?;
+
+                        let exit_requested = *exit_requested;
+                        self.shutdown = Shutdown::Done;
+
+                        if exit_requested {
+                            return Ok(Some(0));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns `Ok(None)` for requests which a worker will answer.
+    fn handle_request(
+        &mut self,
+        id: &Value,
+        method: &str,
+        params: Value,
+    ) -> Result<Option<Value>, ResponseError> {
+        if !matches!(self.shutdown, Shutdown::NotRequested) {
+            return Err(ResponseError {
+                code: INVALID_REQUEST,
+                message: "The server is shutting down.".to_string(),
+            });
+        }
+
+        match method {
+            "initialize" => {
+                let params: InitializeParams = parse_params(params).unwrap_or_default();
+                let root_uris = match params.workspace_folders {
+                    Some(folders) => folders.into_iter().map(|folder| folder.uri).collect(),
+                    None => params.root_uri.into_iter().collect::<Vec<_>>(),
+                };
+
+                self.roots = root_uris
+                    .iter()
+                    .filter_map(|uri| uri_to_path(uri))
+                    .collect();
+
+                Ok(Some(json!({
+                    "capabilities": {
+                        "textDocumentSync": TEXT_DOCUMENT_SYNC_FULL,
+                        "hoverProvider": true,
+                        "definitionProvider": true,
+                        "documentFormattingProvider": true,
+                    },
+                    "serverInfo": {
+                        "name": "roc_language_server",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                })))
+            }
+            "shutdown" => {
+                let now = Instant::now();
+
+                // Edits still waiting out the debounce get typechecked, so the client ends up
+                // with diagnostics for the last thing it sent.
+                for workspace in self.workspaces.values_mut() {
+                    for doc in workspace.documents.values_mut() {
+                        if doc.scheduled.is_some() {
+                            doc.scheduled = Some(now);
+                        }
+                    }
+
+                    workspace.analyze_due(now);
+                    workspace.worker.send(Job::Stop);
+                }
+
+                if self.workspaces.is_empty() {
+                    self.shutdown = Shutdown::Done;
+
+                    Ok(Some(Value::Null))
+                } else {
+                    self.shutdown = Shutdown::Pending {
+                        id: id.clone(),
+                        running_workers: self.workspaces.len(),
+                        exit_requested: false,
+                    };
+
+                    Ok(None)
+                }
+            }
+            "textDocument/hover" | "textDocument/definition" => {
+                let params: TextDocumentPositionParams = parse_params(params)?;
+                let uri = params.text_document.uri;
+                let query = match method {
+                    "textDocument/hover" => Query::Hover(params.position),
+                    _ => Query::Definition(params.position),
+                };
+
+                let root = self.root_of(&uri);
+
+                match self.workspaces.get_mut(&root) {
+                    Some(workspace) if workspace.documents.contains_key(&uri) => {
+                        // The answer should take the latest edits into account.
+                        workspace.analyze_now(&uri);
+                        workspace.worker.send(Job::Query {
+                            id: id.clone(),
+                            uri,
+                            query,
+                        });
+
+                        Ok(None)
+                    }
+                    _ => Ok(Some(Value::Null)),
+                }
+            }
+            "textDocument/formatting" => {
+                let params: DocumentFormattingParams = parse_params(params)?;
+                let uri = params.text_document.uri;
+                let doc = match self
+                    .workspaces
+                    .get(&self.root_of(&uri))
+                    .and_then(|workspace| workspace.documents.get(&uri))
+                {
+                    Some(doc) => doc,
+                    None => return Ok(Some(Value::Null)),
+                };
+
+                let edits = format_src(&doc.text).map(|formatted| {
+                    if formatted == doc.text {
+                        Vec::new()
+                    } else {
+                        vec![TextEdit {
+                            range: LineIndex::new(&doc.text).full_range(),
+                            new_text: formatted,
+                        }]
+                    }
+                });
+
+                Ok(Some(serde_json::to_value(edits).unwrap()))
+            }
+            _ => Err(ResponseError {
+                code: METHOD_NOT_FOUND,
+                message: format!("Unsupported method: {}", method),
+            }),
+        }
+    }
+
+    fn handle_notification(&mut self, method: &str, params: Value) -> io::Result<()> {
+        if !matches!(self.shutdown, Shutdown::NotRequested) {
+            // Only `exit` is expected after `shutdown`.
+            return Ok(());
+        }
+
+        // Malformed notifications can't be answered, so they are dropped.
+        match method {
+            "textDocument/didOpen" => {
+                if let Ok(params) = parse_params::<DidOpenTextDocumentParams>(params) {
+                    let item = params.text_document;
+                    let doc = Document {
+                        version: item.version,
+                        text: item.text,
+                        scheduled: Some(Instant::now()),
+                    };
+
+                    self.workspace_mut(&item.uri)
+                        .documents
+                        .insert(item.uri, doc);
+                }
+            }
+            "textDocument/didChange" => {
+                if let Ok(params) = parse_params::<DidChangeTextDocumentParams>(params) {
+                    let uri = params.text_document.uri;
+
+                    let root = self.root_of(&uri);
+
+                    if let (Some(doc), Some(change)) = (
+                        self.workspaces
+                            .get_mut(&root)
+                            .and_then(|workspace| workspace.documents.get_mut(&uri)),
+                        params.content_changes.into_iter().last(),
+                    ) {
+                        doc.version = params.text_document.version;
+                        doc.text = change.text;
+                        doc.scheduled = Some(Instant::now() + DEBOUNCE);
+                    }
+                }
+            }
+            "textDocument/didSave" => {
+                if let Ok(params) = parse_params::<DidSaveTextDocumentParams>(params) {
+                    let uri = params.text_document.uri;
+                    let workspace = match self.workspaces.get_mut(&self.root_of(&uri)) {
+                        Some(workspace) => workspace,
+                        None => return Ok(()),
+                    };
+
+                    if let (Some(doc), Some(text)) =
+                        (workspace.documents.get_mut(&uri), params.text)
+                    {
+                        doc.text = text;
+                    }
+
+                    // Other open documents in the workspace may import the one that was saved.
+                    let now = Instant::now();
+
+                    for doc in workspace.documents.values_mut() {
+                        doc.scheduled = Some(now);
+                    }
+                }
+            }
+            "textDocument/didClose" => {
+                if let Ok(params) = parse_params::<DidCloseTextDocumentParams>(params) {
+                    let uri = params.text_document.uri;
+
+                    if let Some(workspace) = self.workspaces.get_mut(&self.root_of(&uri)) {
+                        workspace.documents.remove(&uri);
+                        workspace.worker.send(Job::Close { uri: uri.clone() });
+                    }
+
+                    self.publish_diagnostics(&uri, None, &[])?;
+                }
+            }
+            _ => {
+                // Includes `initialized` and `$/`-prefixed notifications, which we may ignore.
+            }
+        }
+
+        Ok(())
+    }
+
+    fn exit(&mut self) -> Option<i32> {
+        match &mut self.shutdown {
+            Shutdown::NotRequested => Some(1),
+            Shutdown::Pending { exit_requested, .. } => {
+                *exit_requested = true;
+
+                None
+            }
+            Shutdown::Done => Some(0),
+        }
+    }
+
+    /// The innermost workspace folder containing the document, or else its directory.
+    fn root_of(&self, uri: &str) -> PathBuf {
+        let path = match uri_to_path(uri) {
+            Some(path) => path,
+            // Documents which aren't on disk are never typechecked, so they can share a workspace.
+            None => return PathBuf::new(),
+        };
+
+        self.roots
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+            .cloned()
+            .or_else(|| path.parent().map(Path::to_path_buf))
+            .unwrap_or_default()
+    }
+
+    fn workspace_mut(&mut self, uri: &str) -> &mut Workspace {
+        let root = self.root_of(uri);
+        let events = &self.events;
+
+        self.workspaces.entry(root).or_insert_with(|| Workspace {
+            documents: MutMap::default(),
+            worker: Worker::spawn(events.clone()),
+        })
+    }
+
+    /// The soonest any document is scheduled to be typechecked.
+    fn next_scheduled(&self) -> Option<Instant> {
+        self.workspaces
+            .values()
+            .flat_map(|workspace| workspace.documents.values())
+            .filter_map(|doc| doc.scheduled)
+            .min()
+    }
+
+    fn publish_diagnostics(
+        &mut self,
+        uri: &str,
+        version: Option<i32>,
+        diagnostics: &[Diagnostic],
+    ) -> io::Result<()> {
+        let params = PublishDiagnosticsParams {
+            uri,
+            version,
+            diagnostics,
+        };
+
+        write_notification(&mut self.writer, "textDocument/publishDiagnostics", params)
+    }
+}
@@ -0,0 +1,201 @@
+//! Each workspace root gets a worker thread, which typechecks the workspace's documents and
+//! answers queries about them, so that slow analysis never holds up the message loop.
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use roc_collections::MutMap;
+use serde_json::Value;
+
+use crate::analysis::{analyze, whole_document_diagnostic, Analysis};
+use crate::protocol::{Diagnostic, Position};
+use crate::rpc::{ResponseError, INTERNAL_ERROR};
+use crate::server::Event;
+
+pub enum Query {
+    Hover(Position),
+    Definition(Position),
+}
+
+pub enum Job {
+    Analyze {
+        uri: String,
+        version: i32,
+        path: PathBuf,
+        text: String,
+    },
+    /// Queries are answered after every job sent before them, so they see the latest analysis.
+    Query {
+        id: Value,
+        uri: String,
+        query: Query,
+    },
+    Close {
+        uri: String,
+    },
+    /// Finishes the jobs sent before this one, then stops the worker.
+    Stop,
+}
+
+pub struct Worker {
+    jobs: Sender<Job>,
+}
+
+impl Worker {
+    pub fn spawn(events: Sender<Event>) -> Self {
+        let (jobs, receiver) = mpsc::channel();
+
+        thread::spawn(move || work(receiver, events));
+
+        Self { jobs }
+    }
+
+    pub fn send(&self, job: Job) {
+        // Once the worker has stopped, there's nothing left to do with the job.
+        let _ = self.jobs.send(job);
+    }
+}
+
+fn work(jobs: Receiver<Job>, events: Sender<Event>) {
+    // The most recent successful analysis of each document. This may be for an older version
+    // of the text if the latest edit doesn't parse, which is still better than nothing for queries.
+    let mut analyses: MutMap<String, Analysis> = MutMap::default();
+
+    while let Ok(job) = jobs.recv() {
+        // More edits may have come in while the last analysis ran; take them all at once,
+        // so that versions which have already been replaced aren't typechecked.
+        let batch: Vec<Job> = std::iter::once(job).chain(jobs.try_iter()).collect();
+
+        for (index, job) in batch.iter().enumerate() {
+            let event = match job {
+                Job::Analyze { .. } if is_superseded(&batch, index) => continue,
+                Job::Analyze {
+                    uri,
+                    version,
+                    path,
+                    text,
+                } => {
+                    let (diagnostics, analysis) = analyze_catching_panics(path, text);
+
+                    if let Some(analysis) = analysis {
+                        analyses.insert(uri.clone(), analysis);
+                    }
+
+                    Event::Analyzed {
+                        uri: uri.clone(),
+                        version: *version,
+                        diagnostics,
+                    }
+                }
+                Job::Query { id, uri, query } => {
+                    let result = answer(&mut analyses, uri, query);
+
+                    Event::Answered {
+                        id: id.clone(),
+                        result,
+                    }
+                }
+                Job::Close { uri } => {
+                    analyses.remove(uri);
+
+                    continue;
+                }
+                Job::Stop => {
+                    let _ = events.send(Event::WorkerStopped);
+
+                    return;
+                }
+            };
+
+            if events.send(event).is_err() {
+                // The server has exited.
+                return;
+            }
+        }
+    }
+}
+
+/// The compiler still panics on some code, especially code that's in the middle of being written.
+/// That mustn't stop the worker, so a panic is reported as a diagnostic for the document instead.
+fn analyze_catching_panics(path: &Path, text: &str) -> (Vec<Diagnostic>, Option<Analysis>) {
+    match panic::catch_unwind(AssertUnwindSafe(|| analyze(path, text))) {
+        Ok(result) => result,
+        Err(payload) => {
+            let message = format!(
+                "The compiler crashed while checking this module: {}",
+                panic_message(payload.as_ref())
+            );
+
+            (vec![whole_document_diagnostic(message)], None)
+        }
+    }
+}
+
+fn answer(
+    analyses: &mut MutMap<String, Analysis>,
+    uri: &str,
+    query: &Query,
+) -> Result<Value, ResponseError> {
+    let analysis = analyses.get_mut(uri);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| match query {
+        Query::Hover(position) => {
+            serde_json::to_value(analysis.and_then(|analysis| analysis.hover(*position)))
+        }
+        Query::Definition(position) => {
+            serde_json::to_value(analysis.and_then(|analysis| analysis.definition(*position)))
+        }
+    }));
+
+    match result {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(error)) => Err(ResponseError {
+            code: INTERNAL_ERROR,
+            message: error.to_string(),
+        }),
+        Err(payload) => {
+            // The panic may have left the analysis half updated, so don't use it again.
+            analyses.remove(uri);
+
+            Err(ResponseError {
+                code: INTERNAL_ERROR,
+                message: format!(
+                    "The compiler crashed while answering this request: {}",
+                    panic_message(payload.as_ref())
+                ),
+            })
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "no message"
+    }
+}
+
+/// Whether the analysis at `index` is made redundant by a later one of the same document,
+/// with no query about that document in between which would need it.
+fn is_superseded(batch: &[Job], index: usize) -> bool {
+    let uri = match &batch[index] {
+        Job::Analyze { uri, .. } => uri,
+        _ => return false,
+    };
+
+    for job in &batch[index + 1..] {
+        match job {
+            Job::Analyze { uri: other, .. } if other == uri => return true,
+            Job::Query { uri: other, .. } | Job::Close { uri: other } if other == uri => {
+                return false
+            }
+            _ => {}
+        }
+    }
+
+    false
+}
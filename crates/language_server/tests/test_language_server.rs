@@ -0,0 +1,398 @@
+#[macro_use]
+extern crate indoc;
+#[macro_use]
+extern crate pretty_assertions;
+
+use roc_language_server::protocol::path_to_uri;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Cursor, Read};
+use std::path::Path;
+
+fn frame(buf: &mut Vec<u8>, message: Value) {
+    let content = serde_json::to_vec(&message).unwrap();
+    buf.extend_from_slice(format!("Content-Length: {}\r\n\r\n", content.len()).as_bytes());
+    buf.extend_from_slice(&content);
+}
+
+fn request(buf: &mut Vec<u8>, id: u64, method: &str, params: Value) {
+    frame(
+        buf,
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }),
+    );
+}
+
+fn notify(buf: &mut Vec<u8>, method: &str, params: Value) {
+    frame(
+        buf,
+        json!({ "jsonrpc": "2.0", "method": method, "params": params }),
+    );
+}
+
+fn read_all(output: &[u8]) -> Vec<Value> {
+    let mut reader = BufReader::new(output);
+    let mut messages = Vec::new();
+
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).unwrap() == 0 {
+            return messages;
+        }
+
+        let len: usize = header
+            .trim()
+            .strip_prefix("Content-Length: ")
+            .unwrap()
+            .parse()
+            .unwrap();
+
+        let mut blank = String::new();
+        reader.read_line(&mut blank).unwrap();
+
+        let mut content = vec![0; len];
+        reader.read_exact(&mut content).unwrap();
+        messages.push(serde_json::from_slice(&content).unwrap());
+    }
+}
+
+fn open(buf: &mut Vec<u8>, uri: &str, src: &str) {
+    notify(
+        buf,
+        "textDocument/didOpen",
+        json!({
+            "textDocument": { "uri": uri, "languageId": "roc", "version": 1, "text": src }
+        }),
+    );
+}
+
+fn change(buf: &mut Vec<u8>, uri: &str, version: i32, src: &str) {
+    notify(
+        buf,
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": uri, "version": version },
+            "contentChanges": [{ "text": src }]
+        }),
+    );
+}
+
+/// Sends `input`, followed by a clean shutdown, and returns every message the server sent.
+fn serve(mut input: Vec<u8>) -> Vec<Value> {
+    request(&mut input, 999, "shutdown", Value::Null);
+    notify(&mut input, "exit", Value::Null);
+
+    let mut output = Vec::new();
+    let exit_code = roc_language_server::run(Cursor::new(input), &mut output).unwrap();
+    assert_eq!(exit_code, 0);
+
+    read_all(&output)
+}
+
+/// Opens `src` as `Test.roc`, sends `requests` and shuts down cleanly.
+/// Returns every message the server sent.
+fn session(src: &str, requests: impl FnOnce(&mut Vec<u8>, &str)) -> Vec<Value> {
+    let uri = path_to_uri(&std::env::temp_dir().join("Test.roc"));
+    let mut input = Vec::new();
+
+    request(&mut input, 0, "initialize", json!({ "capabilities": {} }));
+    notify(&mut input, "initialized", json!({}));
+    open(&mut input, &uri, src);
+    requests(&mut input, &uri);
+
+    serve(input)
+}
+
+fn response(messages: &[Value], id: u64) -> &Value {
+    messages
+        .iter()
+        .find(|msg| msg["id"] == json!(id))
+        .map(|msg| &msg["result"])
+        .unwrap_or_else(|| panic!("no response with id {}", id))
+}
+
+/// The parameters of every `publishDiagnostics` notification for `uri`, in the order they were sent.
+fn published<'a>(messages: &'a [Value], uri: &str) -> Vec<&'a Value> {
+    messages
+        .iter()
+        .filter(|msg| {
+            msg["method"] == "textDocument/publishDiagnostics" && msg["params"]["uri"] == uri
+        })
+        .map(|msg| &msg["params"])
+        .collect()
+}
+
+fn diagnostics(messages: &[Value]) -> &Vec<Value> {
+    messages
+        .iter()
+        .find(|msg| msg["method"] == "textDocument/publishDiagnostics")
+        .and_then(|msg| msg["params"]["diagnostics"].as_array())
+        .expect("no diagnostics were published")
+}
+
+fn position(line: u32, character: u32) -> Value {
+    json!({ "line": line, "character": character })
+}
+
+#[test]
+fn initialize_advertises_capabilities() {
+    let messages = session("", |_, _| {});
+    let capabilities = &response(&messages, 0)["capabilities"];
+
+    assert_eq!(capabilities["textDocumentSync"], json!(1));
+    assert_eq!(capabilities["hoverProvider"], json!(true));
+    assert_eq!(capabilities["definitionProvider"], json!(true));
+    assert_eq!(capabilities["documentFormattingProvider"], json!(true));
+}
+
+#[test]
+fn publishes_type_errors() {
+    let src = indoc!(
+        r#"
+        interface Test
+            exposes [x]
+            imports []
+
+        x : Str
+        x = 1
+        "#
+    );
+
+    let messages = session(src, |_, _| {});
+    let diagnostics = diagnostics(&messages);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["severity"], json!(1));
+    assert_eq!(diagnostics[0]["code"], json!("TYPE MISMATCH"));
+    assert_eq!(diagnostics[0]["range"]["start"], position(5, 4));
+}
+
+#[test]
+fn publishes_warnings() {
+    let src = indoc!(
+        r#"
+        interface Test
+            exposes [x]
+            imports []
+
+        x =
+            unused = 1
+            2
+        "#
+    );
+
+    let messages = session(src, |_, _| {});
+    let diagnostics = diagnostics(&messages);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["severity"], json!(2));
+    assert_eq!(diagnostics[0]["code"], json!("UNUSED DEFINITION"));
+}
+
+#[test]
+fn hover_shows_inferred_type() {
+    let src = indoc!(
+        r#"
+        interface Test
+            exposes [greeting]
+            imports []
+
+        greeting = "Hello"
+        "#
+    );
+
+    let messages = session(src, |input, uri| {
+        request(
+            input,
+            1,
+            "textDocument/hover",
+            json!({ "textDocument": { "uri": uri }, "position": position(4, 2) }),
+        );
+    });
+
+    assert_eq!(
+        response(&messages, 1)["contents"]["value"],
+        json!("```roc\nStr\n```")
+    );
+}
+
+#[test]
+fn definition_of_top_level_value() {
+    let src = indoc!(
+        r#"
+        interface Test
+            exposes [shout]
+            imports []
+
+        greeting = "Hello"
+
+        shout = Str.concat greeting "!"
+        "#
+    );
+
+    let messages = session(src, |input, uri| {
+        request(
+            input,
+            1,
+            "textDocument/definition",
+            json!({ "textDocument": { "uri": uri }, "position": position(6, 20) }),
+        );
+    });
+
+    let location = response(&messages, 1);
+
+    assert_eq!(location["range"]["start"], position(4, 0));
+    assert_eq!(location["range"]["end"], position(4, 8));
+}
+
+#[test]
+fn formatting_replaces_whole_document() {
+    let src = indoc!(
+        r#"
+        interface Test
+            exposes [x]
+            imports []
+
+        x=1
+        "#
+    );
+
+    let messages = session(src, |input, uri| {
+        request(
+            input,
+            1,
+            "textDocument/formatting",
+            json!({ "textDocument": { "uri": uri }, "options": { "tabSize": 4, "insertSpaces": true } }),
+        );
+    });
+
+    let edits = response(&messages, 1).as_array().unwrap();
+
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0]["range"]["start"], position(0, 0));
+    assert_eq!(edits[0]["range"]["end"], position(5, 0));
+    assert!(edits[0]["newText"].as_str().unwrap().contains("x = 1"));
+}
+
+#[test]
+fn unknown_request_is_an_error() {
+    let messages = session("", |input, _| {
+        request(input, 1, "textDocument/rename", json!({}));
+    });
+
+    let error = messages
+        .iter()
+        .find(|msg| msg["id"] == json!(1))
+        .map(|msg| &msg["error"]["code"])
+        .unwrap();
+
+    assert_eq!(error, &json!(-32601));
+}
+
+#[test]
+fn edits_are_debounced() {
+    let src = |def: &str| {
+        format!(
+            "interface Test\n    exposes [x]\n    imports []\n\nx : Str\nx = {}\n",
+            def
+        )
+    };
+
+    let messages = session(&src("\"fine\""), |input, uri| {
+        // Typing in quick succession only gets the last version typechecked.
+        change(input, uri, 2, &src("1"));
+        change(input, uri, 3, &src("1.5"));
+        change(input, uri, 4, &src("[]"));
+    });
+
+    let uri = path_to_uri(&std::env::temp_dir().join("Test.roc"));
+    let published = published(&messages, &uri);
+    let versions: Vec<&Value> = published.iter().map(|params| &params["version"]).collect();
+
+    assert!(!versions.contains(&&json!(2)), "{:?}", versions);
+    assert!(!versions.contains(&&json!(3)), "{:?}", versions);
+
+    let last = published.last().unwrap();
+
+    assert_eq!(last["version"], json!(4));
+    assert_eq!(last["diagnostics"][0]["code"], json!("TYPE MISMATCH"));
+    assert_eq!(
+        last["diagnostics"][0]["range"]["start"],
+        json!({ "line": 5, "character": 4 })
+    );
+}
+
+#[test]
+fn hover_sees_the_latest_edit() {
+    let src = indoc!(
+        r#"
+        interface Test
+            exposes [greeting]
+            imports []
+
+        greeting = "Hello"
+        "#
+    );
+
+    let messages = session(src, |input, uri| {
+        // The hover comes in before the edit's debounce is over, so it must not be answered
+        // from the analysis of the text which was opened.
+        change(input, uri, 2, &src.replace("\"Hello\"", "42"));
+        request(
+            input,
+            1,
+            "textDocument/hover",
+            json!({ "textDocument": { "uri": uri }, "position": position(4, 2) }),
+        );
+    });
+
+    assert_eq!(
+        response(&messages, 1)["contents"]["value"],
+        json!("```roc\nNum *\n```")
+    );
+}
+
+#[test]
+fn each_workspace_folder_is_typechecked() {
+    let folder = |name: &str| std::env::temp_dir().join("roc_language_server").join(name);
+    let uri = |folder: &Path| path_to_uri(&folder.join("Test.roc"));
+    let (first, second) = (folder("first"), folder("second"));
+    let src = indoc!(
+        r#"
+        interface Test
+            exposes [x]
+            imports []
+
+        x : Str
+        x = 1
+        "#
+    );
+
+    let mut input = Vec::new();
+
+    request(
+        &mut input,
+        0,
+        "initialize",
+        json!({
+            "capabilities": {},
+            "workspaceFolders": [
+                { "uri": path_to_uri(&first), "name": "first" },
+                { "uri": path_to_uri(&second), "name": "second" },
+            ],
+        }),
+    );
+    notify(&mut input, "initialized", json!({}));
+    open(&mut input, &uri(&first), src);
+    open(&mut input, &uri(&second), src);
+
+    let messages = serve(input);
+
+    for folder in [&first, &second] {
+        let published = published(&messages, &uri(folder));
+
+        assert!(!published.is_empty(), "nothing published for {:?}", folder);
+        assert_eq!(
+            published[0]["diagnostics"][0]["code"],
+            json!("TYPE MISMATCH")
+        );
+    }
+}
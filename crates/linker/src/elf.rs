@@ -20,7 +20,8 @@ use std::{
 
 use crate::{
     align_by_constraint, align_to_offset_by_constraint, load_struct_inplace,
-    load_struct_inplace_mut, load_structs_inplace_mut, open_mmap, open_mmap_mut,
+    load_struct_inplace_mut, load_structs_inplace, load_structs_inplace_mut, open_mmap,
+    open_mmap_mut, LinkType,
 };
 
 const MIN_SECTION_ALIGNMENT: usize = 0x40;
@@ -28,6 +29,73 @@ const MIN_SECTION_ALIGNMENT: usize = 0x40;
 // TODO: Analyze if this offset is always correct.
 const PLT_ADDRESS_OFFSET: u64 = 0x10;
 
+const AARCH64_B: u32 = 0x1400_0000;
+const AARCH64_NOP: u32 = 0xd503_201f;
+
+/// The instruction sets of the ELF hosts we can preprocess and link into.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ElfArch {
+    X86_64,
+    Aarch64,
+}
+
+impl ElfArch {
+    fn from_header(exec_data: &[u8]) -> Self {
+        let exec_header = load_struct_inplace::<elf::FileHeader64<LE>>(exec_data, 0);
+
+        match exec_header.e_machine.get(LE) {
+            elf::EM_X86_64 => ElfArch::X86_64,
+            elf::EM_AARCH64 => ElfArch::Aarch64,
+            other => internal_error!("Surgical linking does not support ELF machine {}", other),
+        }
+    }
+
+    fn relative_reloc(self) -> u32 {
+        match self {
+            ElfArch::X86_64 => elf::R_X86_64_RELATIVE,
+            ElfArch::Aarch64 => elf::R_AARCH64_RELATIVE,
+        }
+    }
+
+    fn glob_dat_reloc(self) -> u32 {
+        match self {
+            ElfArch::X86_64 => elf::R_X86_64_GLOB_DAT,
+            ElfArch::Aarch64 => elf::R_AARCH64_GLOB_DAT,
+        }
+    }
+
+    fn jump_slot_reloc(self) -> u32 {
+        match self {
+            ElfArch::X86_64 => elf::R_X86_64_JUMP_SLOT,
+            ElfArch::Aarch64 => elf::R_AARCH64_JUMP_SLOT,
+        }
+    }
+
+    fn none_reloc(self) -> u32 {
+        match self {
+            ElfArch::X86_64 => elf::R_X86_64_NONE,
+            ElfArch::Aarch64 => elf::R_AARCH64_NONE,
+        }
+    }
+
+    /// The size of the resolver stub at the start of the PLT, before the per-function entries.
+    fn plt_header_size(self) -> u64 {
+        match self {
+            ElfArch::X86_64 => PLT_ADDRESS_OFFSET,
+            ElfArch::Aarch64 => 0x20,
+        }
+    }
+
+    /// What the host's code and data must be shifted by a multiple of during preprocessing.
+    /// `adrp` computes addresses in 4KiB pages, so on aarch64 the shift must keep page offsets.
+    fn shift_alignment(self) -> u64 {
+        match self {
+            ElfArch::X86_64 => MIN_SECTION_ALIGNMENT as u64,
+            ElfArch::Aarch64 => 0x1000,
+        }
+    }
+}
+
 struct ElfDynamicDeps {
     got_app_syms: Vec<(String, usize)>,
     got_sections: Vec<(usize, usize)>,
@@ -40,6 +108,8 @@ struct ElfDynamicDeps {
 enum VirtualOffset {
     Absolute,
     Relative(u64),
+    /// An aarch64 `b` or `bl` at this address, whose 26-bit word offset needs replacing.
+    Aarch64Branch(u64),
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
@@ -162,13 +232,18 @@ fn collect_roc_definitions<'a>(object: &object::File<'a, &'a [u8]>) -> MutMap<St
 }
 
 struct Surgeries<'a> {
+    arch: ElfArch,
     surgeries: MutMap<String, Vec<SurgeryEntry>>,
     app_func_addresses: MutMap<u64, &'a str>,
     indirect_warning_given: bool,
 }
 
 impl<'a> Surgeries<'a> {
-    fn new(application_symbols: &[Symbol], app_func_addresses: MutMap<u64, &'a str>) -> Self {
+    fn new(
+        arch: ElfArch,
+        application_symbols: &[Symbol],
+        app_func_addresses: MutMap<u64, &'a str>,
+    ) -> Self {
         let mut surgeries = MutMap::default();
 
         // for each symbol that the host expects from the application
@@ -179,6 +254,7 @@ impl<'a> Surgeries<'a> {
         }
 
         Self {
+            arch,
             surgeries,
             app_func_addresses,
             indirect_warning_given: false,
@@ -239,6 +315,19 @@ impl<'a> Surgeries<'a> {
                 internal_error!("Failed to load text section, {:+x?}: {}", sec, err);
             }
         };
+
+        if let ElfArch::Aarch64 = self.arch {
+            self.append_aarch64_branches(
+                object_bytes,
+                sec,
+                &data,
+                file_offset,
+                compressed,
+                verbose,
+            );
+            return;
+        }
+
         let mut decoder = Decoder::with_ip(64, &data, sec.address(), DecoderOptions::NONE);
         let mut inst = Instruction::default();
 
@@ -330,11 +419,98 @@ impl<'a> Surgeries<'a> {
             }
         }
     }
+
+    /// aarch64 instructions are all one 4-byte word, so rather than disassembling we check every
+    /// word for a `b` or `bl` that targets one of the app's PLT entries.
+    fn append_aarch64_branches(
+        &mut self,
+        object_bytes: &[u8],
+        sec: &Section,
+        data: &[u8],
+        file_offset: u64,
+        compressed: bool,
+        verbose: bool,
+    ) {
+        for (i, word) in data.chunks_exact(4).enumerate() {
+            let inst = u32::from_le_bytes(word.try_into().unwrap());
+            if inst & 0x7c00_0000 != AARCH64_B {
+                continue;
+            }
+
+            let inst_address = sec.address() + 4 * i as u64;
+            let target = inst_address.wrapping_add(aarch64_branch_offset(inst) as u64);
+            if let Some(func_name) = self.app_func_addresses.get(&target) {
+                if compressed {
+                    internal_error!(
+                        "Surgical linking does not work with compressed text sections: {:+x?}",
+                        sec
+                    );
+                }
+
+                let offset = file_offset + 4 * i as u64;
+                if verbose {
+                    println!(
+                        "Found branch from {:+x} to {:+x}({})",
+                        inst_address, target, func_name
+                    );
+                    println!(
+                        "\tNeed to surgically replace 4 bytes at file offset {:+x}",
+                        offset,
+                    );
+                    println!(
+                        "\tIts current value is {:+x?}",
+                        &object_bytes[offset as usize..offset as usize + 4]
+                    )
+                }
+                self.surgeries
+                    .get_mut(*func_name)
+                    .unwrap()
+                    .push(SurgeryEntry {
+                        file_offset: offset,
+                        virtual_offset: VirtualOffset::Aarch64Branch(inst_address),
+                        size: 4,
+                    });
+            }
+        }
+    }
+}
+
+/// Builds a SysV `.hash` table over every dynamic symbol of the host. Unlike `.gnu.hash`, it
+/// includes symbols that were undefined when the host was linked, like the app's functions are,
+/// so once surgery defines them a shared library host exports them to `dlsym` and friends.
+fn sysv_hash_table(exec_obj: &object::File) -> Vec<u32> {
+    let symbols: Vec<(usize, &str)> = exec_obj
+        .dynamic_symbols()
+        .map(|sym| (sym.index().0, sym.name().unwrap_or_default()))
+        .collect();
+
+    // the null symbol at index 0 is never part of a chain, but still counts
+    let nchain = symbols
+        .iter()
+        .map(|(index, _)| index + 1)
+        .max()
+        .unwrap_or(1);
+    let nbucket = std::cmp::max(1, nchain / 2);
+
+    let mut buckets = vec![0; nbucket];
+    let mut chains = vec![0; nchain];
+    for (index, name) in symbols {
+        let bucket = elf::hash(name.as_bytes()) as usize % nbucket;
+        chains[index] = buckets[bucket];
+        buckets[bucket] = index as u32;
+    }
+
+    let mut table = vec![nbucket as u32, nchain as u32];
+    table.extend(buckets);
+    table.extend(chains);
+    table
 }
 
 /// Constructs a `Metadata` from a host executable binary, and writes it to disk
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn preprocess_elf(
     endianness: target_lexicon::Endianness,
+    link_type: LinkType,
     host_exe_path: &Path,
     metadata_path: &Path,
     preprocessed_path: &Path,
@@ -351,6 +527,7 @@ pub(crate) fn preprocess_elf(
             internal_error!("Failed to parse executable file: {}", err);
         }
     };
+    let arch = ElfArch::from_header(exec_data);
 
     let mut md = Metadata {
         roc_symbol_vaddresses: collect_roc_definitions(&exec_obj),
@@ -358,6 +535,7 @@ pub(crate) fn preprocess_elf(
     };
 
     if verbose {
+        println!("Architecture: {:?}", arch);
         println!(
             "Found {} roc symbol definitions:",
             md.roc_symbol_vaddresses.len()
@@ -428,7 +606,7 @@ pub(crate) fn preprocess_elf(
                 }
             })
             .filter_map(|(_, reloc)| {
-                if let RelocationKind::Elf(7) = reloc.kind() {
+                if RelocationKind::Elf(arch.jump_slot_reloc()) == reloc.kind() {
                     Some(reloc)
                 } else {
                     None
@@ -437,8 +615,9 @@ pub(crate) fn preprocess_elf(
     for (i, reloc) in plt_relocs.enumerate() {
         for symbol in app_syms.iter() {
             if reloc.target() == RelocationTarget::Symbol(symbol.index()) {
-                let func_address = (i as u64 + 1) * PLT_ADDRESS_OFFSET + plt_address;
-                let func_offset = (i as u64 + 1) * PLT_ADDRESS_OFFSET + plt_offset;
+                let entry_offset = arch.plt_header_size() + i as u64 * PLT_ADDRESS_OFFSET;
+                let func_address = plt_address + entry_offset;
+                let func_offset = plt_offset + entry_offset;
                 app_func_addresses.insert(func_address, symbol.name().unwrap());
                 md.plt_addresses.insert(
                     symbol.name().unwrap().to_string(),
@@ -474,7 +653,7 @@ pub(crate) fn preprocess_elf(
     // look at the text (i.e. code) sections and see collect work needs to be done
     let text_disassembly_start = Instant::now();
 
-    let mut surgeries = Surgeries::new(&app_syms, app_func_addresses);
+    let mut surgeries = Surgeries::new(arch, &app_syms, app_func_addresses);
     surgeries.append_text_sections(exec_data, &exec_obj, verbose);
     md.surgeries = surgeries.surgeries;
 
@@ -494,16 +673,24 @@ pub(crate) fn preprocess_elf(
                 dynamic_lib_count,
                 shared_lib_index,
             } = scan_elf_dynamic_deps(
-                &exec_obj, &mut md, &app_syms, shared_lib, exec_data, verbose,
+                &exec_obj, arch, &mut md, &app_syms, shared_lib, exec_data, verbose,
             );
 
             scanning_dynamic_deps_duration = scanning_dynamic_deps_start.elapsed();
 
             platform_gen_start = Instant::now();
 
+            // A shared library host has to export the app's functions once they are defined.
+            let hash_table = match link_type {
+                LinkType::Dylib => Some(sysv_hash_table(&exec_obj)),
+                LinkType::Executable | LinkType::None => None,
+            };
+
             // TODO little endian
             gen_elf_le(
                 exec_data,
+                arch,
+                hash_table.as_deref(),
                 &mut md,
                 preprocessed_path,
                 &got_app_syms,
@@ -574,6 +761,8 @@ pub(crate) fn preprocess_elf(
 #[allow(clippy::too_many_arguments)]
 fn gen_elf_le(
     exec_data: &[u8],
+    arch: ElfArch,
+    hash_table: Option<&[u32]>,
     md: &mut Metadata,
     preprocessed_path: &Path,
     got_app_syms: &[(String, usize)],
@@ -602,10 +791,13 @@ fn gen_elf_le(
     }
 
     // Copy header and shift everything to enable more program sections.
+    // A new hash table, if any, goes right after those program headers.
     let added_header_count = 3;
-    md.added_byte_count = ph_ent_size as u64 * added_header_count;
-    md.added_byte_count = md.added_byte_count
-        + (MIN_SECTION_ALIGNMENT as u64 - md.added_byte_count % MIN_SECTION_ALIGNMENT as u64);
+    let hash_table_size = hash_table.map_or(0, mem::size_of_val) as u64;
+    let shift_alignment = arch.shift_alignment();
+    md.added_byte_count = ph_ent_size as u64 * added_header_count + hash_table_size;
+    md.added_byte_count =
+        md.added_byte_count + (shift_alignment - md.added_byte_count % shift_alignment);
     let ph_end = ph_offset as usize + ph_num as usize * ph_ent_size as usize;
     let physical_shift_start = ph_end as u64;
 
@@ -678,6 +870,14 @@ fn gen_elf_le(
     out_mmap[physical_shift_start as usize + md.added_byte_count as usize..]
         .copy_from_slice(&exec_data[physical_shift_start as usize..]);
 
+    let hash_table_offset = ph_end + ph_ent_size as usize * added_header_count as usize;
+    let hash_table_vaddr = virtual_shift_start + (hash_table_offset - ph_end) as u64;
+    if let Some(table) = hash_table {
+        for (i, word) in table.iter().enumerate() {
+            out_mmap[hash_table_offset + 4 * i..][..4].copy_from_slice(&word.to_le_bytes());
+        }
+    }
+
     // Update all sections for shift for extra program headers.
     let section_headers = load_structs_inplace_mut::<elf::SectionHeader64<LE>>(
         &mut out_mmap,
@@ -746,18 +946,18 @@ fn gen_elf_le(
                 rel.r_offset.set(LE, r_offset + md.added_byte_count);
                 // Deal with potential adjusts to absolute jumps.
                 // TODO: Verify other relocation types.
-                if rel.r_type(LE, false) == elf::R_X86_64_RELATIVE {
+                if rel.r_type(LE, false) == arch.relative_reloc() {
                     let r_addend = rel.r_addend.get(LE);
                     rel.r_addend.set(LE, r_addend + md.added_byte_count as i64);
                 }
             }
             // If the relocation goes to a roc function, we need to surgically link it and change it to relative.
             let r_type = rel.r_type(LE, false);
-            if r_type == elf::R_X86_64_GLOB_DAT {
+            if r_type == arch.glob_dat_reloc() {
                 let r_sym = rel.r_sym(LE, false);
                 for (name, index) in got_app_syms.iter() {
                     if *index as u32 == r_sym {
-                        rel.set_r_info(LE, false, 0, elf::R_X86_64_RELATIVE);
+                        rel.set_r_info(LE, false, 0, arch.relative_reloc());
                         let addend_addr = sec_offset as usize
                             + i * mem::size_of::<elf::Rela64<LE>>()
                             // This 16 skips the first 2 fields and gets to the addend field.
@@ -782,8 +982,7 @@ fn gen_elf_le(
             .filter_map(|(i, rel)| {
                 let r_type = rel.r_type(LE, false);
                 let r_sym = rel.r_sym(LE, false);
-                if r_type == elf::R_X86_64_JUMP_SLOT && app_sym_indices.contains(&(r_sym as usize))
-                {
+                if r_type == arch.jump_slot_reloc() && app_sym_indices.contains(&(r_sym as usize)) {
                     Some(i)
                 } else {
                     None
@@ -799,7 +998,7 @@ fn gen_elf_le(
         for i in to_remove.iter() {
             relocations.swap(*i, j);
             let r_sym = relocations[j].r_sym(LE, false);
-            relocations[j].set_r_info(LE, false, r_sym, elf::R_X86_64_NONE);
+            relocations[j].set_r_info(LE, false, r_sym, arch.none_reloc());
             j -= 1;
         }
 
@@ -902,6 +1101,27 @@ fn gen_elf_le(
         }
     }
 
+    // Use the new hash table instead of the ones the host was linked with.
+    if hash_table.is_some() {
+        let dyns = load_structs_inplace_mut::<elf::Dyn64<LE>>(
+            &mut out_mmap,
+            dyn_offset as usize,
+            dynamic_lib_count,
+        );
+        let mut hash_table_found = false;
+        for d in dyns {
+            if let elf::DT_HASH | elf::DT_GNU_HASH = d.d_tag.get(LE) as u32 {
+                // glibc prefers DT_GNU_HASH, so that must go. Duplicate DT_HASH entries are fine.
+                d.d_tag.set(LE, elf::DT_HASH as u64);
+                d.d_val.set(LE, hash_table_vaddr);
+                hash_table_found = true;
+            }
+        }
+        if !hash_table_found {
+            internal_error!("Shared library host does not have a symbol hash table");
+        }
+    }
+
     // Update symbol table entries for shift for extra program headers.
     let symtab_offset = md.symbol_table_section_offset + md.added_byte_count;
     let symtab_size = md.symbol_table_size as usize;
@@ -964,6 +1184,7 @@ fn gen_elf_le(
 
 fn scan_elf_dynamic_deps(
     exec_obj: &object::File,
+    arch: ElfArch,
     md: &mut Metadata,
     app_syms: &[Symbol],
     shared_lib: &Path,
@@ -1110,7 +1331,7 @@ fn scan_elf_dynamic_deps(
         }
     })
     .filter_map(|(_, reloc)| {
-        if let RelocationKind::Elf(elf::R_X86_64_GLOB_DAT) = reloc.kind() {
+        if RelocationKind::Elf(arch.glob_dat_reloc()) == reloc.kind() {
             for symbol in app_syms.iter() {
                 if reloc.target() == RelocationTarget::Symbol(symbol.index()) {
                     return Some((symbol.name().unwrap().to_string(), symbol.index().0));
@@ -1129,7 +1350,7 @@ fn scan_elf_dynamic_deps(
         }
     })
     .filter_map(|(_, reloc)| {
-        if let RelocationKind::Elf(elf::R_X86_64_JUMP_SLOT) = reloc.kind() {
+        if RelocationKind::Elf(arch.jump_slot_reloc()) == reloc.kind() {
             for symbol in app_syms.iter() {
                 if reloc.target() == RelocationTarget::Symbol(symbol.index()) {
                     return Some(symbol.index().0);
@@ -1184,7 +1405,13 @@ pub(crate) fn surgery_elf(
     let loading_metadata_duration = loading_metadata_start.elapsed();
 
     let load_and_mmap_start = Instant::now();
-    let max_out_len = md.exec_len + roc_app_bytes.len() as u64 + md.load_align_constraint;
+    let debug_info_len = if app_obj.sections().any(|sec| is_debug_section(&sec)) {
+        debug_splice_len(&open_mmap(executable_path), &app_obj)
+    } else {
+        0
+    };
+    let max_out_len =
+        md.exec_len + roc_app_bytes.len() as u64 + md.load_align_constraint + debug_info_len;
     let mut exec_mmap = open_mmap_mut(executable_path, max_out_len as usize);
     let load_and_mmap_duration = load_and_mmap_start.elapsed();
 
@@ -1249,6 +1476,7 @@ fn surgery_elf_help(
     if !elf64 || !litte_endian {
         internal_error!("Only 64bit little endian elf currently supported for surgery");
     }
+    let arch = ElfArch::from_header(exec_mmap);
     let exec_header = load_struct_inplace::<elf::FileHeader64<LE>>(exec_mmap, 0);
 
     let ph_offset = exec_header.e_phoff.get(LE);
@@ -1257,11 +1485,13 @@ fn surgery_elf_help(
     let sh_offset = exec_header.e_shoff.get(LE);
     let sh_ent_size = exec_header.e_shentsize.get(LE);
     let sh_num = exec_header.e_shnum.get(LE);
+    let sh_str_index = exec_header.e_shstrndx.get(LE);
 
     if verbose {
         println!();
         println!("Is Elf64: {}", elf64);
         println!("Is Little Endian: {}", litte_endian);
+        println!("Architecture: {:?}", arch);
         println!("PH Offset: {:+x}", ph_offset);
         println!("PH Entry Size: {}", ph_ent_size);
         println!("PH Entry Count: {}", ph_num);
@@ -1273,6 +1503,7 @@ fn surgery_elf_help(
     // Backup section header table.
     let sh_size = sh_ent_size as usize * sh_num as usize;
     let sh_tab = exec_mmap[sh_offset as usize..][..sh_size].to_vec();
+    let host_sections = host_sections(exec_mmap, sh_offset, sh_num, sh_str_index);
 
     let mut offset = sh_offset as usize;
     offset = align_by_constraint(offset, MIN_SECTION_ALIGNMENT);
@@ -1467,11 +1698,32 @@ fn surgery_elf_help(
                     if let Some(target_offset) = target_offset {
                         let virt_base = section_virtual_offset + rel.0 as usize;
                         let base = section_offset + rel.0 as usize;
-                        let target: i64 = match rel.1.kind() {
-                            RelocationKind::Relative | RelocationKind::PltRelative => {
+                        let target: i64 = match (arch, rel.1.kind()) {
+                            (
+                                ElfArch::X86_64,
+                                RelocationKind::Relative | RelocationKind::PltRelative,
+                            )
+                            | (ElfArch::Aarch64, RelocationKind::Relative) => {
                                 target_offset - virt_base as i64 + rel.1.addend()
                             }
-                            x => {
+                            (ElfArch::Aarch64, kind) => {
+                                // Everything else on aarch64 is encoded into an instruction.
+                                let address = target_offset + rel.1.addend();
+                                if verbose {
+                                    println!(
+                                        "\t\tRelocation base location: {base:+x} (virt: {virt_base:+x})",
+                                    );
+                                    println!("\t\tFinal relocation target address: {address:+x}");
+                                }
+                                patch_aarch64_instruction(
+                                    &mut exec_mmap[base..][..4],
+                                    virt_base as i64,
+                                    address,
+                                    kind,
+                                );
+                                continue;
+                            }
+                            (_, x) => {
                                 internal_error!("Relocation Kind not yet support: {:?}", x);
                             }
                         };
@@ -1510,6 +1762,17 @@ fn surgery_elf_help(
         }
     }
 
+    let moved_sections = splice_debug_sections(
+        verbose,
+        exec_mmap,
+        &mut offset,
+        sh_offset,
+        sh_str_index as usize,
+        &host_sections,
+        &app_obj,
+        &symbol_vaddr_map,
+    );
+
     offset = align_by_constraint(offset, MIN_SECTION_ALIGNMENT);
     let new_sh_offset = offset;
     exec_mmap[offset..][..sh_size].copy_from_slice(&sh_tab);
@@ -1523,10 +1786,14 @@ fn surgery_elf_help(
         )
         .unwrap_or_else(|e| internal_error!("{}", e));
 
-    // TODO: look into merging symbol tables, debug info, and eh frames to enable better debugger experience.
+    // TODO: look into merging symbol tables and eh frames to enable better debugger experience.
 
-    // Add 3 new sections and segments.
-    let new_section_count = 3;
+    // Add 3 new sections and segments, plus any debug sections the host didn't have.
+    let new_debug_section_count = moved_sections
+        .iter()
+        .filter(|sec| sec.host_index.is_none())
+        .count();
+    let new_section_count = 3 + new_debug_section_count;
     offset += new_section_count * sh_ent_size as usize;
     let section_headers = load_structs_inplace_mut::<elf::SectionHeader64<LE>>(
         exec_mmap,
@@ -1539,7 +1806,7 @@ fn surgery_elf_help(
     let new_text_section_size = new_sh_offset as u64 - new_text_section_offset;
 
     // set the new rodata section header
-    section_headers[section_headers.len() - 3] = elf::SectionHeader64 {
+    section_headers[sh_num as usize] = elf::SectionHeader64 {
         sh_name: endian::U32::new(LE, 0),
         sh_type: endian::U32::new(LE, elf::SHT_PROGBITS),
         sh_flags: endian::U64::new(LE, elf::SHF_ALLOC as u64),
@@ -1553,7 +1820,7 @@ fn surgery_elf_help(
     };
 
     // set the new bss section header
-    section_headers[section_headers.len() - 2] = elf::SectionHeader64 {
+    section_headers[sh_num as usize + 1] = elf::SectionHeader64 {
         sh_name: endian::U32::new(LE, 0),
         sh_type: endian::U32::new(LE, elf::SHT_NOBITS),
         sh_flags: endian::U64::new(LE, (elf::SHF_ALLOC) as u64),
@@ -1567,7 +1834,7 @@ fn surgery_elf_help(
     };
 
     // set the new text section header
-    section_headers[section_headers.len() - 1] = elf::SectionHeader64 {
+    section_headers[sh_num as usize + 2] = elf::SectionHeader64 {
         sh_name: endian::U32::new(LE, 0),
         sh_type: endian::U32::new(LE, elf::SHT_PROGBITS),
         sh_flags: endian::U64::new(LE, (elf::SHF_ALLOC | elf::SHF_EXECINSTR) as u64),
@@ -1580,6 +1847,32 @@ fn surgery_elf_help(
         sh_entsize: endian::U64::new(LE, 0),
     };
 
+    // Point the host's debug sections at their spliced copies, and add the ones it didn't have.
+    let mut new_debug_section_index = sh_num as usize + 3;
+    for moved in moved_sections.iter() {
+        match moved.host_index {
+            Some(index) => {
+                section_headers[index].sh_offset.set(LE, moved.offset);
+                section_headers[index].sh_size.set(LE, moved.size);
+            }
+            None => {
+                section_headers[new_debug_section_index] = elf::SectionHeader64 {
+                    sh_name: endian::U32::new(LE, moved.name_offset),
+                    sh_type: endian::U32::new(LE, elf::SHT_PROGBITS),
+                    sh_flags: endian::U64::new(LE, 0),
+                    sh_addr: endian::U64::new(LE, 0),
+                    sh_offset: endian::U64::new(LE, moved.offset),
+                    sh_size: endian::U64::new(LE, moved.size),
+                    sh_link: endian::U32::new(LE, 0),
+                    sh_info: endian::U32::new(LE, 0),
+                    sh_addralign: endian::U64::new(LE, 1),
+                    sh_entsize: endian::U64::new(LE, 0),
+                };
+                new_debug_section_index += 1;
+            }
+        }
+    }
+
     // Reload and update file header and size.
     let file_header = load_struct_inplace_mut::<elf::FileHeader64<LE>>(exec_mmap, 0);
     file_header.e_shoff.set(LE, new_sh_offset as u64);
@@ -1619,8 +1912,8 @@ fn surgery_elf_help(
     };
 
     // set the new text section program header
-    let new_text_section_index = program_headers.len() - 1;
-    program_headers[new_text_section_index] = elf::ProgramHeader64 {
+    let new_text_segment_index = program_headers.len() - 1;
+    program_headers[new_text_segment_index] = elf::ProgramHeader64 {
         p_type: endian::U32::new(LE, elf::PT_LOAD),
         p_flags: endian::U32::new(LE, elf::PF_R | elf::PF_X),
         p_offset: endian::U64::new(LE, new_text_section_offset),
@@ -1632,6 +1925,7 @@ fn surgery_elf_help(
     };
 
     // Update calls from platform and dynamic symbols.
+    let new_text_section_index = sh_num + 2;
     let dynsym_offset = md.dynamic_symbol_table_section_offset + md.added_byte_count;
     let symtab_offset = md.symbol_table_section_offset + md.added_byte_count;
 
@@ -1663,11 +1957,24 @@ fn surgery_elf_help(
                 println!("\tPerforming surgery: {:+x?}", s);
             }
             let surgery_virt_offset = match s.virtual_offset {
-                VirtualOffset::Relative(vs) => (vs + md.added_byte_count) as i64,
+                VirtualOffset::Relative(vs) | VirtualOffset::Aarch64Branch(vs) => {
+                    (vs + md.added_byte_count) as i64
+                }
                 VirtualOffset::Absolute => 0,
             };
-            match s.size {
-                4 => {
+            match (&s.virtual_offset, s.size) {
+                (VirtualOffset::Aarch64Branch(_), 4) => {
+                    let target = func_virt_offset as i64 - surgery_virt_offset;
+                    if verbose {
+                        println!("\tTarget Jump: {:+x}", target);
+                    }
+                    let inst_bytes =
+                        &mut exec_mmap[(s.file_offset + md.added_byte_count) as usize..][..4];
+                    let inst = u32::from_le_bytes((&*inst_bytes).try_into().unwrap());
+                    let data = aarch64_set_branch_offset(inst, target).to_le_bytes();
+                    inst_bytes.copy_from_slice(&data);
+                }
+                (_, 4) => {
                     let target = (func_virt_offset as i64 - surgery_virt_offset) as i32;
                     if verbose {
                         println!("\tTarget Jump: {:+x}", target);
@@ -1676,7 +1983,7 @@ fn surgery_elf_help(
                     exec_mmap[(s.file_offset + md.added_byte_count) as usize..][..4]
                         .copy_from_slice(&data);
                 }
-                8 => {
+                (_, 8) => {
                     let target = func_virt_offset as i64 - surgery_virt_offset;
                     if verbose {
                         println!("\tTarget Jump: {:+x}", target);
@@ -1685,7 +1992,7 @@ fn surgery_elf_help(
                     exec_mmap[(s.file_offset + md.added_byte_count) as usize..][..8]
                         .copy_from_slice(&data);
                 }
-                x => {
+                (_, x) => {
                     internal_error!("Surgery size not yet supported: {}", x);
                 }
             }
@@ -1696,18 +2003,35 @@ fn surgery_elf_help(
         if let Some((plt_off, plt_vaddr)) = md.plt_addresses.get(func_name) {
             let plt_off = (*plt_off + md.added_byte_count) as usize;
             let plt_vaddr = *plt_vaddr + md.added_byte_count;
-            let jmp_inst_len = 5;
-            let target =
-                (func_virt_offset as i64 - (plt_vaddr as i64 + jmp_inst_len as i64)) as i32;
             if verbose {
                 println!("\tPLT: {:+x}, {:+x}", plt_off, plt_vaddr);
-                println!("\tTarget Jump: {:+x}", target);
             }
-            let data = target.to_le_bytes();
-            exec_mmap[plt_off] = 0xE9;
-            exec_mmap[plt_off + 1..plt_off + jmp_inst_len].copy_from_slice(&data);
-            for i in jmp_inst_len..PLT_ADDRESS_OFFSET as usize {
-                exec_mmap[plt_off + i] = 0x90;
+            match arch {
+                ElfArch::X86_64 => {
+                    let jmp_inst_len = 5;
+                    let target =
+                        (func_virt_offset as i64 - (plt_vaddr as i64 + jmp_inst_len as i64)) as i32;
+                    if verbose {
+                        println!("\tTarget Jump: {:+x}", target);
+                    }
+                    let data = target.to_le_bytes();
+                    exec_mmap[plt_off] = 0xE9;
+                    exec_mmap[plt_off + 1..plt_off + jmp_inst_len].copy_from_slice(&data);
+                    for i in jmp_inst_len..PLT_ADDRESS_OFFSET as usize {
+                        exec_mmap[plt_off + i] = 0x90;
+                    }
+                }
+                ElfArch::Aarch64 => {
+                    let target = func_virt_offset as i64 - plt_vaddr as i64;
+                    if verbose {
+                        println!("\tTarget Jump: {:+x}", target);
+                    }
+                    let data = aarch64_set_branch_offset(AARCH64_B, target).to_le_bytes();
+                    exec_mmap[plt_off..][..4].copy_from_slice(&data);
+                    for i in (4..PLT_ADDRESS_OFFSET as usize).step_by(4) {
+                        exec_mmap[plt_off + i..][..4].copy_from_slice(&AARCH64_NOP.to_le_bytes());
+                    }
+                }
             }
         }
 
@@ -1716,7 +2040,7 @@ fn surgery_elf_help(
                 exec_mmap,
                 dynsym_offset as usize + *i as usize * mem::size_of::<elf::Sym64<LE>>(),
             );
-            sym.st_shndx.set(LE, new_text_section_index as u16);
+            sym.st_shndx.set(LE, new_text_section_index);
             sym.st_value.set(LE, func_virt_offset);
             sym.st_size.set(
                 LE,
@@ -1733,7 +2057,7 @@ fn surgery_elf_help(
                 exec_mmap,
                 symtab_offset as usize + *i as usize * mem::size_of::<elf::Sym64<LE>>(),
             );
-            sym.st_shndx.set(LE, new_text_section_index as u16);
+            sym.st_shndx.set(LE, new_text_section_index);
             sym.st_value.set(LE, func_virt_offset);
             sym.st_size.set(
                 LE,
@@ -1749,6 +2073,316 @@ fn surgery_elf_help(
     *offset_ref = offset;
 }
 
+/// The byte offset that an aarch64 `b` or `bl` instruction branches by.
+fn aarch64_branch_offset(inst: u32) -> i64 {
+    // Move imm26 to the top of the word, then shift it back down (sign extending) as a byte count.
+    (((inst << 6) as i32) >> 4) as i64
+}
+
+/// Re-encodes an aarch64 `b` or `bl` instruction to branch by `offset` bytes.
+fn aarch64_set_branch_offset(inst: u32, offset: i64) -> u32 {
+    if offset % 4 != 0 || !(-(1 << 27)..(1 << 27)).contains(&offset) {
+        internal_error!("aarch64 branch offset {:+x} is out of range", offset);
+    }
+
+    (inst & 0xfc00_0000) | ((offset >> 2) as u32 & 0x03ff_ffff)
+}
+
+/// Applies an aarch64 relocation that is encoded into the instruction at `inst_bytes`.
+/// `address` is the target plus the addend, and `virt_base` is the address of the instruction.
+fn patch_aarch64_instruction(
+    inst_bytes: &mut [u8],
+    virt_base: i64,
+    address: i64,
+    kind: RelocationKind,
+) {
+    let inst = u32::from_le_bytes((&*inst_bytes).try_into().unwrap());
+
+    // The `add` and load/store forms scale their 12-bit immediate by the access size.
+    let set_lo12 = |shift: u32| {
+        let imm12 = ((address & 0xfff) as u32) >> shift;
+        (inst & !(0xfff << 10)) | (imm12 << 10)
+    };
+
+    let inst = match kind {
+        RelocationKind::PltRelative
+        | RelocationKind::Elf(elf::R_AARCH64_CALL26 | elf::R_AARCH64_JUMP26) => {
+            aarch64_set_branch_offset(inst, address - virt_base)
+        }
+        RelocationKind::Elf(
+            elf::R_AARCH64_ADR_PREL_PG_HI21 | elf::R_AARCH64_ADR_PREL_PG_HI21_NC,
+        ) => {
+            let pages = (address >> 12) - (virt_base >> 12);
+            if !(-(1 << 20)..(1 << 20)).contains(&pages) {
+                internal_error!(
+                    "adrp target {:+x} is out of range of {:+x}",
+                    address,
+                    virt_base
+                );
+            }
+            let imm21 = pages as u32 & 0x1f_ffff;
+            (inst & 0x9f00_001f) | ((imm21 & 0b11) << 29) | ((imm21 >> 2) << 5)
+        }
+        RelocationKind::Elf(elf::R_AARCH64_ADD_ABS_LO12_NC | elf::R_AARCH64_LDST8_ABS_LO12_NC) => {
+            set_lo12(0)
+        }
+        RelocationKind::Elf(elf::R_AARCH64_LDST16_ABS_LO12_NC) => set_lo12(1),
+        RelocationKind::Elf(elf::R_AARCH64_LDST32_ABS_LO12_NC) => set_lo12(2),
+        RelocationKind::Elf(elf::R_AARCH64_LDST64_ABS_LO12_NC) => set_lo12(3),
+        RelocationKind::Elf(elf::R_AARCH64_LDST128_ABS_LO12_NC) => set_lo12(4),
+        x => {
+            internal_error!("Relocation Kind not yet support: {:?}", x);
+        }
+    };
+
+    inst_bytes.copy_from_slice(&inst.to_le_bytes());
+}
+
+fn is_debug_section(sec: &Section) -> bool {
+    sec.name().unwrap_or_default().starts_with(".debug")
+}
+
+/// A section of the host executable, as it was before the app was spliced in.
+struct HostSection {
+    name: String,
+    flags: u64,
+    offset: u64,
+    size: u64,
+}
+
+fn host_sections(
+    exec_data: &[u8],
+    sh_offset: u64,
+    sh_num: u16,
+    sh_str_index: u16,
+) -> Vec<HostSection> {
+    let section_headers = load_structs_inplace::<elf::SectionHeader64<LE>>(
+        exec_data,
+        sh_offset as usize,
+        sh_num as usize,
+    );
+    let names_offset = section_headers[sh_str_index as usize].sh_offset.get(LE) as usize;
+
+    section_headers
+        .iter()
+        .map(|header| {
+            let name = &exec_data[names_offset + header.sh_name.get(LE) as usize..];
+            let name = &name[..name.iter().position(|b| *b == 0).unwrap_or(name.len())];
+
+            HostSection {
+                name: String::from_utf8_lossy(name).into_owned(),
+                flags: header.sh_flags.get(LE),
+                offset: header.sh_offset.get(LE),
+                size: header.sh_size.get(LE),
+            }
+        })
+        .collect()
+}
+
+/// An upper bound on how many bytes `splice_debug_sections` adds to the executable.
+fn debug_splice_len(exec_data: &[u8], app_obj: &object::File) -> u64 {
+    let exec_obj = match object::File::parse(exec_data) {
+        Ok(obj) => obj,
+        Err(err) => {
+            internal_error!("Failed to parse executable file: {}", err);
+        }
+    };
+
+    let host_len: u64 = exec_obj
+        .sections()
+        .filter(|sec| is_debug_section(sec) || sec.name() == Ok(".shstrtab"))
+        .map(|sec| sec.size() + MIN_SECTION_ALIGNMENT as u64)
+        .sum();
+
+    let app_len: u64 = app_obj
+        .sections()
+        .filter(is_debug_section)
+        .map(|sec| {
+            let name_len = sec.name().unwrap_or_default().len() as u64 + 1;
+            let header_len = mem::size_of::<elf::SectionHeader64<LE>>() as u64;
+
+            sec.size() + name_len + header_len + MIN_SECTION_ALIGNMENT as u64
+        })
+        .sum();
+
+    host_len + app_len + MIN_SECTION_ALIGNMENT as u64
+}
+
+/// A host section that was copied to the end of the executable, or a new one to add.
+struct MovedSection {
+    /// The host section header to point at the copy, or `None` if a new header is needed.
+    host_index: Option<usize>,
+    /// Offset of the name in `.shstrtab`, for new headers
+    name_offset: u32,
+    offset: u64,
+    size: u64,
+}
+
+/// Append the app's `.debug_*` sections to copies of the host's, so debuggers and profilers can
+/// map addresses in the app back to its source too.
+///
+/// DWARF from separate compilation units can simply be concatenated, as long as the app's
+/// references into its own debug sections are shifted past the host's data.
+#[allow(clippy::too_many_arguments)]
+fn splice_debug_sections(
+    verbose: bool,
+    exec_mmap: &mut MmapMut,
+    offset: &mut usize,
+    sh_offset: u64,
+    sh_str_index: usize,
+    host_sections: &[HostSection],
+    app_obj: &object::File,
+    symbol_vaddr_map: &MutMap<SymbolIndex, usize>,
+) -> Vec<MovedSection> {
+    let mut moved_sections = Vec::new();
+    let mut new_names = Vec::new();
+    // Where each app debug section starts within its spliced section, and in the file.
+    let mut app_section_map: MutMap<SectionIndex, (u64, usize)> = MutMap::default();
+
+    for sec in app_obj.sections().filter(is_debug_section) {
+        let name = sec.name().unwrap_or_default();
+        let data = sec.data().unwrap_or_else(|err| {
+            internal_error!("Failed to load data for section, {:+x?}: {err}", name)
+        });
+
+        let host_index = host_sections.iter().position(|host| host.name == name);
+        let host_size = match host_index {
+            Some(index) => {
+                let host = &host_sections[index];
+                if host.flags & elf::SHF_COMPRESSED as u64 != 0 {
+                    if verbose {
+                        println!("Skipping {name}: the host's copy is compressed");
+                    }
+                    continue;
+                }
+                if host.offset + host.size > sh_offset {
+                    internal_error!("The host's {name} section comes after its section headers");
+                }
+                host.size as usize
+            }
+            None => 0,
+        };
+
+        *offset = align_by_constraint(*offset, MIN_SECTION_ALIGNMENT);
+        let start = *offset;
+        if let Some(index) = host_index {
+            let host = &host_sections[index];
+            exec_mmap.copy_within(
+                host.offset as usize..(host.offset + host.size) as usize,
+                start,
+            );
+        }
+        exec_mmap[start + host_size..][..data.len()].copy_from_slice(data);
+        *offset = start + host_size + data.len();
+
+        if verbose {
+            println!(
+                "Debug section, {}, is being put at offset: {:+x} (app data at {:+x})",
+                name,
+                start,
+                start + host_size
+            );
+        }
+
+        if host_index.is_none() {
+            new_names.push(name.to_string());
+        }
+        app_section_map.insert(sec.index(), (host_size as u64, start + host_size));
+        moved_sections.push(MovedSection {
+            host_index,
+            name_offset: 0,
+            offset: start as u64,
+            size: (host_size + data.len()) as u64,
+        });
+    }
+
+    for sec in app_obj.sections() {
+        let section_offset = match app_section_map.get(&sec.index()) {
+            Some((_, section_offset)) => *section_offset,
+            None => continue,
+        };
+
+        for (rel_offset, rel) in sec.relocations() {
+            let target_section = match rel.target() {
+                RelocationTarget::Symbol(index) => match app_obj.symbol_by_index(index) {
+                    Ok(sym) => match sym.section() {
+                        SymbolSection::Section(target) if app_section_map.contains_key(&target) => {
+                            Some((target, sym.address()))
+                        }
+                        _ => None,
+                    },
+                    Err(_) => None,
+                },
+                RelocationTarget::Section(target) => Some((target, 0)),
+                _ => internal_error!("Relocation target not yet support: {:+x?}", rel),
+            };
+
+            let target = match (target_section, rel.target()) {
+                // A reference into another debug section, which now starts after the host's data.
+                (Some((target, address)), _) => match app_section_map.get(&target) {
+                    Some((base, _)) => base + address,
+                    None => {
+                        internal_error!("Debug relocation into a non-debug section: {:+x?}", rel)
+                    }
+                },
+                (None, RelocationTarget::Symbol(index)) => match symbol_vaddr_map.get(&index) {
+                    Some(vaddr) => *vaddr as u64,
+                    None => internal_error!(
+                        "Undefined Symbol in relocation, {:+x?}: {:+x?}",
+                        rel,
+                        app_obj.symbol_by_index(index)
+                    ),
+                },
+                (None, _) => internal_error!("Relocation target not yet support: {:+x?}", rel),
+            };
+
+            let value = match rel.kind() {
+                RelocationKind::Absolute => target as i64 + rel.addend(),
+                x => internal_error!("Relocation Kind not yet support: {:?}", x),
+            };
+
+            let base = section_offset + rel_offset as usize;
+            match rel.size() {
+                32 => exec_mmap[base..][..4].copy_from_slice(&(value as u32).to_le_bytes()),
+                64 => exec_mmap[base..][..8].copy_from_slice(&(value as u64).to_le_bytes()),
+                other => internal_error!("Relocation size not yet supported: {other}"),
+            }
+        }
+    }
+
+    // Sections the host didn't have need names, so extend a copy of its section name table.
+    if !new_names.is_empty() {
+        let host = &host_sections[sh_str_index];
+
+        *offset = align_by_constraint(*offset, MIN_SECTION_ALIGNMENT);
+        let start = *offset;
+        exec_mmap.copy_within(
+            host.offset as usize..(host.offset + host.size) as usize,
+            start,
+        );
+        *offset += host.size as usize;
+
+        let mut new_sections = moved_sections
+            .iter_mut()
+            .filter(|sec| sec.host_index.is_none());
+        for (name, moved) in new_names.iter().zip(&mut new_sections) {
+            moved.name_offset = (*offset - start) as u32;
+            exec_mmap[*offset..][..name.len()].copy_from_slice(name.as_bytes());
+            exec_mmap[*offset + name.len()] = 0;
+            *offset += name.len() + 1;
+        }
+
+        moved_sections.push(MovedSection {
+            host_index: Some(sh_str_index),
+            name_offset: 0,
+            offset: start as u64,
+            size: (*offset - start) as u64,
+        });
+    }
+
+    moved_sections
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1837,6 +2471,11 @@ mod tests {
         );
 
         let zig = std::env::var("ROC_ZIG").unwrap_or_else(|_| "zig".into());
+        let zig_target = match target.architecture {
+            target_lexicon::Architecture::X86_64 => "x86_64-linux-gnu",
+            target_lexicon::Architecture::Aarch64(_) => "aarch64-linux-gnu",
+            _ => unreachable!(),
+        };
 
         std::fs::write(dir.join("host.zig"), host_zig.as_bytes()).unwrap();
         std::fs::write(dir.join("app.zig"), app_zig.as_bytes()).unwrap();
@@ -1849,7 +2488,7 @@ mod tests {
                 "app.zig",
                 "-fPIC",
                 "-target",
-                "x86_64-linux-gnu",
+                zig_target,
                 "-OReleaseFast",
             ])
             .output()
@@ -1878,7 +2517,7 @@ mod tests {
                 .collect()
         };
 
-        let dylib_bytes = crate::generate_dylib::create_dylib_elf64(&names).unwrap();
+        let dylib_bytes = crate::generate_dylib::create_dylib_elf64(&names, target).unwrap();
         std::fs::write(dir.join("libapp.so"), dylib_bytes).unwrap();
 
         // now we can compile the host (it uses libapp.so, hence the order here)
@@ -1891,7 +2530,7 @@ mod tests {
                 "-fPIE",
                 "-lc",
                 "-target",
-                "x86_64-linux-gnu",
+                zig_target,
                 "-OReleaseFast",
             ])
             .output()
@@ -1910,6 +2549,7 @@ mod tests {
 
         preprocess_elf(
             target_lexicon::Endianness::Little,
+            LinkType::Executable,
             &dir.join("host"),
             &dir.join("metadata"),
             &preprocessed_host_filename,
@@ -1957,4 +2597,194 @@ mod tests {
 
         assert_eq!("Hello foo\n", output);
     }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn zig_host_app_aarch64() {
+        use std::str::FromStr;
+
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+
+        zig_host_app_help(dir, &Triple::from_str("aarch64-unknown-linux-gnu").unwrap());
+
+        let final_bytes = std::fs::read(dir.join("final")).unwrap();
+        let object = object::File::parse(final_bytes.as_slice()).unwrap();
+        assert_eq!(object.architecture(), object::Architecture::Aarch64);
+
+        let roc_magic1 = object
+            .dynamic_symbols()
+            .find(|sym| sym.name().ok() == Some("roc_magic1"))
+            .unwrap()
+            .address();
+
+        // the host's call should now branch straight to the app, bypassing the PLT
+        let text = object.section_by_name(".text").unwrap();
+        let calls_app = text
+            .data()
+            .unwrap()
+            .chunks_exact(4)
+            .enumerate()
+            .any(|(i, word)| {
+                let inst = u32::from_le_bytes(word.try_into().unwrap());
+                let address = text.address() + 4 * i as u64;
+
+                inst & 0xfc00_0000 == 0x9400_0000
+                    && address.wrapping_add(aarch64_branch_offset(inst) as u64) == roc_magic1
+            });
+        assert!(calls_app, "no bl to roc_magic1 at {:+x}", roc_magic1);
+
+        if cfg!(target_arch = "aarch64") {
+            let output = std::process::Command::new(dir.join("final"))
+                .current_dir(dir)
+                .output()
+                .unwrap();
+
+            assert!(output.status.success(), "app.exe failed");
+            assert_eq!("Hello foo\n", String::from_utf8_lossy(&output.stdout));
+        }
+    }
+
+    #[test]
+    fn aarch64_branch_round_trip() {
+        let bl = 0x9400_0000;
+
+        for offset in [0, 4, -4, 0x1234, -0x7ff_fffc, 0x7ff_fffc] {
+            let inst = aarch64_set_branch_offset(bl, offset);
+
+            assert_eq!(inst & 0xfc00_0000, bl);
+            assert_eq!(aarch64_branch_offset(inst), offset);
+        }
+    }
+
+    #[allow(dead_code)]
+    fn zig_build(dir: &Path, args: &[&str]) {
+        let zig = std::env::var("ROC_ZIG").unwrap_or_else(|_| "zig".into());
+
+        let output = std::process::Command::new(&zig)
+            .current_dir(dir)
+            .args(args)
+            .output()
+            .unwrap();
+
+        if !output.status.success() {
+            use std::io::Write;
+
+            std::io::stdout().write_all(&output.stdout).unwrap();
+            std::io::stderr().write_all(&output.stderr).unwrap();
+
+            panic!("zig {} failed", args[0]);
+        }
+    }
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    #[test]
+    fn zig_host_lib() {
+        use std::ffi::CString;
+        use std::str::FromStr;
+
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let target = Triple::from_str("x86_64-unknown-linux-gnu").unwrap();
+
+        let host_zig = indoc!(
+            r#"
+            extern fn roc_magic1(usize) callconv(.C) [*]const u8;
+
+            export fn host_magic(index: usize) [*]const u8 {
+                return roc_magic1(index);
+            }
+            "#
+        );
+
+        let app_zig = indoc!(
+            r#"
+            const X = [_][]const u8 { "foo" };
+
+            export fn roc_magic1(index: usize) [*]const u8 {
+                return X[index].ptr;
+            }
+            "#
+        );
+
+        std::fs::write(dir.join("host.zig"), host_zig.as_bytes()).unwrap();
+        std::fs::write(dir.join("app.zig"), app_zig.as_bytes()).unwrap();
+
+        zig_build(
+            dir,
+            &[
+                "build-obj",
+                "app.zig",
+                "-fPIC",
+                "-target",
+                "x86_64-linux-gnu",
+                "-OReleaseFast",
+            ],
+        );
+
+        let roc_app = std::fs::read(dir.join("app.o")).unwrap();
+
+        let dylib_bytes =
+            crate::generate_dylib::create_dylib_elf64(&["roc_magic1".to_string()], &target)
+                .unwrap();
+        std::fs::write(dir.join("libapp.so"), dylib_bytes).unwrap();
+
+        // the host is itself a shared library, which uses libapp.so
+        zig_build(
+            dir,
+            &[
+                "build-lib",
+                "-dynamic",
+                "libapp.so",
+                "host.zig",
+                "-fPIC",
+                "-lc",
+                "-target",
+                "x86_64-linux-gnu",
+                "-OReleaseFast",
+                "-femit-bin=dynhost.so",
+            ],
+        );
+
+        let preprocessed_host_filename = dir.join(preprocessed_host_filename(&target).unwrap());
+
+        preprocess_elf(
+            target_lexicon::Endianness::Little,
+            LinkType::Dylib,
+            &dir.join("dynhost.so"),
+            &dir.join("metadata"),
+            &preprocessed_host_filename,
+            &dir.join("libapp.so"),
+            false,
+            false,
+        );
+
+        std::fs::copy(&preprocessed_host_filename, dir.join("libfinal.so")).unwrap();
+
+        surgery_elf(
+            &roc_app,
+            &dir.join("metadata"),
+            &dir.join("libfinal.so"),
+            false,
+            false,
+        );
+
+        // both the app's function and the host's wrapper of it are exported
+        let path = CString::new(dir.join("libfinal.so").to_str().unwrap()).unwrap();
+        unsafe {
+            let lib = libc::dlopen(path.as_ptr(), libc::RTLD_NOW);
+            assert!(!lib.is_null(), "dlopen failed");
+
+            for name in ["roc_magic1", "host_magic"] {
+                let name = CString::new(name).unwrap();
+                let f = libc::dlsym(lib, name.as_ptr());
+                assert!(!f.is_null(), "{:?} is not exported", name);
+
+                let f: extern "C" fn(usize) -> *const u8 = std::mem::transmute(f);
+                assert_eq!(b"foo", std::slice::from_raw_parts(f(0), 3));
+            }
+
+            libc::dlclose(lib);
+        }
+    }
 }
@@ -31,10 +31,11 @@ pub enum LinkType {
 }
 
 pub fn supported(link_type: LinkType, target: &Triple) -> bool {
-    if let LinkType::Executable = link_type {
-        match target {
+    match link_type {
+        LinkType::Executable => match target {
             Triple {
-                architecture: target_lexicon::Architecture::X86_64,
+                architecture:
+                    target_lexicon::Architecture::X86_64 | target_lexicon::Architecture::Aarch64(_),
                 operating_system: target_lexicon::OperatingSystem::Linux,
                 binary_format: target_lexicon::BinaryFormat::Elf,
                 ..
@@ -55,9 +56,19 @@ pub fn supported(link_type: LinkType, target: &Triple) -> bool {
             } => true,
 
             _ => false,
-        }
-    } else {
-        false
+        },
+        // shared library hosts are only supported for ELF
+        LinkType::Dylib => matches!(
+            target,
+            Triple {
+                architecture: target_lexicon::Architecture::X86_64
+                    | target_lexicon::Architecture::Aarch64(_),
+                operating_system: target_lexicon::OperatingSystem::Linux,
+                binary_format: target_lexicon::BinaryFormat::Elf,
+                ..
+            }
+        ),
+        LinkType::None => false,
     }
 }
 
@@ -67,19 +78,28 @@ pub fn preprocessed_host_filename(target: &Triple) -> Option<String> {
     roc_target::get_target_triple_str(target).map(|x| format!("{}.{}", x, PRECOMPILED_HOST_EXT))
 }
 
-fn metadata_file_name(target: &Triple) -> String {
-    let target_triple_str = get_target_triple_str(target);
+/// The preprocessed host for `--lib` builds, where the host is itself a shared library.
+pub fn preprocessed_lib_host_filename(target: &Triple) -> Option<String> {
+    roc_target::get_target_triple_str(target).map(|x| format!("{}.lib.{}", x, PRECOMPILED_HOST_EXT))
+}
+
+fn metadata_file_name(target: &Triple, link_type: LinkType) -> String {
+    let target_triple_str = get_target_triple_str(target).unwrap_or("unknown");
 
-    format!("metadata_{}.rm", target_triple_str.unwrap_or("unknown"))
+    match link_type {
+        LinkType::Dylib => format!("metadata_{}.lib.rm", target_triple_str),
+        LinkType::Executable | LinkType::None => format!("metadata_{}.rm", target_triple_str),
+    }
 }
 
 pub fn link_preprocessed_host(
     target: &Triple,
+    link_type: LinkType,
     platform_path: &Path,
     roc_app_bytes: &[u8],
     binary_path: &Path,
 ) {
-    let metadata = platform_path.with_file_name(metadata_file_name(target));
+    let metadata = platform_path.with_file_name(metadata_file_name(target, link_type));
     surgery(roc_app_bytes, &metadata, binary_path, false, false, target)
 }
 
@@ -382,15 +402,20 @@ fn stub_lib_is_up_to_date(target: &Triple, stub_lib_path: &Path, custom_names: &
     it1.eq(it2)
 }
 
+/// Preprocesses the host that was built against the stub `shared_lib`. For `LinkType::Dylib` that
+/// host is a shared library, `dynhost.so`, which must use every app symbol it is meant to export.
 pub fn preprocess_host(
     target: &Triple,
+    link_type: LinkType,
     platform_main_roc: &Path,
     preprocessed_path: &Path,
     shared_lib: &Path,
     stub_dll_symbols: &[String],
 ) {
-    let metadata_path = platform_main_roc.with_file_name(metadata_file_name(target));
-    let host_exe_path = if let target_lexicon::OperatingSystem::Windows = target.operating_system {
+    let metadata_path = platform_main_roc.with_file_name(metadata_file_name(target, link_type));
+    let host_exe_path = if let LinkType::Dylib = link_type {
+        platform_main_roc.with_file_name("dynhost.so")
+    } else if let target_lexicon::OperatingSystem::Windows = target.operating_system {
         platform_main_roc.with_file_name("dynhost.exe")
     } else {
         platform_main_roc.with_file_name("dynhost")
@@ -398,6 +423,7 @@ pub fn preprocess_host(
 
     preprocess(
         target,
+        link_type,
         &host_exe_path,
         &metadata_path,
         preprocessed_path,
@@ -412,6 +438,7 @@ pub fn preprocess_host(
 #[allow(clippy::too_many_arguments)]
 fn preprocess(
     target: &Triple,
+    link_type: LinkType,
     host_exe_path: &Path,
     metadata_path: &Path,
     preprocessed_path: &Path,
@@ -432,6 +459,7 @@ fn preprocess(
         target_lexicon::BinaryFormat::Elf => {
             crate::elf::preprocess_elf(
                 endianness,
+                link_type,
                 host_exe_path,
                 metadata_path,
                 preprocessed_path,
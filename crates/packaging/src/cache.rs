@@ -1,8 +1,8 @@
 #[cfg(not(target_family = "wasm"))]
 use {
-    crate::https::{self, PackageMetadata, Problem},
+    crate::https::{self, PackageMetadata, Problem, TarballSource},
     roc_error_macros::internal_error,
-    std::fs,
+    std::{env, fs},
 };
 #[cfg(not(target_family = "wasm"))]
 const MAX_DOWNLOAD_BYTES: u64 = 32 * 1_000_000_000; // GB
@@ -13,6 +13,12 @@ use std::path::{Path, PathBuf};
 pub enum RocCacheDir<'a> {
     /// Normal scenario: reading from the user's cache dir on disk
     Persistent(&'a Path),
+    /// For air-gapped machines: look for packages in the project's vendor dir (see `roc vendor`)
+    /// and then in the user's cache dir, but never download anything.
+    Offline {
+        cache_dir: &'a Path,
+        vendor_dir: &'a Path,
+    },
     /// For build.rs and tests where we never want to be downloading anything - yell loudly if we try!
     Disallowed,
     /// For tests only; we don't want to write to the real cache during a test!
@@ -20,6 +26,29 @@ pub enum RocCacheDir<'a> {
     Temp(&'a tempfile::TempDir),
 }
 
+impl<'a> RocCacheDir<'a> {
+    /// The dir that downloaded packages are unpacked into, if there is one.
+    pub fn cache_dir(&self) -> Option<&'a Path> {
+        match *self {
+            RocCacheDir::Persistent(cache_dir) | RocCacheDir::Offline { cache_dir, .. } => {
+                Some(cache_dir)
+            }
+            RocCacheDir::Disallowed => None,
+            #[cfg(test)]
+            RocCacheDir::Temp(temp_dir) => Some(temp_dir.path()),
+        }
+    }
+
+    /// The project's vendor dir, if packages must be found there (or in the cache) rather than
+    /// downloaded.
+    pub fn vendor_dir(&self) -> Option<&'a Path> {
+        match *self {
+            RocCacheDir::Offline { vendor_dir, .. } => Some(vendor_dir),
+            _ => None,
+        }
+    }
+}
+
 // Errors in case NixOS users try to use a dynamically linked platform
 #[cfg(target_os = "linux")]
 fn nixos_error_if_dynamic(url: &str, dest_dir: &Path) {
@@ -59,7 +88,9 @@ fn nixos_error_if_dynamic(url: &str, dest_dir: &Path) {
 /// into that dir. If the cache dir on the filesystem, then look into it to see if we already
 /// have an entry for the given URL. If we do, return its info. If we don't already have it, then:
 ///
-/// - Download and decompress the compressed tarball from the given URL
+/// - Download and decompress the compressed tarball from the given URL (or from the mirror in
+///   the ROC_PACKAGE_MIRROR environment variable, if it's set), or else read it from the local
+///   filesystem if the URL is a file:// URL or a path
 /// - Verify its bytes against the hash in the URL
 /// - Extract the tarball's contents into the appropriate cache directory
 ///
@@ -70,83 +101,53 @@ pub fn install_package<'a>(
     roc_cache_dir: RocCacheDir<'_>,
     url: &'a str,
 ) -> Result<(PathBuf, Option<&'a str>), Problem> {
+    let metadata = PackageMetadata::try_from(url).map_err(Problem::InvalidUrl)?;
     let PackageMetadata {
         cache_subdir,
         content_hash,
         root_module_filename,
-    } = PackageMetadata::try_from(url).map_err(Problem::InvalidUrl)?;
+        source,
+    } = metadata;
 
     match roc_cache_dir {
         RocCacheDir::Persistent(cache_dir) => {
-            // e.g. ~/.cache/roc/example.com/roc-packages/
-            let parent_dir = cache_dir.join(cache_subdir);
-            // e.g. ~/.cache/roc/example.com/roc-packages/jDRlAFAA3738vu3-vMpLUoyxtA86Z7CaZneoOKrihbE
-            let dest_dir = parent_dir.join(content_hash);
-
-            if dest_dir.exists() {
-                // If the cache dir exists already, we assume it has the correct contents
-                // (it's a cache, after all!) and return without downloading anything.
-                //
-                #[cfg(target_os = "linux")]
-                {
-                    nixos_error_if_dynamic(url, &dest_dir);
-                }
+            let dest_dir = install_into_cache(cache_dir, url, metadata)?;
 
-                Ok((dest_dir, root_module_filename))
-            } else {
-                // Download into a tempdir; only move it to dest_dir if hash verification passes.
-                println!(
-                    "Downloading \u{001b}[36m{url}\u{001b}[0m\n    into {}\n",
-                    cache_dir.display()
-                );
-                let tempdir = tempfile::tempdir().map_err(Problem::IoErr)?;
-                let tempdir_path = tempdir.path();
-                let downloaded_hash =
-                    https::download_and_hash(url, tempdir_path, MAX_DOWNLOAD_BYTES)?;
-
-                // Download the tarball into memory and verify it.
-                // The tarball name is the hash of its contents.
-                if downloaded_hash == content_hash {
-                    // Now that we've verified the hash, rename the tempdir to the real dir.
-
-                    // Create the destination dir's parent dir, since it may not exist yet.
-                    fs::create_dir_all(parent_dir).map_err(Problem::IoErr)?;
-
-                    // This rename should be super cheap if it succeeds - just an inode change.
-                    if fs::rename(tempdir_path, &dest_dir).is_err() {
-                        // If the rename failed, try a recursive copy -
-                        // it could have failed due to std::io::ErrorKind::CrossesDevices
-                        // (e.g. if the source an destination directories are on different disks)
-                        // which as of this implementation is nightly-only
-                        // https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.CrossesDevices                       match io_err.kind() {
-                        // but if that's what happened, this should work!
-
-                        // fs_extra::dir::copy needs the destination directory to exist already.
-                        fs::create_dir(&dest_dir).map_err(Problem::IoErr)?;
-                        fs_extra::dir::copy(
-                            tempdir_path,
-                            &dest_dir,
-                            &fs_extra::dir::CopyOptions {
-                                content_only: true,
-                                ..Default::default()
-                            },
-                        )
-                        .map_err(Problem::FsExtraErr)?;
-                    }
+            Ok((dest_dir, root_module_filename))
+        }
+        RocCacheDir::Offline {
+            cache_dir,
+            vendor_dir,
+        } => {
+            // Tarballs on this computer aren't vendored, so only HTTPS packages are locked.
+            if let TarballSource::Https(_) = source {
+                crate::vendor::check_locked(vendor_dir, url, content_hash)?;
+            }
+
+            // Prefer the vendored copy, since that's the one the project's lockfile describes.
+            let searched = vec![
+                vendor_dir.join(cache_subdir).join(content_hash),
+                cache_dir.join(cache_subdir).join(content_hash),
+            ];
 
+            match searched.iter().find(|dir| dir.exists()) {
+                Some(dest_dir) => {
                     #[cfg(target_os = "linux")]
                     {
-                        nixos_error_if_dynamic(url, &dest_dir);
+                        nixos_error_if_dynamic(url, dest_dir);
                     }
 
-                    // The package's files are now in the cache. We're done!
-                    Ok((dest_dir, root_module_filename))
-                } else {
-                    Err(Problem::InvalidContentHash {
-                        expected: content_hash.to_string(),
-                        actual: downloaded_hash,
-                    })
+                    Ok((dest_dir.clone(), root_module_filename))
                 }
+                None => match source {
+                    // Tarballs on this computer don't need the network, so they're still fine.
+                    TarballSource::File(_) => {
+                        let dest_dir = install_into_cache(cache_dir, url, metadata)?;
+
+                        Ok((dest_dir, root_module_filename))
+                    }
+                    TarballSource::Https(_) => Err(Problem::NotAvailableOffline { searched }),
+                },
             }
         }
         RocCacheDir::Disallowed => {
@@ -160,6 +161,102 @@ pub fn install_package<'a>(
     }
 }
 
+/// Returns the directory in the cache that the package was installed into.
+#[cfg(not(target_family = "wasm"))]
+fn install_into_cache(
+    cache_dir: &Path,
+    url: &str,
+    metadata: PackageMetadata<'_>,
+) -> Result<PathBuf, Problem> {
+    let PackageMetadata {
+        cache_subdir,
+        content_hash,
+        source,
+        ..
+    } = metadata;
+
+    // e.g. ~/.cache/roc/example.com/roc-packages/
+    let parent_dir = cache_dir.join(cache_subdir);
+    // e.g. ~/.cache/roc/example.com/roc-packages/jDRlAFAA3738vu3-vMpLUoyxtA86Z7CaZneoOKrihbE
+    let dest_dir = parent_dir.join(content_hash);
+
+    if dest_dir.exists() {
+        // If the cache dir exists already, we assume it has the correct contents
+        // (it's a cache, after all!) and return without downloading anything.
+        //
+        #[cfg(target_os = "linux")]
+        {
+            nixos_error_if_dynamic(url, &dest_dir);
+        }
+
+        return Ok(dest_dir);
+    }
+
+    // Unpack into a tempdir; only move it to dest_dir if hash verification passes.
+    let tempdir = tempfile::tempdir().map_err(Problem::IoErr)?;
+    let tempdir_path = tempdir.path();
+    let unpacked_hash = match source {
+        TarballSource::Https(url) => {
+            let download_url = match env::var(https::MIRROR_ENV_VAR) {
+                Ok(mirror) if !mirror.is_empty() => https::mirrored_url(&mirror, url),
+                _ => url.to_string(),
+            };
+
+            println!(
+                "Downloading \u{001b}[36m{download_url}\u{001b}[0m\n    into {}\n",
+                cache_dir.display()
+            );
+
+            // Download the tarball into memory and verify it.
+            https::download_and_hash(&download_url, tempdir_path, MAX_DOWNLOAD_BYTES)?
+        }
+        TarballSource::File(path) => https::unpack_and_hash(Path::new(path), tempdir_path)?,
+    };
+
+    // The tarball name is the hash of its contents.
+    if unpacked_hash != content_hash {
+        return Err(Problem::InvalidContentHash {
+            expected: content_hash.to_string(),
+            actual: unpacked_hash,
+        });
+    }
+
+    // Now that we've verified the hash, rename the tempdir to the real dir.
+
+    // Create the destination dir's parent dir, since it may not exist yet.
+    fs::create_dir_all(parent_dir).map_err(Problem::IoErr)?;
+
+    // This rename should be super cheap if it succeeds - just an inode change.
+    if fs::rename(tempdir_path, &dest_dir).is_err() {
+        // If the rename failed, try a recursive copy -
+        // it could have failed due to std::io::ErrorKind::CrossesDevices
+        // (e.g. if the source an destination directories are on different disks)
+        // which as of this implementation is nightly-only
+        // https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.CrossesDevices                       match io_err.kind() {
+        // but if that's what happened, this should work!
+
+        // fs_extra::dir::copy needs the destination directory to exist already.
+        fs::create_dir(&dest_dir).map_err(Problem::IoErr)?;
+        fs_extra::dir::copy(
+            tempdir_path,
+            &dest_dir,
+            &fs_extra::dir::CopyOptions {
+                content_only: true,
+                ..Default::default()
+            },
+        )
+        .map_err(Problem::FsExtraErr)?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        nixos_error_if_dynamic(url, &dest_dir);
+    }
+
+    // The package's files are now in the cache. We're done!
+    Ok(dest_dir)
+}
+
 #[cfg(windows)]
 // e.g. the "Roc" in %APPDATA%\\Roc
 const ROC_CACHE_DIR_NAME: &str = "Roc";
@@ -1,6 +1,6 @@
 use std::{
     io::{self, Read},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 use crate::tarball::Compression;
@@ -13,7 +13,7 @@ use crate::tarball::Compression;
 // let's try to avoid doing that.
 const BROTLI_BUFFER_BYTES: usize = 8 * 1_000_000; // MB
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct PackageMetadata<'a> {
     /// The BLAKE3 hash of the tarball's contents. Also the .tar filename on disk.
     pub content_hash: &'a str,
@@ -21,6 +21,44 @@ pub struct PackageMetadata<'a> {
     pub cache_subdir: &'a str,
     /// Other code will default this to main.roc, but this module isn't concerned with that default.
     pub root_module_filename: Option<&'a str>,
+    pub source: TarballSource<'a>,
+}
+
+/// Where to get a package's tarball from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TarballSource<'a> {
+    /// Download it from this https:// URL
+    Https(&'a str),
+    /// Read it from this path on the local filesystem, given either as a file:// URL or directly
+    File(&'a str),
+}
+
+/// Tarballs on the local filesystem all live in this subfolder of the cache dir, since
+/// where they happened to be on disk says nothing about which package they are.
+pub const LOCAL_CACHE_SUBDIR: &str = "local";
+
+const FILE_PROTOCOL: &str = "file://";
+
+/// The environment variable that points package downloads at a mirror, e.g. a local artifact
+/// server, instead of at the host in each package's URL.
+pub const MIRROR_ENV_VAR: &str = "ROC_PACKAGE_MIRROR";
+
+/// The URL to download a package from, given the base URL of a mirror: the package's URL,
+/// with everything up to the host replaced by the mirror's base URL.
+///
+/// The mirror doesn't have to use https, because the content hash in the original URL
+/// still gets verified.
+pub fn mirrored_url(mirror_base_url: &str, url: &str) -> String {
+    let without_protocol = match url.split_once("https://") {
+        Some((_, without_protocol)) => without_protocol,
+        None => url,
+    };
+
+    format!(
+        "{}/{}",
+        mirror_base_url.trim_end_matches('/'),
+        without_protocol
+    )
 }
 
 /// Valid URLs must end in one of these:
@@ -65,12 +103,17 @@ impl<'a> TryFrom<&'a str> for PackageMetadata<'a> {
 
 impl<'a> PackageMetadata<'a> {
     fn new(url: &'a str) -> Result<Self, UrlProblem> {
-        // First, verify that the URL starts with https://
-        let without_protocol = match url.split_once("https://") {
-            Some((_, without_protocol)) => without_protocol,
-            None => {
-                return Err(UrlProblem::MissingHttps);
-            }
+        // First, verify that the URL starts with https:// - unless it's a tarball on this
+        // computer, either as a file:// URL or as a plain path.
+        let (without_protocol, is_local) = match url.split_once("https://") {
+            Some((_, without_protocol)) => (without_protocol, false),
+            None => match url.strip_prefix(FILE_PROTOCOL) {
+                Some(path) => (path, true),
+                None if !url.contains("://") => (url, true),
+                None => {
+                    return Err(UrlProblem::MissingHttps);
+                }
+            },
         };
 
         // Next, check if there are misleading characters in the URL
@@ -112,18 +155,29 @@ impl<'a> PackageMetadata<'a> {
             }
         };
 
-        let (path, tarball_name) = match without_ext.rsplit_once('/') {
+        let (path, tarball_name) = match without_ext.rsplit_once(&['/', '\\'][..]) {
             Some((path, hash)) if !hash.is_empty() => (path, hash),
+            None if is_local && !without_ext.is_empty() => ("", without_ext),
             _ => {
                 return Err(UrlProblem::MissingHash);
             }
         };
 
-        Ok(PackageMetadata {
-            cache_subdir: path,
-            content_hash: tarball_name,
-            root_module_filename: fragment,
-        })
+        if is_local {
+            Ok(PackageMetadata {
+                cache_subdir: LOCAL_CACHE_SUBDIR,
+                content_hash: tarball_name,
+                root_module_filename: fragment,
+                source: TarballSource::File(without_fragment),
+            })
+        } else {
+            Ok(PackageMetadata {
+                cache_subdir: path,
+                content_hash: tarball_name,
+                root_module_filename: fragment,
+                source: TarballSource::Https(url),
+            })
+        }
     }
 }
 
@@ -206,6 +260,7 @@ fn url_without_fragment() {
         cache_subdir: "example.com/path",
         content_hash: "hash",
         root_module_filename: None,
+        source: TarballSource::Https("https://example.com/path/hash.tar.gz"),
     });
     assert_eq!(
         PackageMetadata::try_from("https://example.com/path/hash.tar.gz"),
@@ -219,6 +274,7 @@ fn url_with_fragment() {
         cache_subdir: "example.com/path",
         content_hash: "hash",
         root_module_filename: Some("filename.roc"),
+        source: TarballSource::Https("https://example.com/path/hash.tar.gz#filename.roc"),
     });
     assert_eq!(
         PackageMetadata::try_from("https://example.com/path/hash.tar.gz#filename.roc"),
@@ -226,6 +282,70 @@ fn url_with_fragment() {
     );
 }
 
+#[test]
+fn file_url_with_fragment() {
+    let expected = Ok(PackageMetadata {
+        cache_subdir: LOCAL_CACHE_SUBDIR,
+        content_hash: "hash",
+        root_module_filename: Some("filename.roc"),
+        source: TarballSource::File("/srv/packages/hash.tar.br"),
+    });
+    assert_eq!(
+        PackageMetadata::try_from("file:///srv/packages/hash.tar.br#filename.roc"),
+        expected
+    );
+}
+
+#[test]
+fn local_tarball_path() {
+    let expected = Ok(PackageMetadata {
+        cache_subdir: LOCAL_CACHE_SUBDIR,
+        content_hash: "hash",
+        root_module_filename: None,
+        source: TarballSource::File("../packages/hash.tar"),
+    });
+    assert_eq!(PackageMetadata::try_from("../packages/hash.tar"), expected);
+}
+
+#[test]
+fn local_tarball_hash() {
+    let contents = b"package \"example\"\n";
+    let mut header = tar::Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+
+    let mut builder = tar::Builder::new(Vec::new());
+    builder
+        .append_data(&mut header, "main.roc", &contents[..])
+        .unwrap();
+    let archive = builder.into_inner().unwrap();
+    let hash = base64_url::encode(blake3::hash(&archive).as_bytes());
+
+    let src_dir = tempfile::tempdir().unwrap();
+    let tarball_path = src_dir.path().join(format!("{hash}.tar"));
+    std::fs::write(&tarball_path, &archive).unwrap();
+
+    let dest_dir = tempfile::tempdir().unwrap();
+
+    assert_eq!(
+        unpack_and_hash(&tarball_path, dest_dir.path()).unwrap(),
+        hash
+    );
+    assert!(dest_dir.path().join("main.roc").exists());
+}
+
+#[test]
+fn mirrored_urls() {
+    assert_eq!(
+        mirrored_url(
+            "http://localhost:8000/roc/",
+            "https://example.com/path/hash.tar.gz#filename.roc"
+        ),
+        "http://localhost:8000/roc/example.com/path/hash.tar.gz#filename.roc"
+    );
+}
+
 #[derive(Debug)]
 pub enum Problem {
     UnsupportedEncoding(String),
@@ -240,6 +360,27 @@ pub enum Problem {
     InvalidUrl(UrlProblem),
     /// The Content-Length header of the response exceeded max_download_bytes
     DownloadTooBig(u64),
+    /// We were not allowed to download the package, and it was in none of these directories
+    NotAvailableOffline {
+        searched: Vec<PathBuf>,
+    },
+    /// We were running offline, and couldn't read the project's lockfile
+    ReadLockfile(PathBuf, io::Error),
+    /// This line of the project's lockfile isn't a package
+    InvalidLockfile {
+        lockfile: PathBuf,
+        line: usize,
+    },
+    /// We were running offline, and the package isn't in the project's lockfile
+    NotInLockfile {
+        lockfile: PathBuf,
+    },
+    /// The project's lockfile has a different content hash for this package
+    LockfileHashMismatch {
+        lockfile: PathBuf,
+        expected: String,
+        actual: String,
+    },
 }
 
 pub fn download_and_hash(
@@ -280,6 +421,15 @@ pub fn download_and_hash(
     decompress_into(dest_dir, encoding, resp.take(max_download_bytes))
 }
 
+/// Like [download_and_hash], but for a tarball on the local filesystem, whose compression is
+/// inferred from its file extension.
+pub fn unpack_and_hash(path: &Path, dest_dir: &Path) -> Result<String, Problem> {
+    let encoding = Encoding::new("", &path.to_string_lossy())?;
+    let file = std::fs::File::open(path).map_err(Problem::IoErr)?;
+
+    decompress_into(dest_dir, encoding, file)
+}
+
 /// The content encodings we support
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Encoding {
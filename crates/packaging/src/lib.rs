@@ -2,3 +2,5 @@ pub mod cache;
 #[cfg(not(target_family = "wasm"))]
 pub mod https;
 pub mod tarball;
+#[cfg(not(target_family = "wasm"))]
+pub mod vendor;
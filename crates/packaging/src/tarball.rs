@@ -2,13 +2,12 @@ use brotli::enc::BrotliEncoderParams;
 use bumpalo::Bump;
 use flate2::write::GzEncoder;
 use roc_parse::ast::{Header, Module};
-use roc_parse::header::PlatformHeader;
 use roc_parse::module::parse_header;
 use roc_parse::state::State;
 use std::ffi::OsStr;
 use std::fs::File;
 use std::io::{self, Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tar;
 use walkdir::WalkDir;
 
@@ -54,17 +53,119 @@ impl<'a> TryFrom<&'a str> for Compression {
     }
 }
 
-/// Given a path to a .roc file, write a .tar file to disk.
+/// Whether this package source (as written in a module header) is a tarball that has to be
+/// verified and unpacked into the cache - a https:// URL, a file:// URL or a path to a
+/// tarball - as opposed to a .roc file that's used as-is.
+pub fn is_tarball_src(src: &str) -> bool {
+    let without_fragment = match src.rsplit_once('#') {
+        Some((before_fragment, _)) => before_fragment,
+        None => src,
+    };
+
+    src.starts_with("https://")
+        || src.starts_with("file://")
+        || [
+            Compression::Uncompressed,
+            Compression::Gzip,
+            Compression::Brotli,
+        ]
+        .iter()
+        .any(|compression| without_fragment.ends_with(compression.file_ext()))
+}
+
+/// The kinds of module that can be the root of a bundle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BundleRoot {
+    Package,
+    Platform,
+}
+
+/// Reads the header of the module a bundle would be built from, which has to be a package or a
+/// platform.
+pub fn bundle_root(path_to_main: &Path) -> io::Result<BundleRoot> {
+    let arena = Bump::new();
+    let mut buf = Vec::new();
+
+    match read_header(&arena, &mut buf, path_to_main)?.header {
+        Header::Package(_) => Ok(BundleRoot::Package),
+        Header::Platform(_) => Ok(BundleRoot::Platform),
+        Header::Interface(_) | Header::App(_) | Header::Hosted(_) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{} is not a package or platform module, so it can't be bundled.",
+                path_to_main.display()
+            ),
+        )),
+    }
+}
+
+/// Everything that goes into a bundle, as paths relative to the directory of its root module.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    /// The .roc files of the root module and of every module reachable from it
+    pub modules: Vec<PathBuf>,
+    /// The prebuilt host files for each target, e.g. `linux-x86_64.rh` and
+    /// `metadata_linux-x86_64.rm` for `linux-x86_64`. Packages don't have any.
+    pub hosts: Vec<(String, Vec<PathBuf>)>,
+}
+
+impl Manifest {
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.modules
+            .iter()
+            .chain(self.hosts.iter().flat_map(|(_, files)| files))
+            .map(PathBuf::as_path)
+    }
+
+    /// One section per target, each listing that target's files, after a section listing the
+    /// modules (which every target uses).
+    pub fn to_file_contents(&self) -> String {
+        let mut buf = String::from("[modules]\n");
+
+        for module in self.modules.iter() {
+            buf.push_str(&module.to_string_lossy());
+            buf.push('\n');
+        }
+
+        for (target, files) in self.hosts.iter() {
+            buf.push_str(&format!("\n[{target}]\n"));
+
+            for file in files {
+                buf.push_str(&file.to_string_lossy());
+                buf.push('\n');
+            }
+        }
+
+        buf
+    }
+}
+
+/// The filename of the manifest written alongside the archive with this filename.
+pub fn manifest_filename(archive_filename: &str) -> String {
+    let hash = match archive_filename.split_once('.') {
+        Some((hash, _)) => hash,
+        None => archive_filename,
+    };
+
+    format!("{hash}.manifest")
+}
+
+/// Given a path to a .roc file and the manifest of everything that should be bundled with it,
+/// write a .tar file to disk, along with the manifest.
 ///
 /// The .tar file will be in the same directory, and its filename
 /// will be the hash of its contents. This function returns
 /// the name of that filename (including the .tar extension),
 /// so the caller can obtain the path to the file by calling
 /// Path::with_file_name(returned_string) on the Path argument it provided.
-pub fn build(path_to_main: &Path, compression: Compression) -> io::Result<String> {
+pub fn build(
+    path_to_main: &Path,
+    compression: Compression,
+    manifest: &Manifest,
+) -> io::Result<String> {
     let mut archive_bytes = Vec::new();
 
-    write_archive(path_to_main, &mut archive_bytes)?;
+    write_archive(path_to_main, manifest, &mut archive_bytes)?;
 
     // Now that we have our compressed archive, get its BLAKE3 hash
     // and base64url encode it. Use base64url encoding because:
@@ -107,11 +208,16 @@ pub fn build(path_to_main: &Path, compression: Compression) -> io::Result<String
         };
     }
 
+    std::fs::write(
+        path_to_main.with_file_name(manifest_filename(&filename)),
+        manifest.to_file_contents(),
+    )?;
+
     Ok(filename)
 }
 
-/// Write an uncompressed tar archive to the given writer.
-fn write_archive<W: Write>(path: &Path, writer: W) -> io::Result<()> {
+/// Write an uncompressed tar archive of the files in the manifest to the given writer.
+fn write_archive<W: Write>(path: &Path, manifest: &Manifest, writer: W) -> io::Result<()> {
     let root_dir = if let Some(parent) = path.parent() {
         parent
     } else {
@@ -122,107 +228,21 @@ fn write_archive<W: Write>(path: &Path, writer: W) -> io::Result<()> {
         std::process::exit(1);
     };
     let mut builder = tar::Builder::new(writer);
-    let arena = Bump::new();
-    let mut buf = Vec::new();
-
-    // TODO use this when finding .roc files by discovering them from the root module.
-    // let other_modules: &[Module<'_>] =
-    match read_header(&arena, &mut buf, path)?.header {
-        Header::Interface(_) => {
-            todo!();
-            // TODO report error
-        }
-        Header::App(_) => {
-            todo!();
-            // TODO report error
-        }
-        Header::Hosted(_) => {
-            todo!();
-            // TODO report error
-        }
-        Header::Package(_) => {
-            add_dot_roc_files(root_dir, &mut builder)?;
-        }
-        Header::Platform(PlatformHeader { imports: _, .. }) => {
-            // Add all the prebuilt host files to the archive.
-            // These should all be in the same directory as the platform module.
-            for entry in std::fs::read_dir(root_dir)? {
-                let path = entry?.path();
-
-                if [
-                    // surgical linker format
-                    Some("rh"),
-                    // metadata file
-                    Some("rm"),
-                    // legacy linker formats
-                    Some("o"),
-                    Some("obj"),
-                    Some("wasm"),
-                    // optimized wasm builds compile to .zig for now,
-                    // because zig can't emit .bc for wasm yet.
-                    Some("zig"),
-                ]
-                .contains(&path.extension().and_then(OsStr::to_str))
-                {
-                    builder.append_path_with_name(
-                        &path,
-                        // Store it without the root path, so that (for example) we don't store
-                        // `examples/cli/main.roc` and therefore end up with the root of the tarball
-                        // being an `examples/cli/` dir instead of having `main.roc` in the root.
-                        path.strip_prefix(root_dir).unwrap(),
-                    )?;
-                }
-            }
-
-            add_dot_roc_files(root_dir, &mut builder)?;
-        }
-    };
 
-    // TODO: This will be necessary when bundling packages (not platforms, since platforms just
-    // slurp up the whole directory at the moment) and also platforms in a future where they
-    // have precompiled hosts, and we only need to grab the .roc files and the precompiled hostfiles!
-    // {
-    //     // Repeat this process on each of the root module's imports.
-    //     let mut stack = Vec::from_iter_in(other_modules, &arena);
-    //     let mut visited_paths = HashSet::from_iter([path]);
-
-    //     // We could do this all in parallel, but a simple stack seems fast enough for this use case.
-    //     while let Some(path) = stack.pop() {
-    //         let other_modules = match read_header(&arena, &mut buf, path) {
-    //             Module::Interface { .. } => {
-    //                 // TODO use header.imports
-    //                 builder.append_path(path)?;
-    //             }
-    //             Module::App { .. } => {
-    //                 // TODO report error
-    //             }
-    //             Module::Hosted { header } => {
-    //                 // TODO report error
-    //             }
-    //             Module::Platform { header } => {
-    //                 // TODO report error
-    //             }
-    //         };
-    //         let other_paths = todo!("infer from other_modules");
-
-    //         // Recurse on the other paths in the header
-    //         for other_path in other_paths {
-    //             if !visited_paths.contains(other_path) {
-    //                 stack.push(other_path);
-    //             }
-    //         }
-
-    //         paths_visited.insert(path);
-    //     }
-    // }
+    for file in manifest.files() {
+        // Store it without the root path, so that (for example) we don't store
+        // `examples/cli/main.roc` and therefore end up with the root of the tarball
+        // being an `examples/cli/` dir instead of having `main.roc` in the root.
+        builder.append_path_with_name(root_dir.join(file), file)?;
+    }
 
     builder.finish()
 }
 
-fn add_dot_roc_files<W: Write>(
-    root_dir: &Path,
-    builder: &mut tar::Builder<W>,
-) -> Result<(), io::Error> {
+/// Every .roc file in the given directory and its subdirectories, relative to that directory.
+pub fn roc_files(root_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
     for entry in WalkDir::new(root_dir).into_iter().filter_entry(|entry| {
         let path = entry.path();
 
@@ -233,21 +253,14 @@ fn add_dot_roc_files<W: Write>(
         let path = entry.path();
 
         // Only include files, not directories or symlinks.
-        // Symlinks may not work on Windows, and directories will get automatically
-        // added based on the paths of the files inside anyway. (In fact, if we don't
-        // filter out directories in this step, then empty ones can sometimes be added!)
         if path.is_file() {
-            builder.append_path_with_name(
-                path,
-                // Store it without the root path, so that (for example) we don't store
-                // `examples/cli/main.roc` and therefore end up with the root of the tarball
-                // being an `examples/cli/` dir instead of having `main.roc` in the root.
-                path.strip_prefix(root_dir).unwrap(),
-            )?;
+            files.push(path.strip_prefix(root_dir).unwrap().to_path_buf());
         }
     }
 
-    Ok(())
+    files.sort();
+
+    Ok(files)
 }
 
 fn read_header<'a>(
@@ -268,9 +281,43 @@ fn read_header<'a>(
     // (We can't use that for the parser state and still return Module<'a> unfortunately.)
     let arena_buf = bumpalo::collections::Vec::from_iter_in(buf.iter().copied(), arena);
     let parse_state = State::new(arena_buf.into_bump_slice());
-    let (module, _) = parse_header(arena, parse_state).unwrap_or_else(|_err| {
-        todo!(); // TODO report a nice error and exit 1 - or maybe just return Err, for better testability?
-    });
+    let (module, _) = parse_header(arena, parse_state).map_err(|_err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "The header of {} has a syntax error. Running `roc check` on it will show what the problem is.",
+                path.display()
+            ),
+        )
+    })?;
 
     Ok(module)
 }
+
+#[test]
+fn tarball_srcs() {
+    assert!(is_tarball_src("https://example.com/path/hash.tar.gz"));
+    assert!(is_tarball_src("file:///srv/packages/hash.tar.br"));
+    assert!(is_tarball_src("../packages/hash.tar.br#filename.roc"));
+    assert!(!is_tarball_src("../platform/main.roc"));
+}
+
+#[test]
+fn manifest_contents() {
+    let manifest = Manifest {
+        modules: vec![PathBuf::from("main.roc"), PathBuf::from("Effect.roc")],
+        hosts: vec![(
+            "linux-x86_64".to_string(),
+            vec![
+                PathBuf::from("linux-x86_64.rh"),
+                PathBuf::from("metadata_linux-x86_64.rm"),
+            ],
+        )],
+    };
+
+    assert_eq!(
+        manifest.to_file_contents(),
+        "[modules]\nmain.roc\nEffect.roc\n\n[linux-x86_64]\nlinux-x86_64.rh\nmetadata_linux-x86_64.rm\n"
+    );
+    assert_eq!(manifest_filename("hash.tar.br"), "hash.manifest");
+}
@@ -0,0 +1,398 @@
+//! Copying an app's HTTPS packages into the project itself, so that it can be built on machines
+//! without network access (see `roc vendor` and `--offline`).
+use crate::{
+    cache::{self, RocCacheDir},
+    https::{PackageMetadata, Problem},
+    tarball::is_tarball_src,
+};
+use bumpalo::Bump;
+use roc_parse::{
+    ast::{Collection, ExtractSpaces, Header},
+    module::parse_header,
+    state::State,
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The directory, next to the app's main .roc file, that packages get vendored into.
+pub const VENDOR_DIR_NAME: &str = "vendor";
+
+/// The file, next to the app's main .roc file, that lists every vendored package.
+pub const LOCKFILE_NAME: &str = "roc.lock";
+
+const LOCKFILE_HEADER: &str = "# This file is generated by `roc vendor`. Do not edit it by hand!\n";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedPackage {
+    pub url: String,
+    /// The base64url-encoded BLAKE3 hash of the package's tarball.
+    pub content_hash: String,
+}
+
+/// Every package a project depends on (including indirect dependencies), in the order they were
+/// first encountered.
+///
+/// On disk, this is one package per line: its URL, followed by a space and its content hash.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Lockfile {
+    pub packages: Vec<LockedPackage>,
+}
+
+impl Lockfile {
+    pub fn to_file_contents(&self) -> String {
+        let mut buf = String::from(LOCKFILE_HEADER);
+
+        for LockedPackage { url, content_hash } in self.packages.iter() {
+            buf.push_str(url);
+            buf.push(' ');
+            buf.push_str(content_hash);
+            buf.push('\n');
+        }
+
+        buf
+    }
+
+    /// Returns the 1-based number of the first line that isn't a package, a comment or blank.
+    pub fn from_file_contents(contents: &str) -> Result<Self, usize> {
+        let mut packages = Vec::new();
+
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.split_once(' ') {
+                Some((url, content_hash)) if !content_hash.trim().is_empty() => {
+                    packages.push(LockedPackage {
+                        url: url.to_string(),
+                        content_hash: content_hash.trim().to_string(),
+                    });
+                }
+                _ => return Err(index + 1),
+            }
+        }
+
+        Ok(Self { packages })
+    }
+
+    pub fn get(&self, url: &str) -> Option<&LockedPackage> {
+        self.packages.iter().find(|package| package.url == url)
+    }
+}
+
+#[derive(Debug)]
+pub enum VendorProblem {
+    /// We couldn't read the header of this .roc file to find its packages
+    ReadHeader(PathBuf, io::Error),
+    /// This .roc file's header has a syntax error
+    ParseHeader(PathBuf),
+    /// We couldn't write the lockfile to this path
+    WriteLockfile(PathBuf, io::Error),
+    /// We couldn't download, or copy, the package at this URL
+    Package(String, Problem),
+}
+
+/// Download every HTTPS package that the given .roc file depends on (directly or through other
+/// packages) into the cache, copy them into the [VENDOR_DIR_NAME] dir next to it, and write its
+/// [LOCKFILE_NAME].
+///
+/// Packages given by relative path are already part of the project, so they're not copied, but
+/// their own HTTPS dependencies are.
+pub fn vendor_packages(cache_dir: &Path, roc_file: &Path) -> Result<Lockfile, VendorProblem> {
+    let project_dir = roc_file.parent().unwrap_or_else(|| Path::new(""));
+    let vendor_dir = project_dir.join(VENDOR_DIR_NAME);
+    let mut lockfile = Lockfile::default();
+    let mut stack = vec![roc_file.to_path_buf()];
+    let mut visited = vec![];
+
+    while let Some(module_path) = stack.pop() {
+        if visited.contains(&module_path) {
+            continue;
+        }
+
+        let module_dir = module_path.parent().unwrap_or_else(|| Path::new(""));
+
+        for src in package_srcs(&module_path)? {
+            if !src.starts_with("https://") {
+                // Tarballs on this computer can be used offline as they are, so only
+                // modules given by path can lead to more packages to vendor.
+                if !is_tarball_src(&src) {
+                    stack.push(module_dir.join(src));
+                }
+
+                continue;
+            }
+
+            if lockfile.get(&src).is_some() {
+                continue;
+            }
+
+            let (package_dir, opt_root_module) =
+                cache::install_package(RocCacheDir::Persistent(cache_dir), &src)
+                    .map_err(|problem| VendorProblem::Package(src.clone(), problem))?;
+            let content_hash = vendor_package(&vendor_dir, &src, &package_dir)
+                .map_err(|problem| VendorProblem::Package(src.clone(), problem))?;
+
+            stack.push(package_dir.join(opt_root_module.unwrap_or("main.roc")));
+            lockfile.packages.push(LockedPackage {
+                url: src,
+                content_hash,
+            });
+        }
+
+        visited.push(module_path);
+    }
+
+    let lockfile_path = project_dir.join(LOCKFILE_NAME);
+
+    fs::write(&lockfile_path, lockfile.to_file_contents())
+        .map_err(|err| VendorProblem::WriteLockfile(lockfile_path, err))?;
+
+    Ok(lockfile)
+}
+
+/// Running offline, only the packages that `roc vendor` listed in the project's [LOCKFILE_NAME]
+/// may be used, and only with the content hash it recorded for them.
+///
+/// The lockfile is next to the vendor dir, just like `roc vendor` wrote them.
+pub fn check_locked(vendor_dir: &Path, url: &str, content_hash: &str) -> Result<(), Problem> {
+    let lockfile_path = vendor_dir
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(LOCKFILE_NAME);
+    let contents = fs::read_to_string(&lockfile_path)
+        .map_err(|err| Problem::ReadLockfile(lockfile_path.clone(), err))?;
+    let lockfile =
+        Lockfile::from_file_contents(&contents).map_err(|line| Problem::InvalidLockfile {
+            lockfile: lockfile_path.clone(),
+            line,
+        })?;
+
+    match lockfile.get(url) {
+        Some(locked) if locked.content_hash == content_hash => Ok(()),
+        Some(locked) => Err(Problem::LockfileHashMismatch {
+            lockfile: lockfile_path,
+            expected: locked.content_hash.clone(),
+            actual: content_hash.to_string(),
+        }),
+        None => Err(Problem::NotInLockfile {
+            lockfile: lockfile_path,
+        }),
+    }
+}
+
+/// Copy a package out of the cache into the vendor dir, at the same relative path it has in the
+/// cache, and return its content hash.
+fn vendor_package(vendor_dir: &Path, url: &str, package_dir: &Path) -> Result<String, Problem> {
+    let PackageMetadata {
+        cache_subdir,
+        content_hash,
+        ..
+    } = PackageMetadata::try_from(url).map_err(Problem::InvalidUrl)?;
+
+    // e.g. vendor/example.com/roc-packages/jDRlAFAA3738vu3-vMpLUoyxtA86Z7CaZneoOKrihbE
+    let dest_dir = vendor_dir.join(cache_subdir).join(content_hash);
+
+    // Like the cache, the directory name is the hash of its contents, so if it's already
+    // there, it's already what we want.
+    if !dest_dir.exists() {
+        fs::create_dir_all(&dest_dir).map_err(Problem::IoErr)?;
+
+        let copied = fs_extra::dir::copy(
+            package_dir,
+            &dest_dir,
+            &fs_extra::dir::CopyOptions {
+                content_only: true,
+                ..Default::default()
+            },
+        );
+
+        if let Err(err) = copied {
+            // Don't leave a partial copy behind, or the next run would think it's complete.
+            let _ = fs::remove_dir_all(&dest_dir);
+
+            return Err(Problem::FsExtraErr(err));
+        }
+    }
+
+    Ok(content_hash.to_string())
+}
+
+/// The URLs and relative paths of the packages in the header of the given .roc file.
+fn package_srcs(path: &Path) -> Result<Vec<String>, VendorProblem> {
+    let src = fs::read(path).map_err(|err| VendorProblem::ReadHeader(path.to_path_buf(), err))?;
+    let arena = Bump::new();
+    let (module, _) = parse_header(&arena, State::new(&src))
+        .map_err(|_| VendorProblem::ParseHeader(path.to_path_buf()))?;
+
+    let packages = match module.header {
+        Header::App(header) => match header.packages {
+            Some(packages) => packages.item,
+            None => Collection::empty(),
+        },
+        Header::Package(header) => header.packages.item,
+        Header::Platform(header) => header.packages.item,
+        Header::Interface(_) | Header::Hosted(_) => Collection::empty(),
+    };
+
+    Ok(packages
+        .iter()
+        .map(|entry| {
+            let entry = entry.extract_spaces().item;
+
+            entry.package_name.value.to_str().to_string()
+        })
+        .collect())
+}
+
+#[test]
+fn lockfile_round_trip() {
+    let lockfile = Lockfile {
+        packages: vec![
+            LockedPackage {
+                url: "https://example.com/path/hash.tar.br".to_string(),
+                content_hash: "hash".to_string(),
+            },
+            LockedPackage {
+                url: "https://example.com/other/hash2.tar.gz#pkg.roc".to_string(),
+                content_hash: "hash2".to_string(),
+            },
+        ],
+    };
+
+    assert_eq!(
+        Lockfile::from_file_contents(&lockfile.to_file_contents()),
+        Ok(lockfile)
+    );
+}
+
+#[test]
+fn lockfile_invalid_line() {
+    assert_eq!(
+        Lockfile::from_file_contents("# comment\n\nhttps://example.com/path/hash.tar.br\n"),
+        Err(3)
+    );
+}
+
+#[cfg(test)]
+const TEST_URL: &str = "https://example.com/path/hash.tar.br";
+
+/// A project dir with the given lockfile contents, and the cache and vendor dirs to use with it.
+#[cfg(test)]
+fn offline_project(lockfile: &str) -> (tempfile::TempDir, tempfile::TempDir, PathBuf) {
+    let project_dir = tempfile::tempdir().unwrap();
+    let cache_dir = tempfile::tempdir().unwrap();
+    let vendor_dir = project_dir.path().join(VENDOR_DIR_NAME);
+
+    fs::write(project_dir.path().join(LOCKFILE_NAME), lockfile).unwrap();
+
+    (project_dir, cache_dir, vendor_dir)
+}
+
+#[test]
+fn offline_prefers_vendor_dir() {
+    let (_project_dir, cache_dir, vendor_dir) = offline_project(&format!("{} hash\n", TEST_URL));
+
+    fs::create_dir_all(cache_dir.path().join("example.com/path/hash")).unwrap();
+    fs::create_dir_all(vendor_dir.join("example.com/path/hash")).unwrap();
+
+    let roc_cache_dir = RocCacheDir::Offline {
+        cache_dir: cache_dir.path(),
+        vendor_dir: &vendor_dir,
+    };
+
+    assert_eq!(
+        cache::install_package(roc_cache_dir, TEST_URL).unwrap(),
+        (vendor_dir.join("example.com/path/hash"), None)
+    );
+}
+
+#[test]
+fn offline_missing_package() {
+    let (_project_dir, cache_dir, vendor_dir) = offline_project(&format!("{} hash\n", TEST_URL));
+
+    let roc_cache_dir = RocCacheDir::Offline {
+        cache_dir: cache_dir.path(),
+        vendor_dir: &vendor_dir,
+    };
+
+    match cache::install_package(roc_cache_dir, TEST_URL) {
+        Err(Problem::NotAvailableOffline { searched }) => assert_eq!(
+            searched,
+            vec![
+                vendor_dir.join("example.com/path/hash"),
+                cache_dir.path().join("example.com/path/hash"),
+            ]
+        ),
+        other => panic!("expected NotAvailableOffline, got {:?}", other),
+    }
+}
+
+#[test]
+fn offline_without_lockfile() {
+    let project_dir = tempfile::tempdir().unwrap();
+    let cache_dir = tempfile::tempdir().unwrap();
+    let vendor_dir = project_dir.path().join(VENDOR_DIR_NAME);
+
+    fs::create_dir_all(cache_dir.path().join("example.com/path/hash")).unwrap();
+
+    let roc_cache_dir = RocCacheDir::Offline {
+        cache_dir: cache_dir.path(),
+        vendor_dir: &vendor_dir,
+    };
+
+    match cache::install_package(roc_cache_dir, TEST_URL) {
+        Err(Problem::ReadLockfile(path, err)) => {
+            assert_eq!(path, project_dir.path().join(LOCKFILE_NAME));
+            assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        }
+        other => panic!("expected ReadLockfile, got {:?}", other),
+    }
+}
+
+#[test]
+fn offline_package_not_in_lockfile() {
+    let (project_dir, cache_dir, vendor_dir) =
+        offline_project("https://example.com/other/hash2.tar.br hash2\n");
+
+    fs::create_dir_all(vendor_dir.join("example.com/path/hash")).unwrap();
+
+    let roc_cache_dir = RocCacheDir::Offline {
+        cache_dir: cache_dir.path(),
+        vendor_dir: &vendor_dir,
+    };
+
+    match cache::install_package(roc_cache_dir, TEST_URL) {
+        Err(Problem::NotInLockfile { lockfile }) => {
+            assert_eq!(lockfile, project_dir.path().join(LOCKFILE_NAME))
+        }
+        other => panic!("expected NotInLockfile, got {:?}", other),
+    }
+}
+
+#[test]
+fn offline_lockfile_hash_mismatch() {
+    let (_project_dir, cache_dir, vendor_dir) =
+        offline_project(&format!("{} otherhash\n", TEST_URL));
+
+    fs::create_dir_all(vendor_dir.join("example.com/path/hash")).unwrap();
+
+    let roc_cache_dir = RocCacheDir::Offline {
+        cache_dir: cache_dir.path(),
+        vendor_dir: &vendor_dir,
+    };
+
+    match cache::install_package(roc_cache_dir, TEST_URL) {
+        Err(Problem::LockfileHashMismatch {
+            expected, actual, ..
+        }) => {
+            assert_eq!(expected, "otherhash");
+            assert_eq!(actual, "hash");
+        }
+        other => panic!("expected LockfileHashMismatch, got {:?}", other),
+    }
+}
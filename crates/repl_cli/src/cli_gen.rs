@@ -1,3 +1,4 @@
+use crate::task::{is_task, run_task, AppPlatform};
 use bumpalo::Bump;
 use inkwell::context::Context;
 use libloading::Library;
@@ -11,7 +12,7 @@ use roc_mono::ir::OptLevel;
 use roc_mono::layout::STLayoutInterner;
 use roc_parse::ast::Expr;
 use roc_repl_eval::eval::jit_to_ast;
-use roc_repl_eval::gen::{compile_to_mono, format_answer, Problems, ReplOutput};
+use roc_repl_eval::gen::{compile_to_mono, format_answer, Problems, ReplContext, ReplOutput};
 use roc_repl_eval::{ReplApp, ReplAppMemory};
 use roc_reporting::report::DEFAULT_PALETTE;
 use roc_std::RocStr;
@@ -21,6 +22,8 @@ use roc_types::subs::Subs;
 use target_lexicon::Triple;
 
 pub fn gen_and_eval_llvm<'a, I: Iterator<Item = &'a str>>(
+    context: &ReplContext,
+    platform: Option<&AppPlatform>,
     defs: I,
     src: &str,
     target: Triple,
@@ -28,11 +31,19 @@ pub fn gen_and_eval_llvm<'a, I: Iterator<Item = &'a str>>(
 ) -> (Option<ReplOutput>, Problems) {
     let arena = Bump::new();
     let target_info = TargetInfo::from(&target);
+    let defs: Vec<&str> = defs.collect();
 
     let mut loaded;
-    let problems;
+    let mut problems;
 
-    match compile_to_mono(&arena, defs, src, target_info, DEFAULT_PALETTE) {
+    match compile_to_mono(
+        &arena,
+        context,
+        defs.iter().copied(),
+        src,
+        target_info,
+        DEFAULT_PALETTE,
+    ) {
         (Some(mono), probs) => {
             loaded = mono;
             problems = probs;
@@ -51,6 +62,9 @@ pub fn gen_and_eval_llvm<'a, I: Iterator<Item = &'a str>>(
         .unwrap();
     let main_fn_symbol = *main_fn_symbol;
     let main_fn_var = *main_fn_var;
+    let runs_task = platform
+        .filter(|_| problems.errors.is_empty())
+        .filter(|_| is_task(&loaded.subs, &loaded.interns, main_fn_var));
 
     // pretty-print the expr type string for later.
     let expr_type_str = name_and_print_var(
@@ -61,6 +75,26 @@ pub fn gen_and_eval_llvm<'a, I: Iterator<Item = &'a str>>(
         DebugPrint::NOTHING,
     );
 
+    if let Some(platform) = runs_task {
+        // A Task's effects need the platform's host, so it can't be evaluated like other values.
+        let expr = match run_task(context, platform, &defs, src) {
+            Ok(()) => "<task>".to_string(),
+            Err(message) => {
+                problems.errors.push(message);
+
+                String::new()
+            }
+        };
+
+        return (
+            Some(ReplOutput {
+                expr,
+                expr_type: expr_type_str,
+            }),
+            problems,
+        );
+    }
+
     let (_, main_fn_layout) = match loaded.procedures.keys().find(|(s, _)| *s == main_fn_symbol) {
         Some(layout) => *layout,
         None => {
@@ -100,6 +134,42 @@ pub fn gen_and_eval_llvm<'a, I: Iterator<Item = &'a str>>(
     )
 }
 
+/// Infer the type of the given expression (in the context of the given defs) without
+/// generating any code for it or running it.
+pub fn type_of_expr<'a, I: Iterator<Item = &'a str>>(
+    context: &ReplContext,
+    defs: I,
+    src: &str,
+    target: Triple,
+) -> (Option<String>, Problems) {
+    let arena = Bump::new();
+    let target_info = TargetInfo::from(&target);
+
+    let (mut loaded, problems) =
+        match compile_to_mono(&arena, context, defs, src, target_info, DEFAULT_PALETTE) {
+            (Some(mono), probs) if probs.errors.is_empty() => (mono, probs),
+            (_, probs) => {
+                return (None, probs);
+            }
+        };
+
+    debug_assert_eq!(loaded.exposed_to_host.top_level_values.len(), 1);
+    let main_fn_var = match loaded.exposed_to_host.top_level_values.values().next() {
+        Some(var) => *var,
+        None => return (None, problems),
+    };
+
+    let expr_type_str = name_and_print_var(
+        main_fn_var,
+        &mut loaded.subs,
+        loaded.module_id,
+        &loaded.interns,
+        DebugPrint::NOTHING,
+    );
+
+    (Some(expr_type_str), problems)
+}
+
 struct CliApp {
     lib: Library,
 }
@@ -222,6 +292,7 @@ fn mono_module_to_dylib<'a>(
         mode: LlvmBackendMode::GenTest, // so roc_panic is generated
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        debug_sources: None,
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
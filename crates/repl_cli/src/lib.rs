@@ -2,10 +2,12 @@
 mod cli_gen;
 mod colors;
 pub mod repl_state;
+mod task;
 
 use colors::{BLUE, END_COL, PINK};
 use const_format::concatcp;
 use repl_state::ReplState;
+use std::path::{Path, PathBuf};
 
 use crate::repl_state::PROMPT;
 
@@ -25,23 +27,65 @@ pub const WELCOME_MESSAGE: &str = concatcp!(
 // TODO add link to repl tutorial(does not yet exist).
 pub const SHORT_INSTRUCTIONS: &str = "Enter an expression, or :help, or :q to quit.\n\n";
 
-pub fn main() -> i32 {
+/// Run the REPL. If a main module is given, expressions can use its packages and imports.
+pub fn main(opt_main: Option<&Path>) -> i32 {
     use rustyline::error::ReadlineError;
-    use rustyline::Editor;
+    use rustyline::{Config, Editor};
 
     // To debug rustyline:
     // <UNCOMMENT> env_logger::init();
     // <RUN WITH:> RUST_LOG=rustyline=debug cargo run repl 2> debug.log
     print!("{}{}", WELCOME_MESSAGE, SHORT_INSTRUCTIONS);
 
-    let mut editor = Editor::<ReplState>::new();
-    let repl_helper = ReplState::new();
+    let config = Config::builder()
+        .max_history_size(MAX_HISTORY_ENTRIES)
+        .build();
+    let mut editor = Editor::<ReplState>::with_config(config);
+    let mut repl_helper = ReplState::new();
+
+    if let Some(main_path) = opt_main {
+        match repl_helper.use_main_module(main_path) {
+            Ok(output) => println!("{}", output),
+            Err(message) => {
+                eprintln!("{}", message);
+                return 1;
+            }
+        }
+    }
+
     editor.set_helper(Some(repl_helper));
 
+    let history_path = history_path();
+
+    if let Some(path) = history_path.as_ref() {
+        if let Ok(contents) = std::fs::read_to_string(path) {
+            let entries = history_entries(&contents);
+
+            for entry in entries.iter() {
+                editor.add_history_entry(entry.as_str());
+            }
+
+            // Entries only ever get appended to the file, so this is where it gets trimmed back
+            // down to the ones the editor kept.
+            if entries.len() > MAX_HISTORY_ENTRIES {
+                let kept = editor.history().iter().map(|entry| entry.as_str());
+                let _ = std::fs::write(path, history_file_contents(kept));
+            }
+        }
+    }
+
     loop {
         match editor.readline(PROMPT) {
             Ok(line) => {
-                editor.add_history_entry(line.trim());
+                let entry = line.trim();
+
+                // Save after every entry, so history survives however the session ends.
+                // Failing to save it isn't worth interrupting the session over.
+                if editor.add_history_entry(entry) {
+                    if let Some(path) = history_path.as_ref() {
+                        let _ = append_history_entry(path, entry);
+                    }
+                }
 
                 let dimensions = editor.dimensions();
                 let repl_helper = editor.helper_mut().expect("Editor helper was not set");
@@ -76,3 +120,106 @@ pub fn main() -> i32 {
         }
     }
 }
+
+/// How many entries the REPL's history keeps, both in the editor and in the history file.
+const MAX_HISTORY_ENTRIES: usize = 1000;
+
+/// Where the REPL's history is saved between sessions, e.g. ~/.cache/roc/repl_history
+fn history_path() -> Option<PathBuf> {
+    // The packages cache is inside the roc cache dir, e.g. ~/.cache/roc/packages
+    let packages_dir = roc_packaging::cache::roc_cache_dir();
+
+    packages_dir
+        .parent()
+        .map(|roc_cache_dir| roc_cache_dir.join("repl_history"))
+}
+
+fn append_history_entry(path: &Path, entry: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    std::fs::create_dir_all(path.parent().unwrap_or(path))?;
+
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(history_file_contents(std::iter::once(entry)).as_bytes())
+}
+
+/// One history entry per line. Entries can span multiple lines (e.g. a `when` or a def with an
+/// annotation), so newlines and backslashes within them are escaped.
+pub fn history_file_contents<'a>(entries: impl Iterator<Item = &'a str>) -> String {
+    let mut buf = String::new();
+
+    for entry in entries {
+        for c in entry.chars() {
+            match c {
+                '\\' => buf.push_str("\\\\"),
+                '\n' => buf.push_str("\\n"),
+                c => buf.push(c),
+            }
+        }
+
+        buf.push('\n');
+    }
+
+    buf
+}
+
+/// The entries in a history file written by [history_file_contents].
+pub fn history_entries(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let mut entry = String::with_capacity(line.len());
+            let mut chars = line.chars();
+
+            while let Some(c) = chars.next() {
+                match (c, chars.clone().next()) {
+                    ('\\', Some('n')) => {
+                        chars.next();
+                        entry.push('\n');
+                    }
+                    ('\\', Some('\\')) => {
+                        chars.next();
+                        entry.push('\\');
+                    }
+                    (c, _) => entry.push(c),
+                }
+            }
+
+            entry
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{history_entries, history_file_contents};
+
+    #[test]
+    fn history_round_trip() {
+        let entries = [
+            "1 + 1",
+            // An escaped newline in a string literal, which must stay a backslash and an `n`
+            r#"Str.concat "a\nb" "c""#,
+            "x : U8\nx = 1",
+            r"\\n",
+            "when x is\n    1 -> \"\\\\\"\n    _ -> \"\"",
+        ];
+
+        let contents = history_file_contents(entries.iter().copied());
+
+        assert_eq!(contents.lines().count(), entries.len());
+        assert_eq!(history_entries(&contents), entries);
+    }
+
+    #[test]
+    fn history_appended_entries() {
+        let mut contents = history_file_contents(["1 + 1"].into_iter());
+        contents.push_str(&history_file_contents(["\"a\\nb\"\nx"].into_iter()));
+
+        assert_eq!(history_entries(&contents), ["1 + 1", "\"a\\nb\"\nx"]);
+    }
+}
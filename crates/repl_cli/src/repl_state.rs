@@ -1,22 +1,30 @@
-use crate::cli_gen::gen_and_eval_llvm;
+use crate::cli_gen::{gen_and_eval_llvm, type_of_expr};
 use crate::colors::{BLUE, END_COL, GREEN, PINK};
+use crate::task::AppPlatform;
 use bumpalo::Bump;
 use const_format::concatcp;
 use roc_collections::MutSet;
+use roc_fmt::annotation::Formattable;
+use roc_fmt::Buf;
 use roc_mono::ir::OptLevel;
-use roc_parse::ast::{Expr, Pattern, TypeDef, TypeHeader, ValueDef};
+use roc_parse::ast::{
+    Collection, Expr, ExtractSpaces, Header, Pattern, Spaced, TypeDef, TypeHeader, ValueDef,
+};
 use roc_parse::expr::{parse_single_def, ExprParseOptions, SingleDef};
+use roc_parse::header::To;
+use roc_parse::module::{module_defs, parse_header};
 use roc_parse::parser::Parser;
 use roc_parse::parser::{EClosure, EExpr, EPattern};
 use roc_parse::parser::{EWhen, Either};
 use roc_parse::state::State;
 use roc_parse::{join_alias_to_body, join_ann_to_body};
 use roc_region::all::Loc;
-use roc_repl_eval::gen::{Problems, ReplOutput};
+use roc_repl_eval::gen::{Problems, ReplContext, ReplOutput};
 use rustyline::highlight::{Highlighter, PromptInfo};
 use rustyline::validate::{self, ValidationContext, ValidationResult, Validator};
 use rustyline_derive::{Completer, Helper, Hinter};
 use std::borrow::Cow;
+use std::path::Path;
 use target_lexicon::Triple;
 
 pub const PROMPT: &str = concatcp!(BLUE, "»", END_COL, " ");
@@ -55,6 +63,18 @@ pub const TIPS: &str = concatcp!(
     BLUE,
     "  - ",
     END_COL,
+    ":type <expr> shows the type of an expression without evaluating it\n\n",
+    BLUE,
+    "  - ",
+    END_COL,
+    ":load <file.roc> brings the defs in a module into scope (loading it again replaces them)\n\n",
+    BLUE,
+    "  - ",
+    END_COL,
+    ":show lists the defs in scope, and :reset clears them\n\n",
+    BLUE,
+    "  - ",
+    END_COL,
     ":q to quit\n\n",
     BLUE,
     "  - ",
@@ -74,6 +94,9 @@ pub struct ReplState {
     past_defs: Vec<PastDef>,
     past_def_idents: MutSet<String>,
     last_auto_ident: u64,
+    context: ReplContext,
+    /// The platform of the main module, if it's an app, which `Task`s get run with
+    platform: Option<AppPlatform>,
 }
 
 impl Default for ReplState {
@@ -89,6 +112,8 @@ impl ReplState {
             past_defs: Default::default(),
             past_def_idents: Default::default(),
             last_auto_ident: 0,
+            context: ReplContext::default(),
+            platform: None,
         }
     }
 
@@ -106,10 +131,220 @@ impl ReplState {
                 // TODO add link to repl tutorial(does not yet exist).
                 Ok(TIPS.to_string())
             }
+            ParseOutcome::Type(src) => Ok(self.type_and_format(src)),
+            ParseOutcome::Load(path) => Ok(self.load_and_format(path)),
+            ParseOutcome::Reset => {
+                self.reset();
+
+                Ok(concatcp!("\n", GREEN, "Cleared all defs.", END_COL, "\n").to_string())
+            }
+            ParseOutcome::Show => Ok(self.show()),
             ParseOutcome::Exit => Err(0),
         }
     }
 
+    /// Compile everything in the context of the module at the given path (e.g. an app's
+    /// main.roc), so that its packages and imports can be used. An app's own defs are brought
+    /// into scope too, and an interface module imports itself.
+    ///
+    /// For an app, an expression whose type is the platform's `Task` gets run: it's built into
+    /// an executable with the platform's host, as what the app provides to the platform.
+    pub fn use_main_module(&mut self, path: &Path) -> Result<String, String> {
+        let display_path = path.display();
+        let src = std::fs::read_to_string(path)
+            .map_err(|err| format!("I couldn't read {display_path}: {err}"))?;
+        let arena = Bump::new();
+        let (module, _) = parse_header(&arena, State::new(src.as_bytes())).map_err(|_| {
+            format!("I couldn't parse {display_path}. Run `roc check {display_path}` for details.")
+        })?;
+
+        // Packages with relative paths, and imported modules, are relative to the module's dir
+        let mut context = ReplContext {
+            src_dir: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            ..ReplContext::default()
+        };
+        let is_app = matches!(module.header, Header::App(_));
+        let mut platform = None;
+
+        match module.header {
+            Header::App(header) => {
+                let provides = &header.provides;
+
+                // Only a single value can be provided in place of the app's own
+                if let ([entry], None) = (provides.entries.items, provides.types) {
+                    let to = match provides.to.value {
+                        To::ExistingPackage(shorthand) => shorthand.to_string(),
+                        To::NewPackage(package_name) => format!("\"{}\"", package_name.as_str()),
+                    };
+
+                    platform = Some(AppPlatform {
+                        provides: entry.value.extract_spaces().item.as_str().to_string(),
+                        to,
+                    });
+                }
+
+                if let Some(packages) = header.packages {
+                    context.packages = header_entries(packages.item);
+                }
+
+                if let Some(imports) = header.imports {
+                    context.imports = header_entries(imports.item);
+                }
+            }
+            Header::Interface(header) => {
+                let module_name = header.name.value.as_str();
+
+                // e.g. the `Json.Decode` module is in Json/Decode.roc
+                for _ in module_name.split('.').skip(1) {
+                    context.src_dir.pop();
+                }
+
+                context.imports = header_entries(header.imports.item);
+                context.imports.push(module_name.to_string());
+            }
+            Header::Package(header) => {
+                context.packages = header_entries(header.packages.item);
+                context.imports = header_entries(header.exposes.item);
+            }
+            Header::Platform(_) | Header::Hosted(_) => {
+                return Err(format!(
+                    "{display_path} is part of a platform. Use an app, interface or package module instead."
+                ));
+            }
+        }
+
+        // Check the imports up front, rather than reporting their problems after every expression
+        let (_, problems) = type_of_expr(&context, std::iter::empty(), "{}", Triple::host());
+
+        if !problems.errors.is_empty() {
+            return Err(format_output(None, problems, None, None));
+        }
+
+        self.context = context;
+        self.platform = platform;
+
+        if is_app {
+            Ok(self.load_and_format(&path.to_string_lossy()))
+        } else {
+            Ok(format!(
+                "\n{GREEN}Imported{END_COL} {}\n",
+                self.context.imports.join(", ")
+            ))
+        }
+    }
+
+    /// Show the type of the given expression, without evaluating it or naming it.
+    pub fn type_and_format(&self, src: &str) -> String {
+        if src.trim().is_empty() {
+            return "\nEnter an expression after :type to see its type, e.g. :type List.map\n"
+                .to_string();
+        }
+
+        let (opt_type, problems) = type_of_expr(
+            &self.context,
+            self.past_defs.iter().map(|def| def.src.as_str()),
+            src,
+            Triple::host(),
+        );
+        let opt_output = opt_type.map(|expr_type| ReplOutput {
+            expr: src.trim().to_string(),
+            expr_type,
+        });
+
+        format_output(opt_output, problems, None, None)
+    }
+
+    /// Bring the top-level defs of the module at the given path into scope, replacing any
+    /// past defs with the same names (so a module can be loaded again after editing it).
+    pub fn load_and_format(&mut self, path: &str) -> String {
+        if path.is_empty() {
+            return "\nEnter the path of a .roc file after :load, e.g. :load Parser.roc\n"
+                .to_string();
+        }
+
+        let src = match std::fs::read_to_string(path) {
+            Ok(src) => src,
+            Err(err) => return format!("\nI couldn't read {path}: {err}\n"),
+        };
+        let arena = Bump::new();
+        let loaded_defs = match module_def_srcs(&arena, &src) {
+            Some(defs) => defs,
+            None => {
+                return format!("\nI couldn't parse {path}. Run `roc check {path}` for details.\n")
+            }
+        };
+
+        let loaded_idents: MutSet<String> =
+            loaded_defs.iter().map(|def| def.ident.clone()).collect();
+        let remaining_defs: Vec<PastDef> = self
+            .past_defs
+            .iter()
+            .filter(|def| !loaded_idents.contains(&def.ident))
+            .cloned()
+            .collect();
+
+        // Check the defs before keeping them, so that a module with problems doesn't break
+        // every expression entered after it.
+        let (_, problems) = type_of_expr(
+            &self.context,
+            remaining_defs
+                .iter()
+                .chain(loaded_defs.iter())
+                .map(|def| def.src.as_str()),
+            "{}",
+            Triple::host(),
+        );
+
+        if !problems.errors.is_empty() {
+            return format_output(None, problems, None, None);
+        }
+
+        let mut names = String::new();
+
+        self.past_defs.clear();
+        self.past_def_idents.clear();
+
+        for def in remaining_defs.into_iter().chain(loaded_defs) {
+            // A standalone annotation and its body are separate defs with the same name,
+            // so only list each name once.
+            if !self.past_def_idents.contains(&def.ident) && loaded_idents.contains(&def.ident) {
+                if !names.is_empty() {
+                    names.push_str(", ");
+                }
+
+                names.push_str(&def.ident);
+            }
+
+            self.add_past_def(def.ident, def.src);
+        }
+
+        format!("\n{GREEN}Loaded {path}{END_COL}: {names}\n")
+    }
+
+    /// List the defs which are currently in scope, in the order they were entered.
+    pub fn show(&self) -> String {
+        if self.past_defs.is_empty() {
+            return "\nThere are no defs yet.\n".to_string();
+        }
+
+        let mut buf = String::new();
+
+        for def in self.past_defs.iter() {
+            buf.push('\n');
+            buf.push_str(def.src.trim_end());
+            buf.push('\n');
+        }
+
+        buf
+    }
+
+    /// Forget all past defs, including the automatically named ones.
+    pub fn reset(&mut self) {
+        self.past_defs.clear();
+        self.past_def_idents.clear();
+        self.last_auto_ident = 0;
+    }
+
     pub fn eval_and_format(&mut self, src: &str, dimensions: Option<(usize, usize)>) -> String {
         let arena = Bump::new();
         let pending_past_def;
@@ -220,7 +455,13 @@ impl ReplState {
                 // can be evaluated as expressions.
                 return String::new();
             }
-            ParseOutcome::Empty | ParseOutcome::Help | ParseOutcome::Exit => unreachable!(),
+            ParseOutcome::Empty
+            | ParseOutcome::Help
+            | ParseOutcome::Type(_)
+            | ParseOutcome::Load(_)
+            | ParseOutcome::Reset
+            | ParseOutcome::Show
+            | ParseOutcome::Exit => unreachable!(),
         };
 
         // Record e.g. "val1" as a past def, unless our input was exactly the name of
@@ -231,7 +472,15 @@ impl ReplState {
                 Some(existing_ident) => {
                     opt_var_name = Some(existing_ident);
 
+                    // Run a Task when its name is entered on its own, but not when it's defined
+                    let platform = match pending_past_def {
+                        Some(_) => None,
+                        None => self.platform.as_ref(),
+                    };
+
                     gen_and_eval_llvm(
+                        &self.context,
+                        platform,
                         self.past_defs.iter().map(|def| def.src.as_str()),
                         src,
                         Triple::host(),
@@ -240,6 +489,8 @@ impl ReplState {
                 }
                 None => {
                     let (output, problems) = gen_and_eval_llvm(
+                        &self.context,
+                        self.platform.as_ref(),
                         self.past_defs.iter().map(|def| def.src.as_str()),
                         src,
                         Triple::host(),
@@ -292,14 +543,112 @@ enum ParseOutcome<'a> {
     SyntaxErr,
     Empty,
     Help,
+    Type(&'a str),
+    Load(&'a str),
+    Reset,
+    Show,
     Exit,
 }
 
+/// The entries of a collection in a module header, e.g. the `pf.Stdout` in `imports [pf.Stdout]`,
+/// formatted on their own and without comments.
+fn header_entries<'a, T: Formattable + Copy>(
+    collection: Collection<'a, Loc<Spaced<'a, T>>>,
+) -> Vec<String> {
+    collection
+        .items
+        .iter()
+        .map(|entry| {
+            let arena = Bump::new();
+            let mut buf = Buf::new_in(&arena);
+
+            entry.value.extract_spaces().item.format(&mut buf, 0);
+
+            buf.into_bump_str().trim().to_string()
+        })
+        .collect()
+}
+
+/// The rest of the line after the given REPL command (e.g. the `1 + 1` in `:type 1 + 1`),
+/// if the line starts with that command.
+fn command_arg<'a>(line: &'a str, command: &str) -> Option<&'a str> {
+    let rest = line.trim_start().strip_prefix(command)?;
+
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        Some(rest.trim_start())
+    } else {
+        None
+    }
+}
+
+/// The source of each top-level def in the given module which has a name, along with that name.
+/// Returns None if the module doesn't parse.
+fn module_def_srcs<'a>(arena: &'a Bump, src: &'a str) -> Option<Vec<PastDef>> {
+    let (_, state) = parse_header(arena, State::new(src.as_bytes())).ok()?;
+    let (_, defs, _) = module_defs().parse(arena, state, 0).ok()?;
+    let mut def_srcs = Vec::with_capacity(defs.len());
+
+    for (def, region) in defs.defs().zip(defs.regions.iter()) {
+        let opt_ident = match def {
+            Ok(TypeDef::Alias { header, .. })
+            | Ok(TypeDef::Opaque { header, .. })
+            | Ok(TypeDef::Ability { header, .. }) => Some(header.name.value),
+            Err(ValueDef::Annotation(
+                Loc {
+                    value: Pattern::Identifier(ident),
+                    ..
+                },
+                _,
+            ))
+            | Err(ValueDef::Body(
+                Loc {
+                    value: Pattern::Identifier(ident),
+                    ..
+                },
+                _,
+            ))
+            | Err(ValueDef::AnnotatedBody {
+                body_pattern:
+                    Loc {
+                        value: Pattern::Identifier(ident),
+                        ..
+                    },
+                ..
+            }) => Some(*ident),
+            // Top-level expects (and defs which destructure) can't be referred to by name,
+            // so there's no point in bringing them into scope.
+            Err(_) => None,
+        };
+
+        if let Some(ident) = opt_ident {
+            let start = region.start().offset as usize;
+            let end = region.end().offset as usize;
+
+            def_srcs.push(PastDef {
+                ident: ident.trim_end().to_string(),
+                src: src[start..end].to_string(),
+            });
+        }
+    }
+
+    Some(def_srcs)
+}
+
 fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
+    if let Some(src) = command_arg(line, ":type") {
+        return ParseOutcome::Type(src);
+    }
+
+    if let Some(path) = command_arg(line, ":load") {
+        return ParseOutcome::Load(path.trim().trim_matches('"'));
+    }
+
     match line.trim().to_lowercase().as_str() {
         "" => ParseOutcome::Empty,
         ":help" => ParseOutcome::Help,
         ":exit" | ":quit" | ":q" => ParseOutcome::Exit,
+        ":reset" => ParseOutcome::Reset,
+        ":show" => ParseOutcome::Show,
         _ => {
             let src_bytes = line.as_bytes();
 
@@ -491,8 +840,13 @@ pub fn is_incomplete(input: &str) -> bool {
             // them until they enter a blank line!
             !input.ends_with('\n')
         }
+        // The expression whose type to show may span multiple lines, just like any other
+        ParseOutcome::Type(src) => is_incomplete(src),
         ParseOutcome::Empty
         | ParseOutcome::Help
+        | ParseOutcome::Load(_)
+        | ParseOutcome::Reset
+        | ParseOutcome::Show
         | ParseOutcome::Exit
         | ParseOutcome::ValueDef(_)
         | ParseOutcome::TypeDef(_)
@@ -0,0 +1,223 @@
+//! Running `Task`s entered in the REPL, by building them into an executable with the platform
+//! of the app given to `roc repl --main`.
+use bumpalo::Bump;
+use roc_build::link::{LinkType, LinkingStrategy};
+use roc_build::program::{
+    build_loaded_file, report_problems_typechecked, standard_load_config, BuildFileError,
+    BuildOrdering, BuiltFile, CodeGenBackend, CodeGenOptions,
+};
+use roc_gen_llvm::llvm::build::LlvmBackendMode;
+use roc_load::{LoadMonomorphizedError, LoadingProblem, MonomorphizedModule, Threading};
+use roc_module::symbol::Interns;
+use roc_mono::ir::OptLevel;
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_problem::Severity;
+use roc_repl_eval::gen::ReplContext;
+use roc_types::subs::{Content, Subs, Variable};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use target_lexicon::Triple;
+
+/// The platform of an app, which `Task`s entered in the REPL get run with.
+#[derive(Debug, Clone)]
+pub struct AppPlatform {
+    /// The value the platform requires the app to provide, e.g. `main`
+    pub provides: String,
+    /// What the app provides it to, e.g. `pf` or `"platform/main.roc"`
+    pub to: String,
+}
+
+/// Whether a value of this type should be run rather than printed: its type is an alias or
+/// opaque type named `Task`, which is what platforms call their effects.
+pub fn is_task(subs: &Subs, interns: &Interns, var: Variable) -> bool {
+    match subs.get_content_without_compacting(var) {
+        Content::Alias(symbol, _, _, _) => symbol.as_str(interns) == "Task",
+        _ => false,
+    }
+}
+
+/// Build the given expression (in the context of the given defs) into an executable with the
+/// app's platform, as the value the platform requires, and run it.
+///
+/// The expression and defs go in an interface module next to the app, so that they can use the
+/// app's imports, and their names can't clash with what the app provides.
+pub fn run_task(
+    context: &ReplContext,
+    platform: &AppPlatform,
+    defs: &[&str],
+    expr: &str,
+) -> Result<(), String> {
+    let id = std::process::id();
+    let module_name = format!("ReplTask{id}");
+    let module_path = context.src_dir.join(format!("{module_name}.roc"));
+    let app_path = context.src_dir.join(format!(".roc-repl-task-{id}.roc"));
+    let binary_path = std::env::temp_dir().join(format!("roc-repl-task-{id}"));
+
+    let result = std::fs::write(
+        &module_path,
+        task_module_src(&module_name, context, defs, expr),
+    )
+    .and_then(|()| {
+        std::fs::write(
+            &app_path,
+            app_module_src(&module_name, context, platform, &binary_path),
+        )
+    })
+    .map_err(|err| format!("I couldn't write the task's module: {err}"))
+    .and_then(|()| build_and_run(app_path.clone()));
+
+    for path in [&module_path, &app_path] {
+        let _ = std::fs::remove_file(path);
+    }
+
+    result
+}
+
+fn task_module_src(module_name: &str, context: &ReplContext, defs: &[&str], expr: &str) -> String {
+    let mut buf = format!(
+        "interface {module_name}\n    exposes [task]\n    imports [{}]\n\n",
+        context.imports.join(", ")
+    );
+
+    for def in defs {
+        buf.push_str(def);
+        buf.push_str("\n\n");
+    }
+
+    buf.push_str("task =\n");
+
+    for line in expr.lines() {
+        buf.push_str("    ");
+        buf.push_str(line);
+        buf.push('\n');
+    }
+
+    buf
+}
+
+fn app_module_src(
+    module_name: &str,
+    context: &ReplContext,
+    platform: &AppPlatform,
+    binary_path: &Path,
+) -> String {
+    // The app's name is where the executable gets written.
+    let output = binary_path
+        .display()
+        .to_string()
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"");
+    let mut buf = format!("app \"{output}\"\n");
+
+    if !context.packages.is_empty() {
+        buf.push_str(&format!(
+            "    packages {{ {} }}\n",
+            context.packages.join(", ")
+        ));
+    }
+
+    buf.push_str(&format!(
+        "    imports [{module_name}]\n    provides [{provides}] to {to}\n\n{provides} = {module_name}.task\n",
+        provides = platform.provides,
+        to = platform.to,
+    ));
+
+    buf
+}
+
+fn build_and_run(app_path: PathBuf) -> Result<(), String> {
+    let arena = Bump::new();
+    let triple = Triple::host();
+    let compilation_start = Instant::now();
+    let load_config = standard_load_config(
+        &triple,
+        BuildOrdering::BuildIfChecks,
+        Threading::AllAvailable,
+    );
+    let render = load_config.render;
+
+    let mut loaded = match roc_load::load_and_monomorphize(
+        &arena,
+        app_path.clone(),
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        load_config,
+    ) {
+        Ok(loaded) => loaded,
+        Err(LoadMonomorphizedError::ErrorModule(mut module)) => {
+            // e.g. the task's type isn't the one the platform requires
+            report_problems_typechecked(&mut module, render);
+
+            return Err("The task can't be run with the app's platform.".to_string());
+        }
+        Err(LoadMonomorphizedError::LoadingProblem(LoadingProblem::FormattedReport(report))) => {
+            return Err(report);
+        }
+        Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
+            return Err(format!("I couldn't load the task: {problem:?}"));
+        }
+    };
+
+    // The REPL already reported any warnings about the expression and defs, and the module
+    // with them imports everything the app does, which would otherwise all be reported unused.
+    remove_warnings(&mut loaded);
+
+    let linking_strategy = if roc_linker::supported(LinkType::Executable, &triple) {
+        LinkingStrategy::Surgical
+    } else {
+        LinkingStrategy::Legacy
+    };
+    let code_gen_options = CodeGenOptions {
+        backend: CodeGenBackend::Llvm(LlvmBackendMode::BinaryDev),
+        opt_level: OptLevel::Development,
+        emit_debug_info: false,
+    };
+
+    let built = build_loaded_file(
+        &arena,
+        &triple,
+        app_path,
+        code_gen_options,
+        false,
+        LinkType::Executable,
+        linking_strategy,
+        false,
+        None,
+        loaded,
+        compilation_start,
+        render,
+    );
+
+    let binary_path = match built {
+        Ok(BuiltFile { binary_path, .. }) => binary_path,
+        Err(BuildFileError::ErrorModule { mut module, .. }) => {
+            report_problems_typechecked(&mut module, render);
+
+            return Err("The task can't be run with the app's platform.".to_string());
+        }
+        Err(BuildFileError::LoadingProblem(problem)) => {
+            return Err(format!("I couldn't build the task: {problem:?}"));
+        }
+    };
+
+    let status = std::process::Command::new(&binary_path).status();
+    let _ = std::fs::remove_file(&binary_path);
+
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => match status.code() {
+            Some(code) => Err(format!("The task exited with code {code}.")),
+            None => Err("The task was stopped by a signal.".to_string()),
+        },
+        Err(err) => Err(format!("I couldn't run the task: {err}")),
+    }
+}
+
+fn remove_warnings(loaded: &mut MonomorphizedModule) {
+    for problems in loaded.can_problems.values_mut() {
+        problems.retain(|problem| problem.severity() != Severity::Warning);
+    }
+
+    for problems in loaded.type_problems.values_mut() {
+        problems.retain(|problem| problem.severity() != Severity::Warning);
+    }
+}
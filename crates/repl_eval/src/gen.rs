@@ -32,6 +32,28 @@ pub fn format_answer<'a>(arena: &'a Bump, answer: Expr<'_>) -> &'a str {
     }
 }
 
+/// What the REPL compiles expressions in the context of. By default that's only the builtins,
+/// but `roc repl --main` adds the packages and imports of a module, so they can be used too.
+#[derive(Debug, Clone)]
+pub struct ReplContext {
+    /// Entries for the `packages` of the REPL's module, e.g. `pf: "platform/main.roc"`
+    pub packages: Vec<String>,
+    /// Entries for the `imports` of the REPL's module, e.g. `pf.Stdout` or `Parser.{ parse }`
+    pub imports: Vec<String>,
+    /// Where imported modules (and packages with relative paths) are looked up
+    pub src_dir: PathBuf,
+}
+
+impl Default for ReplContext {
+    fn default() -> Self {
+        Self {
+            packages: Vec::new(),
+            imports: Vec::new(),
+            src_dir: PathBuf::from("fake/test/path"),
+        }
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct Problems {
     pub errors: Vec<String>,
@@ -46,19 +68,19 @@ impl Problems {
 
 pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
     arena: &'a Bump,
+    context: &ReplContext,
     defs: I,
     expr: &str,
     target_info: TargetInfo,
     palette: Palette,
 ) -> (Option<MonomorphizedModule<'a>>, Problems) {
     let filename = PathBuf::from("");
-    let src_dir = PathBuf::from("fake/test/path");
-    let (bytes_before_expr, module_src) = promote_expr_to_module(arena, defs, expr);
+    let (bytes_before_expr, module_src) = promote_expr_to_module(arena, context, defs, expr);
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
         filename,
         module_src,
-        src_dir,
+        context.src_dir.clone(),
         RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
         LoadConfig {
             target_info,
@@ -167,6 +189,7 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
 
 fn promote_expr_to_module<'a, 'i, I: Iterator<Item = &'i str>>(
     arena: &'a Bump,
+    context: &ReplContext,
     defs: I,
     expr: &str,
 ) -> (usize, &'a str) {
@@ -174,7 +197,30 @@ fn promote_expr_to_module<'a, 'i, I: Iterator<Item = &'i str>>(
     const REPL_MODULE_MAIN_DEF: &str = "replOutput =\n";
     const INDENT: &str = "    ";
 
-    let mut buffer = bumpalo::collections::string::String::from_str_in(REPL_MODULE_HEADER, arena);
+    let mut buffer = bumpalo::collections::string::String::new_in(arena);
+
+    if context.packages.is_empty() && context.imports.is_empty() {
+        buffer.push_str(REPL_MODULE_HEADER);
+    } else {
+        buffer.push_str("app \"app\"\n");
+
+        if !context.packages.is_empty() {
+            buffer.push_str(INDENT);
+            buffer.push_str("packages { ");
+            buffer.push_str(&context.packages.join(", "));
+            buffer.push_str(" }\n");
+        }
+
+        if !context.imports.is_empty() {
+            buffer.push_str(INDENT);
+            buffer.push_str("imports [");
+            buffer.push_str(&context.imports.join(", "));
+            buffer.push_str("]\n");
+        }
+
+        buffer.push_str(INDENT);
+        buffer.push_str("provides [replOutput] to \"./platform\"\n\n");
+    }
 
     for line in defs {
         // don't indent the defs
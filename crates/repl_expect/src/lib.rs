@@ -170,7 +170,7 @@ mod test {
         unsafe { set_shared_buffer((shared_buffer.as_mut_ptr(), BUFFER_SIZE), &mut result) };
 
         let mut writer = Vec::with_capacity(1024);
-        let (_failed, _passed) = crate::run::run_expects_with_memory(
+        let _results = crate::run::run_expects_with_memory(
             &mut writer,
             RenderTarget::ColorTerminal,
             arena,
@@ -885,6 +885,7 @@ mod test {
                     First Str U8,
                     Next (List { item: Str, rest: NonEmpty }),
                 ]
+                    has [Inspect]
 
                 expect
                     nonEmpty =
@@ -901,15 +902,15 @@ mod test {
                 r#"
                 This expectation failed:
 
-                 8│>  expect
-                 9│>      nonEmpty =
-                10│>          a = "abcdefgh"
-                11│>          b = @NonEmpty (First "ijkl" 67u8)
-                12│>          c = Next [{ item: a, rest: b }]
-                13│>          @NonEmpty c
-                14│>
-                15│>      when nonEmpty is
-                16│>          _ -> Bool.false
+                 9│>  expect
+                10│>      nonEmpty =
+                11│>          a = "abcdefgh"
+                12│>          b = @NonEmpty (First "ijkl" 67u8)
+                13│>          c = Next [{ item: a, rest: b }]
+                14│>          @NonEmpty c
+                15│>
+                16│>      when nonEmpty is
+                17│>          _ -> Bool.false
 
                 When it failed, these variables had these values:
 
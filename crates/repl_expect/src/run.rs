@@ -4,6 +4,7 @@ use std::{
         atomic::{AtomicBool, AtomicU32},
         Arc,
     },
+    time::{Duration, Instant},
 };
 
 use bumpalo::collections::Vec as BumpVec;
@@ -27,7 +28,7 @@ use roc_mono::{
 use roc_region::all::Region;
 use roc_reporting::{error::expect::Renderer, report::RenderTarget};
 use roc_target::TargetInfo;
-use roc_types::subs::Subs;
+use roc_types::subs::{Subs, Variable};
 use target_lexicon::Triple;
 
 pub struct ExpectMemory<'a> {
@@ -125,6 +126,62 @@ impl<'a> ExpectMemory<'a> {
     }
 }
 
+/// The counters behind `roc_coverage_hit`, one per covered region. They live in shared memory,
+/// so that the counts made by the child processes running effectful `expect`s are kept.
+pub struct CoverageCounts {
+    ptr: *mut u64,
+    length: usize,
+}
+
+impl CoverageCounts {
+    pub fn new(length: usize) -> Self {
+        // mmap does not like empty mappings
+        let size = length.max(1) * std::mem::size_of::<u64>();
+
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                size,
+                libc::PROT_WRITE | libc::PROT_READ,
+                libc::MAP_SHARED | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+
+        if ptr as usize == usize::MAX {
+            // ptr = -1
+            internal_error!("failed to mmap the coverage counters")
+        }
+
+        // anonymous mappings start out zeroed
+        Self {
+            ptr: ptr.cast(),
+            length,
+        }
+    }
+
+    /// Makes the program in `lib` count into these counters.
+    pub fn set_coverage_buffer(&self, lib: &libloading::Library) {
+        let set_coverage_buffer = run_roc_dylib!(lib, "set_coverage_buffer", (*mut u64, usize), ());
+        let mut result = RocCallResult::default();
+        unsafe { set_coverage_buffer((self.ptr, self.length), &mut result) };
+    }
+
+    /// The counts so far, indexed by the id of the covered region.
+    pub fn counts(&self) -> &[u64] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.length) }
+    }
+}
+
+impl Drop for CoverageCounts {
+    fn drop(&mut self) {
+        let size = self.length.max(1) * std::mem::size_of::<u64>();
+
+        unsafe { libc::munmap(self.ptr.cast(), size) };
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn run_inline_expects<'a, W: std::io::Write>(
     writer: &mut W,
@@ -135,7 +192,7 @@ pub fn run_inline_expects<'a, W: std::io::Write>(
     lib: &libloading::Library,
     expectations: &mut VecMap<ModuleId, Expectations>,
     expects: ExpectFunctions<'_>,
-) -> std::io::Result<(usize, usize)> {
+) -> std::io::Result<Vec<ExpectResult>> {
     let shm_name = format!("/roc_expect_buffer_{}", std::process::id());
     let mut memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
 
@@ -162,7 +219,7 @@ pub fn run_toplevel_expects<'a, W: std::io::Write>(
     lib: &libloading::Library,
     expectations: &mut VecMap<ModuleId, Expectations>,
     expects: ExpectFunctions<'_>,
-) -> std::io::Result<(usize, usize)> {
+) -> std::io::Result<Vec<ExpectResult>> {
     let shm_name = format!("/roc_expect_buffer_{}", std::process::id());
     let mut memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
 
@@ -179,6 +236,24 @@ pub fn run_toplevel_expects<'a, W: std::io::Write>(
     )
 }
 
+/// How running a single top-level `expect` went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpectOutcome {
+    Passed,
+    Failed,
+    /// The `expect` crashed before it could finish, e.g. because of a call to `crash`.
+    Panicked,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ExpectResult {
+    pub symbol: Symbol,
+    pub region: Region,
+    pub outcome: ExpectOutcome,
+    /// Wall-clock time, including rendering any failures.
+    pub duration: Duration,
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
     writer: &mut W,
@@ -190,12 +265,12 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
     expectations: &mut VecMap<ModuleId, Expectations>,
     expects: ExpectFunctions<'_>,
     memory: &mut ExpectMemory,
-) -> std::io::Result<(usize, usize)> {
-    let mut failed = 0;
-    let mut passed = 0;
+) -> std::io::Result<Vec<ExpectResult>> {
+    let mut results = Vec::with_capacity(expects.fx.len() + expects.pure.len());
 
     for expect in expects.fx {
-        let result = run_expect_fx(
+        let start = Instant::now();
+        let outcome = run_expect_fx(
             writer,
             render_target,
             arena,
@@ -207,16 +282,19 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
             expect,
         )?;
 
-        match result {
-            true => passed += 1,
-            false => failed += 1,
-        }
+        results.push(ExpectResult {
+            symbol: expect.symbol,
+            region: expect.region,
+            outcome,
+            duration: start.elapsed(),
+        });
     }
 
     memory.set_shared_buffer(lib);
 
     for expect in expects.pure {
-        let result = run_expect_pure(
+        let start = Instant::now();
+        let outcome = run_expect_pure(
             writer,
             render_target,
             arena,
@@ -228,13 +306,15 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
             expect,
         )?;
 
-        match result {
-            true => passed += 1,
-            false => failed += 1,
-        }
+        results.push(ExpectResult {
+            symbol: expect.symbol,
+            region: expect.region,
+            outcome,
+            duration: start.elapsed(),
+        });
     }
 
-    Ok((failed, passed))
+    Ok(results)
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -248,7 +328,7 @@ fn run_expect_pure<'a, W: std::io::Write>(
     expectations: &mut VecMap<ModuleId, Expectations>,
     shared_memory: &mut ExpectMemory,
     expect: ToplevelExpect<'_>,
-) -> std::io::Result<bool> {
+) -> std::io::Result<ExpectOutcome> {
     use roc_gen_llvm::try_run_jit_function;
 
     let sequence = ExpectSequence::new(shared_memory.ptr.cast());
@@ -267,8 +347,10 @@ fn run_expect_pure<'a, W: std::io::Write>(
 
         let renderer = Renderer::new(arena, interns, render_target, module_id, filename, &source);
 
-        if let Err((roc_panic_message, _roc_panic_tag)) = result {
+        let outcome = if let Err((roc_panic_message, _roc_panic_tag)) = result {
             renderer.render_panic(writer, &roc_panic_message, expect.region)?;
+
+            ExpectOutcome::Panicked
         } else {
             let mut offset = ExpectSequence::START_OFFSET;
 
@@ -285,13 +367,15 @@ fn run_expect_pure<'a, W: std::io::Write>(
                     offset,
                 )?;
             }
-        }
+
+            ExpectOutcome::Failed
+        };
 
         writeln!(writer)?;
 
-        Ok(false)
+        Ok(outcome)
     } else {
-        Ok(true)
+        Ok(ExpectOutcome::Passed)
     }
 }
 
@@ -306,7 +390,7 @@ fn run_expect_fx<'a, W: std::io::Write>(
     expectations: &mut VecMap<ModuleId, Expectations>,
     parent_memory: &mut ExpectMemory,
     expect: ToplevelExpect<'_>,
-) -> std::io::Result<bool> {
+) -> std::io::Result<ExpectOutcome> {
     use signal_hook::{consts::signal::SIGCHLD, consts::signal::SIGUSR1, iterator::Signals};
 
     let mut signals = Signals::new([SIGCHLD, SIGUSR1]).unwrap();
@@ -344,18 +428,25 @@ fn run_expect_fx<'a, W: std::io::Write>(
 
             std::process::exit(1)
         }
-        1.. => {
-            let mut has_succeeded = true;
+        child_pid @ 1.. => {
+            let mut outcome = ExpectOutcome::Passed;
 
             for sig in &mut signals {
                 match sig {
                     SIGCHLD => {
-                        // done!
-                        return Ok(has_succeeded);
+                        // done! If the child didn't exit normally, the roc code crashed.
+                        let mut status = 0;
+                        unsafe { libc::waitpid(child_pid, &mut status, 0) };
+
+                        if !libc::WIFEXITED(status) || libc::WEXITSTATUS(status) != 0 {
+                            outcome = ExpectOutcome::Panicked;
+                        }
+
+                        return Ok(outcome);
                     }
                     SIGUSR1 => {
                         // this is the signal we use for an expect failure. Let's see what the child told us
-                        has_succeeded = false;
+                        outcome = ExpectOutcome::Failed;
 
                         let frame =
                             ExpectFrame::at_offset(parent_memory.ptr, ExpectSequence::START_OFFSET);
@@ -390,7 +481,7 @@ fn run_expect_fx<'a, W: std::io::Write>(
                 }
             }
 
-            Ok(true)
+            Ok(outcome)
         }
         _ => unreachable!(),
     }
@@ -473,7 +564,8 @@ pub fn render_dbgs_in_memory<'a>(
     )
 }
 
-fn split_expect_lookups(subs: &Subs, lookups: &[ExpectLookup]) -> Vec<Symbol> {
+/// The symbols and types of the values shown when an expectation fails.
+fn split_expect_lookups(subs: &Subs, lookups: &[ExpectLookup]) -> (Vec<Symbol>, Vec<Variable>) {
     lookups
         .iter()
         .filter_map(
@@ -487,11 +579,11 @@ fn split_expect_lookups(subs: &Subs, lookups: &[ExpectLookup]) -> Vec<Symbol> {
                 if subs.is_function(*var) {
                     None
                 } else {
-                    Some(*symbol)
+                    Some((*symbol, *var))
                 }
             },
         )
-        .collect()
+        .unzip()
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -569,9 +661,11 @@ fn render_expect_failure<'a>(
         Some(current) => current,
     };
 
-    let symbols = split_expect_lookups(&data.subs, current);
+    let (symbols, variables) = split_expect_lookups(&data.subs, current);
 
-    let (offset, expressions, variables) = crate::get_values(
+    // Each value was turned into a string by `Inspect.toStr` before the expectation was
+    // reported, so the types to show are those of the original lookups.
+    let (offset, expressions, _inspected_variables) = crate::get_values(
         target_info,
         arena,
         &data.subs,
@@ -747,6 +841,7 @@ pub fn expect_mono_module_to_dylib<'a>(
         mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        debug_sources: None,
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
@@ -1,5 +1,6 @@
 use indoc::indoc;
 use roc_repl_cli::repl_state::{is_incomplete, ReplState, TIPS};
+use roc_repl_cli::{history_entries, history_file_contents};
 
 // These are tests of the REPL state machine. They work without actually
 // running the CLI, and without using rustyline, and instead verify
@@ -101,6 +102,215 @@ fn standalone_annotation() {
     assert_eq!(state.step(&input, None), Ok(String::new()));
 }
 
+#[test]
+fn type_without_evaluating() {
+    let mut state = ReplState::new();
+
+    complete("x = 5", &mut state, Ok(("5 : Num *", "x")));
+    assert_eq!(step(":type x + 1", &mut state), "x + 1 : Num *");
+    assert_eq!(
+        step(":type \\a, b -> a + b", &mut state),
+        "\\a, b -> a + b : Num a, Num a -> Num a"
+    );
+
+    // :type doesn't name its expression, so the next one is still val1
+    complete("x * 2", &mut state, Ok(("10 : Num *", "val1")));
+}
+
+#[test]
+fn multiline_type() {
+    let mut input = ":type \\n ->".to_string();
+
+    incomplete(&mut input);
+
+    input.push_str("    n + 1");
+
+    assert_eq!(
+        step(&input, &mut ReplState::new()),
+        "\\n ->\n    n + 1 : Num a -> Num a"
+    );
+}
+
+#[test]
+fn show_and_reset() {
+    let mut state = ReplState::new();
+
+    assert_eq!(step(":show", &mut state), "There are no defs yet.");
+
+    complete("x = 5", &mut state, Ok(("5 : Num *", "x")));
+    complete("x + 1", &mut state, Ok(("6 : Num *", "val1")));
+    assert_eq!(step(":show", &mut state), "x = 5\n\nval1 = x + 1");
+
+    assert_eq!(step(":reset", &mut state), "Cleared all defs.");
+    assert_eq!(step(":show", &mut state), "There are no defs yet.");
+    complete("1 + 1", &mut state, Ok(("2 : Num *", "val1")));
+}
+
+#[test]
+fn load_module() {
+    let path = std::env::temp_dir().join(format!("ReplLoad{}.roc", std::process::id()));
+    let path_str = path.to_str().unwrap();
+    let mut state = ReplState::new();
+
+    std::fs::write(
+        &path,
+        indoc!(
+            r#"
+            interface Greeting
+                exposes [Name, greet]
+                imports []
+
+            Name : Str
+
+            greet : Name -> Str
+            greet = \name -> "Hello, \(name)!"
+
+            expect greet "Roc" == "Hello, Roc!"
+            "#
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        step(&format!(":load {path_str}"), &mut state),
+        format!("Loaded {path_str}: Name, greet")
+    );
+    complete(
+        r#"greet "Roc""#,
+        &mut state,
+        Ok((r#""Hello, Roc!" : Str"#, "val1")),
+    );
+
+    // Loading the module again replaces its defs with the new ones
+    std::fs::write(
+        &path,
+        indoc!(
+            r#"
+            interface Greeting
+                exposes [greet]
+                imports []
+
+            greet = \name -> "Hi, \(name)!"
+            "#
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        step(&format!(":load {path_str}"), &mut state),
+        format!("Loaded {path_str}: greet")
+    );
+    complete(
+        r#"greet "Roc""#,
+        &mut state,
+        Ok((r#""Hi, Roc!" : Str"#, "val2")),
+    );
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn main_module_imports() {
+    let dir = std::env::temp_dir().join(format!("repl_main_module_{}", std::process::id()));
+    let main_path = dir.join("main.roc");
+    let mut state = ReplState::new();
+
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("Helper.roc"),
+        indoc!(
+            r#"
+            interface Helper
+                exposes [double]
+                imports []
+
+            double = \n -> n * 2
+            "#
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        &main_path,
+        indoc!(
+            r#"
+            app "example"
+                imports [Helper.{ double }]
+                provides [main] to "./platform"
+
+            main = double 21
+            "#
+        ),
+    )
+    .unwrap();
+
+    let output = state.use_main_module(&main_path).unwrap();
+    let output = String::from_utf8(strip_ansi_escapes::strip(output.trim()).unwrap()).unwrap();
+
+    assert_eq!(output, format!("Loaded {}: main", main_path.display()));
+    complete("main", &mut state, Ok(("42 : Num *", "main")));
+    complete("Helper.double 5", &mut state, Ok(("10 : Num *", "val1")));
+    complete("double 6", &mut state, Ok(("12 : Num *", "val2")));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn main_module_runs_tasks() {
+    let dir = std::env::temp_dir().join(format!("repl_main_module_tasks_{}", std::process::id()));
+    let main_path = dir.join("main.roc");
+    let platform_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../cli_testing_examples/benchmarks/platform/main.roc");
+    let mut state = ReplState::new();
+
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        &main_path,
+        format!(
+            indoc!(
+                r#"
+                app "example"
+                    packages {{ pf: "{}" }}
+                    imports [pf.Task]
+                    provides [main] to pf
+
+                main : Task.Task {{}} []
+                main = Task.putLine "Hello from a task"
+                "#
+            ),
+            platform_path.display()
+        ),
+    )
+    .unwrap();
+
+    state.use_main_module(&main_path).unwrap();
+    complete("main", &mut state, Ok(("<task> : Task {} []", "main")));
+    complete(
+        r#"Task.putLine "Hello again""#,
+        &mut state,
+        Ok(("<task> : Task {} *", "val1")),
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn history_file_round_trip() {
+    let entries = ["1 + 1", "t : [A, B]\nt = A", r#""\\n""#];
+    let contents = history_file_contents(entries.iter().copied());
+
+    assert_eq!(contents.lines().count(), entries.len());
+    assert_eq!(history_entries(&contents), entries);
+}
+
+/// step the given input, and return its output with ANSI escape codes stripped.
+fn step(input: &str, state: &mut ReplState) -> String {
+    assert!(!is_incomplete(input));
+
+    let output = state.step(input, None).unwrap();
+
+    std::string::String::from_utf8(strip_ansi_escapes::strip(output.trim()).unwrap()).unwrap()
+}
+
 /// validate and step the given input, then check the Result vs the output
 /// with ANSI escape codes stripped.
 fn complete(input: &str, state: &mut ReplState, expected_step_result: Result<(&str, &str), i32>) {
@@ -7,7 +7,7 @@ use roc_load::MonomorphizedModule;
 use roc_parse::ast::Expr;
 use roc_repl_eval::{
     eval::jit_to_ast,
-    gen::{compile_to_mono, format_answer},
+    gen::{compile_to_mono, format_answer, ReplContext},
     ReplApp, ReplAppMemory,
 };
 use roc_reporting::report::DEFAULT_PALETTE_HTML;
@@ -184,6 +184,7 @@ pub async fn entrypoint_from_js(src: String) -> Result<String, String> {
     // See the variable by the same name in the CLI REPL for how to do this!
     let mono = match compile_to_mono(
         arena,
+        &ReplContext::default(),
         std::iter::empty(),
         &src,
         target_info,
@@ -2,9 +2,12 @@ use std::path::PathBuf;
 
 use roc_collections::MutMap;
 use roc_module::symbol::{Interns, ModuleId};
-use roc_region::all::LineInfo;
+use roc_region::all::{LineColumnRegion, LineInfo};
 use roc_solve_problem::TypeError;
 
+use crate::error::r#type::missing_pattern_to_source;
+use crate::report::RenderTarget;
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct Problems {
     pub fatally_errored: bool,
@@ -57,6 +60,7 @@ pub fn report_problems(
     interns: &Interns,
     can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+    render: RenderTarget,
 ) -> Problems {
     use crate::report::{can_problem, type_problem, Report, RocDocAllocator, DEFAULT_PALETTE};
     use roc_problem::Severity::*;
@@ -81,11 +85,10 @@ pub fn report_problems(
         let problems = can_problems.remove(home).unwrap_or_default();
 
         for problem in problems.into_iter() {
+            let region = problem.region().map(|region| lines.convert_region(region));
             let report = can_problem(&alloc, &lines, module_path.clone(), problem);
             let severity = report.severity;
-            let mut buf = String::new();
-
-            report.render_color_terminal(&mut buf, &alloc, &palette);
+            let buf = render_report(report, region, Vec::new(), render, &alloc, &palette);
 
             match severity {
                 Warning => {
@@ -104,11 +107,18 @@ pub fn report_problems(
         let problems = type_problems.remove(home).unwrap_or_default();
 
         for problem in problems {
+            let region = problem.region().map(|region| lines.convert_region(region));
+            let missing_patterns = match &problem {
+                TypeError::Exhaustive(roc_exhaustive::Error::Incomplete(_, _, missing)) => missing
+                    .iter()
+                    .map(|pattern| missing_pattern_to_source(&alloc, pattern.clone()))
+                    .collect(),
+                _ => Vec::new(),
+            };
+
             if let Some(report) = type_problem(&alloc, &lines, module_path.clone(), problem) {
                 let severity = report.severity;
-                let mut buf = String::new();
-
-                report.render_color_terminal(&mut buf, &alloc, &palette);
+                let buf = render_report(report, region, missing_patterns, render, &alloc, &palette);
 
                 match severity {
                     Warning => {
@@ -126,31 +136,32 @@ pub fn report_problems(
         }
     }
 
-    let problems_reported;
-
     // Only print warnings if there are no errors
-    if errors.is_empty() {
-        problems_reported = warnings.len();
+    let reported = if errors.is_empty() {
+        &warnings
+    } else {
+        &errors
+    };
 
-        for warning in warnings.iter() {
-            println!("\n{}\n", warning);
+    if let RenderTarget::Json = render {
+        // One diagnostic per line, with nothing in between.
+        for problem in reported.iter() {
+            println!("{}", problem);
         }
     } else {
-        problems_reported = errors.len();
-
-        for error in errors.iter() {
-            println!("\n{}\n", error);
+        for problem in reported.iter() {
+            println!("\n{}\n", problem);
         }
-    }
 
-    // If we printed any problems, print a horizontal rule at the end,
-    // and then clear any ANSI escape codes (e.g. colors) we've used.
-    //
-    // The horizontal rule is nice when running the program right after
-    // compiling it, as it lets you clearly see where the compiler
-    // errors/warnings end and the program output begins.
-    if problems_reported > 0 {
-        println!("{}\u{001B}[0m\n", Report::horizontal_rule(&palette));
+        // If we printed any problems, print a horizontal rule at the end,
+        // and then clear any ANSI escape codes (e.g. colors) we've used.
+        //
+        // The horizontal rule is nice when running the program right after
+        // compiling it, as it lets you clearly see where the compiler
+        // errors/warnings end and the program output begins.
+        if !reported.is_empty() {
+            println!("{}\u{001B}[0m\n", Report::horizontal_rule(&palette));
+        }
     }
 
     Problems {
@@ -159,3 +170,27 @@ pub fn report_problems(
         warnings: warnings.len(),
     }
 }
+
+fn render_report<'b>(
+    report: crate::report::Report<'b>,
+    region: Option<LineColumnRegion>,
+    missing_patterns: Vec<String>,
+    render: RenderTarget,
+    alloc: &'b crate::report::RocDocAllocator<'b>,
+    palette: &'b crate::report::Palette,
+) -> String {
+    let mut buf = String::new();
+
+    match render {
+        // We know better than the report which region the problem is about.
+        RenderTarget::Json => {
+            let mut diagnostic = report.into_diagnostic(region);
+            diagnostic.missing_patterns = missing_patterns;
+
+            buf.push_str(&diagnostic.to_json())
+        }
+        _ => report.render(render, &mut buf, alloc, palette),
+    }
+
+    buf
+}
@@ -0,0 +1,80 @@
+//! Reports as structured data, for tools that would otherwise have to scrape terminal output.
+use roc_problem::Severity;
+use roc_region::all::{LineColumn, LineColumnRegion};
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub severity: DiagnosticSeverity,
+    /// The report title, e.g. `TYPE MISMATCH`.
+    pub title: String,
+    pub file: PathBuf,
+    /// The code the report is about, if it points at any.
+    pub region: Option<DiagnosticRegion>,
+    /// The body of the report as plain text, without the header.
+    pub message: String,
+    /// Other code snippets the message refers to.
+    pub related: Vec<DiagnosticRegion>,
+    /// For a pattern match that doesn't cover every possibility, the patterns it is missing,
+    /// as Roc code.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub missing_patterns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    Warning,
+    Error,
+    Fatal,
+}
+
+impl From<Severity> for DiagnosticSeverity {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Warning => DiagnosticSeverity::Warning,
+            Severity::RuntimeError => DiagnosticSeverity::Error,
+            Severity::Fatal => DiagnosticSeverity::Fatal,
+        }
+    }
+}
+
+/// Lines and columns start at 1, matching the line numbers printed in reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DiagnosticPosition {
+    pub line: u32,
+    pub column: u32,
+}
+
+impl From<LineColumn> for DiagnosticPosition {
+    fn from(lc: LineColumn) -> Self {
+        DiagnosticPosition {
+            line: lc.line + 1,
+            column: lc.column + 1,
+        }
+    }
+}
+
+/// The end position is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DiagnosticRegion {
+    pub start: DiagnosticPosition,
+    pub end: DiagnosticPosition,
+}
+
+impl From<LineColumnRegion> for DiagnosticRegion {
+    fn from(region: LineColumnRegion) -> Self {
+        DiagnosticRegion {
+            start: region.start.into(),
+            end: region.end.into(),
+        }
+    }
+}
+
+impl Diagnostic {
+    /// A single line of JSON, so a stream of diagnostics can be read line by line.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("diagnostics always serialize")
+    }
+}
@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use bumpalo::Bump;
 use roc_module::symbol::{Interns, ModuleId, Symbol};
-use roc_parse::ast::Expr;
+use roc_parse::ast::{Expr, StrLiteral};
 use roc_problem::Severity;
 use roc_region::all::{LineColumnRegion, LineInfo, Region};
 use roc_types::{
@@ -49,17 +49,28 @@ impl<'a> Renderer<'a> {
         error_type_to_doc(&self.alloc, error_type)
     }
 
+    /// Values in `expect` and `dbg` reports are turned into strings by `Inspect.toStr` before
+    /// they reach us, so we show the contents of that string rather than a string literal.
+    fn render_inspected(&self, expr: &Expr<'_>) -> &'a str {
+        match expr {
+            Expr::Str(StrLiteral::PlainLine(inspected)) => self.arena.alloc_str(inspected),
+            _ => {
+                use roc_fmt::annotation::Formattable;
+
+                let mut buf = roc_fmt::Buf::new_in(self.arena);
+                expr.format(&mut buf, 0);
+
+                buf.into_bump_str()
+            }
+        }
+    }
+
     fn render_lookup(
         &'a self,
         symbol: Symbol,
         expr: &Expr<'_>,
         error_type: ErrorType,
     ) -> RocDocBuilder<'a> {
-        use roc_fmt::annotation::Formattable;
-
-        let mut buf = roc_fmt::Buf::new_in(self.arena);
-        expr.format(&mut buf, 0);
-
         self.alloc.vcat([
             self.alloc
                 .symbol_unqualified(symbol)
@@ -68,7 +79,7 @@ impl<'a> Renderer<'a> {
             self.alloc
                 .symbol_unqualified(symbol)
                 .append(" = ")
-                .append(buf.into_bump_str()),
+                .append(self.render_inspected(expr)),
         ])
     }
 
@@ -190,15 +201,7 @@ impl<'a> Renderer<'a> {
             line_col_region.start.column + 1
         )?;
 
-        let expr = expressions[0];
-
-        let mut buf = roc_fmt::Buf::new_in(self.arena);
-        {
-            use roc_fmt::annotation::Formattable;
-            expr.format(&mut buf, 0);
-        }
-
-        writeln!(writer, "{}", buf.as_str())
+        writeln!(writer, "{}", self.render_inspected(&expressions[0]))
     }
 
     pub fn render_panic<W>(
@@ -1,7 +1,7 @@
 #![allow(clippy::too_many_arguments)]
 
 use crate::error::canonicalize::{to_circular_def_doc, CIRCULAR_DEF};
-use crate::report::{Annotation, Report, RocDocAllocator, RocDocBuilder};
+use crate::report::{Annotation, CiWrite, Report, RocDocAllocator, RocDocBuilder};
 use itertools::EitherOrBoth;
 use itertools::Itertools;
 use roc_can::expected::{Expected, PExpected};
@@ -5026,7 +5026,45 @@ fn exhaustive_pattern_to_doc<'b>(
     alloc: &'b RocDocAllocator<'b>,
     pattern: roc_exhaustive::Pattern,
 ) -> RocDocBuilder<'b> {
-    pattern_to_doc_help(alloc, pattern, false)
+    pattern_to_doc_help(alloc, pattern, false, false)
+}
+
+/// Renders a pattern that `roc_exhaustive` found to be missing as Roc code, so it can be used
+/// as the pattern of a new `when` branch.
+pub fn missing_pattern_to_source<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    pattern: roc_exhaustive::Pattern,
+) -> String {
+    let mut buf = String::new();
+
+    pattern_to_doc_help(alloc, pattern, false, true)
+        .annotate(Annotation::TypeBlock)
+        .1
+        .render_raw(usize::MAX / 2, &mut CiWrite::new(&mut buf))
+        .expect("<buffer is not a utf-8 encoded string>");
+
+    buf
+}
+
+fn str_literal_source(s: &str) -> String {
+    let mut buf = String::with_capacity(s.len() + 2);
+
+    buf.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '$' => buf.push_str("\\$"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            c if c.is_control() => buf.push_str(&format!("\\u({:x})", c as u32)),
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+
+    buf
 }
 
 const AFTER_TAG_INDENT: &str = "    ";
@@ -5037,6 +5075,7 @@ fn pattern_to_doc_help<'b>(
     alloc: &'b RocDocAllocator<'b>,
     pattern: roc_exhaustive::Pattern,
     in_type_param: bool,
+    for_source: bool,
 ) -> RocDocBuilder<'b> {
     use roc_can::exhaustive::{GUARD_CTOR, NONEXHAUSIVE_CTOR};
     use roc_exhaustive::Literal::*;
@@ -5051,8 +5090,10 @@ fn pattern_to_doc_help<'b>(
             Bit(true) => alloc.text("Bool.true"),
             Bit(false) => alloc.text("Bool.false"),
             Byte(b) => alloc.text(b.to_string()),
+            Float(f) if for_source => alloc.text(f64::from_bits(f).to_string()),
             Float(f) => alloc.text(f.to_string()),
             Decimal(d) => alloc.text(RocDec::from_ne_bytes(d).to_string()),
+            Str(s) if for_source => alloc.string(str_literal_source(&s)),
             Str(s) => alloc.string(s.into()),
         },
         List(arity, patterns) => {
@@ -5060,13 +5101,13 @@ fn pattern_to_doc_help<'b>(
                 ListArity::Exact(_) => alloc.intersperse(
                     patterns
                         .into_iter()
-                        .map(|p| pattern_to_doc_help(alloc, p, false)),
+                        .map(|p| pattern_to_doc_help(alloc, p, false, for_source)),
                     alloc.text(",").append(alloc.space()),
                 ),
                 ListArity::Slice(num_before, num_after) => {
                     let mut all_patterns = patterns
                         .into_iter()
-                        .map(|p| pattern_to_doc_help(alloc, p, in_type_param));
+                        .map(|p| pattern_to_doc_help(alloc, p, in_type_param, for_source));
 
                     let spread = alloc.text("..");
                     let comma_space = alloc.text(",").append(alloc.space());
@@ -5094,7 +5135,13 @@ fn pattern_to_doc_help<'b>(
                         .name
                         .is_tag(&TagName(GUARD_CTOR.into())));
                     debug_assert!(args.len() == 2);
-                    let tag = pattern_to_doc_help(alloc, args[1].clone(), in_type_param);
+                    let tag =
+                        pattern_to_doc_help(alloc, args[1].clone(), in_type_param, for_source);
+
+                    if for_source {
+                        return tag;
+                    }
+
                     alloc.concat([
                         tag,
                         alloc.text(AFTER_TAG_INDENT),
@@ -5108,6 +5155,10 @@ fn pattern_to_doc_help<'b>(
 
                     for (label, v) in field_names.into_iter().zip(args.into_iter()) {
                         match &v {
+                            // A punned field would bind a variable the new branch doesn't use
+                            Anything if for_source => {
+                                arg_docs.push(alloc.text(format!("{}: _", label)));
+                            }
                             Anything => {
                                 arg_docs.push(alloc.text(label.to_string()));
                             }
@@ -5116,7 +5167,7 @@ fn pattern_to_doc_help<'b>(
                                     alloc
                                         .text(label.to_string())
                                         .append(alloc.reflow(": "))
-                                        .append(pattern_to_doc_help(alloc, v, false)),
+                                        .append(pattern_to_doc_help(alloc, v, false, for_source)),
                                 );
                             }
                         }
@@ -5131,7 +5182,7 @@ fn pattern_to_doc_help<'b>(
                     let mut arg_docs = Vec::with_capacity(args.len());
 
                     for v in args.into_iter() {
-                        arg_docs.push(pattern_to_doc_help(alloc, v, false));
+                        arg_docs.push(pattern_to_doc_help(alloc, v, false, for_source));
                     }
 
                     alloc
@@ -5147,6 +5198,7 @@ fn pattern_to_doc_help<'b>(
                                 alloc,
                                 roc_exhaustive::Pattern::Anything,
                                 in_type_param,
+                                for_source,
                             )
                         }
                         _ => {}
@@ -5163,7 +5215,7 @@ fn pattern_to_doc_help<'b>(
                     let has_args = !args.is_empty();
                     let arg_docs = args
                         .into_iter()
-                        .map(|v| pattern_to_doc_help(alloc, v, true));
+                        .map(|v| pattern_to_doc_help(alloc, v, true, for_source));
 
                     // We assume the alternatives are sorted. If not, this assert will trigger
                     debug_assert!(tag_id == ctor.tag_id);
@@ -4,5 +4,6 @@
 #![allow(clippy::large_enum_variant)]
 
 pub mod cli;
+pub mod diagnostic;
 pub mod error;
 pub mod report;
@@ -12,6 +12,8 @@ use byte_unit::Byte;
 #[cfg(not(target_family = "wasm"))]
 use roc_packaging::https::Problem;
 
+use crate::diagnostic::Diagnostic;
+
 pub use crate::error::canonicalize::can_problem;
 pub use crate::error::parse::parse_problem;
 pub use crate::error::r#type::type_problem;
@@ -109,6 +111,8 @@ pub fn pretty_header_with_path(title: &str, path: &Path) -> String {
 pub enum RenderTarget {
     ColorTerminal,
     Generic,
+    /// A single line of JSON per report, for editors and CI tooling.
+    Json,
 }
 
 /// A textual report.
@@ -123,18 +127,57 @@ impl<'b> Report<'b> {
     pub fn render(
         self,
         target: RenderTarget,
-        buf: &'b mut String,
+        buf: &mut String,
         alloc: &'b RocDocAllocator<'b>,
         palette: &'b Palette,
     ) {
         match target {
             RenderTarget::Generic => self.render_ci(buf, alloc),
             RenderTarget::ColorTerminal => self.render_color_terminal(buf, alloc, palette),
+            RenderTarget::Json => self.render_json(buf),
+        }
+    }
+
+    /// Render as one newline-terminated line of JSON, see [`Diagnostic`].
+    pub fn render_json(self, buf: &mut String) {
+        buf.push_str(&self.into_diagnostic(None).to_json());
+        buf.push('\n');
+    }
+
+    /// Turn the report into structured data. The `primary` region is what the report is about;
+    /// if it is not given, the first code snippet in the report is used instead.
+    pub fn into_diagnostic(self, primary: Option<LineColumnRegion>) -> Diagnostic {
+        let err_msg = "<buffer is not a utf-8 encoded string>";
+        let mut message = String::new();
+        let mut writer = CiWrite::new(&mut message);
+
+        self.doc.1.render_raw(70, &mut writer).expect(err_msg);
+
+        let snippets = std::mem::take(&mut writer.snippets);
+        let region = primary.or_else(|| snippets.first().copied());
+        let mut related = Vec::new();
+
+        for snippet in snippets {
+            if Some(snippet) != region && !related.contains(&snippet) {
+                related.push(snippet);
+            }
+        }
+
+        message.truncate(message.trim_end().len());
+
+        Diagnostic {
+            severity: self.severity.into(),
+            title: self.title,
+            file: self.filename,
+            region: region.map(Into::into),
+            message,
+            related: related.into_iter().map(Into::into).collect(),
+            missing_patterns: Vec::new(),
         }
     }
 
     /// Render to CI console output, where no colors are available.
-    pub fn render_ci(self, buf: &'b mut String, alloc: &'b RocDocAllocator<'b>) {
+    pub fn render_ci(self, buf: &mut String, alloc: &'b RocDocAllocator<'b>) {
         let err_msg = "<buffer is not a utf-8 encoded string>";
 
         self.pretty(alloc)
@@ -640,7 +683,10 @@ impl<'a> RocDocAllocator<'a> {
             result = result.append(highlight_line);
         }
 
-        result.annotate(Annotation::CodeBlock)
+        result
+            .annotate(Annotation::CodeBlock)
+            .annotate(Annotation::Snippet(sub_region1))
+            .annotate(Annotation::Snippet(sub_region2))
     }
 
     pub fn region_with_subregion(
@@ -739,7 +785,7 @@ impl<'a> RocDocAllocator<'a> {
             result = result.append(highlight_line);
         }
 
-        result
+        result.annotate(Annotation::Snippet(sub_region))
     }
 
     pub fn region(&'a self, region: LineColumnRegion) -> DocBuilder<'a, Self, Annotation> {
@@ -847,6 +893,8 @@ pub enum Annotation {
     Tip,
     Header,
     ParserSuggestion,
+    /// A code snippet pointing at this region. Only structured output makes use of it.
+    Snippet(LineColumnRegion),
 }
 
 /// Render with minimal formatting
@@ -854,6 +902,7 @@ pub struct CiWrite<W> {
     style_stack: Vec<Annotation>,
     in_type_block: bool,
     in_code_block: bool,
+    snippets: Vec<LineColumnRegion>,
     upstream: W,
 }
 
@@ -863,6 +912,7 @@ impl<W> CiWrite<W> {
             style_stack: vec![],
             in_type_block: false,
             in_code_block: false,
+            snippets: vec![],
             upstream,
         }
     }
@@ -918,6 +968,9 @@ where
             CodeBlock => {
                 self.in_code_block = true;
             }
+            Snippet(region) => {
+                self.snippets.push(*region);
+            }
             Emphasized => {
                 self.write_str("*")?;
             }
@@ -1054,7 +1107,8 @@ where
             ParserSuggestion => {
                 self.write_str(self.palette.parser_suggestion)?;
             }
-            TypeBlock | InlineTypeBlock | Tag | RecordField | TupleElem => { /* nothing yet */ }
+            TypeBlock | InlineTypeBlock | Tag | RecordField | TupleElem | Snippet(_) => { /* nothing yet */
+            }
         }
         self.style_stack.push(*annotation);
         Ok(())
@@ -1072,8 +1126,8 @@ where
                     self.write_str(self.palette.reset)?;
                 }
 
-                TypeBlock | InlineTypeBlock | Tag | Opaque | RecordField | TupleElem => { /* nothing yet */
-                }
+                TypeBlock | InlineTypeBlock | Tag | Opaque | RecordField | TupleElem
+                | Snippet(_) => { /* nothing yet */ }
             },
         }
         Ok(())
@@ -1454,7 +1508,10 @@ pub fn to_https_problem_report<'b>(
                     alloc.reflow(r"For your security, I will only attempt to download "),
                     alloc.reflow(r"files from servers which use the "),
                     alloc.keyword(r"https"),
-                    alloc.reflow(r" protocol."),
+                    alloc.reflow(r" protocol. Tarballs on this computer can be given "),
+                    alloc.reflow(r"as a "),
+                    alloc.keyword(r"file://"),
+                    alloc.reflow(r" URL or as a path instead."),
                 ]),
                 alloc.concat([
                     alloc.tip(),
@@ -1542,6 +1599,166 @@ pub fn to_https_problem_report<'b>(
                 severity: Severity::Fatal,
             }
         }
+        Problem::NotAvailableOffline { searched } => {
+            let doc = alloc.stack([
+                alloc.reflow(r"I was running offline, so I could not download this URL:"),
+                alloc
+                    .string((&url).to_string())
+                    .annotate(Annotation::Url)
+                    .indent(4),
+                alloc.reflow(r"I also didn't find this package in any of these directories:"),
+                alloc.stack(searched.iter().map(|dir| {
+                    alloc
+                        .string(dir.display().to_string())
+                        .annotate(Annotation::PlainText)
+                        .indent(4)
+                })),
+                alloc.concat([
+                    alloc.tip(),
+                    alloc.reflow(r"Run "),
+                    alloc.keyword(r"roc vendor"),
+                    alloc.reflow(r" on a machine with network access to copy this package "),
+                    alloc.reflow(r"into your project's vendor directory."),
+                ]),
+            ]);
+
+            Report {
+                filename: "UNKNOWN.roc".into(),
+                doc,
+                title: "PACKAGE NOT AVAILABLE OFFLINE".to_string(),
+                severity: Severity::Fatal,
+            }
+        }
+        Problem::ReadLockfile(lockfile, err) => {
+            let doc = alloc.stack([
+                alloc.reflow(r"I was running offline, so I needed to check this URL against your project's lockfile:"),
+                alloc
+                    .string((&url).to_string())
+                    .annotate(Annotation::Url)
+                    .indent(4),
+                alloc.reflow(r"But I couldn't read the lockfile at this path:"),
+                alloc
+                    .string(lockfile.display().to_string())
+                    .annotate(Annotation::PlainText)
+                    .indent(4),
+                alloc.concat([
+                    alloc.reflow(r"The error was: "),
+                    alloc.string(err.to_string()),
+                ]),
+                alloc.concat([
+                    alloc.tip(),
+                    alloc.reflow(r"Run "),
+                    alloc.keyword(r"roc vendor"),
+                    alloc.reflow(r" on a machine with network access to create the lockfile "),
+                    alloc.reflow(r"and copy your packages into your project's vendor directory."),
+                ]),
+            ]);
+
+            Report {
+                filename: "UNKNOWN.roc".into(),
+                doc,
+                title: "MISSING LOCKFILE".to_string(),
+                severity: Severity::Fatal,
+            }
+        }
+        Problem::InvalidLockfile { lockfile, line } => {
+            let doc = alloc.stack([
+                alloc.reflow(r"I was running offline, so I needed to check this URL against your project's lockfile:"),
+                alloc
+                    .string((&url).to_string())
+                    .annotate(Annotation::Url)
+                    .indent(4),
+                alloc.concat([
+                    alloc.reflow(r"But line "),
+                    alloc.string(line.to_string()).annotate(Annotation::Keyword),
+                    alloc.reflow(r" of the lockfile at this path isn't a package URL followed by its content hash:"),
+                ]),
+                alloc
+                    .string(lockfile.display().to_string())
+                    .annotate(Annotation::PlainText)
+                    .indent(4),
+                alloc.concat([
+                    alloc.tip(),
+                    alloc.reflow(r"Lockfiles aren't meant to be edited by hand. Run "),
+                    alloc.keyword(r"roc vendor"),
+                    alloc.reflow(r" on a machine with network access to write it again."),
+                ]),
+            ]);
+
+            Report {
+                filename: "UNKNOWN.roc".into(),
+                doc,
+                title: "INVALID LOCKFILE".to_string(),
+                severity: Severity::Fatal,
+            }
+        }
+        Problem::NotInLockfile { lockfile } => {
+            let doc = alloc.stack([
+                alloc.reflow(
+                    r"I was running offline, and this URL isn't in your project's lockfile:",
+                ),
+                alloc
+                    .string((&url).to_string())
+                    .annotate(Annotation::Url)
+                    .indent(4),
+                alloc.reflow(r"This is the lockfile I checked:"),
+                alloc
+                    .string(lockfile.display().to_string())
+                    .annotate(Annotation::PlainText)
+                    .indent(4),
+                alloc.concat([
+                    alloc.tip(),
+                    alloc.reflow(r"If you added or changed a package since you last ran "),
+                    alloc.keyword(r"roc vendor"),
+                    alloc.reflow(r", run it again on a machine with network access."),
+                ]),
+            ]);
+
+            Report {
+                filename: "UNKNOWN.roc".into(),
+                doc,
+                title: "PACKAGE NOT IN LOCKFILE".to_string(),
+                severity: Severity::Fatal,
+            }
+        }
+        Problem::LockfileHashMismatch {
+            lockfile,
+            expected,
+            actual,
+        } => {
+            let doc = alloc.stack([
+                alloc.reflow(r"I was running offline, so I checked this URL against your project's lockfile:"),
+                alloc
+                    .string((&url).to_string())
+                    .annotate(Annotation::Url)
+                    .indent(4),
+                alloc.reflow(r"The lockfile lists a different content hash for it:"),
+                alloc
+                    .string(lockfile.display().to_string())
+                    .annotate(Annotation::PlainText)
+                    .indent(4),
+                alloc.concat([
+                    alloc.reflow(r"The URL has the hash "),
+                    alloc.string(actual).annotate(Annotation::Keyword),
+                    alloc.reflow(r", but the lockfile expects "),
+                    alloc.string(expected).annotate(Annotation::Keyword),
+                    alloc.reflow(r"."),
+                ]),
+                alloc.concat([
+                    alloc.tip(),
+                    alloc.reflow(r"Lockfiles aren't meant to be edited by hand. Run "),
+                    alloc.keyword(r"roc vendor"),
+                    alloc.reflow(r" on a machine with network access to write it again."),
+                ]),
+            ]);
+
+            Report {
+                filename: "UNKNOWN.roc".into(),
+                doc,
+                title: "LOCKFILE MISMATCH".to_string(),
+                severity: Severity::Fatal,
+            }
+        }
     }
 }
 
@@ -21,6 +21,10 @@ mod test_reporting {
     use roc_parse::test_helpers::parse_expr_with;
     use roc_problem::Severity;
     use roc_region::all::LineInfo;
+    use roc_reporting::diagnostic::{
+        Diagnostic, DiagnosticPosition, DiagnosticRegion, DiagnosticSeverity,
+    };
+    use roc_reporting::error::r#type::missing_pattern_to_source;
     use roc_reporting::report::{
         can_problem, parse_problem, type_problem, RenderTarget, Report, ANSI_STYLE_CODES,
         DEFAULT_PALETTE,
@@ -9699,7 +9703,7 @@ In roc, functions are always written as a lambda, like{}
 
     Only builtin abilities can be derived.
 
-    Note: The builtin abilities are `Encoding`, `Decoding`, `Hash`, `Eq`
+    Note: The builtin abilities are `Encoding`, `Decoding`, `Hash`, `Eq`, `Inspect`
     "###
     );
 
@@ -13841,4 +13845,135 @@ In roc, functions are always written as a lambda, like{}
     Tip: It looks like it takes too many arguments. I'm seeing 1 extra.
     "###
     );
+
+    fn diagnostics_new(subdir: &str, src: &str) -> Vec<Diagnostic> {
+        let arena = Bump::new();
+        let filename = filename_from_string(r"/code/proj/Main.roc");
+        let (module_src, type_problems, can_problems, home, interns) =
+            infer_expr_help_new(subdir, &arena, src).unwrap();
+
+        let lines = LineInfo::new(&module_src);
+        let src_lines: Vec<&str> = module_src.split('\n').collect();
+        let alloc = RocDocAllocator::new(&src_lines, home, &interns);
+        let mut diagnostics = Vec::new();
+
+        for problem in can_problems {
+            let report = can_problem(&alloc, &lines, filename.clone(), problem);
+            diagnostics.push(report.into_diagnostic(None));
+        }
+
+        for problem in type_problems {
+            if let Some(report) = type_problem(&alloc, &lines, filename.clone(), problem) {
+                diagnostics.push(report.into_diagnostic(None));
+            }
+        }
+
+        diagnostics
+    }
+
+    fn diagnostic_region(start: (u32, u32), end: (u32, u32)) -> DiagnosticRegion {
+        DiagnosticRegion {
+            start: DiagnosticPosition {
+                line: start.0,
+                column: start.1,
+            },
+            end: DiagnosticPosition {
+                line: end.0,
+                column: end.1,
+            },
+        }
+    }
+
+    #[test]
+    fn diagnostic_of_type_mismatch() {
+        let diagnostics = diagnostics_new(
+            "diagnostic_of_type_mismatch",
+            indoc!(
+                r#"
+                x : Str
+                x = 1
+
+                x
+                "#
+            ),
+        );
+
+        assert_eq!(diagnostics.len(), 1);
+
+        let diagnostic = &diagnostics[0];
+
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostic.title, "TYPE MISMATCH");
+        assert_eq!(diagnostic.region, Some(diagnostic_region((5, 9), (5, 10))));
+        assert_eq!(diagnostic.related, Vec::new());
+        assert!(diagnostic
+            .message
+            .starts_with("Something is off with the body of the `x` definition:"));
+    }
+
+    #[test]
+    fn diagnostic_of_shadowing_has_related_region() {
+        let diagnostics = diagnostics_new(
+            "diagnostic_of_shadowing_has_related_region",
+            indoc!(
+                r#"
+                x = 1
+                x = 2
+
+                x
+                "#
+            ),
+        );
+
+        let diagnostic = diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.title == "DUPLICATE NAME")
+            .unwrap();
+
+        assert_eq!(diagnostic.region, Some(diagnostic_region((4, 5), (4, 6))));
+        assert_eq!(diagnostic.related, vec![diagnostic_region((5, 5), (5, 6))]);
+        assert!(diagnostic
+            .to_json()
+            .starts_with(r#"{"severity":"error","title":"DUPLICATE NAME","#));
+    }
+
+    #[test]
+    fn missing_patterns_as_source() {
+        let arena = Bump::new();
+        let (module_src, type_problems, _can_problems, home, interns) = infer_expr_help_new(
+            "missing_patterns_as_source",
+            &arena,
+            indoc!(
+                r#"
+                x : [Red, Green, Blue Str]
+                x = Red
+
+                when x is
+                    Red -> 1
+                "#
+            ),
+        )
+        .unwrap();
+
+        let src_lines: Vec<&str> = module_src.split('\n').collect();
+        let alloc = RocDocAllocator::new(&src_lines, home, &interns);
+
+        let missing = type_problems
+            .into_iter()
+            .find_map(|problem| match problem {
+                TypeError::Exhaustive(roc_exhaustive::Error::Incomplete(_, _, missing)) => {
+                    Some(missing)
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        let mut missing: Vec<String> = missing
+            .into_iter()
+            .map(|pattern| missing_pattern_to_source(&alloc, pattern))
+            .collect();
+        missing.sort();
+
+        assert_eq!(missing, ["Blue _", "Green"]);
+    }
 }
@@ -12,6 +12,8 @@ use roc_wasm_module::WasmModule;
 pub const FLAG_FUNCTION: &str = "function";
 pub const FLAG_DEBUG: &str = "debug";
 pub const FLAG_HEX: &str = "hex";
+pub const FLAG_DIR: &str = "dir";
+pub const FLAG_ENV: &str = "env";
 pub const WASM_FILE: &str = "WASM_FILE";
 pub const ARGS_FOR_APP: &str = "ARGS_FOR_APP";
 
@@ -38,6 +40,18 @@ fn main() -> io::Result<()> {
         .action(ArgAction::SetTrue)
         .required(false);
 
+    let flag_dir = Arg::new(FLAG_DIR)
+        .long(FLAG_DIR)
+        .help("Give the app access to a directory on the host. Can be repeated. By default, the app can't access any files.")
+        .action(ArgAction::Append)
+        .required(false);
+
+    let flag_env = Arg::new(FLAG_ENV)
+        .long(FLAG_ENV)
+        .help("Pass the host's environment variables through to the app.")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
     let wasm_file_to_run = Arg::new(WASM_FILE)
         .help("The .wasm file to run")
         .required(true);
@@ -51,6 +65,8 @@ fn main() -> io::Result<()> {
         .arg(flag_function)
         .arg(flag_debug)
         .arg(flag_hex)
+        .arg(flag_dir)
+        .arg(flag_env)
         .arg(wasm_file_to_run)
         .arg(args_for_app);
 
@@ -62,6 +78,8 @@ fn main() -> io::Result<()> {
     let is_hex_format = matches.get_flag(FLAG_HEX);
     let start_arg_strings = matches.get_many::<String>(ARGS_FOR_APP).unwrap_or_default();
     let wasm_path = matches.get_one::<String>(WASM_FILE).unwrap();
+    let dirs = matches.get_many::<String>(FLAG_DIR).unwrap_or_default();
+    let inherit_env = matches.get_flag(FLAG_ENV);
     // WASI expects the .wasm file to be argv[0]
     let wasi_argv_iter = once(wasm_path)
         .chain(start_arg_strings)
@@ -87,7 +105,13 @@ fn main() -> io::Result<()> {
 
     // Create an execution instance
 
-    let dispatcher = DefaultImportDispatcher::new(&wasi_argv);
+    let mut dispatcher = DefaultImportDispatcher::new(&wasi_argv);
+    if inherit_env {
+        dispatcher.wasi.inherit_env();
+    }
+    for dir in dirs {
+        dispatcher.wasi.preopen_dir(dir, dir);
+    }
     let mut inst =
         Instance::for_module(&arena, &module, dispatcher, is_debug_mode).unwrap_or_else(|e| {
             eprintln!("{}", e);
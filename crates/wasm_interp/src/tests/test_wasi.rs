@@ -0,0 +1,382 @@
+use crate::wasi::{Errno, WasiDispatcher};
+use roc_wasm_module::Value;
+use std::fs;
+use std::path::PathBuf;
+
+// Addresses in the fake memory
+const PATH: i32 = 0x100;
+const DATA: i32 = 0x200;
+const IOVS: i32 = 0x300;
+const OUT: i32 = 0x400;
+const BUF: i32 = 0x800;
+
+const RIGHTS_READ_WRITE: i64 = (1 << 1) | (1 << 6);
+const OFLAGS_CREAT: i32 = 1;
+
+fn call(wasi: &mut WasiDispatcher, memory: &mut [u8], name: &str, args: &[Value]) -> i32 {
+    wasi.dispatch(name, args, memory)
+        .unwrap()
+        .expect_i32()
+        .unwrap()
+}
+
+fn read_u32(memory: &[u8], addr: i32) -> u32 {
+    u32::from_le_bytes(memory[addr as usize..][..4].try_into().unwrap())
+}
+
+fn read_u64(memory: &[u8], addr: i32) -> u64 {
+    u64::from_le_bytes(memory[addr as usize..][..8].try_into().unwrap())
+}
+
+fn write_str(memory: &mut [u8], addr: i32, s: &str) -> Value {
+    memory[addr as usize..][..s.len()].copy_from_slice(s.as_bytes());
+    Value::I32(s.len() as i32)
+}
+
+/// Set up a single IO vector pointing at `DATA`
+fn write_iov(memory: &mut [u8], len: i32) {
+    memory[IOVS as usize..][..4].copy_from_slice(&DATA.to_le_bytes());
+    memory[IOVS as usize + 4..][..4].copy_from_slice(&len.to_le_bytes());
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("roc_wasm_interp_{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn open(wasi: &mut WasiDispatcher, memory: &mut [u8], dirfd: u32, path: &str) -> i32 {
+    let path_len = write_str(memory, PATH, path);
+    call(
+        wasi,
+        memory,
+        "path_open",
+        &[
+            Value::I32(dirfd as i32),
+            Value::I32(0),
+            Value::I32(PATH),
+            path_len,
+            Value::I32(OFLAGS_CREAT),
+            Value::I64(RIGHTS_READ_WRITE),
+            Value::I64(RIGHTS_READ_WRITE),
+            Value::I32(0),
+            Value::I32(OUT),
+        ],
+    )
+}
+
+#[test]
+fn test_environ() {
+    let mut wasi = WasiDispatcher::default();
+    wasi.env = vec![
+        ("HOME".into(), "/home/roc".into()),
+        ("EMPTY".into(), "".into()),
+    ];
+    let mut memory = vec![0; 0x1000];
+
+    let errno = call(
+        &mut wasi,
+        &mut memory,
+        "environ_sizes_get",
+        &[Value::I32(OUT), Value::I32(OUT + 4)],
+    );
+    assert_eq!(errno, Errno::Success as i32);
+    assert_eq!(read_u32(&memory, OUT), 2);
+    assert_eq!(read_u32(&memory, OUT + 4), 22);
+
+    let errno = call(
+        &mut wasi,
+        &mut memory,
+        "environ_get",
+        &[Value::I32(OUT), Value::I32(BUF)],
+    );
+    assert_eq!(errno, Errno::Success as i32);
+    assert_eq!(read_u32(&memory, OUT), BUF as u32);
+    assert_eq!(read_u32(&memory, OUT + 4), BUF as u32 + 15);
+    assert_eq!(&memory[BUF as usize..][..22], b"HOME=/home/roc\0EMPTY=\0");
+}
+
+#[test]
+fn test_clocks() {
+    let mut wasi = WasiDispatcher::default();
+    let mut memory = vec![0; 0x1000];
+
+    let mut time = |wasi: &mut WasiDispatcher, clock_id| {
+        let errno = call(
+            wasi,
+            &mut memory,
+            "clock_time_get",
+            &[Value::I32(clock_id), Value::I64(1), Value::I32(OUT)],
+        );
+        assert_eq!(errno, Errno::Success as i32);
+        read_u64(&memory, OUT)
+    };
+
+    // Some time after 2020
+    assert!(time(&mut wasi, 0) > 1_577_836_800_000_000_000);
+
+    let before = time(&mut wasi, 1);
+    std::thread::sleep(std::time::Duration::from_millis(1));
+    let after = time(&mut wasi, 1);
+    assert!(after >= before + 1_000_000);
+
+    let errno = call(
+        &mut wasi,
+        &mut memory,
+        "clock_time_get",
+        &[Value::I32(99), Value::I64(1), Value::I32(OUT)],
+    );
+    assert_eq!(errno, Errno::Inval as i32);
+}
+
+#[test]
+fn test_file_round_trip() {
+    let dir = temp_dir("file_round_trip");
+    let mut wasi = WasiDispatcher::default();
+    let dirfd = wasi.preopen_dir("/", &dir);
+    let mut memory = vec![0; 0x1000];
+
+    assert_eq!(
+        open(&mut wasi, &mut memory, dirfd, "hello.txt"),
+        Errno::Success as i32
+    );
+    let fd = Value::I32(read_u32(&memory, OUT) as i32);
+
+    let len = write_str(&mut memory, DATA, "Hello, World!");
+    write_iov(&mut memory, len.expect_i32().unwrap());
+    let errno = call(
+        &mut wasi,
+        &mut memory,
+        "fd_write",
+        &[fd, Value::I32(IOVS), Value::I32(1), Value::I32(OUT)],
+    );
+    assert_eq!(errno, Errno::Success as i32);
+    assert_eq!(read_u32(&memory, OUT), 13);
+
+    let errno = call(
+        &mut wasi,
+        &mut memory,
+        "fd_seek",
+        &[fd, Value::I64(7), Value::I32(0), Value::I32(OUT)],
+    );
+    assert_eq!(errno, Errno::Success as i32);
+    assert_eq!(read_u64(&memory, OUT), 7);
+
+    memory[DATA as usize..][..16].fill(0);
+    write_iov(&mut memory, 16);
+    let errno = call(
+        &mut wasi,
+        &mut memory,
+        "fd_read",
+        &[fd, Value::I32(IOVS), Value::I32(1), Value::I32(OUT)],
+    );
+    assert_eq!(errno, Errno::Success as i32);
+    assert_eq!(read_u32(&memory, OUT), 6);
+    assert_eq!(&memory[DATA as usize..][..6], b"World!");
+
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_close", &[fd]),
+        Errno::Success as i32
+    );
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_close", &[fd]),
+        Errno::Badf as i32
+    );
+
+    assert_eq!(
+        fs::read_to_string(dir.join("hello.txt")).unwrap(),
+        "Hello, World!"
+    );
+}
+
+#[test]
+fn test_readdir() {
+    let dir = temp_dir("readdir");
+    fs::write(dir.join("b.txt"), "").unwrap();
+    fs::create_dir(dir.join("a")).unwrap();
+
+    let mut wasi = WasiDispatcher::default();
+    let dirfd = wasi.preopen_dir("/", &dir);
+    let mut memory = vec![0; 0x1000];
+
+    let errno = call(
+        &mut wasi,
+        &mut memory,
+        "fd_readdir",
+        &[
+            Value::I32(dirfd as i32),
+            Value::I32(BUF),
+            Value::I32(0x100),
+            Value::I64(0),
+            Value::I32(OUT),
+        ],
+    );
+    assert_eq!(errno, Errno::Success as i32);
+    assert_eq!(read_u32(&memory, OUT), 24 + 1 + 24 + 5);
+
+    // The entries are sorted by name
+    let first = BUF;
+    assert_eq!(read_u64(&memory, first), 1);
+    assert_eq!(read_u32(&memory, first + 16), 1);
+    assert_eq!(memory[first as usize + 20], 3); // directory
+    assert_eq!(&memory[first as usize + 24..][..1], b"a");
+
+    let second = first + 24 + 1;
+    assert_eq!(read_u64(&memory, second), 2);
+    assert_eq!(memory[second as usize + 20], 4); // regular file
+    assert_eq!(&memory[second as usize + 24..][..5], b"b.txt");
+
+    // Resume after the first entry
+    let errno = call(
+        &mut wasi,
+        &mut memory,
+        "fd_readdir",
+        &[
+            Value::I32(dirfd as i32),
+            Value::I32(BUF),
+            Value::I32(0x100),
+            Value::I64(1),
+            Value::I32(OUT),
+        ],
+    );
+    assert_eq!(errno, Errno::Success as i32);
+    assert_eq!(read_u32(&memory, OUT), 24 + 5);
+}
+
+#[test]
+fn test_path_cannot_escape_preopened_dir() {
+    let dir = temp_dir("escape");
+    fs::create_dir(dir.join("sub")).unwrap();
+
+    let mut wasi = WasiDispatcher::default();
+    let dirfd = wasi.preopen_dir("/", dir.join("sub"));
+    let mut memory = vec![0; 0x1000];
+
+    assert_eq!(
+        open(&mut wasi, &mut memory, dirfd, "../outside.txt"),
+        Errno::Notcapable as i32
+    );
+    assert_eq!(
+        open(&mut wasi, &mut memory, dirfd, "/etc/passwd"),
+        Errno::Notcapable as i32
+    );
+    assert!(!dir.join("outside.txt").exists());
+
+    // Going up is fine as long as we stay inside
+    assert_eq!(
+        open(&mut wasi, &mut memory, dirfd, "./x/../inside.txt"),
+        Errno::Success as i32
+    );
+    assert!(dir.join("sub").join("inside.txt").exists());
+}
+
+#[cfg(unix)]
+fn symlink(
+    wasi: &mut WasiDispatcher,
+    memory: &mut [u8],
+    target: &str,
+    dirfd: u32,
+    path: &str,
+) -> i32 {
+    let target_len = write_str(memory, DATA, target);
+    let path_len = write_str(memory, PATH, path);
+    call(
+        wasi,
+        memory,
+        "path_symlink",
+        &[
+            Value::I32(DATA),
+            target_len,
+            Value::I32(dirfd as i32),
+            Value::I32(PATH),
+            path_len,
+        ],
+    )
+}
+
+#[test]
+#[cfg(unix)]
+fn test_symlink_cannot_escape_preopened_dir() {
+    let dir = temp_dir("symlink_escape");
+    fs::create_dir(dir.join("sub")).unwrap();
+    fs::write(dir.join("secret.txt"), "secret").unwrap();
+
+    let mut wasi = WasiDispatcher::default();
+    let dirfd = wasi.preopen_dir("/", dir.join("sub"));
+    let mut memory = vec![0; 0x1000];
+
+    // The app can't make links that lead outside
+    for target in ["/etc/passwd", "../secret.txt", "x/../../secret.txt"] {
+        assert_eq!(
+            symlink(&mut wasi, &mut memory, target, dirfd, "link"),
+            Errno::Notcapable as i32,
+            "{}",
+            target
+        );
+        assert!(fs::symlink_metadata(dir.join("sub").join("link")).is_err());
+    }
+
+    // ...nor follow the ones that are already there
+    std::os::unix::fs::symlink(dir.join("secret.txt"), dir.join("sub").join("to_file")).unwrap();
+    std::os::unix::fs::symlink(&dir, dir.join("sub").join("to_dir")).unwrap();
+    std::os::unix::fs::symlink("../new.txt", dir.join("sub").join("dangling")).unwrap();
+
+    for path in ["to_file", "to_dir/secret.txt", "to_dir/new.txt", "dangling"] {
+        assert_eq!(
+            open(&mut wasi, &mut memory, dirfd, path),
+            Errno::Notcapable as i32,
+            "{}",
+            path
+        );
+    }
+    assert!(!dir.join("new.txt").exists());
+
+    // Links that stay inside are fine
+    fs::write(dir.join("sub").join("inside.txt"), "inside").unwrap();
+    assert_eq!(
+        symlink(&mut wasi, &mut memory, "inside.txt", dirfd, "link"),
+        Errno::Success as i32
+    );
+    assert_eq!(
+        open(&mut wasi, &mut memory, dirfd, "link"),
+        Errno::Success as i32
+    );
+}
+
+#[test]
+fn test_prestat() {
+    let mut wasi = WasiDispatcher::default();
+    let dirfd = wasi.preopen_dir("/data", std::env::temp_dir());
+    let mut memory = vec![0; 0x1000];
+
+    assert_eq!(dirfd, 3);
+
+    let errno = call(
+        &mut wasi,
+        &mut memory,
+        "fd_prestat_get",
+        &[Value::I32(3), Value::I32(OUT)],
+    );
+    assert_eq!(errno, Errno::Success as i32);
+    assert_eq!(read_u32(&memory, OUT), 0);
+    assert_eq!(read_u32(&memory, OUT + 4), 5);
+
+    let errno = call(
+        &mut wasi,
+        &mut memory,
+        "fd_prestat_dir_name",
+        &[Value::I32(3), Value::I32(BUF), Value::I32(5)],
+    );
+    assert_eq!(errno, Errno::Success as i32);
+    assert_eq!(&memory[BUF as usize..][..5], b"/data");
+
+    // WASI libc stops looking for preopened directories when it gets this error
+    let errno = call(
+        &mut wasi,
+        &mut memory,
+        "fd_prestat_get",
+        &[Value::I32(4), Value::I32(OUT)],
+    );
+    assert_eq!(errno, Errno::Badf as i32);
+}
@@ -1,14 +1,21 @@
 use rand::prelude::*;
 use roc_wasm_module::Value;
-use std::io::{self, Read, StderrLock, StdoutLock, Write};
+use std::fs::{self, File, Metadata, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, StderrLock, StdoutLock, Write};
+use std::path::{Component, Path, PathBuf};
 use std::process::exit;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 pub const MODULE_NAME: &str = "wasi_snapshot_preview1";
 
 pub struct WasiDispatcher<'a> {
     pub args: &'a [&'a [u8]],
+    /// Environment variables visible to the app, as (name, value) pairs
+    pub env: Vec<(String, String)>,
     pub rng: ThreadRng,
     pub files: Vec<WasiFile>,
+    /// The origin of the monotonic clock
+    start_time: Instant,
 }
 
 impl Default for WasiDispatcher<'_> {
@@ -17,19 +24,59 @@ impl Default for WasiDispatcher<'_> {
     }
 }
 
+/// Something a WASI file descriptor refers to.
+/// The in-memory files behave like pipes: reads consume bytes from the front, writes append.
 pub enum WasiFile {
     ReadOnly(Vec<u8>),
     WriteOnly(Vec<u8>),
     ReadWrite(Vec<u8>),
     HostSystemFile,
+    /// A file on the host, opened by the app with `path_open`
+    HostFile(File),
+    /// A directory on the host, opened by the app with `path_open`
+    HostDir(PathBuf),
+    /// A host directory that the app can open paths in, known to the app as `guest_path`
+    PreopenedDir {
+        guest_path: String,
+        host_path: PathBuf,
+    },
+    /// A closed file descriptor, which can be reused
+    Closed,
 }
 
 enum WriteLock<'a> {
     StdOut(StdoutLock<'a>),
     Stderr(StderrLock<'a>),
     RegularFile(&'a mut Vec<u8>),
+    HostFile(&'a mut File),
 }
 
+const FILETYPE_UNKNOWN: u8 = 0;
+const FILETYPE_CHARACTER_DEVICE: u8 = 2;
+const FILETYPE_DIRECTORY: u8 = 3;
+const FILETYPE_REGULAR_FILE: u8 = 4;
+const FILETYPE_SYMBOLIC_LINK: u8 = 7;
+
+const CLOCK_REALTIME: i32 = 0;
+const CLOCK_MONOTONIC: i32 = 1;
+const CLOCK_PROCESS_CPUTIME: i32 = 2;
+const CLOCK_THREAD_CPUTIME: i32 = 3;
+
+/// We don't enforce rights, so we grant all of them
+const ALL_RIGHTS: u64 = (1 << 29) - 1;
+const RIGHT_FD_READ: u64 = 1 << 1;
+const RIGHT_FD_WRITE: u64 = 1 << 6;
+
+const OFLAGS_CREAT: i32 = 1 << 0;
+const OFLAGS_DIRECTORY: i32 = 1 << 1;
+const OFLAGS_EXCL: i32 = 1 << 2;
+const OFLAGS_TRUNC: i32 = 1 << 3;
+const FDFLAGS_APPEND: i32 = 1 << 0;
+const LOOKUPFLAGS_SYMLINK_FOLLOW: i32 = 1 << 0;
+
+const EVENTTYPE_CLOCK: u8 = 0;
+const SUBCLOCKFLAGS_ABSTIME: u16 = 1 << 0;
+
 /// Implementation of WASI syscalls
 /// References for other engines:
 /// https://github.com/wasmerio/wasmer/blob/ef8d2f651ed29b4b06fdc2070eb8189922c54d82/lib/wasi/src/syscalls/mod.rs
@@ -38,12 +85,120 @@ impl<'a> WasiDispatcher<'a> {
     pub fn new(args: &'a [&'a [u8]]) -> Self {
         WasiDispatcher {
             args,
+            env: Vec::new(),
             rng: thread_rng(),
             files: vec![
                 WasiFile::HostSystemFile,
                 WasiFile::HostSystemFile,
                 WasiFile::HostSystemFile,
             ],
+            start_time: Instant::now(),
+        }
+    }
+
+    /// Give the app access to a directory on the host, under the name `guest_path`.
+    /// Returns the file descriptor of the directory.
+    pub fn preopen_dir(&mut self, guest_path: &str, host_path: impl Into<PathBuf>) -> u32 {
+        self.insert_file(WasiFile::PreopenedDir {
+            guest_path: guest_path.to_string(),
+            host_path: host_path.into(),
+        })
+    }
+
+    /// Pass the host's environment variables through to the app.
+    /// Variables that aren't valid Unicode are skipped.
+    pub fn inherit_env(&mut self) {
+        let vars = std::env::vars_os().filter_map(|(name, value)| {
+            Some((name.into_string().ok()?, value.into_string().ok()?))
+        });
+
+        self.env.extend(vars);
+    }
+
+    /// Use the lowest closed file descriptor, like POSIX does
+    fn insert_file(&mut self, file: WasiFile) -> u32 {
+        match self
+            .files
+            .iter()
+            .position(|f| matches!(f, WasiFile::Closed))
+        {
+            Some(fd) => {
+                self.files[fd] = file;
+                fd as u32
+            }
+            None => {
+                self.files.push(file);
+                (self.files.len() - 1) as u32
+            }
+        }
+    }
+
+    /// Find the host path for a path the app gave us relative to one of its directories.
+    /// Paths that would escape from that directory are not allowed, and neither are paths that
+    /// lead out of the preopened directories through a symlink.
+    fn resolve_path(&self, dirfd: usize, path: &[u8]) -> Result<PathBuf, Errno> {
+        let dir = match self.files.get(dirfd) {
+            Some(WasiFile::HostDir(dir) | WasiFile::PreopenedDir { host_path: dir, .. }) => dir,
+            Some(WasiFile::Closed) | None => return Err(Errno::Badf),
+            Some(_) => return Err(Errno::Notdir),
+        };
+        let path = std::str::from_utf8(path).map_err(|_| Errno::Ilseq)?;
+
+        let mut resolved = dir.clone();
+        let mut depth = 0;
+        for component in Path::new(path).components() {
+            match component {
+                Component::Normal(name) => {
+                    resolved.push(name);
+                    depth += 1;
+                }
+                Component::CurDir => {}
+                Component::ParentDir if depth > 0 => {
+                    resolved.pop();
+                    depth -= 1;
+                }
+                Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                    return Err(Errno::Notcapable);
+                }
+            }
+        }
+
+        self.check_sandboxed(&resolved)?;
+
+        Ok(resolved)
+    }
+
+    /// Check that a host path really is inside one of the preopened directories, once symlinks
+    /// are followed. Directories opened with `path_open` are inside them already.
+    fn check_sandboxed(&self, path: &Path) -> Result<(), Errno> {
+        let real = real_path(path, MAX_SYMLINKS)?;
+        let is_inside = self.files.iter().any(|file| match file {
+            WasiFile::PreopenedDir { host_path, .. } => fs::canonicalize(host_path)
+                .map(|root| real.starts_with(root))
+                .unwrap_or(false),
+            _ => false,
+        });
+
+        if is_inside {
+            Ok(())
+        } else {
+            Err(Errno::Notcapable)
+        }
+    }
+
+    fn clock_time(&self, clock_id: i32) -> Option<u64> {
+        match clock_id {
+            CLOCK_REALTIME => {
+                let since_epoch = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default();
+                Some(since_epoch.as_nanos() as u64)
+            }
+            // We don't track CPU time, so the CPU clocks are approximated by the monotonic clock
+            CLOCK_MONOTONIC | CLOCK_PROCESS_CPUTIME | CLOCK_THREAD_CPUTIME => {
+                Some(self.start_time.elapsed().as_nanos() as u64)
+            }
+            _ => None,
         }
     }
 
@@ -88,14 +243,118 @@ impl<'a> WasiDispatcher<'a> {
 
                 success_code
             }
-            "environ_get" => todo!("WASI {}({:?})", function_name, arguments),
-            "environ_sizes_get" => todo!("WASI {}({:?})", function_name, arguments),
-            "clock_res_get" => success_code, // this dummy implementation seems to be good enough for some functions
-            "clock_time_get" => success_code,
-            "fd_advise" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_allocate" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_close" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_datasync" => todo!("WASI {}({:?})", function_name, arguments),
+            "environ_get" => {
+                // uint8_t ** environ
+                let mut ptr_ptr_environ = arguments[0].expect_i32().unwrap() as usize;
+                // uint8_t * environ_buf, holding "NAME=value" C strings
+                let mut ptr_environ_buf = arguments[1].expect_i32().unwrap() as usize;
+
+                for (name, value) in self.env.iter() {
+                    write_u32(memory, ptr_ptr_environ, ptr_environ_buf as u32);
+                    for bytes in [name.as_bytes(), b"=", value.as_bytes()] {
+                        memory[ptr_environ_buf..][..bytes.len()].copy_from_slice(bytes);
+                        ptr_environ_buf += bytes.len();
+                    }
+                    memory[ptr_environ_buf] = 0; // C string zero termination
+                    ptr_environ_buf += 1;
+                    ptr_ptr_environ += 4;
+                }
+
+                success_code
+            }
+            "environ_sizes_get" => {
+                // number of environment variables
+                let ptr_count = arguments[0].expect_i32().unwrap() as usize;
+                // size of environment variables buffer
+                let ptr_buf_size = arguments[1].expect_i32().unwrap() as usize;
+
+                write_u32(memory, ptr_count, self.env.len() as u32);
+
+                let buf_size: usize = self
+                    .env
+                    .iter()
+                    .map(|(name, value)| name.len() + value.len() + 2)
+                    .sum();
+                write_u32(memory, ptr_buf_size, buf_size as u32);
+
+                success_code
+            }
+            "clock_res_get" => {
+                let clock_id = arguments[0].expect_i32().unwrap();
+                // Out param: the resolution in nanoseconds
+                let ptr_resolution = arguments[1].expect_i32().unwrap() as usize;
+
+                match self.clock_time(clock_id) {
+                    Some(_) => {
+                        write_u64(memory, ptr_resolution, 1);
+                        success_code
+                    }
+                    None => Some(Value::I32(Errno::Inval as i32)),
+                }
+            }
+            "clock_time_get" => {
+                let clock_id = arguments[0].expect_i32().unwrap();
+                // arguments[1] is the maximum lag we'd accept, but we're always as precise as we can be
+                // Out param: the time in nanoseconds
+                let ptr_time = arguments[2].expect_i32().unwrap() as usize;
+
+                match self.clock_time(clock_id) {
+                    Some(time) => {
+                        write_u64(memory, ptr_time, time);
+                        success_code
+                    }
+                    None => Some(Value::I32(Errno::Inval as i32)),
+                }
+            }
+            "fd_advise" => {
+                // This is only a hint about how the app will access the file, so we can ignore it
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                match self.files.get(fd) {
+                    Some(WasiFile::Closed) | None => Some(Value::I32(Errno::Badf as i32)),
+                    Some(_) => success_code,
+                }
+            }
+            "fd_allocate" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let offset = arguments[1].expect_i64().unwrap() as u64;
+                let len = arguments[2].expect_i64().unwrap() as u64;
+
+                let result = match self.files.get(fd) {
+                    Some(WasiFile::HostFile(file)) => file.metadata().and_then(|metadata| {
+                        if metadata.len() < offset + len {
+                            file.set_len(offset + len)
+                        } else {
+                            Ok(())
+                        }
+                    }),
+                    _ => return Some(Value::I32(Errno::Badf as i32)),
+                };
+
+                errno_value(result.map_err(Errno::from))
+            }
+            "fd_close" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                match self.files.get_mut(fd) {
+                    Some(WasiFile::Closed) | None => Some(Value::I32(Errno::Badf as i32)),
+                    Some(file) => {
+                        *file = WasiFile::Closed;
+                        success_code
+                    }
+                }
+            }
+            "fd_datasync" | "fd_sync" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let result = match self.files.get(fd) {
+                    Some(WasiFile::HostFile(file)) if function_name == "fd_datasync" => {
+                        file.sync_data()
+                    }
+                    Some(WasiFile::HostFile(file)) => file.sync_all(),
+                    Some(WasiFile::Closed) | None => return Some(Value::I32(Errno::Badf as i32)),
+                    Some(_) => Ok(()),
+                };
+
+                errno_value(result.map_err(Errno::from))
+            }
             "fd_fdstat_get" => {
                 // (i32, i32) -> i32
 
@@ -104,9 +363,10 @@ impl<'a> WasiDispatcher<'a> {
                 // ptr to a wasi_fdstat_t
                 let stat_mut_ptr = arguments[1].expect_i32().unwrap() as usize;
 
-                match fd {
-                    1 => {
-                        // Tell WASI that stdout is a tty (no seek or tell)
+                let (filetype, rights) = match self.files.get(fd) {
+                    Some(WasiFile::Closed) | None => return Some(Value::I32(Errno::Badf as i32)),
+                    Some(_) if fd <= 2 => {
+                        // Tell WASI that stdio is a tty (no seek or tell), even if we're faking it with a Vec
                         // https://github.com/WebAssembly/wasi-libc/blob/659ff414560721b1660a19685110e484a081c3d4/libc-bottom-half/sources/isatty.c
                         // *Not* a tty if:
                         //     (statbuf.fs_filetype != __WASI_FILETYPE_CHARACTER_DEVICE ||
@@ -114,25 +374,112 @@ impl<'a> WasiDispatcher<'a> {
                         // So it's sufficient to set:
                         //     .fs_filetype = __WASI_FILETYPE_CHARACTER_DEVICE
                         //     .fs_rights_base = 0
+                        (FILETYPE_CHARACTER_DEVICE, 0)
+                    }
+                    Some(WasiFile::HostSystemFile) => (FILETYPE_CHARACTER_DEVICE, 0),
+                    Some(WasiFile::HostDir(_) | WasiFile::PreopenedDir { .. }) => {
+                        (FILETYPE_DIRECTORY, ALL_RIGHTS)
+                    }
+                    Some(
+                        WasiFile::HostFile(_)
+                        | WasiFile::ReadOnly(_)
+                        | WasiFile::WriteOnly(_)
+                        | WasiFile::ReadWrite(_),
+                    ) => (FILETYPE_REGULAR_FILE, ALL_RIGHTS),
+                };
 
-                        const WASI_FILETYPE_CHARACTER_DEVICE: u8 = 2;
-                        memory[stat_mut_ptr] = WASI_FILETYPE_CHARACTER_DEVICE;
+                // struct fdstat { u8 filetype; u16 flags; u64 rights_base; u64 rights_inheriting; }
+                memory[stat_mut_ptr..][..24].fill(0);
+                memory[stat_mut_ptr] = filetype;
+                write_u64(memory, stat_mut_ptr + 8, rights);
+                write_u64(memory, stat_mut_ptr + 16, rights);
 
-                        for b in memory[stat_mut_ptr + 1..stat_mut_ptr + 24].iter_mut() {
-                            *b = 0;
-                        }
+                success_code
+            }
+            "fd_fdstat_set_flags" => Some(Value::I32(Errno::Nosys as i32)),
+            "fd_fdstat_set_rights" => {
+                // We don't enforce rights, so there's nothing to restrict
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                match self.files.get(fd) {
+                    Some(WasiFile::Closed) | None => Some(Value::I32(Errno::Badf as i32)),
+                    Some(_) => success_code,
+                }
+            }
+            "fd_filestat_get" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                // ptr to a wasi_filestat_t
+                let ptr_buf = arguments[1].expect_i32().unwrap() as usize;
+
+                let result = match self.files.get(fd) {
+                    Some(WasiFile::HostFile(file)) => file.metadata().map(|m| Filestat::from(&m)),
+                    Some(
+                        WasiFile::HostDir(path)
+                        | WasiFile::PreopenedDir {
+                            host_path: path, ..
+                        },
+                    ) => fs::metadata(path).map(|m| Filestat::from(&m)),
+                    Some(
+                        WasiFile::ReadOnly(content)
+                        | WasiFile::WriteOnly(content)
+                        | WasiFile::ReadWrite(content),
+                    ) => Ok(Filestat {
+                        filetype: FILETYPE_REGULAR_FILE,
+                        size: content.len() as u64,
+                        ..Default::default()
+                    }),
+                    Some(WasiFile::HostSystemFile) => Ok(Filestat {
+                        filetype: FILETYPE_CHARACTER_DEVICE,
+                        ..Default::default()
+                    }),
+                    Some(WasiFile::Closed) | None => return Some(Value::I32(Errno::Badf as i32)),
+                };
+
+                errno_value(
+                    result
+                        .map(|stat| stat.write(memory, ptr_buf))
+                        .map_err(Errno::from),
+                )
+            }
+            "fd_filestat_set_size" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let size = arguments[1].expect_i64().unwrap() as u64;
+
+                match self.files.get(fd) {
+                    Some(WasiFile::HostFile(file)) => {
+                        errno_value(file.set_len(size).map_err(Errno::from))
                     }
-                    _ => todo!("WASI {}({:?})", function_name, arguments),
+                    _ => Some(Value::I32(Errno::Badf as i32)),
                 }
+            }
+            "fd_filestat_set_times" => Some(Value::I32(Errno::Nosys as i32)),
+            "fd_pread" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let ptr_iovs = arguments[1].expect_i32().unwrap() as usize;
+                let iovs_len = arguments[2].expect_i32().unwrap() as usize;
+                let offset = arguments[3].expect_i64().unwrap() as u64;
+                // Out param: number of bytes read
+                let ptr_nread = arguments[4].expect_i32().unwrap() as usize;
 
-                success_code
+                let file = match self.files.get_mut(fd) {
+                    Some(WasiFile::HostFile(file)) => file,
+                    Some(WasiFile::Closed) | None => return Some(Value::I32(Errno::Badf as i32)),
+                    Some(_) => return Some(Value::I32(Errno::Spipe as i32)),
+                };
+
+                // Reading at an offset must not move the file position
+                let result = file.stream_position().and_then(|position| {
+                    file.seek(SeekFrom::Start(offset))?;
+                    let n_read = read_iovs(memory, ptr_iovs, iovs_len, |buf| file.read(buf));
+                    file.seek(SeekFrom::Start(position))?;
+                    n_read
+                });
+
+                errno_value(
+                    result
+                        .map(|n_read| write_u32(memory, ptr_nread, n_read as u32))
+                        .map_err(Errno::from),
+                )
             }
-            "fd_fdstat_set_flags" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_fdstat_set_rights" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_get" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_set_size" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_set_times" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_pread" => todo!("WASI {}({:?})", function_name, arguments),
             "fd_prestat_get" => {
                 // The preopened file descriptor to query
                 let fd = arguments[0].expect_i32().unwrap() as usize;
@@ -140,19 +487,60 @@ impl<'a> WasiDispatcher<'a> {
                 //  preopen type: 4 bytes, where 0=dir is the only one supported, it seems
                 //  preopen name length: 4 bytes
                 let ptr_buf = arguments[1].expect_i32().unwrap() as usize;
-                memory[ptr_buf..][..8].copy_from_slice(&0u64.to_le_bytes());
-                if fd < self.files.len() {
-                    success_code
-                } else {
-                    println!("WASI warning: file descriptor {} does not exist", fd);
-                    Some(Value::I32(Errno::Badf as i32))
+
+                // WASI libc asks about every file descriptor from 3 upwards, until it gets `Badf`
+                match self.files.get(fd) {
+                    Some(WasiFile::PreopenedDir { guest_path, .. }) => {
+                        write_u32(memory, ptr_buf, 0);
+                        write_u32(memory, ptr_buf + 4, guest_path.len() as u32);
+                        success_code
+                    }
+                    _ => Some(Value::I32(Errno::Badf as i32)),
                 }
             }
             "fd_prestat_dir_name" => {
-                // We're not giving names to any of our files so just return success
-                success_code
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let ptr_path = arguments[1].expect_i32().unwrap() as usize;
+                let path_len = arguments[2].expect_i32().unwrap() as usize;
+
+                match self.files.get(fd) {
+                    Some(WasiFile::PreopenedDir { guest_path, .. }) => {
+                        let len = path_len.min(guest_path.len());
+                        memory[ptr_path..][..len].copy_from_slice(&guest_path.as_bytes()[..len]);
+                        success_code
+                    }
+                    _ => Some(Value::I32(Errno::Badf as i32)),
+                }
+            }
+            "fd_pwrite" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let ptr_iovs = arguments[1].expect_i32().unwrap() as usize;
+                let iovs_len = arguments[2].expect_i32().unwrap() as usize;
+                let offset = arguments[3].expect_i64().unwrap() as u64;
+                // Out param: number of bytes written
+                let ptr_nwritten = arguments[4].expect_i32().unwrap() as usize;
+
+                let file = match self.files.get_mut(fd) {
+                    Some(WasiFile::HostFile(file)) => file,
+                    Some(WasiFile::Closed) | None => return Some(Value::I32(Errno::Badf as i32)),
+                    Some(_) => return Some(Value::I32(Errno::Spipe as i32)),
+                };
+
+                // Writing at an offset must not move the file position
+                let result = file.stream_position().and_then(|position| {
+                    file.seek(SeekFrom::Start(offset))?;
+                    let n_written =
+                        write_iovs(memory, ptr_iovs, iovs_len, |bytes| file.write_all(bytes));
+                    file.seek(SeekFrom::Start(position))?;
+                    n_written
+                });
+
+                errno_value(
+                    result
+                        .map(|n_written| write_u32(memory, ptr_nwritten, n_written as u32))
+                        .map_err(Errno::from),
+                )
             }
-            "fd_pwrite" => todo!("WASI {}({:?})", function_name, arguments),
             "fd_read" => {
                 use WasiFile::*;
 
@@ -161,57 +549,145 @@ impl<'a> WasiDispatcher<'a> {
                 // Array of IO vectors
                 let ptr_iovs = arguments[1].expect_i32().unwrap() as usize;
                 // Length of array
-                let iovs_len = arguments[2].expect_i32().unwrap();
+                let iovs_len = arguments[2].expect_i32().unwrap() as usize;
                 // Out param: number of bytes read
                 let ptr_nread = arguments[3].expect_i32().unwrap() as usize;
 
-                // https://man7.org/linux/man-pages/man2/readv.2.html
-                // struct iovec {
-                //     void  *iov_base;    /* Starting address */
-                //     size_t iov_len;     /* Number of bytes to transfer */
-                // };
-
-                let mut n_read: usize = 0;
-                match self.files.get(fd) {
+                let result = match self.files.get_mut(fd) {
                     Some(ReadOnly(content) | ReadWrite(content)) => {
-                        for _ in 0..iovs_len {
-                            let iov_base = read_u32(memory, ptr_iovs) as usize;
-                            let iov_len = read_i32(memory, ptr_iovs + 4) as usize;
-                            let remaining = content.len() - n_read;
-                            let len = remaining.min(iov_len);
-                            if len == 0 {
-                                break;
-                            }
-                            memory[iov_base..][..len].copy_from_slice(&content[n_read..][..len]);
-                            n_read += len;
-                        }
+                        read_iovs(memory, ptr_iovs, iovs_len, |buf| {
+                            let len = buf.len().min(content.len());
+                            buf[..len].copy_from_slice(&content[..len]);
+                            content.drain(..len);
+                            Ok(len)
+                        })
                     }
                     Some(HostSystemFile) if fd == 0 => {
-                        let mut stdin = io::stdin();
-                        for _ in 0..iovs_len {
-                            let iov_base = read_u32(memory, ptr_iovs) as usize;
-                            let iov_len = read_i32(memory, ptr_iovs + 4) as usize;
-                            match stdin.read(&mut memory[iov_base..][..iov_len]) {
-                                Ok(n) => {
-                                    n_read += n;
-                                }
-                                Err(_) => {
-                                    break;
-                                }
-                            }
-                        }
+                        let mut stdin = io::stdin().lock();
+                        read_iovs(memory, ptr_iovs, iovs_len, |buf| stdin.read(buf))
+                    }
+                    Some(HostFile(file)) => {
+                        read_iovs(memory, ptr_iovs, iovs_len, |buf| file.read(buf))
+                    }
+                    Some(HostDir(_) | PreopenedDir { .. }) => {
+                        return Some(Value::I32(Errno::Isdir as i32))
                     }
                     _ => return Some(Value::I32(Errno::Badf as i32)),
                 };
 
-                memory[ptr_nread..][..4].copy_from_slice(&(n_read as u32).to_le_bytes());
+                errno_value(
+                    result
+                        .map(|n_read| write_u32(memory, ptr_nread, n_read as u32))
+                        .map_err(Errno::from),
+                )
+            }
+            "fd_readdir" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                // Where to write the directory entries
+                let ptr_buf = arguments[1].expect_i32().unwrap() as usize;
+                let buf_len = arguments[2].expect_i32().unwrap() as usize;
+                // The index of the first entry to write, i.e. the `d_next` of the last one we've seen
+                let cookie = arguments[3].expect_i64().unwrap() as usize;
+                // Out param: number of bytes written. If it's `buf_len`, there may be more entries.
+                let ptr_bufused = arguments[4].expect_i32().unwrap() as usize;
+
+                let path = match self.files.get(fd) {
+                    Some(
+                        WasiFile::HostDir(path)
+                        | WasiFile::PreopenedDir {
+                            host_path: path, ..
+                        },
+                    ) => path,
+                    Some(WasiFile::Closed) | None => return Some(Value::I32(Errno::Badf as i32)),
+                    Some(_) => return Some(Value::I32(Errno::Notdir as i32)),
+                };
+
+                let mut entries =
+                    match fs::read_dir(path).and_then(|dir| dir.collect::<io::Result<Vec<_>>>()) {
+                        Ok(entries) => entries,
+                        Err(e) => return Some(Value::I32(Errno::from(e) as i32)),
+                    };
+
+                // Keep the order stable between calls, so cookies stay valid
+                entries.sort_by_key(|entry| entry.file_name());
+
+                let mut bufused = 0;
+                for (index, entry) in entries.iter().enumerate().skip(cookie) {
+                    let name = entry.file_name();
+                    let name = name.to_string_lossy();
+                    let filetype = entry
+                        .file_type()
+                        .map(filetype_from)
+                        .unwrap_or(FILETYPE_UNKNOWN);
+
+                    // struct dirent { u64 d_next; u64 d_ino; u32 d_namlen; u8 d_type; }
+                    let mut dirent = [0; 24];
+                    dirent[0..8].copy_from_slice(&(index as u64 + 1).to_le_bytes());
+                    dirent[16..20].copy_from_slice(&(name.len() as u32).to_le_bytes());
+                    dirent[20] = filetype;
+
+                    // The last entry gets truncated if it doesn't fit
+                    for bytes in [&dirent[..], name.as_bytes()] {
+                        let len = bytes.len().min(buf_len - bufused);
+                        memory[ptr_buf + bufused..][..len].copy_from_slice(&bytes[..len]);
+                        bufused += len;
+                    }
+
+                    if bufused == buf_len {
+                        break;
+                    }
+                }
+
+                write_u32(memory, ptr_bufused, bufused as u32);
                 success_code
             }
-            "fd_readdir" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_renumber" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_seek" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_sync" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_tell" => todo!("WASI {}({:?})", function_name, arguments),
+            "fd_renumber" => {
+                let from = arguments[0].expect_i32().unwrap() as usize;
+                let to = arguments[1].expect_i32().unwrap() as usize;
+
+                let is_open =
+                    |fd: usize| !matches!(self.files.get(fd), Some(WasiFile::Closed) | None);
+                if !is_open(from) || !is_open(to) {
+                    return Some(Value::I32(Errno::Badf as i32));
+                }
+
+                if from != to {
+                    self.files[to] = std::mem::replace(&mut self.files[from], WasiFile::Closed);
+                }
+
+                success_code
+            }
+            "fd_seek" | "fd_tell" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+
+                let (position, ptr_new_offset) = if function_name == "fd_seek" {
+                    let offset = arguments[1].expect_i64().unwrap();
+                    let whence = arguments[2].expect_i32().unwrap();
+                    let position = match whence {
+                        0 => SeekFrom::Start(offset as u64),
+                        1 => SeekFrom::Current(offset),
+                        2 => SeekFrom::End(offset),
+                        _ => return Some(Value::I32(Errno::Inval as i32)),
+                    };
+                    (position, arguments[3].expect_i32().unwrap() as usize)
+                } else {
+                    (
+                        SeekFrom::Current(0),
+                        arguments[1].expect_i32().unwrap() as usize,
+                    )
+                };
+
+                match self.files.get_mut(fd) {
+                    Some(WasiFile::HostFile(file)) => errno_value(
+                        file.seek(position)
+                            .map(|new_offset| write_u64(memory, ptr_new_offset, new_offset))
+                            .map_err(Errno::from),
+                    ),
+                    Some(WasiFile::Closed) | None => Some(Value::I32(Errno::Badf as i32)),
+                    // stdio and in-memory files are streams
+                    Some(_) => Some(Value::I32(Errno::Spipe as i32)),
+                }
+            }
             "fd_write" => {
                 use WasiFile::*;
 
@@ -235,6 +711,7 @@ impl<'a> WasiDispatcher<'a> {
                     Some(WriteOnly(content) | ReadWrite(content)) => {
                         WriteLock::RegularFile(content)
                     }
+                    Some(HostFile(file)) => WriteLock::HostFile(file),
                     _ => return Some(Value::I32(Errno::Badf as i32)),
                 };
 
@@ -263,6 +740,7 @@ impl<'a> WasiDispatcher<'a> {
                         WriteLock::StdOut(stdout) => stdout.write_all(bytes),
                         WriteLock::Stderr(stderr) => stderr.write_all(bytes),
                         WriteLock::RegularFile(content) => content.write_all(bytes),
+                        WriteLock::HostFile(file) => file.write_all(bytes),
                     };
                     if write_result.is_err() {
                         break;
@@ -284,23 +762,169 @@ impl<'a> WasiDispatcher<'a> {
                     Err(_) => Some(Value::I32(Errno::Io as i32)),
                 }
             }
-            "path_create_directory" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_filestat_get" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_filestat_set_times" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_link" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_open" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_readlink" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_remove_directory" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_rename" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_symlink" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_unlink_file" => todo!("WASI {}({:?})", function_name, arguments),
-            "poll_oneoff" => todo!("WASI {}({:?})", function_name, arguments),
+            "path_create_directory" => {
+                let dirfd = arguments[0].expect_i32().unwrap() as usize;
+                let path = read_bytes(memory, &arguments[1], &arguments[2]);
+
+                let result = self
+                    .resolve_path(dirfd, path)
+                    .and_then(|path| fs::create_dir(path).map_err(Errno::from));
+
+                errno_value(result)
+            }
+            "path_filestat_get" => {
+                let dirfd = arguments[0].expect_i32().unwrap() as usize;
+                let lookup_flags = arguments[1].expect_i32().unwrap();
+                let path = read_bytes(memory, &arguments[2], &arguments[3]);
+                // ptr to a wasi_filestat_t
+                let ptr_buf = arguments[4].expect_i32().unwrap() as usize;
+
+                let result = self.resolve_path(dirfd, path).and_then(|path| {
+                    let metadata = if lookup_flags & LOOKUPFLAGS_SYMLINK_FOLLOW != 0 {
+                        fs::metadata(path)
+                    } else {
+                        fs::symlink_metadata(path)
+                    };
+                    metadata.map(|m| Filestat::from(&m)).map_err(Errno::from)
+                });
+
+                errno_value(result.map(|stat| stat.write(memory, ptr_buf)))
+            }
+            "path_filestat_set_times" => Some(Value::I32(Errno::Nosys as i32)),
+            "path_link" => {
+                let old_dirfd = arguments[0].expect_i32().unwrap() as usize;
+                // arguments[1] holds lookup flags for the old path, which std doesn't let us control
+                let old_path = read_bytes(memory, &arguments[2], &arguments[3]);
+                let new_dirfd = arguments[4].expect_i32().unwrap() as usize;
+                let new_path = read_bytes(memory, &arguments[5], &arguments[6]);
+
+                let result = self.resolve_path(old_dirfd, old_path).and_then(|old_path| {
+                    let new_path = self.resolve_path(new_dirfd, new_path)?;
+                    fs::hard_link(old_path, new_path).map_err(Errno::from)
+                });
+
+                errno_value(result)
+            }
+            "path_open" => {
+                let dirfd = arguments[0].expect_i32().unwrap() as usize;
+                // arguments[1] holds lookup flags, which std doesn't let us control
+                let path = read_bytes(memory, &arguments[2], &arguments[3]);
+                let oflags = arguments[4].expect_i32().unwrap();
+                let rights = arguments[5].expect_i64().unwrap() as u64;
+                // arguments[6] holds the rights for files opened through this one, which we don't enforce
+                let fdflags = arguments[7].expect_i32().unwrap();
+                // Out param: the new file descriptor
+                let ptr_fd = arguments[8].expect_i32().unwrap() as usize;
+
+                let result = self
+                    .resolve_path(dirfd, path)
+                    .and_then(|path| open_path(&path, oflags, rights, fdflags))
+                    .map(|file| self.insert_file(file));
+
+                errno_value(result.map(|fd| write_u32(memory, ptr_fd, fd)))
+            }
+            "path_readlink" => {
+                let dirfd = arguments[0].expect_i32().unwrap() as usize;
+                let path = read_bytes(memory, &arguments[1], &arguments[2]);
+                let ptr_buf = arguments[3].expect_i32().unwrap() as usize;
+                let buf_len = arguments[4].expect_i32().unwrap() as usize;
+                // Out param: number of bytes written
+                let ptr_bufused = arguments[5].expect_i32().unwrap() as usize;
+
+                let result = self
+                    .resolve_path(dirfd, path)
+                    .and_then(|path| fs::read_link(path).map_err(Errno::from));
+
+                errno_value(result.map(|target| {
+                    let target = target.to_string_lossy();
+                    let len = buf_len.min(target.len());
+                    memory[ptr_buf..][..len].copy_from_slice(&target.as_bytes()[..len]);
+                    write_u32(memory, ptr_bufused, len as u32);
+                }))
+            }
+            "path_remove_directory" => {
+                let dirfd = arguments[0].expect_i32().unwrap() as usize;
+                let path = read_bytes(memory, &arguments[1], &arguments[2]);
+
+                let result = self
+                    .resolve_path(dirfd, path)
+                    .and_then(|path| fs::remove_dir(path).map_err(Errno::from));
+
+                errno_value(result)
+            }
+            "path_rename" => {
+                let old_dirfd = arguments[0].expect_i32().unwrap() as usize;
+                let old_path = read_bytes(memory, &arguments[1], &arguments[2]);
+                let new_dirfd = arguments[3].expect_i32().unwrap() as usize;
+                let new_path = read_bytes(memory, &arguments[4], &arguments[5]);
+
+                let result = self.resolve_path(old_dirfd, old_path).and_then(|old_path| {
+                    let new_path = self.resolve_path(new_dirfd, new_path)?;
+                    fs::rename(old_path, new_path).map_err(Errno::from)
+                });
+
+                errno_value(result)
+            }
+            "path_symlink" => {
+                // The link target is stored as-is, and only resolved when the link is followed
+                let target = read_bytes(memory, &arguments[0], &arguments[1]);
+                let dirfd = arguments[2].expect_i32().unwrap() as usize;
+                let link_path = read_bytes(memory, &arguments[3], &arguments[4]);
+
+                let target = match std::str::from_utf8(target) {
+                    Ok(target) => target,
+                    Err(_) => return Some(Value::I32(Errno::Ilseq as i32)),
+                };
+
+                // Otherwise the app could follow the link out of its directories
+                if Path::new(target).has_root() {
+                    return Some(Value::I32(Errno::Notcapable as i32));
+                }
+
+                let result = self.resolve_path(dirfd, link_path).and_then(|link_path| {
+                    let link_dir = link_path.parent().unwrap_or(&link_path);
+                    self.check_sandboxed(&link_dir.join(target))?;
+                    symlink(target, &link_path)
+                });
+
+                errno_value(result)
+            }
+            "path_unlink_file" => {
+                let dirfd = arguments[0].expect_i32().unwrap() as usize;
+                let path = read_bytes(memory, &arguments[1], &arguments[2]);
+
+                let result = self
+                    .resolve_path(dirfd, path)
+                    .and_then(|path| fs::remove_file(path).map_err(Errno::from));
+
+                errno_value(result)
+            }
+            "poll_oneoff" => {
+                // Array of subscriptions
+                let ptr_subscriptions = arguments[0].expect_i32().unwrap() as usize;
+                // Array of events to fill in
+                let ptr_events = arguments[1].expect_i32().unwrap() as usize;
+                let n_subscriptions = arguments[2].expect_i32().unwrap() as usize;
+                // Out param: number of events written
+                let ptr_nevents = arguments[3].expect_i32().unwrap() as usize;
+
+                match self.poll_oneoff(memory, ptr_subscriptions, ptr_events, n_subscriptions) {
+                    Ok(n_events) => {
+                        write_u32(memory, ptr_nevents, n_events as u32);
+                        success_code
+                    }
+                    Err(errno) => Some(Value::I32(errno as i32)),
+                }
+            }
             "proc_exit" => {
                 let exit_code = arguments[0].expect_i32().unwrap();
                 exit(exit_code);
             }
-            "proc_raise" => todo!("WASI {}({:?})", function_name, arguments),
-            "sched_yield" => todo!("WASI {}({:?})", function_name, arguments),
+            "proc_raise" => Some(Value::I32(Errno::Nosys as i32)),
+            "sched_yield" => {
+                std::thread::yield_now();
+                success_code
+            }
             "random_get" => {
                 // A pointer to a buffer where the random bytes will be written
                 let ptr_buf = arguments[0].expect_i32().unwrap() as usize;
@@ -311,12 +935,281 @@ impl<'a> WasiDispatcher<'a> {
                 }
                 success_code
             }
-            "sock_recv" => todo!("WASI {}({:?})", function_name, arguments),
-            "sock_send" => todo!("WASI {}({:?})", function_name, arguments),
-            "sock_shutdown" => todo!("WASI {}({:?})", function_name, arguments),
+            // We never hand out any sockets
+            "sock_recv" | "sock_send" | "sock_shutdown" => Some(Value::I32(Errno::Notsock as i32)),
             _ => panic!("Unknown WASI function {}({:?})", function_name, arguments),
         }
     }
+
+    /// File descriptors are always ready, so we only wait when there are nothing but clocks to wait for.
+    /// In that case, we sleep until the earliest timeout and report just that one.
+    fn poll_oneoff(
+        &self,
+        memory: &mut [u8],
+        ptr_subscriptions: usize,
+        ptr_events: usize,
+        n_subscriptions: usize,
+    ) -> Result<usize, Errno> {
+        // struct subscription { u64 userdata; u8 tag; union { clock, fd_readwrite } u; }, 48 bytes
+        //     clock: { u32 id; u64 timeout; u64 precision; u16 flags; } at offset 16
+        //     fd_readwrite: { u32 fd; } at offset 16
+        // struct event { u64 userdata; u16 error; u8 type; fd_readwrite }, 32 bytes
+        const SUBSCRIPTION_SIZE: usize = 48;
+        const EVENT_SIZE: usize = 32;
+
+        if n_subscriptions == 0 {
+            return Err(Errno::Inval);
+        }
+
+        let write_event =
+            |memory: &mut [u8], index: usize, ptr_subscription: usize, error: Errno| {
+                let ptr_event = ptr_events + index * EVENT_SIZE;
+                memory.copy_within(ptr_subscription..ptr_subscription + 8, ptr_event);
+                memory[ptr_event + 8..ptr_event + EVENT_SIZE].fill(0);
+                memory[ptr_event + 8] = error as u8;
+                memory[ptr_event + 10] = memory[ptr_subscription + 8];
+            };
+
+        let mut n_events = 0;
+        let mut earliest_clock: Option<(usize, Duration)> = None;
+
+        for i in 0..n_subscriptions {
+            let ptr_subscription = ptr_subscriptions + i * SUBSCRIPTION_SIZE;
+            let tag = memory[ptr_subscription + 8];
+
+            if tag == EVENTTYPE_CLOCK {
+                let clock_id = read_i32(memory, ptr_subscription + 16);
+                let timeout = read_u64(memory, ptr_subscription + 24);
+                let flags = read_u32(memory, ptr_subscription + 40) as u16;
+
+                let wait = match self.clock_time(clock_id) {
+                    Some(now) if flags & SUBCLOCKFLAGS_ABSTIME != 0 => timeout.saturating_sub(now),
+                    Some(_) => timeout,
+                    None => {
+                        write_event(memory, n_events, ptr_subscription, Errno::Inval);
+                        n_events += 1;
+                        continue;
+                    }
+                };
+                let wait = Duration::from_nanos(wait);
+
+                if !matches!(earliest_clock, Some((_, earliest)) if earliest <= wait) {
+                    earliest_clock = Some((ptr_subscription, wait));
+                }
+            } else {
+                let fd = read_u32(memory, ptr_subscription + 16) as usize;
+                let error = match self.files.get(fd) {
+                    Some(WasiFile::Closed) | None => Errno::Badf,
+                    Some(_) => Errno::Success,
+                };
+                write_event(memory, n_events, ptr_subscription, error);
+                n_events += 1;
+            }
+        }
+
+        if n_events == 0 {
+            if let Some((ptr_subscription, wait)) = earliest_clock {
+                std::thread::sleep(wait);
+                write_event(memory, n_events, ptr_subscription, Errno::Success);
+                n_events += 1;
+            }
+        }
+
+        Ok(n_events)
+    }
+}
+
+/// `struct filestat`, which describes a file
+#[derive(Default)]
+struct Filestat {
+    dev: u64,
+    ino: u64,
+    filetype: u8,
+    nlink: u64,
+    size: u64,
+    atim: u64,
+    mtim: u64,
+    ctim: u64,
+}
+
+impl From<&Metadata> for Filestat {
+    fn from(metadata: &Metadata) -> Self {
+        let nanos = |time: io::Result<SystemTime>| {
+            time.ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_nanos() as u64)
+        };
+
+        #[cfg(unix)]
+        let (dev, ino, nlink) = {
+            use std::os::unix::fs::MetadataExt;
+            (metadata.dev(), metadata.ino(), metadata.nlink())
+        };
+        #[cfg(not(unix))]
+        let (dev, ino, nlink) = (0, 0, 1);
+
+        Filestat {
+            dev,
+            ino,
+            filetype: filetype_from(metadata.file_type()),
+            nlink,
+            size: metadata.len(),
+            atim: nanos(metadata.accessed()),
+            mtim: nanos(metadata.modified()),
+            // std doesn't expose the status change time on every platform
+            ctim: nanos(metadata.modified()),
+        }
+    }
+}
+
+impl Filestat {
+    fn write(&self, memory: &mut [u8], addr: usize) {
+        memory[addr..][..64].fill(0);
+        write_u64(memory, addr, self.dev);
+        write_u64(memory, addr + 8, self.ino);
+        memory[addr + 16] = self.filetype;
+        write_u64(memory, addr + 24, self.nlink);
+        write_u64(memory, addr + 32, self.size);
+        write_u64(memory, addr + 40, self.atim);
+        write_u64(memory, addr + 48, self.mtim);
+        write_u64(memory, addr + 56, self.ctim);
+    }
+}
+
+fn filetype_from(file_type: fs::FileType) -> u8 {
+    if file_type.is_dir() {
+        FILETYPE_DIRECTORY
+    } else if file_type.is_file() {
+        FILETYPE_REGULAR_FILE
+    } else if file_type.is_symlink() {
+        FILETYPE_SYMBOLIC_LINK
+    } else {
+        FILETYPE_UNKNOWN
+    }
+}
+
+fn open_path(path: &Path, oflags: i32, rights: u64, fdflags: i32) -> Result<WasiFile, Errno> {
+    let append = fdflags & FDFLAGS_APPEND != 0;
+    let write = rights & RIGHT_FD_WRITE != 0 || append;
+
+    if path.is_dir() {
+        return if write {
+            Err(Errno::Isdir)
+        } else {
+            Ok(WasiFile::HostDir(path.to_path_buf()))
+        };
+    } else if oflags & OFLAGS_DIRECTORY != 0 {
+        return Err(if path.exists() {
+            Errno::Notdir
+        } else {
+            Errno::Noent
+        });
+    }
+
+    let file = OpenOptions::new()
+        .read(rights & RIGHT_FD_READ != 0 || !write)
+        .write(write)
+        .append(append)
+        .create(oflags & OFLAGS_CREAT != 0)
+        .create_new(oflags & OFLAGS_CREAT != 0 && oflags & OFLAGS_EXCL != 0)
+        .truncate(oflags & OFLAGS_TRUNC != 0)
+        .open(path)?;
+
+    Ok(WasiFile::HostFile(file))
+}
+
+/// How many symlinks to follow before giving up, like Linux's MAXSYMLINKS
+const MAX_SYMLINKS: u32 = 40;
+
+/// Where `path` really is on the host, with symlinks followed. Unlike `fs::canonicalize`, the last
+/// component doesn't have to exist, since the app may be about to create it.
+fn real_path(path: &Path, symlinks_left: u32) -> Result<PathBuf, Errno> {
+    if let Ok(real) = fs::canonicalize(path) {
+        return Ok(real);
+    }
+
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => real_path(parent, symlinks_left)?,
+        Some(_) => std::env::current_dir().map_err(Errno::from)?,
+        None => return Err(Errno::Noent),
+    };
+
+    let name = match path.file_name() {
+        Some(name) => name,
+        // the path ends in `..`
+        None => return Ok(parent.parent().unwrap_or(&parent).to_path_buf()),
+    };
+
+    match fs::read_link(path) {
+        // a symlink to something that doesn't exist (yet)
+        Ok(_) if symlinks_left == 0 => Err(Errno::Loop),
+        Ok(target) => real_path(&parent.join(target), symlinks_left - 1),
+        Err(_) => Ok(parent.join(name)),
+    }
+}
+
+#[cfg(unix)]
+fn symlink(target: &str, link_path: &Path) -> Result<(), Errno> {
+    std::os::unix::fs::symlink(target, link_path).map_err(Errno::from)
+}
+
+#[cfg(not(unix))]
+fn symlink(_target: &str, _link_path: &Path) -> Result<(), Errno> {
+    Err(Errno::Nosys)
+}
+
+/// Read into each of the app's IO vectors in turn, stopping early if `read` comes up short
+fn read_iovs(
+    memory: &mut [u8],
+    ptr_iovs: usize,
+    iovs_len: usize,
+    mut read: impl FnMut(&mut [u8]) -> io::Result<usize>,
+) -> io::Result<usize> {
+    let mut n_read = 0;
+    for i in 0..iovs_len {
+        let ptr_iov = ptr_iovs + 8 * i;
+        let iov_base = read_u32(memory, ptr_iov) as usize;
+        let iov_len = read_u32(memory, ptr_iov + 4) as usize;
+
+        let n = read(&mut memory[iov_base..][..iov_len])?;
+        n_read += n;
+        if n < iov_len {
+            break;
+        }
+    }
+    Ok(n_read)
+}
+
+fn write_iovs(
+    memory: &[u8],
+    ptr_iovs: usize,
+    iovs_len: usize,
+    mut write: impl FnMut(&[u8]) -> io::Result<()>,
+) -> io::Result<usize> {
+    let mut n_written = 0;
+    for i in 0..iovs_len {
+        let ptr_iov = ptr_iovs + 8 * i;
+        let iov_base = read_u32(memory, ptr_iov) as usize;
+        let iov_len = read_u32(memory, ptr_iov + 4) as usize;
+
+        write(&memory[iov_base..][..iov_len])?;
+        n_written += iov_len;
+    }
+    Ok(n_written)
+}
+
+fn errno_value(result: Result<(), Errno>) -> Option<Value> {
+    let errno = match result {
+        Ok(()) => Errno::Success,
+        Err(errno) => errno,
+    };
+    Some(Value::I32(errno as i32))
+}
+
+fn read_bytes<'m>(memory: &'m [u8], ptr: &Value, len: &Value) -> &'m [u8] {
+    let ptr = ptr.expect_i32().unwrap() as usize;
+    let len = len.expect_i32().unwrap() as usize;
+    &memory[ptr..][..len]
 }
 
 fn read_u32(memory: &[u8], addr: usize) -> u32 {
@@ -331,6 +1224,12 @@ fn read_i32(memory: &[u8], addr: usize) -> i32 {
     i32::from_le_bytes(bytes)
 }
 
+fn read_u64(memory: &[u8], addr: usize) -> u64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&memory[addr..][..8]);
+    u64::from_le_bytes(bytes)
+}
+
 fn write_u32(memory: &mut [u8], addr: usize, value: u32) {
     memory[addr..][..4].copy_from_slice(&value.to_le_bytes());
 }
@@ -339,6 +1238,10 @@ fn write_i32(memory: &mut [u8], addr: usize, value: i32) {
     memory[addr..][..4].copy_from_slice(&value.to_le_bytes());
 }
 
+fn write_u64(memory: &mut [u8], addr: usize, value: u64) {
+    memory[addr..][..8].copy_from_slice(&value.to_le_bytes());
+}
+
 /// Error codes returned by functions.
 /// Not all of these error codes are returned by the functions provided by this
 /// API; some are used in higher-level library layers, and others are provided
@@ -501,3 +1404,15 @@ pub enum Errno {
     /// Extension: Capabilities insufficient.
     Notcapable,
 }
+
+impl From<io::Error> for Errno {
+    fn from(error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::NotFound => Errno::Noent,
+            io::ErrorKind::PermissionDenied => Errno::Access,
+            io::ErrorKind::AlreadyExists => Errno::Exist,
+            io::ErrorKind::InvalidInput => Errno::Inval,
+            _ => Errno::Io,
+        }
+    }
+}